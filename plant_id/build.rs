@@ -0,0 +1,12 @@
+//! Generates the gRPC server from proto/plant_care.proto. The proto is
+//! parsed by protox, so building needs no protoc installed.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let descriptors = protox::compile(["proto/plant_care.proto"], ["proto"])?;
+    tonic_build::configure()
+        .build_client(false)
+        .compile_fds(descriptors)?;
+
+    println!("cargo:rerun-if-changed=proto/plant_care.proto");
+    Ok(())
+}
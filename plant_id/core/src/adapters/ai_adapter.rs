@@ -0,0 +1,1169 @@
+//! AI ADAPTER
+//!
+//! Secondary adapter for interacting with AI models via OpenRouter API.
+//! Handles chat completions, care schedule generation, and conversations in
+//! which the model calls tools to look things up.
+//!
+//! Sampling parameters come from `--temperature`, `--top-p`, `--max-tokens`
+//! and `--seed`, then `AI_TEMPERATURE`, `AI_TOP_P`, `AI_MAX_TOKENS` and
+//! `AI_SEED`; those left unset are the model's defaults, except that
+//! diagnosis turns use a low temperature so they keep to the protocol.
+//!
+//! The answer style comes from `--style`, then `AI_STYLE` (see
+//! `AnswerStyle::parse`); its instructions end every system prompt.
+//!
+//! A `traced` adapter appends every request and the answer or error it got
+//! to a JSON lines file; diagnosis sessions keep one each (`trace_path`),
+//! which `plant-care debug-bundle` attaches to bug reports.
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use crate::adapters::{http_client, metrics_adapter, payload};
+use crate::config::{self, get_secret};
+use crate::domain::encyclopedia::{EncyclopediaEntry, EntryKind};
+use crate::domain::{AnswerStyle, CareDifficulty, CareSchedule, ContainmentPlan, DiagnosisBatch, Growth, RoomProfile, SpeciesCandidate, TokenUsage};
+use crate::domain::ChatMessage as SessionMessage;
+use crate::dto::{
+    CareExplanationDto, IdentificationTiebreak, PlacementExplanation, PlantSuggestionDto, SpeciesNameCheck, TriageRanking,
+    VacationAdviceDto,
+};
+
+/// Where requests go unless `OPENROUTER_BASE_URL` points elsewhere, e.g. at
+/// an OpenAI-compatible gateway such as LiteLLM
+const DEFAULT_BASE_URL: &str = "https://openrouter.ai/api/v1";
+
+/// Temperature of diagnosis turns unless one is configured, as their JSON
+/// actions must follow the protocol
+const PROTOCOL_TEMPERATURE: f32 = 0.2;
+
+static GENERATION_OVERRIDES: OnceLock<GenerationParams> = OnceLock::new();
+
+static STYLE_OVERRIDES: OnceLock<AnswerStyle> = OnceLock::new();
+
+/// Sampling parameters sent with every request; unset ones are left to the
+/// model
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl GenerationParams {
+    /// Parameters set by `AI_TEMPERATURE`, `AI_TOP_P`, `AI_MAX_TOKENS` and `AI_SEED`
+    pub fn from_env() -> Result<Self> {
+        fn var<T: FromStr>(name: &str) -> Result<Option<T>> {
+            match std::env::var(name) {
+                Ok(value) if !value.trim().is_empty() => value
+                    .trim()
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| anyhow::anyhow!("Invalid {} '{}': expected a number", name, value)),
+                _ => Ok(None),
+            }
+        }
+
+        Self {
+            temperature: var("AI_TEMPERATURE")?,
+            top_p: var("AI_TOP_P")?,
+            max_tokens: var("AI_MAX_TOKENS")?,
+            seed: var("AI_SEED")?,
+        }
+        .validated()
+    }
+
+    /// These parameters, with those set in `overrides` replacing them
+    pub fn overridden_by(self, overrides: GenerationParams) -> Self {
+        Self {
+            temperature: overrides.temperature.or(self.temperature),
+            top_p: overrides.top_p.or(self.top_p),
+            max_tokens: overrides.max_tokens.or(self.max_tokens),
+            seed: overrides.seed.or(self.seed),
+        }
+    }
+
+    pub fn validated(self) -> Result<Self> {
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                anyhow::bail!("The temperature must be between 0 and 2, not {}", temperature);
+            }
+        }
+        if let Some(top_p) = self.top_p {
+            if !(top_p > 0.0 && top_p <= 1.0) {
+                anyhow::bail!("top_p must be more than 0 and at most 1, not {}", top_p);
+            }
+        }
+        if self.max_tokens == Some(0) {
+            anyhow::bail!("max_tokens must be at least 1");
+        }
+
+        Ok(self)
+    }
+}
+
+/// Override the environment's generation parameters for the rest of the
+/// process; those not set in `params` keep the environment's
+pub fn set_generation_params(params: GenerationParams) -> Result<()> {
+    GENERATION_OVERRIDES.set(params.validated()?).ok();
+    Ok(())
+}
+
+/// Override the environment's answer style for the rest of the process;
+/// the parts not set in `style` keep the environment's
+pub fn set_answer_style(style: AnswerStyle) {
+    STYLE_OVERRIDES.set(style).ok();
+}
+
+/// Answer style set by `AI_STYLE`, with the overrides of `set_answer_style`
+pub fn answer_style() -> Result<AnswerStyle> {
+    let style = match std::env::var("AI_STYLE") {
+        Ok(value) => AnswerStyle::parse(&value).context("Invalid AI_STYLE")?,
+        Err(_) => AnswerStyle::default(),
+    };
+    Ok(style.overridden_by(STYLE_OVERRIDES.get().copied().unwrap_or_default()))
+}
+
+#[derive(Clone)]
+pub struct AiAdapter {
+    client: Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+    params: GenerationParams,
+    style: AnswerStyle,
+    /// Tokens used by requests, shared by clones until `metered`
+    usage: Arc<Mutex<TokenUsage>>,
+    /// File requests are traced to, if any
+    trace: Option<PathBuf>,
+}
+
+/// Where the AI requests of a diagnosis session are traced, in `TRACES_DIR`
+/// or the data directory
+pub fn trace_path(session_id: &str) -> PathBuf {
+    let dir = std::env::var("TRACES_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| config::data_dir().join("traces"));
+    dir.join(format!("{}.jsonl", session_id))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChatMessage {
+    role: String,
+    content: MessageContent,
+}
+
+/// Message content: plain text, or text with attached images for vision models
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImageUrl {
+    url: String,
+}
+
+/// An image attached to a request, e.g. a photo requested during diagnosis
+#[derive(Debug, Clone)]
+pub struct ImageAttachment {
+    pub description: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(flatten)]
+    params: GenerationParams,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<UsageReport>,
+}
+
+/// Tokens used by a request, as reported by the API
+#[derive(Debug, Deserialize)]
+struct UsageReport {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCompletionResponse {
+    choices: Vec<ToolChoice>,
+    #[serde(default)]
+    usage: Option<UsageReport>,
+}
+
+/// The message is kept as sent, so it can be replayed with the tool results
+#[derive(Debug, Deserialize)]
+struct ToolChoice {
+    message: Value,
+}
+
+/// A function the model may call to look something up while answering
+#[derive(Debug, Clone)]
+pub struct ToolDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON schema of the arguments
+    pub parameters: Value,
+}
+
+/// A call to one of the tools, as requested by the model
+#[derive(Debug, Clone)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    /// Null when the model sent arguments that are not JSON
+    pub arguments: Value,
+}
+
+/// What the model did with its turn
+#[derive(Debug)]
+pub enum ToolTurn {
+    /// Asked for tools to be run; their results go back with
+    /// `ToolConversation::push_result` before the next turn
+    Calls(Vec<ToolCall>),
+    Answer(String),
+}
+
+/// A conversation in which the model may call tools. It keeps the calls the
+/// model made and their results, which it needs to see on its next turn.
+#[derive(Debug, Clone)]
+pub struct ToolConversation {
+    messages: Vec<Value>,
+}
+
+impl ToolConversation {
+    pub fn new(system_prompt: &str, question: &str) -> Self {
+        Self {
+            messages: vec![
+                json!({"role": "system", "content": system_prompt}),
+                json!({"role": "user", "content": question}),
+            ],
+        }
+    }
+
+    /// Answer one of the calls of the model's last turn
+    pub fn push_result(&mut self, call: &ToolCall, result: &Value) {
+        self.messages.push(json!({
+            "role": "tool",
+            "tool_call_id": call.id,
+            "content": result.to_string()
+        }));
+    }
+}
+
+impl AiAdapter {
+    pub fn new() -> Result<Self> {
+        let model = std::env::var("AI_MODEL")
+            .unwrap_or_else(|_| "anthropic/claude-3.5-sonnet".to_string());
+        Self::with_model(model)
+    }
+
+    /// Create an adapter for a specific model, ignoring `AI_MODEL`
+    pub fn with_model(model: String) -> Result<Self> {
+        let api_key = get_secret("OPENROUTER_API_KEY")?;
+        let params = GenerationParams::from_env()?
+            .overridden_by(GENERATION_OVERRIDES.get().copied().unwrap_or_default());
+
+        Ok(Self {
+            client: http_client::build_client()?,
+            base_url: http_client::base_url("OPENROUTER_BASE_URL", DEFAULT_BASE_URL)?,
+            api_key,
+            model,
+            params,
+            style: answer_style()?,
+            usage: Arc::default(),
+            trace: None,
+        })
+    }
+
+    /// Model answers are requested from (`AI_MODEL`)
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Sampling parameters sent with requests
+    pub fn params(&self) -> GenerationParams {
+        self.params
+    }
+
+    /// How answers are worded
+    pub fn style(&self) -> AnswerStyle {
+        self.style
+    }
+
+    /// A copy of the adapter for answers that must follow a structured
+    /// protocol: at a low temperature unless one is configured. It counts
+    /// tokens with the original.
+    fn for_protocol(&self) -> Self {
+        let mut adapter = self.clone();
+        adapter.params.temperature.get_or_insert(PROTOCOL_TEMPERATURE);
+        adapter
+    }
+
+    /// A copy of the adapter that counts the tokens of its own requests
+    /// from zero, to measure one piece of work
+    pub fn metered(&self) -> Self {
+        Self {
+            usage: Arc::default(),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of the adapter that appends its requests, with the answer or
+    /// error each got, to `path`
+    pub fn traced(&self, path: PathBuf) -> Self {
+        Self {
+            trace: Some(path),
+            ..self.clone()
+        }
+    }
+
+    /// Tokens used by the requests made so far
+    pub fn usage(&self) -> TokenUsage {
+        *self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record_usage(&self, report: Option<&UsageReport>) {
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.add(&TokenUsage {
+            requests: 1,
+            prompt_tokens: report.map_or(0, |r| r.prompt_tokens),
+            completion_tokens: report.map_or(0, |r| r.completion_tokens),
+        });
+    }
+
+    pub async fn get_completion(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        let messages = vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt.to_string().into(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: user_prompt.to_string().into(),
+            },
+        ];
+
+        self.send_chat_request(messages).await
+    }
+
+    /// Get a completion for a multi-turn conversation
+    pub async fn get_chat_completion(
+        &self,
+        system_prompt: &str,
+        history: &[SessionMessage],
+    ) -> Result<String> {
+        let mut messages = vec![ChatMessage {
+            role: "system".to_string(),
+            content: system_prompt.to_string().into(),
+        }];
+        messages.extend(history.iter().map(|m| ChatMessage {
+            role: m.role.clone(),
+            content: m.content.clone().into(),
+        }));
+
+        self.send_chat_request(messages).await
+    }
+
+    /// Take the model's next turn in a conversation where it may call
+    /// `tools`. The turn is added to the conversation.
+    pub async fn complete_with_tools(
+        &self,
+        conversation: &mut ToolConversation,
+        tools: &[ToolDefinition],
+    ) -> Result<ToolTurn> {
+        let started = Instant::now();
+        let sent = self.trace.as_ref().map(|_| json!(self.styled(&conversation.messages)));
+        let result = self.request_tool_turn(conversation, tools).await;
+        self.record_request(started, &result);
+        if let Some(sent) = sent {
+            // The model's turn is the message the conversation ends with
+            let answer = result.as_ref().map(|_| conversation.messages.last().cloned().unwrap_or_default());
+            self.write_trace(started, sent, answer);
+        }
+
+        result
+    }
+
+    async fn send_chat_request(&self, mut messages: Vec<ChatMessage>) -> Result<String> {
+        if let Some(instructions) = self.style.instructions() {
+            for message in messages.iter_mut().filter(|m| m.role == "system") {
+                if let MessageContent::Text(prompt) = &mut message.content {
+                    *prompt = format!("{}\n\n{}", prompt, instructions);
+                }
+            }
+        }
+        let started = Instant::now();
+        let sent = self.trace.as_ref().map(|_| json!(messages));
+        let result = self.request_completion(messages).await;
+        self.record_request(started, &result);
+        if let Some(sent) = sent {
+            self.write_trace(started, sent, result.as_ref().map(|answer| json!(answer)));
+        }
+
+        result
+    }
+
+    /// Messages of a tool conversation, with the answer style's instructions
+    /// added to the system prompt. The conversation keeps the prompt as it
+    /// was given, so they are not added again on the next turn.
+    fn styled(&self, messages: &[Value]) -> Vec<Value> {
+        let mut messages = messages.to_vec();
+        if let Some(instructions) = self.style.instructions() {
+            for message in messages.iter_mut().filter(|m| m["role"] == "system") {
+                if let Some(prompt) = message["content"].as_str() {
+                    message["content"] = json!(format!("{}\n\n{}", prompt, instructions));
+                }
+            }
+        }
+        messages
+    }
+
+    /// Append a request and what it got to the trace file. Attached images
+    /// are left out, and failing to trace only logs a warning.
+    fn write_trace(&self, started: Instant, mut messages: Value, answer: Result<Value, &anyhow::Error>) {
+        let Some(path) = &self.trace else {
+            return;
+        };
+        leave_out_images(&mut messages);
+        let mut entry = json!({
+            "at": Utc::now(),
+            "model": self.model,
+            "params": self.params,
+            "duration_ms": started.elapsed().as_millis() as u64,
+            "messages": messages
+        });
+        match answer {
+            Ok(answer) => entry["answer"] = answer,
+            Err(e) => entry["error"] = json!(format!("{:#}", e)),
+        }
+
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| OpenOptions::new().create(true).append(true).open(path))
+            .and_then(|mut file| writeln!(file, "{}", entry));
+        if let Err(e) = written {
+            log::warn!("Could not write the AI trace {}: {}", path.display(), e);
+        }
+    }
+
+    fn record_request<T>(&self, started: Instant, result: &Result<T>) {
+        metrics::histogram!(
+            metrics_adapter::AI_REQUEST_DURATION,
+            "model" => self.model.clone(),
+            "outcome" => metrics_adapter::outcome(result)
+        )
+        .record(started.elapsed().as_secs_f64());
+    }
+
+    async fn request_completion(&self, messages: Vec<ChatMessage>) -> Result<String> {
+        let request = ChatCompletionRequest {
+            model: self.model.clone(),
+            messages,
+            params: self.params,
+        };
+
+        let completion: ChatCompletionResponse = payload::read_json("AI", self.post_completion(&request).await?).await?;
+        self.record_usage(completion.usage.as_ref());
+
+        completion
+            .choices
+            .first()
+            .map(|c| c.message.content.clone())
+            .context("No response from AI")
+    }
+
+    async fn request_tool_turn(
+        &self,
+        conversation: &mut ToolConversation,
+        tools: &[ToolDefinition],
+    ) -> Result<ToolTurn> {
+        let tools: Vec<Value> = tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters
+                    }
+                })
+            })
+            .collect();
+        let mut request = json!({
+            "model": self.model,
+            "messages": self.styled(&conversation.messages),
+            "tools": tools
+        });
+        if let (Some(request), Value::Object(params)) = (request.as_object_mut(), json!(self.params)) {
+            request.extend(params);
+        }
+
+        let completion: ToolCompletionResponse = payload::read_json("AI", self.post_completion(&request).await?).await?;
+        self.record_usage(completion.usage.as_ref());
+        let message = completion
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message)
+            .context("No response from AI")?;
+
+        let calls = tool_calls(&message);
+        if calls.is_empty() {
+            let answer = message["content"].as_str().context("No response from AI")?.to_string();
+            conversation.messages.push(json!({"role": "assistant", "content": answer}));
+            return Ok(ToolTurn::Answer(answer));
+        }
+
+        conversation.messages.push(message);
+        Ok(ToolTurn::Calls(calls))
+    }
+
+    async fn post_completion(&self, request: &impl Serialize) -> Result<reqwest::Response> {
+        let response = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| http_client::request_error("AI", e))?;
+
+        if !response.status().is_success() {
+            let error_text = payload::error_text(response).await?;
+            anyhow::bail!("AI API error: {}", error_text);
+        }
+
+        Ok(response)
+    }
+
+    /// Care schedule for a plant, suited to the specimen's maturity when
+    /// its `growth` is known and to the `room` it lives in
+    pub async fn generate_care_schedule(
+        &self,
+        plant_name: &str,
+        growth: Option<&Growth>,
+        room: Option<&RoomProfile>,
+    ) -> Result<CareSchedule> {
+        // Using the prompt you provided
+        let system_prompt = r#"You are an expert Botanist. The user will provide you with the name of a plant.
+Your task is to research this plant and provide a detailed care schedule.
+You MUST return your response as a single, minified JSON object with NO markdown formatting.
+The JSON object must have the following fields:
+{
+  "light": "description of light requirements",
+  "water": "description of watering schedule",
+  "humidity": "description of humidity requirements",
+  "temperature": "description of temperature range",
+  "care_instructions": "additional care tips and notes"
+}
+Be specific and practical in your recommendations.
+If the user describes the specimen (how long they have had it, where it came from), suit the schedule
+to its maturity: seedlings and fresh cuttings need steady moisture, bright indirect light and little or
+diluted fertilizer, while mature specimens can dry out more between waterings and take regular feeding
+and repotting.
+If the user describes the room the plant lives in (window, sun, temperature, humidity, drafts, heating),
+write the schedule for that room rather than for a typical home: say so when the light there falls short,
+water less in cool rooms and more in warm or heated ones, and only recommend humidity measures when the
+room's humidity is below what the plant needs."#;
+
+        let mut user_prompt = format!("Generate a care schedule for: {}", plant_name);
+        if let Some(growth) = growth {
+            user_prompt.push_str(&format!("\nThis specimen: {}", growth.describe()));
+        }
+        if let Some(room) = room {
+            user_prompt.push_str(&format!("\nIts room: {}", room.describe()));
+        }
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        let json_str = extract_json(&response);
+
+        let care_schedule: CareSchedule = serde_json::from_str(json_str)
+            .context("Failed to parse care schedule from AI response")?;
+
+        Ok(care_schedule)
+    }
+
+    /// Rate how demanding a plant is to keep
+    pub async fn rate_care_difficulty(&self, plant_name: &str) -> Result<CareDifficulty> {
+        let system_prompt = r#"You are an expert Botanist. The user will provide you with the name of a plant.
+Rate how difficult the plant is to keep alive and healthy as a houseplant.
+You MUST return your response as a single, minified JSON object with NO markdown formatting.
+The JSON object must have the following fields:
+{
+  "rating": 1-5 (1 = very forgiving, 5 = for experienced growers only),
+  "failure_modes": ["the 2-4 most common ways owners lose this plant, one short phrase each"],
+  "beginner_friendly": true or false
+}"#;
+
+        let user_prompt = format!("Rate the care difficulty of: {}", plant_name);
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        let difficulty: CareDifficulty = serde_json::from_str(extract_json(&response))
+            .context("Failed to parse care difficulty from AI response")?;
+
+        difficulty.validated()
+    }
+
+    /// Whether a plant name is a real plant, and which one it most likely
+    /// means if not
+    pub async fn check_species_name(&self, plant_name: &str) -> Result<SpeciesNameCheck> {
+        let system_prompt = r#"You are an expert Botanist. The user will provide you with a plant name they typed.
+Decide whether it names a real plant (a species, cultivar, genus or well-known common name), allowing
+for any capitalization. If it looks like a misspelling or does not name a real plant, suggest the
+plant they most likely meant, as its scientific name.
+You MUST return your response as a single, minified JSON object with NO markdown formatting:
+{"is_plant": true or false, "suggestion": "Scientific name" or null}"#;
+
+        let user_prompt = format!("Check this plant name: {}", plant_name);
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        serde_json::from_str(extract_json(&response)).context("Failed to parse species name check from AI response")
+    }
+
+    pub async fn generate_diagnosis_response(
+        &self,
+        diagnosis_context: &serde_json::Value,
+        photos: &[ImageAttachment],
+    ) -> Result<String> {
+        // Using the simplified diagnostic kernel prompt for JSON responses
+        let system_prompt = r#"You are a plant diagnostic AI. Your job is to analyze plant problems and determine the next action.
+
+Analyze the diagnosis context and return a JSON response with "action" and "payload" keys.
+
+Available Actions:
+1. GET_PLANT_VITALS: Fetch plant data (use if plant_vitals is null)
+   {"action": "GET_PLANT_VITALS", "payload": {}}
+
+2. LOG_STATE: Store intermediate findings
+   {"action": "LOG_STATE", "payload": {"hypothesis": "sun scorch", "confidence": 0.7}}
+
+3. ASK_USER: Ask a clarifying question
+   {"action": "ASK_USER", "payload": {"question": "How many hours of direct sunlight does your plant get?"}}
+
+4. REQUEST_PHOTO: Ask the user to attach a photo of a specific part of the plant
+   {"action": "REQUEST_PHOTO", "payload": {"part": "roots", "reason": "Check for brown, mushy roots"}}
+   Photos the user attached are included with this message, described in "photos".
+
+5. SUGGEST_CARE_UPDATE: Propose changes to the stored care schedule when the problem is caused
+   by care (only include fields that change, then CONCLUDE)
+   {"action": "SUGGEST_CARE_UPDATE", "payload": {"changes": {"water": "Water every 10 days"}, "reason": "Soil stays wet too long"}}
+
+6. SEARCH: Look up a rare disease, pest or species you are unsure about
+   {"action": "SEARCH", "payload": {"query": "Rhizoctonia root rot houseplant"}}
+   Results are added to "search_results" in the context.
+
+7. CONCLUDE: Provide final diagnosis with its severity, your confidence (0-1) and ranked alternative diagnoses
+   {"action": "CONCLUDE", "payload": {"finding": "Sun Scorch", "recommendation": "Move to bright, indirect light",
+    "severity": "minor", "confidence": 0.8, "alternatives": [{"finding": "Underwatering", "probability": 0.15}]}}
+   severity is one of "info" (nothing to treat), "minor" (cosmetic or easily fixed), "serious" (needs
+   treatment soon) or "critical" (the plant may die, or pests may spread, without action now).
+
+If the context has a "summary", it condenses earlier questions, answers and logged state;
+treat it as established facts.
+
+If the context has "language", the owner writes in that language: write the question, the photo
+"part" and "reason", the conclusion's "finding" and "recommendation" and the alternatives' findings
+in it, for the whole session. Keep the action names, JSON keys and severity values in English.
+
+If plant_vitals has "room", it describes where the plant lives: the window's direction, hours of
+direct sun, the light level, and what the owner recorded of the average temperature (°C), humidity
+(%), drafts and heating. Do not ASK about these; take them into account (cold drafts and low humidity
+explain many leaf problems) and only ask about what the room leaves out.
+
+If plant_vitals has "moisture_trend", it summarizes the soil moisture readings the owner logged over
+the last 30 days against the watering schedule. A "warning" there points at chronic over- or
+under-watering; weigh it when the symptoms fit (yellowing, root rot, crisp leaves...).
+
+If the context has "species_experience", it lists how earlier treatments worked on this owner's
+plants of the same species (worked/failed counts). Prefer recommendations that worked and avoid
+repeating ones that failed.
+
+If the context has "health_assessment", Plant.id examined the photo the owner started with:
+"is_healthy_probability" and the "diseases" it found, likeliest first, each with its probability (0-1).
+Start from these findings: confirm or rule out the likeliest with your questions before looking
+further, and do not treat a suggestion with a low probability as established.
+
+If the context has "batch", the owner saw the same problem ("problem") on several plants at once,
+likely with a shared cause such as a pest spreading between them. "other_plants" lists the other
+plants with their room and, once their own diagnosis concluded, its finding. Check the cause found
+on the other plants first, ask whether the plants touch or share a room, tools or watering can, and
+say in the recommendation how to keep this plant from reinfecting the others.
+
+Strategy:
+1. Check if plant_vitals is null - if so, use GET_PLANT_VITALS
+2. Ask 2-4 targeted questions to narrow down the issue
+3. Track hypotheses using LOG_STATE
+4. Use SEARCH sparingly, only for uncommon problems
+5. When confident, use CONCLUDE
+
+Return ONLY valid JSON, no markdown formatting."#;
+
+        let user_prompt = format!(
+            "Analyze this diagnosis context and determine the next action:\n\n{}",
+            serde_json::to_string(diagnosis_context)?
+        );
+
+        let adapter = self.for_protocol();
+        if photos.is_empty() {
+            return adapter.get_completion(system_prompt, &user_prompt).await;
+        }
+
+        let mut parts = vec![ContentPart::Text { text: user_prompt }];
+        for photo in photos {
+            parts.push(ContentPart::Text {
+                text: format!("Photo: {}", photo.description),
+            });
+            parts.push(ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: image_data_url(&photo.data),
+                },
+            });
+        }
+
+        let messages = vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt.to_string().into(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: MessageContent::Parts(parts),
+            },
+        ];
+
+        adapter.send_chat_request(messages).await
+    }
+
+    /// Condense older diagnosis turns and logged state into a short summary
+    pub async fn summarize_diagnosis_context(
+        &self,
+        previous_summary: Option<&str>,
+        old_turns: &[serde_json::Value],
+        state: &serde_json::Value,
+    ) -> Result<String> {
+        let system_prompt = r#"You compress the transcript of a plant diagnosis session.
+Write a concise plain-text summary (at most 150 words) that preserves every fact the owner
+reported, every question already asked, and every hypothesis with its confidence.
+Merge the previous summary, if any, into your new one. Return ONLY the summary text."#;
+
+        let user_prompt = format!(
+            "Previous summary: {}\n\nConversation turns:\n{}\n\nLogged state:\n{}",
+            previous_summary.unwrap_or("none"),
+            serde_json::to_string(old_turns)?,
+            serde_json::to_string(state)?
+        );
+
+        let summary = self.get_completion(system_prompt, &user_prompt).await?;
+
+        Ok(summary.trim().to_string())
+    }
+
+    /// Rank plants with open symptoms by how urgently they need attention
+    pub async fn rank_triage_candidates(
+        &self,
+        candidates: &serde_json::Value,
+    ) -> Result<Vec<TriageRanking>> {
+        let system_prompt = r#"You are an expert plant doctor triaging a houseplant collection.
+You will receive a JSON list of plants, each with the open symptoms observed by the owner.
+Rank ALL plants from most to least urgent. Consider how quickly each problem can kill or
+permanently damage the plant, and whether it can spread to other plants.
+You MUST return a single JSON array with NO markdown formatting, one object per plant:
+[{"plant_id": "...", "urgency": 1-10, "reason": "one short sentence"}]"#;
+
+        let user_prompt = format!(
+            "Triage these plants:\n\n{}",
+            serde_json::to_string_pretty(candidates)?
+        );
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        serde_json::from_str(extract_json(&response))
+            .context("Failed to parse triage ranking from AI response")
+    }
+
+    /// Explain suggested moves between rooms, worked out from a light model
+    pub async fn explain_placements(
+        &self,
+        moves: &serde_json::Value,
+    ) -> Result<Vec<PlacementExplanation>> {
+        let system_prompt = r#"You are an expert houseplant keeper advising an owner where to keep their plants.
+You will receive a JSON list of suggested moves. Each gives the plant, the light it needs according to
+its care schedule, the room it is in now and the light there (if known), and the room it should move
+to and the light there. The rooms were matched with a simple light model; do NOT suggest other rooms.
+For each move, explain in one or two plain sentences why it helps this particular plant (what too much
+or too little light does to it) and how to make the move gently (acclimatising to sun, distance from
+the window...).
+You MUST return a single JSON array with NO markdown formatting, one object per move:
+[{"plant_id": "...", "explanation": "..."}]"#;
+
+        let user_prompt = format!(
+            "Explain these moves:\n\n{}",
+            serde_json::to_string_pretty(moves)?
+        );
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        serde_json::from_str(extract_json(&response))
+            .context("Failed to parse placement explanations from AI response")
+    }
+
+    /// Explain why a plant's care guidance suits its species and
+    /// surroundings, citing the numbered sources of the request
+    pub async fn explain_care(&self, request: &serde_json::Value) -> Result<CareExplanationDto> {
+        let system_prompt = r#"You are an expert houseplant keeper explaining a plant's care guidance to its owner.
+You will receive the plant and its species, one part of its care guidance ("topic" and "guidance"), what
+is known about where it lives (room, window, direct sun, attributes such as the pot, soil moisture
+readings) and numbered reference sources about the species.
+Explain in one or two short paragraphs WHY this guidance makes sense for this species and these
+surroundings: how the species grows in the wild, what its roots and leaves need, and how the room and
+readings affect it. If the readings or surroundings suggest the guidance should be adjusted, say so.
+Cite a source with its number in square brackets, e.g. [1], right after each claim it supports. Only
+cite the sources you were given, and do not invent any; claims from general knowledge need no citation.
+You MUST return a single JSON object with NO markdown formatting:
+{"explanation": "...", "cited": [1, 2]}"#;
+
+        let user_prompt = format!(
+            "Explain this care guidance:\n\n{}",
+            serde_json::to_string_pretty(request)?
+        );
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        serde_json::from_str(extract_json(&response))
+            .context("Failed to parse care explanation from AI response")
+    }
+
+    /// Species suggestions for the owner's conditions and collection
+    pub async fn recommend_plants(&self, request: &serde_json::Value) -> Result<Vec<PlantSuggestionDto>> {
+        let system_prompt = r#"You are an expert houseplant keeper helping an owner choose new plants.
+You will receive the light the owner can offer, the pets they keep, how much effort they want to put
+into care, and the plants they already own or have on their wishlist.
+Suggest the requested number of houseplant species that:
+- do well in that light,
+- are NOT toxic to the listed pets,
+- suit the effort level (low: very forgiving, medium: some attention, high: demanding is fine),
+- are NOT already owned or wished for, and add variety to the collection.
+For each species, give its botanical name, a common name, one or two plain sentences on why it suits
+this owner, the light it needs (e.g. "low to bright indirect light"), a care difficulty rating from
+1 (very forgiving) to 5 (for experienced growers), and whether it is toxic to cats and to dogs.
+You MUST return a single JSON array with NO markdown formatting:
+[{"species": "...", "common_name": "...", "reason": "...", "light": "...", "difficulty": 1, "toxic_to_cats": false, "toxic_to_dogs": false}]"#;
+
+        let user_prompt = format!(
+            "Suggest plants for:\n\n{}",
+            serde_json::to_string_pretty(request)?
+        );
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        serde_json::from_str(extract_json(&response))
+            .context("Failed to parse plant suggestions from AI response")
+    }
+
+    /// Tips for leaving a collection alone, on top of the watering visits
+    /// already planned from the care schedules
+    pub async fn advise_vacation(&self, trip: &serde_json::Value) -> Result<VacationAdviceDto> {
+        let system_prompt = r#"You are an expert houseplant keeper helping an owner prepare for a trip.
+You will receive the trip length and a JSON list of plants with their care schedule and the days a
+plant sitter will water them (none when the watering before departure lasts the whole trip).
+Do NOT change the watering days. Instead:
+- Suggest 2-5 practical preparation steps for before departure (moving plants away from hot
+  windows, grouping them for humidity, self-watering spikes for thirsty plants...), specific to
+  these plants and the trip length.
+- For each plant, write a note of one or two plain sentences for a plant sitter who knows nothing
+  about plants: what to watch out for and what not to do.
+You MUST return a single JSON object with NO markdown formatting:
+{"prep_tips": ["..."], "plants": [{"plant_id": "...", "note": "..."}]}"#;
+
+        let user_prompt = format!(
+            "Prepare this trip:\n\n{}",
+            serde_json::to_string_pretty(trip)?
+        );
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        serde_json::from_str(extract_json(&response))
+            .context("Failed to parse vacation advice from AI response")
+    }
+
+    /// Look at a photo to pick between species that identifiers disagree on
+    pub async fn break_identification_tie(
+        &self,
+        image: &[u8],
+        candidates: &[SpeciesCandidate],
+    ) -> Result<IdentificationTiebreak> {
+        let system_prompt = r#"You are an expert botanist settling a plant identification.
+Two identification services named different species for the same photo. You will receive the photo
+and a JSON list of the candidate species, each with the service that suggested it and its confidence
+(null when the service did not give one). Look at the photo for distinguishing features (leaf shape,
+venation, fenestrations, growth habit, stems...) and pick the candidate it shows.
+You MUST pick one of the candidates, and return a single JSON object with NO markdown formatting:
+{"scientific_name": "the candidate's scientific name, as given", "reasoning": "one or two plain sentences on the features that decided it"}"#;
+
+        let parts = vec![
+            ContentPart::Text {
+                text: format!("Candidates:\n\n{}", serde_json::to_string_pretty(candidates)?),
+            },
+            ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: image_data_url(image),
+                },
+            },
+        ];
+        let messages = vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt.to_string().into(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: MessageContent::Parts(parts),
+            },
+        ];
+
+        let response = self.send_chat_request(messages).await?;
+
+        serde_json::from_str(extract_json(&response))
+            .context("Failed to parse identification tiebreak from AI response")
+    }
+
+    /// Combine the findings of a batch of linked diagnoses into one plan
+    /// for stopping the problem from spreading
+    pub async fn plan_containment(&self, batch: &DiagnosisBatch) -> Result<ContainmentPlan> {
+        let system_prompt = r#"You are a plant pathologist and entomologist containing an outbreak in a
+houseplant collection. The owner saw the same problem on several plants. You will receive the
+problem and a JSON list of the plants, each with its room (if known) and the finding and severity of
+its own diagnosis (null when it was not diagnosed). Work out the cause the plants most likely share,
+which plants to isolate, and the steps to contain and treat it across all of them (quarantine,
+cleaning pots, tools and shelves, treating neighbours preventively, how long to keep checking...).
+You MUST return a single JSON object with NO markdown formatting:
+{"suspected_cause": "...", "isolate": ["plant name as given", ...], "steps": ["practical step", ...],
+ "monitor": "one sentence on which other plants to watch and for how long"}"#;
+
+        let user_prompt = format!(
+            "Problem: {}\n\nPlants:\n{}",
+            batch.problem,
+            serde_json::to_string_pretty(&batch.plants)?
+        );
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        serde_json::from_str(extract_json(&response))
+            .context("Failed to parse containment plan from AI response")
+    }
+
+    /// Describe a pest or disease that isn't in the bundled encyclopedia
+    pub async fn describe_encyclopedia_entry(
+        &self,
+        kind: EntryKind,
+        name: &str,
+        my_plants: &[String],
+    ) -> Result<EncyclopediaEntry> {
+        let system_prompt = r#"You are a plant pathologist and entomologist writing encyclopedia entries
+for houseplant owners. The user names a pest or disease and lists the plants they own.
+You MUST return a single JSON object with NO markdown formatting:
+{
+  "name": "canonical common name",
+  "kind": "pest" or "disease",
+  "aliases": ["other common or scientific names"],
+  "symptoms": ["observable symptom", ...],
+  "treatment": ["practical treatment step", ...],
+  "susceptible": ["lowercase genus or common name of susceptible plants", ...]
+}
+In "susceptible", include the lowercase name of every listed plant that is susceptible,
+followed by other commonly affected plants."#;
+
+        let user_prompt = format!(
+            "Describe the {}: {}\n\nMy plants: {}",
+            kind.as_str(),
+            name,
+            if my_plants.is_empty() {
+                "none".to_string()
+            } else {
+                my_plants.join(", ")
+            }
+        );
+
+        let response = self.get_completion(system_prompt, &user_prompt).await?;
+
+        serde_json::from_str(extract_json(&response))
+            .context("Failed to parse encyclopedia entry from AI response")
+    }
+}
+
+
+/// The tool calls of an assistant message, in the order they were made
+fn tool_calls(message: &Value) -> Vec<ToolCall> {
+    message["tool_calls"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|call| {
+            let function = &call["function"];
+            Some(ToolCall {
+                id: call["id"].as_str()?.to_string(),
+                name: function["name"].as_str()?.to_string(),
+                arguments: function["arguments"]
+                    .as_str()
+                    .and_then(|args| serde_json::from_str(args).ok())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Encode an image as a data URL, detecting its type from the contents
+fn image_data_url(data: &[u8]) -> String {
+    let mime = image::guess_format(data)
+        .map(|format| format.to_mime_type())
+        .unwrap_or("image/jpeg");
+    format!("data:{};base64,{}", mime, BASE64.encode(data))
+}
+
+/// Replace the images attached to messages by their type and size, which is
+/// all a trace needs of them
+fn leave_out_images(value: &mut Value) {
+    match value {
+        Value::String(text) if text.starts_with("data:") => {
+            if let Some((mime, data)) = text.split_once(";base64,") {
+                *text = format!("{};base64,<{} bytes left out>", mime, data.len() * 3 / 4);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(leave_out_images),
+        Value::Object(fields) => fields.values_mut().for_each(leave_out_images),
+        _ => {}
+    }
+}
+
+/// Extract JSON from an AI response (may be wrapped in markdown code blocks)
+fn extract_json(response: &str) -> &str {
+    if response.contains("```json") {
+        response
+            .split("```json")
+            .nth(1)
+            .and_then(|s| s.split("```").next())
+            .unwrap_or(response)
+            .trim()
+    } else if response.contains("```") {
+        response
+            .split("```")
+            .nth(1)
+            .and_then(|s| s.split("```").next())
+            .unwrap_or(response)
+            .trim()
+    } else {
+        response.trim()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_params() {
+        let env = GenerationParams {
+            temperature: Some(0.7),
+            seed: Some(42),
+            ..Default::default()
+        };
+        let flags = GenerationParams {
+            temperature: Some(0.0),
+            max_tokens: Some(800),
+            ..Default::default()
+        };
+        let params = env.overridden_by(flags);
+        assert_eq!(params.temperature, Some(0.0));
+        assert_eq!(params.seed, Some(42));
+
+        // Unset parameters are left out of the request
+        let request = ChatCompletionRequest {
+            model: "m".to_string(),
+            messages: Vec::new(),
+            params,
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({"model": "m", "messages": [], "temperature": 0.0, "max_tokens": 800, "seed": 42})
+        );
+
+        assert!(GenerationParams { temperature: Some(2.5), ..Default::default() }.validated().is_err());
+        assert!(GenerationParams { top_p: Some(0.0), ..Default::default() }.validated().is_err());
+        assert!(GenerationParams { max_tokens: Some(0), ..Default::default() }.validated().is_err());
+    }
+
+    #[test]
+    fn test_traces_leave_out_images() {
+        let mut messages = json!([{
+            "role": "user",
+            "content": [
+                {"type": "text", "text": "Roots"},
+                {"type": "image_url", "image_url": {"url": image_data_url(&[0; 300])}}
+            ]
+        }]);
+        leave_out_images(&mut messages);
+        assert_eq!(messages[0]["content"][0]["text"], "Roots");
+        assert_eq!(
+            messages[0]["content"][1]["image_url"]["url"],
+            "data:image/jpeg;base64,<300 bytes left out>"
+        );
+    }
+}
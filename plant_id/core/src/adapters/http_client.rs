@@ -0,0 +1,144 @@
+/*!
+ * HTTP CLIENT
+ *
+ * Shared HTTP client construction for adapters that call external APIs,
+ * so every request is bounded by the same configurable timeout and goes
+ * through the same proxy and trusted certificates.
+ *
+ * The timeout comes from `--timeout`, then `REQUEST_TIMEOUT_SECS`,
+ * then `DEFAULT_TIMEOUT_SECS`. Requests go through `HTTPS_PROXY`,
+ * `HTTP_PROXY` or `ALL_PROXY` except for the hosts in `NO_PROXY`, and
+ * `CA_BUNDLE` names a PEM file of extra root certificates to trust, such
+ * as a corporate proxy's.
+ */
+
+use anyhow::{Context, Result};
+use reqwest::{Certificate, Client, NoProxy, Proxy, Url};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Default limit for a single API request
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Limit for establishing a connection
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+static REQUEST_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// Override the request timeout for the rest of the process
+pub fn set_request_timeout(timeout: Duration) {
+    REQUEST_TIMEOUT.set(timeout).ok();
+}
+
+pub fn request_timeout() -> Duration {
+    *REQUEST_TIMEOUT.get_or_init(|| {
+        let secs = std::env::var("REQUEST_TIMEOUT_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+        Duration::from_secs(secs)
+    })
+}
+
+pub fn build_client() -> Result<Client> {
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .timeout(request_timeout());
+    for proxy in proxies()? {
+        builder = builder.proxy(proxy);
+    }
+    for certificate in ca_bundle()? {
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Base URL of an API, from `var` when it is set (to reach it through a
+/// gateway or a test server) and `default` otherwise, without a trailing slash
+pub fn base_url(var: &str, default: &str) -> Result<String> {
+    match std::env::var(var) {
+        Ok(value) if !value.trim().is_empty() => parse_base_url(var, &value),
+        _ => Ok(default.to_string()),
+    }
+}
+
+fn parse_base_url(var: &str, value: &str) -> Result<String> {
+    let value = value.trim().trim_end_matches('/');
+    let url = Url::parse(value).with_context(|| format!("{} is not a URL: '{}'", var, value))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("{} must be an http or https URL: '{}'", var, value);
+    }
+    Ok(value.to_string())
+}
+
+/// An environment variable in upper or lower case, as proxy variables are
+/// commonly set either way
+fn env_either(name: &str) -> Option<String> {
+    std::env::var(name)
+        .or_else(|_| std::env::var(name.to_lowercase()))
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Proxies from the environment. Building them here, rather than leaving
+/// it to reqwest, reports a malformed proxy URL instead of ignoring it.
+fn proxies() -> Result<Vec<Proxy>> {
+    let mut proxies = Vec::new();
+    for var in ["HTTPS_PROXY", "HTTP_PROXY", "ALL_PROXY"] {
+        let Some(url) = env_either(var) else {
+            continue;
+        };
+        let url = url.trim();
+        let proxy = match var {
+            "HTTPS_PROXY" => Proxy::https(url),
+            "HTTP_PROXY" => Proxy::http(url),
+            _ => Proxy::all(url),
+        }
+        .with_context(|| format!("{} is not a valid proxy URL: '{}'", var, url))?;
+        proxies.push(proxy.no_proxy(NoProxy::from_env()));
+    }
+    Ok(proxies)
+}
+
+/// Extra root certificates from the PEM file named by `CA_BUNDLE`
+fn ca_bundle() -> Result<Vec<Certificate>> {
+    let Some(path) = std::env::var("CA_BUNDLE").ok().filter(|p| !p.trim().is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let pem = std::fs::read(path.trim()).with_context(|| format!("Failed to read CA_BUNDLE {}", path))?;
+    let certificates =
+        Certificate::from_pem_bundle(&pem).with_context(|| format!("CA_BUNDLE {} is not a PEM file", path))?;
+    if certificates.is_empty() {
+        anyhow::bail!("CA_BUNDLE {} contains no certificates", path);
+    }
+    Ok(certificates)
+}
+
+/// Turn a failed request into an error that explains timeouts
+pub fn request_error(service: &str, error: reqwest::Error) -> anyhow::Error {
+    if error.is_timeout() {
+        anyhow::anyhow!(
+            "{} request timed out after {}s (use --timeout to allow longer)",
+            service,
+            request_timeout().as_secs()
+        )
+    } else {
+        anyhow::Error::new(error).context(format!("{} request failed", service))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_override() {
+        assert_eq!(
+            parse_base_url("OPENROUTER_BASE_URL", " http://localhost:4000/v1/ ").unwrap(),
+            "http://localhost:4000/v1"
+        );
+        assert!(parse_base_url("OPENROUTER_BASE_URL", "localhost:4000").is_err());
+        assert!(parse_base_url("PLANT_ID_BASE_URL", "ftp://example.com").is_err());
+    }
+}
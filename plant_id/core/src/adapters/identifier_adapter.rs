@@ -0,0 +1,182 @@
+/*!
+ * IDENTIFIER ADAPTER
+ *
+ * Secondary port for a second opinion on the species Plant.id recognised.
+ * `SECOND_IDENTIFIER` selects it: `offline` runs a local model given by
+ * `OFFLINE_MODEL_COMMAND`, `trefle` checks Plant.id's species against the
+ * Trefle database (needs `TREFLE_API_TOKEN`), and `none`, the default,
+ * trusts Plant.id alone.
+ *
+ * The offline model is any program that takes the path of a photo as its
+ * last argument and prints the species it sees as JSON:
+ * `{"scientific_name": "...", "confidence": 0.8, "common_names": ["..."]}`.
+ */
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::adapters::species_data_adapter::TrefleAdapter;
+use crate::adapters::{IdentifierPort, SpeciesDataPort};
+use crate::domain::SpeciesCandidate;
+
+/// How long the offline model may take to answer
+const OFFLINE_MODEL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Create the identifier selected by `SECOND_IDENTIFIER`, if any
+pub fn from_env() -> Result<Option<Arc<dyn IdentifierPort>>> {
+    let identifier = std::env::var("SECOND_IDENTIFIER").unwrap_or_else(|_| "none".to_string());
+
+    match identifier.as_str() {
+        "none" | "" => Ok(None),
+        "offline" => Ok(Some(Arc::new(OfflineModel::from_env()?))),
+        "trefle" => Ok(Some(Arc::new(TrefleIdentifier::new()?))),
+        other => anyhow::bail!(
+            "Unknown SECOND_IDENTIFIER '{}': expected 'offline', 'trefle' or 'none'",
+            other
+        ),
+    }
+}
+
+/// A local model run as a program, from `OFFLINE_MODEL_COMMAND`
+pub struct OfflineModel {
+    program: String,
+    args: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelAnswer {
+    scientific_name: String,
+    #[serde(default)]
+    confidence: Option<f64>,
+    #[serde(default)]
+    common_names: Vec<String>,
+}
+
+impl OfflineModel {
+    pub fn from_env() -> Result<Self> {
+        let command = std::env::var("OFFLINE_MODEL_COMMAND")
+            .context("SECOND_IDENTIFIER=offline needs OFFLINE_MODEL_COMMAND, e.g. 'python3 classify.py'")?;
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = words.next().context("OFFLINE_MODEL_COMMAND is empty")?;
+
+        Ok(Self {
+            program,
+            args: words.collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl IdentifierPort for OfflineModel {
+    fn name(&self) -> &str {
+        "offline model"
+    }
+
+    async fn identify(&self, image: &[u8], _plant_id: &SpeciesCandidate) -> Result<Option<SpeciesCandidate>> {
+        let path = std::env::temp_dir().join(format!("plant-care-{}.img", Uuid::new_v4()));
+        tokio::fs::write(&path, image).await.context("Failed to write the photo for the offline model")?;
+
+        let output = tokio::time::timeout(
+            OFFLINE_MODEL_TIMEOUT,
+            Command::new(&self.program).args(&self.args).arg(&path).output(),
+        )
+        .await;
+        tokio::fs::remove_file(&path).await.ok();
+
+        let output = output
+            .with_context(|| format!("{} did not answer within {:?}", self.program, OFFLINE_MODEL_TIMEOUT))?
+            .with_context(|| format!("Failed to run {}", self.program))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let answer = parse_answer(&String::from_utf8_lossy(&output.stdout))
+            .with_context(|| format!("Failed to parse the species {} printed", self.program))?;
+
+        Ok(answer.map(|answer| SpeciesCandidate {
+            source: self.name().to_string(),
+            scientific_name: answer.scientific_name,
+            common_names: answer.common_names,
+            confidence: answer.confidence,
+        }))
+    }
+}
+
+/// The species an offline model printed: one JSON object, or a list of them
+/// with the best first. An empty list means it saw no plant it knows.
+fn parse_answer(stdout: &str) -> Result<Option<ModelAnswer>> {
+    let stdout = stdout.trim();
+    if stdout.starts_with('[') {
+        let answers: Vec<ModelAnswer> = serde_json::from_str(stdout)?;
+        return Ok(answers.into_iter().next());
+    }
+    Ok(Some(serde_json::from_str(stdout)?))
+}
+
+/// Looks Plant.id's species up in Trefle, which may know it under another
+/// name. Trefle does not look at the photo, so it gives no confidence.
+pub struct TrefleIdentifier {
+    trefle: TrefleAdapter,
+}
+
+impl TrefleIdentifier {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            trefle: TrefleAdapter::new()?,
+        })
+    }
+}
+
+#[async_trait]
+impl IdentifierPort for TrefleIdentifier {
+    fn name(&self) -> &str {
+        "Trefle"
+    }
+
+    async fn identify(&self, _image: &[u8], plant_id: &SpeciesCandidate) -> Result<Option<SpeciesCandidate>> {
+        let profile = self.trefle.lookup(&plant_id.scientific_name).await?;
+
+        Ok(profile.map(|profile| SpeciesCandidate {
+            source: self.name().to_string(),
+            scientific_name: profile.scientific_name,
+            common_names: profile.common_name.into_iter().collect(),
+            confidence: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer() {
+        let answer = parse_answer(r#"{"scientific_name": "Monstera adansonii", "confidence": 0.64}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(answer.scientific_name, "Monstera adansonii");
+        assert_eq!(answer.confidence, Some(0.64));
+        assert!(answer.common_names.is_empty());
+
+        let answer = parse_answer(
+            "[{\"scientific_name\": \"Ficus lyrata\", \"common_names\": [\"Fiddle-leaf fig\"]}, {\"scientific_name\": \"Ficus elastica\"}]\n",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(answer.scientific_name, "Ficus lyrata");
+        assert_eq!(answer.common_names, vec!["Fiddle-leaf fig"]);
+
+        assert!(parse_answer("[]").unwrap().is_none());
+        assert!(parse_answer("Monstera adansonii").is_err());
+    }
+}
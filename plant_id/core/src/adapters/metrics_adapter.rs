@@ -0,0 +1,123 @@
+/*!
+ * METRICS ADAPTER
+ *
+ * Services and adapters record measurements through the `metrics` facade;
+ * without an installed exporter they cost nothing. `MetricsExporter`
+ * collects them in the Prometheus format, for the HTTP server's `/metrics`
+ * endpoint or, in CLI mode, a file written after each command.
+ */
+
+use anyhow::{Context, Result};
+use metrics::{describe_counter, describe_histogram, Unit};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::config::database::slow_query_threshold;
+
+/// Latency of AI requests, labelled by `model` and `outcome`
+pub const AI_REQUEST_DURATION: &str = "plant_care_ai_request_duration_seconds";
+
+/// Plant identifications, labelled by `outcome` (success or failure)
+pub const IDENTIFICATIONS: &str = "plant_care_identifications_total";
+
+/// Plant.id health assessments of diagnosis photos, labelled by `outcome`
+pub const HEALTH_ASSESSMENTS: &str = "plant_care_health_assessments_total";
+
+/// Diagnosis cycles, labelled by `result` (ask, request_photo, conclude or error)
+pub const DIAGNOSIS_CYCLES: &str = "plant_care_diagnosis_cycles_total";
+
+/// Duration of database queries, labelled by `query`
+pub const DB_QUERY_DURATION: &str = "plant_care_db_query_duration_seconds";
+
+/// Plant reads answered by the plant cache, labelled by `result` (hit or miss)
+pub const PLANT_CACHE: &str = "plant_care_plant_cache_total";
+
+/// Label value for the outcome of an operation
+pub fn outcome<T>(result: &Result<T>) -> &'static str {
+    if result.is_ok() {
+        "success"
+    } else {
+        "failure"
+    }
+}
+
+/// Record the time since `started` as the duration of a database query,
+/// and log it: at debug level, or as a warning above the slow-query
+/// threshold
+pub fn record_db_query(query: &'static str, started: Instant) {
+    let elapsed = started.elapsed();
+    metrics::histogram!(DB_QUERY_DURATION, "query" => query).record(elapsed.as_secs_f64());
+
+    let duration_ms = elapsed.as_millis() as u64;
+    let threshold = slow_query_threshold();
+    if elapsed >= threshold {
+        log::warn!(
+            target: "plant_care::db",
+            query,
+            duration_ms,
+            threshold_ms = threshold.as_millis() as u64;
+            "Slow query {} took {} ms",
+            query,
+            duration_ms
+        );
+    } else {
+        log::debug!(target: "plant_care::db", query, duration_ms; "Query {} took {} ms", query, duration_ms);
+    }
+}
+
+static EXPORTER: OnceLock<MetricsExporter> = OnceLock::new();
+
+/// Collects the metrics of this process in the Prometheus format
+#[derive(Clone)]
+pub struct MetricsExporter {
+    handle: PrometheusHandle,
+}
+
+impl MetricsExporter {
+    /// Install the exporter as the process-wide recorder, or return the
+    /// one already installed
+    pub fn install() -> Result<Self> {
+        if let Some(exporter) = EXPORTER.get() {
+            return Ok(exporter.clone());
+        }
+
+        let handle = PrometheusBuilder::new()
+            .install_recorder()
+            .context("Failed to install the metrics recorder")?;
+
+        describe_histogram!(AI_REQUEST_DURATION, Unit::Seconds, "Latency of AI requests");
+        describe_counter!(IDENTIFICATIONS, "Plant identifications by outcome");
+        describe_counter!(HEALTH_ASSESSMENTS, "Health assessments of diagnosis photos by outcome");
+        describe_counter!(DIAGNOSIS_CYCLES, "Diagnosis cycles by result");
+        describe_histogram!(DB_QUERY_DURATION, Unit::Seconds, "Duration of database queries");
+        describe_counter!(PLANT_CACHE, "Plant reads answered from the plant cache (hit) or the database (miss)");
+
+        Ok(EXPORTER.get_or_init(|| Self { handle }).clone())
+    }
+
+    /// Current metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        self.handle.render()
+    }
+
+    /// Write the current metrics to `path`, replacing its contents. The
+    /// file can be read by node_exporter's textfile collector.
+    pub fn write_file(&self, path: &str) -> Result<()> {
+        // Write then rename, so a collector never reads a partial file
+        let temp = format!("{}.tmp", path);
+        std::fs::write(&temp, self.render()).context("Failed to write metrics file")?;
+        std::fs::rename(&temp, path).context("Failed to write metrics file")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(outcome(&Ok::<(), anyhow::Error>(())), "success");
+        assert_eq!(outcome::<()>(&Err(anyhow::anyhow!("timeout"))), "failure");
+    }
+}
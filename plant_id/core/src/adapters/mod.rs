@@ -0,0 +1,51 @@
+/*!
+ * ADAPTERS
+ *
+ * Secondary adapters that interact with external services.
+ * These implement the hexagonal architecture's secondary ports.
+ *
+ * The ports, progress reporters and the diagnosis action executor build on
+ * every target; the adapters that do I/O need the `native` feature.
+ */
+
+#[cfg(feature = "native")]
+pub mod ai_adapter;
+#[cfg(feature = "native")]
+pub mod http_client;
+#[cfg(feature = "native")]
+pub mod identifier_adapter;
+#[cfg(feature = "native")]
+pub mod metrics_adapter;
+#[cfg(feature = "native")]
+pub mod notification_adapter;
+#[cfg(feature = "native")]
+pub mod payload;
+#[cfg(feature = "native")]
+pub mod plant_id_adapter;
+pub mod ports;
+pub mod progress_adapter;
+#[cfg(feature = "native")]
+pub mod s3_storage_adapter;
+pub mod sandbox_executor;
+#[cfg(feature = "native")]
+pub mod search_adapter;
+#[cfg(feature = "native")]
+pub mod smtp_client;
+#[cfg(feature = "native")]
+pub mod species_data_adapter;
+#[cfg(feature = "native")]
+pub mod storage_adapter;
+#[cfg(feature = "native")]
+pub mod sync_adapter;
+
+#[cfg(feature = "native")]
+pub use ai_adapter::AiAdapter;
+#[cfg(feature = "native")]
+pub use metrics_adapter::MetricsExporter;
+#[cfg(feature = "native")]
+pub use plant_id_adapter::PlantIdAdapter;
+pub use ports::{HealthAssessmentPort, IdentifierPort, NotificationPort, SearchPort, SearchResult, SpeciesDataPort, StoragePort, SyncRemote};
+pub use progress_adapter::{JsonProgress, LineProgress, ProgressReporter, SilentProgress};
+#[cfg(feature = "native")]
+pub use s3_storage_adapter::S3StorageAdapter;
+pub use sandbox_executor::{ActionEffect, ExecutionResult, SandboxExecutor};
@@ -0,0 +1,358 @@
+/*!
+ * NOTIFICATION ADAPTER
+ *
+ * Secondary port for delivering events to webhooks and email. They are
+ * configured with `NOTIFY_WEBHOOKS`, a comma-separated list of
+ * `<backend>:<url>` entries:
+ *
+ * - `json:<url>`  POSTs the event as JSON
+ * - `slack:<url>` posts a message to a Slack incoming webhook
+ * - `ntfy:<url>`  publishes a push notification to an ntfy topic URL
+ * - `email:<address>` emails the event through the `SMTP_*` mail server (see `smtp_client`)
+ * - `desktop`     shows a desktop notification (`notify-send` or macOS's Notification Center)
+ *
+ * e.g. `NOTIFY_WEBHOOKS=slack:https://hooks.slack.com/services/...,ntfy:https://ntfy.sh/my-plants`
+ *
+ * Every backend gets every event unless `NOTIFY_ROUTES` limits it, with
+ * comma-separated `<backend>=<event>|<event>...` rules. Events are named
+ * as in the JSON `event` field, or `urgent` for critical diagnoses, e.g.
+ * `NOTIFY_ROUTES=email=watering_overdue|urgent`.
+ *
+ * Events are delivered as soon as they happen. Urgent ones (critical
+ * diagnoses) go out with the highest priority the backend supports.
+ */
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::process::Command;
+
+use crate::adapters::smtp_client::{self, Email, SmtpConfig};
+use crate::adapters::{http_client, NotificationPort};
+use crate::domain::NotificationEvent;
+
+/// Routing rule matching critical diagnoses
+const URGENT: &str = "urgent";
+
+/// Create the webhooks configured in `NOTIFY_WEBHOOKS`, routed by
+/// `NOTIFY_ROUTES`
+pub fn from_env() -> Result<Vec<Arc<dyn NotificationPort>>> {
+    let config = std::env::var("NOTIFY_WEBHOOKS").unwrap_or_default();
+    let routes = parse_routes(&std::env::var("NOTIFY_ROUTES").unwrap_or_default())?;
+    let client = http_client::build_client()?;
+    // Only read when an email entry needs it
+    let mut smtp: Option<Arc<SmtpConfig>> = None;
+
+    let webhooks = parse_webhooks(&config)?;
+    if let Some(backend) = routes.keys().find(|b| !webhooks.iter().any(|(w, _)| w == *b)) {
+        log::warn!("NOTIFY_ROUTES has a rule for '{}', which NOTIFY_WEBHOOKS does not list", backend);
+    }
+
+    webhooks
+        .into_iter()
+        .map(|(backend, url)| -> Result<Arc<dyn NotificationPort>> {
+            let client = client.clone();
+            let notifier: Arc<dyn NotificationPort> = match (backend, url) {
+                ("desktop", None) => Arc::new(DesktopNotifier),
+                ("json", Some(url)) => Arc::new(JsonWebhookNotifier { client, url }),
+                ("slack", Some(url)) => Arc::new(SlackNotifier { client, url }),
+                ("ntfy", Some(url)) => Arc::new(NtfyNotifier { client, url }),
+                ("email", Some(to)) => {
+                    let smtp = match &smtp {
+                        Some(smtp) => smtp.clone(),
+                        None => smtp.insert(Arc::new(SmtpConfig::from_env()?)).clone(),
+                    };
+                    Arc::new(EmailNotifier { smtp, to })
+                }
+                (other, _) => anyhow::bail!(
+                    "Unknown webhook backend '{}' in NOTIFY_WEBHOOKS: expected 'json', 'slack', 'ntfy', 'email' or 'desktop'",
+                    other
+                ),
+            };
+            Ok(match routes.get(backend) {
+                Some(events) => Arc::new(RoutedNotifier {
+                    inner: notifier,
+                    events: events.clone(),
+                }),
+                None => notifier,
+            })
+        })
+        .collect()
+}
+
+/// Split `NOTIFY_WEBHOOKS` into (backend, url) pairs, the url being an
+/// address for `email`. `desktop` is the only entry without one.
+fn parse_webhooks(config: &str) -> Result<Vec<(&str, Option<String>)>> {
+    config
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            if entry == "desktop" {
+                return Ok((entry, None));
+            }
+            let (backend, url) = entry
+                .split_once(':')
+                .filter(|(backend, url)| match *backend {
+                    "email" => url.contains('@'),
+                    _ => url.starts_with("http"),
+                })
+                .with_context(|| {
+                    format!("Invalid NOTIFY_WEBHOOKS entry '{}': expected <backend>:<url> or email:<address>", entry)
+                })?;
+            Ok((backend, Some(url.to_string())))
+        })
+        .collect()
+}
+
+/// Split `NOTIFY_ROUTES` into the events each backend takes
+fn parse_routes(config: &str) -> Result<HashMap<String, Vec<String>>> {
+    config
+        .split(',')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(|rule| {
+            let (backend, events) = rule
+                .split_once('=')
+                .with_context(|| format!("Invalid NOTIFY_ROUTES rule '{}': expected <backend>=<event>|<event>...", rule))?;
+            let events: Vec<String> = events
+                .split('|')
+                .map(str::trim)
+                .filter(|event| !event.is_empty())
+                .map(|event| {
+                    if event != URGENT && !NotificationEvent::NAMES.contains(&event) {
+                        anyhow::bail!(
+                            "Unknown event '{}' in NOTIFY_ROUTES: expected {} or '{}'",
+                            event,
+                            NotificationEvent::NAMES.join(", "),
+                            URGENT
+                        );
+                    }
+                    Ok(event.to_string())
+                })
+                .collect::<Result<_>>()?;
+            Ok((backend.trim().to_string(), events))
+        })
+        .collect()
+}
+
+/// A backend that only takes the events its `NOTIFY_ROUTES` rule names
+struct RoutedNotifier {
+    inner: Arc<dyn NotificationPort>,
+    events: Vec<String>,
+}
+
+#[async_trait]
+impl NotificationPort for RoutedNotifier {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn accepts(&self, event: &NotificationEvent) -> bool {
+        self.events
+            .iter()
+            .any(|e| e == event.name() || (e == URGENT && event.is_urgent()))
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        self.inner.notify(event).await
+    }
+}
+
+async fn send(name: &str, request: RequestBuilder) -> Result<()> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| http_client::request_error(name, e))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("{} webhook returned {}", name, response.status());
+    }
+
+    Ok(())
+}
+
+/// POSTs the event itself as JSON
+pub struct JsonWebhookNotifier {
+    client: Client,
+    url: String,
+}
+
+#[async_trait]
+impl NotificationPort for JsonWebhookNotifier {
+    fn name(&self) -> &str {
+        "JSON"
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        send(self.name(), self.client.post(&self.url).json(event)).await
+    }
+}
+
+/// Posts to a Slack incoming webhook
+pub struct SlackNotifier {
+    client: Client,
+    url: String,
+}
+
+#[async_trait]
+impl NotificationPort for SlackNotifier {
+    fn name(&self) -> &str {
+        "Slack"
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        let body = json!({ "text": format!("*{}*\n{}", event.title(), event.message()) });
+        send(self.name(), self.client.post(&self.url).json(&body)).await
+    }
+}
+
+/// Publishes to an ntfy topic (https://ntfy.sh or a self-hosted server)
+pub struct NtfyNotifier {
+    client: Client,
+    url: String,
+}
+
+#[async_trait]
+impl NotificationPort for NtfyNotifier {
+    fn name(&self) -> &str {
+        "ntfy"
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        let request = self
+            .client
+            .post(&self.url)
+            // Query parameters rather than headers, which cannot carry emoji
+            .query(&[
+                ("title", event.title().as_str()),
+                ("tags", event.name()),
+                ("priority", if event.is_urgent() { "urgent" } else { "default" }),
+            ])
+            .body(event.message());
+        send(self.name(), request).await
+    }
+}
+
+/// Emails the event through the configured mail server
+pub struct EmailNotifier {
+    smtp: Arc<SmtpConfig>,
+    to: String,
+}
+
+#[async_trait]
+impl NotificationPort for EmailNotifier {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        let email = Email {
+            to: self.to.clone(),
+            subject: event.title(),
+            body: format!("{}\n\n-- \nSent by plant-care", event.message()),
+            urgent: event.is_urgent(),
+        };
+        smtp_client::send(&self.smtp, &email).await
+    }
+}
+
+/// Shows a notification on this computer's desktop
+pub struct DesktopNotifier;
+
+#[async_trait]
+impl NotificationPort for DesktopNotifier {
+    fn name(&self) -> &str {
+        "desktop"
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        let (program, mut command) = if cfg!(target_os = "macos") {
+            let mut command = Command::new("osascript");
+            // Debug formatting quotes and escapes the strings as AppleScript expects
+            command.arg("-e").arg(format!(
+                "display notification {:?} with title {:?}",
+                event.message(),
+                event.title()
+            ));
+            ("osascript", command)
+        } else {
+            let mut command = Command::new("notify-send");
+            command
+                .arg("--app-name=plant-care")
+                .arg(if event.is_urgent() { "--urgency=critical" } else { "--urgency=normal" })
+                .arg(event.title())
+                .arg(event.message());
+            ("notify-send", command)
+        };
+
+        let status = command
+            .status()
+            .await
+            .with_context(|| format!("Failed to run {}", program))?;
+        if !status.success() {
+            anyhow::bail!("{} exited with {}", program, status);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Severity;
+
+    #[test]
+    fn test_parse_webhooks() {
+        let webhooks =
+            parse_webhooks("slack:https://hooks.slack.com/x, ntfy:https://ntfy.sh/plants, desktop").unwrap();
+        assert_eq!(
+            webhooks,
+            vec![
+                ("slack", Some("https://hooks.slack.com/x".to_string())),
+                ("ntfy", Some("https://ntfy.sh/plants".to_string())),
+                ("desktop", None),
+            ]
+        );
+        assert!(parse_webhooks("").unwrap().is_empty());
+        assert!(parse_webhooks("https://example.com/hook").is_err());
+        assert_eq!(
+            parse_webhooks("email:me@example.com").unwrap(),
+            vec![("email", Some("me@example.com".to_string()))]
+        );
+        assert!(parse_webhooks("email:https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_routes() {
+        let routes = parse_routes("email=watering_overdue|urgent, desktop=urgent").unwrap();
+        let email = RoutedNotifier {
+            inner: Arc::new(DesktopNotifier),
+            events: routes["email"].clone(),
+        };
+        let overdue = NotificationEvent::WateringOverdue {
+            plant_id: "p".to_string(),
+            plant_name: "Fern".to_string(),
+            days_overdue: 2,
+        };
+        let diagnosis = |severity| NotificationEvent::DiagnosisConcluded {
+            plant_id: "p".to_string(),
+            plant_name: "Fern".to_string(),
+            diagnosis_id: "d".to_string(),
+            finding: "Root rot".to_string(),
+            recommendation: "Repot".to_string(),
+            severity: Some(severity),
+        };
+        assert!(email.accepts(&overdue));
+        assert!(email.accepts(&diagnosis(Severity::Critical)));
+        assert!(!email.accepts(&diagnosis(Severity::Minor)));
+        assert_eq!(routes["desktop"], ["urgent"]);
+
+        assert!(parse_routes("email=watered").is_err());
+        assert!(parse_routes("email").is_err());
+    }
+}
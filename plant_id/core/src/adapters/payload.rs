@@ -0,0 +1,212 @@
+/*!
+ * PAYLOAD LIMITS
+ *
+ * Bounds on what goes out to and comes back from the external APIs, so an
+ * accidental 200 MB RAW file is refused before it is encoded and uploaded,
+ * and a misbehaving endpoint cannot stream an unbounded body into memory.
+ *
+ * Photos may be JPEG, PNG or WebP and up to `MAX_IMAGE_MB` megabytes
+ * (`DEFAULT_MAX_IMAGE_MB` by default); the servers accept requests large
+ * enough to carry one. API responses must be JSON and at most
+ * `MAX_RESPONSE_BYTES`. Breaches are reported as a `PayloadError`,
+ * which callers can find with `anyhow::Error::downcast_ref`.
+ */
+
+use anyhow::{Context, Result};
+use image::ImageFormat;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Default limit for a photo, in megabytes
+const DEFAULT_MAX_IMAGE_MB: u64 = 20;
+
+/// Limit for the body of an API response
+pub const MAX_RESPONSE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Limit for the part of an error response quoted in the error message
+const MAX_ERROR_TEXT_BYTES: usize = 4 * 1024;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Room for the rest of a request carrying a photo, such as the prompt
+const REQUEST_OVERHEAD_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PayloadError {
+    #[error("{name} is {:.1} MB, over the {:.1} MB limit (set MAX_IMAGE_MB to allow larger photos)", megabytes(.size), megabytes(.limit))]
+    ImageTooLarge { name: String, size: u64, limit: u64 },
+
+    #[error("{name} is not a JPEG, PNG or WebP image")]
+    UnsupportedImage { name: String },
+
+    #[error("{name} is not valid base64")]
+    InvalidEncoding { name: String },
+
+    #[error("{service} response is larger than {limit} bytes")]
+    ResponseTooLarge { service: String, limit: usize },
+
+    #[error("{service} responded with {content_type} instead of JSON")]
+    UnexpectedContentType { service: String, content_type: String },
+}
+
+fn megabytes(bytes: &u64) -> f64 {
+    *bytes as f64 / BYTES_PER_MB as f64
+}
+
+/// Largest photo accepted, from `MAX_IMAGE_MB`
+pub fn max_image_bytes() -> u64 {
+    std::env::var("MAX_IMAGE_MB")
+        .ok()
+        .and_then(|mb| mb.trim().parse::<u64>().ok())
+        .filter(|mb| *mb > 0)
+        .unwrap_or(DEFAULT_MAX_IMAGE_MB)
+        * BYTES_PER_MB
+}
+
+/// Largest request body a server accepts: a photo of `max_image_bytes`,
+/// base64-encoded, and the rest of the request
+pub fn max_request_bytes() -> usize {
+    (max_image_bytes().div_ceil(3) * 4 + REQUEST_OVERHEAD_BYTES) as usize
+}
+
+/// Read a photo from disk, refusing it by its size before reading it
+pub fn read_image(path: &Path) -> Result<Vec<u8>> {
+    let name = path.display().to_string();
+    let size = std::fs::metadata(path)
+        .with_context(|| format!("Failed to read image file {}", name))?
+        .len();
+    check_size(&name, size, max_image_bytes())?;
+
+    let data = std::fs::read(path).with_context(|| format!("Failed to read image file {}", name))?;
+    check_image(&name, &data)?;
+    Ok(data)
+}
+
+/// Check a photo's size and format, returning the format
+pub fn check_image(name: &str, data: &[u8]) -> Result<ImageFormat, PayloadError> {
+    check_image_within(name, data, max_image_bytes())
+}
+
+fn check_image_within(name: &str, data: &[u8], limit: u64) -> Result<ImageFormat, PayloadError> {
+    check_size(name, data.len() as u64, limit)?;
+    match image::guess_format(data) {
+        Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)) => Ok(format),
+        _ => Err(PayloadError::UnsupportedImage { name: name.to_string() }),
+    }
+}
+
+fn check_size(name: &str, size: u64, limit: u64) -> Result<(), PayloadError> {
+    if size > limit {
+        return Err(PayloadError::ImageTooLarge {
+            name: name.to_string(),
+            size,
+            limit,
+        });
+    }
+    Ok(())
+}
+
+/// Parse a successful API response, which must be JSON within
+/// `MAX_RESPONSE_BYTES`
+pub async fn read_json<T: DeserializeOwned>(service: &str, response: reqwest::Response) -> Result<T> {
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned());
+    check_content_type(service, content_type.as_deref())?;
+
+    if response.content_length().is_some_and(|length| length > MAX_RESPONSE_BYTES as u64) {
+        return Err(PayloadError::ResponseTooLarge {
+            service: service.to_string(),
+            limit: MAX_RESPONSE_BYTES,
+        }
+        .into());
+    }
+    let (body, truncated) = read_body(response, MAX_RESPONSE_BYTES).await?;
+    if truncated {
+        return Err(PayloadError::ResponseTooLarge {
+            service: service.to_string(),
+            limit: MAX_RESPONSE_BYTES,
+        }
+        .into());
+    }
+
+    serde_json::from_slice(&body).with_context(|| format!("{} returned an unexpected response", service))
+}
+
+/// The start of a failed API response, to quote in the error
+pub async fn error_text(response: reqwest::Response) -> Result<String> {
+    let (body, truncated) = read_body(response, MAX_ERROR_TEXT_BYTES).await?;
+    let mut text = String::from_utf8_lossy(&body).into_owned();
+    if truncated {
+        text.push_str("...");
+    }
+    Ok(text)
+}
+
+fn check_content_type(service: &str, content_type: Option<&str>) -> Result<(), PayloadError> {
+    match content_type {
+        // application/json, and variants such as application/problem+json
+        Some(content_type) if content_type.to_ascii_lowercase().contains("json") => Ok(()),
+        other => Err(PayloadError::UnexpectedContentType {
+            service: service.to_string(),
+            content_type: other.unwrap_or("no content type").to_string(),
+        }),
+    }
+}
+
+/// Read at most `limit` bytes of the body, and whether there was more
+async fn read_body(mut response: reqwest::Response, limit: usize) -> Result<(Vec<u8>, bool)> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            body.extend_from_slice(&chunk[..limit - body.len()]);
+            return Ok((body, true));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((body, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_image_limits() {
+        let mut png = Vec::new();
+        ImageBuffer::from_pixel(4, 4, Rgb([30u8, 120, 40]))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        assert_eq!(check_image_within("leaf.png", &png, 1024).unwrap(), ImageFormat::Png);
+        assert!(matches!(
+            check_image_within("leaf.png", &png, 16),
+            Err(PayloadError::ImageTooLarge { size, limit: 16, .. }) if size == png.len() as u64
+        ));
+        assert!(matches!(
+            check_image_within("leaf.cr2", b"not an image at all", 1024),
+            Err(PayloadError::UnsupportedImage { .. })
+        ));
+
+        let error = PayloadError::ImageTooLarge {
+            name: "leaf.cr2".to_string(),
+            size: 200 * BYTES_PER_MB,
+            limit: 20 * BYTES_PER_MB,
+        };
+        assert!(error.to_string().starts_with("leaf.cr2 is 200.0 MB, over the 20.0 MB limit"));
+    }
+
+    #[test]
+    fn test_responses_must_be_json() {
+        assert!(check_content_type("AI", Some("application/json; charset=utf-8")).is_ok());
+        assert!(check_content_type("AI", Some("application/problem+json")).is_ok());
+        assert!(matches!(
+            check_content_type("PlantID", Some("text/html")),
+            Err(PayloadError::UnexpectedContentType { ref service, .. }) if service == "PlantID"
+        ));
+        assert!(check_content_type("AI", None).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+/*
+ * PLANT ID ADAPTER
+ *
+ * Secondary adapter for plant identification using PlantID API, and for
+ * its health assessment of photos of sick plants.
+ */
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::adapters::{http_client, metrics_adapter, payload, HealthAssessmentPort};
+use crate::config::get_secret;
+use crate::domain::{DiseaseSuggestion, HealthAssessment, SpeciesCandidate};
+use crate::dto::PlantCreationDto;
+
+/// Where requests go unless `PLANT_ID_BASE_URL` points elsewhere
+const DEFAULT_BASE_URL: &str = "https://api.plant.id/v2";
+
+/// Plant.id's health assessment, when PLANT_ID_API_KEY is set; without it
+/// diagnoses start from the owner's description alone
+pub fn health_assessor() -> Option<Arc<dyn HealthAssessmentPort>> {
+    match PlantIdAdapter::new() {
+        Ok(adapter) => Some(Arc::new(adapter)),
+        Err(e) => {
+            log::debug!("Photos will not be assessed before diagnoses: {:#}", e);
+            None
+        }
+    }
+}
+
+pub struct PlantIdAdapter {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+#[derive(Debug, Serialize)]
+struct IdentificationRequest {
+    images: Vec<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    plant_details: Vec<&'static str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdentificationResponse {
+    suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Suggestion {
+    plant_name: String,
+    #[serde(default)]
+    probability: Option<f64>,
+    #[serde(default)]
+    plant_details: Option<PlantDetails>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PlantDetails {
+    scientific_name: Option<String>,
+    #[serde(default)]
+    common_names: Option<Vec<String>>,
+}
+
+impl From<Suggestion> for SpeciesCandidate {
+    fn from(suggestion: Suggestion) -> Self {
+        let details = suggestion.plant_details.unwrap_or_default();
+        let mut common_names: Vec<String> = Vec::new();
+        for name in details.common_names.unwrap_or_default() {
+            let name = name.trim().to_string();
+            if !name.is_empty() && !common_names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                common_names.push(name);
+            }
+        }
+
+        Self {
+            source: "Plant.id".to_string(),
+            scientific_name: details.scientific_name.unwrap_or(suggestion.plant_name),
+            common_names,
+            confidence: suggestion.probability,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HealthAssessmentRequest {
+    images: Vec<String>,
+    disease_details: Vec<&'static str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HealthAssessmentResponse {
+    health_assessment: HealthResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct HealthResult {
+    #[serde(default)]
+    is_healthy_probability: Option<f64>,
+    #[serde(default)]
+    diseases: Vec<Disease>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Disease {
+    name: String,
+    probability: f64,
+    /// Set on a broader category of a more specific suggestion
+    #[serde(default)]
+    redundant: bool,
+    #[serde(default)]
+    disease_details: Option<DiseaseDetails>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DiseaseDetails {
+    #[serde(default)]
+    common_names: Option<Vec<String>>,
+}
+
+impl From<HealthResult> for HealthAssessment {
+    fn from(result: HealthResult) -> Self {
+        let diseases = result
+            .diseases
+            .into_iter()
+            .filter(|disease| !disease.redundant)
+            .map(|disease| DiseaseSuggestion {
+                name: disease.name,
+                probability: disease.probability,
+                common_names: disease
+                    .disease_details
+                    .unwrap_or_default()
+                    .common_names
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        HealthAssessment::new("Plant.id", result.is_healthy_probability, diseases)
+    }
+}
+
+impl PlantIdAdapter {
+    pub fn new() -> Result<Self> {
+        let api_key = get_secret("PLANT_ID_API_KEY")?;
+
+        Ok(Self {
+            client: http_client::build_client()?,
+            base_url: http_client::base_url("PLANT_ID_BASE_URL", DEFAULT_BASE_URL)?,
+            api_key,
+        })
+    }
+
+    pub async fn identify_plant(&self, dto: &PlantCreationDto) -> Result<SpeciesCandidate> {
+        let result = self.request_identification(dto).await;
+        metrics::counter!(metrics_adapter::IDENTIFICATIONS, "outcome" => metrics_adapter::outcome(&result))
+            .increment(1);
+
+        result
+    }
+
+    async fn request_identification(&self, dto: &PlantCreationDto) -> Result<SpeciesCandidate> {
+        let request = IdentificationRequest {
+            images: dto.images.clone(),
+            latitude: dto.latitude,
+            longitude: dto.longitude,
+            plant_details: vec!["common_names", "scientific_name"],
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/identify", self.base_url))
+            .header("Api-Key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| http_client::request_error("PlantID", e))?;
+
+        if !response.status().is_success() {
+            let error_text = payload::error_text(response).await?;
+            anyhow::bail!("PlantID API error: {}", error_text);
+        }
+
+        let identification: IdentificationResponse = payload::read_json("PlantID", response).await?;
+
+        identification
+            .suggestions
+            .into_iter()
+            .next()
+            .map(SpeciesCandidate::from)
+            .context("No plant suggestions returned from PlantID API")
+    }
+
+    async fn request_health_assessment(&self, image: &[u8]) -> Result<HealthAssessment> {
+        let request = HealthAssessmentRequest {
+            images: vec![BASE64.encode(image)],
+            disease_details: vec!["common_names"],
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/health_assessment", self.base_url))
+            .header("Api-Key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| http_client::request_error("PlantID", e))?;
+
+        if !response.status().is_success() {
+            let error_text = payload::error_text(response).await?;
+            anyhow::bail!("PlantID API error: {}", error_text);
+        }
+
+        let assessment: HealthAssessmentResponse = payload::read_json("PlantID", response).await?;
+        Ok(assessment.health_assessment.into())
+    }
+}
+
+#[async_trait]
+impl HealthAssessmentPort for PlantIdAdapter {
+    async fn assess_health(&self, image: &[u8]) -> Result<HealthAssessment> {
+        let result = self.request_health_assessment(image).await;
+        metrics::counter!(metrics_adapter::HEALTH_ASSESSMENTS, "outcome" => metrics_adapter::outcome(&result))
+            .increment(1);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identification_from_suggestion() {
+        let suggestion: Suggestion = serde_json::from_value(serde_json::json!({
+            "plant_name": "Monstera deliciosa",
+            "probability": 0.87,
+            "plant_details": {
+                "scientific_name": "Monstera deliciosa",
+                "common_names": ["Swiss cheese plant", " swiss cheese plant ", "Split-leaf philodendron"]
+            }
+        }))
+        .unwrap();
+        let identification = SpeciesCandidate::from(suggestion);
+        assert_eq!(identification.common_names, vec!["Swiss cheese plant", "Split-leaf philodendron"]);
+        assert_eq!(identification.display_name(), "Swiss cheese plant");
+        assert_eq!(identification.confidence, Some(0.87));
+
+        // Rare species often have no common name at all
+        let suggestion: Suggestion = serde_json::from_value(serde_json::json!({
+            "plant_name": "Anthurium warocqueanum",
+            "plant_details": { "common_names": null }
+        }))
+        .unwrap();
+        let identification = SpeciesCandidate::from(suggestion);
+        assert_eq!(identification.scientific_name, "Anthurium warocqueanum");
+        assert_eq!(identification.display_name(), "Anthurium warocqueanum");
+    }
+
+    #[test]
+    fn test_health_assessment_from_response() {
+        let response: HealthAssessmentResponse = serde_json::from_value(serde_json::json!({
+            "health_assessment": {
+                "is_healthy": false,
+                "is_healthy_probability": 0.08,
+                "diseases": [
+                    { "name": "Fungi", "probability": 0.71, "redundant": true },
+                    {
+                        "name": "water excess or uneven watering",
+                        "probability": 0.64,
+                        "disease_details": { "common_names": ["overwatering"] }
+                    },
+                    { "name": "Septoria leaf spot", "probability": 0.21, "disease_details": { "common_names": null } }
+                ]
+            }
+        }))
+        .unwrap();
+        let assessment = HealthAssessment::from(response.health_assessment);
+
+        assert_eq!(assessment.is_healthy_probability, Some(0.08));
+        let names: Vec<&str> = assessment.diseases.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["water excess or uneven watering", "Septoria leaf spot"]);
+        assert_eq!(assessment.diseases[0].common_names, ["overwatering"]);
+    }
+}
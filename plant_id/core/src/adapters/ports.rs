@@ -0,0 +1,101 @@
+/*!
+ * PORTS
+ *
+ * The interfaces services reach external systems through. They build on
+ * every target, so a front-end that cannot use the native adapters (a
+ * browser, say) can provide its own implementations.
+ */
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{HealthAssessment, NotificationEvent, SpeciesCandidate, SpeciesProfile};
+
+/// Port for storing plant images
+#[async_trait]
+pub trait StoragePort: Send + Sync {
+    /// Store an image, returning the URL it can be retrieved by
+    async fn upload_image(&self, image_data: &[u8], filename: &str) -> Result<String>;
+
+    /// Read back a stored image
+    async fn read_image(&self, url: &str) -> Result<Vec<u8>>;
+
+    /// Remove a stored image; missing images are not an error
+    async fn delete_image(&self, url: &str) -> Result<()>;
+
+    /// A URL a client can fetch the image from directly for `expires_in_secs`
+    async fn presigned_url(&self, url: &str, expires_in_secs: u32) -> Result<String>;
+}
+
+/// A single reference found for a query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub snippet: String,
+    pub url: Option<String>,
+}
+
+/// Port for looking up diseases, pests and species
+#[async_trait]
+pub trait SearchPort: Send + Sync {
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>>;
+}
+
+/// Port for looking up factual species data in a plant database
+#[async_trait]
+pub trait SpeciesDataPort: Send + Sync {
+    /// Name of the database, shown where its data is used
+    fn name(&self) -> &str;
+
+    /// The best match for a plant name, or None when the database does not
+    /// know the plant
+    async fn lookup(&self, name: &str) -> Result<Option<SpeciesProfile>>;
+}
+
+/// Port for a second opinion on the species in a photo, checked against
+/// Plant.id's answer
+#[async_trait]
+pub trait IdentifierPort: Send + Sync {
+    /// Name of the identifier, shown with its suggestions
+    fn name(&self) -> &str;
+
+    /// The species in a photo, given the one Plant.id recognised, or None
+    /// when the identifier has no opinion
+    async fn identify(&self, image: &[u8], plant_id: &SpeciesCandidate) -> Result<Option<SpeciesCandidate>>;
+}
+
+/// Port for a machine assessment of the diseases in a photo, which
+/// diagnoses started with a photo begin from
+#[async_trait]
+pub trait HealthAssessmentPort: Send + Sync {
+    async fn assess_health(&self, image: &[u8]) -> Result<HealthAssessment>;
+}
+
+/// Port for delivering notification events
+#[async_trait]
+pub trait NotificationPort: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// Whether the event should be sent here at all
+    fn accepts(&self, _event: &NotificationEvent) -> bool {
+        true
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()>;
+}
+
+/// Port for storing event batches
+#[async_trait]
+pub trait SyncRemote: Send + Sync {
+    /// Where the batches are stored, for display
+    fn location(&self) -> String;
+
+    /// Names of every batch on the remote, as `<device id>/<batch>`
+    async fn list_batches(&self) -> Result<Vec<String>>;
+
+    async fn read_batch(&self, name: &str) -> Result<Vec<u8>>;
+
+    async fn write_batch(&self, name: &str, data: &[u8]) -> Result<()>;
+}
@@ -0,0 +1,85 @@
+/*!
+ * PROGRESS ADAPTER
+ *
+ * Port through which services report the steps of long-running operations
+ * (identifying a plant, generating its care schedule, storing its photo),
+ * so every front-end can show progress its own way. The CLI renders a
+ * spinner; this module provides the front-end independent reporters.
+ */
+
+use chrono::Utc;
+use serde_json::json;
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Port for reporting the progress of a long-running operation
+pub trait ProgressReporter: Send + Sync {
+    /// A new step of the operation started
+    fn step(&self, message: &str);
+
+    /// The operation ended, successfully or not; clear any transient display
+    fn finish(&self) {}
+
+    /// Run `f` with any transient display hidden, e.g. to ask the user
+    /// something in the middle of the operation
+    fn suspend(&self, f: &mut dyn FnMut()) {
+        f()
+    }
+}
+
+/// Ignores progress, for callers with nowhere to show it
+pub struct SilentProgress;
+
+impl ProgressReporter for SilentProgress {
+    fn step(&self, _message: &str) {}
+}
+
+/// Announces each step as a plain line on stderr, which screen readers read
+/// out as it comes and logs keep in order
+pub struct LineProgress;
+
+impl ProgressReporter for LineProgress {
+    fn step(&self, message: &str) {
+        writeln!(std::io::stderr(), "{}", message).ok();
+    }
+
+    fn finish(&self) {
+        writeln!(std::io::stderr(), "Done.").ok();
+    }
+}
+
+/// Writes one JSON object per line to stderr, for scripts and log collectors:
+/// `{"type":"progress","step":1,"message":"Identifying plant...","at":"..."}`
+#[derive(Default)]
+pub struct JsonProgress {
+    steps: AtomicUsize,
+}
+
+impl JsonProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ProgressReporter for JsonProgress {
+    fn step(&self, message: &str) {
+        let step = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        let line = json!({
+            "type": "progress",
+            "step": step,
+            "message": message,
+            "at": Utc::now().to_rfc3339(),
+        });
+        writeln!(std::io::stderr(), "{}", line).ok();
+    }
+
+    fn finish(&self) {
+        let line = json!({
+            "type": "progress",
+            "done": true,
+            "steps": self.steps.load(Ordering::Relaxed),
+            "at": Utc::now().to_rfc3339(),
+        });
+        writeln!(std::io::stderr(), "{}", line).ok();
+    }
+}
@@ -0,0 +1,123 @@
+/*!
+ * S3 STORAGE ADAPTER
+ *
+ * Stores images in an S3-compatible bucket (AWS S3, MinIO, ...).
+ *
+ * Configuration:
+ * - `S3_BUCKET` (required)
+ * - `S3_REGION` (default `us-east-1`)
+ * - `S3_ENDPOINT` for non-AWS services such as MinIO (enables path-style URLs)
+ * - `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` (environment or OS keychain)
+ */
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use s3::creds::Credentials;
+use s3::{Bucket, Region};
+
+use crate::adapters::{http_client, StoragePort};
+use crate::config::get_secret;
+
+/// Prefix of image URLs stored by this adapter
+const URL_SCHEME: &str = "s3://";
+
+/// Key prefix under which images are stored in the bucket
+const KEY_PREFIX: &str = "images";
+
+pub struct S3StorageAdapter {
+    bucket: Box<Bucket>,
+}
+
+impl S3StorageAdapter {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            bucket: bucket_from_env()?,
+        })
+    }
+
+    /// Object key for an `s3://bucket/key` URL
+    fn key_for(&self, url: &str) -> Result<String> {
+        let path = url
+            .strip_prefix(URL_SCHEME)
+            .with_context(|| format!("Not an S3 image URL: {}", url))?;
+        let (bucket, key) = path
+            .split_once('/')
+            .with_context(|| format!("Malformed S3 image URL: {}", url))?;
+
+        if bucket != self.bucket.name() {
+            anyhow::bail!("Image {} is stored in a different bucket", url);
+        }
+
+        Ok(key.to_string())
+    }
+}
+
+/// Open the bucket configured in the environment (see the module docs)
+pub(crate) fn bucket_from_env() -> Result<Box<Bucket>> {
+    let bucket_name =
+        std::env::var("S3_BUCKET").context("Missing required environment variable: S3_BUCKET")?;
+    let region_name = std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+
+    let credentials = Credentials::new(
+        Some(&get_secret("AWS_ACCESS_KEY_ID")?),
+        Some(&get_secret("AWS_SECRET_ACCESS_KEY")?),
+        None,
+        None,
+        None,
+    )?;
+
+    let bucket = match std::env::var("S3_ENDPOINT") {
+        Ok(endpoint) => {
+            let region = Region::Custom {
+                region: region_name,
+                endpoint,
+            };
+            Bucket::new(&bucket_name, region, credentials)?.with_path_style()
+        }
+        Err(_) => Bucket::new(&bucket_name, region_name.parse()?, credentials)?,
+    };
+
+    Ok(bucket.with_request_timeout(http_client::request_timeout())?)
+}
+
+#[async_trait]
+impl StoragePort for S3StorageAdapter {
+    async fn upload_image(&self, image_data: &[u8], filename: &str) -> Result<String> {
+        let key = format!("{}/{}", KEY_PREFIX, filename);
+        let response = self.bucket.put_object(&key, image_data).await?;
+
+        if response.status_code() >= 300 {
+            anyhow::bail!("S3 upload failed with status {}", response.status_code());
+        }
+
+        Ok(format!("{}{}/{}", URL_SCHEME, self.bucket.name(), key))
+    }
+
+    async fn read_image(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self.bucket.get_object(self.key_for(url)?).await?;
+
+        if response.status_code() >= 300 {
+            anyhow::bail!("S3 download failed with status {}", response.status_code());
+        }
+
+        Ok(response.to_vec())
+    }
+
+    async fn delete_image(&self, url: &str) -> Result<()> {
+        let response = self.bucket.delete_object(self.key_for(url)?).await?;
+
+        // S3 answers 204 for both deleted and missing objects
+        if response.status_code() >= 300 && response.status_code() != 404 {
+            anyhow::bail!("S3 delete failed with status {}", response.status_code());
+        }
+
+        Ok(())
+    }
+
+    async fn presigned_url(&self, url: &str, expires_in_secs: u32) -> Result<String> {
+        Ok(self
+            .bucket
+            .presign_get(self.key_for(url)?, expires_in_secs, None)
+            .await?)
+    }
+}
@@ -11,7 +11,8 @@
 use anyhow::{Context, Result};
 use serde_json::Value as JsonValue;
 
-use crate::domain::enums::DiagnosisAction;
+use crate::domain::{CareScheduleUpdate, DiagnosisAction, Severity};
+use crate::dto::AlternativeDiagnosisDto;
 
 pub struct SandboxExecutor;
 
@@ -21,6 +22,12 @@ pub struct ExecutionResult {
     pub payload: JsonValue,
 }
 
+impl Default for SandboxExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SandboxExecutor {
     pub fn new() -> Self {
         Self
@@ -126,6 +133,32 @@ impl SandboxExecutor {
                     .context("ASK_USER payload must contain a 'question' string")?;
                 Ok(())
             }
+            DiagnosisAction::RequestPhoto => {
+                // REQUEST_PHOTO must name the part of the plant to photograph
+                payload["part"]
+                    .as_str()
+                    .context("REQUEST_PHOTO payload must contain a 'part' string")?;
+                Ok(())
+            }
+            DiagnosisAction::SuggestCareUpdate => {
+                // SUGGEST_CARE_UPDATE must change at least one schedule field
+                let changes: CareScheduleUpdate = serde_json::from_value(payload["changes"].clone())
+                    .context("SUGGEST_CARE_UPDATE payload must contain a 'changes' object")?;
+                if changes.is_empty() {
+                    anyhow::bail!("SUGGEST_CARE_UPDATE changes must update at least one field");
+                }
+                Ok(())
+            }
+            DiagnosisAction::Search => {
+                // SEARCH must contain a non-empty query
+                let query = payload["query"]
+                    .as_str()
+                    .context("SEARCH payload must contain a 'query' string")?;
+                if query.trim().is_empty() {
+                    anyhow::bail!("SEARCH query must not be empty");
+                }
+                Ok(())
+            }
             DiagnosisAction::Conclude => {
                 // CONCLUDE must have "finding" and "recommendation" fields
                 payload["finding"]
@@ -134,6 +167,27 @@ impl SandboxExecutor {
                 payload["recommendation"]
                     .as_str()
                     .context("CONCLUDE payload must contain a 'recommendation' string")?;
+                // Optional severity, confidence and differential diagnoses
+                if !payload["severity"].is_null() {
+                    payload["severity"]
+                        .as_str()
+                        .and_then(Severity::from_str)
+                        .context("CONCLUDE 'severity' must be 'info', 'minor', 'serious' or 'critical'")?;
+                }
+                if !payload["confidence"].is_null() {
+                    validate_probability(&payload["confidence"], "confidence")?;
+                }
+                if !payload["alternatives"].is_null() {
+                    let alternatives = payload["alternatives"]
+                        .as_array()
+                        .context("CONCLUDE 'alternatives' must be an array")?;
+                    for alternative in alternatives {
+                        alternative["finding"]
+                            .as_str()
+                            .context("Each alternative must contain a 'finding' string")?;
+                        validate_probability(&alternative["probability"], "probability")?;
+                    }
+                }
                 Ok(())
             }
         }
@@ -171,6 +225,31 @@ impl SandboxExecutor {
                     .to_string();
                 Ok(ActionEffect::AskUser(question))
             }
+            DiagnosisAction::RequestPhoto => {
+                let part = result.payload["part"]
+                    .as_str()
+                    .unwrap()
+                    .to_string();
+                let reason = result.payload["reason"]
+                    .as_str()
+                    .map(str::to_string);
+                Ok(ActionEffect::RequestPhoto { part, reason })
+            }
+            DiagnosisAction::SuggestCareUpdate => {
+                let changes = serde_json::from_value(result.payload["changes"].clone())?;
+                let reason = result.payload["reason"]
+                    .as_str()
+                    .map(str::to_string);
+                Ok(ActionEffect::SuggestCareUpdate { changes, reason })
+            }
+            DiagnosisAction::Search => {
+                let query = result.payload["query"]
+                    .as_str()
+                    .unwrap()
+                    .trim()
+                    .to_string();
+                Ok(ActionEffect::Search(query))
+            }
             DiagnosisAction::Conclude => {
                 let finding = result.payload["finding"]
                     .as_str()
@@ -180,7 +259,20 @@ impl SandboxExecutor {
                     .as_str()
                     .unwrap()
                     .to_string();
-                Ok(ActionEffect::Conclude { finding, recommendation })
+                let severity = result.payload["severity"].as_str().and_then(Severity::from_str);
+                let confidence = result.payload["confidence"].as_f64();
+                let mut alternatives: Vec<AlternativeDiagnosisDto> = result.payload["alternatives"]
+                    .as_array()
+                    .map(|a| a.iter().filter_map(|v| serde_json::from_value(v.clone()).ok()).collect())
+                    .unwrap_or_default();
+                alternatives.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+                Ok(ActionEffect::Conclude {
+                    finding,
+                    recommendation,
+                    severity,
+                    confidence,
+                    alternatives,
+                })
             }
         }
     }
@@ -195,13 +287,39 @@ pub enum ActionEffect {
     FetchPlantVitals,
     /// Ask user a question
     AskUser(String),
+    /// Ask user to attach a photo of part of the plant
+    RequestPhoto {
+        part: String,
+        reason: Option<String>,
+    },
+    /// Propose changes to the plant's stored care schedule
+    SuggestCareUpdate {
+        changes: CareScheduleUpdate,
+        reason: Option<String>,
+    },
+    /// Look up a disease, pest or species term
+    Search(String),
     /// Conclude the diagnosis
     Conclude {
         finding: String,
         recommendation: String,
+        severity: Option<Severity>,
+        confidence: Option<f64>,
+        alternatives: Vec<AlternativeDiagnosisDto>,
     },
 }
 
+/// Check that a payload value is a probability between 0 and 1
+fn validate_probability(value: &JsonValue, field: &str) -> Result<()> {
+    let probability = value
+        .as_f64()
+        .with_context(|| format!("'{}' must be a number", field))?;
+    if !(0.0..=1.0).contains(&probability) {
+        anyhow::bail!("'{}' must be between 0 and 1, got {}", field, probability);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,5 +369,73 @@ Here's the action:
         let result = executor.validate_payload(&DiagnosisAction::Conclude, &payload);
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_validate_conclude_confidence() {
+        let executor = SandboxExecutor::new();
+        let payload = serde_json::json!({
+            "finding": "Root rot",
+            "recommendation": "Reduce watering",
+            "confidence": 0.7,
+            "alternatives": [{"finding": "Underwatering", "probability": 0.2}]
+        });
+        assert!(executor.validate_payload(&DiagnosisAction::Conclude, &payload).is_ok());
+
+        let payload = serde_json::json!({
+            "finding": "Root rot",
+            "recommendation": "Reduce watering",
+            "confidence": 70
+        });
+        assert!(executor.validate_payload(&DiagnosisAction::Conclude, &payload).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_conclude_severity() {
+        let executor = SandboxExecutor::new();
+        let payload = serde_json::json!({
+            "finding": "Root rot",
+            "recommendation": "Repot in fresh soil",
+            "severity": "critical"
+        });
+        assert!(executor.validate_payload(&DiagnosisAction::Conclude, &payload).is_ok());
+
+        let result = ExecutionResult {
+            action: DiagnosisAction::Conclude,
+            payload,
+        };
+        match executor.execute_action(&result, &mut serde_json::json!({})).unwrap() {
+            ActionEffect::Conclude { severity, .. } => assert_eq!(severity, Some(Severity::Critical)),
+            other => panic!("expected a conclusion, got {:?}", other),
+        }
+
+        let payload = serde_json::json!({
+            "finding": "Root rot",
+            "recommendation": "Repot in fresh soil",
+            "severity": "dire"
+        });
+        assert!(executor.validate_payload(&DiagnosisAction::Conclude, &payload).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_photo_payload() {
+        let executor = SandboxExecutor::new();
+
+        let payload = serde_json::json!({"part": "roots", "reason": "Check for rot"});
+        assert!(executor.validate_payload(&DiagnosisAction::RequestPhoto, &payload).is_ok());
+
+        let payload = serde_json::json!({"reason": "Check for rot"});
+        assert!(executor.validate_payload(&DiagnosisAction::RequestPhoto, &payload).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_suggest_care_update_payload() {
+        let executor = SandboxExecutor::new();
+
+        let payload = serde_json::json!({"changes": {"water": "Every 10 days"}, "reason": "Overwatering"});
+        assert!(executor.validate_payload(&DiagnosisAction::SuggestCareUpdate, &payload).is_ok());
+
+        let payload = serde_json::json!({"changes": {}});
+        assert!(executor.validate_payload(&DiagnosisAction::SuggestCareUpdate, &payload).is_err());
+    }
 }
 
@@ -0,0 +1,173 @@
+/*!
+ * SEARCH ADAPTER
+ *
+ * Secondary port for reference lookups requested by the AI during a
+ * diagnosis. Wikipedia is used by default; `SEARCH_BACKEND=encyclopedia`
+ * restricts lookups to the bundled pest and disease encyclopedia, which
+ * works offline.
+ */
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::adapters::{http_client, payload, SearchPort, SearchResult};
+use crate::domain::encyclopedia;
+
+/// Maximum number of results returned per query
+const MAX_RESULTS: usize = 3;
+
+/// Create the search backend selected by `SEARCH_BACKEND` (`wikipedia` or `encyclopedia`)
+pub fn from_env() -> Result<Arc<dyn SearchPort>> {
+    let backend = std::env::var("SEARCH_BACKEND").unwrap_or_else(|_| "wikipedia".to_string());
+
+    match backend.as_str() {
+        "wikipedia" => Ok(Arc::new(WikipediaSearchAdapter::new()?)),
+        "encyclopedia" => Ok(Arc::new(EncyclopediaSearchAdapter)),
+        other => anyhow::bail!(
+            "Unknown SEARCH_BACKEND '{}': expected 'wikipedia' or 'encyclopedia'",
+            other
+        ),
+    }
+}
+
+/// Searches the bundled pest and disease encyclopedia
+pub struct EncyclopediaSearchAdapter;
+
+#[async_trait]
+impl SearchPort for EncyclopediaSearchAdapter {
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>> {
+        let query = query.to_lowercase();
+        let mut matches = encyclopedia::find_mentions(&query);
+
+        // Also match queries that only mention a symptom
+        for entry in encyclopedia::entries() {
+            if !matches.iter().any(|m| m.name == entry.name)
+                && entry.symptoms.iter().any(|s| s.to_lowercase().contains(&query))
+            {
+                matches.push(entry);
+            }
+        }
+
+        Ok(matches
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|entry| SearchResult {
+                title: format!("{} ({})", entry.name, entry.kind.as_str()),
+                snippet: format!(
+                    "Symptoms: {}. Treatment: {}",
+                    entry.symptoms.join("; "),
+                    entry.treatment.join("; ")
+                ),
+                url: None,
+            })
+            .collect())
+    }
+}
+
+/// Searches English Wikipedia and returns the introduction of each article
+pub struct WikipediaSearchAdapter {
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct WikipediaResponse {
+    query: Option<WikipediaQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WikipediaQuery {
+    pages: HashMap<String, WikipediaPage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WikipediaPage {
+    title: String,
+    #[serde(default)]
+    extract: String,
+    /// Rank of the page in the search results
+    #[serde(default)]
+    index: usize,
+}
+
+impl WikipediaSearchAdapter {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: http_client::build_client()?,
+        })
+    }
+}
+
+#[async_trait]
+impl SearchPort for WikipediaSearchAdapter {
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>> {
+        let limit = MAX_RESULTS.to_string();
+        let response = self
+            .client
+            .get("https://en.wikipedia.org/w/api.php")
+            .header("User-Agent", concat!("plant-care/", env!("CARGO_PKG_VERSION")))
+            .query(&[
+                ("action", "query"),
+                ("format", "json"),
+                ("generator", "search"),
+                ("gsrsearch", query),
+                ("gsrlimit", limit.as_str()),
+                ("prop", "extracts"),
+                ("exintro", "1"),
+                ("explaintext", "1"),
+                ("exsentences", "4"),
+            ])
+            .send()
+            .await
+            .map_err(|e| http_client::request_error("Wikipedia", e))?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Wikipedia API error: {}", response.status());
+        }
+
+        let body: WikipediaResponse = payload::read_json("Wikipedia", response).await?;
+
+        // No `query` key means there were no matches
+        let mut pages: Vec<WikipediaPage> = body
+            .query
+            .map(|q| q.pages.into_values().collect())
+            .unwrap_or_default();
+        pages.sort_by_key(|p| p.index);
+
+        Ok(pages
+            .into_iter()
+            .map(|page| SearchResult {
+                url: Some(format!(
+                    "https://en.wikipedia.org/wiki/{}",
+                    page.title.replace(' ', "_")
+                )),
+                title: page.title,
+                snippet: page.extract,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_encyclopedia_search_finds_named_entry() {
+        let results = EncyclopediaSearchAdapter
+            .search("Spider mites on a palm")
+            .await
+            .unwrap();
+
+        assert!(results[0].title.starts_with("Spider mites"));
+    }
+
+    #[tokio::test]
+    async fn test_encyclopedia_search_without_match() {
+        let results = EncyclopediaSearchAdapter.search("quantum chromodynamics").await.unwrap();
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,519 @@
+/*!
+ * SMTP CLIENT
+ *
+ * Just enough SMTP to hand a plain-text email to the owner's mail server
+ * for email notifications: EHLO, STARTTLS or a TLS connection from the
+ * start, AUTH PLAIN or LOGIN, then one message to one recipient.
+ *
+ * Credentials are only sent over TLS, or unencrypted to a relay on this
+ * machine, and only when the server offers AUTH PLAIN or LOGIN.
+ *
+ * The server is configured with:
+ *
+ * - `SMTP_HOST`      the mail server (required)
+ * - `SMTP_SECURITY`  `starttls` (default), `tls` for a TLS connection from the start, or `none`
+ * - `SMTP_PORT`      defaults to 587 with `starttls`, 465 with `tls` and 25 with `none`
+ * - `SMTP_USERNAME`  and `SMTP_PASSWORD` (environment or OS keychain), when the server wants a login
+ * - `SMTP_FROM`      the sender address, defaulting to `SMTP_USERNAME`
+ *
+ * Certificates are checked against the bundled web roots and `CA_BUNDLE`.
+ */
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+use crate::adapters::http_client;
+use crate::config::get_secret;
+
+/// Limit for establishing a connection
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Longest line of a base64-encoded body
+const BASE64_LINE_LENGTH: usize = 76;
+
+/// Most bytes of text in one encoded word of a header: 45 bytes are 60
+/// base64 characters, which with `=?UTF-8?B?` and `?=` make the 75 allowed
+const ENCODED_WORD_BYTES: usize = 45;
+
+/// How the connection to the mail server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Upgraded to TLS with STARTTLS after connecting
+    StartTls,
+    /// TLS from the start
+    Tls,
+    /// Unencrypted, for a relay on this machine or a server that needs no
+    /// login
+    None,
+}
+
+impl SmtpSecurity {
+    fn default_port(self) -> u16 {
+        match self {
+            Self::StartTls => 587,
+            Self::Tls => 465,
+            Self::None => 25,
+        }
+    }
+}
+
+/// A mail server to send through
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    /// Login, with the password
+    pub credentials: Option<(String, String)>,
+    /// Sender address
+    pub from: String,
+}
+
+impl SmtpConfig {
+    /// The mail server configured in the `SMTP_*` variables
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        let host = var("SMTP_HOST").context("Email notifications need SMTP_HOST, the mail server to send through")?;
+        let security = match var("SMTP_SECURITY").as_deref() {
+            None | Some("starttls") => SmtpSecurity::StartTls,
+            Some("tls") => SmtpSecurity::Tls,
+            Some("none") => SmtpSecurity::None,
+            Some(other) => anyhow::bail!(
+                "Invalid SMTP_SECURITY '{}': expected 'starttls', 'tls' or 'none'",
+                other
+            ),
+        };
+        let port = match var("SMTP_PORT") {
+            Some(port) => port
+                .parse()
+                .with_context(|| format!("Invalid SMTP_PORT '{}'", port))?,
+            None => security.default_port(),
+        };
+        let username = var("SMTP_USERNAME");
+        let credentials = match &username {
+            Some(username) => Some((username.clone(), get_secret("SMTP_PASSWORD")?)),
+            None => None,
+        };
+        let from = var("SMTP_FROM")
+            .or(username)
+            .context("Email notifications need SMTP_FROM, the address to send from")?;
+
+        Ok(Self {
+            host,
+            port,
+            security,
+            credentials,
+            from,
+        })
+    }
+}
+
+/// A plain-text email
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    /// Flagged as high priority for mail clients that show it
+    pub urgent: bool,
+}
+
+impl Email {
+    /// The message as sent after DATA, headers and base64 body, with CRLF
+    /// line endings and without the terminating dot
+    fn to_message(&self, from: &str) -> String {
+        let mut headers = vec![
+            format!("From: {}", from),
+            format!("To: {}", self.to),
+            format!("Subject: {}", encode_header(&self.subject)),
+            format!("Date: {}", Utc::now().to_rfc2822()),
+            format!("Message-ID: <{}@plant-care>", uuid::Uuid::new_v4()),
+            "MIME-Version: 1.0".to_string(),
+            "Content-Type: text/plain; charset=utf-8".to_string(),
+            "Content-Transfer-Encoding: base64".to_string(),
+        ];
+        if self.urgent {
+            headers.push("X-Priority: 1".to_string());
+            headers.push("Importance: high".to_string());
+        }
+
+        // Base64 keeps any text 7-bit clean, and never starts a line with a dot
+        let body = BASE64.encode(self.body.replace('\n', "\r\n"));
+        let lines: Vec<&str> = body
+            .as_bytes()
+            .chunks(BASE64_LINE_LENGTH)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+            .collect();
+
+        format!("{}\r\n\r\n{}", headers.join("\r\n"), lines.join("\r\n"))
+    }
+}
+
+/// A header value, encoded (RFC 2047) when it is not plain ASCII. Long
+/// values are split into encoded words of at most 75 characters, on
+/// character boundaries, each on its own folded line.
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        if i + c.len_utf8() - start > ENCODED_WORD_BYTES {
+            words.push(&value[start..i]);
+            start = i;
+        }
+    }
+    words.push(&value[start..]);
+
+    words
+        .iter()
+        .map(|word| format!("=?UTF-8?B?{}?=", BASE64.encode(word)))
+        .collect::<Vec<_>>()
+        .join("\r\n ")
+}
+
+/// Hand `email` to the mail server, within the request timeout
+pub async fn send(config: &SmtpConfig, email: &Email) -> Result<()> {
+    let timeout = http_client::request_timeout();
+    tokio::time::timeout(timeout, deliver(config, email))
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Mail server {} timed out after {}s (use --timeout to allow longer)",
+                config.host,
+                timeout.as_secs()
+            )
+        })?
+}
+
+async fn deliver(config: &SmtpConfig, email: &Email) -> Result<()> {
+    if config.security == SmtpSecurity::None && config.credentials.is_some() && !is_loopback(&config.host) {
+        anyhow::bail!(
+            "Refusing to send SMTP_PASSWORD unencrypted to {}: set SMTP_SECURITY to starttls or tls",
+            config.host
+        );
+    }
+
+    let connect = TcpStream::connect((config.host.as_str(), config.port));
+    let stream = tokio::time::timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS), connect)
+        .await
+        .map_err(|_| anyhow::anyhow!("Timed out connecting to {}:{}", config.host, config.port))?
+        .with_context(|| format!("Failed to connect to {}:{}", config.host, config.port))?;
+
+    match config.security {
+        SmtpSecurity::None => {
+            let mut session = Session::new(stream);
+            session.expect(220).await?;
+            let extensions = session.ehlo().await?;
+            session.send_email(config, email, &extensions).await
+        }
+        SmtpSecurity::Tls => {
+            let stream = tls_connector()?.connect(server_name(&config.host)?, stream).await?;
+            let mut session = Session::new(stream);
+            session.expect(220).await?;
+            let extensions = session.ehlo().await?;
+            session.send_email(config, email, &extensions).await
+        }
+        SmtpSecurity::StartTls => {
+            let mut session = Session::new(stream);
+            session.expect(220).await?;
+            if !session.ehlo().await?.iter().any(|e| e == "STARTTLS") {
+                anyhow::bail!(
+                    "Mail server {} does not offer STARTTLS: set SMTP_SECURITY to tls, or to none for a local relay",
+                    config.host
+                );
+            }
+            session.command("STARTTLS", 220).await?;
+            let stream = tls_connector()?
+                .connect(server_name(&config.host)?, session.into_inner())
+                .await?;
+            let mut session = Session::new(stream);
+            let extensions = session.ehlo().await?;
+            session.send_email(config, email, &extensions).await
+        }
+    }
+}
+
+/// Whether `host` is this machine, where nothing unencrypted leaves it
+fn is_loopback(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost") || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn server_name(host: &str) -> Result<ServerName<'static>> {
+    ServerName::try_from(host.to_string()).with_context(|| format!("Invalid SMTP_HOST '{}'", host))
+}
+
+/// Trusting the bundled web roots and the certificates in `CA_BUNDLE`
+fn tls_connector() -> Result<TlsConnector> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    if let Some(path) = std::env::var("CA_BUNDLE").ok().filter(|p| !p.trim().is_empty()) {
+        for certificate in CertificateDer::pem_file_iter(path.trim())
+            .with_context(|| format!("Failed to read CA_BUNDLE {}", path))?
+        {
+            roots.add(certificate.with_context(|| format!("CA_BUNDLE {} is not a PEM file", path))?)?;
+        }
+    }
+
+    let config = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// One conversation with the server over a plain or TLS stream
+struct Session<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Session<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+
+    /// Read a reply, failing unless it has the `expected` code
+    async fn expect(&mut self, expected: u16) -> Result<String> {
+        let mut text = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await? == 0 {
+                anyhow::bail!("The mail server closed the connection");
+            }
+            let line = line.trim_end();
+            let code: u16 = line
+                .get(..3)
+                .and_then(|code| code.parse().ok())
+                .with_context(|| format!("Unexpected reply from the mail server: {}", line))?;
+            text.push(line.get(4..).unwrap_or_default().to_string());
+            // "250-" continues a reply, "250 " ends it
+            if line.as_bytes().get(3) != Some(&b'-') {
+                if code != expected {
+                    anyhow::bail!("Mail server answered {} {}", code, text.join(" "));
+                }
+                return Ok(text.join("\n"));
+            }
+        }
+    }
+
+    async fn command(&mut self, command: &str, expected: u16) -> Result<String> {
+        self.stream.write_all(format!("{}\r\n", command).as_bytes()).await?;
+        self.stream.flush().await?;
+        self.expect(expected).await
+    }
+
+    /// Greet the server, returning the extensions it offers (`STARTTLS`,
+    /// `AUTH PLAIN LOGIN`...) in upper case
+    async fn ehlo(&mut self) -> Result<Vec<String>> {
+        let reply = self.command("EHLO localhost", 250).await?;
+        // The first line is the server's greeting
+        Ok(reply.lines().skip(1).map(|line| line.trim().to_ascii_uppercase()).collect())
+    }
+
+    async fn send_email(&mut self, config: &SmtpConfig, email: &Email, extensions: &[String]) -> Result<()> {
+        if let Some((username, password)) = &config.credentials {
+            self.log_in(config, username, password, extensions)
+                .await
+                .context("The mail server did not accept SMTP_USERNAME and SMTP_PASSWORD")?;
+        }
+        self.command(&format!("MAIL FROM:<{}>", config.from), 250).await?;
+        self.command(&format!("RCPT TO:<{}>", email.to), 250).await?;
+        self.command("DATA", 354).await?;
+        self.command(&format!("{}\r\n.", email.to_message(&config.from)), 250)
+            .await?;
+        // The message is accepted; a server hanging up early does not matter
+        self.command("QUIT", 221).await.ok();
+        Ok(())
+    }
+
+    /// Log in with AUTH PLAIN, or AUTH LOGIN for servers that only offer that
+    async fn log_in(
+        &mut self,
+        config: &SmtpConfig,
+        username: &str,
+        password: &str,
+        extensions: &[String],
+    ) -> Result<()> {
+        let offers = |mechanism: &str| {
+            extensions
+                .iter()
+                .filter_map(|e| e.strip_prefix("AUTH "))
+                .any(|mechanisms| mechanisms.split_whitespace().any(|m| m == mechanism))
+        };
+
+        if offers("PLAIN") {
+            let token = BASE64.encode(format!("\0{}\0{}", username, password));
+            self.command(&format!("AUTH PLAIN {}", token), 235).await?;
+        } else if offers("LOGIN") {
+            self.command("AUTH LOGIN", 334).await?;
+            self.command(&BASE64.encode(username), 334).await?;
+            self.command(&BASE64.encode(password), 235).await?;
+        } else {
+            anyhow::bail!(
+                "Mail server {} offers neither AUTH PLAIN nor AUTH LOGIN to log in with SMTP_USERNAME",
+                config.host
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// A server offering the `auth` mechanisms that accepts everything and
+    /// keeps what it was sent
+    async fn fake_server(auth: &'static str) -> (u16, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            stream.write_all(b"220 test ESMTP\r\n").await.unwrap();
+            let mut received = Vec::new();
+            let mut in_data = false;
+            // Replies left to the username and password of AUTH LOGIN
+            let mut login_prompts: Vec<&[u8]> = Vec::new();
+            loop {
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end().to_string();
+                let reply: &[u8] = if in_data {
+                    in_data = line != ".";
+                    if in_data {
+                        received.push(line);
+                        continue;
+                    }
+                    b"250 queued\r\n"
+                } else if let Some(prompt) = login_prompts.pop() {
+                    prompt
+                } else if line.starts_with("EHLO") {
+                    received.push(line);
+                    stream.write_all(format!("250-test\r\n250 AUTH {}\r\n", auth).as_bytes()).await.unwrap();
+                    continue;
+                } else if line.starts_with("AUTH PLAIN") {
+                    b"235 ok\r\n"
+                } else if line == "AUTH LOGIN" {
+                    login_prompts = vec![b"235 ok\r\n", b"334 UGFzc3dvcmQ6\r\n"];
+                    b"334 VXNlcm5hbWU6\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line == "QUIT" {
+                    received.push(line);
+                    stream.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                received.push(line);
+                stream.write_all(reply).await.unwrap();
+            }
+            received
+        });
+
+        (port, server)
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let (port, server) = fake_server("PLAIN LOGIN").await;
+        let config = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            security: SmtpSecurity::None,
+            credentials: Some(("me".to_string(), "secret".to_string())),
+            from: "plants@example.com".to_string(),
+        };
+        let email = Email {
+            to: "me@example.com".to_string(),
+            subject: "🚨 Critical diagnosis for Fern".to_string(),
+            body: "Root rot.\nRepot now.".to_string(),
+            urgent: true,
+        };
+        send(&config, &email).await.unwrap();
+
+        let received = server.await.unwrap();
+        assert!(received.contains(&format!("AUTH PLAIN {}", BASE64.encode("\0me\0secret"))));
+        assert!(received.contains(&"RCPT TO:<me@example.com>".to_string()));
+        assert!(received.contains(&format!("Subject: =?UTF-8?B?{}?=", BASE64.encode(&email.subject))));
+        assert!(received.contains(&"X-Priority: 1".to_string()));
+        assert!(received.contains(&BASE64.encode("Root rot.\r\nRepot now.")));
+        assert_eq!(received.last().map(String::as_str), Some("QUIT"));
+
+        // A password never leaves the machine unencrypted
+        let remote = SmtpConfig {
+            host: "mail.example.com".to_string(),
+            ..config
+        };
+        let error = send(&remote, &email).await.unwrap_err();
+        assert!(error.to_string().starts_with("Refusing to send SMTP_PASSWORD unencrypted"));
+        assert!(is_loopback("localhost") && is_loopback("[::1]") && !is_loopback("10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_log_in_with_auth_login() {
+        let (port, server) = fake_server("LOGIN").await;
+        let config = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            security: SmtpSecurity::None,
+            credentials: Some(("me".to_string(), "secret".to_string())),
+            from: "plants@example.com".to_string(),
+        };
+        let email = Email {
+            to: "me@example.com".to_string(),
+            subject: "Water Fern".to_string(),
+            body: "Due today.".to_string(),
+            urgent: false,
+        };
+        send(&config, &email).await.unwrap();
+
+        let received = server.await.unwrap();
+        let login = received.iter().position(|line| line == "AUTH LOGIN").unwrap();
+        assert_eq!(received[login + 1], BASE64.encode("me"));
+        assert_eq!(received[login + 2], BASE64.encode("secret"));
+        assert!(received.contains(&"Subject: Water Fern".to_string()));
+    }
+
+    #[test]
+    fn test_long_subject_is_split_into_encoded_words() {
+        let subject = "🚨 Critical diagnosis for Monstera deliciosa «Thai Constellation»: root rot, repot now";
+        let encoded = encode_header(subject);
+
+        let mut decoded = Vec::new();
+        for word in encoded.split("\r\n ") {
+            assert!(word.len() <= 75, "{} is too long", word);
+            let text = word.strip_prefix("=?UTF-8?B?").and_then(|w| w.strip_suffix("?=")).unwrap();
+            decoded.extend(BASE64.decode(text).unwrap());
+        }
+        assert!(encoded.contains("\r\n "));
+        assert_eq!(String::from_utf8(decoded).unwrap(), subject);
+    }
+}
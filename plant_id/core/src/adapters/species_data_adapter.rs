@@ -0,0 +1,264 @@
+/*!
+ * SPECIES DATA ADAPTER
+ *
+ * Secondary port for factual species data from a plant database, used as
+ * the baseline of generated care schedules. `SPECIES_DATA_SOURCE` selects
+ * the database: `perenual` (needs `PERENUAL_API_KEY`), `trefle` (needs
+ * `TREFLE_API_TOKEN`) or `none`, the default, which leaves care schedules
+ * entirely to the AI.
+ */
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::adapters::{http_client, payload, SpeciesDataPort};
+use crate::config::get_secret;
+use crate::domain::species_profile::{self, SpeciesProfile};
+
+/// Create the database selected by `SPECIES_DATA_SOURCE`, if any
+pub fn from_env() -> Result<Option<Arc<dyn SpeciesDataPort>>> {
+    let source = std::env::var("SPECIES_DATA_SOURCE").unwrap_or_else(|_| "none".to_string());
+
+    match source.as_str() {
+        "none" | "" => Ok(None),
+        "perenual" => Ok(Some(Arc::new(PerenualAdapter::new()?))),
+        "trefle" => Ok(Some(Arc::new(TrefleAdapter::new()?))),
+        other => anyhow::bail!(
+            "Unknown SPECIES_DATA_SOURCE '{}': expected 'perenual', 'trefle' or 'none'",
+            other
+        ),
+    }
+}
+
+/// GET a JSON document, naming `service` in errors
+async fn get_json<T: DeserializeOwned>(
+    client: &Client,
+    service: &str,
+    url: &str,
+    query: &[(&str, &str)],
+) -> Result<T> {
+    let response = client
+        .get(url)
+        .header("User-Agent", concat!("plant-care/", env!("CARGO_PKG_VERSION")))
+        .query(query)
+        .send()
+        .await
+        .map_err(|e| http_client::request_error(service, e))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("{} API error: {}", service, response.status());
+    }
+
+    payload::read_json(service, response).await
+}
+
+/// Plain text of a JSON value that should be a string. Paid-only fields of
+/// the free plans hold an upgrade notice instead, which is skipped.
+fn text(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .map(|s| s.trim().trim_matches('"').to_string())
+        .filter(|s| !s.is_empty() && !s.to_lowercase().contains("upgrade"))
+}
+
+/// Perenual (perenual.com): watering and sunlight categories
+pub struct PerenualAdapter {
+    client: Client,
+    api_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PerenualList {
+    #[serde(default)]
+    data: Vec<PerenualSpecies>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PerenualSpecies {
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PerenualDetails {
+    #[serde(default)]
+    common_name: Value,
+    #[serde(default)]
+    scientific_name: Value,
+    #[serde(default)]
+    family: Value,
+    #[serde(default)]
+    watering: Value,
+    #[serde(default)]
+    watering_general_benchmark: Value,
+    #[serde(default)]
+    sunlight: Value,
+}
+
+impl PerenualAdapter {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: http_client::build_client()?,
+            api_key: get_secret("PERENUAL_API_KEY")?,
+        })
+    }
+}
+
+#[async_trait]
+impl SpeciesDataPort for PerenualAdapter {
+    fn name(&self) -> &str {
+        "Perenual"
+    }
+
+    async fn lookup(&self, name: &str) -> Result<Option<SpeciesProfile>> {
+        let list: PerenualList = get_json(
+            &self.client,
+            "Perenual",
+            "https://perenual.com/api/species-list",
+            &[("key", &self.api_key), ("q", name)],
+        )
+        .await?;
+        let Some(species) = list.data.first() else {
+            return Ok(None);
+        };
+
+        let details: PerenualDetails = get_json(
+            &self.client,
+            "Perenual",
+            &format!("https://perenual.com/api/species/details/{}", species.id),
+            &[("key", &self.api_key)],
+        )
+        .await?;
+
+        let scientific_name = match &details.scientific_name {
+            Value::Array(names) => names.first().and_then(text),
+            other => text(other),
+        };
+        let sunlight: Vec<String> = details
+            .sunlight
+            .as_array()
+            .map(|values| values.iter().filter_map(text).collect())
+            .unwrap_or_default();
+        let interval = match (
+            text(&details.watering_general_benchmark["value"]),
+            text(&details.watering_general_benchmark["unit"]),
+        ) {
+            (Some(value), Some(unit)) => Some(format!("{} {}", value, unit)),
+            _ => None,
+        };
+
+        Ok(Some(SpeciesProfile {
+            source: self.name().to_string(),
+            scientific_name: scientific_name.unwrap_or_else(|| name.to_string()),
+            common_name: text(&details.common_name),
+            family: text(&details.family),
+            light: species_profile::light_from_sunlight(&sunlight),
+            water: text(&details.watering)
+                .and_then(|w| species_profile::water_from_frequency(&w, interval.as_deref())),
+            humidity: None,
+            temperature: None,
+        }))
+    }
+}
+
+/// Trefle (trefle.io): growing conditions on 0-10 scales and temperatures
+pub struct TrefleAdapter {
+    client: Client,
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrefleList {
+    #[serde(default)]
+    data: Vec<TreflePlant>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreflePlant {
+    slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrefleSpeciesResponse {
+    data: TrefleSpecies,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrefleSpecies {
+    scientific_name: String,
+    common_name: Option<String>,
+    family: Option<String>,
+    growth: Option<TrefleGrowth>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TrefleGrowth {
+    light: Option<u8>,
+    atmospheric_humidity: Option<u8>,
+    soil_humidity: Option<u8>,
+    minimum_temperature: Option<TrefleTemperature>,
+    maximum_temperature: Option<TrefleTemperature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrefleTemperature {
+    deg_c: Option<f64>,
+}
+
+impl TrefleAdapter {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: http_client::build_client()?,
+            token: get_secret("TREFLE_API_TOKEN")?,
+        })
+    }
+}
+
+#[async_trait]
+impl SpeciesDataPort for TrefleAdapter {
+    fn name(&self) -> &str {
+        "Trefle"
+    }
+
+    async fn lookup(&self, name: &str) -> Result<Option<SpeciesProfile>> {
+        let list: TrefleList = get_json(
+            &self.client,
+            "Trefle",
+            "https://trefle.io/api/v1/plants/search",
+            &[("token", &self.token), ("q", name)],
+        )
+        .await?;
+        let Some(plant) = list.data.first() else {
+            return Ok(None);
+        };
+
+        let species: TrefleSpeciesResponse = get_json(
+            &self.client,
+            "Trefle",
+            &format!("https://trefle.io/api/v1/species/{}", plant.slug),
+            &[("token", &self.token)],
+        )
+        .await?;
+        let species = species.data;
+        let growth = species.growth.unwrap_or_default();
+        let degrees = |t: &Option<TrefleTemperature>| t.as_ref().and_then(|t| t.deg_c);
+
+        Ok(Some(SpeciesProfile {
+            source: self.name().to_string(),
+            scientific_name: species.scientific_name,
+            common_name: species.common_name,
+            family: species.family,
+            light: growth.light.map(species_profile::light_from_scale),
+            water: growth.soil_humidity.map(species_profile::water_from_scale),
+            humidity: growth.atmospheric_humidity.map(species_profile::humidity_from_scale),
+            temperature: species_profile::temperature_range(
+                degrees(&growth.minimum_temperature),
+                degrees(&growth.maximum_temperature),
+            ),
+        }))
+    }
+}
@@ -0,0 +1,102 @@
+/*!
+ * STORAGE ADAPTER
+ *
+ * Secondary port for image storage. Images are stored on the local
+ * filesystem by default, or in an S3-compatible bucket when
+ * `STORAGE_BACKEND=s3` so they survive machine reinstalls.
+ *
+ * Plant photos are content-addressed: stored under the SHA-256 of their
+ * bytes, so the same photo is only stored once.
+ */
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::adapters::{S3StorageAdapter, StoragePort};
+use crate::crypto;
+
+/// Create the storage backend selected by `STORAGE_BACKEND` (`local` or `s3`)
+pub fn from_env() -> Result<Arc<dyn StoragePort>> {
+    let backend = std::env::var("STORAGE_BACKEND").unwrap_or_else(|_| "local".to_string());
+
+    match backend.as_str() {
+        "local" => Ok(Arc::new(LocalStorageAdapter::new())),
+        "s3" => Ok(Arc::new(S3StorageAdapter::new()?)),
+        other => anyhow::bail!("Unknown STORAGE_BACKEND '{}': expected 'local' or 's3'", other),
+    }
+}
+
+/// Store an image under the hash of its content, returning its URL and
+/// the hash
+pub async fn store_by_hash(storage: &dyn StoragePort, image_data: &[u8]) -> Result<(String, String)> {
+    let hash = crypto::content_hash(image_data);
+    let extension = image::guess_format(image_data)
+        .ok()
+        .and_then(|f| f.extensions_str().first().copied())
+        .unwrap_or("jpg");
+
+    let url = storage
+        .upload_image(image_data, &format!("{}.{}", hash, extension))
+        .await?;
+
+    Ok((url, hash))
+}
+
+/// Stores images in a local directory
+pub struct LocalStorageAdapter {
+    storage_dir: PathBuf,
+}
+
+impl LocalStorageAdapter {
+    pub fn new() -> Self {
+        let storage_dir = std::env::var("STORAGE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                let mut dir = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+                dir.push("plant-care");
+                dir.push("images");
+                dir
+            });
+
+        // Create directory if it doesn't exist
+        fs::create_dir_all(&storage_dir).ok();
+
+        Self { storage_dir }
+    }
+}
+
+impl Default for LocalStorageAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl StoragePort for LocalStorageAdapter {
+    async fn upload_image(&self, image_data: &[u8], filename: &str) -> Result<String> {
+        let file_path = self.storage_dir.join(filename);
+        fs::write(&file_path, image_data)?;
+
+        Ok(file_path.to_string_lossy().to_string())
+    }
+
+    async fn read_image(&self, url: &str) -> Result<Vec<u8>> {
+        Ok(fs::read(url)?)
+    }
+
+    async fn delete_image(&self, url: &str) -> Result<()> {
+        let path = PathBuf::from(url);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    async fn presigned_url(&self, url: &str, _expires_in_secs: u32) -> Result<String> {
+        // Local files need no signing
+        Ok(format!("file://{}", url))
+    }
+}
@@ -0,0 +1,151 @@
+/*!
+ * SYNC ADAPTER
+ *
+ * Secondary port for the remote that devices exchange their event logs
+ * through. Each device writes batches of events under its own device ID
+ * and reads the batches written by the others; batches are never changed
+ * once written.
+ *
+ * The remote is selected with `SYNC_REMOTE`:
+ * - `s3` uses the bucket configured for S3 storage (`S3_BUCKET`, ...)
+ * - `dir:<path>` uses a shared directory (network drive, Syncthing folder...)
+ */
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use s3::Bucket;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::adapters::{s3_storage_adapter, SyncRemote};
+
+/// Key prefix under which batches are stored in the bucket
+const S3_KEY_PREFIX: &str = "sync/";
+
+/// Create the remote selected by `SYNC_REMOTE`
+pub fn from_env() -> Result<Arc<dyn SyncRemote>> {
+    let remote = std::env::var("SYNC_REMOTE")
+        .context("No sync remote configured: set SYNC_REMOTE to 's3' or 'dir:<path>'")?;
+
+    if remote == "s3" {
+        return Ok(Arc::new(S3SyncRemote {
+            bucket: s3_storage_adapter::bucket_from_env()?,
+        }));
+    }
+
+    match remote.split_once(':') {
+        Some(("dir", path)) if !path.is_empty() => Ok(Arc::new(DirectorySyncRemote::new(path)?)),
+        _ => anyhow::bail!(
+            "Unknown SYNC_REMOTE '{}': expected 's3' or 'dir:<path>'",
+            remote
+        ),
+    }
+}
+
+/// Stores batches in the S3 bucket, under `sync/`
+pub struct S3SyncRemote {
+    bucket: Box<Bucket>,
+}
+
+#[async_trait]
+impl SyncRemote for S3SyncRemote {
+    fn location(&self) -> String {
+        format!("s3://{}/{}", self.bucket.name(), S3_KEY_PREFIX)
+    }
+
+    async fn list_batches(&self) -> Result<Vec<String>> {
+        let pages = self.bucket.list(S3_KEY_PREFIX.to_string(), None).await?;
+
+        Ok(pages
+            .into_iter()
+            .flat_map(|page| page.contents)
+            .filter_map(|object| object.key.strip_prefix(S3_KEY_PREFIX).map(str::to_string))
+            .collect())
+    }
+
+    async fn read_batch(&self, name: &str) -> Result<Vec<u8>> {
+        let response = self
+            .bucket
+            .get_object(format!("{}{}", S3_KEY_PREFIX, name))
+            .await?;
+
+        if response.status_code() >= 300 {
+            anyhow::bail!("S3 download failed with status {}", response.status_code());
+        }
+
+        Ok(response.to_vec())
+    }
+
+    async fn write_batch(&self, name: &str, data: &[u8]) -> Result<()> {
+        let response = self
+            .bucket
+            .put_object(format!("{}{}", S3_KEY_PREFIX, name), data)
+            .await?;
+
+        if response.status_code() >= 300 {
+            anyhow::bail!("S3 upload failed with status {}", response.status_code());
+        }
+
+        Ok(())
+    }
+}
+
+/// Stores batches as files in a shared directory
+pub struct DirectorySyncRemote {
+    root: PathBuf,
+}
+
+impl DirectorySyncRemote {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)
+            .with_context(|| format!("Failed to create sync directory {}", root.display()))?;
+
+        Ok(Self { root })
+    }
+}
+
+#[async_trait]
+impl SyncRemote for DirectorySyncRemote {
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    async fn list_batches(&self) -> Result<Vec<String>> {
+        let mut batches = Vec::new();
+
+        for device in fs::read_dir(&self.root)? {
+            let device = device?;
+            if !device.file_type()?.is_dir() {
+                continue;
+            }
+            for batch in fs::read_dir(device.path())? {
+                let file_name = batch?.file_name().to_string_lossy().to_string();
+                if file_name.ends_with(".json") {
+                    batches.push(format!("{}/{}", device.file_name().to_string_lossy(), file_name));
+                }
+            }
+        }
+
+        Ok(batches)
+    }
+
+    async fn read_batch(&self, name: &str) -> Result<Vec<u8>> {
+        Ok(fs::read(self.root.join(name))?)
+    }
+
+    async fn write_batch(&self, name: &str, data: &[u8]) -> Result<()> {
+        let path = self.root.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write under a temporary name so other devices never read half a batch
+        let partial = path.with_extension("partial");
+        fs::write(&partial, data)?;
+        fs::rename(&partial, &path)?;
+
+        Ok(())
+    }
+}
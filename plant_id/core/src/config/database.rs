@@ -0,0 +1,827 @@
+/*!
+ * DATABASE CONNECTION MANAGEMENT
+ *
+ * Manages SQLite database connections and migrations.
+ * This is infrastructure code that supports repositories (secondary adapters).
+ */
+
+use anyhow::Result;
+use log::LevelFilter;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{ConnectOptions, Pool, Sqlite};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::repositories::PlantCache;
+
+/// Queries slower than this are logged as warnings, unless `SLOW_QUERY_MS`
+/// says otherwise
+const DEFAULT_SLOW_QUERY_MS: u64 = 200;
+
+/// Threshold above which a query is logged as slow, from `SLOW_QUERY_MS`
+pub fn slow_query_threshold() -> Duration {
+    static THRESHOLD: OnceLock<Duration> = OnceLock::new();
+    *THRESHOLD.get_or_init(|| {
+        let millis = std::env::var("SLOW_QUERY_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_SLOW_QUERY_MS);
+        Duration::from_millis(millis)
+    })
+}
+
+#[derive(Clone)]
+pub struct Database {
+    pool: Pool<Sqlite>,
+    /// Shared by every clone, so all repositories see the same plants
+    plant_cache: Option<PlantCache>,
+}
+
+impl Database {
+    /// Create a new database connection pool
+    pub async fn new() -> Result<Self> {
+        let database_path = std::env::var("DATABASE_PATH")
+            .unwrap_or_else(|_| "plant_care.db".to_string());
+
+        // Every statement is logged by sqlx (target `sqlx::query`) with its
+        // duration at debug level, and as a warning when it is slow
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", database_path))?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .log_statements(LevelFilter::Debug)
+            .log_slow_statements(LevelFilter::Warn, slow_query_threshold());
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await?;
+
+        Ok(Self { pool, plant_cache: None })
+    }
+
+    /// Create a private in-memory database, discarded when dropped
+    pub async fn in_memory() -> Result<Self> {
+        // A single connection, since each in-memory connection is its own database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        Ok(Self { pool, plant_cache: None })
+    }
+
+    /// Get a reference to the connection pool
+    pub fn pool(&self) -> &Pool<Sqlite> {
+        &self.pool
+    }
+
+    /// Keep plants read through this database in `cache`. Meant for
+    /// long-running processes; one-off commands read each plant once anyway.
+    pub fn with_plant_cache(mut self, cache: PlantCache) -> Self {
+        self.plant_cache = Some(cache);
+        self
+    }
+
+    pub(crate) fn plant_cache(&self) -> Option<&PlantCache> {
+        self.plant_cache.as_ref()
+    }
+
+    /// Run database migrations
+    pub async fn migrate(&self) -> Result<()> {
+        // Create plants table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS plants (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                care_schedule TEXT NOT NULL,
+                image_url TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create diagnosis_sessions table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS diagnosis_sessions (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                status TEXT NOT NULL,
+                diagnosis_context TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create diagnosis_messages table: the turns of each diagnosis
+        // conversation, in the order they were added
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS diagnosis_messages (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES diagnosis_sessions(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create chat_sessions table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS chat_sessions (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                messages TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create journal_entries table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS journal_entries (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                note TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create treatment_outcomes table (kept when a plant is deleted, so
+        // species experience is not lost)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS treatment_outcomes (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                species TEXT NOT NULL,
+                diagnosis_id TEXT NOT NULL,
+                finding TEXT NOT NULL,
+                recommendation TEXT NOT NULL,
+                worked INTEGER NOT NULL,
+                note TEXT,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create feedback table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS feedback (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                model TEXT NOT NULL,
+                rating INTEGER NOT NULL,
+                comment TEXT,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create care_tasks table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS care_tasks (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                last_done_at TEXT,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create templates table: named care schedules applied to new plants
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS templates (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                care_schedule TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create shopping_entries table: supplies added to or taken off
+        // the shopping list by hand
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS shopping_entries (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                removed INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create moisture_readings table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS moisture_readings (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                value INTEGER NOT NULL,
+                recorded_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create plant_attributes table: owner-defined key/value fields
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS plant_attributes (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE (plant_id, key),
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create expenses table: what each plant has cost, in cents
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS expenses (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                amount_cents INTEGER NOT NULL,
+                description TEXT NOT NULL,
+                spent_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create rooms table: the light in the rooms plants are kept in
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS rooms (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                window TEXT,
+                sun_hours REAL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create wishlist_items table: plants the owner would like to get
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS wishlist_items (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                species TEXT NOT NULL,
+                common_name TEXT,
+                note TEXT NOT NULL,
+                added_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create care_schedule_versions table: every schedule a plant has
+        // had, with what changed and where it came from
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS care_schedule_versions (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                schedule TEXT NOT NULL,
+                changes TEXT NOT NULL,
+                source TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create care_overrides table: the species baseline of a plant's
+        // care schedule and the owner's changes to it, for plants with any
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS care_overrides (
+                plant_id TEXT PRIMARY KEY,
+                baseline TEXT NOT NULL,
+                overrides TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create identification_disagreements table: photos the identifiers
+        // named different species for, with how the species was chosen
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS identification_disagreements (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                candidates TEXT NOT NULL,
+                chosen TEXT NOT NULL,
+                resolution TEXT NOT NULL,
+                corrected_to TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create plant_quarantines table: infested plants kept away from the
+        // collection until a clean follow-up diagnosis
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS plant_quarantines (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                reason TEXT,
+                started_at TEXT NOT NULL,
+                last_inspected_at TEXT,
+                released_at TEXT,
+                release_diagnosis_id TEXT,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create preventive_inspections table: one-off checks of plants whose
+        // relatives were diagnosed with a pest or disease
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS preventive_inspections (
+                id TEXT PRIMARY KEY,
+                plant_id TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                source_diagnosis_id TEXT,
+                due_on TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                done_at TEXT,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create quarantined_rows table: copies of unreadable rows that
+        // `db repair` reset or removed
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS quarantined_rows (
+                id TEXT PRIMARY KEY,
+                table_name TEXT NOT NULL,
+                row_id TEXT NOT NULL,
+                data TEXT NOT NULL,
+                error TEXT NOT NULL,
+                quarantined_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create events table: an append-only audit log of every change,
+        // kept when the records it describes are deleted
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS events (
+                id TEXT PRIMARY KEY,
+                entity_type TEXT NOT NULL,
+                entity_id TEXT NOT NULL,
+                plant_id TEXT,
+                action TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create sync tables: this device's sync settings, and the batches
+        // pulled from other devices
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS sync_state (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS sync_batches (
+                name TEXT PRIMARY KEY,
+                applied_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create users table: roles of the people using this device. Not
+        // synced, so handing one device to a sitter leaves the others alone
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS users (
+                id TEXT PRIMARY KEY,
+                role TEXT NOT NULL,
+                pin_hash TEXT,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create jobs table: queued work, such as generating a care
+        // schedule while the AI was down or redelivering a webhook. Not
+        // synced; each device runs its own.
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS jobs (
+                id TEXT PRIMARY KEY,
+                user_id TEXT,
+                plant_id TEXT,
+                kind TEXT NOT NULL,
+                payload TEXT,
+                status TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                run_at TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (plant_id) REFERENCES plants(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create indexes for better query performance
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_plants_user_id ON plants(user_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_diagnosis_sessions_plant_id ON diagnosis_sessions(plant_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_diagnosis_messages_session_id ON diagnosis_messages(session_id, created_at)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_chat_sessions_plant_id ON chat_sessions(plant_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_journal_entries_plant_id ON journal_entries(plant_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_treatment_outcomes_species ON treatment_outcomes(species)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_care_tasks_plant_id ON care_tasks(plant_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_templates_user_name ON templates(user_id, name COLLATE NOCASE)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_shopping_entries_user_name ON shopping_entries(user_id, name COLLATE NOCASE)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_moisture_readings_plant_id ON moisture_readings(plant_id, recorded_at)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_expenses_plant_id ON expenses(plant_id, spent_at)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_care_schedule_versions_plant_id ON care_schedule_versions(plant_id, version)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_identification_disagreements_plant_id ON identification_disagreements(plant_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_plant_quarantines_plant_id ON plant_quarantines(plant_id, released_at)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_events_plant_id ON events(plant_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status, run_at)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Columns added after a table was first released
+        self.add_column_if_missing("journal_entries", "is_problem", "INTEGER NOT NULL DEFAULT 0")
+            .await?;
+        self.add_column_if_missing("plants", "archived_at", "TEXT").await?;
+        self.add_column_if_missing("plants", "archive_reason", "TEXT").await?;
+        self.add_column_if_missing("plants", "room", "TEXT").await?;
+        self.add_column_if_missing("plants", "tags", "TEXT NOT NULL DEFAULT '[]'").await?;
+        self.add_column_if_missing("plants", "difficulty", "TEXT").await?;
+        self.add_column_if_missing("events", "synced", "INTEGER NOT NULL DEFAULT 0").await?;
+        self.add_column_if_missing("users", "api_key_hash", "TEXT").await?;
+        self.add_column_if_missing("diagnosis_sessions", "state", "TEXT").await?;
+        self.add_column_if_missing("plants", "image_hash", "TEXT").await?;
+        self.add_column_if_missing("plants", "scientific_name", "TEXT").await?;
+        self.add_column_if_missing("plants", "common_names", "TEXT NOT NULL DEFAULT '[]'").await?;
+        self.add_column_if_missing("plants", "nickname", "TEXT").await?;
+        self.add_column_if_missing("plants", "family", "TEXT").await?;
+        self.add_column_if_missing("plants", "acquired_at", "TEXT").await?;
+        self.add_column_if_missing("plants", "acquired_from", "TEXT").await?;
+        self.add_column_if_missing("rooms", "temperature", "REAL").await?;
+        self.add_column_if_missing("rooms", "humidity", "REAL").await?;
+        self.add_column_if_missing("rooms", "drafty", "INTEGER").await?;
+        self.add_column_if_missing("rooms", "heated", "INTEGER").await?;
+
+        sqlx::query(
+            r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_users_api_key_hash ON users(api_key_hash)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // The index first covered (user_id, image_hash), which the planner
+        // could pick over idx_plants_user_id for listings. Photo hashes are
+        // selective on their own.
+        let image_hash_index: Option<String> =
+            sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'index' AND name = 'idx_plants_image_hash'")
+                .fetch_optional(&self.pool)
+                .await?;
+        if image_hash_index.is_some_and(|sql| sql.contains("user_id")) {
+            sqlx::query("DROP INDEX idx_plants_image_hash").execute(&self.pool).await?;
+        }
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_plants_image_hash ON plants(image_hash)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.move_conversation_history().await?;
+
+        let had_versions: bool = sqlx::query_scalar("SELECT COUNT(*) > 0 FROM care_schedule_versions")
+            .fetch_one(&self.pool)
+            .await?;
+        self.move_schedule_revisions().await?;
+        if !had_versions {
+            self.seed_schedule_versions().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Move the AI-generated schedules the first schedule history kept in
+    /// `schedule_revisions` into `care_schedule_versions`, numbered in the
+    /// order they were made
+    async fn move_schedule_revisions(&self) -> Result<()> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'schedule_revisions'",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if !exists {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO care_schedule_versions (id, plant_id, version, schedule, changes, source, created_at)
+            SELECT id, plant_id,
+                   ROW_NUMBER() OVER (PARTITION BY plant_id ORDER BY created_at),
+                   schedule, changes, json_object('kind', 'ai', 'model', model), created_at
+            FROM schedule_revisions
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DROP TABLE schedule_revisions").execute(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Make the schedule of each plant saved before versions were kept its
+    /// version 1, so that it can be rolled back to
+    async fn seed_schedule_versions(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO care_schedule_versions (id, plant_id, version, schedule, changes, source, created_at)
+            SELECT lower(hex(randomblob(16))), id, 1, care_schedule, '[]', json_object('kind', 'imported'), created_at
+            FROM plants
+            WHERE NOT EXISTS (SELECT 1 FROM care_schedule_versions v WHERE v.plant_id = plants.id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Move the conversation turns older versions kept in each session's
+    /// `diagnosis_context` into `diagnosis_messages`. Sessions that already
+    /// have messages only lose the stale copy.
+    async fn move_conversation_history(&self) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO diagnosis_messages (id, session_id, role, content, created_at)
+            SELECT lower(hex(randomblob(16))), s.id,
+                   COALESCE(json_extract(turn.value, '$.role'), 'user'),
+                   COALESCE(json_extract(turn.value, '$.message'), ''),
+                   s.created_at
+            FROM diagnosis_sessions s,
+                 json_each(CASE WHEN json_valid(s.diagnosis_context) THEN s.diagnosis_context END, '$.conversation_history') turn
+            WHERE NOT EXISTS (SELECT 1 FROM diagnosis_messages m WHERE m.session_id = s.id)
+            ORDER BY s.id, turn.key
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE diagnosis_sessions
+            SET diagnosis_context = json_remove(diagnosis_context, '$.conversation_history')
+            WHERE CASE WHEN json_valid(diagnosis_context)
+                THEN json_type(diagnosis_context, '$.conversation_history') IS NOT NULL END
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Add a column to an existing table unless it is already present
+    async fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> Result<()> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info(?) WHERE name = ?",
+        )
+        .bind(table)
+        .bind(column)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if !exists {
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
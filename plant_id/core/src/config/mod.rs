@@ -0,0 +1,40 @@
+/*!
+ * CONFIGURATION MODULE
+ *
+ * Handles application configuration including database setup,
+ * environment variables, and other infrastructure concerns.
+ */
+
+// Declare config modules
+pub mod database;
+pub mod secrets;
+pub mod timezone;
+
+// Re-export main configuration types
+pub use database::Database;
+
+// Re-export secret lookup (environment variables, then OS keychain)
+pub use secrets::get_secret;
+
+// Re-export the owner's time zone
+pub use timezone::local_zone;
+
+/// The directory local files are kept in by default (`plant-care` in the
+/// platform's data directory), such as the log file and AI traces
+pub fn data_dir() -> std::path::PathBuf {
+    let mut dir = dirs::data_local_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+    dir.push("plant-care");
+    dir
+}
+
+/// The configuration file: `PLANT_CARE_CONFIG`, or `plant-care/config.toml`
+/// in the platform's config directory
+pub fn config_file() -> std::path::PathBuf {
+    if let Some(path) = std::env::var_os("PLANT_CARE_CONFIG").filter(|p| !p.is_empty()) {
+        return std::path::PathBuf::from(path);
+    }
+    let mut path = dirs::config_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+    path.push("plant-care");
+    path.push("config.toml");
+    path
+}
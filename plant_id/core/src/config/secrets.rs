@@ -0,0 +1,109 @@
+/*!
+ * SECRET STORAGE
+ *
+ * API keys are kept in the OS keychain (macOS Keychain, Windows Credential
+ * Manager, Linux kernel keyring) rather than in a plaintext .env file.
+ * Environment variables still take precedence, which keeps CI and
+ * containers working without a keychain.
+ */
+
+use anyhow::{Context, Result};
+
+/// Service name secrets are stored under in the OS keychain
+const KEYRING_SERVICE: &str = "plant-care";
+
+/// Secrets managed by `init`
+pub const MANAGED_SECRETS: &[&str] = &[
+    "OPENROUTER_API_KEY",
+    "PLANT_ID_API_KEY",
+    "PERENUAL_API_KEY",
+    "TREFLE_API_TOKEN",
+    "TELEGRAM_BOT_TOKEN",
+    "DISCORD_BOT_TOKEN",
+    "SMTP_PASSWORD",
+];
+
+/// Storage backend for secrets
+pub trait SecretStore {
+    fn get(&self, key: &str) -> Result<Option<String>>;
+    fn set(&self, key: &str, value: &str) -> Result<()>;
+}
+
+/// Secret store backed by the OS keychain
+pub struct KeyringSecretStore;
+
+impl SecretStore for KeyringSecretStore {
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match keyring::Entry::new(KEYRING_SERVICE, key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context(format!("Failed to read {} from the OS keychain", key)),
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        keyring::Entry::new(KEYRING_SERVICE, key)?
+            .set_password(value)
+            .context(format!("Failed to store {} in the OS keychain", key))
+    }
+}
+
+/// Get a secret from the environment, falling back to the OS keychain
+pub fn get_secret(key: &str) -> Result<String> {
+    lookup(&KeyringSecretStore, key)
+}
+
+fn lookup(store: &dyn SecretStore, key: &str) -> Result<String> {
+    if let Ok(value) = std::env::var(key) {
+        return Ok(value);
+    }
+
+    let stored = store.get(key).unwrap_or_else(|e| {
+        log::debug!("Keychain lookup failed: {:#}", e);
+        None
+    });
+
+    stored.context(format!(
+        "Missing {}: run `plant-care init` or set the environment variable",
+        key
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemorySecretStore(RefCell<HashMap<String, String>>);
+
+    impl SecretStore for MemorySecretStore {
+        fn get(&self, key: &str) -> Result<Option<String>> {
+            Ok(self.0.borrow().get(key).cloned())
+        }
+
+        fn set(&self, key: &str, value: &str) -> Result<()> {
+            self.0.borrow_mut().insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_lookup_falls_back_to_store() {
+        let store = MemorySecretStore::default();
+        assert!(lookup(&store, "PLANT_CARE_TEST_SECRET_A").is_err());
+
+        store.set("PLANT_CARE_TEST_SECRET_A", "from-store").unwrap();
+        assert_eq!(lookup(&store, "PLANT_CARE_TEST_SECRET_A").unwrap(), "from-store");
+    }
+
+    #[test]
+    fn test_env_overrides_store() {
+        let store = MemorySecretStore::default();
+        store.set("PLANT_CARE_TEST_SECRET_B", "from-store").unwrap();
+        std::env::set_var("PLANT_CARE_TEST_SECRET_B", "from-env");
+
+        assert_eq!(lookup(&store, "PLANT_CARE_TEST_SECRET_B").unwrap(), "from-env");
+    }
+}
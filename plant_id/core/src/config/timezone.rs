@@ -0,0 +1,28 @@
+/*!
+ * TIME ZONE
+ *
+ * The owner's time zone, used for "today" and to show dates. It comes from
+ * `--timezone`, then `TIMEZONE` (an IANA name such as `Europe/Paris`),
+ * then the system's time zone.
+ */
+
+use std::sync::OnceLock;
+
+use crate::domain::LocalZone;
+
+static LOCAL_ZONE: OnceLock<LocalZone> = OnceLock::new();
+
+/// Override the time zone for the rest of the process
+pub fn set_local_zone(zone: LocalZone) {
+    LOCAL_ZONE.set(zone).ok();
+}
+
+pub fn local_zone() -> &'static LocalZone {
+    LOCAL_ZONE.get_or_init(|| match std::env::var("TIMEZONE") {
+        Ok(name) if !name.trim().is_empty() => LocalZone::named(&name).unwrap_or_else(|e| {
+            log::warn!("{:#}; using the system time zone", e);
+            LocalZone::system()
+        }),
+        _ => LocalZone::system(),
+    })
+}
@@ -0,0 +1,169 @@
+/*!
+ * CRYPTO UTILITIES
+ *
+ * Passphrase-based encryption for export archives, which contain photos
+ * and personal notes. Uses AES-256-GCM with a key derived from the
+ * passphrase by Argon2id.
+ *
+ * Encrypted layout: MAGIC | salt (16 bytes) | nonce (12 bytes) | ciphertext
+ *
+ * Also hashes the PIN that ends sitter mode, with Argon2id as well, and
+ * generates the API keys of the HTTP server.
+ */
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Header identifying encrypted plant-care files
+const MAGIC: &[u8] = b"PLANTCARE-ENC1\n";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Whether data was produced by `encrypt`
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypt data with a passphrase
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt)?);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+
+    let mut output = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(MAGIC);
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+
+    Ok(output)
+}
+
+/// Decrypt data produced by `encrypt`
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let body = data
+        .strip_prefix(MAGIC)
+        .context("Data is not an encrypted plant-care file")?;
+    if body.len() < SALT_LEN + NONCE_LEN {
+        anyhow::bail!("Encrypted file is truncated");
+    }
+
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt)?);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Decryption failed: wrong passphrase or corrupted file"))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>> {
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Hash a PIN or password for storage
+pub fn hash_secret(secret: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(secret.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow::anyhow!("Hashing failed: {}", e))
+}
+
+/// Whether a secret matches a hash made by `hash_secret`
+pub fn verify_secret(secret: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(secret.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+/// Prefix that makes API keys recognizable, e.g. in leaked-secret scans
+const API_KEY_PREFIX: &str = "pck_";
+
+/// A new random API key
+pub fn generate_api_key() -> String {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    format!("{}{}", API_KEY_PREFIX, hex(&key))
+}
+
+/// Hash of an API key for storage and lookup. API keys are random, so a
+/// fast unsalted hash is enough, unlike for PINs and passphrases.
+pub fn hash_api_key(key: &str) -> String {
+    content_hash(key.as_bytes())
+}
+
+/// SHA-256 of `data` as lowercase hex, identifying content such as images
+pub fn content_hash(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let encrypted = encrypt(b"secret plant data", "hunter2").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(decrypt(&encrypted, "hunter2").unwrap(), b"secret plant data");
+    }
+
+    #[test]
+    fn test_wrong_passphrase_fails() {
+        let encrypted = encrypt(b"secret plant data", "hunter2").unwrap();
+        assert!(decrypt(&encrypted, "wrong").is_err());
+    }
+
+    #[test]
+    fn test_secret_hash() {
+        let hash = hash_secret("1234").unwrap();
+        assert!(verify_secret("1234", &hash));
+        assert!(!verify_secret("4321", &hash));
+        assert!(!verify_secret("1234", "not a hash"));
+    }
+
+    #[test]
+    fn test_api_keys() {
+        let key = generate_api_key();
+        assert!(key.starts_with("pck_"));
+        assert_eq!(key.len(), 4 + 64);
+        assert_ne!(key, generate_api_key());
+        assert_eq!(hash_api_key(&key), hash_api_key(&key));
+        assert_eq!(
+            hash_api_key("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(b"photo"), content_hash(b"photo"));
+        assert_ne!(content_hash(b"photo"), content_hash(b"other photo"));
+        assert_eq!(content_hash(b"abc"), hash_api_key("abc"));
+    }
+
+    #[test]
+    fn test_plaintext_is_not_encrypted() {
+        assert!(!is_encrypted(b"{\"plants\": []}"));
+    }
+}
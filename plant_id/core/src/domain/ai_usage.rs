@@ -0,0 +1,159 @@
+//! AI USAGE AND COST
+//!
+//! Tokens used by AI requests, as the API reports them, and what they cost
+//! at the model's price. Diagnosis sessions keep their usage in the context
+//! under `usage`, so the cost of the next diagnosis can be estimated from the
+//! average of earlier ones.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// Usage assumed for a diagnosis until earlier ones were measured
+pub const TYPICAL_DIAGNOSIS: TokenUsage = TokenUsage {
+    requests: 4,
+    prompt_tokens: 12_000,
+    completion_tokens: 1_200,
+};
+
+/// Prices per million tokens in USD (prompt, completion) of common models,
+/// as listed by OpenRouter
+const PRICES: &[(&str, f64, f64)] = &[
+    ("anthropic/claude-3.5-sonnet", 3.0, 15.0),
+    ("anthropic/claude-3.5-haiku", 0.8, 4.0),
+    ("anthropic/claude-3-haiku", 0.25, 1.25),
+    ("anthropic/claude-3-opus", 15.0, 75.0),
+    ("openai/gpt-4o", 2.5, 10.0),
+    ("openai/gpt-4o-mini", 0.15, 0.6),
+    ("google/gemini-flash-1.5", 0.075, 0.3),
+    ("google/gemini-pro-1.5", 1.25, 5.0),
+    ("meta-llama/llama-3.1-70b-instruct", 0.12, 0.3),
+];
+
+/// Tokens used by one or more AI requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub requests: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.requests += other.requests;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    /// Usage recorded in a diagnosis context, if any
+    pub fn of_session(context: &JsonValue) -> Option<Self> {
+        serde_json::from_value(context.get("usage")?.clone()).ok()
+    }
+
+    /// The average of `history`, rounded up
+    pub fn average(history: &[TokenUsage]) -> Option<Self> {
+        let count = history.len() as u64;
+        if count == 0 {
+            return None;
+        }
+
+        let mut total = TokenUsage::default();
+        for usage in history {
+            total.add(usage);
+        }
+        Some(Self {
+            requests: (total.requests as u64).div_ceil(count) as u32,
+            prompt_tokens: total.prompt_tokens.div_ceil(count),
+            completion_tokens: total.completion_tokens.div_ceil(count),
+        })
+    }
+}
+
+/// What a model charges per million tokens, in USD
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ModelPrice {
+    pub prompt: f64,
+    pub completion: f64,
+}
+
+impl ModelPrice {
+    /// Listed price of a model; variants such as ":beta" cost the same
+    pub fn of(model: &str) -> Option<Self> {
+        let base = model.split(':').next().unwrap_or(model);
+        PRICES
+            .iter()
+            .find(|(name, _, _)| name.eq_ignore_ascii_case(base))
+            .map(|&(_, prompt, completion)| Self { prompt, completion })
+    }
+
+    /// Read a price such as "3,15": USD per million prompt tokens, then per
+    /// million completion tokens
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = || {
+            anyhow::anyhow!("Invalid price '{}': expected USD per million prompt,completion tokens, like 3,15", text)
+        };
+        let (prompt, completion) = text.split_once(',').ok_or_else(invalid)?;
+        let prompt: f64 = prompt.trim().parse().map_err(|_| invalid())?;
+        let completion: f64 = completion.trim().parse().map_err(|_| invalid())?;
+        if !(prompt >= 0.0 && completion >= 0.0 && prompt.is_finite() && completion.is_finite()) {
+            return Err(invalid());
+        }
+
+        Ok(Self { prompt, completion })
+    }
+
+    /// Cost of `usage` in USD
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt + usage.completion_tokens as f64 * self.completion) / 1_000_000.0
+    }
+}
+
+/// An amount in USD, with more decimals for the fractions of a cent AI
+/// requests tend to cost
+pub fn format_usd(amount: f64) -> String {
+    if amount > 0.0 && amount < 0.001 {
+        "<$0.001".to_string()
+    } else if amount < 1.0 {
+        format!("${:.3}", amount)
+    } else {
+        format!("${:.2}", amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cost_of_usage() {
+        let history = [
+            TokenUsage { requests: 3, prompt_tokens: 9_000, completion_tokens: 800 },
+            TokenUsage { requests: 4, prompt_tokens: 12_001, completion_tokens: 1_000 },
+        ];
+        let average = TokenUsage::average(&history).unwrap();
+        assert_eq!(average, TokenUsage { requests: 4, prompt_tokens: 10_501, completion_tokens: 900 });
+        assert!(TokenUsage::average(&[]).is_none());
+
+        let sonnet = ModelPrice::of("anthropic/claude-3.5-sonnet:beta").unwrap();
+        let cost = sonnet.cost(&TokenUsage { requests: 1, prompt_tokens: 10_000, completion_tokens: 1_000 });
+        assert!((cost - 0.045).abs() < 1e-9);
+        assert!(ModelPrice::of("someone/unknown-model").is_none());
+
+        assert_eq!(ModelPrice::parse(" 0.5, 2 ").unwrap(), ModelPrice { prompt: 0.5, completion: 2.0 });
+        assert!(ModelPrice::parse("3").is_err());
+        assert!(ModelPrice::parse("-1,2").is_err());
+
+        let context = json!({"usage": {"requests": 2, "prompt_tokens": 100, "completion_tokens": 20}});
+        assert_eq!(TokenUsage::of_session(&context).unwrap().total_tokens(), 120);
+        assert!(TokenUsage::of_session(&json!({})).is_none());
+
+        assert_eq!(format_usd(0.045), "$0.045");
+        assert_eq!(format_usd(0.0002), "<$0.001");
+        assert_eq!(format_usd(1.5), "$1.50");
+    }
+}
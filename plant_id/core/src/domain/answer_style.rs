@@ -0,0 +1,124 @@
+//! ANSWER STYLE
+//!
+//! How the AI writes for the owner: for a beginner or a horticulturist, and
+//! concisely or in detail. Either part may be left to the model. The style's
+//! instructions are added to every system prompt, so diagnoses, chats,
+//! explanations and advice all read the same way.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// How much botany the owner wants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expertise {
+    /// Plain words and simple steps
+    Beginner,
+    /// Precise terms, scientific names and the physiology behind them
+    Horticulturist,
+}
+
+/// How long answers should be
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verbosity {
+    Concise,
+    Verbose,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnswerStyle {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expertise: Option<Expertise>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verbosity: Option<Verbosity>,
+}
+
+impl AnswerStyle {
+    /// Read a style such as "beginner,concise": an expertise level
+    /// (beginner or horticulturist), a length (concise or verbose), or both
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut style = Self::default();
+        for word in text.split([',', ' ']).map(str::trim).filter(|w| !w.is_empty()) {
+            match word.to_ascii_lowercase().as_str() {
+                "beginner" => style.expertise = Some(Expertise::Beginner),
+                "horticulturist" => style.expertise = Some(Expertise::Horticulturist),
+                "concise" => style.verbosity = Some(Verbosity::Concise),
+                "verbose" => style.verbosity = Some(Verbosity::Verbose),
+                _ => anyhow::bail!(
+                    "Unknown answer style '{}': expected beginner or horticulturist, concise or verbose",
+                    word
+                ),
+            }
+        }
+
+        Ok(style)
+    }
+
+    /// This style, with the parts set in `overrides` replacing its own
+    pub fn overridden_by(self, overrides: AnswerStyle) -> Self {
+        Self {
+            expertise: overrides.expertise.or(self.expertise),
+            verbosity: overrides.verbosity.or(self.verbosity),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Instructions to add to a system prompt, if any part is set. They
+    /// only concern the wording, so prompts asking for JSON still get it.
+    pub fn instructions(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        match self.expertise {
+            Some(Expertise::Beginner) => lines.push(
+                "Write for a beginner: use plain words, explain any botanical term or Latin name you need, \
+                 and give recommendations as simple numbered steps.",
+            ),
+            Some(Expertise::Horticulturist) => lines.push(
+                "Write for an experienced horticulturist: use precise botanical terms, name pathogens and \
+                 pests by their scientific names, and explain the physiology behind symptoms and treatments.",
+            ),
+            None => {}
+        }
+        match self.verbosity {
+            Some(Verbosity::Concise) => {
+                lines.push("Be concise: give only the essentials, in as few sentences as will do.")
+            }
+            Some(Verbosity::Verbose) => lines.push(
+                "Be thorough: explain your reasoning, the alternatives you considered and what to watch for afterwards.",
+            ),
+            None => {}
+        }
+        if lines.is_empty() {
+            return None;
+        }
+
+        lines.push(
+            "This is about how you word your answers only: keep to any format asked for above, such as JSON.",
+        );
+        Some(format!("Answer style:\n{}", lines.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer_style() {
+        let style = AnswerStyle::parse("Beginner, concise").unwrap();
+        assert_eq!(style.expertise, Some(Expertise::Beginner));
+        assert_eq!(style.verbosity, Some(Verbosity::Concise));
+
+        let overridden = style.overridden_by(AnswerStyle::parse("horticulturist").unwrap());
+        assert_eq!(overridden.expertise, Some(Expertise::Horticulturist));
+        assert_eq!(overridden.verbosity, Some(Verbosity::Concise));
+
+        assert!(AnswerStyle::parse("").unwrap().is_default());
+        assert_eq!(AnswerStyle::default().instructions(), None);
+        assert!(overridden.instructions().unwrap().contains("scientific names"));
+        assert!(AnswerStyle::parse("chatty").is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! APP IMPORT
+//!
+//! Collections exported from other plant apps, read into our plants. Each
+//! app lays out its CSV export its own way: Planta has one row per plant,
+//! with its watering dates in one column, and Greg one row per care event.
+//! Columns are found by their header, in any case, under each name the app
+//! has used for them.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::care_schedule::CareSchedule;
+use super::Plant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceApp {
+    Planta,
+    Greg,
+}
+
+/// Header names of one kind of column, lowercase
+type Column = &'static [&'static str];
+
+const NAME: Column = &["name", "plant name", "plant", "common name"];
+const NICKNAME: Column = &["nickname", "custom name"];
+const SPECIES: Column = &["scientific name", "latin name", "species", "botanical name"];
+const ROOM: Column = &["site", "room", "location"];
+const PHOTO: Column = &["photo", "image", "photo url", "image url", "picture"];
+const WATERED: Column = &["watered", "watering history", "watering log", "last watered"];
+const FERTILIZED: Column = &["fertilized", "fertilizing history", "fertilizing log", "last fertilized"];
+const WATERING_DAYS: Column = &["watering interval", "water every", "watering frequency"];
+const NOTES: Column = &["notes", "note"];
+const DATE: Column = &["date", "completed", "completed at", "done at"];
+const EVENT: Column = &["event", "action", "task", "type"];
+
+/// A plant as another app exported it
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportedPlant {
+    /// The species' everyday name, or whatever the app called the plant
+    pub name: String,
+    pub nickname: Option<String>,
+    pub scientific_name: Option<String>,
+    pub room: Option<String>,
+    /// Path (relative to the export) or URL of the plant's photo
+    pub photo: Option<String>,
+    /// Oldest first
+    pub watered: Vec<NaiveDate>,
+    /// Oldest first
+    pub fertilized: Vec<NaiveDate>,
+    pub watering_days: Option<u32>,
+    pub notes: Option<String>,
+}
+
+impl ImportedPlant {
+    pub fn display_name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The plant of the collection this one already is: the one with the
+    /// same name, unless both have botanical names and they differ
+    pub fn duplicate_of<'a>(&self, plants: &'a [Plant]) -> Option<&'a Plant> {
+        plants.iter().find(|plant| {
+            plant.display_name().eq_ignore_ascii_case(self.display_name())
+                && match (&plant.scientific_name, &self.scientific_name) {
+                    (Some(ours), Some(theirs)) => ours.eq_ignore_ascii_case(theirs),
+                    _ => true,
+                }
+        })
+    }
+
+    /// A new plant of `user_id`. Its care schedule is the default one, with
+    /// the app's watering interval if it had one, until a proper one is
+    /// written.
+    pub fn to_plant(&self, user_id: &str) -> Plant {
+        let mut schedule = CareSchedule::default();
+        if let Some(days) = self.watering_days {
+            schedule.water = format!("Every {} days", days);
+        }
+        let mut plant = Plant::new(user_id.to_string(), self.name.clone(), schedule);
+        plant.set_nickname(self.nickname.as_deref());
+        plant.scientific_name = self.scientific_name.clone();
+        plant.room = self.room.clone();
+        plant
+    }
+}
+
+impl SourceApp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Planta => "Planta",
+            Self::Greg => "Greg",
+        }
+    }
+
+    /// Read the rows of a CSV export under their `headers`
+    pub fn read(&self, headers: &[String], rows: &[Vec<String>]) -> Result<Vec<ImportedPlant>> {
+        let headers: Vec<String> = headers.iter().map(|h| h.trim().to_lowercase()).collect();
+        let column = |names: Column| headers.iter().position(|h| names.contains(&h.as_str()));
+        let Some(name) = column(NAME) else {
+            anyhow::bail!(
+                "Not a {} export: it has no plant name column (found: {})",
+                self.as_str(),
+                headers.join(", ")
+            );
+        };
+
+        if *self == Self::Greg && (column(DATE).is_none() || column(EVENT).is_none()) {
+            anyhow::bail!(
+                "Not a Greg export: it needs a date and an event column (found: {})",
+                headers.join(", ")
+            );
+        }
+
+        let mut plants: Vec<ImportedPlant> = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            // The header is line 1
+            let line = index + 2;
+            let cell = |column: Option<usize>| {
+                column
+                    .and_then(|c| row.get(c))
+                    .map(|value| value.trim())
+                    .filter(|value| !value.is_empty())
+            };
+            let Some(plant_name) = cell(Some(name)) else { continue };
+            let species = cell(column(SPECIES)).map(str::to_string);
+
+            match self {
+                Self::Planta => {
+                    let mut plant = ImportedPlant {
+                        name: plant_name.to_string(),
+                        nickname: cell(column(NICKNAME)).map(str::to_string),
+                        scientific_name: species,
+                        room: cell(column(ROOM)).map(str::to_string),
+                        photo: cell(column(PHOTO)).map(str::to_string),
+                        watered: parse_dates(cell(column(WATERED)).unwrap_or_default())
+                            .with_context(|| format!("Line {}", line))?,
+                        fertilized: parse_dates(cell(column(FERTILIZED)).unwrap_or_default())
+                            .with_context(|| format!("Line {}", line))?,
+                        watering_days: cell(column(WATERING_DAYS)).and_then(leading_number),
+                        notes: cell(column(NOTES)).map(str::to_string),
+                    };
+                    plant.watered.sort();
+                    plant.fertilized.sort();
+                    plants.push(plant);
+                }
+                Self::Greg => {
+                    // Greg plants are named by their owners; the species
+                    // is the name we know them by
+                    let (plant_name, nickname) = match &species {
+                        Some(species) if !species.eq_ignore_ascii_case(plant_name) => {
+                            (species.clone(), Some(plant_name.to_string()))
+                        }
+                        _ => (plant_name.to_string(), None),
+                    };
+                    let index = match plants
+                        .iter()
+                        .position(|p| p.name == plant_name && p.nickname == nickname)
+                    {
+                        Some(index) => index,
+                        None => {
+                            plants.push(ImportedPlant {
+                                name: plant_name,
+                                nickname,
+                                scientific_name: species,
+                                ..Default::default()
+                            });
+                            plants.len() - 1
+                        }
+                    };
+                    let plant = &mut plants[index];
+                    plant.room = plant.room.take().or_else(|| cell(column(ROOM)).map(str::to_string));
+                    plant.photo = plant.photo.take().or_else(|| cell(column(PHOTO)).map(str::to_string));
+                    plant.notes = plant.notes.take().or_else(|| cell(column(NOTES)).map(str::to_string));
+
+                    let event = cell(column(EVENT)).unwrap_or_default().to_lowercase();
+                    let dates = if event.starts_with("water") {
+                        &mut plant.watered
+                    } else if event.starts_with("fertili") || event.starts_with("feed") {
+                        &mut plant.fertilized
+                    } else {
+                        continue;
+                    };
+                    let day = cell(column(DATE)).with_context(|| format!("Line {}: the {} has no date", line, event))?;
+                    dates.extend(parse_dates(day).with_context(|| format!("Line {}", line))?);
+                    dates.sort();
+                }
+            }
+        }
+
+        Ok(plants)
+    }
+}
+
+/// Dates separated by semicolons, commas or bars, each a day (2024-03-01)
+/// or a timestamp starting with one
+fn parse_dates(text: &str) -> Result<Vec<NaiveDate>> {
+    text.split([';', ',', '|'])
+        .map(str::trim)
+        .filter(|date| !date.is_empty())
+        .map(|date| {
+            date.get(..10)
+                .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+                .with_context(|| format!("Unreadable date '{}': expected YYYY-MM-DD", date))
+        })
+        .collect()
+}
+
+/// The number a text such as "7 days" starts with
+fn leading_number(text: &str) -> Option<u32> {
+    let digits: String = text.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok().filter(|days| *days > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv(text: &str) -> (Vec<String>, Vec<Vec<String>>) {
+        let mut lines = text.lines().map(|line| line.split(',').map(str::to_string).collect::<Vec<_>>());
+        (lines.next().unwrap(), lines.collect())
+    }
+
+    #[test]
+    fn test_read_planta_export() {
+        let (headers, rows) = csv(
+            "Name,Custom name,Latin name,Site,Image,Watered,Watering interval\n\
+             Monstera,Bob,Monstera deliciosa,Living room,photos/bob.jpg,2024-05-03;2024-04-26T08:00:00Z,7 days\n\
+             Fern,,,,,,",
+        );
+        let plants = SourceApp::Planta.read(&headers, &rows).unwrap();
+        assert_eq!(plants.len(), 2);
+        assert_eq!(plants[0].display_name(), "Bob");
+        assert_eq!(plants[0].watered[0], NaiveDate::from_ymd_opt(2024, 4, 26).unwrap());
+        assert_eq!(plants[0].to_plant("u").care_schedule.water, "Every 7 days");
+        assert_eq!(plants[1].scientific_name, None);
+
+        let mut existing = Plant::new("u".to_string(), "Monstera".to_string(), CareSchedule::default());
+        existing.set_nickname(Some("bob"));
+        assert!(plants[0].duplicate_of(std::slice::from_ref(&existing)).is_some());
+        existing.scientific_name = Some("Monstera adansonii".to_string());
+        assert!(plants[0].duplicate_of(&[existing]).is_none());
+
+        let (headers, rows) = csv("Name,Watered\nFern,last week");
+        let error = SourceApp::Planta.read(&headers, &rows).unwrap_err();
+        assert_eq!(format!("{:#}", error), "Line 2: Unreadable date 'last week': expected YYYY-MM-DD");
+        assert!(SourceApp::Planta.read(&["Title".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_read_greg_export() {
+        let (headers, rows) = csv(
+            "Plant,Species,Room,Date,Event\n\
+             Bob,Monstera deliciosa,Kitchen,2024-05-01,Watered\n\
+             Bob,Monstera deliciosa,Kitchen,2024-04-20,Watered\n\
+             Bob,Monstera deliciosa,Kitchen,2024-04-01,Fertilized\n\
+             Bob,Monstera deliciosa,Kitchen,2024-04-02,Repotted\n\
+             Spike,,,2024-05-02,Watered",
+        );
+        let plants = SourceApp::Greg.read(&headers, &rows).unwrap();
+        assert_eq!(plants.len(), 2);
+        assert_eq!(plants[0].name, "Monstera deliciosa");
+        assert_eq!(plants[0].nickname.as_deref(), Some("Bob"));
+        assert_eq!(plants[0].watered.len(), 2);
+        assert_eq!(plants[0].watered[1], NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+        assert_eq!(plants[0].fertilized.len(), 1);
+        assert_eq!(plants[1].display_name(), "Spike");
+
+        let (headers, rows) = csv("Name,Watered\nFern,2024-05-01");
+        assert!(SourceApp::Greg.read(&headers, &rows).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! AUDIT EVENT DOMAIN MODEL
+//!
+//! An append-only record of one state change (a plant created, a diagnosis
+//! moving on, a schedule edit...), with a snapshot of the record after the
+//! change. Replaying a plant's events shows what changed when.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::enums::{AuditAction, AuditEntity};
+
+/// Fields left out of change descriptions, since they change on every write
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+
+/// Longest value shown in a change description
+const MAX_VALUE_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: String,
+    pub entity: AuditEntity,
+    pub entity_id: String,
+    /// Plant the record belongs to, if any
+    pub plant_id: Option<String>,
+    pub action: AuditAction,
+    /// The record after the change (before it, for deletions)
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    pub fn new(
+        entity: AuditEntity,
+        entity_id: &str,
+        plant_id: Option<&str>,
+        action: AuditAction,
+        snapshot: &impl Serialize,
+    ) -> Result<Self> {
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            entity,
+            entity_id: entity_id.to_string(),
+            plant_id: plant_id.map(str::to_string),
+            action,
+            payload: serde_json::to_value(snapshot)?,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Describe what changed between two snapshots of the same record, one
+/// line per changed field (e.g. `care_schedule.water: "Weekly" → "Every 10 days"`)
+pub fn describe_changes(previous: &Value, current: &Value) -> Vec<String> {
+    let mut changes = Vec::new();
+    collect_changes("", previous, current, &mut changes);
+    changes
+}
+
+fn collect_changes(path: &str, previous: &Value, current: &Value, changes: &mut Vec<String>) {
+    if previous == current {
+        return;
+    }
+
+    match (previous, current) {
+        (Value::Object(before), Value::Object(after)) => {
+            let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                if IGNORED_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                collect_changes(
+                    &field,
+                    before.get(key).unwrap_or(&Value::Null),
+                    after.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (Value::Array(before), Value::Array(after)) => {
+            changes.push(format!("{}: {} → {} items", path, before.len(), after.len()));
+        }
+        _ => changes.push(format!("{}: {} → {}", path, short(previous), short(current))),
+    }
+}
+
+fn short(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() <= MAX_VALUE_CHARS {
+        return text;
+    }
+    let mut truncated: String = text.chars().take(MAX_VALUE_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_describe_changes_lists_changed_fields() {
+        let before = json!({
+            "name": "Monstera",
+            "care_schedule": {"water": "Weekly", "light": "Bright, indirect"},
+            "updated_at": "2024-01-01T00:00:00+00:00",
+        });
+        let after = json!({
+            "name": "Monstera",
+            "care_schedule": {"water": "Every 10 days", "light": "Bright, indirect"},
+            "updated_at": "2024-02-01T00:00:00+00:00",
+        });
+
+        assert_eq!(
+            describe_changes(&before, &after),
+            vec![r#"care_schedule.water: "Weekly" → "Every 10 days""#]
+        );
+    }
+
+    #[test]
+    fn test_describe_changes_summarizes_arrays() {
+        let before = json!({"status": "PENDING_USER_INPUT", "history": [1]});
+        let after = json!({"status": "COMPLETED", "history": [1, 2, 3]});
+
+        assert_eq!(
+            describe_changes(&before, &after),
+            vec![
+                "history: 1 → 3 items".to_string(),
+                r#"status: "PENDING_USER_INPUT" → "COMPLETED""#.to_string(),
+            ]
+        );
+    }
+}
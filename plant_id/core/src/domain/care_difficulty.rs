@@ -0,0 +1,154 @@
+//! CARE DIFFICULTY
+//!
+//! How demanding a plant is to keep, rated by the AI when the plant is
+//! identified, and the comparisons `list --difficulty` filters with
+//! (`<=2`, `>3`, `4`...).
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Easiest rating
+pub const MIN_DIFFICULTY: u8 = 1;
+
+/// Hardest rating
+pub const MAX_DIFFICULTY: u8 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CareDifficulty {
+    /// From 1 (very forgiving) to 5 (for experienced growers)
+    pub rating: u8,
+    /// The most common ways owners lose this plant
+    #[serde(default)]
+    pub failure_modes: Vec<String>,
+    /// Whether the plant suits someone new to houseplants
+    pub beginner_friendly: bool,
+}
+
+impl CareDifficulty {
+    /// Check an AI-provided rating, dropping blank failure modes
+    pub fn validated(mut self) -> Result<Self> {
+        if !(MIN_DIFFICULTY..=MAX_DIFFICULTY).contains(&self.rating) {
+            anyhow::bail!(
+                "Difficulty must be between {} and {}, got {}",
+                MIN_DIFFICULTY,
+                MAX_DIFFICULTY,
+                self.rating
+            );
+        }
+
+        self.failure_modes = self
+            .failure_modes
+            .into_iter()
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect();
+
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+/// A comparison against the difficulty rating, such as `<=2`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DifficultyFilter {
+    comparison: Comparison,
+    rating: u8,
+}
+
+impl DifficultyFilter {
+    /// Parse `<N`, `<=N`, `=N` (or just `N`), `>=N` or `>N`
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        let (comparison, number) = [
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::Equal),
+        ]
+        .into_iter()
+        .find_map(|(prefix, comparison)| {
+            expression.strip_prefix(prefix).map(|rest| (comparison, rest))
+        })
+        .unwrap_or((Comparison::Equal, expression));
+
+        let rating: u8 = number.trim().parse().map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid difficulty '{}': expected a rating like 2, <=2 or >3",
+                expression
+            )
+        })?;
+        if !(MIN_DIFFICULTY..=MAX_DIFFICULTY).contains(&rating) {
+            anyhow::bail!(
+                "Invalid difficulty '{}': ratings go from {} to {}",
+                expression,
+                MIN_DIFFICULTY,
+                MAX_DIFFICULTY
+            );
+        }
+
+        Ok(Self { comparison, rating })
+    }
+
+    /// Plants that were never rated do not match
+    pub fn matches(&self, difficulty: Option<&CareDifficulty>) -> bool {
+        let Some(difficulty) = difficulty else {
+            return false;
+        };
+
+        match self.comparison {
+            Comparison::Less => difficulty.rating < self.rating,
+            Comparison::LessOrEqual => difficulty.rating <= self.rating,
+            Comparison::Equal => difficulty.rating == self.rating,
+            Comparison::GreaterOrEqual => difficulty.rating >= self.rating,
+            Comparison::Greater => difficulty.rating > self.rating,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rated(rating: u8) -> CareDifficulty {
+        CareDifficulty {
+            rating,
+            failure_modes: Vec::new(),
+            beginner_friendly: rating <= 2,
+        }
+    }
+
+    #[test]
+    fn test_difficulty_filter() {
+        let easy = DifficultyFilter::parse("<=2").unwrap();
+        assert!(easy.matches(Some(&rated(1))));
+        assert!(easy.matches(Some(&rated(2))));
+        assert!(!easy.matches(Some(&rated(3))));
+        assert!(!easy.matches(None));
+
+        assert!(DifficultyFilter::parse(" > 3").unwrap().matches(Some(&rated(4))));
+        assert!(DifficultyFilter::parse("4").unwrap().matches(Some(&rated(4))));
+        assert!(!DifficultyFilter::parse("=4").unwrap().matches(Some(&rated(5))));
+
+        assert!(DifficultyFilter::parse("<=6").is_err());
+        assert!(DifficultyFilter::parse("easy").is_err());
+    }
+
+    #[test]
+    fn test_validated_rejects_out_of_range() {
+        assert!(rated(0).validated().is_err());
+
+        let mut difficulty = rated(3);
+        difficulty.failure_modes = vec![" Overwatering ".to_string(), " ".to_string()];
+        assert_eq!(difficulty.validated().unwrap().failure_modes, vec!["Overwatering"]);
+    }
+}
@@ -0,0 +1,206 @@
+//! CARE OVERRIDES
+//!
+//! A plant's care schedule is its species baseline, as generated by the AI
+//! or copied from a template, with the owner's own changes laid over it.
+//! The two are kept apart so that regenerating the baseline never undoes a
+//! manual tweak: the tweak is laid over the new baseline in turn. The
+//! plant's `care_schedule` is always the merged schedule.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{CareSchedule, CareScheduleUpdate, ScheduleSource};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CareOverrides {
+    pub plant_id: String,
+    /// The schedule as generated for the species
+    pub baseline: CareSchedule,
+    /// The owner's changes to it
+    pub overrides: CareScheduleUpdate,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One field of a merged schedule
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergedField {
+    pub label: &'static str,
+    pub value: String,
+    /// The species baseline's value, when the owner overrode it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<String>,
+}
+
+impl CareOverrides {
+    /// No overrides of `baseline`
+    pub fn new(plant_id: &str, baseline: CareSchedule) -> Self {
+        Self {
+            plant_id: plant_id.to_string(),
+            baseline,
+            overrides: CareScheduleUpdate::default(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// The baseline with the overrides applied
+    pub fn merged(&self) -> CareSchedule {
+        self.overrides.apply_to(&self.baseline)
+    }
+
+    /// Every field of the merged schedule, marked where it is overridden
+    pub fn fields(&self) -> Vec<MergedField> {
+        let merged = self.merged();
+        fields(&merged)
+            .into_iter()
+            .zip(fields(&self.baseline))
+            .zip(override_fields(&self.overrides))
+            .map(|(((label, value), (_, baseline)), overridden)| MergedField {
+                label,
+                value: value.clone(),
+                baseline: overridden.as_ref().map(|_| baseline.clone()),
+            })
+            .collect()
+    }
+
+    /// Take in a schedule saved from `source`, returning the merged
+    /// schedule to store. A new baseline (from the AI, a template or the
+    /// default) keeps the overrides over it. An edit (by hand or from a
+    /// diagnosis) overrides the fields that differ from the baseline, and
+    /// gives back those set to it. A schedule restored whole (a rollback or
+    /// a backup) is stored as it is: fields matching an override stay
+    /// overridden, and the rest become the baseline.
+    pub fn apply(&mut self, schedule: &CareSchedule, source: &ScheduleSource) -> CareSchedule {
+        let edited = matches!(source, ScheduleSource::Manual | ScheduleSource::Diagnosis);
+        let restored = matches!(source, ScheduleSource::Rollback { .. } | ScheduleSource::Imported);
+        let new = fields(schedule);
+        for ((baseline, overridden), (_, value)) in fields_mut(&mut self.baseline)
+            .into_iter()
+            .zip(override_fields_mut(&mut self.overrides))
+            .zip(new)
+        {
+            if edited {
+                *overridden = (value != baseline).then(|| value.clone());
+            } else if !(restored && overridden.as_ref() == Some(value)) {
+                *baseline = value.clone();
+                if restored {
+                    *overridden = None;
+                }
+            }
+        }
+        self.updated_at = Utc::now();
+        self.merged()
+    }
+}
+
+fn fields(schedule: &CareSchedule) -> [(&'static str, &String); 5] {
+    [
+        ("Light", &schedule.light),
+        ("Water", &schedule.water),
+        ("Humidity", &schedule.humidity),
+        ("Temperature", &schedule.temperature),
+        ("Care instructions", &schedule.care_instructions),
+    ]
+}
+
+fn fields_mut(schedule: &mut CareSchedule) -> [&mut String; 5] {
+    [
+        &mut schedule.light,
+        &mut schedule.water,
+        &mut schedule.humidity,
+        &mut schedule.temperature,
+        &mut schedule.care_instructions,
+    ]
+}
+
+fn override_fields(overrides: &CareScheduleUpdate) -> [&Option<String>; 5] {
+    [
+        &overrides.light,
+        &overrides.water,
+        &overrides.humidity,
+        &overrides.temperature,
+        &overrides.care_instructions,
+    ]
+}
+
+fn override_fields_mut(overrides: &mut CareScheduleUpdate) -> [&mut Option<String>; 5] {
+    [
+        &mut overrides.light,
+        &mut overrides.water,
+        &mut overrides.humidity,
+        &mut overrides.temperature,
+        &mut overrides.care_instructions,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ai() -> ScheduleSource {
+        ScheduleSource::Ai {
+            model: "model-a".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_regenerating_keeps_overrides() {
+        let mut care = CareOverrides::new("p", CareSchedule::default());
+        let edited = CareSchedule {
+            water: "Every 5 days".to_string(),
+            ..CareSchedule::default()
+        };
+        assert_eq!(care.apply(&edited, &ScheduleSource::Manual).water, "Every 5 days");
+
+        let regenerated = CareSchedule {
+            water: "Every 7 days".to_string(),
+            light: "Bright light".to_string(),
+            ..CareSchedule::default()
+        };
+        let merged = care.apply(&regenerated, &ai());
+        assert_eq!(merged.water, "Every 5 days");
+        assert_eq!(merged.light, "Bright light");
+
+        let fields = care.fields();
+        let marked: Vec<&MergedField> = fields.iter().filter(|f| f.baseline.is_some()).collect();
+        assert_eq!(marked.len(), 1);
+        assert_eq!(marked[0].label, "Water");
+        assert_eq!(marked[0].baseline.as_deref(), Some("Every 7 days"));
+
+        // Editing a field back to the baseline gives it back
+        assert_eq!(care.apply(&regenerated, &ScheduleSource::Manual).water, "Every 7 days");
+        assert!(care.is_empty());
+    }
+
+    #[test]
+    fn test_restoring_a_schedule() {
+        let mut care = CareOverrides::new("p", CareSchedule::default());
+        let edited = CareSchedule {
+            water: "Every 5 days".to_string(),
+            humidity: "High".to_string(),
+            ..CareSchedule::default()
+        };
+        care.apply(&edited, &ScheduleSource::Manual);
+
+        let restored = CareSchedule {
+            water: "Every 5 days".to_string(),
+            light: "Low light".to_string(),
+            ..CareSchedule::default()
+        };
+        let merged = care.apply(&restored, &ScheduleSource::Rollback { version: 1 });
+        assert_eq!(merged.water, restored.water);
+        assert_eq!(merged.light, restored.light);
+        assert_eq!(merged.humidity, restored.humidity);
+        assert_eq!(care.overrides.water.as_deref(), Some("Every 5 days"));
+        assert_eq!(care.baseline.water, CareSchedule::default().water);
+        assert_eq!(care.overrides.humidity, None);
+
+        // Resetting is editing back to the baseline
+        let baseline = care.baseline.clone();
+        assert_eq!(care.apply(&baseline, &ScheduleSource::Manual).water, CareSchedule::default().water);
+        assert!(care.is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CareSchedule {
+    pub light: String,
+    pub water: String,
+    pub humidity: String,
+    pub temperature: String,
+    pub care_instructions: String,
+}
+
+impl Default for CareSchedule {
+    fn default() -> Self {
+        Self {
+            light: "Bright, indirect sunlight".to_string(),
+            water: "Water when top inch of soil is dry".to_string(),
+            humidity: "Moderate humidity (40-60%)".to_string(),
+            temperature: "18-24°C (65-75°F)".to_string(),
+            care_instructions: String::new(),
+        }
+    }
+}
+
+impl CareSchedule {
+    /// Fields that differ in `new`, as (label, value here, value in `new`)
+    pub fn changes_to(&self, new: &CareSchedule) -> Vec<(&'static str, String, String)> {
+        CareScheduleUpdate::from(new).changes(self)
+    }
+}
+
+/// Proposed changes to a care schedule; fields left unset keep their value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CareScheduleUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub light: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub water: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub care_instructions: Option<String>,
+}
+
+impl CareScheduleUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.fields().iter().all(|(_, value)| value.is_none())
+    }
+
+    /// The schedule with this update applied
+    pub fn apply_to(&self, schedule: &CareSchedule) -> CareSchedule {
+        let pick = |update: &Option<String>, current: &String| {
+            update.clone().unwrap_or_else(|| current.clone())
+        };
+
+        CareSchedule {
+            light: pick(&self.light, &schedule.light),
+            water: pick(&self.water, &schedule.water),
+            humidity: pick(&self.humidity, &schedule.humidity),
+            temperature: pick(&self.temperature, &schedule.temperature),
+            care_instructions: pick(&self.care_instructions, &schedule.care_instructions),
+        }
+    }
+
+    /// Fields that would change, as (label, current value, proposed value)
+    pub fn changes(&self, schedule: &CareSchedule) -> Vec<(&'static str, String, String)> {
+        let current = [
+            &schedule.light,
+            &schedule.water,
+            &schedule.humidity,
+            &schedule.temperature,
+            &schedule.care_instructions,
+        ];
+
+        self.fields()
+            .into_iter()
+            .zip(current)
+            .filter_map(|((label, proposed), current)| {
+                let proposed = proposed.as_ref()?;
+                (proposed != current).then(|| (label, current.clone(), proposed.clone()))
+            })
+            .collect()
+    }
+
+    fn fields(&self) -> [(&'static str, &Option<String>); 5] {
+        [
+            ("Light", &self.light),
+            ("Water", &self.water),
+            ("Humidity", &self.humidity),
+            ("Temperature", &self.temperature),
+            ("Care instructions", &self.care_instructions),
+        ]
+    }
+}
+
+/// An update replacing every field of a schedule
+impl From<&CareSchedule> for CareScheduleUpdate {
+    fn from(schedule: &CareSchedule) -> Self {
+        Self {
+            light: Some(schedule.light.clone()),
+            water: Some(schedule.water.clone()),
+            humidity: Some(schedule.humidity.clone()),
+            temperature: Some(schedule.temperature.clone()),
+            care_instructions: Some(schedule.care_instructions.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_only_changes_given_fields() {
+        let schedule = CareSchedule::default();
+        let update = CareScheduleUpdate {
+            water: Some("Every 10 days".to_string()),
+            light: Some(schedule.light.clone()),
+            ..Default::default()
+        };
+
+        let updated = update.apply_to(&schedule);
+        assert_eq!(updated.water, "Every 10 days");
+        assert_eq!(updated.humidity, schedule.humidity);
+
+        let changes = update.changes(&schedule);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, "Water");
+    }
+
+    #[test]
+    fn test_empty_update() {
+        assert!(CareScheduleUpdate::default().is_empty());
+    }
+}
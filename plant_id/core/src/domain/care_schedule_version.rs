@@ -0,0 +1,215 @@
+//! CARE SCHEDULE VERSIONS
+//!
+//! Every time a plant's care schedule changes, a numbered version records
+//! the new schedule, what changed from the one it replaced, and where it
+//! came from: an AI model, a manual edit, a template... Versions let the
+//! owner roll a schedule back, and the latest one tells whether a schedule
+//! has gone stale and should be regenerated.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{CareSchedule, Plant};
+
+/// Where a version of a care schedule came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleSource {
+    /// Generated by an AI model
+    Ai { model: String },
+    /// Edited by hand
+    Manual,
+    /// Copied from a care schedule template
+    Template { name: String },
+    /// Changes proposed by a diagnosis and accepted by the owner
+    Diagnosis,
+    /// Restored from an earlier version
+    Rollback { version: u32 },
+    /// Imported from a backup or a shared plant profile, or saved before
+    /// versions were kept
+    Imported,
+    /// The default schedule, kept while the AI could not write one
+    Placeholder,
+}
+
+impl ScheduleSource {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Ai { model } => format!("generated by {}", model),
+            Self::Manual => "edited by hand".to_string(),
+            Self::Template { name } => format!("template '{}'", name),
+            Self::Diagnosis => "suggested by a diagnosis".to_string(),
+            Self::Rollback { version } => format!("rolled back to version {}", version),
+            Self::Imported => "imported".to_string(),
+            Self::Placeholder => "default, until the AI writes one".to_string(),
+        }
+    }
+}
+
+/// One field of a schedule that a version changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleChange {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CareScheduleVersion {
+    pub id: String,
+    pub plant_id: String,
+    /// Counts from 1 for each plant
+    pub version: u32,
+    pub schedule: CareSchedule,
+    /// Fields that differ from the previous version, empty for the first
+    pub changes: Vec<ScheduleChange>,
+    pub source: ScheduleSource,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CareScheduleVersion {
+    pub fn new(
+        plant_id: String,
+        version: u32,
+        previous: Option<&CareSchedule>,
+        schedule: CareSchedule,
+        source: ScheduleSource,
+    ) -> Self {
+        let changes = previous
+            .map(|previous| previous.changes_to(&schedule))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(field, old, new)| ScheduleChange {
+                field: field.to_string(),
+                old,
+                new,
+            })
+            .collect();
+
+        Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            version,
+            schedule,
+            changes,
+            source,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Why a plant's care schedule should be regenerated
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Staleness {
+    /// The schedule is older than the allowed age
+    Old { days: i64 },
+    /// The schedule was generated by another model than the current one
+    ModelChanged { model: String },
+    /// The plant still has the default schedule it was added with
+    Placeholder,
+}
+
+impl Staleness {
+    /// Whether `plant`'s schedule needs regenerating, given its latest
+    /// version. Without one, the schedule dates from when the plant was
+    /// added and its model is unknown, so only its age counts.
+    pub fn of(
+        plant: &Plant,
+        latest: Option<&CareScheduleVersion>,
+        max_age: Duration,
+        model: &str,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        match latest.map(|v| &v.source) {
+            Some(ScheduleSource::Ai { model: generated_by }) if generated_by != model => {
+                return Some(Self::ModelChanged {
+                    model: generated_by.clone(),
+                });
+            }
+            Some(ScheduleSource::Placeholder) => return Some(Self::Placeholder),
+            _ => {}
+        }
+
+        let changed_at = latest.map_or(plant.created_at, |v| v.created_at);
+        let age = now - changed_at;
+        (age > max_age).then(|| Self::Old { days: age.num_days() })
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Old { days } => format!("{} days old", days),
+            Self::ModelChanged { model } => format!("generated by {}", model),
+            Self::Placeholder => "default schedule".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ai(model: &str) -> ScheduleSource {
+        ScheduleSource::Ai {
+            model: model.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_version_records_changes() {
+        let previous = CareSchedule::default();
+        let schedule = CareSchedule {
+            water: "Every 10 days".to_string(),
+            ..CareSchedule::default()
+        };
+        let version = CareScheduleVersion::new("p".to_string(), 2, Some(&previous), schedule.clone(), ai("model-a"));
+
+        assert_eq!(
+            version.changes,
+            vec![ScheduleChange {
+                field: "Water".to_string(),
+                old: previous.water.clone(),
+                new: "Every 10 days".to_string(),
+            }]
+        );
+        let first = CareScheduleVersion::new("p".to_string(), 1, None, schedule, ScheduleSource::Manual);
+        assert!(first.changes.is_empty());
+    }
+
+    #[test]
+    fn test_staleness() {
+        let now = Utc::now();
+        let mut plant = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        let max_age = Duration::days(180);
+
+        plant.created_at = now - Duration::days(30);
+        assert_eq!(Staleness::of(&plant, None, max_age, "model-a", now), None);
+        plant.created_at = now - Duration::days(200);
+        assert_eq!(
+            Staleness::of(&plant, None, max_age, "model-a", now),
+            Some(Staleness::Old { days: 200 })
+        );
+
+        let mut version = CareScheduleVersion::new(plant.id.clone(), 1, None, CareSchedule::default(), ai("model-a"));
+        version.created_at = now - Duration::days(10);
+        assert_eq!(Staleness::of(&plant, Some(&version), max_age, "model-a", now), None);
+        assert_eq!(
+            Staleness::of(&plant, Some(&version), max_age, "model-b", now),
+            Some(Staleness::ModelChanged {
+                model: "model-a".to_string()
+            })
+        );
+
+        // A schedule edited by hand has no model to go out of date
+        version.source = ScheduleSource::Manual;
+        assert_eq!(Staleness::of(&plant, Some(&version), max_age, "model-b", now), None);
+
+        // The default schedule is never good enough, however new
+        version.source = ScheduleSource::Placeholder;
+        assert_eq!(
+            Staleness::of(&plant, Some(&version), max_age, "model-a", now),
+            Some(Staleness::Placeholder)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! CARE SHEET
+//!
+//! The care instructions of a room's plants, or of the whole collection, on
+//! one printout to stick on the fridge or hand to a sitter: each plant's
+//! light, watering, humidity and temperature, the owner's notes, and a
+//! warning when it is toxic to cats or dogs.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::toxicity::{self, Pet};
+use super::Plant;
+
+/// One plant's part of the sheet
+#[derive(Debug, Clone, Serialize)]
+pub struct CareSheetEntry {
+    pub plant_id: String,
+    pub name: String,
+    /// Botanical name, when it says more than the name
+    pub scientific_name: Option<String>,
+    pub room: Option<String>,
+    pub light: String,
+    pub water: String,
+    pub humidity: String,
+    pub temperature: String,
+    pub notes: Option<String>,
+    pub toxic_to: Vec<Pet>,
+}
+
+impl CareSheetEntry {
+    /// The care instructions, as (label, text), in the order they are printed
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("Light", self.light.clone()),
+            ("Water", self.water.clone()),
+            ("Humidity", self.humidity.clone()),
+            ("Temperature", self.temperature.clone()),
+        ];
+        if let Some(notes) = &self.notes {
+            fields.push(("Notes", notes.clone()));
+        }
+        if !self.toxic_to.is_empty() {
+            let pets: Vec<String> = self.toxic_to.iter().map(|pet| format!("{}s", pet.as_str())).collect();
+            fields.push(("Caution", format!("Toxic to {}; keep out of reach", pets.join(" and "))));
+        }
+        fields
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CareSheet {
+    /// Room the sheet is for, as the plants name it; None for the whole
+    /// collection
+    pub room: Option<String>,
+    pub printed_on: NaiveDate,
+    /// In alphabetical order
+    pub plants: Vec<CareSheetEntry>,
+}
+
+impl CareSheet {
+    /// Sheet of the `plants` given, which are expected to be in `room`
+    pub fn new(room: Option<&str>, plants: &[Plant], printed_on: NaiveDate) -> Self {
+        let mut entries: Vec<CareSheetEntry> = plants
+            .iter()
+            .map(|plant| {
+                let names = std::iter::once(plant.name.as_str())
+                    .chain(plant.scientific_name.as_deref())
+                    .chain(plant.common_names.iter().map(String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let schedule = &plant.care_schedule;
+                CareSheetEntry {
+                    plant_id: plant.id.clone(),
+                    name: plant.display_name().to_string(),
+                    scientific_name: plant
+                        .scientific_name
+                        .clone()
+                        .filter(|s| !s.eq_ignore_ascii_case(plant.display_name())),
+                    room: plant.room.clone(),
+                    light: schedule.light.clone(),
+                    water: schedule.water.clone(),
+                    humidity: schedule.humidity.clone(),
+                    temperature: schedule.temperature.clone(),
+                    notes: Some(schedule.care_instructions.trim().to_string()).filter(|n| !n.is_empty()),
+                    toxic_to: toxicity::toxic_to(&names).unwrap_or_default(),
+                }
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.name.to_lowercase());
+
+        // The room as the plants spell it, rather than as it was typed
+        let room = room.map(|room| {
+            plants
+                .iter()
+                .filter_map(|p| p.room.as_deref())
+                .find(|r| r.eq_ignore_ascii_case(room))
+                .unwrap_or(room)
+                .to_string()
+        });
+
+        Self {
+            room,
+            printed_on,
+            plants: entries,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match &self.room {
+            Some(room) => format!("Plant Care: {}", capitalize(room)),
+            None => "Plant Care".to_string(),
+        }
+    }
+
+    /// The line under the title: when the sheet was printed and how many
+    /// plants it covers
+    pub fn subtitle(&self) -> String {
+        format!(
+            "Printed {} \u{b7} {} plant{}",
+            self.printed_on.format("%b %-d, %Y"),
+            self.plants.len(),
+            if self.plants.len() == 1 { "" } else { "s" }
+        )
+    }
+
+    /// The line under a plant's name: its botanical name and, on a sheet of
+    /// the whole collection, its room
+    pub fn plant_subtitle(&self, entry: &CareSheetEntry) -> Option<String> {
+        let room = entry.room.as_deref().filter(|_| self.room.is_none());
+        let parts: Vec<&str> = entry.scientific_name.as_deref().into_iter().chain(room).collect();
+        (!parts.is_empty()).then(|| parts.join(" \u{b7} "))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n{}\n", self.title(), self.subtitle());
+        for entry in &self.plants {
+            markdown.push_str(&format!("\n## {}\n\n", entry.name));
+            if let Some(subtitle) = self.plant_subtitle(entry) {
+                markdown.push_str(&format!("{}\n\n", subtitle));
+            }
+            for (label, text) in entry.fields() {
+                markdown.push_str(&format!("- **{}:** {}\n", label, text));
+            }
+        }
+
+        markdown
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+
+    #[test]
+    fn test_care_sheet() {
+        let mut monstera = Plant::new("u".to_string(), "Monstera".to_string(), CareSchedule::default());
+        monstera.scientific_name = Some("Monstera deliciosa".to_string());
+        monstera.room = Some("Kitchen".to_string());
+        let mut basil = Plant::new("u".to_string(), "Basil".to_string(), CareSchedule::default());
+        basil.room = Some("kitchen".to_string());
+        basil.care_schedule.care_instructions = "Pinch off flowers".to_string();
+
+        let sheet = CareSheet::new(Some("KITCHEN"), &[monstera, basil], NaiveDate::from_ymd_opt(2026, 10, 15).unwrap());
+        assert_eq!(sheet.title(), "Plant Care: Kitchen");
+        assert_eq!(sheet.subtitle(), "Printed Oct 15, 2026 \u{b7} 2 plants");
+
+        let names: Vec<&str> = sheet.plants.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Basil", "Monstera"]);
+        assert_eq!(sheet.plant_subtitle(&sheet.plants[1]).as_deref(), Some("Monstera deliciosa"));
+        assert!(sheet.plants[0].fields().contains(&("Notes", "Pinch off flowers".to_string())));
+        assert_eq!(
+            sheet.plants[1].fields().last().unwrap().1,
+            "Toxic to cats and dogs; keep out of reach"
+        );
+
+        let markdown = sheet.to_markdown();
+        assert!(markdown.starts_with("# Plant Care: Kitchen\n"));
+        assert!(markdown.contains("\n## Basil\n\n- **Light:** Bright, indirect sunlight\n"));
+    }
+}
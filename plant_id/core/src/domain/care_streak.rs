@@ -0,0 +1,159 @@
+//! CARE STREAKS
+//!
+//! Runs of on-time waterings, read from the care task changes in the audit
+//! log. A watering is on time when it is done on or before the day it was
+//! due; a late one, or a plant overdue right now, ends the streak. Long
+//! streaks earn badges.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use super::enums::CareTaskKind;
+use super::{AuditEntity, AuditEvent, CareTask, LocalZone};
+
+/// Streak lengths that earn a badge: (waterings, emoji, name)
+const MILESTONES: &[(u32, &str, &str)] = &[
+    (3, "🌱", "Sprout"),
+    (7, "🌿", "Green Thumb"),
+    (14, "🪴", "Dedicated Gardener"),
+    (30, "🌳", "Plant Whisperer"),
+    (60, "🏆", "Master Gardener"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Badge {
+    /// On-time waterings in a row it takes
+    pub streak: u32,
+    pub emoji: &'static str,
+    pub name: &'static str,
+}
+
+impl Badge {
+    /// The best badge a streak of `streak` has earned
+    pub fn earned(streak: u32) -> Option<Self> {
+        MILESTONES
+            .iter()
+            .rev()
+            .find(|(length, _, _)| streak >= *length)
+            .map(|&(streak, emoji, name)| Self { streak, emoji, name })
+    }
+
+    /// The badge earned by reaching exactly `streak`, if it is a milestone
+    pub fn milestone(streak: u32) -> Option<Self> {
+        Self::earned(streak).filter(|badge| badge.streak == streak)
+    }
+
+    /// The next badge to work towards, if any is left
+    pub fn next(streak: u32) -> Option<Self> {
+        MILESTONES
+            .iter()
+            .find(|(length, _, _)| streak < *length)
+            .map(|&(streak, emoji, name)| Self { streak, emoji, name })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Streak {
+    /// On-time waterings in a row up to now
+    pub current: u32,
+    /// Longest run so far
+    pub best: u32,
+}
+
+impl Streak {
+    /// Streak of `waterings`, oldest first, each marked on time or late.
+    /// Something overdue now ends the current run.
+    pub fn of(waterings: &[Watering], overdue: bool) -> Self {
+        let mut streak = Self::default();
+        for watering in waterings {
+            streak.current = if watering.on_time { streak.current + 1 } else { 0 };
+            streak.best = streak.best.max(streak.current);
+        }
+        if overdue {
+            streak.current = 0;
+        }
+        streak
+    }
+
+    pub fn badge(&self) -> Option<Badge> {
+        Badge::earned(self.current)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watering {
+    pub at: DateTime<Utc>,
+    pub on_time: bool,
+}
+
+/// When a plant was watered, oldest first, according to its care task
+/// changes in the audit log
+pub fn watered_at(events: &[AuditEvent]) -> Vec<DateTime<Utc>> {
+    let mut times: Vec<DateTime<Utc>> = events
+        .iter()
+        .filter(|event| event.entity == AuditEntity::CareTask)
+        .filter_map(|event| serde_json::from_value::<CareTask>(event.payload.clone()).ok())
+        .filter(|task| task.kind == CareTaskKind::Water)
+        .filter_map(|task| task.last_done_at)
+        .collect();
+    times.sort();
+    times.dedup();
+    times
+}
+
+/// Mark each watering on time or late, given the days between waterings.
+/// The first is always on time, as nothing was due before it.
+pub fn mark_waterings(times: &[DateTime<Utc>], interval_days: i64, zone: &LocalZone) -> Vec<Watering> {
+    let mut previous: Option<NaiveDate> = None;
+    times
+        .iter()
+        .map(|&at| {
+            let day = zone.date(at);
+            let on_time = previous.is_none_or(|before| (day - before).num_days() <= interval_days);
+            previous = Some(day);
+            Watering { at, on_time }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AuditAction, CareSchedule, Plant};
+    use chrono::Duration;
+
+    #[test]
+    fn test_streak_of_waterings() {
+        let utc = LocalZone::utc();
+        let plant = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        let start = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(9, 0, 0).unwrap().and_utc();
+
+        // Every 7 days, one 9 days late, logged twice by a sync
+        let events: Vec<AuditEvent> = [0, 7, 14, 23, 29, 29, 36]
+            .iter()
+            .map(|days| {
+                let mut task = CareTask::new(&plant.id, CareTaskKind::Water);
+                task.last_done_at = Some(start + Duration::days(*days));
+                AuditEvent::new(AuditEntity::CareTask, &task.id, Some(&plant.id), AuditAction::Updated, &task).unwrap()
+            })
+            .collect();
+        let times = watered_at(&events);
+        assert_eq!(times.len(), 6);
+
+        let waterings = mark_waterings(&times, 7, &utc);
+        let late: Vec<bool> = waterings.iter().map(|w| !w.on_time).collect();
+        assert_eq!(late, vec![false, false, false, true, false, false]);
+
+        let streak = Streak::of(&waterings, false);
+        assert_eq!(streak, Streak { current: 2, best: 3 });
+        assert_eq!(streak.badge(), None);
+        assert_eq!(Streak::of(&waterings, true).current, 0);
+
+        assert_eq!(Badge::earned(3).unwrap().name, "Sprout");
+        assert_eq!(Badge::earned(9).unwrap().name, "Green Thumb");
+        assert_eq!(Badge::milestone(7).unwrap().name, "Green Thumb");
+        assert!(Badge::milestone(8).is_none());
+        assert_eq!(Badge::next(8).unwrap().streak, 14);
+        assert!(Badge::next(60).is_none());
+    }
+}
@@ -0,0 +1,205 @@
+//! CARE TASK DOMAIN MODEL
+//!
+//! Recurring care (watering, fertilizing) of one plant. How often a task
+//! recurs is read from the plant's care schedule, so editing the schedule
+//! reschedules the task; only when it was last done is stored.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::enums::CareTaskKind;
+use super::{CareSchedule, LocalZone};
+
+/// Interval used when the schedule does not say how often to water
+pub const DEFAULT_WATERING_DAYS: i64 = 7;
+
+/// Interval used when the schedule does not say how often to fertilize
+pub const DEFAULT_FERTILIZING_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CareTask {
+    pub id: String,
+    pub plant_id: String,
+    pub kind: CareTaskKind,
+    pub last_done_at: Option<DateTime<Utc>>,
+}
+
+impl CareTask {
+    /// A task that has never been done. The ID is derived from the plant and
+    /// kind, so every device agrees on it.
+    pub fn new(plant_id: &str, kind: CareTaskKind) -> Self {
+        let name = format!("{}:{}", plant_id, kind.as_str());
+        Self {
+            id: Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string(),
+            plant_id: plant_id.to_string(),
+            kind,
+            last_done_at: None,
+        }
+    }
+
+    /// Days between two occurrences, according to the care schedule
+    pub fn interval_days(&self, schedule: &CareSchedule) -> i64 {
+        match self.kind {
+            CareTaskKind::Water => {
+                interval_days(&schedule.water).unwrap_or(DEFAULT_WATERING_DAYS)
+            }
+            CareTaskKind::Fertilize => fertilizing_text(&schedule.care_instructions)
+                .and_then(interval_days)
+                .unwrap_or(DEFAULT_FERTILIZING_DAYS),
+        }
+    }
+
+    /// Day the task is next due, counting calendar days from the day it
+    /// was last done in `zone`; a task never done is due on `today`
+    pub fn due_date(&self, schedule: &CareSchedule, today: NaiveDate, zone: &LocalZone) -> NaiveDate {
+        match self.last_done_at {
+            Some(done) => zone.date(done) + Duration::days(self.interval_days(schedule)),
+            None => today,
+        }
+    }
+}
+
+/// Read a care frequency such as "every 7-10 days", "weekly" or "twice a
+/// week" as a number of days. Ranges use their lower bound, so care is
+/// never late. An explicit "every <n> <unit>" wins over phrases elsewhere in
+/// the text, so "every 2 weeks, check soil daily" is 14 days.
+pub fn interval_days(text: &str) -> Option<i64> {
+    let text = text.to_lowercase();
+
+    // "every 5 days", "every 1-2 weeks", "every 2 to 3 weeks"
+    let words: Vec<&str> = text.split_whitespace().collect();
+    for (i, word) in words.iter().enumerate() {
+        if *word != "every" {
+            continue;
+        }
+        let Some(amount) = words
+            .get(i + 1)
+            .and_then(|n| n.split(['-', '–']).next())
+            .and_then(|n| n.parse::<i64>().ok())
+        else {
+            continue;
+        };
+        let unit = words[i + 2..]
+            .iter()
+            .take(3)
+            .find_map(|w| unit_days(w.trim_matches(|c: char| !c.is_alphabetic())));
+        if let Some(unit) = unit {
+            return Some(amount * unit);
+        }
+    }
+
+    let fixed = [
+        ("every other day", 2),
+        ("every other week", 14),
+        ("twice a week", 3),
+        ("twice weekly", 3),
+        ("twice a month", 14),
+        ("biweekly", 14),
+        ("fortnight", 14),
+        ("daily", 1),
+        ("every day", 1),
+        ("weekly", 7),
+        ("once a week", 7),
+        ("every week", 7),
+        ("monthly", 30),
+        ("once a month", 30),
+        ("every month", 30),
+    ];
+    fixed
+        .into_iter()
+        .find(|(phrase, _)| text.contains(phrase))
+        .map(|(_, days)| days)
+}
+
+fn unit_days(word: &str) -> Option<i64> {
+    match word {
+        "day" | "days" => Some(1),
+        "week" | "weeks" => Some(7),
+        "month" | "months" => Some(30),
+        _ => None,
+    }
+}
+
+/// The sentence of the care instructions about fertilizing, if any
+fn fertilizing_text(instructions: &str) -> Option<&str> {
+    instructions.split(['.', ';', '\n']).find(|sentence| {
+        let sentence = sentence.to_lowercase();
+        sentence.contains("fertiliz") || sentence.contains("fertilis") || sentence.contains("feed")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_days_reads_common_frequencies() {
+        assert_eq!(interval_days("Water every 7-10 days"), Some(7));
+        assert_eq!(interval_days("Water every 2 to 3 weeks in winter"), Some(14));
+        assert_eq!(interval_days("Water weekly in summer"), Some(7));
+        assert_eq!(interval_days("Mist twice a week"), Some(3));
+        assert_eq!(interval_days("Water every 10 days; mist daily"), Some(10));
+        assert_eq!(interval_days("Water every 2 weeks, check soil daily"), Some(14));
+        assert_eq!(interval_days("Water every other week"), Some(14));
+        assert_eq!(interval_days("Water when top inch of soil is dry"), None);
+    }
+
+    #[test]
+    fn test_fertilizing_interval_comes_from_care_instructions() {
+        let schedule = CareSchedule {
+            care_instructions: "Rotate monthly. Fertilize every 2 weeks in spring.".to_string(),
+            ..CareSchedule::default()
+        };
+        let task = CareTask::new("p1", CareTaskKind::Fertilize);
+        assert_eq!(task.interval_days(&schedule), 14);
+
+        let plain = CareSchedule::default();
+        assert_eq!(task.interval_days(&plain), DEFAULT_FERTILIZING_DAYS);
+    }
+
+    #[test]
+    fn test_due_date_follows_last_done() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let mut task = CareTask::new("p1", CareTaskKind::Water);
+        let utc = LocalZone::utc();
+        assert_eq!(task.due_date(&CareSchedule::default(), today, &utc), today);
+
+        task.last_done_at = Some(
+            NaiveDate::from_ymd_opt(2024, 5, 1)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap()
+                .and_utc(),
+        );
+        assert_eq!(
+            task.due_date(&CareSchedule::default(), today, &utc),
+            NaiveDate::from_ymd_opt(2024, 5, 8).unwrap()
+        );
+        assert_eq!(task.id, CareTask::new("p1", CareTaskKind::Water).id);
+    }
+
+    #[test]
+    fn test_due_date_counts_local_days() {
+        let auckland = LocalZone::named("Pacific/Auckland").unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 4, 10).unwrap();
+        let mut task = CareTask::new("p1", CareTaskKind::Water);
+        // Watered at 9 in the morning of April 1st in Auckland, still March
+        // 31st in UTC; daylight saving ends there on April 7th
+        task.last_done_at = Some(
+            NaiveDate::from_ymd_opt(2024, 3, 31)
+                .unwrap()
+                .and_hms_opt(20, 0, 0)
+                .unwrap()
+                .and_utc(),
+        );
+        assert_eq!(
+            task.due_date(&CareSchedule::default(), today, &auckland),
+            NaiveDate::from_ymd_opt(2024, 4, 8).unwrap()
+        );
+        assert_eq!(
+            task.due_date(&CareSchedule::default(), today, &LocalZone::utc()),
+            NaiveDate::from_ymd_opt(2024, 4, 7).unwrap()
+        );
+    }
+}
@@ -1,32 +1,35 @@
+//! CARE TEMPLATE DOMAIN MODEL
+//!
+//! A named care schedule for a group of plants ("succulent default",
+//! "tropical aroid"), applied to new plants instead of generating one.
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::care_schedule::CareSchedule;
+use super::CareSchedule;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Plant {
+pub struct CareTemplate {
     pub id: String,
     pub user_id: String,
+    /// Unique per user, compared case-insensitively
     pub name: String,
-    pub care_schedule: CareSchedule,
-    pub image_url: Option<String>,
+    pub schedule: CareSchedule,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-impl Plant {
-    pub fn new(user_id: String, name: String, care_schedule: CareSchedule) -> Self {
+impl CareTemplate {
+    pub fn new(user_id: String, name: String, schedule: CareSchedule) -> Self {
         let now = Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
             user_id,
             name,
-            care_schedule,
-            image_url: None,
+            schedule,
             created_at: now,
             updated_at: now,
         }
     }
 }
-
@@ -0,0 +1,51 @@
+//! CHAT SESSION DOMAIN MODEL
+//!
+//! Represents an open-ended conversation about a plant. Unlike a diagnosis
+//! session, a chat does not follow the structured action protocol; it is a
+//! plain exchange of messages grounded in the plant's records.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A single message exchanged in a chat session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Represents a free-form conversation about a plant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatSession {
+    pub id: String,
+    pub plant_id: String,
+    pub messages: Vec<ChatMessage>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ChatSession {
+    pub fn new(plant_id: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            messages: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Append a message to the conversation
+    pub fn push_message(&mut self, role: &str, content: String) {
+        let now = Utc::now();
+        self.messages.push(ChatMessage {
+            role: role.to_string(),
+            content,
+            created_at: now,
+        });
+        self.updated_at = now;
+    }
+}
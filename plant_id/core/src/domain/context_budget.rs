@@ -0,0 +1,137 @@
+/*!
+ * DIAGNOSIS CONTEXT BUDGET
+ *
+ * The diagnosis context is sent to the AI on every cycle, together with the
+ * conversation turns not yet summarized, so it must stay bounded. Once it
+ * grows past a token threshold, older turns and logged state are split off
+ * to be condensed into a `summary` block, while the most recent turns are
+ * kept verbatim. The turns themselves stay stored; the context only counts
+ * how many of them the summary covers.
+ */
+
+use serde_json::{json, Value as JsonValue};
+
+/// Estimated size above which the context is summarized
+pub const CONTEXT_TOKEN_THRESHOLD: usize = 3000;
+
+/// Number of most recent conversation turns kept verbatim
+pub const KEEP_RECENT_TURNS: usize = 4;
+
+/// Rough token estimate for a JSON value (about four characters per token)
+pub fn estimate_tokens(value: &JsonValue) -> usize {
+    value.to_string().len().div_ceil(4)
+}
+
+/// Number of conversation turns already folded into the summary
+pub fn summarized_turns(context: &JsonValue) -> usize {
+    context["summarized_turns"].as_u64().unwrap_or(0) as usize
+}
+
+/// The context as sent to the AI, with the turns after the summary and
+/// without bookkeeping such as the token usage
+pub fn with_history(context: &JsonValue, history: &[JsonValue]) -> JsonValue {
+    let mut context = context.clone();
+    if let Some(object) = context.as_object_mut() {
+        object.remove("summarized_turns");
+        object.remove("usage");
+        object.insert("conversation_history".to_string(), json!(history));
+    }
+    context
+}
+
+/// The part of a context that should be folded into the summary
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactionInput {
+    pub previous_summary: Option<String>,
+    pub old_turns: Vec<JsonValue>,
+    pub state: JsonValue,
+}
+
+/// Decide whether a context and the turns after its summary need
+/// compacting, and if so return the turns and state to summarize. Returns
+/// `None` while the context is within budget or there is nothing old enough
+/// to compress.
+pub fn compaction_input(
+    context: &JsonValue,
+    history: &[JsonValue],
+    threshold: usize,
+) -> Option<CompactionInput> {
+    if estimate_tokens(&with_history(context, history)) <= threshold {
+        return None;
+    }
+
+    let state = context["state"].clone();
+    let state_is_empty = state.as_object().is_none_or(|s| s.is_empty());
+
+    if history.len() <= KEEP_RECENT_TURNS && state_is_empty {
+        return None;
+    }
+
+    let split = history.len().saturating_sub(KEEP_RECENT_TURNS);
+    Some(CompactionInput {
+        previous_summary: context["summary"].as_str().map(str::to_string),
+        old_turns: history[..split].to_vec(),
+        state,
+    })
+}
+
+/// Record the new summary in place of the summarized turns and state
+pub fn apply_summary(context: &mut JsonValue, summarized: usize, summary: String) {
+    let total = summarized_turns(context) + summarized;
+    let Some(context) = context.as_object_mut() else {
+        return;
+    };
+
+    context.insert("summarized_turns".to_string(), json!(total));
+    context.insert("state".to_string(), json!({}));
+    context.insert("summary".to_string(), JsonValue::String(summary));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turns(count: usize) -> Vec<JsonValue> {
+        (0..count)
+            .map(|i| json!({"role": "user", "message": format!("turn {} {}", i, "x".repeat(200))}))
+            .collect()
+    }
+
+    fn context() -> JsonValue {
+        json!({
+            "initial_prompt": "yellow leaves",
+            "state": {"hypothesis": "overwatering"},
+            "plant_vitals": null
+        })
+    }
+
+    #[test]
+    fn test_small_context_is_left_alone() {
+        assert_eq!(compaction_input(&context(), &turns(3), CONTEXT_TOKEN_THRESHOLD), None);
+    }
+
+    #[test]
+    fn test_large_context_keeps_recent_turns() {
+        let mut context = context();
+        let history = turns(10);
+        let input = compaction_input(&context, &history, 100).unwrap();
+        assert_eq!(input.old_turns.len(), 10 - KEEP_RECENT_TURNS);
+        assert_eq!(input.state["hypothesis"], "overwatering");
+
+        apply_summary(&mut context, input.old_turns.len(), "summary".to_string());
+        assert_eq!(summarized_turns(&context), 10 - KEEP_RECENT_TURNS);
+        assert_eq!(context["state"], json!({}));
+        assert_eq!(context["summary"], "summary");
+
+        let remaining = &history[summarized_turns(&context)..];
+        assert_eq!(remaining.len(), KEEP_RECENT_TURNS);
+        assert!(remaining[0]["message"].as_str().unwrap().starts_with("turn 6"));
+
+        let sent = with_history(&context, remaining);
+        assert_eq!(sent["conversation_history"].as_array().unwrap().len(), KEEP_RECENT_TURNS);
+        assert!(sent.get("summarized_turns").is_none());
+
+        apply_summary(&mut context, 2, "again".to_string());
+        assert_eq!(summarized_turns(&context), 10 - KEEP_RECENT_TURNS + 2);
+    }
+}
@@ -0,0 +1,170 @@
+//! DIAGNOSIS BATCH
+//!
+//! Pests spread, so the same symptom on several plants is diagnosed as one
+//! batch: a linked session per plant, each seeing the shared problem, the
+//! other plants and what was found on them so far. Once the plants are
+//! diagnosed, the findings are combined into a single containment plan.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::domain::{Plant, Severity};
+
+/// A plant in a batch and the conclusion of its session, once it has one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchPlant {
+    pub plant_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub room: Option<String>,
+    /// The linked session, once started
+    #[serde(default)]
+    pub diagnosis_id: Option<String>,
+    #[serde(default)]
+    pub finding: Option<String>,
+    #[serde(default)]
+    pub severity: Option<Severity>,
+}
+
+/// How to stop a shared problem from spreading, worked out from the
+/// findings of every plant in the batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainmentPlan {
+    /// The cause the plants most likely share
+    pub suspected_cause: String,
+    /// Names of the plants to keep apart from the rest of the collection
+    #[serde(default)]
+    pub isolate: Vec<String>,
+    /// What to do, in order
+    #[serde(default)]
+    pub steps: Vec<String>,
+    /// Where else to look, e.g. healthy plants in the same rooms
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+/// Linked diagnosis sessions for plants showing the same problem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisBatch {
+    pub id: String,
+    /// The symptom the owner saw on every plant
+    pub problem: String,
+    pub plants: Vec<BatchPlant>,
+    #[serde(default)]
+    pub containment: Option<ContainmentPlan>,
+}
+
+impl DiagnosisBatch {
+    pub fn new(problem: String, plants: &[Plant]) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            problem,
+            plants: plants
+                .iter()
+                .map(|plant| BatchPlant {
+                    plant_id: plant.id.clone(),
+                    name: plant.display_name().to_string(),
+                    room: plant.room.clone(),
+                    diagnosis_id: None,
+                    finding: None,
+                    severity: None,
+                })
+                .collect(),
+            containment: None,
+        }
+    }
+
+    pub fn plant(&self, plant_id: &str) -> Option<&BatchPlant> {
+        self.plants.iter().find(|p| p.plant_id == plant_id)
+    }
+
+    fn plant_mut(&mut self, plant_id: &str) -> Option<&mut BatchPlant> {
+        self.plants.iter_mut().find(|p| p.plant_id == plant_id)
+    }
+
+    /// Link a plant's session to the batch
+    pub fn attach(&mut self, plant_id: &str, diagnosis_id: String) {
+        if let Some(plant) = self.plant_mut(plant_id) {
+            plant.diagnosis_id = Some(diagnosis_id);
+        }
+    }
+
+    /// Note the conclusion of a plant's session
+    pub fn record_finding(&mut self, plant_id: &str, finding: String, severity: Option<Severity>) {
+        if let Some(plant) = self.plant_mut(plant_id) {
+            plant.finding = Some(finding);
+            plant.severity = severity;
+        }
+    }
+
+    /// Plants whose sessions have concluded
+    pub fn diagnosed(&self) -> impl Iterator<Item = &BatchPlant> {
+        self.plants.iter().filter(|p| p.finding.is_some())
+    }
+
+    /// The shared context given to the session of one plant: the problem,
+    /// the other plants and what their sessions found so far
+    pub fn shared_context(&self, plant_id: &str) -> Value {
+        let others: Vec<Value> = self
+            .plants
+            .iter()
+            .filter(|p| p.plant_id != plant_id)
+            .map(|p| {
+                json!({
+                    "name": p.name,
+                    "room": p.room,
+                    "finding": p.finding,
+                    "severity": p.severity
+                })
+            })
+            .collect();
+
+        json!({
+            "id": self.id,
+            "problem": self.problem,
+            "other_plants": others
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+
+    fn plant(name: &str, room: Option<&str>) -> Plant {
+        let mut plant = Plant::new("u".to_string(), name.to_string(), CareSchedule::default());
+        plant.room = room.map(str::to_string);
+        plant
+    }
+
+    #[test]
+    fn test_sessions_see_the_other_plants_findings() {
+        let plants = [
+            plant("Calathea", Some("Living room")),
+            plant("Fern", Some("Living room")),
+            plant("Ivy", None),
+        ];
+        let mut batch = DiagnosisBatch::new("Webbing on leaves".to_string(), &plants);
+        assert_eq!(batch.diagnosed().count(), 0);
+
+        batch.attach(&plants[0].id, "d1".to_string());
+        batch.record_finding(&plants[0].id, "Spider mites".to_string(), Some(Severity::Serious));
+        assert_eq!(batch.plant(&plants[0].id).unwrap().diagnosis_id.as_deref(), Some("d1"));
+        assert_eq!(batch.diagnosed().count(), 1);
+
+        let context = batch.shared_context(&plants[1].id);
+        assert_eq!(context["problem"], "Webbing on leaves");
+        let others = context["other_plants"].as_array().unwrap();
+        assert_eq!(others.len(), 2);
+        assert_eq!(others[0]["name"], "Calathea");
+        assert_eq!(others[0]["finding"], "Spider mites");
+        assert_eq!(others[0]["severity"], "serious");
+        assert!(others[1]["finding"].is_null());
+
+        // Findings for plants outside the batch are ignored
+        batch.record_finding("elsewhere", "Thrips".to_string(), None);
+        assert_eq!(batch.diagnosed().count(), 1);
+    }
+}
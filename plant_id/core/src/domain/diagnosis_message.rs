@@ -0,0 +1,41 @@
+//! DIAGNOSIS MESSAGE DOMAIN MODEL
+//!
+//! One turn of a diagnosis conversation: the owner's description and
+//! answers, and the questions the AI asked. Turns are stored apart from the
+//! session context so a long conversation can be paged through without
+//! rewriting the whole session on every cycle.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisMessage {
+    pub id: String,
+    pub session_id: String,
+    /// "user" or "assistant"
+    pub role: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DiagnosisMessage {
+    pub fn new(session_id: String, role: &str, content: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            session_id,
+            role: role.to_string(),
+            content,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// The turn as the diagnostic kernel sees it in `conversation_history`
+    pub fn to_turn(&self) -> Value {
+        json!({
+            "role": self.role,
+            "message": self.content
+        })
+    }
+}
@@ -0,0 +1,458 @@
+//! DIAGNOSIS SESSION DOMAIN MODEL
+//!
+//! Represents a diagnostic conversation session between the user and AI.
+//! Contains the full context needed for the diagnostic kernel to operate;
+//! the conversation itself is kept as separate `DiagnosisMessage` turns.
+//!
+//! A session moves through `DiagnosisState`s:
+//!
+//! ```text
+//! Created → GatheringVitals → Questioning(1) → Questioning(2) → ...
+//!                  │                  │
+//!                  └──────────────────┴──→ Concluded
+//! ```
+//!
+//! and can be cancelled from any state before it concludes. Any other
+//! transition is refused, so e.g. a concluded session cannot be answered.
+//!
+//! A session can be branched at one of the owner's answers: the new session
+//! starts over from the question before it, to explore a different answer,
+//! and the original is left as it was.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::domain::enums::DiagnosisStatus;
+use crate::domain::DiagnosisMessage;
+
+/// Conclusions below this confidence are flagged to the user as uncertain
+pub const LOW_CONFIDENCE_THRESHOLD: f64 = 0.6;
+
+/// Where a diagnosis is in its conversation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum DiagnosisState {
+    /// Saved with the owner's problem description
+    Created,
+    /// The plant's vitals are in the context; the AI has not asked anything yet
+    GatheringVitals,
+    /// Waiting for the answer to the AI's `n`th question or photo request
+    Questioning(u32),
+    Concluded,
+    Cancelled,
+}
+
+impl DiagnosisState {
+    /// The coarse status shown in listings and used to filter sessions
+    pub fn status(&self) -> DiagnosisStatus {
+        match self {
+            Self::Created | Self::GatheringVitals | Self::Questioning(_) => {
+                DiagnosisStatus::PendingUserInput
+            }
+            Self::Concluded => DiagnosisStatus::Completed,
+            Self::Cancelled => DiagnosisStatus::Cancelled,
+        }
+    }
+
+    /// Whether the session can no longer change
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Concluded | Self::Cancelled)
+    }
+
+    /// Whether a session in this state may move to `next`
+    pub fn can_become(&self, next: DiagnosisState) -> bool {
+        match (*self, next) {
+            (Self::Created, Self::GatheringVitals) => true,
+            (Self::GatheringVitals, Self::Questioning(1)) => true,
+            (Self::Questioning(n), Self::Questioning(m)) => m == n + 1,
+            (Self::GatheringVitals | Self::Questioning(_), Self::Concluded) => true,
+            (from, Self::Cancelled) => !from.is_final(),
+            _ => false,
+        }
+    }
+
+    /// Parse the stored form. The statuses stored before sessions had a
+    /// state are accepted too.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "CREATED" => Some(Self::Created),
+            "GATHERING_VITALS" => Some(Self::GatheringVitals),
+            "CONCLUDED" | "COMPLETED" => Some(Self::Concluded),
+            "CANCELLED" => Some(Self::Cancelled),
+            // Legacy sessions were waiting for an answer to some question
+            "PENDING_USER_INPUT" => Some(Self::Questioning(1)),
+            _ => s
+                .strip_prefix("QUESTIONING:")
+                .and_then(|n| n.parse().ok())
+                .filter(|n| *n > 0)
+                .map(Self::Questioning),
+        }
+    }
+}
+
+impl fmt::Display for DiagnosisState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Created => write!(f, "CREATED"),
+            Self::GatheringVitals => write!(f, "GATHERING_VITALS"),
+            Self::Questioning(n) => write!(f, "QUESTIONING:{}", n),
+            Self::Concluded => write!(f, "CONCLUDED"),
+            Self::Cancelled => write!(f, "CANCELLED"),
+        }
+    }
+}
+
+impl From<DiagnosisState> for String {
+    fn from(state: DiagnosisState) -> Self {
+        state.to_string()
+    }
+}
+
+impl TryFrom<String> for DiagnosisState {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s).ok_or_else(|| format!("Invalid diagnosis state '{}'", s))
+    }
+}
+
+/// Context entries worked out over the conversation, which a branch leaves
+/// behind to work out again from its own turns
+const CONVERSATION_ENTRIES: &[&str] = &[
+    "state",
+    "summary",
+    "summarized_turns",
+    "search_results",
+    "pending_photo_request",
+    "care_update",
+    "result",
+    "usage",
+];
+
+/// Represents an ongoing or completed diagnosis session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisSession {
+    pub id: String,
+    pub plant_id: String,
+    /// Sessions recorded before states existed only have a `status`
+    #[serde(alias = "status")]
+    pub state: DiagnosisState,
+    pub diagnosis_context: Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The parts of a session shown in listings, without the full context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisSummary {
+    pub id: String,
+    pub plant_id: String,
+    pub status: DiagnosisStatus,
+    /// The problem the owner described
+    pub problem: String,
+    /// The conclusion, once the diagnosis is complete
+    pub result: Option<Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Conditions on the sessions listed by `history`, all of which must match
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosisFilter {
+    pub status: Option<DiagnosisStatus>,
+    /// Only sessions started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Text the finding of the conclusion contains, in any case
+    pub finding: Option<String>,
+}
+
+impl DiagnosisFilter {
+    /// Whether the filter has no conditions (and so matches every session)
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.since.is_none() && self.finding.is_none()
+    }
+}
+
+impl DiagnosisSession {
+    pub fn new(plant_id: String, initial_prompt: String) -> Self {
+        let now = Utc::now();
+        let context = serde_json::json!({
+            "initial_prompt": initial_prompt,
+            "state": {},
+            "plant_vitals": null
+        });
+
+        Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            state: DiagnosisState::Created,
+            diagnosis_context: context,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn status(&self) -> DiagnosisStatus {
+        self.state.status()
+    }
+
+    /// The plant's vitals were added to the context
+    pub fn gather_vitals(&mut self) -> Result<()> {
+        self.transition(DiagnosisState::GatheringVitals)
+    }
+
+    /// The AI asked the owner a question or for a photo
+    pub fn ask(&mut self) -> Result<()> {
+        let next = match self.state {
+            DiagnosisState::Questioning(n) => DiagnosisState::Questioning(n + 1),
+            _ => DiagnosisState::Questioning(1),
+        };
+        self.transition(next)
+    }
+
+    pub fn conclude(&mut self) -> Result<()> {
+        self.transition(DiagnosisState::Concluded)
+    }
+
+    pub fn cancel(&mut self) -> Result<()> {
+        self.transition(DiagnosisState::Cancelled)
+    }
+
+    /// Check that the session is waiting for the owner's answer
+    pub fn expect_answer(&self) -> Result<()> {
+        match self.state {
+            DiagnosisState::Questioning(_) => Ok(()),
+            state if state.is_final() => {
+                anyhow::bail!("Cannot update a completed or cancelled diagnosis")
+            }
+            _ => anyhow::bail!("The diagnosis has not asked anything yet"),
+        }
+    }
+
+    /// A new session with the turns before turn `at` (numbered from 1) of
+    /// `turns`, this session's conversation, waiting for a different answer
+    /// than the owner's turn `at` to the AI's question before it. Photos
+    /// attached from that answer on are left behind, as is what was worked
+    /// out over the conversation; the new session records where it came from
+    /// under `branched_from`.
+    pub fn branch(&self, turns: &[DiagnosisMessage], at: usize) -> Result<(Self, Vec<DiagnosisMessage>)> {
+        let answer = match at.checked_sub(1).and_then(|i| turns.get(i)) {
+            Some(answer) => answer,
+            None => anyhow::bail!("The session has {} turns; there is no turn {}", turns.len(), at),
+        };
+        if at == 1 {
+            anyhow::bail!("Turn 1 is the problem description; start a new diagnosis to describe it differently");
+        }
+        if answer.role != "user" || turns[at - 2].role != "assistant" {
+            anyhow::bail!("Turn {} is not an answer to a question of the AI", at);
+        }
+
+        let mut context = self.diagnosis_context.clone();
+        if let Some(entries) = context.as_object_mut() {
+            for entry in CONVERSATION_ENTRIES {
+                entries.remove(*entry);
+            }
+            entries.insert("state".to_string(), serde_json::json!({}));
+            if let Some(photos) = entries.get_mut("photos").and_then(Value::as_array_mut) {
+                photos.retain(|photo| {
+                    photo["attached_at"]
+                        .as_str()
+                        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                        .is_some_and(|attached| attached < answer.created_at)
+                });
+            }
+            entries.insert(
+                "branched_from".to_string(),
+                serde_json::json!({"diagnosis_id": self.id, "turn": at}),
+            );
+        }
+
+        let now = Utc::now();
+        let questions = turns[..at - 1].iter().filter(|t| t.role == "assistant").count();
+        let branch = Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id: self.plant_id.clone(),
+            state: DiagnosisState::Questioning(questions as u32),
+            diagnosis_context: context,
+            created_at: now,
+            updated_at: now,
+        };
+        let copied = turns[..at - 1]
+            .iter()
+            .map(|turn| DiagnosisMessage {
+                id: Uuid::new_v4().to_string(),
+                session_id: branch.id.clone(),
+                ..turn.clone()
+            })
+            .collect();
+
+        Ok((branch, copied))
+    }
+
+    fn transition(&mut self, next: DiagnosisState) -> Result<()> {
+        if !self.state.can_become(next) {
+            anyhow::bail!("A diagnosis cannot go from {} to {}", self.state, next);
+        }
+        self.state = next;
+        Ok(())
+    }
+
+    /// Remove the `conversation_history` kept in the context by older
+    /// versions, returning its turns as messages
+    pub fn take_legacy_history(&mut self) -> Vec<DiagnosisMessage> {
+        let Some(history) = self
+            .diagnosis_context
+            .as_object_mut()
+            .and_then(|c| c.remove("conversation_history"))
+        else {
+            return Vec::new();
+        };
+
+        history
+            .as_array()
+            .map(|turns| {
+                turns
+                    .iter()
+                    .map(|turn| DiagnosisMessage {
+                        created_at: self.created_at,
+                        ..DiagnosisMessage::new(
+                            self.id.clone(),
+                            turn["role"].as_str().unwrap_or("user"),
+                            turn["message"].as_str().unwrap_or_default().to_string(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_legacy_history() {
+        let mut session = DiagnosisSession::new("p1".to_string(), "Yellow leaves".to_string());
+        assert!(session.take_legacy_history().is_empty());
+
+        session.diagnosis_context["conversation_history"] = serde_json::json!([
+            {"role": "user", "message": "Yellow leaves"},
+            {"role": "assistant", "message": "How often do you water?"}
+        ]);
+        let messages = session.take_legacy_history();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].role, "assistant");
+        assert_eq!(messages[1].content, "How often do you water?");
+        assert_eq!(messages[0].session_id, session.id);
+        assert!(session.diagnosis_context.get("conversation_history").is_none());
+    }
+
+    #[test]
+    fn test_diagnosis_lifecycle() {
+        let mut session = DiagnosisSession::new("p1".to_string(), "Yellow leaves".to_string());
+        assert_eq!(session.state, DiagnosisState::Created);
+        assert!(session.expect_answer().is_err());
+
+        session.gather_vitals().unwrap();
+        session.ask().unwrap();
+        session.ask().unwrap();
+        assert_eq!(session.state, DiagnosisState::Questioning(2));
+        assert_eq!(session.status(), DiagnosisStatus::PendingUserInput);
+        assert!(session.expect_answer().is_ok());
+
+        session.conclude().unwrap();
+        assert_eq!(session.status(), DiagnosisStatus::Completed);
+        assert!(session.expect_answer().is_err());
+    }
+
+    #[test]
+    fn test_illegal_transitions() {
+        let mut session = DiagnosisSession::new("p1".to_string(), "Yellow leaves".to_string());
+        assert!(session.ask().is_err());
+        assert!(session.conclude().is_err());
+
+        session.gather_vitals().unwrap();
+        assert!(session.gather_vitals().is_err());
+        session.conclude().unwrap();
+
+        // Final states stay final
+        assert!(session.ask().is_err());
+        assert!(session.cancel().is_err());
+        assert_eq!(session.state, DiagnosisState::Concluded);
+
+        assert!(!DiagnosisState::Questioning(1).can_become(DiagnosisState::Questioning(3)));
+        assert!(!DiagnosisState::Cancelled.can_become(DiagnosisState::Concluded));
+        assert!(DiagnosisState::Created.can_become(DiagnosisState::Cancelled));
+    }
+
+    #[test]
+    fn test_branch_at_an_answer() {
+        let mut session = DiagnosisSession::new("p1".to_string(), "Yellow leaves".to_string());
+        session.state = DiagnosisState::Concluded;
+        session.diagnosis_context["state"] = serde_json::json!({"hypothesis": "overwatering"});
+        session.diagnosis_context["result"] = serde_json::json!({"finding": "Overwatering"});
+        let start = Utc::now() - chrono::Duration::minutes(10);
+        let turns: Vec<DiagnosisMessage> = [
+            ("user", "Yellow leaves"),
+            ("assistant", "How often do you water?"),
+            ("user", "Every day"),
+            ("assistant", "Please attach a photo of the roots."),
+            ("user", "Here"),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (role, content))| DiagnosisMessage {
+            created_at: start + chrono::Duration::minutes(i as i64),
+            ..DiagnosisMessage::new(session.id.clone(), role, content.to_string())
+        })
+        .collect();
+        session.diagnosis_context["photos"] = serde_json::json!([
+            {"part": "roots", "url": "a.jpg", "attached_at": turns[4].created_at.to_rfc3339()}
+        ]);
+
+        let (branch, copied) = session.branch(&turns, 3).unwrap();
+        assert_eq!(copied.len(), 2);
+        assert!(copied.iter().all(|t| t.session_id == branch.id));
+        assert_eq!(branch.state, DiagnosisState::Questioning(1));
+        assert!(branch.expect_answer().is_ok());
+        assert_eq!(branch.diagnosis_context["initial_prompt"], "Yellow leaves");
+        assert_eq!(branch.diagnosis_context["state"], serde_json::json!({}));
+        assert!(branch.diagnosis_context.get("result").is_none());
+        assert_eq!(branch.diagnosis_context["photos"], serde_json::json!([]));
+        assert_eq!(branch.diagnosis_context["branched_from"]["turn"], 3);
+
+        assert_eq!(session.branch(&turns, 5).unwrap().0.state, DiagnosisState::Questioning(2));
+        assert!(session.branch(&turns, 1).is_err());
+        assert!(session.branch(&turns, 4).is_err());
+        assert!(session.branch(&turns, 6).is_err());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        for state in [
+            DiagnosisState::Created,
+            DiagnosisState::GatheringVitals,
+            DiagnosisState::Questioning(3),
+            DiagnosisState::Concluded,
+            DiagnosisState::Cancelled,
+        ] {
+            assert_eq!(DiagnosisState::from_str(&state.to_string()), Some(state));
+        }
+        assert_eq!(DiagnosisState::from_str("QUESTIONING:0"), None);
+
+        // Sessions serialized before states existed
+        let mut json = serde_json::to_value(DiagnosisSession::new("p1".into(), "Wilting".into())).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("state");
+        object.insert("status".to_string(), serde_json::json!("COMPLETED"));
+        let session: DiagnosisSession = serde_json::from_value(json).unwrap();
+        assert_eq!(session.state, DiagnosisState::Concluded);
+    }
+}
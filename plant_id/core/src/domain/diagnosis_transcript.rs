@@ -0,0 +1,152 @@
+//! DIAGNOSIS TRANSCRIPT
+//!
+//! A diagnosis session as a Markdown document: the plant's vitals, the
+//! conversation, the photos attached and the conclusion, to review or share
+//! the session afterwards.
+
+use serde_json::Value;
+
+use super::{DiagnosisMessage, DiagnosisSession, DiagnosisState, LocalZone};
+
+/// The session so far, rendered for `plant_name`
+pub fn to_markdown(
+    session: &DiagnosisSession,
+    plant_name: &str,
+    messages: &[DiagnosisMessage],
+    zone: &LocalZone,
+) -> String {
+    let status = match session.state {
+        DiagnosisState::Concluded => "Concluded",
+        DiagnosisState::Cancelled => "Cancelled",
+        _ => "In progress",
+    };
+    let mut markdown = format!(
+        "# Diagnosis of {}\n\n- Session: {}\n- Started: {}\n- Status: {}\n",
+        plant_name,
+        session.id,
+        zone.local(session.created_at).format("%Y-%m-%d %H:%M"),
+        status
+    );
+
+    let context = &session.diagnosis_context;
+    let vitals = &context["plant_vitals"];
+    if vitals.is_object() {
+        markdown.push_str("\n## Vitals\n\n");
+        let schedule = &vitals["care_schedule"];
+        for (label, key) in [
+            ("Light", "light"),
+            ("Water", "water"),
+            ("Humidity", "humidity"),
+            ("Temperature", "temperature"),
+        ] {
+            if let Some(value) = schedule[key].as_str() {
+                markdown.push_str(&format!("- {}: {}\n", label, value));
+            }
+        }
+        if let Some(attributes) = vitals["attributes"].as_object() {
+            for (key, value) in attributes {
+                markdown.push_str(&format!("- {}: {}\n", key, value.as_str().unwrap_or_default()));
+            }
+        }
+        if let Some(summary) = vitals["moisture_trend"]["summary"].as_str() {
+            markdown.push_str(&format!("- Soil moisture: {}\n", summary));
+        }
+    }
+
+    markdown.push_str("\n## Conversation\n");
+    for message in messages {
+        let speaker = if message.role == "assistant" { "AI" } else { "You" };
+        markdown.push_str(&format!(
+            "\n**{}** ({}): {}\n",
+            speaker,
+            zone.local(message.created_at).format("%H:%M"),
+            message.content
+        ));
+    }
+
+    if let Some(photos) = context["photos"].as_array().filter(|p| !p.is_empty()) {
+        markdown.push_str("\n## Photos\n\n");
+        for photo in photos {
+            markdown.push_str(&format!(
+                "- {}: {}\n",
+                photo["part"].as_str().unwrap_or("plant"),
+                photo["url"].as_str().unwrap_or_default()
+            ));
+        }
+    }
+
+    let result = &context["result"];
+    if result.is_object() {
+        markdown.push_str("\n## Conclusion\n\n");
+        push_field(&mut markdown, "Finding", &result["finding"]);
+        push_field(&mut markdown, "Severity", &result["severity"]);
+        if let Some(confidence) = result["confidence"].as_f64() {
+            markdown.push_str(&format!("- Confidence: {:.0}%\n", confidence * 100.0));
+        }
+        push_field(&mut markdown, "Recommendation", &result["recommendation"]);
+        let alternatives: Vec<String> = result["alternatives"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|a| {
+                let finding = a["finding"].as_str()?;
+                Some(format!("{} ({:.0}%)", finding, a["probability"].as_f64().unwrap_or(0.0) * 100.0))
+            })
+            .collect();
+        if !alternatives.is_empty() {
+            markdown.push_str(&format!("- Alternatives: {}\n", alternatives.join(", ")));
+        }
+    }
+
+    markdown
+}
+
+fn push_field(markdown: &mut String, label: &str, value: &Value) {
+    if let Some(value) = value.as_str() {
+        markdown.push_str(&format!("- {}: {}\n", label, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_transcript_grows_with_the_session() {
+        let utc = LocalZone::utc();
+        let mut session = DiagnosisSession::new("p".to_string(), "Yellow leaves".to_string());
+        session.diagnosis_context["plant_vitals"] = json!({
+            "name": "Fern",
+            "care_schedule": {"light": "Shade", "water": "Weekly", "humidity": "High", "temperature": "18-24°C"},
+            "attributes": {"pot": "terracotta"}
+        });
+        let mut messages = vec![DiagnosisMessage::new(session.id.clone(), "user", "Yellow leaves".to_string())];
+
+        let markdown = to_markdown(&session, "Fern", &messages, &utc);
+        assert!(markdown.starts_with("# Diagnosis of Fern\n"));
+        assert!(markdown.contains("- Status: In progress\n"));
+        assert!(markdown.contains("- Water: Weekly\n- Humidity: High\n"));
+        assert!(markdown.contains("- pot: terracotta\n"));
+        assert!(markdown.contains("**You** ("));
+        assert!(!markdown.contains("## Conclusion"));
+
+        messages.push(DiagnosisMessage::new(session.id.clone(), "assistant", "How often?".to_string()));
+        session.diagnosis_context["photos"] = json!([{"part": "roots", "url": "file:///roots.jpg"}]);
+        session.state = DiagnosisState::Concluded;
+        session.diagnosis_context["result"] = json!({
+            "finding": "Overwatering",
+            "severity": "minor",
+            "confidence": 0.8,
+            "recommendation": "Let the soil dry out",
+            "alternatives": [{"finding": "Low light", "probability": 0.15}]
+        });
+
+        let markdown = to_markdown(&session, "Fern", &messages, &utc);
+        assert!(markdown.contains("- Status: Concluded\n"));
+        assert!(markdown.contains("): How often?\n"));
+        assert!(markdown.contains("## Photos\n\n- roots: file:///roots.jpg\n"));
+        assert!(markdown.contains("- Finding: Overwatering\n- Severity: minor\n- Confidence: 80%\n"));
+        assert!(markdown.contains("- Alternatives: Low light (15%)\n"));
+    }
+}
@@ -0,0 +1,117 @@
+/*!
+ * PEST AND DISEASE ENCYCLOPEDIA
+ *
+ * Bundled reference entries for common houseplant pests and diseases.
+ * Used by the `lookup` command and to link diagnosis findings to entries.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use super::plant::Plant;
+
+/// Bundled encyclopedia data
+const ENTRIES_JSON: &str = include_str!("encyclopedia.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Pest,
+    Disease,
+}
+
+impl EntryKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pest => "pest",
+            Self::Disease => "disease",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncyclopediaEntry {
+    pub name: String,
+    pub kind: EntryKind,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub symptoms: Vec<String>,
+    pub treatment: Vec<String>,
+    /// Lowercase name fragments (genus or common name) of susceptible plants
+    #[serde(default)]
+    pub susceptible: Vec<String>,
+}
+
+impl EncyclopediaEntry {
+    fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+
+    /// Whether a plant's name matches one of the susceptible plant groups
+    pub fn is_susceptible(&self, plant: &Plant) -> bool {
+        let name = plant.name.to_lowercase();
+        self.susceptible.iter().any(|s| name.contains(s.as_str()))
+    }
+}
+
+/// All bundled entries
+pub fn entries() -> &'static [EncyclopediaEntry] {
+    static ENTRIES: OnceLock<Vec<EncyclopediaEntry>> = OnceLock::new();
+    ENTRIES.get_or_init(|| {
+        serde_json::from_str(ENTRIES_JSON).expect("bundled encyclopedia data is valid JSON")
+    })
+}
+
+/// Find an entry by name or alias (case-insensitive)
+pub fn lookup(kind: EntryKind, query: &str) -> Option<&'static EncyclopediaEntry> {
+    let query = query.trim().to_lowercase();
+    entries()
+        .iter()
+        .filter(|e| e.kind == kind)
+        .find(|e| e.names().any(|n| n.to_lowercase() == query))
+}
+
+/// Find entries whose name or alias is mentioned in free text,
+/// such as a diagnosis finding
+pub fn find_mentions(text: &str) -> Vec<&'static EncyclopediaEntry> {
+    let text = text.to_lowercase();
+    entries()
+        .iter()
+        .filter(|e| e.names().any(|n| contains_word(&text, &n.to_lowercase())))
+        .collect()
+}
+
+/// Whether `needle` occurs in `text` on word boundaries, so that
+/// "rust" does not match "frustrated"
+pub(crate) fn contains_word(text: &str, needle: &str) -> bool {
+    text.match_indices(needle).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_entries_parse() {
+        assert!(!entries().is_empty());
+    }
+
+    #[test]
+    fn test_lookup_by_alias() {
+        let entry = lookup(EntryKind::Pest, "Red Spider Mite").unwrap();
+        assert_eq!(entry.name, "Spider mites");
+        assert!(lookup(EntryKind::Disease, "spider mites").is_none());
+    }
+
+    #[test]
+    fn test_find_mentions_in_finding() {
+        let mentions = find_mentions("Early-stage root rot caused by overwatering");
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].name, "Root rot");
+        assert!(find_mentions("Owner is frustrated with slow growth").is_empty());
+    }
+}
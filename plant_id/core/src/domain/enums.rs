@@ -0,0 +1,442 @@
+/*!
+ * DOMAIN ENUMERATIONS
+ *
+ * Defines the core business enumerations used throughout the domain.
+ */
+
+// `from_str` returns an Option for parsing stored and AI-provided values
+#![allow(clippy::should_implement_trait)]
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DiagnosisStatus {
+    PendingUserInput,
+    Completed,
+    Cancelled,
+}
+
+impl DiagnosisStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PendingUserInput => "PENDING_USER_INPUT",
+            Self::Completed => "COMPLETED",
+            Self::Cancelled => "CANCELLED",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "PENDING_USER_INPUT" => Some(Self::PendingUserInput),
+            "COMPLETED" => Some(Self::Completed),
+            "CANCELLED" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Why a plant left the collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ArchiveReason {
+    Died,
+    Gifted,
+    Sold,
+}
+
+impl ArchiveReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Died => "DIED",
+            Self::Gifted => "GIFTED",
+            Self::Sold => "SOLD",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "DIED" => Some(Self::Died),
+            "GIFTED" => Some(Self::Gifted),
+            "SOLD" => Some(Self::Sold),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of AI answer a feedback rating refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FeedbackKind {
+    Diagnosis,
+    CareSchedule,
+}
+
+impl FeedbackKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Diagnosis => "DIAGNOSIS",
+            Self::CareSchedule => "CARE_SCHEDULE",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "DIAGNOSIS" => Some(Self::Diagnosis),
+            "CARE_SCHEDULE" => Some(Self::CareSchedule),
+            _ => None,
+        }
+    }
+}
+
+/// What a user may do with the collection on this device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UserRole {
+    Owner,
+    /// Looks after the plants while the owner is away
+    Sitter,
+}
+
+impl UserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Owner => "OWNER",
+            Self::Sitter => "SITTER",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "OWNER" => Some(Self::Owner),
+            "SITTER" => Some(Self::Sitter),
+            _ => None,
+        }
+    }
+}
+
+/// Recurring care a plant needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CareTaskKind {
+    Water,
+    Fertilize,
+}
+
+impl CareTaskKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Water => "WATER",
+            Self::Fertilize => "FERTILIZE",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "WATER" => Some(Self::Water),
+            "FERTILIZE" => Some(Self::Fertilize),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of record an audit event describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditEntity {
+    Plant,
+    Diagnosis,
+    DiagnosisMessage,
+    Chat,
+    Journal,
+    TreatmentOutcome,
+    Feedback,
+    CareTask,
+    CareTemplate,
+    ShoppingEntry,
+    MoistureReading,
+    PlantAttribute,
+    Expense,
+    Room,
+    WishlistItem,
+    ScheduleVersion,
+    IdentificationDisagreement,
+    Quarantine,
+    PreventiveInspection,
+    CareOverrides,
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plant => "PLANT",
+            Self::Diagnosis => "DIAGNOSIS",
+            Self::DiagnosisMessage => "DIAGNOSIS_MESSAGE",
+            Self::Chat => "CHAT",
+            Self::Journal => "JOURNAL",
+            Self::TreatmentOutcome => "TREATMENT_OUTCOME",
+            Self::Feedback => "FEEDBACK",
+            Self::CareTask => "CARE_TASK",
+            Self::CareTemplate => "CARE_TEMPLATE",
+            Self::ShoppingEntry => "SHOPPING_ENTRY",
+            Self::MoistureReading => "MOISTURE_READING",
+            Self::PlantAttribute => "PLANT_ATTRIBUTE",
+            Self::Expense => "EXPENSE",
+            Self::Room => "ROOM",
+            Self::WishlistItem => "WISHLIST_ITEM",
+            Self::ScheduleVersion => "SCHEDULE_VERSION",
+            Self::IdentificationDisagreement => "IDENTIFICATION_DISAGREEMENT",
+            Self::Quarantine => "QUARANTINE",
+            Self::PreventiveInspection => "PREVENTIVE_INSPECTION",
+            Self::CareOverrides => "CARE_OVERRIDES",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "PLANT" => Some(Self::Plant),
+            "DIAGNOSIS" => Some(Self::Diagnosis),
+            "DIAGNOSIS_MESSAGE" => Some(Self::DiagnosisMessage),
+            "CHAT" => Some(Self::Chat),
+            "JOURNAL" => Some(Self::Journal),
+            "TREATMENT_OUTCOME" => Some(Self::TreatmentOutcome),
+            "FEEDBACK" => Some(Self::Feedback),
+            "CARE_TASK" => Some(Self::CareTask),
+            "CARE_TEMPLATE" => Some(Self::CareTemplate),
+            "SHOPPING_ENTRY" => Some(Self::ShoppingEntry),
+            "MOISTURE_READING" => Some(Self::MoistureReading),
+            "PLANT_ATTRIBUTE" => Some(Self::PlantAttribute),
+            "EXPENSE" => Some(Self::Expense),
+            "ROOM" => Some(Self::Room),
+            "WISHLIST_ITEM" => Some(Self::WishlistItem),
+            "SCHEDULE_VERSION" => Some(Self::ScheduleVersion),
+            "IDENTIFICATION_DISAGREEMENT" => Some(Self::IdentificationDisagreement),
+            "QUARANTINE" => Some(Self::Quarantine),
+            "PREVENTIVE_INSPECTION" => Some(Self::PreventiveInspection),
+            "CARE_OVERRIDES" => Some(Self::CareOverrides),
+            _ => None,
+        }
+    }
+}
+
+/// What an expense was for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExpenseKind {
+    /// Buying the plant itself
+    Purchase,
+    /// Pots, soil, fertilizer, treatments...
+    Supply,
+}
+
+impl ExpenseKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Purchase => "PURCHASE",
+            Self::Supply => "SUPPLY",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "PURCHASE" => Some(Self::Purchase),
+            "SUPPLY" => Some(Self::Supply),
+            _ => None,
+        }
+    }
+}
+
+/// Work a background job does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobKind {
+    /// Have the AI write a plant's care schedule
+    GenerateSchedule,
+    /// Deliver an event to a webhook that could not be reached
+    DeliverWebhook,
+    /// Regenerate a user's stale care schedules
+    RefreshSchedules,
+    /// Sync with the other devices
+    Sync,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GenerateSchedule => "GENERATE_SCHEDULE",
+            Self::DeliverWebhook => "DELIVER_WEBHOOK",
+            Self::RefreshSchedules => "REFRESH_SCHEDULES",
+            Self::Sync => "SYNC",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "GENERATE_SCHEDULE" => Some(Self::GenerateSchedule),
+            "DELIVER_WEBHOOK" => Some(Self::DeliverWebhook),
+            "REFRESH_SCHEDULES" => Some(Self::RefreshSchedules),
+            "SYNC" => Some(Self::Sync),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::GenerateSchedule => "generate care schedule",
+            Self::DeliverWebhook => "deliver webhook",
+            Self::RefreshSchedules => "refresh stale care schedules",
+            Self::Sync => "sync",
+        }
+    }
+}
+
+/// Where a background job stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobStatus {
+    Pending,
+    Done,
+    /// Failed MAX_JOB_ATTEMPTS times
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Done => "DONE",
+            Self::Failed => "FAILED",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "PENDING" => Some(Self::Pending),
+            "DONE" => Some(Self::Done),
+            "FAILED" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Direction a room's window faces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WindowOrientation {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl WindowOrientation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::North => "NORTH",
+            Self::East => "EAST",
+            Self::South => "SOUTH",
+            Self::West => "WEST",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "NORTH" => Some(Self::North),
+            "EAST" => Some(Self::East),
+            "SOUTH" => Some(Self::South),
+            "WEST" => Some(Self::West),
+            _ => None,
+        }
+    }
+}
+
+/// State change recorded by an audit event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "CREATED",
+            Self::Updated => "UPDATED",
+            Self::Deleted => "DELETED",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "CREATED" => Some(Self::Created),
+            "UPDATED" => Some(Self::Updated),
+            "DELETED" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+}
+
+/// How serious a diagnosis finding is, least serious first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Nothing to treat, e.g. natural leaf drop
+    Info,
+    /// Cosmetic or easily fixed
+    Minor,
+    /// Needs treatment soon or the plant will decline
+    Serious,
+    /// The plant may die or the problem may spread to others without action now
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Minor => "minor",
+            Self::Serious => "serious",
+            Self::Critical => "critical",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "info" => Some(Self::Info),
+            "minor" => Some(Self::Minor),
+            "serious" => Some(Self::Serious),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Actions that can be taken during diagnosis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosisAction {
+    GetPlantVitals,
+    LogState,
+    AskUser,
+    RequestPhoto,
+    SuggestCareUpdate,
+    Search,
+    Conclude,
+}
+
+impl DiagnosisAction {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "GET_PLANT_VITALS" => Some(Self::GetPlantVitals),
+            "LOG_STATE" => Some(Self::LogState),
+            "ASK_USER" => Some(Self::AskUser),
+            "REQUEST_PHOTO" => Some(Self::RequestPhoto),
+            "SUGGEST_CARE_UPDATE" => Some(Self::SuggestCareUpdate),
+            "SEARCH" => Some(Self::Search),
+            "CONCLUDE" => Some(Self::Conclude),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! EXPENSES
+//!
+//! What a plant has cost its owner: the purchase price and the supplies
+//! bought for it since (pots, soil, fertilizer...). Amounts are kept in
+//! cents so totals add up exactly; no currency is assumed.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::enums::ExpenseKind;
+use super::LocalZone;
+
+/// Largest single expense, in cents, to catch typos like a missing point
+pub const MAX_AMOUNT_CENTS: i64 = 100_000 * 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Expense {
+    pub id: String,
+    pub plant_id: String,
+    pub kind: ExpenseKind,
+    pub amount_cents: i64,
+    pub description: String,
+    pub spent_at: DateTime<Utc>,
+}
+
+impl Expense {
+    pub fn new(plant_id: String, kind: ExpenseKind, amount_cents: i64, description: String) -> Result<Self> {
+        if amount_cents <= 0 {
+            anyhow::bail!("The amount must be more than zero");
+        }
+        if amount_cents > MAX_AMOUNT_CENTS {
+            anyhow::bail!("The amount is more than {}", format_amount(MAX_AMOUNT_CENTS));
+        }
+
+        let description = match (description.trim(), kind) {
+            ("", ExpenseKind::Purchase) => "Purchase".to_string(),
+            ("", ExpenseKind::Supply) => "Supplies".to_string(),
+            (text, _) => text.to_string(),
+        };
+
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            kind,
+            amount_cents,
+            description,
+            spent_at: Utc::now(),
+        })
+    }
+}
+
+/// Read an amount such as "12.99", "12,99" or "12" as cents
+pub fn parse_amount(text: &str) -> Result<i64> {
+    let text = text.trim().replace(',', ".");
+    let invalid = || anyhow::anyhow!("Invalid amount '{}': expected a number like 12.99", text);
+
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    if !whole.chars().all(|c| c.is_ascii_digit())
+        || !fraction.chars().all(|c| c.is_ascii_digit())
+        || fraction.len() > 2
+    {
+        return Err(invalid());
+    }
+
+    let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+    let cents: i64 = format!("{:0<2}", fraction).parse().map_err(|_| invalid())?;
+    whole
+        .checked_mul(100)
+        .and_then(|w| w.checked_add(cents))
+        .ok_or_else(invalid)
+}
+
+/// Cents as an amount with two decimals, e.g. 1299 as "12.99"
+pub fn format_amount(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02}", sign, cents.abs() / 100, cents.abs() % 100)
+}
+
+/// What one plant has cost, in cents
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PlantCosts {
+    pub purchase: i64,
+    pub supplies: i64,
+}
+
+impl PlantCosts {
+    pub fn total(&self) -> i64 {
+        self.purchase + self.supplies
+    }
+}
+
+/// Costs per plant ID
+pub fn totals_by_plant(expenses: &[Expense]) -> BTreeMap<String, PlantCosts> {
+    let mut totals: BTreeMap<String, PlantCosts> = BTreeMap::new();
+    for expense in expenses {
+        let costs = totals.entry(expense.plant_id.clone()).or_default();
+        match expense.kind {
+            ExpenseKind::Purchase => costs.purchase += expense.amount_cents,
+            ExpenseKind::Supply => costs.supplies += expense.amount_cents,
+        }
+    }
+    totals
+}
+
+/// Spending per month ("2024-05") of `zone`, oldest first
+pub fn totals_by_month(expenses: &[Expense], zone: &LocalZone) -> BTreeMap<String, i64> {
+    let mut totals = BTreeMap::new();
+    for expense in expenses {
+        let month = zone.local(expense.spent_at).format("%Y-%m").to_string();
+        *totals.entry(month).or_insert(0) += expense.amount_cents;
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("12.99").unwrap(), 1299);
+        assert_eq!(parse_amount("12,5").unwrap(), 1250);
+        assert_eq!(parse_amount("7").unwrap(), 700);
+        assert_eq!(parse_amount(".5").unwrap(), 50);
+        assert!(parse_amount("12.999").is_err());
+        assert!(parse_amount("-3").is_err());
+        assert!(parse_amount("ten").is_err());
+        assert!(parse_amount(".").is_err());
+        assert_eq!(format_amount(1299), "12.99");
+        assert_eq!(format_amount(5), "0.05");
+    }
+
+    #[test]
+    fn test_totals() {
+        let expense = |plant: &str, kind, cents, month| Expense {
+            spent_at: Utc.with_ymd_and_hms(2024, month, 10, 12, 0, 0).unwrap(),
+            ..Expense::new(plant.to_string(), kind, cents, String::new()).unwrap()
+        };
+        let expenses = vec![
+            expense("p1", ExpenseKind::Purchase, 2500, 4),
+            expense("p1", ExpenseKind::Supply, 1299, 5),
+            expense("p2", ExpenseKind::Supply, 450, 5),
+        ];
+
+        let by_plant = totals_by_plant(&expenses);
+        assert_eq!(by_plant["p1"], PlantCosts { purchase: 2500, supplies: 1299 });
+        assert_eq!(by_plant["p1"].total(), 3799);
+        assert_eq!(by_plant["p2"].total(), 450);
+
+        let by_month = totals_by_month(&expenses, &LocalZone::utc());
+        assert_eq!(by_month.into_iter().collect::<Vec<_>>(), vec![
+            ("2024-04".to_string(), 2500),
+            ("2024-05".to_string(), 1749),
+        ]);
+
+        assert!(Expense::new("p1".to_string(), ExpenseKind::Supply, 0, String::new()).is_err());
+        assert_eq!(expenses[0].description, "Purchase");
+    }
+}
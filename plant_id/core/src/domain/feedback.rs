@@ -0,0 +1,124 @@
+//! FEEDBACK DOMAIN MODEL
+//!
+//! The user's 1-5 rating of an AI answer (a diagnosis conclusion or a care
+//! schedule), tagged with the model that produced it so models can be
+//! compared.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::enums::FeedbackKind;
+
+pub const MIN_RATING: u8 = 1;
+pub const MAX_RATING: u8 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feedback {
+    pub id: String,
+    pub kind: FeedbackKind,
+    /// Diagnosis ID, or the plant name for care schedules
+    pub subject: String,
+    /// AI model that produced the rated answer
+    pub model: String,
+    pub rating: u8,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Feedback {
+    pub fn new(
+        kind: FeedbackKind,
+        subject: String,
+        model: String,
+        rating: u8,
+        comment: Option<String>,
+    ) -> Result<Self> {
+        if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+            anyhow::bail!("Rating must be between {} and {}", MIN_RATING, MAX_RATING);
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            kind,
+            subject,
+            model,
+            rating,
+            comment,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Average rating of one model for one kind of answer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRating {
+    pub model: String,
+    pub kind: FeedbackKind,
+    pub count: usize,
+    pub average: f64,
+}
+
+/// Group ratings by model and kind, best average first
+pub fn compare_models(feedback: &[Feedback]) -> Vec<ModelRating> {
+    let mut totals: Vec<(String, FeedbackKind, usize, u32)> = Vec::new();
+
+    for entry in feedback {
+        match totals
+            .iter_mut()
+            .find(|(model, kind, _, _)| model == &entry.model && *kind == entry.kind)
+        {
+            Some((_, _, count, sum)) => {
+                *count += 1;
+                *sum += u32::from(entry.rating);
+            }
+            None => totals.push((entry.model.clone(), entry.kind, 1, u32::from(entry.rating))),
+        }
+    }
+
+    let mut ratings: Vec<ModelRating> = totals
+        .into_iter()
+        .map(|(model, kind, count, sum)| ModelRating {
+            model,
+            kind,
+            count,
+            average: f64::from(sum) / count as f64,
+        })
+        .collect();
+    ratings.sort_by(|a, b| b.average.total_cmp(&a.average).then(b.count.cmp(&a.count)));
+    ratings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(model: &str, kind: FeedbackKind, rating: u8) -> Feedback {
+        Feedback::new(kind, "subject".to_string(), model.to_string(), rating, None).unwrap()
+    }
+
+    #[test]
+    fn test_rating_out_of_range_is_rejected() {
+        assert!(Feedback::new(FeedbackKind::Diagnosis, "d1".into(), "m".into(), 0, None).is_err());
+        assert!(Feedback::new(FeedbackKind::Diagnosis, "d1".into(), "m".into(), 6, None).is_err());
+    }
+
+    #[test]
+    fn test_compare_models_ranks_by_average() {
+        let feedback = vec![
+            rating("model-a", FeedbackKind::Diagnosis, 3),
+            rating("model-b", FeedbackKind::Diagnosis, 5),
+            rating("model-a", FeedbackKind::Diagnosis, 4),
+            rating("model-a", FeedbackKind::CareSchedule, 2),
+        ];
+
+        let ratings = compare_models(&feedback);
+        assert_eq!(ratings.len(), 3);
+        assert_eq!(ratings[0].model, "model-b");
+        assert_eq!(ratings[1].model, "model-a");
+        assert_eq!(ratings[1].kind, FeedbackKind::Diagnosis);
+        assert_eq!(ratings[1].count, 2);
+        assert_eq!(ratings[1].average, 3.5);
+    }
+}
@@ -0,0 +1,84 @@
+//! HEALTH ASSESSMENT
+//!
+//! A machine assessment of a photo of a sick plant, such as Plant.id's:
+//! how likely the plant is healthy and the diseases it may have, each with
+//! its probability. A diagnosis started with a photo keeps it in the
+//! context under `health_assessment`, so the AI starts from these findings
+//! instead of from nothing.
+
+use serde::{Deserialize, Serialize};
+
+/// Disease suggestions kept, the likeliest ones
+pub const MAX_DISEASE_SUGGESTIONS: usize = 5;
+
+/// Suggestions less likely than this are left out
+const MIN_DISEASE_PROBABILITY: f64 = 0.05;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthAssessment {
+    /// Who assessed the photo, e.g. "Plant.id"
+    pub source: String,
+    /// From 0 to 1, when the assessor says
+    #[serde(default)]
+    pub is_healthy_probability: Option<f64>,
+    /// Likeliest first
+    #[serde(default)]
+    pub diseases: Vec<DiseaseSuggestion>,
+}
+
+/// A disease, pest or disorder an assessor recognised in a photo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiseaseSuggestion {
+    pub name: String,
+    /// From 0 to 1
+    pub probability: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub common_names: Vec<String>,
+}
+
+impl HealthAssessment {
+    /// An assessment keeping the likeliest MAX_DISEASE_SUGGESTIONS diseases
+    /// that are not too unlikely
+    pub fn new(source: &str, is_healthy_probability: Option<f64>, mut diseases: Vec<DiseaseSuggestion>) -> Self {
+        diseases.retain(|d| d.probability >= MIN_DISEASE_PROBABILITY);
+        diseases.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        diseases.truncate(MAX_DISEASE_SUGGESTIONS);
+
+        Self {
+            source: source.to_string(),
+            is_healthy_probability,
+            diseases,
+        }
+    }
+
+    /// The likeliest disease, if any was suggested
+    pub fn top_disease(&self) -> Option<&DiseaseSuggestion> {
+        self.diseases.first()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disease(name: &str, probability: f64) -> DiseaseSuggestion {
+        DiseaseSuggestion {
+            name: name.to_string(),
+            probability,
+            common_names: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_likeliest_diseases_are_kept() {
+        let mut diseases: Vec<DiseaseSuggestion> = (1..=7).map(|i| disease(&format!("d{}", i), i as f64 / 10.0)).collect();
+        diseases.push(disease("unlikely", 0.01));
+
+        let assessment = HealthAssessment::new("Plant.id", Some(0.1), diseases);
+        let names: Vec<&str> = assessment.diseases.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["d7", "d6", "d5", "d4", "d3"]);
+        assert_eq!(assessment.top_disease().map(|d| d.probability), Some(0.7));
+
+        assert_eq!(HealthAssessment::new("Plant.id", None, Vec::new()).top_disease(), None);
+    }
+}
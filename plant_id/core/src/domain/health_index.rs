@@ -0,0 +1,289 @@
+//! HEALTH INDEX
+//!
+//! A 0-100 score of how a plant is doing, drawn from three signals: whether
+//! its care reminders are kept, how serious its recent diagnoses were, and
+//! the tone of its recent journal notes. 100 means nothing points at a
+//! problem; each signal can only take points away.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+
+use super::encyclopedia;
+use super::enums::CareTaskKind;
+use super::{
+    CareSchedule, CareTask, DiagnosisSession, DiagnosisState, JournalEntry, LocalZone, TreatmentOutcome,
+};
+
+pub const MAX_HEALTH: u8 = 100;
+
+/// Plants scoring below this need attention
+pub const NEEDS_ATTENTION_BELOW: u8 = 70;
+
+/// How far back diagnoses and journal notes count
+pub const WINDOW_DAYS: i64 = 30;
+
+/// Points each signal can take away, adding up to `MAX_HEALTH`
+const ADHERENCE_POINTS: f64 = 40.0;
+const DIAGNOSIS_POINTS: f64 = 35.0;
+const JOURNAL_POINTS: f64 = 25.0;
+
+/// Severity of a concluded diagnosis naming a pest or disease, and of one
+/// naming anything else (usually a care mistake)
+const PEST_OR_DISEASE_SEVERITY: f64 = 1.0;
+const CARE_ISSUE_SEVERITY: f64 = 0.6;
+
+/// Severity of a diagnosis still in progress: something is wrong, but
+/// nobody knows what yet
+const OPEN_DIAGNOSIS_SEVERITY: f64 = 0.5;
+
+/// Words that make a journal note read as good or bad news. Negations
+/// ("no yellow leaves") are not understood.
+const NEGATIVE_WORDS: &[&str] = &[
+    "yellow", "yellowing", "brown", "browning", "wilt", "wilted", "wilting", "droop", "drooping",
+    "droopy", "dying", "dead", "rot", "rotten", "rotting", "mold", "mould", "mouldy", "moldy",
+    "pest", "pests", "bugs", "spots", "spotted", "crispy", "limp", "mushy", "burnt", "scorched",
+    "curling", "shrivelled", "shriveled", "sad", "struggling", "dropped", "dropping",
+];
+const POSITIVE_WORDS: &[&str] = &[
+    "new", "growth", "growing", "thriving", "healthy", "lush", "flower", "flowering", "bloom",
+    "blooming", "happy", "perky", "recovered", "recovering", "better", "sprout", "sprouting",
+    "unfurled", "unfurling", "shoot", "shoots",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthIndex {
+    pub score: u8,
+    /// From 0 (reminders ignored for a full interval or more) to 1 (kept)
+    pub adherence: f64,
+    /// From 0 (no recent problem) to 1 (a pest or disease, untreated)
+    pub diagnosis_severity: f64,
+    /// From -1 (only bad news) to 1 (only good news); 0 without notes
+    pub journal_sentiment: f64,
+}
+
+impl HealthIndex {
+    pub fn needs_attention(&self) -> bool {
+        self.score < NEEDS_ATTENTION_BELOW
+    }
+}
+
+/// Score a plant from its care tasks, diagnosis sessions, treatment
+/// outcomes and journal, as of `now` in `zone`
+pub fn compute(
+    schedule: &CareSchedule,
+    tasks: &[CareTask],
+    sessions: &[DiagnosisSession],
+    outcomes: &[TreatmentOutcome],
+    journal: &[JournalEntry],
+    now: DateTime<Utc>,
+    zone: &LocalZone,
+) -> HealthIndex {
+    let adherence = adherence(schedule, tasks, zone.date(now), zone);
+    let diagnosis_severity = diagnosis_severity(sessions, outcomes, now);
+    let journal_sentiment = journal_sentiment(journal, now);
+
+    let penalty = (1.0 - adherence) * ADHERENCE_POINTS
+        + diagnosis_severity * DIAGNOSIS_POINTS
+        + (-journal_sentiment).max(0.0) * JOURNAL_POINTS;
+    let score = (MAX_HEALTH as f64 - penalty).round().clamp(0.0, MAX_HEALTH as f64) as u8;
+
+    HealthIndex {
+        score,
+        adherence,
+        diagnosis_severity,
+        journal_sentiment,
+    }
+}
+
+/// How well reminders are kept: each overdue task counts by how much of its
+/// interval it is late, watering three times as much as fertilizing. Tasks
+/// never done say nothing about adherence and are left out.
+fn adherence(schedule: &CareSchedule, tasks: &[CareTask], today: NaiveDate, zone: &LocalZone) -> f64 {
+    let mut late = 0.0;
+    let mut total = 0.0;
+
+    for task in tasks.iter().filter(|t| t.last_done_at.is_some()) {
+        let weight = match task.kind {
+            CareTaskKind::Water => 3.0,
+            CareTaskKind::Fertilize => 1.0,
+        };
+        let overdue = (today - task.due_date(schedule, today, zone)).num_days().max(0) as f64;
+        let interval = task.interval_days(schedule).max(1) as f64;
+
+        late += weight * (overdue / interval).min(1.0);
+        total += weight;
+    }
+
+    if total == 0.0 {
+        1.0
+    } else {
+        1.0 - late / total
+    }
+}
+
+/// Severity of the worst recent diagnosis. A conclusion counts by what it
+/// found and how confident it was, fading over the window; one whose
+/// treatment was reported to work no longer counts.
+fn diagnosis_severity(sessions: &[DiagnosisSession], outcomes: &[TreatmentOutcome], now: DateTime<Utc>) -> f64 {
+    let since = now - Duration::days(WINDOW_DAYS);
+
+    sessions
+        .iter()
+        .filter(|s| s.updated_at >= since)
+        .map(|session| match session.state {
+            DiagnosisState::Concluded => {
+                let treated = outcomes
+                    .iter()
+                    .any(|o| o.diagnosis_id == session.id && o.worked);
+                if treated {
+                    return 0.0;
+                }
+
+                let result = &session.diagnosis_context["result"];
+                let finding = result["finding"].as_str().unwrap_or_default();
+                let base = if encyclopedia::find_mentions(finding).is_empty() {
+                    CARE_ISSUE_SEVERITY
+                } else {
+                    PEST_OR_DISEASE_SEVERITY
+                };
+                let confidence = result["confidence"].as_f64().unwrap_or(1.0).clamp(0.5, 1.0);
+                let age = (now - session.updated_at).num_days() as f64;
+
+                base * confidence * (1.0 - age / WINDOW_DAYS as f64)
+            }
+            DiagnosisState::Cancelled => 0.0,
+            _ => OPEN_DIAGNOSIS_SEVERITY,
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Average tone of the journal notes written within the window
+fn journal_sentiment(journal: &[JournalEntry], now: DateTime<Utc>) -> f64 {
+    let since = now - Duration::days(WINDOW_DAYS);
+    let recent: Vec<f64> = journal
+        .iter()
+        .filter(|e| e.created_at >= since)
+        .map(note_sentiment)
+        .collect();
+
+    if recent.is_empty() {
+        0.0
+    } else {
+        recent.iter().sum::<f64>() / recent.len() as f64
+    }
+}
+
+/// Tone of one note: -1 if flagged as a problem, otherwise the balance of
+/// good and bad words in it
+fn note_sentiment(entry: &JournalEntry) -> f64 {
+    if entry.is_problem {
+        return -1.0;
+    }
+
+    let text = entry.note.to_lowercase();
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let negative = words.iter().filter(|w| NEGATIVE_WORDS.contains(w)).count() as f64;
+    let positive = words.iter().filter(|w| POSITIVE_WORDS.contains(w)).count() as f64;
+
+    if negative + positive == 0.0 {
+        0.0
+    } else {
+        (positive - negative) / (positive + negative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> CareSchedule {
+        CareSchedule {
+            water: "every 7 days".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn watered(days_ago: i64, now: DateTime<Utc>) -> CareTask {
+        CareTask {
+            last_done_at: Some(now - Duration::days(days_ago)),
+            ..CareTask::new("p1", CareTaskKind::Water)
+        }
+    }
+
+    fn note(text: &str, is_problem: bool, now: DateTime<Utc>) -> JournalEntry {
+        JournalEntry {
+            created_at: now,
+            ..JournalEntry::new("p1".to_string(), text.to_string(), is_problem)
+        }
+    }
+
+    fn concluded(finding: &str, now: DateTime<Utc>) -> DiagnosisSession {
+        let mut session = DiagnosisSession::new("p1".to_string(), "Spots".to_string());
+        session.state = DiagnosisState::Concluded;
+        session.diagnosis_context["result"] = serde_json::json!({"finding": finding, "confidence": 0.9});
+        session.updated_at = now;
+        session
+    }
+
+    #[test]
+    fn test_healthy_plant() {
+        let now = Utc::now();
+        let index = compute(&schedule(), &[watered(2, now)], &[], &[], &[], now, &LocalZone::utc());
+        assert_eq!(index.score, MAX_HEALTH);
+        assert!(!index.needs_attention());
+    }
+
+    #[test]
+    fn test_overdue_watering() {
+        let now = Utc::now();
+        let late = compute(&schedule(), &[watered(10, now)], &[], &[], &[], now, &LocalZone::utc());
+        let forgotten = compute(&schedule(), &[watered(30, now)], &[], &[], &[], now, &LocalZone::utc());
+
+        assert!(late.adherence > forgotten.adherence);
+        assert_eq!(forgotten.adherence, 0.0);
+        assert_eq!(forgotten.score, MAX_HEALTH - ADHERENCE_POINTS as u8);
+    }
+
+    #[test]
+    fn test_diagnosis_severity() {
+        let now = Utc::now();
+        let pest = concluded("Spider mites", now);
+        let care = concluded("Overwatering", now);
+        let pest_index = compute(&schedule(), &[], std::slice::from_ref(&pest), &[], &[], now, &LocalZone::utc());
+        let care_index = compute(&schedule(), &[], &[care], &[], &[], now, &LocalZone::utc());
+        assert!(pest_index.score < care_index.score);
+        assert!(pest_index.needs_attention());
+
+        let outcome = TreatmentOutcome::new(
+            "p1".to_string(),
+            "Monstera",
+            pest.id.clone(),
+            "Spider mites".to_string(),
+            "Neem oil".to_string(),
+            true,
+            None,
+        );
+        let treated = compute(&schedule(), &[], std::slice::from_ref(&pest), &[outcome], &[], now, &LocalZone::utc());
+        assert_eq!(treated.score, MAX_HEALTH);
+
+        let old = compute(&schedule(), &[], &[pest], &[], &[], now + Duration::days(WINDOW_DAYS + 1), &LocalZone::utc());
+        assert_eq!(old.diagnosis_severity, 0.0);
+    }
+
+    #[test]
+    fn test_journal_sentiment() {
+        let now = Utc::now();
+        assert_eq!(note_sentiment(&note("New leaf unfurling, looks happy", false, now)), 1.0);
+        assert_eq!(note_sentiment(&note("Leaves yellowing and drooping", false, now)), -1.0);
+        assert_eq!(note_sentiment(&note("Rotated the pot", false, now)), 0.0);
+        assert_eq!(note_sentiment(&note("Rotated the pot", true, now)), -1.0);
+
+        let journal = [note("Leaves wilting", false, now), note("Repotted", false, now)];
+        let index = compute(&schedule(), &[], &[], &[], &journal, now, &LocalZone::utc());
+        assert_eq!(index.journal_sentiment, -0.5);
+        assert!(index.score < MAX_HEALTH);
+    }
+}
@@ -0,0 +1,174 @@
+//! PLANT IDENTIFICATION
+//!
+//! A photo is identified by Plant.id and, when one is configured, by a
+//! second source. When the two name different species, the owner picks
+//! one or has the AI look at the photo to break the tie. The disagreement
+//! is recorded with how it was settled, so that a wrong pick can be
+//! corrected later.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A species an identifier recognised in a photo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeciesCandidate {
+    /// Identifier that suggested the species, e.g. "Plant.id"
+    pub source: String,
+    pub scientific_name: String,
+    /// Everyday names, most used first; often empty for rare species
+    #[serde(default)]
+    pub common_names: Vec<String>,
+    /// How sure the identifier is, from 0 to 1, when it says
+    #[serde(default)]
+    pub confidence: Option<f64>,
+}
+
+impl SpeciesCandidate {
+    /// The name a new plant is shown with: its first common name, or the
+    /// scientific name when there is none
+    pub fn display_name(&self) -> &str {
+        self.common_names.first().unwrap_or(&self.scientific_name)
+    }
+
+    /// Whether both name the same species. Only the genus and species are
+    /// compared, so authors and varieties ("Monstera deliciosa Liebm.",
+    /// "... var. borsigiana") do not count as a disagreement.
+    pub fn same_species(&self, other: &SpeciesCandidate) -> bool {
+        let binomial = |name: &str| -> Vec<String> {
+            name.split_whitespace().take(2).map(str::to_lowercase).collect()
+        };
+        binomial(&self.scientific_name) == binomial(&other.scientific_name)
+    }
+
+    /// "Monstera deliciosa (Plant.id, 87%)"
+    pub fn describe(&self) -> String {
+        match self.confidence {
+            Some(confidence) => format!(
+                "{} ({}, {:.0}%)",
+                self.scientific_name,
+                self.source,
+                confidence.clamp(0.0, 1.0) * 100.0
+            ),
+            None => format!("{} ({})", self.scientific_name, self.source),
+        }
+    }
+}
+
+/// How the species of a disputed identification was chosen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpeciesResolution {
+    /// Picked by the owner
+    Owner,
+    /// Picked by an AI model after looking at the photo
+    Tiebreak { model: String, reasoning: String },
+    /// Nobody was asked: Plant.id's answer was kept
+    Default,
+}
+
+impl SpeciesResolution {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Owner => "picked by you".to_string(),
+            Self::Tiebreak { model, .. } => format!("picked by {}", model),
+            Self::Default => "Plant.id's answer kept".to_string(),
+        }
+    }
+}
+
+/// Identifiers that named different species for a plant's photo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentificationDisagreement {
+    pub id: String,
+    pub plant_id: String,
+    pub candidates: Vec<SpeciesCandidate>,
+    /// Scientific name the plant was added as
+    pub chosen: String,
+    pub resolution: SpeciesResolution,
+    /// Species the owner later said the plant really is
+    pub corrected_to: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IdentificationDisagreement {
+    pub fn new(
+        plant_id: String,
+        candidates: Vec<SpeciesCandidate>,
+        chosen: String,
+        resolution: SpeciesResolution,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            candidates,
+            chosen,
+            resolution,
+            corrected_to: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// The species the plant is now believed to be
+    pub fn species(&self) -> &str {
+        self.corrected_to.as_deref().unwrap_or(&self.chosen)
+    }
+
+    /// The candidate with the given scientific name, if one was suggested
+    pub fn candidate(&self, scientific_name: &str) -> Option<&SpeciesCandidate> {
+        let wanted = SpeciesCandidate {
+            source: String::new(),
+            scientific_name: scientific_name.to_string(),
+            common_names: Vec::new(),
+            confidence: None,
+        };
+        self.candidates.iter().find(|c| c.same_species(&wanted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(source: &str, name: &str, confidence: Option<f64>) -> SpeciesCandidate {
+        SpeciesCandidate {
+            source: source.to_string(),
+            scientific_name: name.to_string(),
+            common_names: Vec::new(),
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_same_species_ignores_authors_and_varieties() {
+        let plant_id = candidate("Plant.id", "Monstera deliciosa Liebm.", Some(0.87));
+        assert!(plant_id.same_species(&candidate("Trefle", "monstera deliciosa", None)));
+        assert!(plant_id.same_species(&candidate("model", "Monstera deliciosa var. borsigiana", None)));
+        assert!(!plant_id.same_species(&candidate("model", "Monstera adansonii", Some(0.6))));
+
+        assert_eq!(plant_id.describe(), "Monstera deliciosa Liebm. (Plant.id, 87%)");
+        assert_eq!(candidate("Trefle", "Monstera adansonii", None).describe(), "Monstera adansonii (Trefle)");
+    }
+
+    #[test]
+    fn test_correction_changes_the_species() {
+        let mut disagreement = IdentificationDisagreement::new(
+            "p1".to_string(),
+            vec![
+                candidate("Plant.id", "Monstera deliciosa", Some(0.55)),
+                candidate("model", "Monstera adansonii", Some(0.6)),
+            ],
+            "Monstera deliciosa".to_string(),
+            SpeciesResolution::Default,
+        );
+        assert_eq!(disagreement.species(), "Monstera deliciosa");
+        assert_eq!(disagreement.candidate("monstera adansonii").unwrap().source, "model");
+        assert!(disagreement.candidate("Rhaphidophora tetrasperma").is_none());
+
+        disagreement.corrected_to = Some("Monstera adansonii".to_string());
+        assert_eq!(disagreement.species(), "Monstera adansonii");
+    }
+}
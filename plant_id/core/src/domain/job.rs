@@ -0,0 +1,145 @@
+//! BACKGROUND JOBS
+//!
+//! Work that is queued rather than done on the spot: the care schedule of a
+//! plant added while the AI could not be reached, an event a webhook failed
+//! to take, a refresh of stale schedules or a sync asked for with
+//! `--queue`. A worker (`plant-care jobs run`) picks up jobs once they are
+//! due. One that fails is retried with exponential backoff, and given up
+//! after MAX_JOB_ATTEMPTS. Jobs stay on the device that queued them (they
+//! are not synced).
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::enums::{JobKind, JobStatus};
+
+/// Tries before a job is given up on
+pub const MAX_JOB_ATTEMPTS: u32 = 5;
+
+/// Wait before the first retry; each later one waits twice as long
+const BASE_BACKOFF_SECS: i64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    /// The user the work is for, or none for work of the whole device such
+    /// as webhook deliveries
+    pub user_id: Option<String>,
+    pub plant_id: Option<String>,
+    pub kind: JobKind,
+    /// What the job needs to know beyond its plant, e.g. the event to
+    /// deliver
+    pub payload: Option<Value>,
+    pub status: JobStatus,
+    pub attempts: u32,
+    /// Why the last try failed
+    pub last_error: Option<String>,
+    /// When the job is next due
+    pub run_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Job {
+    /// A job due right away
+    pub fn new(user_id: Option<&str>, kind: JobKind) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.map(str::to_string),
+            plant_id: None,
+            kind,
+            payload: None,
+            status: JobStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            run_at: now,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn for_plant(mut self, plant_id: &str) -> Self {
+        self.plant_id = Some(plant_id.to_string());
+        self
+    }
+
+    pub fn with_payload(mut self, payload: Value) -> Self {
+        self.payload = Some(payload);
+        self
+    }
+
+    /// Whether a worker should run the job now
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == JobStatus::Pending && self.run_at <= now
+    }
+
+    pub fn succeeded(&mut self) {
+        self.attempts += 1;
+        self.status = JobStatus::Done;
+        self.last_error = None;
+        self.updated_at = Utc::now();
+    }
+
+    /// Record a failed try and put the job off by `backoff`, giving it up
+    /// after MAX_JOB_ATTEMPTS
+    pub fn failed(&mut self, error: String) {
+        let now = Utc::now();
+        self.attempts += 1;
+        if self.attempts >= MAX_JOB_ATTEMPTS {
+            self.status = JobStatus::Failed;
+        }
+        self.last_error = Some(error);
+        self.run_at = now + backoff(self.attempts);
+        self.updated_at = now;
+    }
+}
+
+/// How long a job waits after its `attempts`th failed try: 30 seconds, then
+/// 1, 2, 4 minutes and so on
+pub fn backoff(attempts: u32) -> Duration {
+    Duration::seconds(BASE_BACKOFF_SECS << attempts.saturating_sub(1).min(16))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_is_given_up_after_max_attempts() {
+        let mut job = Job::new(Some("u"), JobKind::GenerateSchedule).for_plant("p");
+        assert_eq!(job.attempts, 0);
+        assert!(job.is_due(Utc::now()));
+
+        for _ in 1..MAX_JOB_ATTEMPTS {
+            job.failed("timeout".to_string());
+            assert_eq!(job.status, JobStatus::Pending);
+        }
+        job.failed("timeout".to_string());
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.attempts, MAX_JOB_ATTEMPTS);
+        assert!(!job.is_due(job.run_at));
+
+        let mut retried = Job::new(Some("u"), JobKind::GenerateSchedule).for_plant("p");
+        retried.failed("timeout".to_string());
+        retried.succeeded();
+        assert_eq!((retried.status, retried.attempts, retried.last_error), (JobStatus::Done, 2, None));
+
+        assert_eq!(JobKind::from_str(JobKind::DeliverWebhook.as_str()), Some(JobKind::DeliverWebhook));
+        assert_eq!(JobStatus::from_str("FAILED"), Some(JobStatus::Failed));
+    }
+
+    #[test]
+    fn test_failed_jobs_back_off_exponentially() {
+        assert_eq!(backoff(1), Duration::seconds(30));
+        assert_eq!(backoff(2), Duration::minutes(1));
+        assert_eq!(backoff(4), Duration::minutes(4));
+
+        let mut job = Job::new(None, JobKind::Sync);
+        job.failed("remote unreachable".to_string());
+        assert!(!job.is_due(Utc::now()));
+        assert!(job.is_due(Utc::now() + Duration::seconds(31)));
+    }
+}
@@ -0,0 +1,30 @@
+//! JOURNAL ENTRY DOMAIN MODEL
+//!
+//! Free-text notes the user keeps about a plant over time
+//! (repotting, new growth, observations).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    pub plant_id: String,
+    pub note: String,
+    /// Whether the note describes a problem (symptom, pest sighting, ...)
+    pub is_problem: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl JournalEntry {
+    pub fn new(plant_id: String, note: String, is_problem: bool) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            note,
+            is_problem,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! LANGUAGE DETECTION
+//!
+//! Guesses the language a problem description is written in, so the AI can
+//! answer in it. Texts in a script of their own (Cyrillic, Greek, Arabic,
+//! Japanese kana...) are told apart by their letters; languages written in
+//! Latin letters by the common words they use. A text too short or too mixed
+//! to tell is left undetected.
+
+use serde::{Deserialize, Serialize};
+
+/// Common-word hits a Latin-script text needs before its language is trusted
+const MIN_WORD_HITS: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Language {
+    /// ISO 639-1 code, e.g. "es"
+    pub code: String,
+    /// English name, e.g. "Spanish"
+    pub name: String,
+}
+
+impl Language {
+    fn new(code: &str, name: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    /// Language recorded in a diagnosis context, if any
+    pub fn of_session(context: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(context.get("language")?.clone()).ok()
+    }
+}
+
+/// Frequent words of each language written in Latin letters, as used in
+/// plant problem descriptions
+const COMMON_WORDS: &[(&str, &str, &[&str])] = &[
+    (
+        "en",
+        "English",
+        &[
+            "the", "and", "is", "are", "my", "it", "its", "has", "have", "with", "leaves", "leaf", "on", "of",
+            "to", "they", "this", "but", "not", "turning", "plant", "some", "been", "was", "were", "yellow", "brown",
+        ],
+    ),
+    (
+        "es",
+        "Spanish",
+        &[
+            "el", "los", "las", "y", "es", "mi", "tiene", "hojas", "hoja", "planta", "con", "pero", "muy", "están",
+            "está", "se", "del", "por", "amarillas", "marrones", "una", "unos", "riego", "sus", "como",
+        ],
+    ),
+    (
+        "fr",
+        "French",
+        &[
+            "le", "les", "et", "est", "mon", "ma", "mes", "feuilles", "feuille", "plante", "avec", "mais", "très",
+            "sont", "des", "du", "une", "jaunes", "brunes", "elle", "il", "au", "sur", "ne", "pas",
+        ],
+    ),
+    (
+        "de",
+        "German",
+        &[
+            "der", "die", "das", "und", "ist", "meine", "mein", "hat", "blätter", "blatt", "pflanze", "mit", "aber",
+            "sehr", "sind", "nicht", "ein", "eine", "gelbe", "braune", "werden", "auf", "sie", "es", "ich",
+        ],
+    ),
+    (
+        "it",
+        "Italian",
+        &[
+            "il", "gli", "e", "è", "mia", "mio", "ha", "foglie", "foglia", "pianta", "con", "ma", "molto", "sono",
+            "della", "delle", "una", "gialle", "marroni", "non", "che", "di", "sulle",
+        ],
+    ),
+    (
+        "pt",
+        "Portuguese",
+        &[
+            "o", "os", "as", "e", "é", "minha", "meu", "tem", "folhas", "folha", "planta", "com", "mas", "muito",
+            "estão", "está", "da", "das", "uma", "amarelas", "marrons", "não", "que", "em",
+        ],
+    ),
+    (
+        "nl",
+        "Dutch",
+        &[
+            "de", "het", "en", "is", "mijn", "heeft", "bladeren", "blad", "plant", "met", "maar", "erg", "zijn",
+            "niet", "een", "gele", "bruine", "worden", "op", "ze", "ik", "van",
+        ],
+    ),
+];
+
+/// The language `text` is written in, when it can be told
+pub fn detect(text: &str) -> Option<Language> {
+    by_script(text).or_else(|| by_words(text))
+}
+
+/// Languages with a script of their own, when most letters are in it
+fn by_script(text: &str) -> Option<Language> {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.is_empty() {
+        return None;
+    }
+    let share = |test: fn(&char) -> bool| letters.iter().filter(|c| test(c)).count() * 2 > letters.len();
+
+    // Japanese mixes kana with Han characters, so Han text with any kana is Japanese
+    let kana = letters.iter().any(|c| matches!(c, '\u{3040}'..='\u{30FF}'));
+    let scripts: [(bool, &str, &str); 9] = [
+        (share(|c| matches!(c, '\u{0400}'..='\u{04FF}')), "ru", "Russian"),
+        (share(|c| matches!(c, '\u{0370}'..='\u{03FF}')), "el", "Greek"),
+        (share(|c| matches!(c, '\u{0590}'..='\u{05FF}')), "he", "Hebrew"),
+        (share(|c| matches!(c, '\u{0600}'..='\u{06FF}')), "ar", "Arabic"),
+        (share(|c| matches!(c, '\u{0900}'..='\u{097F}')), "hi", "Hindi"),
+        (share(|c| matches!(c, '\u{0E00}'..='\u{0E7F}')), "th", "Thai"),
+        (share(|c| matches!(c, '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}')), "ko", "Korean"),
+        (kana && share(|c| matches!(c, '\u{3040}'..='\u{30FF}' | '\u{4E00}'..='\u{9FFF}')), "ja", "Japanese"),
+        (share(|c| matches!(c, '\u{4E00}'..='\u{9FFF}')), "zh", "Chinese"),
+    ];
+
+    scripts
+        .iter()
+        .find(|(found, _, _)| *found)
+        .map(|(_, code, name)| Language::new(code, name))
+}
+
+/// The Latin-script language whose common words the text uses most, if
+/// clearly ahead of the others
+fn by_words(text: &str) -> Option<Language> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut scores: Vec<(usize, &str, &str)> = COMMON_WORDS
+        .iter()
+        .map(|(code, name, common)| (words.iter().filter(|w| common.contains(w)).count(), *code, *name))
+        .collect();
+    scores.sort_by_key(|s| std::cmp::Reverse(s.0));
+
+    let (best, code, name) = scores[0];
+    let runner_up = scores.get(1).map_or(0, |s| s.0);
+    (best >= MIN_WORD_HITS && best > runner_up).then(|| Language::new(code, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(text: &str) -> Option<String> {
+        detect(text).map(|l| l.code)
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(code("The leaves are turning yellow and have brown spots").as_deref(), Some("en"));
+        assert_eq!(code("Mi planta tiene las hojas amarillas y está muy triste").as_deref(), Some("es"));
+        assert_eq!(code("Les feuilles de ma plante sont jaunes et tombent").as_deref(), Some("fr"));
+        assert_eq!(code("Meine Pflanze hat gelbe Blätter und die Erde ist nass").as_deref(), Some("de"));
+        assert_eq!(code("Le foglie della mia pianta sono gialle").as_deref(), Some("it"));
+        assert_eq!(code("As folhas da minha planta estão amarelas").as_deref(), Some("pt"));
+        assert_eq!(code("Mijn plant heeft gele bladeren en ze vallen af").as_deref(), Some("nl"));
+        assert_eq!(code("У моего растения желтеют листья").as_deref(), Some("ru"));
+        assert_eq!(code("葉が黄色くなってきました").as_deref(), Some("ja"));
+        assert_eq!(code("叶子变黄了").as_deref(), Some("zh"));
+
+        // Too short to tell
+        assert_eq!(code("Monstera"), None);
+        assert_eq!(code("help!!"), None);
+
+        let context = serde_json::json!({"language": {"code": "es", "name": "Spanish"}});
+        assert_eq!(Language::of_session(&context).unwrap().name, "Spanish");
+    }
+}
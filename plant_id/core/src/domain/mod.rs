@@ -0,0 +1,111 @@
+/*!
+ * DOMAIN MODELS
+ *
+ * Core business entities that represent the application domain.
+ * These are framework-agnostic and contain pure business logic.
+ */
+
+// Declare domain modules
+pub mod ai_usage;
+pub mod answer_style;
+pub mod app_import;
+pub mod audit_event;
+pub mod care_difficulty;
+pub mod care_override;
+pub mod care_schedule;
+pub mod care_schedule_version;
+pub mod care_sheet;
+pub mod care_streak;
+pub mod care_task;
+pub mod care_template;
+pub mod chat_session;
+pub mod context_budget;
+pub mod diagnosis_batch;
+pub mod diagnosis_message;
+pub mod diagnosis_session;
+pub mod diagnosis_transcript;
+pub mod encyclopedia;
+pub mod expense;
+pub mod health_assessment;
+pub mod health_index;
+pub mod feedback;
+pub mod identification;
+pub mod job;
+pub mod journal_entry;
+pub mod language;
+pub mod moisture_reading;
+pub mod notification;
+pub mod placement;
+pub mod plant;
+pub mod plant_age;
+pub mod plant_attribute;
+pub mod plant_family;
+pub mod plant_filter;
+pub mod preventive_inspection;
+pub mod problem_report;
+pub mod quarantine;
+pub mod recommendation;
+pub mod reminder;
+pub mod request_error;
+pub mod room;
+pub mod shopping_list;
+pub mod species_catalog;
+pub mod species_profile;
+pub mod timezone;
+pub mod toxicity;
+pub mod treatment_outcome;
+pub mod user;
+pub mod vacation_plan;
+pub mod wishlist;
+pub mod enums;
+pub mod validation;
+
+// Re-export domain entities
+pub use ai_usage::{ModelPrice, TokenUsage};
+pub use answer_style::{AnswerStyle, Expertise, Verbosity};
+pub use app_import::{ImportedPlant, SourceApp};
+pub use audit_event::AuditEvent;
+pub use care_difficulty::{CareDifficulty, DifficultyFilter};
+pub use care_override::{CareOverrides, MergedField};
+pub use care_schedule::{CareSchedule, CareScheduleUpdate};
+pub use care_schedule_version::{CareScheduleVersion, ScheduleChange, ScheduleSource, Staleness};
+pub use care_sheet::{CareSheet, CareSheetEntry};
+pub use care_streak::{Badge, Streak};
+pub use care_task::CareTask;
+pub use care_template::CareTemplate;
+pub use chat_session::{ChatMessage, ChatSession};
+pub use diagnosis_batch::{BatchPlant, ContainmentPlan, DiagnosisBatch};
+pub use diagnosis_message::DiagnosisMessage;
+pub use diagnosis_session::{DiagnosisFilter, DiagnosisSession, DiagnosisState, DiagnosisSummary};
+pub use expense::Expense;
+pub use feedback::Feedback;
+pub use health_assessment::{DiseaseSuggestion, HealthAssessment};
+pub use health_index::HealthIndex;
+pub use identification::{IdentificationDisagreement, SpeciesCandidate, SpeciesResolution};
+pub use job::Job;
+pub use journal_entry::JournalEntry;
+pub use language::Language;
+pub use moisture_reading::{MoistureReading, MoistureTrend};
+pub use notification::NotificationEvent;
+pub use plant::{NameMatch, Plant, PlantArchive};
+pub use plant_age::{Growth, Maturity};
+pub use plant_attribute::{AttributeFilter, PlantAttribute};
+pub use plant_family::{GroupRank, PlantGroup};
+pub use plant_filter::PlantFilter;
+pub use preventive_inspection::PreventiveInspection;
+pub use problem_report::{Incident, ProblemCluster, ProblemReport, RecurringProblem};
+pub use quarantine::Quarantine;
+pub use recommendation::Recommendation;
+pub use reminder::{Reminder, ReminderKind};
+pub use request_error::RequestError;
+pub use room::{Environment, Room, RoomProfile};
+pub use shopping_list::{ShoppingEntry, ShoppingItem};
+pub use species_profile::SpeciesProfile;
+pub use timezone::LocalZone;
+pub use treatment_outcome::TreatmentOutcome;
+pub use user::{Permission, User};
+pub use vacation_plan::VacationPlan;
+pub use wishlist::WishlistItem;
+
+// Re-export enums for easier access
+pub use enums::{ArchiveReason, AuditAction, AuditEntity, CareTaskKind, DiagnosisStatus, DiagnosisAction, ExpenseKind, FeedbackKind, JobKind, JobStatus, Severity, UserRole, WindowOrientation};
@@ -0,0 +1,229 @@
+//! SOIL MOISTURE
+//!
+//! Moisture meter readings the owner logs for a plant, and the trend drawn
+//! from them: whether the soil stays soggy or bone-dry often enough, given
+//! how often the schedule says to water, to point at chronic over- or
+//! under-watering.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::care_task::{interval_days, DEFAULT_WATERING_DAYS};
+use super::CareSchedule;
+
+/// Readings are a percentage, as shown by most moisture meters
+pub const MAX_MOISTURE: u8 = 100;
+
+/// Readings at or above this mean the soil is wet
+pub const WET_THRESHOLD: u8 = 70;
+
+/// Readings at or below this mean the soil is dry
+pub const DRY_THRESHOLD: u8 = 20;
+
+/// How far back the trend looks
+pub const TREND_WINDOW_DAYS: i64 = 30;
+
+/// Fewest readings a trend is drawn from
+pub const MIN_TREND_READINGS: usize = 3;
+
+/// Share of wet (or dry) readings from which a pattern counts as chronic
+const CHRONIC_SHARE: f64 = 0.6;
+
+/// Daily change below which moisture counts as steady, in points
+const STEADY_CHANGE_PER_DAY: f64 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoistureReading {
+    pub id: String,
+    pub plant_id: String,
+    /// Soil moisture from 0 (dry) to 100 (saturated)
+    pub value: u8,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl MoistureReading {
+    pub fn new(plant_id: String, value: u8) -> Result<Self> {
+        if value > MAX_MOISTURE {
+            anyhow::bail!("Moisture must be between 0 and {}%, got {}", MAX_MOISTURE, value);
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            value,
+            recorded_at: Utc::now(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WateringPattern {
+    Overwatering,
+    Underwatering,
+    Balanced,
+}
+
+/// Soil moisture over the last `TREND_WINDOW_DAYS`
+#[derive(Debug, Clone, Serialize)]
+pub struct MoistureTrend {
+    pub readings: usize,
+    pub wet_readings: usize,
+    pub dry_readings: usize,
+    /// Mean reading, in percent
+    pub average: f64,
+    /// Change per day over the window, negative while the soil dries out
+    pub change_per_day: f64,
+    /// Days between waterings according to the care schedule
+    pub watering_interval_days: i64,
+    pub pattern: WateringPattern,
+}
+
+impl MoistureTrend {
+    /// One-line description, e.g. "54% on average over 6 readings, drying out"
+    pub fn summary(&self) -> String {
+        let direction = if self.change_per_day.abs() < STEADY_CHANGE_PER_DAY {
+            "steady"
+        } else if self.change_per_day < 0.0 {
+            "drying out"
+        } else {
+            "getting wetter"
+        };
+
+        format!(
+            "{:.0}% on average over {} readings, {}",
+            self.average, self.readings, direction
+        )
+    }
+
+    /// Advice when the readings show a chronic watering problem
+    pub fn warning(&self) -> Option<String> {
+        match self.pattern {
+            WateringPattern::Overwatering => Some(format!(
+                "Soil was wet in {} of {} readings: watering every {} days looks too often. \
+                 Let the top of the soil dry out before watering again.",
+                self.wet_readings, self.readings, self.watering_interval_days
+            )),
+            WateringPattern::Underwatering => Some(format!(
+                "Soil was dry in {} of {} readings: watering every {} days does not seem to be enough.",
+                self.dry_readings, self.readings, self.watering_interval_days
+            )),
+            WateringPattern::Balanced => None,
+        }
+    }
+}
+
+/// Trend of the readings taken in the last `TREND_WINDOW_DAYS`, or `None`
+/// with fewer than `MIN_TREND_READINGS` of them
+pub fn analyze(
+    readings: &[MoistureReading],
+    schedule: &CareSchedule,
+    now: DateTime<Utc>,
+) -> Option<MoistureTrend> {
+    let since = now - Duration::days(TREND_WINDOW_DAYS);
+    let recent: Vec<&MoistureReading> = readings.iter().filter(|r| r.recorded_at >= since).collect();
+    if recent.len() < MIN_TREND_READINGS {
+        return None;
+    }
+
+    let count = recent.len() as f64;
+    let wet_readings = recent.iter().filter(|r| r.value >= WET_THRESHOLD).count();
+    let dry_readings = recent.iter().filter(|r| r.value <= DRY_THRESHOLD).count();
+    let average = recent.iter().map(|r| r.value as f64).sum::<f64>() / count;
+
+    // Least-squares slope of the value against the day it was taken
+    let days: Vec<f64> = recent
+        .iter()
+        .map(|r| (r.recorded_at - since).num_seconds() as f64 / 86_400.0)
+        .collect();
+    let mean_day = days.iter().sum::<f64>() / count;
+    let (covariance, variance) = recent.iter().zip(&days).fold((0.0, 0.0), |(cov, var), (r, day)| {
+        let dx = day - mean_day;
+        (cov + dx * (r.value as f64 - average), var + dx * dx)
+    });
+    // Readings taken the same day say nothing about the direction
+    let span = days.iter().cloned().fold(f64::MIN, f64::max) - days.iter().cloned().fold(f64::MAX, f64::min);
+    let change_per_day = if span >= 1.0 { covariance / variance } else { 0.0 };
+
+    let pattern = if wet_readings as f64 >= count * CHRONIC_SHARE {
+        WateringPattern::Overwatering
+    } else if dry_readings as f64 >= count * CHRONIC_SHARE {
+        WateringPattern::Underwatering
+    } else {
+        WateringPattern::Balanced
+    };
+
+    Some(MoistureTrend {
+        readings: recent.len(),
+        wet_readings,
+        dry_readings,
+        average,
+        change_per_day,
+        watering_interval_days: interval_days(&schedule.water).unwrap_or(DEFAULT_WATERING_DAYS),
+        pattern,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn readings(values: &[u8], now: DateTime<Utc>) -> Vec<MoistureReading> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| MoistureReading {
+                recorded_at: now - Duration::days((values.len() - i) as i64),
+                ..MoistureReading::new("p".to_string(), value).unwrap()
+            })
+            .collect()
+    }
+
+    fn watering(water: &str) -> CareSchedule {
+        CareSchedule {
+            water: water.to_string(),
+            ..CareSchedule::default()
+        }
+    }
+
+    #[test]
+    fn test_analyze_patterns() {
+        let now = Utc::now();
+
+        let soggy = analyze(&readings(&[85, 80, 90, 75, 40], now), &watering("Every 3 days"), now).unwrap();
+        assert_eq!(soggy.pattern, WateringPattern::Overwatering);
+        assert!(soggy.warning().unwrap().contains("every 3 days"));
+
+        let parched = analyze(&readings(&[10, 15, 60, 5], now), &watering("Every 14 days"), now).unwrap();
+        assert_eq!(parched.pattern, WateringPattern::Underwatering);
+        assert_eq!(parched.watering_interval_days, 14);
+
+        let fine = analyze(&readings(&[70, 50, 30, 65, 45], now), &watering("Weekly"), now).unwrap();
+        assert_eq!(fine.pattern, WateringPattern::Balanced);
+        assert!(fine.warning().is_none());
+
+        assert!(analyze(&readings(&[90, 90], now), &watering("Weekly"), now).is_none());
+    }
+
+    #[test]
+    fn test_trend_ignores_old_readings_and_tracks_direction() {
+        let now = Utc::now();
+        let mut history = readings(&[80, 60, 40, 20], now);
+        history.push(MoistureReading {
+            recorded_at: now - Duration::days(TREND_WINDOW_DAYS + 1),
+            ..MoistureReading::new("p".to_string(), 0).unwrap()
+        });
+
+        let trend = analyze(&history, &CareSchedule::default(), now).unwrap();
+        assert_eq!(trend.readings, 4);
+        assert!((trend.change_per_day + 20.0).abs() < 0.01);
+        assert_eq!(trend.summary(), "50% on average over 4 readings, drying out");
+    }
+
+    #[test]
+    fn test_reading_out_of_range() {
+        assert!(MoistureReading::new("p".to_string(), 101).is_err());
+    }
+}
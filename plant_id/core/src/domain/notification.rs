@@ -0,0 +1,113 @@
+//! NOTIFICATION EVENTS
+//!
+//! Things worth telling the user about outside the CLI, delivered to the
+//! configured webhooks by the event bus.
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::Severity;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    PlantAdded {
+        plant_id: String,
+        plant_name: String,
+    },
+    DiagnosisConcluded {
+        plant_id: String,
+        plant_name: String,
+        diagnosis_id: String,
+        finding: String,
+        recommendation: String,
+        severity: Option<Severity>,
+    },
+    WateringOverdue {
+        plant_id: String,
+        plant_name: String,
+        days_overdue: i64,
+    },
+    /// A run of on-time waterings reached a badge, for one plant or, without
+    /// a plant, across the collection
+    StreakMilestone {
+        plant_id: Option<String>,
+        plant_name: Option<String>,
+        streak: u32,
+        badge: String,
+    },
+}
+
+impl NotificationEvent {
+    /// Every event name
+    pub const NAMES: &'static [&'static str] =
+        &["plant_added", "diagnosis_concluded", "watering_overdue", "streak_milestone"];
+
+    /// Machine-readable event name, as sent in the `event` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PlantAdded { .. } => "plant_added",
+            Self::DiagnosisConcluded { .. } => "diagnosis_concluded",
+            Self::WateringOverdue { .. } => "watering_overdue",
+            Self::StreakMilestone { .. } => "streak_milestone",
+        }
+    }
+
+    /// Whether the event needs attention right away: a critical diagnosis.
+    /// Backends that support it deliver these with their highest priority.
+    pub fn is_urgent(&self) -> bool {
+        matches!(
+            self,
+            Self::DiagnosisConcluded {
+                severity: Some(Severity::Critical),
+                ..
+            }
+        )
+    }
+
+    /// Short headline for chat and push notifications
+    pub fn title(&self) -> String {
+        match self {
+            Self::PlantAdded { plant_name, .. } => format!("🌱 {} added", plant_name),
+            Self::DiagnosisConcluded { plant_name, .. } if self.is_urgent() => {
+                format!("🚨 Critical diagnosis for {}", plant_name)
+            }
+            Self::DiagnosisConcluded { plant_name, .. } => {
+                format!("🎯 Diagnosis for {}", plant_name)
+            }
+            Self::WateringOverdue { plant_name, .. } => format!("💧 {} needs water", plant_name),
+            Self::StreakMilestone { badge, .. } => format!("🏅 {} badge earned", badge),
+        }
+    }
+
+    /// Human-readable body
+    pub fn message(&self) -> String {
+        match self {
+            Self::PlantAdded { plant_name, .. } => {
+                format!("{} was added to your collection.", plant_name)
+            }
+            Self::DiagnosisConcluded {
+                finding,
+                recommendation,
+                ..
+            } => format!("{}: {}", finding, recommendation),
+            Self::WateringOverdue {
+                plant_name,
+                days_overdue,
+                ..
+            } => format!(
+                "Watering {} is overdue by {} day{}.",
+                plant_name,
+                days_overdue,
+                if *days_overdue == 1 { "" } else { "s" }
+            ),
+            Self::StreakMilestone {
+                plant_name: Some(plant_name),
+                streak,
+                ..
+            } => format!("{} on-time waterings in a row for {}. Keep it up!", streak, plant_name),
+            Self::StreakMilestone { streak, .. } => {
+                format!("{} on-time waterings in a row across your collection. Keep it up!", streak)
+            }
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! PLACEMENT
+//!
+//! A coarse light model shared by plants and rooms. A plant's needs are
+//! read from the light line of its care schedule as a range of levels
+//! ("low to bright indirect"); a room's level comes from its window and
+//! hours of sun. Plants whose room falls outside their range are advised to
+//! move to the room that suits them best.
+
+use serde::Serialize;
+use std::fmt;
+
+use super::{Plant, Room};
+
+/// How much light a spot gets, from darkest to sunniest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LightLevel {
+    Low,
+    Medium,
+    /// Bright, indirect or only briefly direct
+    Bright,
+    /// Several hours of direct sun
+    Direct,
+}
+
+impl fmt::Display for LightLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Low => "low light",
+            Self::Medium => "medium light",
+            Self::Bright => "bright indirect light",
+            Self::Direct => "direct sun",
+        })
+    }
+}
+
+/// The light levels a plant does well in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LightRequirement {
+    pub min: LightLevel,
+    pub max: LightLevel,
+}
+
+impl LightRequirement {
+    /// Read a care schedule's light line, e.g. "Bright indirect light,
+    /// tolerates low light". None when it names no level.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.to_lowercase();
+        let words: Vec<&str> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        let previous = |i: usize| if i > 0 { words[i - 1] } else { "" };
+
+        let mut levels = Vec::new();
+        for (i, word) in words.iter().enumerate() {
+            let level = match *word {
+                "shade" | "shady" if matches!(previous(i), "partial" | "part" | "light") => {
+                    Some(LightLevel::Medium)
+                }
+                "low" | "shade" | "shady" => Some(LightLevel::Low),
+                "medium" | "moderate" | "filtered" | "dappled" | "partial" | "part" => {
+                    Some(LightLevel::Medium)
+                }
+                "bright" => Some(LightLevel::Bright),
+                "sun" if previous(i) == "full" => Some(LightLevel::Direct),
+                "sunny" => Some(LightLevel::Direct),
+                "direct" if !matches!(previous(i), "no" | "avoid" | "without" | "not") => {
+                    Some(LightLevel::Direct)
+                }
+                _ => None,
+            };
+            levels.extend(level);
+        }
+
+        Some(Self {
+            min: *levels.iter().min()?,
+            max: *levels.iter().max()?,
+        })
+    }
+
+    pub fn accepts(&self, level: LightLevel) -> bool {
+        (self.min..=self.max).contains(&level)
+    }
+
+    /// How many levels `level` is outside the range
+    fn distance(&self, level: LightLevel) -> u8 {
+        if level < self.min {
+            self.min as u8 - level as u8
+        } else if level > self.max {
+            level as u8 - self.max as u8
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for LightRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{} to {}", self.min, self.max)
+        }
+    }
+}
+
+/// How a plant's current spot suits it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Fit {
+    Fits,
+    TooDark,
+    TooBright,
+    /// The plant has no room, or its room's light was never recorded
+    UnknownRoom,
+    /// The care schedule does not say how much light the plant needs
+    UnknownNeeds,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlacementAdvice {
+    pub plant_id: String,
+    pub plant_name: String,
+    pub requirement: Option<LightRequirement>,
+    pub room: Option<String>,
+    pub light: Option<LightLevel>,
+    pub fit: Fit,
+    /// Better room for the plant, if one of the recorded rooms is
+    pub suggested_room: Option<String>,
+    pub suggested_light: Option<LightLevel>,
+    /// Why the move helps, when the AI explained it
+    pub explanation: Option<String>,
+}
+
+impl PlacementAdvice {
+    pub fn is_move(&self) -> bool {
+        self.suggested_room.is_some()
+    }
+}
+
+/// Match every plant's light needs against the rooms
+pub fn advise(plants: &[Plant], rooms: &[Room]) -> Vec<PlacementAdvice> {
+    plants.iter().map(|plant| advise_plant(plant, rooms)).collect()
+}
+
+fn advise_plant(plant: &Plant, rooms: &[Room]) -> PlacementAdvice {
+    let requirement = LightRequirement::parse(&plant.care_schedule.light);
+    let current = plant
+        .room
+        .as_ref()
+        .and_then(|name| rooms.iter().find(|r| r.name.eq_ignore_ascii_case(name)));
+    let light = current.map(Room::light_level);
+
+    let fit = match (requirement, light) {
+        (None, _) => Fit::UnknownNeeds,
+        (Some(_), None) => Fit::UnknownRoom,
+        (Some(r), Some(level)) if level < r.min => Fit::TooDark,
+        (Some(r), Some(level)) if level > r.max => Fit::TooBright,
+        (Some(_), Some(_)) => Fit::Fits,
+    };
+
+    // The closest room to the requirement, if it beats the current one
+    let suggestion = requirement.filter(|_| fit != Fit::Fits).and_then(|requirement| {
+        let current_distance = light.map(|l| requirement.distance(l));
+        rooms
+            .iter()
+            .filter(|room| current.map(|c| c.id != room.id).unwrap_or(true))
+            .min_by_key(|room| requirement.distance(room.light_level()))
+            .filter(|room| {
+                let distance = requirement.distance(room.light_level());
+                current_distance.map(|d| distance < d).unwrap_or(distance == 0)
+            })
+    });
+
+    PlacementAdvice {
+        plant_id: plant.id.clone(),
+        plant_name: plant.display_name().to_string(),
+        requirement,
+        room: plant.room.clone(),
+        light,
+        fit,
+        suggested_room: suggestion.map(|r| r.name.clone()),
+        suggested_light: suggestion.map(Room::light_level),
+        explanation: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::enums::WindowOrientation;
+    use crate::domain::CareSchedule;
+
+    fn plant(light: &str, room: Option<&str>) -> Plant {
+        let mut plant = Plant::new(
+            "u".to_string(),
+            "Fern".to_string(),
+            CareSchedule {
+                light: light.to_string(),
+                ..Default::default()
+            },
+        );
+        plant.room = room.map(str::to_string);
+        plant
+    }
+
+    #[test]
+    fn test_parse_light_requirement() {
+        let parse = |text| LightRequirement::parse(text).map(|r| (r.min, r.max));
+
+        assert_eq!(parse("Bright indirect light"), Some((LightLevel::Bright, LightLevel::Bright)));
+        assert_eq!(
+            parse("Bright, indirect; tolerates low light"),
+            Some((LightLevel::Low, LightLevel::Bright))
+        );
+        assert_eq!(parse("Full sun to partial shade"), Some((LightLevel::Medium, LightLevel::Direct)));
+        assert_eq!(parse("Medium light, no direct sun"), Some((LightLevel::Medium, LightLevel::Medium)));
+        assert_eq!(parse("Shade"), Some((LightLevel::Low, LightLevel::Low)));
+        assert_eq!(parse("Keep near a window"), None);
+    }
+
+    #[test]
+    fn test_advise_moves() {
+        let rooms = vec![
+            Room::new("u", "Hallway", None, None).unwrap(),
+            Room::new("u", "Kitchen", Some(WindowOrientation::East), None).unwrap(),
+            Room::new("u", "Balcony", Some(WindowOrientation::South), Some(8.0)).unwrap(),
+        ];
+
+        let dark = advise_plant(&plant("Bright indirect light", Some("hallway")), &rooms);
+        assert_eq!(dark.fit, Fit::TooDark);
+        assert_eq!(dark.suggested_room.as_deref(), Some("Kitchen"));
+
+        let scorched = advise_plant(&plant("Low to medium light", Some("Balcony")), &rooms);
+        assert_eq!(scorched.fit, Fit::TooBright);
+        assert_eq!(scorched.suggested_room.as_deref(), Some("Hallway"));
+
+        let fine = advise_plant(&plant("Full sun", Some("Balcony")), &rooms);
+        assert_eq!(fine.fit, Fit::Fits);
+        assert!(!fine.is_move());
+
+        let homeless = advise_plant(&plant("Full sun", None), &rooms);
+        assert_eq!(homeless.fit, Fit::UnknownRoom);
+        assert_eq!(homeless.suggested_room.as_deref(), Some("Balcony"));
+
+        let unknown = advise_plant(&plant("", Some("Kitchen")), &rooms);
+        assert_eq!(unknown.fit, Fit::UnknownNeeds);
+        assert!(!unknown.is_move());
+    }
+}
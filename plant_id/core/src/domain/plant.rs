@@ -0,0 +1,201 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::care_difficulty::CareDifficulty;
+use super::care_schedule::CareSchedule;
+use super::enums::ArchiveReason;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Plant {
+    pub id: String,
+    pub user_id: String,
+    /// The species' name: its first common name when known
+    pub name: String,
+    /// What the owner calls this plant ("Bob"), shown instead of the name
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// Botanical name from identification ("Monstera deliciosa")
+    #[serde(default)]
+    pub scientific_name: Option<String>,
+    /// Everyday names from identification ("Swiss cheese plant")
+    #[serde(default)]
+    pub common_names: Vec<String>,
+    pub care_schedule: CareSchedule,
+    pub image_url: Option<String>,
+    /// SHA-256 of the photo the plant was added from
+    #[serde(default)]
+    pub image_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set once the plant has left the collection
+    #[serde(default)]
+    pub archived: Option<PlantArchive>,
+    /// Room or spot the plant lives in ("balcony", "kitchen window")
+    #[serde(default)]
+    pub room: Option<String>,
+    /// Free-form labels, lowercase and without duplicates
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// How demanding the plant is, when the AI could rate it
+    #[serde(default)]
+    pub difficulty: Option<CareDifficulty>,
+    /// Botanical family from the species database ("Araceae")
+    #[serde(default)]
+    pub family: Option<String>,
+    /// When the owner got the plant, when that was before it was added
+    #[serde(default)]
+    pub acquired_at: Option<DateTime<Utc>>,
+    /// Where the plant came from ("garden center", "cutting from a friend")
+    #[serde(default)]
+    pub acquired_from: Option<String>,
+}
+
+/// How an identifier given on the command line names a plant. The order
+/// is the order of preference when several plants match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NameMatch {
+    Nickname,
+    Name,
+    ScientificName,
+    CommonName,
+}
+
+/// Record of a plant leaving the collection
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlantArchive {
+    pub reason: ArchiveReason,
+    pub archived_at: DateTime<Utc>,
+}
+
+impl Plant {
+    pub fn new(user_id: String, name: String, care_schedule: CareSchedule) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            name,
+            nickname: None,
+            scientific_name: None,
+            common_names: Vec::new(),
+            care_schedule,
+            image_url: None,
+            image_hash: None,
+            created_at: now,
+            updated_at: now,
+            archived: None,
+            room: None,
+            tags: Vec::new(),
+            difficulty: None,
+            family: None,
+            acquired_at: None,
+            acquired_from: None,
+        }
+    }
+
+    /// Add a tag, returning whether the plant did not have it yet
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() || self.tags.contains(&tag) {
+            return false;
+        }
+        self.tags.push(tag);
+        true
+    }
+
+    /// Remove a tag, returning whether the plant had it
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let tag = normalize_tag(tag);
+        let before = self.tags.len();
+        self.tags.retain(|t| *t != tag);
+        self.tags.len() != before
+    }
+
+    /// The species, to look up and to group plants by: the scientific name,
+    /// or the name of plants identified before scientific names were kept
+    pub fn species(&self) -> &str {
+        self.scientific_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The name to show: the nickname when there is one
+    pub fn display_name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Set or clear the nickname, ignoring surrounding whitespace
+    pub fn set_nickname(&mut self, nickname: Option<&str>) {
+        self.nickname = nickname.map(str::trim).filter(|n| !n.is_empty()).map(str::to_string);
+    }
+
+    /// Whether the plant is known by `name`, ignoring case, and how
+    pub fn name_match(&self, name: &str) -> Option<NameMatch> {
+        let name = name.trim();
+        if self.nickname.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)) {
+            Some(NameMatch::Nickname)
+        } else if self.name.eq_ignore_ascii_case(name) {
+            Some(NameMatch::Name)
+        } else if self.scientific_name.as_deref().is_some_and(|s| s.eq_ignore_ascii_case(name)) {
+            Some(NameMatch::ScientificName)
+        } else if self.common_names.iter().any(|c| c.eq_ignore_ascii_case(name)) {
+            Some(NameMatch::CommonName)
+        } else {
+            None
+        }
+    }
+
+    /// When the owner got the plant: when it was acquired, or else added
+    pub fn acquired(&self) -> DateTime<Utc> {
+        self.acquired_at.unwrap_or(self.created_at)
+    }
+
+    /// Set or clear where the plant came from, ignoring surrounding whitespace
+    pub fn set_acquired_from(&mut self, from: Option<&str>) {
+        self.acquired_from = from.map(str::trim).filter(|f| !f.is_empty()).map(str::to_string);
+    }
+
+    /// Days between joining and leaving the collection (or now, if still active)
+    pub fn lifespan_days(&self) -> i64 {
+        let end = self.archived.as_ref().map_or_else(Utc::now, |a| a.archived_at);
+        (end - self.acquired()).num_days()
+    }
+}
+
+/// Tags are compared and stored trimmed and lowercase
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_match_prefers_own_name() {
+        let mut plant = Plant::new("u".to_string(), "Swiss cheese plant".to_string(), CareSchedule::default());
+        plant.scientific_name = Some("Monstera deliciosa".to_string());
+        plant.common_names = vec!["Swiss cheese plant".to_string(), "Split-leaf philodendron".to_string()];
+
+        assert_eq!(plant.name_match("swiss cheese plant"), Some(NameMatch::Name));
+        assert_eq!(plant.name_match("MONSTERA DELICIOSA"), Some(NameMatch::ScientificName));
+        assert_eq!(plant.name_match("split-leaf philodendron"), Some(NameMatch::CommonName));
+        assert_eq!(plant.name_match("Monstera"), None);
+        assert_eq!(plant.species(), "Monstera deliciosa");
+
+        plant.scientific_name = None;
+        assert_eq!(plant.species(), "Swiss cheese plant");
+    }
+
+    #[test]
+    fn test_nickname_comes_first() {
+        let mut plant = Plant::new("u".to_string(), "Swiss cheese plant".to_string(), CareSchedule::default());
+        assert_eq!(plant.display_name(), "Swiss cheese plant");
+
+        plant.set_nickname(Some("  "));
+        assert_eq!(plant.nickname, None);
+        plant.set_nickname(Some(" Bob "));
+        assert_eq!(plant.display_name(), "Bob");
+        assert_eq!(plant.name_match("bob"), Some(NameMatch::Nickname));
+        assert_eq!(plant.name_match("swiss cheese plant"), Some(NameMatch::Name));
+    }
+}
@@ -0,0 +1,139 @@
+//! PLANT AGE
+//!
+//! How long the owner has had a plant and, from where it came from, how
+//! mature it probably is: a plant grown from seed a few months ago is a
+//! seedling, while one bought at a garden center is usually established.
+//! The AI is told so when it writes a care schedule, as seedlings and fresh
+//! cuttings need gentler watering and feeding than mature specimens.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::Plant;
+
+/// Words in `acquired_from` telling the plant was grown from seed
+const FROM_SEED: &[&str] = &["seed", "sown", "sowed"];
+
+/// Words in `acquired_from` telling the plant was propagated from another
+const FROM_PROPAGATION: &[&str] = &["cutting", "propagat", "division", "divided", "offset", "pup", "plantlet", "runner"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Maturity {
+    Seedling,
+    /// A young plant, or a rooted cutting still growing in
+    Young,
+    Mature,
+}
+
+impl Maturity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Seedling => "seedling",
+            Self::Young => "young plant",
+            Self::Mature => "mature specimen",
+        }
+    }
+}
+
+/// What is known about a plant's age, for the AI
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Growth {
+    /// Days the owner has had the plant
+    pub days_owned: i64,
+    pub acquired_from: Option<String>,
+}
+
+impl Growth {
+    pub fn of(plant: &Plant, now: DateTime<Utc>) -> Self {
+        Self::new(plant.acquired(), plant.acquired_from.clone(), now)
+    }
+
+    pub fn new(acquired_at: DateTime<Utc>, acquired_from: Option<String>, now: DateTime<Utc>) -> Self {
+        Self {
+            days_owned: (now - acquired_at).num_days().max(0),
+            acquired_from,
+        }
+    }
+
+    /// How mature the plant probably is, when that can be told
+    pub fn maturity(&self) -> Option<Maturity> {
+        let from = self.acquired_from.as_deref().unwrap_or_default().to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|w| from.contains(w));
+
+        if mentions(FROM_SEED) {
+            Some(match self.days_owned {
+                ..=179 => Maturity::Seedling,
+                180..=729 => Maturity::Young,
+                _ => Maturity::Mature,
+            })
+        } else if mentions(FROM_PROPAGATION) {
+            Some(if self.days_owned < 365 { Maturity::Young } else { Maturity::Mature })
+        } else {
+            // Bought plants are usually established, but their age is unknown
+            (self.days_owned >= 730).then_some(Maturity::Mature)
+        }
+    }
+
+    /// One line about the specimen, such as "owned for 3 months, grown from
+    /// seed, likely a seedling"
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("owned for {}", describe_age(self.days_owned))];
+        if let Some(from) = &self.acquired_from {
+            parts.push(format!("acquired from: {}", from));
+        }
+        if let Some(maturity) = self.maturity() {
+            parts.push(format!("likely a {}", maturity.as_str()));
+        }
+        parts.join(", ")
+    }
+}
+
+/// A number of days as a person would say it: "5 days", "3 weeks",
+/// "4 months", "2 years 1 month"
+pub fn describe_age(days: i64) -> String {
+    let plural = |n: i64, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    match days {
+        ..=0 => "less than a day".to_string(),
+        1..=13 => plural(days, "day"),
+        14..=60 => plural(days / 7, "week"),
+        61..=364 => plural(days / 30, "month"),
+        _ => {
+            let (years, months) = (days / 365, (days % 365) / 30);
+            if months == 0 {
+                plural(years, "year")
+            } else {
+                format!("{} {}", plural(years, "year"), plural(months, "month"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_maturity_from_age_and_origin() {
+        let now = Utc::now();
+        let growth = |days: i64, from: Option<&str>| Growth::new(now - Duration::days(days), from.map(str::to_string), now);
+
+        assert_eq!(growth(60, Some("Grown from seed")).maturity(), Some(Maturity::Seedling));
+        assert_eq!(growth(400, Some("seeds from a friend")).maturity(), Some(Maturity::Young));
+        assert_eq!(growth(90, Some("Cutting from mom's plant")).maturity(), Some(Maturity::Young));
+        assert_eq!(growth(500, Some("cutting")).maturity(), Some(Maturity::Mature));
+        assert_eq!(growth(90, Some("Garden center")).maturity(), None);
+        assert_eq!(growth(800, None).maturity(), Some(Maturity::Mature));
+
+        assert_eq!(
+            growth(95, Some("seed")).describe(),
+            "owned for 3 months, acquired from: seed, likely a seedling"
+        );
+        assert_eq!(describe_age(0), "less than a day");
+        assert_eq!(describe_age(1), "1 day");
+        assert_eq!(describe_age(20), "2 weeks");
+        assert_eq!(describe_age(400), "1 year 1 month");
+        assert_eq!(describe_age(730), "2 years");
+    }
+}
@@ -0,0 +1,151 @@
+//! PLANT ATTRIBUTES
+//!
+//! Free-form facts the owner keeps about a plant (pot size, soil mix,
+//! purchase price, where it was bought...) as key/value pairs. Keys are
+//! normalized to lower_snake_case, so `Pot Size` and `pot_size` are the same
+//! field; values are kept as typed.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const MAX_KEY_LEN: usize = 40;
+pub const MAX_VALUE_LEN: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlantAttribute {
+    pub id: String,
+    pub plant_id: String,
+    pub key: String,
+    pub value: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PlantAttribute {
+    pub fn new(plant_id: &str, key: &str, value: &str) -> Result<Self> {
+        let key = normalize_key(key)?;
+        let value = value.trim();
+        if value.is_empty() {
+            anyhow::bail!("The value of '{}' is empty", key);
+        }
+        if value.chars().count() > MAX_VALUE_LEN {
+            anyhow::bail!("The value of '{}' is longer than {} characters", key, MAX_VALUE_LEN);
+        }
+
+        Ok(Self {
+            id: attribute_id(plant_id, &key),
+            plant_id: plant_id.to_string(),
+            key,
+            value: value.to_string(),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// ID of a plant's attribute, derived from the plant and key so every
+/// device agrees on it
+pub fn attribute_id(plant_id: &str, key: &str) -> String {
+    let name = format!("{}:attribute:{}", plant_id, key);
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string()
+}
+
+/// Turn a key such as "Pot Size" into "pot_size"
+pub fn normalize_key(key: &str) -> Result<String> {
+    let key = key
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    if key.is_empty() {
+        anyhow::bail!("Attribute name is empty");
+    }
+    if !key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        anyhow::bail!("Attribute name '{}' may only contain letters, digits and underscores", key);
+    }
+    if key.chars().count() > MAX_KEY_LEN {
+        anyhow::bail!("Attribute name '{}' is longer than {} characters", key, MAX_KEY_LEN);
+    }
+
+    Ok(key)
+}
+
+/// Selects plants by their attributes with `key=value` conditions, all of
+/// which must match. Values are compared case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttributeFilter {
+    conditions: Vec<(String, String)>,
+}
+
+impl AttributeFilter {
+    /// Parse `key=value` expressions; several conditions can also be given
+    /// in one expression, separated by commas
+    pub fn parse<S: AsRef<str>>(expressions: &[S]) -> Result<Self> {
+        let mut conditions = Vec::new();
+
+        for expression in expressions {
+            for part in expression.as_ref().split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let (key, value) = part.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!("Invalid condition '{}': expected key=value", part)
+                })?;
+                let value = value.trim();
+                if value.is_empty() {
+                    anyhow::bail!("Invalid condition '{}': missing value", part);
+                }
+                conditions.push((normalize_key(key)?, value.to_lowercase()));
+            }
+        }
+
+        Ok(Self { conditions })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Whether a plant with these attributes matches
+    pub fn matches(&self, attributes: &[PlantAttribute]) -> bool {
+        self.conditions.iter().all(|(key, value)| {
+            attributes
+                .iter()
+                .any(|a| a.key == *key && a.value.to_lowercase() == *value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_key() {
+        assert_eq!(normalize_key(" Pot Size ").unwrap(), "pot_size");
+        assert_eq!(normalize_key("soil-mix").unwrap(), "soil_mix");
+        assert!(normalize_key("  ").is_err());
+        assert!(normalize_key("price ($)").is_err());
+
+        let attribute = PlantAttribute::new("p1", "Pot size", " 14cm ").unwrap();
+        assert_eq!(attribute.value, "14cm");
+        assert_eq!(attribute.id, PlantAttribute::new("p1", "pot_size", "16cm").unwrap().id);
+        assert!(PlantAttribute::new("p1", "pot_size", "").is_err());
+    }
+
+    #[test]
+    fn test_attribute_filter() {
+        let attributes = vec![
+            PlantAttribute::new("p1", "pot_size", "14cm").unwrap(),
+            PlantAttribute::new("p1", "source_shop", "Green Corner").unwrap(),
+        ];
+
+        let filter = AttributeFilter::parse(&["pot_size=14CM", "source shop=green corner"]).unwrap();
+        assert!(filter.matches(&attributes));
+        assert!(!AttributeFilter::parse(&["pot_size=16cm"]).unwrap().matches(&attributes));
+        assert!(!AttributeFilter::parse(&["soil_mix=peat"]).unwrap().matches(&attributes));
+
+        assert!(AttributeFilter::parse(&["pot_size"]).is_err());
+        assert!(AttributeFilter::parse::<&str>(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+//! PLANT FAMILIES
+//!
+//! Groups the collection by botanical family, from the species database,
+//! or by genus, from the scientific name, for plants the database does not
+//! know. Relatives share pests and diseases, so a pest or disease found on
+//! one plant is a reason to look over the rest of its group.
+
+use serde::Serialize;
+
+use super::Plant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupRank {
+    Family,
+    Genus,
+}
+
+/// The plants of a family or genus
+#[derive(Debug, Clone, Serialize)]
+pub struct PlantGroup {
+    pub rank: GroupRank,
+    /// "Araceae", or "Monstera" for a genus
+    pub name: String,
+    pub plants: Vec<Plant>,
+}
+
+impl Plant {
+    /// The genus: the first word of the scientific name
+    pub fn genus(&self) -> Option<&str> {
+        self.scientific_name
+            .as_deref()
+            .and_then(|name| name.split_whitespace().next())
+            .filter(|genus| genus.chars().next().is_some_and(char::is_uppercase))
+    }
+
+    /// The group the plant belongs to: its family when known, else its genus
+    pub fn group(&self) -> Option<(GroupRank, &str)> {
+        match (&self.family, self.genus()) {
+            (Some(family), _) => Some((GroupRank::Family, family.as_str())),
+            (None, Some(genus)) => Some((GroupRank::Genus, genus)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Whether two plants are of the same family, or failing that, genus
+pub fn are_related(a: &Plant, b: &Plant) -> bool {
+    match (&a.family, &b.family) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        _ => a.genus().is_some_and(|genus| b.genus().is_some_and(|other| genus.eq_ignore_ascii_case(other))),
+    }
+}
+
+/// The other plants related to `plant`
+pub fn relatives<'a>(plant: &Plant, plants: &'a [Plant]) -> Vec<&'a Plant> {
+    plants
+        .iter()
+        .filter(|other| other.id != plant.id && are_related(plant, other))
+        .collect()
+}
+
+/// Plants grouped by family, or genus when the family is unknown, largest
+/// group first. Plants in neither are left out.
+pub fn group(plants: &[Plant]) -> Vec<PlantGroup> {
+    let mut groups: Vec<PlantGroup> = Vec::new();
+    for plant in plants {
+        let Some((rank, name)) = plant.group() else {
+            continue;
+        };
+        match groups
+            .iter_mut()
+            .find(|g| g.rank == rank && g.name.eq_ignore_ascii_case(name))
+        {
+            Some(group) => group.plants.push(plant.clone()),
+            None => groups.push(PlantGroup {
+                rank,
+                name: name.to_string(),
+                plants: vec![plant.clone()],
+            }),
+        }
+    }
+
+    groups.sort_by(|a, b| {
+        b.plants
+            .len()
+            .cmp(&a.plants.len())
+            .then_with(|| a.rank.cmp(&b.rank))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+
+    fn plant(scientific_name: Option<&str>, family: Option<&str>) -> Plant {
+        let mut plant = Plant::new("u".to_string(), "Plant".to_string(), CareSchedule::default());
+        plant.scientific_name = scientific_name.map(str::to_string);
+        plant.family = family.map(str::to_string);
+        plant
+    }
+
+    #[test]
+    fn test_group_by_family_then_genus() {
+        let monstera = plant(Some("Monstera deliciosa"), Some("Araceae"));
+        let pothos = plant(Some("Epipremnum aureum"), Some("Araceae"));
+        let fig = plant(Some("Ficus lyrata"), None);
+        let rubber = plant(Some("Ficus elastica"), None);
+        let unknown = plant(None, None);
+        let plants = vec![monstera.clone(), fig.clone(), pothos.clone(), rubber, unknown];
+
+        let groups = group(&plants);
+        assert_eq!(groups.len(), 2);
+        assert_eq!((groups[0].rank, groups[0].name.as_str()), (GroupRank::Family, "Araceae"));
+        assert_eq!((groups[1].rank, groups[1].name.as_str()), (GroupRank::Genus, "Ficus"));
+        assert_eq!(groups[1].plants.len(), 2);
+
+        let related: Vec<&str> = relatives(&monstera, &plants).iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(related, vec![pothos.id.as_str()]);
+        assert_eq!(relatives(&fig, &plants).len(), 1);
+        assert_eq!(plant(Some("cultivar"), None).genus(), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! PLANT FILTER
+//!
+//! Selects plants with `key=value` conditions, all of which must match:
+//! - `room=<room>`: plants in that room (`room=none` for plants without one)
+//! - `tag=<tag>`: plants with that tag
+//! - `name=<text>`: plants whose name contains the text
+//! - `id=<id>`: one plant, by ID or the start of it
+//!
+//! Values are compared case-insensitively.
+
+use anyhow::Result;
+
+use super::plant::normalize_tag;
+use super::Plant;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Room(Option<String>),
+    Tag(String),
+    Name(String),
+    Id(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlantFilter {
+    conditions: Vec<Condition>,
+}
+
+impl PlantFilter {
+    /// Parse `key=value` expressions; several conditions can also be given
+    /// in one expression, separated by commas
+    pub fn parse<S: AsRef<str>>(expressions: &[S]) -> Result<Self> {
+        let mut conditions = Vec::new();
+
+        for expression in expressions {
+            for part in expression.as_ref().split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let (key, value) = part.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!("Invalid filter '{}': expected key=value", part)
+                })?;
+                let value = value.trim();
+                if value.is_empty() {
+                    anyhow::bail!("Invalid filter '{}': missing value", part);
+                }
+
+                conditions.push(match key.trim().to_lowercase().as_str() {
+                    "room" if value.eq_ignore_ascii_case("none") => Condition::Room(None),
+                    "room" => Condition::Room(Some(value.to_lowercase())),
+                    "tag" => Condition::Tag(normalize_tag(value)),
+                    "name" => Condition::Name(value.to_lowercase()),
+                    "id" => Condition::Id(value.to_string()),
+                    other => anyhow::bail!(
+                        "Unknown filter key '{}': expected room, tag, name or id",
+                        other
+                    ),
+                });
+            }
+        }
+
+        Ok(Self { conditions })
+    }
+
+    /// Whether the filter has no conditions (and so matches every plant)
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    pub fn matches(&self, plant: &Plant) -> bool {
+        self.conditions.iter().all(|condition| match condition {
+            Condition::Room(room) => {
+                plant.room.as_ref().map(|r| r.to_lowercase()) == *room
+            }
+            Condition::Tag(tag) => plant.tags.contains(tag),
+            Condition::Name(text) => {
+                plant.name.to_lowercase().contains(text)
+                    || plant.nickname.as_ref().is_some_and(|n| n.to_lowercase().contains(text))
+            }
+            Condition::Id(id) => plant.id.starts_with(id.as_str()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+
+    fn plant(name: &str, room: Option<&str>, tags: &[&str]) -> Plant {
+        let mut plant = Plant::new("u".to_string(), name.to_string(), CareSchedule::default());
+        plant.room = room.map(str::to_string);
+        for tag in tags {
+            plant.add_tag(tag);
+        }
+        plant
+    }
+
+    #[test]
+    fn test_filter_conditions_must_all_match() {
+        let filter = PlantFilter::parse(&["room=Balcony", "tag=herb"]).unwrap();
+
+        assert!(filter.matches(&plant("Basil", Some("balcony"), &["Herb"])));
+        assert!(!filter.matches(&plant("Mint", Some("balcony"), &[])));
+        assert!(!filter.matches(&plant("Thyme", Some("kitchen"), &["herb"])));
+    }
+
+    #[test]
+    fn test_filter_parsing() {
+        let filter = PlantFilter::parse(&["name=fern, room=none"]).unwrap();
+        assert!(filter.matches(&plant("Boston Fern", None, &[])));
+        assert!(!filter.matches(&plant("Boston Fern", Some("bathroom"), &[])));
+        let mut nicknamed = plant("Nephrolepis exaltata", None, &[]);
+        nicknamed.set_nickname(Some("Fernando"));
+        assert!(filter.matches(&nicknamed));
+
+        assert!(PlantFilter::parse(&["room"]).is_err());
+        assert!(PlantFilter::parse(&["color=green"]).is_err());
+        assert!(PlantFilter::parse::<&str>(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+//! PREVENTIVE INSPECTION
+//!
+//! A one-off reminder to look a plant over for a pest or disease found on a
+//! relative of it, before it has had time to spread. Unlike a quarantine
+//! inspection it does not recur: it is done once and closed.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreventiveInspection {
+    pub id: String,
+    pub plant_id: String,
+    /// What to look for, e.g. "Spider mites (found on Calathea)"
+    pub reason: String,
+    /// The diagnosis of the relative that found the pest or disease
+    pub source_diagnosis_id: Option<String>,
+    pub due_on: NaiveDate,
+    pub created_at: DateTime<Utc>,
+    pub done_at: Option<DateTime<Utc>>,
+}
+
+impl PreventiveInspection {
+    pub fn new(plant_id: String, reason: String, source_diagnosis_id: Option<String>, due_on: NaiveDate) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            reason,
+            source_diagnosis_id,
+            due_on,
+            created_at: Utc::now(),
+            done_at: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.done_at.is_none()
+    }
+}
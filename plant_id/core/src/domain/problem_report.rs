@@ -0,0 +1,291 @@
+//! PROBLEM REPORT
+//!
+//! Problems that keep coming back, from the findings of every concluded
+//! diagnosis. Findings are grouped when they name the same encyclopedia
+//! entry ("Fungus gnat infestation", "fungus gnats in the topsoil") or share
+//! most of their words ("Root rot", "Root rot from overwatering"). A problem
+//! recurs when it was found at least twice on one plant, on one species or in
+//! one room; species and rooms only count when more than one plant had it,
+//! as a single plant's problems are already reported for that plant.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Times a problem must be found in one place to recur
+pub const MIN_RECURRENCES: usize = 2;
+
+/// Share of words two findings must have in common to be the same problem
+const MIN_SIMILARITY: f64 = 0.5;
+
+/// Words that say how bad or how likely a problem is, not what it is
+const FILLER_WORDS: &[&str] = &[
+    "and", "the", "from", "with", "due", "caused", "possible", "possibly", "likely", "probable", "probably",
+    "suspected", "mild", "moderate", "severe", "early", "late", "sign", "stage", "minor", "some",
+];
+
+/// A concluded diagnosis that found a problem
+#[derive(Debug, Clone, Serialize)]
+pub struct Incident {
+    pub session_id: String,
+    pub plant_id: String,
+    pub plant_name: String,
+    pub species: String,
+    pub room: Option<String>,
+    pub finding: String,
+    /// Encyclopedia entry the finding was linked to, if any
+    pub entry: Option<String>,
+    pub concluded_at: DateTime<Utc>,
+}
+
+/// Findings judged to be the same problem
+#[derive(Debug, Clone, Serialize)]
+pub struct ProblemCluster {
+    /// The entry's name, or else the most common wording of the finding
+    pub problem: String,
+    /// Oldest first
+    pub incidents: Vec<Incident>,
+}
+
+impl ProblemCluster {
+    /// Names of the plants that had the problem, in the order first found
+    pub fn plant_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for incident in &self.incidents {
+            if !names.contains(&incident.plant_name.as_str()) {
+                names.push(&incident.plant_name);
+            }
+        }
+        names
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Plant,
+    Species,
+    Room,
+}
+
+/// A problem found again and again on one plant, species or room
+#[derive(Debug, Clone, Serialize)]
+pub struct RecurringProblem {
+    pub problem: String,
+    pub scope: Scope,
+    /// The plant's name, the species or the room
+    pub name: String,
+    pub incidents: usize,
+    pub plants: usize,
+    pub last_seen: DateTime<Utc>,
+}
+
+impl RecurringProblem {
+    /// E.g. "3 incidents of fungus gnats in the bathroom"
+    pub fn describe(&self) -> String {
+        let place = match self.scope {
+            Scope::Plant => format!("on {}", self.name),
+            Scope::Species => format!("on {} plants", self.name),
+            Scope::Room => format!("in the {}", self.name.to_lowercase()),
+        };
+        format!("{} incidents of {} {}", self.incidents, self.problem.to_lowercase(), place)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProblemReport {
+    pub since: DateTime<Utc>,
+    /// Concluded diagnoses that found a problem
+    pub diagnoses: usize,
+    /// Most often found first
+    pub problems: Vec<ProblemCluster>,
+    /// Most incidents first
+    pub recurring: Vec<RecurringProblem>,
+}
+
+impl ProblemReport {
+    pub fn new(mut incidents: Vec<Incident>, since: DateTime<Utc>) -> Self {
+        incidents.sort_by_key(|incident| incident.concluded_at);
+        let diagnoses = incidents.len();
+
+        let mut groups: Vec<Group> = Vec::new();
+        for incident in incidents {
+            let words = finding_words(&incident.finding);
+            let existing = groups.iter_mut().find(|group| match &incident.entry {
+                Some(name) => group.entry.as_ref().is_some_and(|e| e.eq_ignore_ascii_case(name)),
+                None => group.entry.is_none() && group.words.iter().any(|w| similar(w, &words)),
+            });
+            match existing {
+                Some(group) => {
+                    group.words.push(words);
+                    group.incidents.push(incident);
+                }
+                None => groups.push(Group {
+                    entry: incident.entry.clone(),
+                    words: vec![words],
+                    incidents: vec![incident],
+                }),
+            }
+        }
+
+        let mut problems: Vec<ProblemCluster> = groups
+            .into_iter()
+            .map(|group| ProblemCluster {
+                problem: group.entry.unwrap_or_else(|| common_wording(&group.incidents)),
+                incidents: group.incidents,
+            })
+            .collect();
+        problems.sort_by(|a, b| {
+            b.incidents
+                .len()
+                .cmp(&a.incidents.len())
+                .then_with(|| b.incidents.last().map(|i| i.concluded_at).cmp(&a.incidents.last().map(|i| i.concluded_at)))
+        });
+
+        let mut recurring: Vec<RecurringProblem> = Vec::new();
+        for cluster in &problems {
+            recurring.extend(recurrences(cluster, Scope::Plant, |i| Some((&i.plant_id, &i.plant_name))));
+            recurring.extend(recurrences(cluster, Scope::Species, |i| Some((&i.species, &i.species))));
+            recurring.extend(recurrences(cluster, Scope::Room, |i| i.room.as_ref().map(|r| (r, r))));
+        }
+        recurring.sort_by(|a, b| b.incidents.cmp(&a.incidents).then_with(|| b.last_seen.cmp(&a.last_seen)));
+
+        Self {
+            since,
+            diagnoses,
+            problems,
+            recurring,
+        }
+    }
+}
+
+/// A cluster while it is being built: its entry, or else the words of each
+/// of its findings
+struct Group {
+    entry: Option<String>,
+    words: Vec<BTreeSet<String>>,
+    incidents: Vec<Incident>,
+}
+
+/// The places in `scope` where a cluster's problem recurred. `place` gives
+/// an incident's place as (key, name), keys being compared in any case.
+fn recurrences<'a>(
+    cluster: &'a ProblemCluster,
+    scope: Scope,
+    place: impl Fn(&'a Incident) -> Option<(&'a String, &'a String)>,
+) -> Vec<RecurringProblem> {
+    let mut places: Vec<(&String, &String, Vec<&Incident>)> = Vec::new();
+    for incident in &cluster.incidents {
+        let Some((key, name)) = place(incident) else { continue };
+        match places.iter_mut().find(|(k, _, _)| k.eq_ignore_ascii_case(key)) {
+            Some((_, _, members)) => members.push(incident),
+            None => places.push((key, name, vec![incident])),
+        }
+    }
+
+    places
+        .into_iter()
+        .filter_map(|(_, name, members)| {
+            let plants: BTreeSet<&str> = members.iter().map(|i| i.plant_id.as_str()).collect();
+            let spans_plants = scope == Scope::Plant || plants.len() > 1;
+            (members.len() >= MIN_RECURRENCES && spans_plants).then(|| RecurringProblem {
+                problem: cluster.problem.clone(),
+                scope,
+                name: name.clone(),
+                incidents: members.len(),
+                plants: plants.len(),
+                last_seen: members.iter().map(|i| i.concluded_at).max().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// The words of a finding that say what the problem is, lowercased and in
+/// the singular
+fn finding_words(finding: &str) -> BTreeSet<String> {
+    finding
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 2 && !FILLER_WORDS.contains(word))
+        .map(|word| match word.strip_suffix('s') {
+            Some(stem) if !stem.ends_with('s') => stem.to_string(),
+            _ => word.to_string(),
+        })
+        .collect()
+}
+
+/// Whether two findings share most of their words, or the shorter one (of
+/// at least two words) is all in the longer
+fn similar(a: &BTreeSet<String>, b: &BTreeSet<String>) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    let common = a.intersection(b).count();
+    let (shorter, _) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    common as f64 / a.union(b).count() as f64 >= MIN_SIMILARITY || (shorter.len() > 1 && common == shorter.len())
+}
+
+/// The finding worded most often, the shortest on a tie
+fn common_wording(incidents: &[Incident]) -> String {
+    let mut wordings: Vec<(String, usize)> = Vec::new();
+    for incident in incidents {
+        let finding = incident.finding.trim();
+        match wordings.iter_mut().find(|(w, _)| w.eq_ignore_ascii_case(finding)) {
+            Some((_, count)) => *count += 1,
+            None => wordings.push((finding.to_string(), 1)),
+        }
+    }
+    wordings
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then_with(|| b.len().cmp(&a.len())))
+        .map(|(wording, _)| wording)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn incident(plant: &str, species: &str, room: &str, finding: &str, entry: Option<&str>, days_ago: i64) -> Incident {
+        Incident {
+            session_id: format!("{}-{}", plant, days_ago),
+            plant_id: plant.to_lowercase(),
+            plant_name: plant.to_string(),
+            species: species.to_string(),
+            room: Some(room.to_string()),
+            finding: finding.to_string(),
+            entry: entry.map(str::to_string),
+            concluded_at: Utc::now() - Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn test_problem_report() {
+        let incidents = vec![
+            incident("Fern", "Nephrolepis exaltata", "Bathroom", "Fungus gnats", Some("Fungus gnats"), 90),
+            incident("Calathea", "Calathea orbifolia", "bathroom", "Fungus gnat larvae in soil", Some("Fungus gnats"), 60),
+            incident("Fern", "Nephrolepis exaltata", "Bathroom", "Fungus gnats again", Some("Fungus gnats"), 10),
+            incident("Bob", "Monstera deliciosa", "Kitchen", "Root rot", None, 40),
+            incident("Bob", "Monstera deliciosa", "Kitchen", "Severe root rot from overwatering", None, 5),
+            incident("Pothos", "Epipremnum aureum", "Kitchen", "Sunburn", None, 20),
+        ];
+        let report = ProblemReport::new(incidents, Utc::now() - Duration::days(365));
+
+        assert_eq!(report.diagnoses, 6);
+        let problems: Vec<(&str, usize)> = report.problems.iter().map(|p| (p.problem.as_str(), p.incidents.len())).collect();
+        assert_eq!(problems, [("Fungus gnats", 3), ("Root rot", 2), ("Sunburn", 1)]);
+        assert_eq!(report.problems[0].plant_names(), ["Fern", "Calathea"]);
+
+        let recurring: Vec<String> = report.recurring.iter().map(RecurringProblem::describe).collect();
+        assert_eq!(
+            recurring,
+            [
+                "3 incidents of fungus gnats in the bathroom",
+                "2 incidents of root rot on Bob",
+                "2 incidents of fungus gnats on Fern",
+            ]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! QUARANTINE DOMAIN MODEL
+//!
+//! An infested plant is kept away from the rest of the collection until a
+//! follow-up diagnosis finds it clean. While it is quarantined, it is
+//! inspected every week, and plants sharing its room are pointed out
+//! because pests are likely to have spread to them.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{DiagnosisSession, DiagnosisState, LocalZone, Plant, Severity};
+
+/// Days between inspections of a quarantined plant
+pub const INSPECTION_INTERVAL_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quarantine {
+    pub id: String,
+    pub plant_id: String,
+    /// What the plant is infested with, in the owner's words
+    pub reason: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub last_inspected_at: Option<DateTime<Utc>>,
+    pub released_at: Option<DateTime<Utc>>,
+    /// The diagnosis that found the plant clean
+    pub release_diagnosis_id: Option<String>,
+}
+
+impl Quarantine {
+    pub fn new(plant_id: String, reason: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            reason,
+            started_at: Utc::now(),
+            last_inspected_at: None,
+            released_at: None,
+            release_diagnosis_id: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.released_at.is_none()
+    }
+
+    /// Day of the next inspection, a week after the last one or after the
+    /// plant was quarantined
+    pub fn next_inspection(&self, zone: &LocalZone) -> NaiveDate {
+        let since = self.last_inspected_at.unwrap_or(self.started_at);
+        zone.date(since) + Duration::days(INSPECTION_INTERVAL_DAYS)
+    }
+
+    /// End the quarantine on a diagnosis of the plant concluded since it
+    /// started that found nothing to treat
+    pub fn release(&mut self, diagnosis: &DiagnosisSession) -> Result<()> {
+        if !self.is_active() {
+            anyhow::bail!("The plant is not quarantined");
+        }
+        if diagnosis.plant_id != self.plant_id
+            || diagnosis.state != DiagnosisState::Concluded
+            || diagnosis.updated_at < self.started_at
+        {
+            anyhow::bail!("The plant has not been diagnosed since it was quarantined");
+        }
+
+        let severity = diagnosis.diagnosis_context["result"]["severity"]
+            .as_str()
+            .and_then(Severity::from_str);
+        if severity != Some(Severity::Info) {
+            anyhow::bail!(
+                "The follow-up diagnosis did not find the plant clean: {}",
+                diagnosis.diagnosis_context["result"]["finding"]
+                    .as_str()
+                    .unwrap_or("unknown finding")
+            );
+        }
+
+        self.released_at = Some(Utc::now());
+        self.release_diagnosis_id = Some(diagnosis.id.clone());
+        Ok(())
+    }
+}
+
+/// Plants sharing a room with a quarantined plant, each with the names of
+/// the quarantined plants it shares the room with. Plants without a room
+/// are never neighbours.
+pub fn neighbours<'a>(plants: &'a [Plant], quarantines: &[Quarantine]) -> Vec<(&'a Plant, Vec<&'a str>)> {
+    let quarantined: Vec<&Plant> = plants
+        .iter()
+        .filter(|p| quarantines.iter().any(|q| q.is_active() && q.plant_id == p.id))
+        .collect();
+
+    plants
+        .iter()
+        .filter(|p| !quarantined.iter().any(|q| q.id == p.id))
+        .filter_map(|plant| {
+            let room = plant.room.as_deref()?;
+            let sick: Vec<&str> = quarantined
+                .iter()
+                .filter(|q| q.room.as_deref().is_some_and(|r| r.eq_ignore_ascii_case(room)))
+                .map(|q| q.display_name())
+                .collect();
+            (!sick.is_empty()).then_some((plant, sick))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+    use serde_json::json;
+
+    fn plant(name: &str, room: Option<&str>) -> Plant {
+        let mut plant = Plant::new("u".to_string(), name.to_string(), CareSchedule::default());
+        plant.room = room.map(str::to_string);
+        plant
+    }
+
+    #[test]
+    fn test_neighbours_share_a_room() {
+        let plants = [
+            plant("Calathea", Some("Living room")),
+            plant("Fern", Some("living room")),
+            plant("Ivy", Some("Kitchen")),
+            plant("Cactus", None),
+        ];
+        let quarantines = [Quarantine::new(plants[0].id.clone(), Some("Spider mites".to_string()))];
+
+        let found = neighbours(&plants, &quarantines);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0.name, "Fern");
+        assert_eq!(found[0].1, vec!["Calathea"]);
+
+        let mut released = quarantines[0].clone();
+        released.released_at = Some(Utc::now());
+        assert!(neighbours(&plants, &[released]).is_empty());
+    }
+
+    #[test]
+    fn test_release_needs_a_clean_diagnosis_since_quarantine() {
+        let mut quarantine = Quarantine::new("p1".to_string(), None);
+        let utc = LocalZone::utc();
+        assert_eq!(
+            quarantine.next_inspection(&utc),
+            quarantine.started_at.date_naive() + Duration::days(INSPECTION_INTERVAL_DAYS)
+        );
+
+        let mut diagnosis = DiagnosisSession::new("p1".to_string(), "Follow-up".to_string());
+        assert!(quarantine.release(&diagnosis).is_err());
+
+        diagnosis.state = DiagnosisState::Concluded;
+        diagnosis.diagnosis_context["result"] = json!({"finding": "Spider mites", "severity": "serious"});
+        assert!(quarantine.release(&diagnosis).is_err());
+
+        diagnosis.diagnosis_context["result"] = json!({"finding": "No pests left", "severity": "info"});
+        diagnosis.updated_at = quarantine.started_at - Duration::days(1);
+        assert!(quarantine.release(&diagnosis).is_err());
+
+        diagnosis.updated_at = Utc::now();
+        quarantine.release(&diagnosis).unwrap();
+        assert!(!quarantine.is_active());
+        assert_eq!(quarantine.release_diagnosis_id.as_deref(), Some(diagnosis.id.as_str()));
+        assert!(quarantine.release(&diagnosis).is_err());
+    }
+}
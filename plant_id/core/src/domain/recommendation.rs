@@ -0,0 +1,156 @@
+//! RECOMMENDATIONS
+//!
+//! New plants suggested for the owner's conditions: the light they can
+//! offer, the pets they keep and how much effort they want to put in. The
+//! AI proposes species; its claims are then checked against the bundled
+//! toxicity list and the effort level, and species already in the
+//! collection or on the wishlist are left out.
+
+use serde::{Deserialize, Serialize};
+
+use super::care_difficulty::{MAX_DIFFICULTY, MIN_DIFFICULTY};
+use super::placement::{LightLevel, LightRequirement};
+use super::toxicity::{self, Pet};
+use super::{Plant, WishlistItem};
+
+/// How much care the owner is willing to give
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Effort {
+    Low,
+    Medium,
+    High,
+}
+
+impl Effort {
+    /// Hardest care difficulty rating that suits this effort
+    pub fn max_difficulty(&self) -> u8 {
+        match self {
+            Self::Low => 2,
+            Self::Medium => 3,
+            Self::High => MAX_DIFFICULTY,
+        }
+    }
+}
+
+/// What the new plants have to cope with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Conditions {
+    pub light: LightLevel,
+    pub pets: Vec<Pet>,
+    pub effort: Effort,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    pub species: String,
+    pub common_name: Option<String>,
+    /// Why the plant suits the owner
+    pub reason: String,
+    /// Light the plant needs, as the AI described it
+    pub light: String,
+    /// From 1 (very forgiving) to 5 (for experienced growers)
+    pub difficulty: u8,
+    pub toxic_to: Vec<Pet>,
+    /// Whether `toxic_to` comes from the bundled list rather than the AI
+    pub toxicity_verified: bool,
+}
+
+impl Recommendation {
+    fn names(&self) -> impl Iterator<Item = String> + '_ {
+        std::iter::once(&self.species)
+            .chain(self.common_name.as_ref())
+            .map(|n| n.trim().to_lowercase())
+            .filter(|n| !n.is_empty())
+    }
+
+    /// Whether a plant or wishlist name refers to this species
+    fn matches_name(&self, name: &str) -> bool {
+        let name = name.trim().to_lowercase();
+        !name.is_empty() && self.names().any(|n| n.contains(&name) || name.contains(&n))
+    }
+}
+
+/// Check the AI's suggestions against the conditions, keeping the ones
+/// that suit them in the order given
+pub fn screen(
+    suggestions: Vec<Recommendation>,
+    conditions: &Conditions,
+    collection: &[Plant],
+    wishlist: &[WishlistItem],
+) -> Vec<Recommendation> {
+    suggestions
+        .into_iter()
+        .map(|mut suggestion| {
+            let names = suggestion.names().collect::<Vec<_>>().join(" ");
+            if let Some(pets) = toxicity::toxic_to(&names) {
+                suggestion.toxic_to = pets;
+                suggestion.toxicity_verified = true;
+            }
+            suggestion
+        })
+        .filter(|s| (MIN_DIFFICULTY..=conditions.effort.max_difficulty()).contains(&s.difficulty))
+        .filter(|s| !s.toxic_to.iter().any(|pet| conditions.pets.contains(pet)))
+        .filter(|s| {
+            LightRequirement::parse(&s.light)
+                .map(|requirement| requirement.accepts(conditions.light))
+                .unwrap_or(true)
+        })
+        .filter(|s| !collection.iter().any(|plant| s.matches_name(&plant.name)))
+        .filter(|s| !wishlist.iter().any(|item| s.matches_name(&item.species)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+
+    fn suggestion(species: &str, common_name: &str, light: &str, difficulty: u8) -> Recommendation {
+        Recommendation {
+            species: species.to_string(),
+            common_name: Some(common_name.to_string()),
+            reason: String::new(),
+            light: light.to_string(),
+            difficulty,
+            toxic_to: Vec::new(),
+            toxicity_verified: false,
+        }
+    }
+
+    #[test]
+    fn test_screen() {
+        let conditions = Conditions {
+            light: LightLevel::Low,
+            pets: vec![Pet::Cat],
+            effort: Effort::Low,
+        };
+        let collection = vec![Plant::new(
+            "u".to_string(),
+            "Spider Plant".to_string(),
+            CareSchedule::default(),
+        )];
+        let wishlist = vec![WishlistItem::new("u", "Aspidistra elatior", None, String::new()).unwrap()];
+
+        let kept = screen(
+            vec![
+                // The AI forgot it is toxic
+                suggestion("Zamioculcas zamiifolia", "ZZ plant", "Low to bright indirect light", 1),
+                suggestion("Chlorophytum comosum", "Spider plant", "Low to bright light", 1),
+                suggestion("Aspidistra elatior", "Cast iron plant", "Low light", 1),
+                suggestion("Nephrolepis exaltata", "Boston fern", "Low to medium light", 3),
+                suggestion("Echeveria elegans", "Mexican snowball", "Full sun", 2),
+                suggestion("Chamaedorea elegans", "Parlor palm", "Low to medium light", 2),
+                suggestion("Tradescantia zebrina", "Inch plant", "", 2),
+            ],
+            &conditions,
+            &collection,
+            &wishlist,
+        );
+
+        let species: Vec<_> = kept.iter().map(|r| r.species.as_str()).collect();
+        assert_eq!(species, vec!["Chamaedorea elegans", "Tradescantia zebrina"]);
+        assert!(kept[0].toxicity_verified);
+        assert!(!kept[1].toxicity_verified);
+    }
+}
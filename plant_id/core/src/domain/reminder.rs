@@ -0,0 +1,135 @@
+//! REMINDERS
+//!
+//! What is coming up for a plant: its recurring care tasks, its weekly
+//! inspection while it is quarantined, and preventive inspections for pests
+//! found on its relatives. The checklist shows the reminders due today or
+//! earlier; listings show the next one.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::enums::CareTaskKind;
+use super::{CareTask, LocalZone, Plant, PreventiveInspection, Quarantine};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderKind {
+    Water,
+    Fertilize,
+    /// Look a quarantined plant, or a relative of a diagnosed one, over for
+    /// pests
+    Inspect,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reminder {
+    pub kind: ReminderKind,
+    /// Care task, quarantine or preventive inspection the reminder comes from
+    pub source_id: String,
+    pub due: NaiveDate,
+}
+
+impl Reminder {
+    /// Days the reminder is past due on `today`: 0 when due today,
+    /// negative while it is still ahead
+    pub fn overdue_days(&self, today: NaiveDate) -> i64 {
+        (today - self.due).num_days()
+    }
+}
+
+/// Every recurring care task of a plant, given the ones done at least once.
+/// Tasks never done are included, due right away.
+pub fn care_tasks(plant_id: &str, done: &[CareTask]) -> Vec<CareTask> {
+    [CareTaskKind::Water, CareTaskKind::Fertilize]
+        .into_iter()
+        .map(|kind| {
+            done.iter()
+                .find(|t| t.kind == kind)
+                .cloned()
+                .unwrap_or_else(|| CareTask::new(plant_id, kind))
+        })
+        .collect()
+}
+
+/// Everything coming up for a plant as of `today` in `zone`, soonest first
+pub fn reminders(
+    plant: &Plant,
+    done: &[CareTask],
+    quarantine: Option<&Quarantine>,
+    inspections: &[PreventiveInspection],
+    today: NaiveDate,
+    zone: &LocalZone,
+) -> Vec<Reminder> {
+    let mut reminders: Vec<Reminder> = care_tasks(&plant.id, done)
+        .into_iter()
+        .map(|task| Reminder {
+            kind: match task.kind {
+                CareTaskKind::Water => ReminderKind::Water,
+                CareTaskKind::Fertilize => ReminderKind::Fertilize,
+            },
+            due: task.due_date(&plant.care_schedule, today, zone),
+            source_id: task.id,
+        })
+        .collect();
+
+    if let Some(quarantine) = quarantine.filter(|q| q.is_active()) {
+        reminders.push(Reminder {
+            kind: ReminderKind::Inspect,
+            source_id: quarantine.id.clone(),
+            due: quarantine.next_inspection(zone),
+        });
+    }
+
+    reminders.extend(inspections.iter().filter(|i| i.is_open()).map(|inspection| Reminder {
+        kind: ReminderKind::Inspect,
+        source_id: inspection.id.clone(),
+        due: inspection.due_on,
+    }));
+
+    reminders.sort_by_key(|r| r.due);
+    reminders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_reminders_soonest_first() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let utc = LocalZone::utc();
+        let plant = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+
+        let mut fed = CareTask::new(&plant.id, CareTaskKind::Fertilize);
+        fed.last_done_at = Some((today - Duration::days(25)).and_hms_opt(9, 0, 0).unwrap().and_utc());
+        let mut watered = CareTask::new(&plant.id, CareTaskKind::Water);
+        watered.last_done_at = Some((today - Duration::days(1)).and_hms_opt(9, 0, 0).unwrap().and_utc());
+
+        let found = reminders(&plant, &[fed.clone(), watered], None, &[], today, &utc);
+        let kinds: Vec<ReminderKind> = found.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, vec![ReminderKind::Fertilize, ReminderKind::Water]);
+        assert_eq!(found[0].overdue_days(today), -5);
+        assert_eq!(found[1].due, today + Duration::days(6));
+
+        // Never watered: due right away, and an inspection comes with a quarantine
+        let mut quarantine = Quarantine::new(plant.id.clone(), None);
+        quarantine.started_at = (today - Duration::days(8)).and_hms_opt(9, 0, 0).unwrap().and_utc();
+        let found = reminders(&plant, &[fed.clone()], Some(&quarantine), &[], today, &utc);
+        assert_eq!(found[0].kind, ReminderKind::Inspect);
+        assert_eq!(found[0].overdue_days(today), 1);
+        assert_eq!(found[1].kind, ReminderKind::Water);
+        assert_eq!(found[1].overdue_days(today), 0);
+
+        // A preventive inspection until it is done
+        let mut inspection =
+            PreventiveInspection::new(plant.id.clone(), "Spider mites".to_string(), None, today + Duration::days(2));
+        let found = reminders(&plant, &[fed.clone()], None, std::slice::from_ref(&inspection), today, &utc);
+        assert_eq!(found[1].kind, ReminderKind::Inspect);
+        assert_eq!(found[1].source_id, inspection.id);
+        inspection.done_at = Some(Utc::now());
+        let found = reminders(&plant, &[fed], None, &[inspection], today, &utc);
+        assert!(found.iter().all(|r| r.kind != ReminderKind::Inspect));
+    }
+}
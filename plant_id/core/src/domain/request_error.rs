@@ -0,0 +1,29 @@
+/*!
+ * REQUEST ERRORS
+ *
+ * Why a request for a record was turned down. Frontends that answer with
+ * a status, such as the HTTP and gRPC servers, tell these apart by type
+ * rather than by their message.
+ */
+
+#[derive(Debug, thiserror::Error)]
+pub enum RequestError {
+    /// No such record, or not one of the user's
+    #[error("{0} not found")]
+    NotFound(&'static str),
+
+    #[error("Multiple plants are named '{name}' ({ids}); use the plant ID instead")]
+    AmbiguousName { name: String, ids: String },
+
+    /// The user's role does not allow it, e.g. a plant sitter starting an
+    /// AI session
+    #[error("A plant sitter cannot {0}. Ask the owner to end sitter mode.")]
+    NotPermitted(&'static str),
+
+    /// The record belongs to another user
+    #[error("Unauthorized access to {0}")]
+    Unauthorized(&'static str),
+
+    #[error("Invalid {what}: {reason}")]
+    Invalid { what: &'static str, reason: String },
+}
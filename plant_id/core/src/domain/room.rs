@@ -0,0 +1,299 @@
+//! ROOM DOMAIN MODEL
+//!
+//! What the owner knows about a room: which way its window faces and how
+//! many hours of direct sun come through it, and its environment (average
+//! temperature and humidity, drafts, heating). Plants are put in rooms by
+//! name (`Plant::room`); a room only needs recording here for placement
+//! advice, and so the AI is told where a plant lives instead of asking.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::enums::WindowOrientation;
+use super::placement::LightLevel;
+
+/// Direct sun from which a spot counts as sunny
+const DIRECT_SUN_HOURS: f64 = 4.0;
+
+/// Direct sun from which a spot counts as bright
+const BRIGHT_SUN_HOURS: f64 = 1.0;
+
+/// Average temperatures a room can be recorded with, in °C
+const TEMPERATURE_RANGE: std::ops::RangeInclusive<f64> = -10.0..=45.0;
+
+/// What the owner measured or knows about a room's climate. Unknown
+/// fields are left out of what the AI is told.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    /// Average temperature in °C
+    pub temperature: Option<f64>,
+    /// Average relative humidity in percent
+    pub humidity: Option<f64>,
+    /// Whether cold drafts reach plants there (an outside door, a leaky
+    /// window, an air conditioning vent)
+    pub drafty: Option<bool>,
+    /// Whether the room is heated in winter, which dries the air
+    pub heated: Option<bool>,
+}
+
+impl Environment {
+    pub fn validated(self) -> Result<Self> {
+        if let Some(temperature) = self.temperature {
+            if !TEMPERATURE_RANGE.contains(&temperature) {
+                anyhow::bail!(
+                    "Average temperature must be between {} and {}°C, got {}",
+                    TEMPERATURE_RANGE.start(),
+                    TEMPERATURE_RANGE.end(),
+                    temperature
+                );
+            }
+        }
+        if let Some(humidity) = self.humidity {
+            if !(0.0..=100.0).contains(&humidity) {
+                anyhow::bail!("Humidity must be between 0 and 100%, got {}", humidity);
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// These values, with those given in `update` replacing them
+    pub fn updated_with(self, update: Environment) -> Self {
+        Self {
+            temperature: update.temperature.or(self.temperature),
+            humidity: update.humidity.or(self.humidity),
+            drafty: update.drafty.or(self.drafty),
+            heated: update.heated.or(self.heated),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Room {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    /// None for a room without a window
+    pub window: Option<WindowOrientation>,
+    /// Hours of direct sun on a typical day, when the owner knows them
+    pub sun_hours: Option<f64>,
+    #[serde(default)]
+    pub environment: Environment,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What the AI is told about the room a plant lives in, so that it does
+/// not have to ask about the light, warmth or air there
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoomProfile {
+    pub name: String,
+    /// The window's direction, "none" for a room without one; unknown when
+    /// the room is not recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direct_sun_hours: Option<f64>,
+    /// Whether the sun hours were estimated from the window
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub sun_hours_estimated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub light: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_temperature_c: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_humidity_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drafty: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heated: Option<bool>,
+}
+
+impl RoomProfile {
+    /// The profile of the room named `name`, from its record if it has one
+    pub fn of(name: &str, room: Option<&Room>) -> Self {
+        let Some(room) = room else {
+            return Self {
+                name: name.to_string(),
+                window: None,
+                direct_sun_hours: None,
+                sun_hours_estimated: false,
+                light: None,
+                average_temperature_c: None,
+                average_humidity_percent: None,
+                drafty: None,
+                heated: None,
+            };
+        };
+
+        Self {
+            name: room.name.clone(),
+            window: Some(room.window.map_or("none".to_string(), |w| w.as_str().to_lowercase())),
+            direct_sun_hours: Some(room.direct_sun_hours()),
+            sun_hours_estimated: room.sun_hours.is_none(),
+            light: Some(room.light_level().to_string()),
+            average_temperature_c: room.environment.temperature,
+            average_humidity_percent: room.environment.humidity,
+            drafty: room.environment.drafty,
+            heated: room.environment.heated,
+        }
+    }
+
+    /// E.g. "kitchen: east window, about 3 hours of direct sun (estimated),
+    /// bright indirect light, averages 21°C, humidity around 40%, drafty"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        match self.window.as_deref() {
+            Some("none") => parts.push("no window".to_string()),
+            Some(window) => parts.push(format!("{} window", window)),
+            None => {}
+        }
+        if let Some(hours) = self.direct_sun_hours.filter(|_| self.window.as_deref() != Some("none")) {
+            let estimated = if self.sun_hours_estimated { " (estimated)" } else { "" };
+            parts.push(format!("about {} hours of direct sun{}", hours, estimated));
+        }
+        if let Some(light) = &self.light {
+            parts.push(light.clone());
+        }
+        if let Some(temperature) = self.average_temperature_c {
+            parts.push(format!("averages {}°C", temperature));
+        }
+        if let Some(humidity) = self.average_humidity_percent {
+            parts.push(format!("humidity around {}%", humidity));
+        }
+        match self.drafty {
+            Some(true) => parts.push("drafty".to_string()),
+            Some(false) => parts.push("no drafts".to_string()),
+            None => {}
+        }
+        match self.heated {
+            Some(true) => parts.push("heated in winter".to_string()),
+            Some(false) => parts.push("unheated".to_string()),
+            None => {}
+        }
+
+        if parts.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}: {}", self.name, parts.join(", "))
+        }
+    }
+}
+
+impl Room {
+    pub fn new(
+        user_id: &str,
+        name: &str,
+        window: Option<WindowOrientation>,
+        sun_hours: Option<f64>,
+    ) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Room name is empty");
+        }
+        if let Some(hours) = sun_hours {
+            if !(0.0..=24.0).contains(&hours) {
+                anyhow::bail!("Hours of direct sun must be between 0 and 24, got {}", hours);
+            }
+        }
+
+        Ok(Self {
+            id: room_id(user_id, name),
+            user_id: user_id.to_string(),
+            name: name.to_string(),
+            window,
+            sun_hours,
+            environment: Environment::default(),
+            updated_at: Utc::now(),
+        })
+    }
+
+    pub fn with_environment(mut self, environment: Environment) -> Result<Self> {
+        self.environment = environment.validated()?;
+        Ok(self)
+    }
+
+    pub fn profile(&self) -> RoomProfile {
+        RoomProfile::of(&self.name, Some(self))
+    }
+
+    /// Hours of direct sun, estimated from the window when not given. The
+    /// estimates assume the northern hemisphere.
+    pub fn direct_sun_hours(&self) -> f64 {
+        self.sun_hours.unwrap_or(match self.window {
+            Some(WindowOrientation::South) => 6.0,
+            Some(WindowOrientation::West) => 4.0,
+            Some(WindowOrientation::East) => 3.0,
+            Some(WindowOrientation::North) | None => 0.0,
+        })
+    }
+
+    pub fn light_level(&self) -> LightLevel {
+        let hours = self.direct_sun_hours();
+        if hours >= DIRECT_SUN_HOURS {
+            LightLevel::Direct
+        } else if hours >= BRIGHT_SUN_HOURS {
+            LightLevel::Bright
+        } else if self.window.is_some() {
+            LightLevel::Medium
+        } else {
+            LightLevel::Low
+        }
+    }
+}
+
+/// ID of a user's room, derived from its name so every device agrees on it
+pub fn room_id(user_id: &str, name: &str) -> String {
+    let name = format!("{}:room:{}", user_id, name.trim().to_lowercase());
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_room_light_level() {
+        let room = |window, hours| Room::new("u", "Living room", window, hours).unwrap();
+
+        assert_eq!(room(Some(WindowOrientation::South), None).light_level(), LightLevel::Direct);
+        assert_eq!(room(Some(WindowOrientation::East), None).light_level(), LightLevel::Bright);
+        assert_eq!(room(Some(WindowOrientation::North), None).light_level(), LightLevel::Medium);
+        assert_eq!(room(None, None).light_level(), LightLevel::Low);
+        // Measured hours win over the estimate
+        assert_eq!(room(Some(WindowOrientation::South), Some(0.5)).light_level(), LightLevel::Medium);
+
+        assert_eq!(room(None, None).id, Room::new("u", " living ROOM", None, None).unwrap().id);
+        assert!(Room::new("u", "Attic", None, Some(25.0)).is_err());
+    }
+
+    #[test]
+    fn test_room_profile() {
+        let environment = Environment {
+            temperature: Some(17.5),
+            drafty: Some(true),
+            ..Default::default()
+        };
+        let room = Room::new("u", "Hallway", Some(WindowOrientation::East), None)
+            .unwrap()
+            .with_environment(environment)
+            .unwrap();
+        assert_eq!(
+            room.profile().describe(),
+            "Hallway: east window, about 3 hours of direct sun (estimated), bright indirect light, averages 17.5°C, drafty"
+        );
+        assert_eq!(RoomProfile::of("Attic", None).describe(), "Attic");
+
+        let updated = environment.updated_with(Environment {
+            humidity: Some(60.0),
+            drafty: Some(false),
+            ..Default::default()
+        });
+        assert_eq!((updated.temperature, updated.humidity, updated.drafty), (Some(17.5), Some(60.0), Some(false)));
+        assert!(Environment { humidity: Some(120.0), ..Default::default() }.validated().is_err());
+    }
+}
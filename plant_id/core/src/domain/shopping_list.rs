@@ -0,0 +1,209 @@
+//! SHOPPING LIST
+//!
+//! Supplies needed to carry out recent diagnoses (neem oil, perlite,
+//! fertilizer...), found by matching recommendations and treatment steps
+//! against a bundled catalog, merged with the items the owner added or
+//! removed by hand.
+//!
+//! A removed supply stays off the list until a newer diagnosis recommends
+//! it again.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::encyclopedia::contains_word;
+
+/// Supplies recognised in recommendations: the name shown on the list,
+/// then the lowercase phrases that refer to it
+const SUPPLIES: &[(&str, &[&str])] = &[
+    ("Neem oil", &["neem oil", "neem"]),
+    ("Insecticidal soap", &["insecticidal soap"]),
+    ("Horticultural oil", &["horticultural oil"]),
+    ("Isopropyl alcohol", &["isopropyl alcohol", "rubbing alcohol", "alcohol"]),
+    ("Cotton swabs", &["cotton swab", "cotton swabs"]),
+    ("Sticky traps", &["sticky trap", "sticky traps"]),
+    ("BTI larvicide", &["bacillus thuringiensis", "bti"]),
+    ("Spinosad", &["spinosad"]),
+    ("Predatory mites", &["predatory mites", "predatory mite"]),
+    ("Fungicide", &["fungicide", "fungicides"]),
+    ("Potassium bicarbonate", &["potassium bicarbonate"]),
+    ("Hydrogen peroxide", &["hydrogen peroxide"]),
+    ("Perlite", &["perlite"]),
+    ("Orchid bark", &["orchid bark"]),
+    ("Cactus mix", &["cactus mix", "succulent mix", "cactus soil"]),
+    (
+        "Potting mix",
+        &["potting mix", "potting soil", "fresh soil", "fresh mix", "well-draining mix"],
+    ),
+    ("Horticultural sand", &["horticultural sand", "sand"]),
+    ("Pot with drainage holes", &["drainage holes", "drainage hole"]),
+    ("Fertilizer", &["fertilizer", "fertiliser", "plant food"]),
+    ("Sterile scissors", &["sterile scissors", "pruning shears", "scissors"]),
+    ("Humidifier", &["humidifier"]),
+    ("Pebble tray", &["pebble tray"]),
+    ("Grow light", &["grow light", "grow lights"]),
+    ("Moisture meter", &["moisture meter"]),
+];
+
+/// Catalog supplies mentioned in free text, such as a recommendation
+pub fn find_supplies(text: &str) -> Vec<&'static str> {
+    let text = text.to_lowercase();
+    SUPPLIES
+        .iter()
+        .filter(|(_, phrases)| phrases.iter().any(|p| contains_word(&text, p)))
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// An item the owner added to the list by hand, or took off it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingEntry {
+    pub id: String,
+    pub user_id: String,
+    /// Unique per user, compared case-insensitively
+    pub name: String,
+    /// Whether the item was taken off the list rather than added
+    pub removed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ShoppingEntry {
+    pub fn new(user_id: String, name: String, removed: bool) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            name,
+            removed,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// A supply recommended by one diagnosis
+#[derive(Debug, Clone)]
+pub struct ShoppingSuggestion {
+    pub supply: String,
+    /// What it is for, e.g. "Monstera: Spider mites"
+    pub reason: String,
+    /// When the recommending diagnosis concluded
+    pub recommended_at: DateTime<Utc>,
+}
+
+/// A line of the shopping list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShoppingItem {
+    pub name: String,
+    /// Diagnoses that recommended it, empty for items added by hand
+    pub reasons: Vec<String>,
+    pub added_manually: bool,
+}
+
+/// Merge suggestions and manual entries into one deduplicated list,
+/// sorted by name
+pub fn build_list(suggestions: &[ShoppingSuggestion], entries: &[ShoppingEntry]) -> Vec<ShoppingItem> {
+    let mut items: BTreeMap<String, (ShoppingItem, DateTime<Utc>)> = BTreeMap::new();
+
+    for suggestion in suggestions {
+        let (item, latest) = items.entry(suggestion.supply.to_lowercase()).or_insert_with(|| {
+            (
+                ShoppingItem {
+                    name: suggestion.supply.clone(),
+                    reasons: Vec::new(),
+                    added_manually: false,
+                },
+                suggestion.recommended_at,
+            )
+        });
+        if !item.reasons.contains(&suggestion.reason) {
+            item.reasons.push(suggestion.reason.clone());
+        }
+        *latest = (*latest).max(suggestion.recommended_at);
+    }
+
+    for entry in entries {
+        let key = entry.name.to_lowercase();
+        if entry.removed {
+            if items.get(&key).is_some_and(|(_, latest)| *latest <= entry.updated_at) {
+                items.remove(&key);
+            }
+            continue;
+        }
+
+        let (item, _) = items.entry(key).or_insert_with(|| {
+            (
+                ShoppingItem {
+                    name: entry.name.clone(),
+                    reasons: Vec::new(),
+                    added_manually: true,
+                },
+                entry.updated_at,
+            )
+        });
+        item.added_manually = true;
+    }
+
+    items.into_values().map(|(item, _)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn suggestion(supply: &str, reason: &str, recommended_at: DateTime<Utc>) -> ShoppingSuggestion {
+        ShoppingSuggestion {
+            supply: supply.to_string(),
+            reason: reason.to_string(),
+            recommended_at,
+        }
+    }
+
+    fn entry(name: &str, removed: bool, updated_at: DateTime<Utc>) -> ShoppingEntry {
+        ShoppingEntry {
+            updated_at,
+            ..ShoppingEntry::new("u".to_string(), name.to_string(), removed)
+        }
+    }
+
+    #[test]
+    fn test_find_supplies() {
+        let supplies =
+            find_supplies("Apply insecticidal soap or Neem oil every 5-7 days; use yellow sticky traps");
+        assert_eq!(supplies, vec!["Neem oil", "Insecticidal soap", "Sticky traps"]);
+        assert_eq!(find_supplies("Repot into fresh soil with perlite"), vec!["Perlite", "Potting mix"]);
+        assert!(find_supplies("Move it to a brighter spot").is_empty());
+        assert!(find_supplies("Thousands of tiny insects").is_empty());
+    }
+
+    #[test]
+    fn test_build_list_merges_and_dedupes() {
+        let now = Utc::now();
+        let suggestions = [
+            suggestion("Neem oil", "Fern: Spider mites", now),
+            suggestion("Neem oil", "Ivy: Aphids", now),
+            suggestion("Perlite", "Fern: Root rot", now),
+        ];
+        let entries = [entry("neem OIL", false, now), entry("Gloves", false, now)];
+
+        let list = build_list(&suggestions, &entries);
+        let names: Vec<&str> = list.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["Gloves", "Neem oil", "Perlite"]);
+        assert_eq!(list[1].reasons, vec!["Fern: Spider mites", "Ivy: Aphids"]);
+        assert!(list[1].added_manually);
+        assert!(!list[2].added_manually);
+    }
+
+    #[test]
+    fn test_removed_item_returns_when_recommended_again() {
+        let now = Utc::now();
+        let removed = [entry("Perlite", true, now)];
+
+        let earlier = [suggestion("Perlite", "Fern: Root rot", now - Duration::days(1))];
+        assert!(build_list(&earlier, &removed).is_empty());
+
+        let later = [suggestion("Perlite", "Fern: Root rot", now + Duration::days(1))];
+        assert_eq!(build_list(&later, &removed).len(), 1);
+    }
+}
@@ -0,0 +1,166 @@
+//! SPECIES CATALOG
+//!
+//! Bundled names of common houseplants, to catch a mistyped plant name
+//! before a care schedule is written for it. A name is known when it is a
+//! species' scientific or common name, or one of them followed by a
+//! cultivar ("Monstera deliciosa Thai Constellation"). Otherwise the
+//! species whose names it is a few typos from, or the start of, are
+//! suggested instead.
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Bundled catalog data
+const SPECIES_JSON: &str = include_str!("species.json");
+
+/// Most suggestions given for a name
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Fewest letters a name needs to be completed as the start of another
+const MIN_PREFIX: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogSpecies {
+    pub scientific_name: String,
+    #[serde(default)]
+    pub common_names: Vec<String>,
+}
+
+impl CatalogSpecies {
+    fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.scientific_name.as_str()).chain(self.common_names.iter().map(String::as_str))
+    }
+
+    /// E.g. "Monstera deliciosa (Swiss cheese plant)"
+    pub fn describe(&self) -> String {
+        match self.common_names.first() {
+            Some(common) => format!("{} ({})", self.scientific_name, common),
+            None => self.scientific_name.clone(),
+        }
+    }
+}
+
+/// How a plant name compares to the catalog
+#[derive(Debug, Clone)]
+pub enum SpeciesMatch {
+    Known(&'static CatalogSpecies),
+    /// Species the name may have meant, closest first
+    Close(Vec<&'static CatalogSpecies>),
+    Unknown,
+}
+
+/// All bundled species
+pub fn species() -> &'static [CatalogSpecies] {
+    static SPECIES: OnceLock<Vec<CatalogSpecies>> = OnceLock::new();
+    SPECIES.get_or_init(|| serde_json::from_str(SPECIES_JSON).expect("bundled species data is valid JSON"))
+}
+
+/// Look a plant name up in the catalog (case-insensitive)
+pub fn resolve(name: &str) -> SpeciesMatch {
+    let name = normalize(name);
+    if name.is_empty() {
+        return SpeciesMatch::Unknown;
+    }
+
+    let known = species().iter().find(|s| {
+        s.names()
+            .map(normalize)
+            .any(|n| name == n || name.strip_prefix(&n).is_some_and(|rest| rest.starts_with(' ')))
+    });
+    if let Some(known) = known {
+        return SpeciesMatch::Known(known);
+    }
+
+    // Each species by its closest name, or the closest start of one
+    let mut close: Vec<(usize, &CatalogSpecies)> = species()
+        .iter()
+        .filter_map(|s| {
+            s.names()
+                .map(normalize)
+                .filter_map(|n| {
+                    let whole = Some(edit_distance(&name, &n)).filter(|d| *d <= max_typos(&n));
+                    let start = prefix_distance(&name, &n).filter(|d| *d <= max_typos(&name));
+                    whole.into_iter().chain(start).min()
+                })
+                .min()
+                .map(|distance| (distance, s))
+        })
+        .collect();
+    close.sort_by_key(|(distance, _)| *distance);
+    close.truncate(MAX_SUGGESTIONS);
+
+    if close.is_empty() {
+        SpeciesMatch::Unknown
+    } else {
+        SpeciesMatch::Close(close.into_iter().map(|(_, s)| s).collect())
+    }
+}
+
+/// Lowercase, with single spaces between words
+fn normalize(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Edits turning `name` into the start of `other`, allowing for a letter
+/// missed or doubled; None when `name` is too short to complete
+fn prefix_distance(name: &str, other: &str) -> Option<usize> {
+    let length = name.chars().count();
+    if length < MIN_PREFIX {
+        return None;
+    }
+    (length - 1..=length + 1)
+        .filter_map(|end| other.char_indices().map(|(i, _)| i).chain([other.len()]).nth(end))
+        .map(|end| edit_distance(name, &other[..end]))
+        .min()
+}
+
+/// Typos allowed in a name: one per four letters, up to three
+fn max_typos(name: &str) -> usize {
+    (name.chars().count() / 4).clamp(1, 3)
+}
+
+/// Edits (insertions, deletions, substitutions and swaps of neighbouring
+/// letters) turning `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows: Vec<Vec<usize>> = vec![(0..=b.len()).collect()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1).min(row[j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestions(name: &str) -> Vec<&'static str> {
+        match resolve(name) {
+            SpeciesMatch::Close(close) => close.iter().map(|s| s.scientific_name.as_str()).collect(),
+            other => panic!("expected suggestions for {}, got {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert!(matches!(resolve("monstera  DELICIOSA"), SpeciesMatch::Known(s) if s.scientific_name == "Monstera deliciosa"));
+        assert!(matches!(resolve("Snake plant"), SpeciesMatch::Known(s) if s.scientific_name == "Dracaena trifasciata"));
+        assert!(matches!(resolve("Monstera deliciosa Thai Constellation"), SpeciesMatch::Known(_)));
+
+        assert_eq!(suggestions("Monstrea delicosa")[0], "Monstera deliciosa");
+        assert_eq!(suggestions("pothso")[0], "Epipremnum aureum");
+        assert_eq!(suggestions("fiddle leaf"), ["Ficus lyrata"]);
+        assert_eq!(suggestions("Fidle leaf")[0], "Ficus lyrata");
+        assert!(matches!(resolve("Mystery shrub"), SpeciesMatch::Unknown));
+        assert!(matches!(resolve(""), SpeciesMatch::Unknown));
+    }
+}
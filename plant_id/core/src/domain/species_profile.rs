@@ -0,0 +1,171 @@
+//! SPECIES PROFILE
+//!
+//! Factual data about a species from a plant database (Perenual, Trefle),
+//! used as the baseline of a care schedule instead of the AI's answer for
+//! the fields the database covers. The databases describe growing
+//! conditions as categories or 0-10 scales; the helpers here turn those
+//! into care schedule text that the validation and light model understand.
+
+use serde::{Deserialize, Serialize};
+
+use super::care_schedule::CareScheduleUpdate;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeciesProfile {
+    /// Database the profile comes from, e.g. "Trefle"
+    pub source: String,
+    pub scientific_name: String,
+    pub common_name: Option<String>,
+    pub family: Option<String>,
+    pub light: Option<String>,
+    pub water: Option<String>,
+    pub humidity: Option<String>,
+    pub temperature: Option<String>,
+}
+
+impl SpeciesProfile {
+    /// The database's care data, as an update over a generated schedule
+    pub fn care_update(&self) -> CareScheduleUpdate {
+        CareScheduleUpdate {
+            light: self.light.clone(),
+            water: self.water.clone(),
+            humidity: self.humidity.clone(),
+            temperature: self.temperature.clone(),
+            care_instructions: None,
+        }
+    }
+
+    /// Names of the care schedule fields the database provides
+    pub fn care_fields(&self) -> Vec<&'static str> {
+        [
+            ("light", &self.light),
+            ("water", &self.water),
+            ("humidity", &self.humidity),
+            ("temperature", &self.temperature),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.is_some())
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+/// Light on a 0 (deep shade) to 10 (full sun all day) scale
+pub fn light_from_scale(level: u8) -> String {
+    match level {
+        0..=2 => "Low light",
+        3..=4 => "Medium light",
+        5..=7 => "Bright indirect light",
+        _ => "Full sun",
+    }
+    .to_string()
+}
+
+/// Soil moisture on a 0 (dry, desert plants) to 10 (aquatic) scale
+pub fn water_from_scale(level: u8) -> String {
+    match level {
+        0..=3 => "Let the soil dry out completely between waterings",
+        4..=5 => "Water when the top few centimetres of soil are dry",
+        6..=7 => "Keep the soil lightly moist",
+        _ => "Keep the soil consistently wet",
+    }
+    .to_string()
+}
+
+/// Air humidity on a 0 (10% or less) to 10 (90% or more) scale
+pub fn humidity_from_scale(level: u8) -> String {
+    format!("Around {}% relative humidity", level.clamp(1, 9) as u32 * 10)
+}
+
+/// A temperature range in °C, when both ends are known
+pub fn temperature_range(min_c: Option<f64>, max_c: Option<f64>) -> Option<String> {
+    match (min_c, max_c) {
+        (Some(min), Some(max)) if min <= max => Some(format!("{:.0}-{:.0}°C", min, max)),
+        _ => None,
+    }
+}
+
+/// Light from sunlight categories such as ["full sun", "part shade"]
+pub fn light_from_sunlight(sunlight: &[String]) -> Option<String> {
+    let text = sunlight
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut chars = text.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+/// Watering from a frequency category ("Frequent", "Average", "Minimum",
+/// "None") and an optional interval such as "7-10 days"
+pub fn water_from_frequency(frequency: &str, interval: Option<&str>) -> Option<String> {
+    let advice = match frequency.trim().to_lowercase().as_str() {
+        "frequent" => "Keep the soil lightly moist",
+        "average" => "Water when the top of the soil is dry",
+        "minimum" => "Let the soil dry out between waterings",
+        "none" => "Rarely needs watering",
+        _ => return None,
+    };
+
+    Some(match interval.map(str::trim).filter(|i| !i.is_empty()) {
+        Some(interval) => format!("{}, about every {}", advice, interval),
+        None => advice.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::placement::{LightLevel, LightRequirement};
+    use crate::domain::validation::parse_temperature_range;
+
+    #[test]
+    fn test_scales_become_schedule_text() {
+        let level = |text: &str| LightRequirement::parse(text).map(|r| r.min);
+        assert_eq!(level(&light_from_scale(1)), Some(LightLevel::Low));
+        assert_eq!(level(&light_from_scale(4)), Some(LightLevel::Medium));
+        assert_eq!(level(&light_from_scale(6)), Some(LightLevel::Bright));
+        assert_eq!(level(&light_from_scale(10)), Some(LightLevel::Direct));
+        assert_eq!(
+            level(&light_from_sunlight(&["part shade".to_string(), "full sun".to_string()]).unwrap()),
+            Some(LightLevel::Medium)
+        );
+        assert_eq!(light_from_sunlight(&[]), None);
+
+        assert_eq!(humidity_from_scale(6), "Around 60% relative humidity");
+        assert_eq!(humidity_from_scale(0), "Around 10% relative humidity");
+
+        let range = temperature_range(Some(-5.0), Some(30.0)).unwrap();
+        assert_eq!(parse_temperature_range(&range), Some((-5.0, 30.0)));
+        assert_eq!(temperature_range(Some(20.0), None), None);
+
+        assert_eq!(
+            water_from_frequency("Average", Some("7-10 days")).as_deref(),
+            Some("Water when the top of the soil is dry, about every 7-10 days")
+        );
+        assert_eq!(water_from_frequency("Upgrade to premium", None), None);
+    }
+
+    #[test]
+    fn test_care_update() {
+        let profile = SpeciesProfile {
+            source: "Trefle".to_string(),
+            scientific_name: "Monstera deliciosa".to_string(),
+            common_name: None,
+            family: None,
+            light: Some(light_from_scale(6)),
+            water: None,
+            humidity: None,
+            temperature: temperature_range(Some(15.0), Some(30.0)),
+        };
+
+        assert_eq!(profile.care_fields(), vec!["light", "temperature"]);
+        let update = profile.care_update();
+        assert_eq!(update.light.as_deref(), Some("Bright indirect light"));
+        assert_eq!(update.water, None);
+        assert_eq!(update.care_instructions, None);
+    }
+}
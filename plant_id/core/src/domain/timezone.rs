@@ -0,0 +1,164 @@
+//! TIME ZONES
+//!
+//! Timestamps are stored in UTC. The owner's time zone decides which
+//! calendar day an instant falls on: what "today" is, when a care task done
+//! last night was done, and how dates are shown. Recurrence is counted in
+//! local calendar days, so a daylight saving change never moves a task to
+//! another day.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Utc};
+use jiff::tz::TimeZone;
+
+#[derive(Debug, Clone)]
+pub struct LocalZone {
+    tz: TimeZone,
+    name: String,
+}
+
+impl LocalZone {
+    pub fn utc() -> Self {
+        Self {
+            tz: TimeZone::UTC,
+            name: "UTC".to_string(),
+        }
+    }
+
+    /// The time zone of the system (`TZ`, then `/etc/localtime` on Unix),
+    /// or UTC when it cannot be determined
+    pub fn system() -> Self {
+        let tz = TimeZone::system();
+        let name = tz.iana_name().unwrap_or("local").to_string();
+        Self { tz, name }
+    }
+
+    /// An IANA time zone such as "Europe/Paris"
+    pub fn named(name: &str) -> Result<Self> {
+        let name = name.trim();
+        let tz = TimeZone::get(name)
+            .with_context(|| format!("Unknown time zone '{}': expected a name such as 'Europe/Paris'", name))?;
+        Ok(Self {
+            tz,
+            name: name.to_string(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The wall-clock time of an instant in this zone
+    pub fn local(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        let offset = jiff::Timestamp::new(at.timestamp(), at.timestamp_subsec_nanos() as i32)
+            .map(|ts| self.tz.to_offset(ts).seconds())
+            .unwrap_or(0);
+        let offset = FixedOffset::east_opt(offset).unwrap_or(FixedOffset::east_opt(0).unwrap());
+        at.with_timezone(&offset)
+    }
+
+    /// The calendar day an instant falls on in this zone
+    pub fn date(&self, at: DateTime<Utc>) -> NaiveDate {
+        self.local(at).date_naive()
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.date(Utc::now())
+    }
+
+    /// The instant a local day begins. A day whose midnight is skipped by a
+    /// daylight saving change begins when the clocks go forward.
+    pub fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        let start = jiff::civil::Date::new(date.year() as i16, date.month() as i8, date.day() as i8)
+            .and_then(|day| day.to_zoned(self.tz.clone()))
+            .map(|zoned| zoned.timestamp());
+
+        match start {
+            Ok(ts) => DateTime::from_timestamp(ts.as_second(), 0).unwrap_or_default(),
+            // Outside the range of the time zone database
+            Err(_) => date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+        }
+    }
+
+    /// How far an instant is from `now` in calendar days of this zone:
+    /// "today", "yesterday", "3 weeks ago", "in 2 days"
+    pub fn relative(&self, at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        match (self.date(now) - self.date(at)).num_days() {
+            0 => "today".to_string(),
+            1 => "yesterday".to_string(),
+            -1 => "tomorrow".to_string(),
+            days if days < 0 => format!("in {}", span(-days)),
+            days => format!("{} ago", span(days)),
+        }
+    }
+}
+
+/// A number of days in the largest unit that keeps it readable
+fn span(days: i64) -> String {
+    let (count, unit) = match days {
+        0..=13 => (days, "day"),
+        14..=59 => (days / 7, "week"),
+        60..=364 => (days / 30, "month"),
+        _ => (days / 365, "year"),
+    };
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    #[test]
+    fn test_dates_follow_the_local_day() {
+        let paris = LocalZone::named("Europe/Paris").unwrap();
+        // 23:30 UTC is already the next day in Paris, in summer and winter
+        let summer = Utc.with_ymd_and_hms(2024, 7, 1, 23, 30, 0).unwrap();
+        assert_eq!(paris.date(summer), NaiveDate::from_ymd_opt(2024, 7, 2).unwrap());
+        assert_eq!(paris.local(summer).format("%H:%M").to_string(), "01:30");
+        let winter = Utc.with_ymd_and_hms(2024, 1, 1, 23, 30, 0).unwrap();
+        assert_eq!(paris.local(winter).format("%H:%M").to_string(), "00:30");
+
+        let new_york = LocalZone::named("America/New_York").unwrap();
+        assert_eq!(new_york.date(summer), NaiveDate::from_ymd_opt(2024, 7, 1).unwrap());
+        assert_eq!(LocalZone::utc().date(summer), NaiveDate::from_ymd_opt(2024, 7, 1).unwrap());
+
+        assert!(LocalZone::named("Mars/Olympus_Mons").is_err());
+    }
+
+    #[test]
+    fn test_start_of_day_across_dst() {
+        let paris = LocalZone::named("Europe/Paris").unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        // The clocks go forward on March 31st, so that day is 23 hours long
+        assert_eq!(paris.start_of_day(day(30)), Utc.with_ymd_and_hms(2024, 3, 29, 23, 0, 0).unwrap());
+        assert_eq!(paris.start_of_day(day(31)), Utc.with_ymd_and_hms(2024, 3, 30, 23, 0, 0).unwrap());
+        let april = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        assert_eq!(paris.start_of_day(april), Utc.with_ymd_and_hms(2024, 3, 31, 22, 0, 0).unwrap());
+
+        // Santiago skips midnight: the day begins at 01:00
+        let santiago = LocalZone::named("America/Santiago").unwrap();
+        let start = santiago.start_of_day(NaiveDate::from_ymd_opt(2024, 9, 8).unwrap());
+        assert_eq!(santiago.local(start).format("%H:%M").to_string(), "01:00");
+    }
+
+    #[test]
+    fn test_relative_dates() {
+        let utc = LocalZone::utc();
+        let now = Utc.with_ymd_and_hms(2024, 6, 15, 9, 0, 0).unwrap();
+        let ago = |days| now - chrono::Duration::days(days);
+        assert_eq!(utc.relative(now, now), "today");
+        assert_eq!(utc.relative(ago(1), now), "yesterday");
+        assert_eq!(utc.relative(ago(-1), now), "tomorrow");
+        assert_eq!(utc.relative(ago(5), now), "5 days ago");
+        assert_eq!(utc.relative(ago(21), now), "3 weeks ago");
+        assert_eq!(utc.relative(ago(95), now), "3 months ago");
+        assert_eq!(utc.relative(ago(400), now), "1 year ago");
+        assert_eq!(utc.relative(ago(-3), now), "in 3 days");
+
+        // 22:00 UTC the day before is already today in Tokyo
+        let tokyo = LocalZone::named("Asia/Tokyo").unwrap();
+        let late = Utc.with_ymd_and_hms(2024, 6, 14, 22, 0, 0).unwrap();
+        assert_eq!(utc.relative(late, now), "yesterday");
+        assert_eq!(tokyo.relative(late, now), "today");
+    }
+}
@@ -0,0 +1,120 @@
+//! TOXICITY
+//!
+//! Whether common houseplants are toxic to cats and dogs, following the
+//! ASPCA's lists. Plants are matched by lowercase genus or common name
+//! fragments, the same way as the encyclopedia's susceptible plants.
+
+use serde::{Deserialize, Serialize};
+
+use super::encyclopedia::contains_word;
+
+/// A pet the owner keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pet {
+    Cat,
+    Dog,
+}
+
+impl Pet {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cat => "cat",
+            Self::Dog => "dog",
+        }
+    }
+}
+
+/// Known toxicity: name fragments, then whether the plant is toxic to cats
+/// and to dogs
+const PLANTS: &[(&[&str], bool, bool)] = &[
+    // Toxic
+    (&["monstera", "swiss cheese plant"], true, true),
+    (&["philodendron"], true, true),
+    (&["epipremnum", "pothos"], true, true),
+    (&["dieffenbachia", "dumb cane"], true, true),
+    (&["zamioculcas", "zz plant"], true, true),
+    (&["sansevieria", "trifasciata", "snake plant", "mother-in-law's tongue"], true, true),
+    (&["spathiphyllum", "peace lily"], true, true),
+    (&["aloe"], true, true),
+    (&["crassula", "jade plant"], true, true),
+    (&["dracaena", "dragon tree", "corn plant"], true, true),
+    (&["aglaonema", "chinese evergreen"], true, true),
+    (&["caladium"], true, true),
+    (&["alocasia", "elephant ear"], true, true),
+    (&["ficus", "fiddle leaf fig", "rubber plant", "weeping fig"], true, true),
+    (&["hedera", "english ivy"], true, true),
+    (&["cycas", "sago palm"], true, true),
+    (&["syngonium", "arrowhead plant"], true, true),
+    (&["schefflera", "umbrella tree"], true, true),
+    (&["kalanchoe"], true, true),
+    (&["codiaeum", "croton"], true, true),
+    (&["asparagus fern"], true, true),
+    (&["cyclamen"], true, true),
+    (&["euphorbia", "poinsettia"], true, true),
+    (&["anthurium", "flamingo flower"], true, true),
+    (&["lilium", "lily"], true, false),
+    // Safe
+    (&["chlorophytum", "spider plant"], false, false),
+    (&["nephrolepis", "boston fern"], false, false),
+    (&["calathea", "goeppertia"], false, false),
+    (&["maranta", "prayer plant"], false, false),
+    (&["chamaedorea", "parlor palm", "parlour palm"], false, false),
+    (&["dypsis", "areca palm"], false, false),
+    (&["beaucarnea", "ponytail palm"], false, false),
+    (&["peperomia"], false, false),
+    (&["pilea"], false, false),
+    (&["hoya", "wax plant"], false, false),
+    (&["saintpaulia", "african violet"], false, false),
+    (&["haworthia", "haworthiopsis"], false, false),
+    (&["bromeliad", "guzmania", "vriesea"], false, false),
+    (&["schlumbergera", "christmas cactus"], false, false),
+    (&["phalaenopsis", "moth orchid"], false, false),
+    (&["fittonia", "nerve plant"], false, false),
+    (&["aspidistra", "cast iron plant"], false, false),
+    (&["pachira", "money tree"], false, false),
+    (&["asplenium", "bird's nest fern"], false, false),
+    (&["echeveria"], false, false),
+];
+
+/// The pets a plant is known to be toxic to, or None when the plant is not
+/// in the bundled list
+pub fn toxic_to(name: &str) -> Option<Vec<Pet>> {
+    let name = name.to_lowercase();
+    // "Peace lily" is not a true lily: prefer the longest matching fragment
+    let (_, cats, dogs) = PLANTS
+        .iter()
+        .filter_map(|(fragments, cats, dogs)| {
+            fragments
+                .iter()
+                .filter(|f| contains_word(&name, f))
+                .map(|f| f.len())
+                .max()
+                .map(|len| (len, *cats, *dogs))
+        })
+        .max_by_key(|(len, _, _)| *len)?;
+
+    let mut pets = Vec::new();
+    if cats {
+        pets.push(Pet::Cat);
+    }
+    if dogs {
+        pets.push(Pet::Dog);
+    }
+    Some(pets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toxic_to() {
+        assert_eq!(toxic_to("Monstera deliciosa"), Some(vec![Pet::Cat, Pet::Dog]));
+        assert_eq!(toxic_to("Spider Plant"), Some(vec![]));
+        assert_eq!(toxic_to("Easter lily (Lilium longiflorum)"), Some(vec![Pet::Cat]));
+        assert_eq!(toxic_to("Peace lily"), Some(vec![Pet::Cat, Pet::Dog]));
+        assert_eq!(toxic_to("Calathea orbifolia"), Some(vec![]));
+        assert_eq!(toxic_to("Tradescantia zebrina"), None);
+    }
+}
@@ -0,0 +1,131 @@
+//! TREATMENT OUTCOME DOMAIN MODEL
+//!
+//! Whether a diagnosis recommendation actually worked, as reported by the
+//! user in a follow-up check-in. Outcomes are grouped per species so future
+//! diagnoses of the same species can learn from them.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreatmentOutcome {
+    pub id: String,
+    pub plant_id: String,
+    /// Normalized species name, see `species_key`
+    pub species: String,
+    pub diagnosis_id: String,
+    pub finding: String,
+    pub recommendation: String,
+    pub worked: bool,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TreatmentOutcome {
+    pub fn new(
+        plant_id: String,
+        plant_name: &str,
+        diagnosis_id: String,
+        finding: String,
+        recommendation: String,
+        worked: bool,
+        note: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            plant_id,
+            species: species_key(plant_name),
+            diagnosis_id,
+            finding,
+            recommendation,
+            worked,
+            note,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Key used to group plants of the same species
+pub fn species_key(plant_name: &str) -> String {
+    plant_name.trim().to_lowercase()
+}
+
+/// How often a recommendation worked for a finding on one species
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpeciesExperience {
+    pub finding: String,
+    pub recommendation: String,
+    pub worked: usize,
+    pub failed: usize,
+}
+
+/// Group outcomes by finding and recommendation, most reported first
+pub fn summarize_outcomes(outcomes: &[TreatmentOutcome]) -> Vec<SpeciesExperience> {
+    let mut experience: Vec<SpeciesExperience> = Vec::new();
+
+    for outcome in outcomes {
+        let existing = experience.iter_mut().find(|e| {
+            e.finding.eq_ignore_ascii_case(&outcome.finding)
+                && e.recommendation.eq_ignore_ascii_case(&outcome.recommendation)
+        });
+        let entry = match existing {
+            Some(entry) => entry,
+            None => {
+                experience.push(SpeciesExperience {
+                    finding: outcome.finding.clone(),
+                    recommendation: outcome.recommendation.clone(),
+                    worked: 0,
+                    failed: 0,
+                });
+                experience.last_mut().unwrap()
+            }
+        };
+
+        if outcome.worked {
+            entry.worked += 1;
+        } else {
+            entry.failed += 1;
+        }
+    }
+
+    experience.sort_by_key(|e| std::cmp::Reverse(e.worked + e.failed));
+    experience
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(finding: &str, recommendation: &str, worked: bool) -> TreatmentOutcome {
+        TreatmentOutcome::new(
+            "p1".to_string(),
+            "Monstera",
+            "d1".to_string(),
+            finding.to_string(),
+            recommendation.to_string(),
+            worked,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_species_key_normalizes_name() {
+        assert_eq!(species_key("  Monstera Deliciosa "), "monstera deliciosa");
+    }
+
+    #[test]
+    fn test_summarize_groups_outcomes() {
+        let outcomes = vec![
+            outcome("Root rot", "Repot in fresh soil", true),
+            outcome("root rot", "repot in fresh soil", false),
+            outcome("Root rot", "Repot in fresh soil", true),
+            outcome("Sun scorch", "Move away from window", true),
+        ];
+
+        let summary = summarize_outcomes(&outcomes);
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].finding, "Root rot");
+        assert_eq!((summary[0].worked, summary[0].failed), (2, 1));
+    }
+}
@@ -0,0 +1,94 @@
+//! USER DOMAIN MODEL
+//!
+//! Who uses the collection on this device and what they may do. Everyone
+//! is an owner until the owner hands the device over to a plant sitter,
+//! who can read care instructions and tick off care tasks but cannot
+//! remove plants or start AI sessions. Sitter mode ends with the PIN the
+//! owner chose when handing over.
+//!
+//! Users of the HTTP server authenticate with an API key, which maps the
+//! request to their user ID so repositories only return their records.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::enums::UserRole;
+use super::request_error::RequestError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub role: UserRole,
+    /// Hash of the PIN that ends sitter mode
+    pub pin_hash: Option<String>,
+    /// Hash of the user's API key for the HTTP server
+    pub api_key_hash: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl User {
+    pub fn owner(id: String) -> Self {
+        Self {
+            id,
+            role: UserRole::Owner,
+            pin_hash: None,
+            api_key_hash: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Actions not every role may take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Delete or archive plants
+    RemovePlants,
+    /// Start or continue a diagnosis or chat
+    StartAiSessions,
+}
+
+impl Permission {
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::RemovePlants => "remove plants",
+            Self::StartAiSessions => "start AI sessions",
+        }
+    }
+}
+
+impl UserRole {
+    pub fn allows(&self, permission: Permission) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Sitter => match permission {
+                Permission::RemovePlants | Permission::StartAiSessions => false,
+            },
+        }
+    }
+
+    /// Fail unless the role allows `permission`
+    pub fn require(&self, permission: Permission) -> Result<()> {
+        if !self.allows(permission) {
+            return Err(RequestError::NotPermitted(permission.describe()).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sitter_permissions() {
+        assert!(UserRole::Owner.require(Permission::RemovePlants).is_ok());
+
+        let error = UserRole::Sitter.require(Permission::StartAiSessions).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "A plant sitter cannot start AI sessions. Ask the owner to end sitter mode."
+        );
+        assert!(!UserRole::Sitter.allows(Permission::RemovePlants));
+    }
+}
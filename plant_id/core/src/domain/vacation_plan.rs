@@ -0,0 +1,200 @@
+//! VACATION PLAN
+//!
+//! What to do before leaving the collection alone and what a plant sitter
+//! has to do while the owner is away. Watering visits come from each care
+//! schedule, assuming every plant is watered the day before departure; the
+//! AI adds practical tips on top.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use super::enums::CareTaskKind;
+use super::{CareTask, Plant};
+
+/// Longest trip a plan can be made for
+pub const MAX_TRIP_DAYS: i64 = 90;
+
+/// One plant's part of the sitter instructions
+#[derive(Debug, Clone, Serialize)]
+pub struct SitterEntry {
+    pub plant_id: String,
+    pub plant_name: String,
+    pub room: Option<String>,
+    /// Days the sitter has to water, empty when the pre-departure watering
+    /// lasts the whole trip
+    pub watering_dates: Vec<NaiveDate>,
+    pub watering: String,
+    pub light: String,
+    /// Plain-language advice for someone who does not know the plant
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VacationPlan {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Pre-departure checklist
+    pub prep: Vec<String>,
+    pub plants: Vec<SitterEntry>,
+}
+
+impl VacationPlan {
+    /// Plan built from the care schedules alone
+    pub fn new(from: NaiveDate, to: NaiveDate, plants: &[Plant]) -> Result<Self> {
+        if to < from {
+            anyhow::bail!("The trip must end on or after {}", from);
+        }
+        let trip_days = (to - from).num_days() + 1;
+        if trip_days > MAX_TRIP_DAYS {
+            anyhow::bail!("Trips are limited to {} days, got {}", MAX_TRIP_DAYS, trip_days);
+        }
+
+        let day_before = from - Duration::days(1);
+        let entries: Vec<SitterEntry> = plants
+            .iter()
+            .map(|plant| SitterEntry {
+                plant_id: plant.id.clone(),
+                plant_name: plant.display_name().to_string(),
+                room: plant.room.clone(),
+                watering_dates: watering_dates(
+                    CareTask::new(&plant.id, CareTaskKind::Water).interval_days(&plant.care_schedule),
+                    day_before,
+                    to,
+                ),
+                watering: plant.care_schedule.water.clone(),
+                light: plant.care_schedule.light.clone(),
+                note: None,
+            })
+            .collect();
+
+        let mut prep = vec![format!(
+            "Water every plant thoroughly on {}",
+            day_before.format("%a %b %-d")
+        )];
+        let visits = entries.iter().filter(|e| !e.watering_dates.is_empty()).count();
+        if visits > 0 {
+            prep.push(format!(
+                "Arrange a plant sitter: {} of {} plants need watering while you are away",
+                visits,
+                entries.len()
+            ));
+            prep.push("Give the sitter the instructions below and show them where each plant is".to_string());
+        }
+        let feeding_due = plants.iter().any(|p| {
+            CareTask::new(&p.id, CareTaskKind::Fertilize).interval_days(&p.care_schedule) <= trip_days
+        });
+        if feeding_due {
+            prep.push(format!(
+                "Skip fertilizing while away and resume after {}",
+                to.format("%a %b %-d")
+            ));
+        }
+
+        Ok(Self {
+            from,
+            to,
+            prep,
+            plants: entries,
+        })
+    }
+
+    pub fn trip_days(&self) -> i64 {
+        (self.to - self.from).num_days() + 1
+    }
+
+    /// The checklist and sitter sheet as a markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# Vacation Care Plan ({} to {})\n\n## Before You Leave\n\n",
+            self.from, self.to
+        );
+        for item in &self.prep {
+            markdown.push_str(&format!("- [ ] {}\n", item));
+        }
+
+        markdown.push_str(&format!(
+            "\n## Plant Sitter Instructions\n\nThe plants were all watered on {}. \
+             Water only on the days listed, until the water runs out of the drainage holes.\n",
+            (self.from - Duration::days(1)).format("%a %b %-d")
+        ));
+        for entry in &self.plants {
+            markdown.push_str(&format!("\n### {}", entry.plant_name));
+            if let Some(room) = &entry.room {
+                markdown.push_str(&format!(" ({})", room));
+            }
+            markdown.push_str("\n\n");
+
+            if entry.watering_dates.is_empty() {
+                markdown.push_str("- No watering needed\n");
+            } else {
+                let dates: Vec<String> = entry
+                    .watering_dates
+                    .iter()
+                    .map(|d| d.format("%a %b %-d").to_string())
+                    .collect();
+                markdown.push_str(&format!("- Water on: {}\n", dates.join(", ")));
+                markdown.push_str(&format!("- How: {}\n", entry.watering));
+            }
+            markdown.push_str(&format!("- Light: {}\n", entry.light));
+            if let Some(note) = &entry.note {
+                markdown.push_str(&format!("- Note: {}\n", note));
+            }
+        }
+
+        markdown
+    }
+}
+
+/// Days a plant watered on `last_watered` is due again, up to `until`
+pub fn watering_dates(interval_days: i64, last_watered: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+    let interval = Duration::days(interval_days.max(1));
+    std::iter::successors(Some(last_watered + interval), |date| Some(*date + interval))
+        .take_while(|date| *date <= until)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 7, day).unwrap()
+    }
+
+    fn plant(name: &str, water: &str) -> Plant {
+        Plant::new(
+            "u".to_string(),
+            name.to_string(),
+            CareSchedule {
+                water: water.to_string(),
+                ..CareSchedule::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_watering_dates() {
+        assert_eq!(watering_dates(5, date(1), date(14)), vec![date(6), date(11)]);
+        assert!(watering_dates(14, date(1), date(14)).is_empty());
+    }
+
+    #[test]
+    fn test_plan_from_schedules() {
+        let plants = [plant("Fern", "Every 3 days"), plant("Cactus", "Every 3 weeks")];
+        let plan = VacationPlan::new(date(1), date(7), &plants).unwrap();
+
+        assert_eq!(plan.trip_days(), 7);
+        assert_eq!(plan.plants[0].watering_dates, vec![date(3), date(6)]);
+        assert!(plan.plants[1].watering_dates.is_empty());
+        assert!(plan.prep[0].contains("Sun Jun 30"));
+        assert!(plan.prep[1].contains("1 of 2 plants"));
+
+        let markdown = plan.to_markdown();
+        assert!(markdown.contains("### Fern\n\n- Water on: Wed Jul 3, Sat Jul 6\n"));
+        assert!(markdown.contains("### Cactus\n\n- No watering needed\n"));
+
+        assert!(VacationPlan::new(date(7), date(1), &plants).is_err());
+    }
+}
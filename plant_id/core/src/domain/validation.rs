@@ -0,0 +1,291 @@
+/*!
+ * CARE SCHEDULE VALIDATION
+ *
+ * Sanity checks for AI-generated care schedules. Errors mean the schedule is
+ * nonsense and should be regenerated; warnings are surfaced to the user but
+ * do not block saving.
+ */
+
+use anyhow::Result;
+
+use super::care_schedule::{CareSchedule, CareScheduleUpdate};
+use super::request_error::RequestError;
+
+/// Plausible temperature bounds for cultivated plants, in °C
+const MIN_PLAUSIBLE_TEMP_C: f64 = -20.0;
+const MAX_PLAUSIBLE_TEMP_C: f64 = 50.0;
+
+#[derive(Debug, Default, Clone)]
+pub struct ValidationReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// The schedule `update` turns `schedule` into, with the warnings raised
+/// for it. Fails if the update changes nothing or the result is invalid.
+pub fn validate_care_schedule_update(
+    schedule: &CareSchedule,
+    update: &CareScheduleUpdate,
+) -> Result<(CareSchedule, Vec<String>)> {
+    if update.is_empty() {
+        return Err(RequestError::Invalid {
+            what: "care schedule update",
+            reason: "no changes given".to_string(),
+        }
+        .into());
+    }
+
+    let schedule = update.apply_to(schedule);
+    let report = validate_care_schedule(&schedule);
+    if !report.is_valid() {
+        return Err(RequestError::Invalid {
+            what: "care schedule",
+            reason: report.errors.join("; "),
+        }
+        .into());
+    }
+
+    Ok((schedule, report.warnings))
+}
+
+/// Validate a care schedule, collecting errors and warnings
+pub fn validate_care_schedule(schedule: &CareSchedule) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    if schedule.light.trim().is_empty() {
+        report.errors.push("Light requirements are empty".to_string());
+    }
+
+    if schedule.water.trim().is_empty() {
+        report.errors.push("Watering instructions are empty".to_string());
+    }
+
+    match parse_temperature_range(&schedule.temperature) {
+        Some((min, max)) => {
+            if min > max {
+                report.errors.push(format!(
+                    "Temperature range is inverted ({:.0}°C > {:.0}°C)",
+                    min, max
+                ));
+            } else if min < MIN_PLAUSIBLE_TEMP_C || max > MAX_PLAUSIBLE_TEMP_C {
+                report.errors.push(format!(
+                    "Temperature range {:.0}-{:.0}°C is implausible",
+                    min, max
+                ));
+            }
+        }
+        None => report.errors.push(format!(
+            "Temperature '{}' does not contain a parsable range",
+            schedule.temperature
+        )),
+    }
+
+    let humidity = parse_percentages(&schedule.humidity);
+    if humidity.is_empty() {
+        report
+            .warnings
+            .push("Humidity has no percentage value".to_string());
+    } else if humidity.iter().any(|h| !(0.0..=100.0).contains(h)) {
+        report.errors.push(format!(
+            "Humidity '{}' is outside 0-100%",
+            schedule.humidity
+        ));
+    }
+
+    if schedule.care_instructions.trim().is_empty() {
+        report
+            .warnings
+            .push("No additional care instructions were provided".to_string());
+    }
+
+    report
+}
+
+/// Parse the first temperature range in a description, returned in °C.
+///
+/// Accepts forms like "18-24°C", "65 to 75 F" or "min 10°C". The unit is
+/// taken from the first °C/°F marker; without one, values above 50 are
+/// assumed to be Fahrenheit.
+pub fn parse_temperature_range(text: &str) -> Option<(f64, f64)> {
+    let numbers = extract_numbers(text);
+    let (min, max) = match numbers.as_slice() {
+        [] => return None,
+        [single] => (single.value, single.value),
+        [first, second, ..] => (first.value, second.value),
+    };
+
+    let lower = text.to_lowercase();
+    let celsius_at = ["°c", "ºc", "celsius", " c "]
+        .iter()
+        .filter_map(|m| lower.find(m))
+        .min();
+    let fahrenheit_at = ["°f", "ºf", "fahrenheit", " f "]
+        .iter()
+        .filter_map(|m| lower.find(m))
+        .min();
+
+    let is_fahrenheit = match (celsius_at, fahrenheit_at) {
+        (Some(c), Some(f)) => f < c,
+        (None, Some(_)) => true,
+        (Some(_), None) => false,
+        (None, None) => min.max(max) > MAX_PLAUSIBLE_TEMP_C,
+    };
+
+    if is_fahrenheit {
+        Some((to_celsius(min), to_celsius(max)))
+    } else {
+        Some((min, max))
+    }
+}
+
+fn to_celsius(fahrenheit: f64) -> f64 {
+    (fahrenheit - 32.0) * 5.0 / 9.0
+}
+
+/// Extract all values written as percentages ("40-60%" yields both bounds)
+fn parse_percentages(text: &str) -> Vec<f64> {
+    let numbers = extract_numbers(text);
+    let mut values = Vec::new();
+
+    for (i, number) in numbers.iter().enumerate() {
+        if !text[number.end..].trim_start().starts_with('%') {
+            continue;
+        }
+
+        // Include the lower bound of a range such as "40-60%"
+        if let Some(previous) = i.checked_sub(1).map(|j| &numbers[j]) {
+            let between = text[previous.end..number.start].trim();
+            if matches!(between, "-" | "–" | "to") {
+                values.push(previous.value);
+            }
+        }
+        values.push(number.value);
+    }
+
+    values
+}
+
+/// A number found in free text, with its byte span
+struct NumberToken {
+    value: f64,
+    start: usize,
+    end: usize,
+}
+
+/// Extract numbers from free text. A '-' is only treated as a sign when it
+/// doesn't directly follow a digit, so "18-24" is read as a range rather
+/// than 18 and -24.
+fn extract_numbers(text: &str) -> Vec<NumberToken> {
+    let bytes = text.as_bytes();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let is_sign = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit())
+            && (i == 0 || !bytes[i - 1].is_ascii_digit());
+
+        if bytes[i].is_ascii_digit() || is_sign {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                i += 1;
+            }
+            if let Ok(value) = text[start..i].trim_end_matches('.').parse::<f64>() {
+                numbers.push(NumberToken { value, start, end: i });
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(temperature: &str, humidity: &str, water: &str) -> CareSchedule {
+        CareSchedule {
+            light: "Bright, indirect light".to_string(),
+            water: water.to_string(),
+            humidity: humidity.to_string(),
+            temperature: temperature.to_string(),
+            care_instructions: "Wipe leaves monthly".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_default_schedule_is_valid() {
+        let report = validate_care_schedule(&CareSchedule {
+            care_instructions: "Rotate monthly".to_string(),
+            ..CareSchedule::default()
+        });
+        assert!(report.is_valid());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_fahrenheit_range() {
+        let (min, max) = parse_temperature_range("65-75°F").unwrap();
+        assert!((min - 18.3).abs() < 0.1);
+        assert!((max - 23.9).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_rejects_implausible_temperature() {
+        let report = validate_care_schedule(&schedule("200-300°C", "50%", "Weekly"));
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_rejects_unparsable_temperature() {
+        let report = validate_care_schedule(&schedule("warm", "50%", "Weekly"));
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_rejects_humidity_over_100() {
+        let report = validate_care_schedule(&schedule("18-24°C", "80-120%", "Weekly"));
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_rejects_empty_watering() {
+        let report = validate_care_schedule(&schedule("18-24°C", "50%", "  "));
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_warns_on_missing_humidity_percentage() {
+        let report = validate_care_schedule(&schedule("18-24°C", "High humidity", "Weekly"));
+        assert!(report.is_valid());
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_validate_care_schedule_update() {
+        let current = schedule("18-24°C", "50%", "Weekly");
+
+        let update = CareScheduleUpdate {
+            water: Some("Every 5 days".to_string()),
+            ..CareScheduleUpdate::default()
+        };
+        let (updated, _) = validate_care_schedule_update(&current, &update).unwrap();
+        assert_eq!(updated.water, "Every 5 days");
+
+        let update = CareScheduleUpdate {
+            temperature: Some("200°C".to_string()),
+            ..CareScheduleUpdate::default()
+        };
+        assert!(validate_care_schedule_update(&current, &update).is_err());
+        assert!(validate_care_schedule_update(&current, &CareScheduleUpdate::default()).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! WISHLIST
+//!
+//! Plants the owner would like to get, usually picked from recommendations.
+//! A species is on a user's wishlist at most once.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WishlistItem {
+    pub id: String,
+    pub user_id: String,
+    pub species: String,
+    pub common_name: Option<String>,
+    /// Why the plant is wanted, e.g. the recommendation's reason
+    pub note: String,
+    pub added_at: DateTime<Utc>,
+}
+
+impl WishlistItem {
+    pub fn new(user_id: &str, species: &str, common_name: Option<String>, note: String) -> Result<Self> {
+        let species = species.trim();
+        if species.is_empty() {
+            anyhow::bail!("Species name is empty");
+        }
+
+        Ok(Self {
+            id: wishlist_item_id(user_id, species),
+            user_id: user_id.to_string(),
+            species: species.to_string(),
+            common_name: common_name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+            note: note.trim().to_string(),
+            added_at: Utc::now(),
+        })
+    }
+
+    /// "Common name (Species)", or the species alone
+    pub fn display_name(&self) -> String {
+        match &self.common_name {
+            Some(common) => format!("{} ({})", common, self.species),
+            None => self.species.clone(),
+        }
+    }
+}
+
+/// ID of a species on a user's wishlist, derived from the user and the
+/// species so every device agrees on it
+pub fn wishlist_item_id(user_id: &str, species: &str) -> String {
+    let name = format!("{}:wishlist:{}", user_id, species.trim().to_lowercase());
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string()
+}
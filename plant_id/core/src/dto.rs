@@ -0,0 +1,327 @@
+/*!
+ * DATA TRANSFER OBJECTS (DTOs)
+ *
+ * Structures used to transfer data between layers and external systems.
+ */
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use std::collections::HashMap;
+
+use crate::domain::{
+    CareOverrides, CareSchedule, CareScheduleUpdate, CareTask, ChatSession, DiagnosisMessage, DiagnosisSession,
+    Expense, JournalEntry, MoistureReading, Plant, PlantAttribute, PreventiveInspection, Quarantine,
+    ReminderKind, Severity,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlantCreationDto {
+    pub images: Vec<String>, // Base64 encoded images
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Care schedule to use instead of generating one (e.g. from a template)
+    #[serde(default)]
+    pub care_schedule: Option<CareSchedule>,
+    /// Name of the template the care schedule comes from, if any
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Add a new plant even if one was already added from the same photo
+    #[serde(default)]
+    pub force_new: bool,
+    /// What to call the plant instead of its species' name
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// When the owner got the plant, if before today
+    #[serde(default)]
+    pub acquired_at: Option<DateTime<Utc>>,
+    /// Where the plant came from ("garden center", "grown from seed")
+    #[serde(default)]
+    pub acquired_from: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiagnosisStartDto {
+    pub prompt: String,
+    /// Base64 encoded photo of the problem, assessed before the diagnosis
+    /// starts
+    #[serde(default)]
+    pub photo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiagnosisUpdateDto {
+    pub message: String,
+    /// Base64 encoded photo answering a photo request
+    #[serde(default)]
+    pub photo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type")]
+pub enum DiagnosisResponseDto {
+    #[serde(rename = "ask")]
+    Ask(DiagnosisAskResponse),
+    #[serde(rename = "request_photo")]
+    RequestPhoto(DiagnosisPhotoRequestResponse),
+    #[serde(rename = "conclude")]
+    Conclude(Box<DiagnosisConcludeResponse>),
+}
+
+impl DiagnosisResponseDto {
+    /// The session the response belongs to
+    pub fn diagnosis_id(&self) -> &str {
+        match self {
+            Self::Ask(ask) => &ask.diagnosis_id,
+            Self::RequestPhoto(request) => &request.diagnosis_id,
+            Self::Conclude(conclusion) => &conclusion.diagnosis_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiagnosisAskResponse {
+    pub diagnosis_id: String,
+    pub question: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiagnosisPhotoRequestResponse {
+    pub diagnosis_id: String,
+    /// Part of the plant to photograph, e.g. "roots" or "leaf underside"
+    pub part: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiagnosisConcludeResponse {
+    pub diagnosis_id: String,
+    pub finding: String,
+    pub recommendation: String,
+    /// Names of encyclopedia entries mentioned in the finding
+    #[serde(default)]
+    pub encyclopedia_entries: Vec<String>,
+    /// Care schedule changes proposed alongside the conclusion
+    #[serde(default)]
+    pub care_update: Option<CareUpdateSuggestionDto>,
+    /// How serious the finding is
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    /// How sure the AI is of the finding, from 0 to 1
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    /// Other possible diagnoses, most likely first
+    #[serde(default)]
+    pub alternatives: Vec<AlternativeDiagnosisDto>,
+}
+
+/// A differential diagnosis considered alongside the main finding
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AlternativeDiagnosisDto {
+    pub finding: String,
+    pub probability: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CareUpdateSuggestionDto {
+    pub changes: CareScheduleUpdate,
+    pub reason: Option<String>,
+}
+
+/// AI-assigned urgency for a plant with open symptoms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriageRanking {
+    pub plant_id: String,
+    pub urgency: u8,
+    pub reason: String,
+}
+
+/// The species the AI picked from disagreeing identifications, after
+/// looking at the photo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentificationTiebreak {
+    pub scientific_name: String,
+    pub reasoning: String,
+}
+
+/// The AI's opinion of a plant name no bundled species matched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesNameCheck {
+    /// Whether the name is a real plant as written
+    pub is_plant: bool,
+    /// The plant most likely meant, when the name looks misspelled or made up
+    #[serde(default)]
+    pub suggestion: Option<String>,
+}
+
+/// AI explanation of a suggested move to another room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlacementExplanation {
+    pub plant_id: String,
+    pub explanation: String,
+}
+
+/// AI explanation of a plant's care guidance, citing the numbered sources
+/// it was given
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CareExplanationDto {
+    pub explanation: String,
+    /// Numbers of the sources the explanation relies on
+    #[serde(default)]
+    pub cited: Vec<usize>,
+}
+
+/// A new plant suggested by the AI, before it is checked against the
+/// owner's conditions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlantSuggestionDto {
+    pub species: String,
+    #[serde(default)]
+    pub common_name: Option<String>,
+    pub reason: String,
+    #[serde(default)]
+    pub light: String,
+    pub difficulty: u8,
+    #[serde(default)]
+    pub toxic_to_cats: bool,
+    #[serde(default)]
+    pub toxic_to_dogs: bool,
+}
+
+/// AI additions to a vacation plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VacationAdviceDto {
+    /// Extra steps for the pre-departure checklist
+    #[serde(default)]
+    pub prep_tips: Vec<String>,
+    #[serde(default)]
+    pub plants: Vec<VacationPlantNoteDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VacationPlantNoteDto {
+    pub plant_id: String,
+    /// One or two plain sentences for the plant sitter
+    pub note: String,
+}
+
+/// Self-contained plant profile that can be shared with other users.
+/// Contains no IDs, location data or conversation transcripts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlantProfileDto {
+    pub format_version: u32,
+    pub name: String,
+    pub care_schedule: CareSchedule,
+    pub diagnoses: Vec<DiagnosisSummaryDto>,
+    /// Base64 encoded PNG thumbnail of the plant's photo
+    pub thumbnail: Option<String>,
+    pub exported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisSummaryDto {
+    pub date: DateTime<Utc>,
+    pub finding: String,
+    pub recommendation: String,
+}
+
+/// Flat plant record for spreadsheet exports
+#[derive(Debug, Clone, Serialize)]
+pub struct PlantExportRow {
+    pub id: String,
+    pub name: String,
+    pub nickname: Option<String>,
+    pub room: Option<String>,
+    /// Tags separated by "; "
+    pub tags: String,
+    /// Care difficulty from 1 to 5, when rated
+    pub difficulty: Option<u8>,
+    pub light: String,
+    pub water: String,
+    pub humidity: String,
+    pub temperature: String,
+    pub care_instructions: String,
+    pub image_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Flat diagnosis record for spreadsheet exports
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosisExportRow {
+    pub id: String,
+    pub plant_id: String,
+    pub plant_name: String,
+    pub status: String,
+    pub problem: String,
+    pub finding: Option<String>,
+    pub recommendation: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Flat upcoming-care record for spreadsheet exports
+#[derive(Debug, Clone, Serialize)]
+pub struct ReminderExportRow {
+    pub plant_id: String,
+    pub plant_name: String,
+    pub kind: ReminderKind,
+    pub due: NaiveDate,
+    /// Days past due, negative while still ahead
+    pub overdue_days: i64,
+    /// Care task, quarantine or preventive inspection the reminder comes from
+    pub source_id: String,
+}
+
+/// Full backup of a user's collection, including embedded images
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionArchiveDto {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub plants: Vec<Plant>,
+    #[serde(default)]
+    pub attributes: Vec<PlantAttribute>,
+    #[serde(default)]
+    pub expenses: Vec<Expense>,
+    pub diagnoses: Vec<DiagnosisSession>,
+    /// Conversation turns of the diagnoses (format version 2 and later;
+    /// earlier archives keep them in each diagnosis context)
+    #[serde(default)]
+    pub diagnosis_messages: Vec<DiagnosisMessage>,
+    pub journal: Vec<JournalEntry>,
+    #[serde(default)]
+    pub moisture_readings: Vec<MoistureReading>,
+    pub chats: Vec<ChatSession>,
+    /// Species baselines and the owner's changes to them, for plants with any
+    #[serde(default)]
+    pub care_overrides: Vec<CareOverrides>,
+    /// Base64 encoded images keyed by plant ID
+    pub images: HashMap<String, String>,
+}
+
+/// Everything recorded about one plant at a point in time, to put it back
+/// the way it was after a risky edit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlantSnapshotDto {
+    pub format_version: u32,
+    pub taken_at: DateTime<Utc>,
+    pub plant: Plant,
+    /// Base64 encoded photo of the plant
+    pub image: Option<String>,
+    pub attributes: Vec<PlantAttribute>,
+    /// Recurring care tasks the reminders are worked out from
+    pub care_tasks: Vec<CareTask>,
+    pub quarantine: Option<Quarantine>,
+    /// Preventive inspections not yet done
+    pub inspections: Vec<PreventiveInspection>,
+    pub expenses: Vec<Expense>,
+    pub diagnoses: Vec<DiagnosisSession>,
+    pub diagnosis_messages: Vec<DiagnosisMessage>,
+    pub journal: Vec<JournalEntry>,
+    pub moisture_readings: Vec<MoistureReading>,
+    pub chats: Vec<ChatSession>,
+    /// The species baseline and the owner's changes to it, if any
+    #[serde(default)]
+    pub care_overrides: Option<CareOverrides>,
+}
@@ -0,0 +1,111 @@
+/*!
+ * PLANT CARE CORE
+ *
+ * Plant identification, care scheduling and AI-driven health diagnosis,
+ * independent of any user interface. The `plant-care` CLI is one frontend;
+ * GUIs and chat bots can be built on the same API.
+ *
+ * Layout (hexagonal architecture):
+ * - `domain`: entities and pure business rules
+ * - `services`: use cases, the main entry point for frontends
+ * - `repositories`: SQLite persistence
+ * - `adapters`: external services (OpenRouter, Plant.id, storage, search, sync)
+ * - `dto`: request and response types exchanged with services
+ * - `config`: database setup and secret lookup
+ *
+ * The most commonly used types are re-exported at the crate root.
+ *
+ * Persistence and the adapters that do I/O sit behind the default `native`
+ * feature. With `default-features = false` the crate builds for
+ * `wasm32-unknown-unknown`, keeping the domain rules (validation, schedule
+ * parsing), the DTOs, the ports and the diagnosis action executor for a
+ * browser front-end.
+ *
+ * # Example
+ *
+ * ```no_run
+ * # #[cfg(feature = "native")] {
+ * use plant_care_core::{
+ *     AiAdapter, AttributeRepository, Database, DiagnosisResponseDto, DiagnosisService, DiagnosisStartDto,
+ *     EventBus, MoistureRepository, OutcomeRepository, DiagnosisRepository, PlantRepository,
+ *     RoomRepository, UserRepository,
+ * };
+ * use plant_care_core::adapters::{notification_adapter, search_adapter, storage_adapter};
+ *
+ * # async fn run() -> anyhow::Result<()> {
+ * let db = Database::new().await?;
+ * db.migrate().await?;
+ *
+ * let diagnosis_service = DiagnosisService::new(
+ *     PlantRepository::new(db.clone()),
+ *     DiagnosisRepository::new(db.clone()),
+ *     OutcomeRepository::new(db.clone()),
+ *     MoistureRepository::new(db.clone()),
+ *     AttributeRepository::new(db.clone()),
+ *     RoomRepository::new(db.clone()),
+ *     UserRepository::new(db),
+ *     AiAdapter::new()?,
+ *     storage_adapter::from_env()?,
+ *     search_adapter::from_env()?,
+ *     EventBus::new(notification_adapter::from_env()?),
+ * );
+ *
+ * let dto = DiagnosisStartDto {
+ *     prompt: "Leaves are turning yellow".to_string(),
+ *     photo: None,
+ * };
+ * match diagnosis_service.start_diagnosis("plant-id", dto, "local-user".to_string()).await? {
+ *     DiagnosisResponseDto::Ask(ask) => println!("{}", ask.question),
+ *     DiagnosisResponseDto::RequestPhoto(request) => println!("Photo of the {}", request.part),
+ *     DiagnosisResponseDto::Conclude(conclusion) => println!("{}", conclusion.finding),
+ * }
+ * # Ok(())
+ * # }
+ * # }
+ * ```
+ */
+
+pub mod adapters;
+#[cfg(feature = "native")]
+pub mod config;
+#[cfg(feature = "native")]
+pub mod crypto;
+pub mod domain;
+pub mod dto;
+#[cfg(feature = "native")]
+pub mod pdf;
+#[cfg(feature = "native")]
+pub mod repositories;
+pub mod services;
+
+#[cfg(feature = "native")]
+mod templates;
+
+// Key types, for frontends that only need the common path
+pub use adapters::{NotificationPort, ProgressReporter, SearchPort, StoragePort, SyncRemote};
+#[cfg(feature = "native")]
+pub use adapters::{AiAdapter, PlantIdAdapter};
+#[cfg(feature = "native")]
+pub use config::Database;
+pub use domain::{
+    ArchiveReason, AuditEvent, CareSchedule, CareTask, CareTemplate, ChatSession, DiagnosisSession,
+    DiagnosisStatus, Feedback, FeedbackKind, JournalEntry, NotificationEvent, Plant,
+    Severity, TreatmentOutcome,
+};
+pub use dto::{
+    DiagnosisConcludeResponse, DiagnosisResponseDto, DiagnosisStartDto, DiagnosisUpdateDto,
+    PlantCreationDto,
+};
+#[cfg(feature = "native")]
+pub use repositories::{
+    AttributeRepository, CareTaskRepository, ChatRepository, DiagnosisRepository, EventRepository,
+    ExpenseRepository, FeedbackRepository, JournalRepository, MoistureRepository, OutcomeRepository,
+    PlantRepository, RoomRepository, SyncRepository, TemplateRepository, UserRepository,
+};
+pub use services::EventBus;
+#[cfg(feature = "native")]
+pub use services::{
+    ArchiveService, AuditService, BackupService, BulkService, CareScheduleService, ChatService,
+    ChecklistService, DiagnosisService, PlantService, SitterService, SyncService,
+    TemplateService, TriageService,
+};
@@ -0,0 +1,495 @@
+/*!
+ * PDF UTILITIES
+ *
+ * Renders the simple markdown documents the app produces (headings, bullet
+ * and checkbox lists, paragraphs) as a printable PDF, and sheets of boxed
+ * cards laid out to fit on one page. Uses the standard Helvetica fonts
+ * every PDF reader provides, so no font is embedded. Their WinAnsi
+ * encoding covers Latin-1 and typographic punctuation (curly quotes,
+ * dashes, the euro sign); anything else, such as emoji, shows as "?", and
+ * `unsupported_chars` tells the caller which characters those are so it
+ * can warn before writing the file.
+ */
+
+/// A4 page size, in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+
+const MARGIN: f32 = 56.0;
+
+/// Average Helvetica glyph width, as a fraction of the font size
+const AVERAGE_CHAR_WIDTH: f32 = 0.5;
+
+/// How a markdown line is typeset
+struct Style {
+    bold: bool,
+    size: f32,
+    /// Space above the line, in points
+    space_before: f32,
+    indent: f32,
+}
+
+const TITLE: Style = Style { bold: true, size: 18.0, space_before: 0.0, indent: 0.0 };
+const HEADING: Style = Style { bold: true, size: 14.0, space_before: 14.0, indent: 0.0 };
+const SUBHEADING: Style = Style { bold: true, size: 12.0, space_before: 10.0, indent: 0.0 };
+const BODY: Style = Style { bold: false, size: 11.0, space_before: 0.0, indent: 0.0 };
+const LIST_ITEM: Style = Style { bold: false, size: 11.0, space_before: 0.0, indent: 14.0 };
+
+/// Margin of a sheet of cards, narrower to leave room for them
+const SHEET_MARGIN: f32 = 36.0;
+
+/// Cards of a sheet are laid out in this many columns, top to bottom
+const SHEET_COLUMNS: usize = 2;
+
+/// Space between columns and between cards
+const GUTTER: f32 = 10.0;
+
+/// Glyph width wrapped cards allow for: more than the average, as their
+/// lines must not run out of the box
+const CARD_CHAR_WIDTH: f32 = 0.56;
+
+/// Text sizes of cards: the largest that fits one page is used, down to
+/// the smallest, below which the cards continue on more pages
+const MAX_CARD_TEXT: f32 = 11.0;
+const MIN_CARD_TEXT: f32 = 6.5;
+
+/// A boxed block of a sheet: a title, a line under it and labelled text
+#[derive(Debug, Clone, Default)]
+pub struct Card {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub fields: Vec<(String, String)>,
+}
+
+/// A card typeset at one text size
+struct CardLayout {
+    /// Title lines, then subtitle lines
+    header: Vec<(bool, String)>,
+    /// Wrapped fields: the label, on a field's first line only, and the text
+    body: Vec<(Option<String>, String)>,
+    size: f32,
+    header_height: f32,
+    height: f32,
+}
+
+impl CardLayout {
+    fn new(card: &Card, size: f32, width: f32) -> Self {
+        let padding = size * 0.7;
+        let chars = |size: f32| ((width - 2.0 * padding) / (size * CARD_CHAR_WIDTH)) as usize;
+
+        let mut header: Vec<(bool, String)> =
+            wrap(&card.title, chars(size * 1.25)).into_iter().map(|line| (true, line)).collect();
+        if let Some(subtitle) = &card.subtitle {
+            header.extend(wrap(subtitle, chars(size * 0.9)).into_iter().map(|line| (false, line)));
+        }
+
+        let mut body = Vec::new();
+        for (label, text) in &card.fields {
+            let prefix = format!("{}:", label);
+            for (i, line) in wrap(&format!("{} {}", prefix, text), chars(size)).into_iter().enumerate() {
+                match line.strip_prefix(&prefix) {
+                    Some(rest) if i == 0 => body.push((Some(prefix.clone()), rest.to_string())),
+                    _ => body.push((None, line)),
+                }
+            }
+        }
+
+        let header_height = header
+            .iter()
+            .map(|(title, _)| if *title { size * 1.25 } else { size * 0.9 } * 1.3)
+            .sum::<f32>()
+            + 2.0 * padding * 0.75;
+        let height = header_height + body.len() as f32 * size * 1.3 + 2.0 * padding * 0.75;
+
+        Self {
+            header,
+            body,
+            size,
+            header_height,
+            height,
+        }
+    }
+
+    /// Draw the card with its top left corner at (x, top)
+    fn draw(&self, content: &mut String, x: f32, top: f32, width: f32) {
+        let size = self.size;
+        let padding = size * 0.7;
+        content.push_str(&format!(
+            "0.90 0.95 0.90 rg {:.1} {:.1} {:.1} {:.1} re f 0 g
+",
+            x,
+            top - self.header_height,
+            width,
+            self.header_height
+        ));
+        content.push_str(&format!(
+            "0.55 G 0.8 w {:.1} {:.1} {:.1} {:.1} re S 0 G
+",
+            x,
+            top - self.height,
+            width,
+            self.height
+        ));
+
+        let mut y = top - padding * 0.75;
+        for (title, line) in &self.header {
+            let (font, line_size) = if *title { ("F2", size * 1.25) } else { ("F1", size * 0.9) };
+            y -= line_size * 1.3;
+            content.push_str(&format!(
+                "BT /{} {:.1} Tf {:.1} {:.1} Td ({}) Tj ET
+",
+                font,
+                line_size,
+                x + padding,
+                y + line_size * 0.3,
+                escape(line)
+            ));
+        }
+
+        y = top - self.header_height - padding * 0.75;
+        for (label, text) in &self.body {
+            y -= size * 1.3;
+            let label = label
+                .as_ref()
+                .map(|label| format!("/F2 {:.1} Tf ({}) Tj ", size, escape(label)))
+                .unwrap_or_default();
+            content.push_str(&format!(
+                "BT {:.1} {:.1} Td {}/F1 {:.1} Tf ({}) Tj ET
+",
+                x + padding,
+                y + size * 0.3,
+                label,
+                size,
+                escape(text)
+            ));
+        }
+    }
+}
+
+/// Render a sheet of cards under a title, in columns read top to bottom,
+/// with the text as large as fits on one page
+pub fn from_cards(title: &str, subtitle: &str, cards: &[Card]) -> Vec<u8> {
+    let width = (PAGE_WIDTH - 2.0 * SHEET_MARGIN - GUTTER * (SHEET_COLUMNS - 1) as f32) / SHEET_COLUMNS as f32;
+    let first_top = PAGE_HEIGHT - SHEET_MARGIN - TITLE.size * 1.35 - BODY.size * 1.35 - GUTTER;
+
+    let mut size = MAX_CARD_TEXT;
+    let mut placed = place_cards(cards, size, width, first_top);
+    while size > MIN_CARD_TEXT && placed.last().is_some_and(|(page, ..)| *page > 0) {
+        size -= 0.5;
+        placed = place_cards(cards, size, width, first_top);
+    }
+
+    let pages = placed.last().map_or(1, |(page, ..)| page + 1);
+    let mut contents = vec![String::new(); pages];
+    contents[0].push_str(&format!(
+        "BT /F2 {} Tf {:.1} {:.1} Td ({}) Tj ET
+",
+        TITLE.size,
+        SHEET_MARGIN,
+        PAGE_HEIGHT - SHEET_MARGIN - TITLE.size,
+        escape(title)
+    ));
+    contents[0].push_str(&format!(
+        "0.35 g BT /F1 {} Tf {:.1} {:.1} Td ({}) Tj ET 0 g
+",
+        BODY.size - 1.0,
+        SHEET_MARGIN,
+        PAGE_HEIGHT - SHEET_MARGIN - TITLE.size * 1.35 - BODY.size,
+        escape(subtitle)
+    ));
+    for (page, column, top, layout) in &placed {
+        let x = SHEET_MARGIN + *column as f32 * (width + GUTTER);
+        layout.draw(&mut contents[*page], x, *top, width);
+    }
+
+    write_document(&contents)
+}
+
+/// Flow cards down the columns of the sheet's pages at a text size, as
+/// (page, column, top, layout)
+fn place_cards(cards: &[Card], size: f32, width: f32, first_top: f32) -> Vec<(usize, usize, f32, CardLayout)> {
+    let (mut page, mut column, mut top) = (0, 0, first_top);
+    let mut placed = Vec::new();
+
+    for card in cards {
+        let layout = CardLayout::new(card, size, width);
+        let page_top = if page == 0 { first_top } else { PAGE_HEIGHT - SHEET_MARGIN };
+        // A card taller than a whole column is placed anyway, and cut off
+        if top - layout.height < SHEET_MARGIN && top < page_top {
+            column += 1;
+            if column == SHEET_COLUMNS {
+                column = 0;
+                page += 1;
+            }
+            top = if page == 0 { first_top } else { PAGE_HEIGHT - SHEET_MARGIN };
+        }
+        let height = layout.height;
+        placed.push((page, column, top, layout));
+        top -= height + GUTTER;
+    }
+
+    placed
+}
+
+/// Render a markdown document as a PDF
+pub fn from_markdown(markdown: &str) -> Vec<u8> {
+    let mut pages: Vec<String> = Vec::new();
+    let mut content = String::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+
+    for line in markdown.lines() {
+        let (style, text) = typeset(line);
+        if text.is_empty() {
+            y -= BODY.size * 0.6;
+            continue;
+        }
+
+        let leading = style.size * 1.35;
+        let width = PAGE_WIDTH - 2.0 * MARGIN - style.indent;
+        let max_chars = (width / (style.size * AVERAGE_CHAR_WIDTH)) as usize;
+
+        y -= style.space_before;
+        for (i, wrapped) in wrap(&text, max_chars).iter().enumerate() {
+            if y - leading < MARGIN {
+                pages.push(std::mem::take(&mut content));
+                y = PAGE_HEIGHT - MARGIN;
+            }
+            y -= leading;
+
+            // Continuation lines of a list item line up with its text
+            let x = MARGIN + style.indent + if i > 0 && style.indent > 0.0 { style.size } else { 0.0 };
+            content.push_str(&format!(
+                "BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n",
+                if style.bold { "F2" } else { "F1" },
+                style.size,
+                x,
+                y,
+                escape(wrapped)
+            ));
+        }
+    }
+    pages.push(content);
+
+    write_document(&pages)
+}
+
+/// Style and text of a markdown line
+fn typeset(line: &str) -> (&'static Style, String) {
+    let line = line.trim_end();
+    if let Some(text) = line.strip_prefix("# ") {
+        (&TITLE, text.to_string())
+    } else if let Some(text) = line.strip_prefix("## ") {
+        (&HEADING, text.to_string())
+    } else if let Some(text) = line.strip_prefix("### ") {
+        (&SUBHEADING, text.to_string())
+    } else if let Some(text) = line.strip_prefix("- [ ] ") {
+        (&LIST_ITEM, format!("[  ] {}", text))
+    } else if let Some(text) = line.strip_prefix("- [x] ") {
+        (&LIST_ITEM, format!("[x] {}", text))
+    } else if let Some(text) = line.strip_prefix("- ") {
+        (&LIST_ITEM, format!("\u{2022} {}", text))
+    } else {
+        (&BODY, line.replace("**", ""))
+    }
+}
+
+/// Break text into lines of at most `width` characters, at spaces when
+/// possible
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split(' ') {
+        let used = current.chars().count();
+        if used > 0 && used + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+
+        while current.chars().count() > width {
+            let rest: String = current.chars().skip(width).collect();
+            current = current.chars().take(width).collect();
+            lines.push(std::mem::replace(&mut current, rest));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Encode text as a PDF string literal body, in WinAnsi encoding
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match (c, win_ansi(c)) {
+            ('(' | ')' | '\\', _) => format!("\\{}", c),
+            (c, Some(_)) if c.is_ascii() => c.to_string(),
+            (_, Some(code)) => format!("\\{:03o}", code),
+            (_, None) => "?".to_string(),
+        })
+        .collect()
+}
+
+/// Characters of `text` the standard fonts have no glyph for, which the
+/// PDF shows as "?", each once in order of appearance
+pub fn unsupported_chars(text: &str) -> Vec<char> {
+    let mut unsupported = Vec::new();
+    for c in text.chars() {
+        if !c.is_control() && win_ansi(c).is_none() && !unsupported.contains(&c) {
+            unsupported.push(c);
+        }
+    }
+    unsupported
+}
+
+/// Code of a character in WinAnsiEncoding, the encoding of the fonts
+fn win_ansi(c: char) -> Option<u8> {
+    const PUNCTUATION: [(char, u8); 27] = [
+        ('\u{20ac}', 0x80),
+        ('\u{201a}', 0x82),
+        ('\u{0192}', 0x83),
+        ('\u{201e}', 0x84),
+        ('\u{2026}', 0x85),
+        ('\u{2020}', 0x86),
+        ('\u{2021}', 0x87),
+        ('\u{02c6}', 0x88),
+        ('\u{2030}', 0x89),
+        ('\u{0160}', 0x8a),
+        ('\u{2039}', 0x8b),
+        ('\u{0152}', 0x8c),
+        ('\u{017d}', 0x8e),
+        ('\u{2018}', 0x91),
+        ('\u{2019}', 0x92),
+        ('\u{201c}', 0x93),
+        ('\u{201d}', 0x94),
+        ('\u{2022}', 0x95),
+        ('\u{2013}', 0x96),
+        ('\u{2014}', 0x97),
+        ('\u{02dc}', 0x98),
+        ('\u{2122}', 0x99),
+        ('\u{0161}', 0x9a),
+        ('\u{203a}', 0x9b),
+        ('\u{0153}', 0x9c),
+        ('\u{017e}', 0x9e),
+        ('\u{0178}', 0x9f),
+    ];
+    match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => Some(c as u8),
+        c => PUNCTUATION.iter().find(|(p, _)| *p == c).map(|(_, code)| *code),
+    }
+}
+
+/// Assemble page content streams into a PDF file
+fn write_document(pages: &[String]) -> Vec<u8> {
+    // Objects 1-4 are the catalog, page tree and fonts; each page then
+    // takes two objects, the page and its content stream
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 5 + 2 * i).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (content, id) in pages.iter().zip(&page_ids) {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .as_bytes(),
+    );
+
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_markdown_is_well_formed() {
+        let long_list: String = (0..120).map(|i| format!("- Item {}\n", i)).collect();
+        let pdf = from_markdown(&format!("# Title (draft)\n\n## Section\n{}", long_list));
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Count 3"));
+        assert!(text.contains("(Title \\(draft\\)) Tj"));
+
+        // startxref points at the cross-reference table
+        let xref: usize = text.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+        assert!(text[xref..].starts_with("xref"));
+    }
+
+    #[test]
+    fn test_wrap_and_escape() {
+        assert_eq!(wrap("water the fern weekly", 10), vec!["water the", "fern", "weekly"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(escape("18°C \u{2022} ☀"), "18\\260C \\225 ?");
+        assert_eq!(escape("Don\u{2019}t \u{2013} 5\u{20ac}"), "Don\\222t \\226 5\\200");
+        assert_eq!(unsupported_chars("☀ Sunny 🌿, ☀ and 18°C \u{2014} fine\n"), vec!['☀', '🌿']);
+    }
+
+    #[test]
+    fn test_cards_shrink_to_fit_one_page() {
+        let card = |i: usize| Card {
+            title: format!("Plant {}", i),
+            subtitle: Some("Kitchen".to_string()),
+            fields: vec![
+                ("Light".to_string(), "Bright, indirect sunlight".to_string()),
+                ("Water".to_string(), "Water when the top inch of soil is dry".to_string()),
+            ],
+        };
+
+        let few: Vec<Card> = (0..4).map(card).collect();
+        let text = String::from_utf8_lossy(&from_cards("Plant Care", "4 plants", &few)).to_string();
+        assert!(text.contains("/Count 1 >>"));
+        assert!(text.contains(&format!("/F2 {:.1} Tf (Light:) Tj", MAX_CARD_TEXT)));
+
+        let many: Vec<Card> = (0..24).map(card).collect();
+        let placed = place_cards(&many, MAX_CARD_TEXT, 200.0, 700.0);
+        assert!(placed.last().unwrap().0 > 0);
+        let text = String::from_utf8_lossy(&from_cards("Plant Care", "24 plants", &many)).to_string();
+        assert!(text.contains("/Count 1 >>"));
+
+        let too_many: Vec<Card> = (0..200).map(card).collect();
+        let text = String::from_utf8_lossy(&from_cards("Plant Care", "200 plants", &too_many)).to_string();
+        assert!(!text.contains("/Count 1 >>"));
+    }
+}
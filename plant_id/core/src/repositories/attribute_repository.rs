@@ -0,0 +1,162 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::plant_attribute::attribute_id;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, PlantAttribute};
+use crate::repositories::event_repository;
+
+const ATTRIBUTE_COLUMNS: &str = "a.id, a.plant_id, a.key, a.value, a.updated_at";
+
+#[derive(Clone)]
+pub struct AttributeRepository {
+    db: Database,
+}
+
+impl AttributeRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Attributes of a plant, by key
+    pub async fn get_all_by_plant_id(&self, plant_id: &str) -> Result<Vec<PlantAttribute>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM plant_attributes a
+            WHERE a.plant_id = ?
+            ORDER BY a.key
+            "#,
+            ATTRIBUTE_COLUMNS
+        ))
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(attribute_from_row).collect()
+    }
+
+    /// Attributes of all of a user's plants
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<PlantAttribute>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM plant_attributes a
+            JOIN plants p ON p.id = a.plant_id
+            WHERE p.user_id = ?
+            ORDER BY a.plant_id, a.key
+            "#,
+            ATTRIBUTE_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(attribute_from_row).collect()
+    }
+
+    /// Set an attribute, replacing the plant's previous value for its key
+    pub async fn save(&self, attribute: &PlantAttribute) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+        save(&mut tx, attribute).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Remove a plant's attribute. Returns false if it had none by that key.
+    pub async fn delete(&self, plant_id: &str, key: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+        let deleted = delete(&mut tx, plant_id, key).await?;
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
+}
+
+/// Set an attribute on `conn`, with its audit event
+pub(crate) async fn save(conn: &mut SqliteConnection, attribute: &PlantAttribute) -> Result<()> {
+    let existed = sqlx::query("SELECT 1 FROM plant_attributes WHERE id = ?")
+        .bind(&attribute.id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .is_some();
+
+    sqlx::query(
+        r#"
+        INSERT INTO plant_attributes (id, plant_id, key, value, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&attribute.id)
+    .bind(&attribute.plant_id)
+    .bind(&attribute.key)
+    .bind(&attribute.value)
+    .bind(attribute.updated_at.to_rfc3339())
+    .execute(&mut *conn)
+    .await?;
+
+    let action = if existed {
+        AuditAction::Updated
+    } else {
+        AuditAction::Created
+    };
+    let event = AuditEvent::new(
+        AuditEntity::PlantAttribute,
+        &attribute.id,
+        Some(&attribute.plant_id),
+        action,
+        attribute,
+    )?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(())
+}
+
+/// Remove an attribute on `conn`, with its audit event. Returns false if
+/// the plant had none by that key.
+pub(crate) async fn delete(conn: &mut SqliteConnection, plant_id: &str, key: &str) -> Result<bool> {
+    // Snapshot the attribute for the audit log before it is gone
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM plant_attributes a WHERE a.id = ?",
+        ATTRIBUTE_COLUMNS
+    ))
+    .bind(attribute_id(plant_id, key))
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some(row) = row else {
+        return Ok(false);
+    };
+    let attribute = attribute_from_row(&row)?;
+
+    sqlx::query("DELETE FROM plant_attributes WHERE id = ?")
+        .bind(&attribute.id)
+        .execute(&mut *conn)
+        .await?;
+
+    let event = AuditEvent::new(
+        AuditEntity::PlantAttribute,
+        &attribute.id,
+        Some(&attribute.plant_id),
+        AuditAction::Deleted,
+        &attribute,
+    )?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(true)
+}
+
+fn attribute_from_row(row: &SqliteRow) -> Result<PlantAttribute> {
+    let updated_at: String = row.get("updated_at");
+
+    Ok(PlantAttribute {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        key: row.get("key"),
+        value: row.get("value"),
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+    })
+}
@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, CareOverrides, CareSchedule, Plant, ScheduleSource};
+use crate::repositories::event_repository;
+
+/// Reads the owner's overrides of plants' care schedules. They are written
+/// by `PlantRepository` whenever it saves a changed schedule, and restored
+/// from archives. Every change is an audit event under the plant's ID, so
+/// sync carries the overrides along with the plant.
+#[derive(Clone)]
+pub struct CareOverrideRepository {
+    db: Database,
+}
+
+impl CareOverrideRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// A plant's baseline schedule and overrides. A plant without overrides
+    /// has its schedule as the baseline.
+    pub async fn get(&self, plant: &Plant) -> Result<CareOverrides> {
+        let mut conn = self.db.pool().acquire().await?;
+        Ok(load(&mut conn, &plant.id)
+            .await?
+            .unwrap_or_else(|| CareOverrides::new(&plant.id, plant.care_schedule.clone())))
+    }
+
+    /// A plant's overrides, if it has any
+    pub async fn get_by_plant_id(&self, plant_id: &str) -> Result<Option<CareOverrides>> {
+        let mut conn = self.db.pool().acquire().await?;
+        load(&mut conn, plant_id).await
+    }
+
+    /// Put back a plant's overrides, e.g. from an archive
+    pub async fn save(&self, care: &CareOverrides) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+        save(&mut tx, care).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Lay a plant's overrides over the schedule it is being saved with from
+/// `source`, keeping track of any new ones, and return the schedule to
+/// store. `stored` is the schedule the plant had, the baseline of a plant
+/// without overrides.
+pub(crate) async fn merge(
+    conn: &mut SqliteConnection,
+    plant_id: &str,
+    stored: Option<&CareSchedule>,
+    schedule: &CareSchedule,
+    source: &ScheduleSource,
+) -> Result<CareSchedule> {
+    let mut care = match (load(&mut *conn, plant_id).await?, stored) {
+        (Some(care), _) => care,
+        (None, Some(stored)) => CareOverrides::new(plant_id, stored.clone()),
+        (None, None) => return Ok(schedule.clone()),
+    };
+    let merged = care.apply(schedule, source);
+    save(conn, &care).await?;
+
+    Ok(merged)
+}
+
+/// Save a plant's overrides on `conn`, or delete them when there are none,
+/// with the audit event of the change
+pub(crate) async fn save(conn: &mut SqliteConnection, care: &CareOverrides) -> Result<()> {
+    let stored = load(&mut *conn, &care.plant_id).await?;
+    let action = match (&stored, care.is_empty()) {
+        (None, true) => return Ok(()),
+        (Some(_), true) => AuditAction::Deleted,
+        (None, false) => AuditAction::Created,
+        (Some(_), false) => AuditAction::Updated,
+    };
+
+    if action == AuditAction::Deleted {
+        delete(&mut *conn, &care.plant_id).await?;
+    } else {
+        upsert(&mut *conn, care).await?;
+    }
+
+    // A deletion records the overrides as they were
+    let snapshot = match &stored {
+        Some(stored) if action == AuditAction::Deleted => stored,
+        _ => care,
+    };
+    let event = AuditEvent::new(
+        AuditEntity::CareOverrides,
+        &care.plant_id,
+        Some(&care.plant_id),
+        action,
+        snapshot,
+    )?;
+    event_repository::record(&mut *conn, &event).await
+}
+
+/// Insert or replace a plant's overrides
+pub(crate) async fn upsert(conn: &mut SqliteConnection, care: &CareOverrides) -> Result<()> {
+    sqlx::query("INSERT OR REPLACE INTO care_overrides (plant_id, baseline, overrides, updated_at) VALUES (?, ?, ?, ?)")
+        .bind(&care.plant_id)
+        .bind(serde_json::to_string(&care.baseline)?)
+        .bind(serde_json::to_string(&care.overrides)?)
+        .bind(care.updated_at.to_rfc3339())
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+pub(crate) async fn delete(conn: &mut SqliteConnection, plant_id: &str) -> Result<()> {
+    sqlx::query("DELETE FROM care_overrides WHERE plant_id = ?")
+        .bind(plant_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+async fn load(conn: &mut SqliteConnection, plant_id: &str) -> Result<Option<CareOverrides>> {
+    let row = sqlx::query("SELECT plant_id, baseline, overrides, updated_at FROM care_overrides WHERE plant_id = ?")
+        .bind(plant_id)
+        .fetch_optional(conn)
+        .await?;
+
+    row.as_ref().map(overrides_from_row).transpose()
+}
+
+fn overrides_from_row(row: &SqliteRow) -> Result<CareOverrides> {
+    let baseline: String = row.get("baseline");
+    let overrides: String = row.get("overrides");
+    let updated_at: String = row.get("updated_at");
+
+    Ok(CareOverrides {
+        plant_id: row.get("plant_id"),
+        baseline: serde_json::from_str(&baseline).context("baseline is not a care schedule")?,
+        overrides: serde_json::from_str(&overrides).context("overrides is not a care schedule update")?,
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::{PlantRepository, SyncRepository};
+
+    #[tokio::test]
+    async fn test_regenerating_keeps_manual_changes() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let overrides = CareOverrideRepository::new(db);
+
+        let mut plant = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&plant).await.unwrap();
+        assert!(overrides.get(&plant).await.unwrap().is_empty());
+
+        plant.care_schedule.water = "Every 4 days".to_string();
+        plants.update(&plant).await.unwrap();
+
+        plant.care_schedule = CareSchedule {
+            water: "Every 7 days".to_string(),
+            light: "Low light".to_string(),
+            ..CareSchedule::default()
+        };
+        let source = ScheduleSource::Ai {
+            model: "model-a".to_string(),
+        };
+        let version = plants.update_schedule(&plant, source).await.unwrap();
+        assert_eq!(version.schedule.water, "Every 4 days");
+        assert_eq!(version.schedule.light, "Low light");
+
+        let stored = plants.get_by_id(&plant.id, "u").await.unwrap().unwrap();
+        assert_eq!(stored.care_schedule.water, "Every 4 days");
+        let care = overrides.get(&stored).await.unwrap();
+        assert_eq!(care.baseline.water, "Every 7 days");
+        assert_eq!(care.overrides.water.as_deref(), Some("Every 4 days"));
+    }
+
+    #[tokio::test]
+    async fn test_overrides_sync_to_other_devices() {
+        let here = Database::in_memory().await.unwrap();
+        here.migrate().await.unwrap();
+        let there = Database::in_memory().await.unwrap();
+        there.migrate().await.unwrap();
+        let plants = PlantRepository::new(here.clone());
+
+        let mut plant = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&plant).await.unwrap();
+        plant.care_schedule.water = "Every 4 days".to_string();
+        plants.update(&plant).await.unwrap();
+
+        let events = SyncRepository::new(here).unsynced_events().await.unwrap();
+        assert!(events.iter().any(|e| e.entity == AuditEntity::CareOverrides));
+        SyncRepository::new(there.clone()).apply(&[], &events).await.unwrap();
+
+        // A regenerate on the other device keeps the tweak
+        let plants = PlantRepository::new(there.clone());
+        plant.care_schedule.water = "Every 7 days".to_string();
+        let source = ScheduleSource::Ai {
+            model: "model-a".to_string(),
+        };
+        let version = plants.update_schedule(&plant, source).await.unwrap();
+        assert_eq!(version.schedule.water, "Every 4 days");
+        let care = CareOverrideRepository::new(there).get_by_plant_id(&plant.id).await.unwrap().unwrap();
+        assert_eq!(care.overrides.water.as_deref(), Some("Every 4 days"));
+    }
+}
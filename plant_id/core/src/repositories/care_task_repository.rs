@@ -0,0 +1,93 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteConnection;
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, CareTask, CareTaskKind};
+use crate::repositories::event_repository;
+
+#[derive(Clone)]
+pub struct CareTaskRepository {
+    db: Database,
+}
+
+impl CareTaskRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Tasks of a plant that have been done at least once
+    pub async fn get_all_by_plant_id(&self, plant_id: &str) -> Result<Vec<CareTask>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, plant_id, kind, last_done_at
+            FROM care_tasks
+            WHERE plant_id = ?
+            "#,
+        )
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut tasks = Vec::new();
+        for row in rows {
+            let kind: String = row.get("kind");
+            let last_done_at: Option<String> = row.get("last_done_at");
+
+            tasks.push(CareTask {
+                id: row.get("id"),
+                plant_id: row.get("plant_id"),
+                kind: CareTaskKind::from_str(&kind)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown care task kind: {}", kind))?,
+                last_done_at: last_done_at
+                    .map(|t| DateTime::parse_from_rfc3339(&t).map(|t| t.with_timezone(&Utc)))
+                    .transpose()?,
+            });
+        }
+
+        Ok(tasks)
+    }
+
+    /// Insert or update a task
+    pub async fn save(&self, task: &CareTask) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+        save(&mut tx, task).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Insert or update a task on `conn`, with its audit event
+pub(crate) async fn save(conn: &mut SqliteConnection, task: &CareTask) -> Result<()> {
+    let existed = sqlx::query("SELECT 1 FROM care_tasks WHERE id = ?")
+        .bind(&task.id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .is_some();
+
+    sqlx::query(
+        r#"
+        INSERT INTO care_tasks (id, plant_id, kind, last_done_at)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET last_done_at = excluded.last_done_at
+        "#,
+    )
+    .bind(&task.id)
+    .bind(&task.plant_id)
+    .bind(task.kind.as_str())
+    .bind(task.last_done_at.map(|t| t.to_rfc3339()))
+    .execute(&mut *conn)
+    .await?;
+
+    let action = if existed {
+        AuditAction::Updated
+    } else {
+        AuditAction::Created
+    };
+    let event = AuditEvent::new(AuditEntity::CareTask, &task.id, Some(&task.plant_id), action, task)?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(())
+}
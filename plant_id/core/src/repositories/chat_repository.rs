@@ -0,0 +1,115 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteConnection;
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, ChatSession};
+use crate::repositories::event_repository;
+
+#[derive(Clone)]
+pub struct ChatRepository {
+    db: Database,
+}
+
+impl ChatRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, session: &ChatSession) -> Result<ChatSession> {
+        let mut tx = self.db.pool().begin().await?;
+        insert(&mut tx, session).await?;
+        tx.commit().await?;
+
+        Ok(session.clone())
+    }
+
+    pub async fn get_all_by_plant_id(&self, plant_id: &str) -> Result<Vec<ChatSession>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, plant_id, messages, created_at, updated_at
+            FROM chat_sessions
+            WHERE plant_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            let messages = serde_json::from_str(row.get("messages"))?;
+            let created_at: String = row.get("created_at");
+            let updated_at: String = row.get("updated_at");
+
+            sessions.push(ChatSession {
+                id: row.get("id"),
+                plant_id: row.get("plant_id"),
+                messages,
+                created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+                updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+            });
+        }
+
+        Ok(sessions)
+    }
+
+    pub async fn update(&self, session: &ChatSession) -> Result<()> {
+        let messages_json = serde_json::to_string(&session.messages)?;
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE chat_sessions
+            SET messages = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&messages_json)
+        .bind(session.updated_at.to_rfc3339())
+        .bind(&session.id)
+        .execute(&mut *tx)
+        .await?;
+
+        record(&mut tx, session, AuditAction::Updated).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Insert a chat session on `conn`, with its audit event
+pub(crate) async fn insert(conn: &mut SqliteConnection, session: &ChatSession) -> Result<()> {
+    let messages_json = serde_json::to_string(&session.messages)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO chat_sessions (id, plant_id, messages, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&session.id)
+    .bind(&session.plant_id)
+    .bind(&messages_json)
+    .bind(session.created_at.to_rfc3339())
+    .bind(session.updated_at.to_rfc3339())
+    .execute(&mut *conn)
+    .await?;
+
+    record(&mut *conn, session, AuditAction::Created).await?;
+
+    Ok(())
+}
+
+async fn record(conn: &mut SqliteConnection, session: &ChatSession, action: AuditAction) -> Result<()> {
+    let event = AuditEvent::new(
+        AuditEntity::Chat,
+        &session.id,
+        Some(&session.plant_id),
+        action,
+        session,
+    )?;
+    event_repository::record(conn, &event).await
+}
@@ -0,0 +1,832 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::sqlite::SqliteConnection;
+use std::collections::HashSet;
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+use crate::adapters::metrics_adapter;
+use crate::config::Database;
+use crate::domain::{
+    AuditAction, AuditEntity, AuditEvent, DiagnosisFilter, DiagnosisMessage, DiagnosisSession,
+    DiagnosisState, DiagnosisStatus, DiagnosisSummary, TokenUsage,
+};
+use crate::repositories::event_repository;
+use crate::repositories::repair_repository::{self, QuarantinedRow, Repair};
+
+/// Sessions this process created or advanced since `track_sessions`, the
+/// ones an interrupted or failed command may have left waiting for input
+static TOUCHED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Remember the IDs of the sessions created or updated from now on, for
+/// `cancel_pending_touched`. Servers leave this off: their sessions belong
+/// to their users, not to the run.
+pub fn track_sessions() {
+    TOUCHED.lock().unwrap_or_else(PoisonError::into_inner).get_or_insert_with(HashSet::new);
+}
+
+fn touch(id: &str) {
+    if let Some(ids) = TOUCHED.lock().unwrap_or_else(PoisonError::into_inner).as_mut() {
+        ids.insert(id.to_string());
+    }
+}
+
+/// A row of the diagnosis_sessions table
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+struct SessionRecord {
+    id: String,
+    plant_id: String,
+    status: String,
+    state: Option<String>,
+    diagnosis_context: String,
+    created_at: String,
+    updated_at: String,
+}
+
+/// A session as listed, leaving out the rest of the context
+#[derive(Debug, sqlx::FromRow)]
+struct SummaryRecord {
+    id: String,
+    plant_id: String,
+    status: String,
+    /// Whether diagnosis_context is valid JSON, which the other fields are
+    /// extracted from
+    context_valid: bool,
+    problem: Option<String>,
+    result: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+/// A row of the diagnosis_messages table
+#[derive(Debug, Serialize, sqlx::FromRow)]
+struct MessageRecord {
+    id: String,
+    session_id: String,
+    role: String,
+    content: String,
+    created_at: String,
+}
+
+#[derive(Clone)]
+pub struct DiagnosisRepository {
+    db: Database,
+}
+
+impl DiagnosisRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, session: &DiagnosisSession) -> Result<DiagnosisSession> {
+        let started = Instant::now();
+        let mut tx = self.db.pool().begin().await?;
+        insert(&mut tx, session).await?;
+        tx.commit().await?;
+        metrics_adapter::record_db_query("diagnoses.create", started);
+        touch(&session.id);
+
+        Ok(session.clone())
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<DiagnosisSession>> {
+        let started = Instant::now();
+        let row = sqlx::query_as!(
+            SessionRecord,
+            r#"
+            SELECT id AS "id!", plant_id, status, state, diagnosis_context, created_at, updated_at
+            FROM diagnosis_sessions
+            WHERE id = ?
+            "#,
+            id,
+        )
+        .fetch_optional(self.db.pool())
+        .await?;
+        metrics_adapter::record_db_query("diagnoses.get_by_id", started);
+
+        row.map(DiagnosisSession::try_from)
+            .transpose()
+            .with_context(|| format!("Diagnosis session {} is corrupt; run `plant-care db repair`", id))
+    }
+
+    pub async fn get_all_by_plant_id(
+        &self,
+        plant_id: &str,
+        _user_id: &str,
+    ) -> Result<Vec<DiagnosisSession>> {
+        let started = Instant::now();
+        let rows = sqlx::query_as!(
+            SessionRecord,
+            r#"
+            SELECT id AS "id!", plant_id, status, state, diagnosis_context, created_at, updated_at
+            FROM diagnosis_sessions
+            WHERE plant_id = ?
+            ORDER BY created_at DESC
+            "#,
+            plant_id,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        metrics_adapter::record_db_query("diagnoses.get_all_by_plant_id", started);
+
+        Ok(repair_repository::skip_corrupt("diagnosis_sessions", rows, |row| row.id.clone()))
+    }
+
+    /// A page of a plant's sessions, newest first, without their contexts
+    pub async fn get_summaries_by_plant_id(
+        &self,
+        plant_id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<DiagnosisSummary>> {
+        let started = Instant::now();
+        let (limit, offset) = (limit as i64, offset as i64);
+        let rows = sqlx::query_as!(
+            SummaryRecord,
+            r#"
+            SELECT id AS "id!", plant_id, status,
+                json_valid(diagnosis_context) AS "context_valid!: bool",
+                CASE WHEN json_valid(diagnosis_context)
+                    THEN json_extract(diagnosis_context, '$.initial_prompt') END AS "problem?: String",
+                CASE WHEN json_valid(diagnosis_context)
+                    THEN json_extract(diagnosis_context, '$.result') END AS "result?: String",
+                created_at, updated_at
+            FROM diagnosis_sessions
+            WHERE plant_id = ?
+            ORDER BY created_at DESC
+            LIMIT ? OFFSET ?
+            "#,
+            plant_id,
+            limit,
+            offset,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        metrics_adapter::record_db_query("diagnoses.get_summaries_by_plant_id", started);
+
+        Ok(repair_repository::skip_corrupt("diagnosis_sessions", rows, |row| row.id.clone()))
+    }
+
+    /// Sessions of a plant whose ID starts with `prefix`, without their contexts
+    pub async fn find_summaries_by_prefix(
+        &self,
+        plant_id: &str,
+        prefix: &str,
+    ) -> Result<Vec<DiagnosisSummary>> {
+        let rows = sqlx::query_as!(
+            SummaryRecord,
+            r#"
+            SELECT id AS "id!", plant_id, status,
+                json_valid(diagnosis_context) AS "context_valid!: bool",
+                CASE WHEN json_valid(diagnosis_context)
+                    THEN json_extract(diagnosis_context, '$.initial_prompt') END AS "problem?: String",
+                CASE WHEN json_valid(diagnosis_context)
+                    THEN json_extract(diagnosis_context, '$.result') END AS "result?: String",
+                created_at, updated_at
+            FROM diagnosis_sessions
+            WHERE plant_id = ? AND substr(id, 1, length(?)) = ?
+            ORDER BY created_at DESC
+            "#,
+            plant_id,
+            prefix,
+            prefix,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(repair_repository::skip_corrupt("diagnosis_sessions", rows, |row| row.id.clone()))
+    }
+
+    /// A user's diagnoses concluded since `since` on plants still in the
+    /// collection, without their contexts
+    pub async fn get_concluded_summaries_since(
+        &self,
+        user_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<DiagnosisSummary>> {
+        let started = Instant::now();
+        let completed = DiagnosisStatus::Completed.as_str();
+        let since = since.to_rfc3339();
+        let rows = sqlx::query_as!(
+            SummaryRecord,
+            r#"
+            SELECT id AS "id!", plant_id, status,
+                json_valid(diagnosis_context) AS "context_valid!: bool",
+                CASE WHEN json_valid(diagnosis_context)
+                    THEN json_extract(diagnosis_context, '$.initial_prompt') END AS "problem?: String",
+                CASE WHEN json_valid(diagnosis_context)
+                    THEN json_extract(diagnosis_context, '$.result') END AS "result?: String",
+                created_at, updated_at
+            FROM diagnosis_sessions
+            WHERE plant_id IN (SELECT id FROM plants WHERE user_id = ? AND archived_at IS NULL)
+              AND status = ? AND updated_at >= ?
+            ORDER BY updated_at DESC
+            "#,
+            user_id,
+            completed,
+            since,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        metrics_adapter::record_db_query("diagnoses.get_concluded_summaries_since", started);
+
+        Ok(repair_repository::skip_corrupt("diagnosis_sessions", rows, |row| row.id.clone()))
+    }
+
+    /// A page of a user's sessions matching `filter`, newest first, without
+    /// their contexts. `plant_id` narrows them down to one plant.
+    pub async fn query_summaries(
+        &self,
+        user_id: &str,
+        plant_id: Option<&str>,
+        filter: &DiagnosisFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<DiagnosisSummary>> {
+        let started = Instant::now();
+        let (limit, offset) = (limit as i64, offset as i64);
+        let status = filter.status.map(|s| s.as_str());
+        let since = filter.since.map(|at| at.to_rfc3339());
+        let finding = filter.finding.as_ref().map(|f| f.to_lowercase());
+        let rows = sqlx::query_as!(
+            SummaryRecord,
+            r#"
+            SELECT id AS "id!", plant_id, status,
+                json_valid(diagnosis_context) AS "context_valid!: bool",
+                CASE WHEN json_valid(diagnosis_context)
+                    THEN json_extract(diagnosis_context, '$.initial_prompt') END AS "problem?: String",
+                CASE WHEN json_valid(diagnosis_context)
+                    THEN json_extract(diagnosis_context, '$.result') END AS "result?: String",
+                created_at, updated_at
+            FROM diagnosis_sessions
+            WHERE plant_id IN (SELECT id FROM plants WHERE user_id = ?1)
+              AND (?2 IS NULL OR plant_id = ?2)
+              AND (?3 IS NULL OR status = ?3)
+              AND (?4 IS NULL OR created_at >= ?4)
+              AND (?5 IS NULL OR (json_valid(diagnosis_context)
+                  AND instr(lower(json_extract(diagnosis_context, '$.result.finding')), ?5) > 0))
+            ORDER BY created_at DESC
+            LIMIT ?6 OFFSET ?7
+            "#,
+            user_id,
+            plant_id,
+            status,
+            since,
+            finding,
+            limit,
+            offset,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        metrics_adapter::record_db_query("diagnoses.query_summaries", started);
+
+        Ok(repair_repository::skip_corrupt("diagnosis_sessions", rows, |row| row.id.clone()))
+    }
+
+    /// Number of a user's sessions matching `filter`, of one plant when
+    /// `plant_id` is given
+    pub async fn count_matching(
+        &self,
+        user_id: &str,
+        plant_id: Option<&str>,
+        filter: &DiagnosisFilter,
+    ) -> Result<usize> {
+        let status = filter.status.map(|s| s.as_str());
+        let since = filter.since.map(|at| at.to_rfc3339());
+        let finding = filter.finding.as_ref().map(|f| f.to_lowercase());
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM diagnosis_sessions
+            WHERE plant_id IN (SELECT id FROM plants WHERE user_id = ?1)
+              AND (?2 IS NULL OR plant_id = ?2)
+              AND (?3 IS NULL OR status = ?3)
+              AND (?4 IS NULL OR created_at >= ?4)
+              AND (?5 IS NULL OR (json_valid(diagnosis_context)
+                  AND instr(lower(json_extract(diagnosis_context, '$.result.finding')), ?5) > 0))
+            "#,
+            user_id,
+            plant_id,
+            status,
+            since,
+            finding,
+        )
+        .fetch_one(self.db.pool())
+        .await?;
+
+        Ok(count as usize)
+    }
+
+    /// Number of diagnosis sessions of a plant
+    pub async fn count_by_plant_id(&self, plant_id: &str) -> Result<usize> {
+        let count =
+            sqlx::query_scalar!("SELECT COUNT(*) FROM diagnosis_sessions WHERE plant_id = ?", plant_id)
+                .fetch_one(self.db.pool())
+                .await?;
+
+        Ok(count as usize)
+    }
+
+    /// Token usage of a user's latest concluded sessions that recorded it,
+    /// newest first
+    pub async fn get_recent_usage(&self, user_id: &str, limit: usize) -> Result<Vec<TokenUsage>> {
+        let completed = DiagnosisStatus::Completed.as_str();
+        let limit = limit as i64;
+        let rows = sqlx::query_scalar!(
+            r#"
+            SELECT json_extract(diagnosis_context, '$.usage') AS "usage!: String"
+            FROM diagnosis_sessions
+            WHERE plant_id IN (SELECT id FROM plants WHERE user_id = ?)
+              AND status = ? AND json_valid(diagnosis_context)
+              AND json_type(diagnosis_context, '$.usage') = 'object'
+            ORDER BY updated_at DESC
+            LIMIT ?
+            "#,
+            user_id,
+            completed,
+            limit,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows.iter().filter_map(|usage| serde_json::from_str(usage).ok()).collect())
+    }
+
+    pub async fn update(&self, session: &DiagnosisSession) -> Result<()> {
+        let started = Instant::now();
+        let context_json = serde_json::to_string(&session.diagnosis_context)?;
+        let status = session.status().as_str();
+        let state = session.state.to_string();
+        let updated_at = session.updated_at.to_rfc3339();
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE diagnosis_sessions
+            SET status = ?, state = ?, diagnosis_context = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+            status,
+            state,
+            context_json,
+            updated_at,
+            session.id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record(&mut tx, session, AuditAction::Updated).await?;
+        tx.commit().await?;
+        metrics_adapter::record_db_query("diagnoses.update", started);
+        touch(&session.id);
+
+        Ok(())
+    }
+
+    /// Mark the sessions this process touched (see `track_sessions`) that
+    /// are still waiting for input as cancelled, returning how many were
+    /// updated
+    pub async fn cancel_pending_touched(&self, reason: &str) -> Result<u64> {
+        let ids: Vec<String> = match TOUCHED.lock().unwrap_or_else(PoisonError::into_inner).as_ref() {
+            Some(ids) => ids.iter().cloned().collect(),
+            None => return Ok(0),
+        };
+        let cancelled = DiagnosisStatus::Cancelled.as_str();
+        let cancelled_state = DiagnosisState::Cancelled.to_string();
+        let now = Utc::now().to_rfc3339();
+        let pending = DiagnosisStatus::PendingUserInput.as_str();
+        let mut tx = self.db.pool().begin().await?;
+
+        let mut count = 0;
+        for id in &ids {
+            let row = sqlx::query_as!(
+                SessionRecord,
+                r#"
+                UPDATE diagnosis_sessions
+                SET status = ?, state = ?, diagnosis_context = json_set(diagnosis_context, '$.cancel_reason', ?), updated_at = ?
+                WHERE id = ? AND status = ? AND json_valid(diagnosis_context)
+                RETURNING id AS "id!", plant_id, status, state, diagnosis_context, created_at, updated_at
+                "#,
+                cancelled,
+                cancelled_state,
+                reason,
+                now,
+                id,
+                pending,
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if let Some(row) = row {
+                record(&mut tx, &DiagnosisSession::try_from(row)?, AuditAction::Updated).await?;
+                count += 1;
+            }
+        }
+        tx.commit().await?;
+
+        Ok(count)
+    }
+
+    /// Add a turn to the end of a session's conversation
+    pub async fn append_message(
+        &self,
+        session: &DiagnosisSession,
+        message: &DiagnosisMessage,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let mut tx = self.db.pool().begin().await?;
+        insert_message(&mut tx, session, message).await?;
+        tx.commit().await?;
+        metrics_adapter::record_db_query("diagnoses.append_message", started);
+
+        Ok(())
+    }
+
+    /// A page of a session's conversation, oldest turn first: up to `limit`
+    /// turns (all remaining when `None`) after skipping `offset`
+    pub async fn get_messages(
+        &self,
+        session_id: &str,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<Vec<DiagnosisMessage>> {
+        let started = Instant::now();
+        let (limit, offset) = (limit.map_or(-1, |l| l as i64), offset as i64);
+        let rows = sqlx::query_as!(
+            MessageRecord,
+            r#"
+            SELECT id AS "id!", session_id, role, content, created_at
+            FROM diagnosis_messages
+            WHERE session_id = ?
+            ORDER BY created_at, rowid
+            LIMIT ? OFFSET ?
+            "#,
+            session_id,
+            limit,
+            offset,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        metrics_adapter::record_db_query("diagnoses.get_messages", started);
+
+        rows.into_iter().map(DiagnosisMessage::try_from).collect()
+    }
+
+    /// Number of turns in a session's conversation
+    pub async fn count_messages(&self, session_id: &str) -> Result<usize> {
+        let count =
+            sqlx::query_scalar!("SELECT COUNT(*) FROM diagnosis_messages WHERE session_id = ?", session_id)
+                .fetch_one(self.db.pool())
+                .await?;
+
+        Ok(count as usize)
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        // Snapshot the session for the audit log before it is gone
+        let row = sqlx::query_as!(
+            SessionRecord,
+            r#"SELECT id AS "id!", plant_id, status, state, diagnosis_context, created_at, updated_at FROM diagnosis_sessions WHERE id = ?"#,
+            id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(row) = row else {
+            return Ok(());
+        };
+        let session = DiagnosisSession::try_from(row)?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM diagnosis_sessions
+            WHERE id = ?
+            "#,
+            id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record(&mut tx, &session, AuditAction::Deleted).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Insert a session on `conn`, with its audit event
+pub(crate) async fn insert(conn: &mut SqliteConnection, session: &DiagnosisSession) -> Result<()> {
+    let context_json = serde_json::to_string(&session.diagnosis_context)?;
+    let status = session.status().as_str();
+    let state = session.state.to_string();
+    let created_at = session.created_at.to_rfc3339();
+    let updated_at = session.updated_at.to_rfc3339();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO diagnosis_sessions (id, plant_id, status, state, diagnosis_context, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+        session.id,
+        session.plant_id,
+        status,
+        state,
+        context_json,
+        created_at,
+        updated_at,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    record(&mut *conn, session, AuditAction::Created).await?;
+
+    Ok(())
+}
+
+/// Add a turn to a session's conversation on `conn`, with its audit event
+pub(crate) async fn insert_message(
+    conn: &mut SqliteConnection,
+    session: &DiagnosisSession,
+    message: &DiagnosisMessage,
+) -> Result<()> {
+    let created_at = message.created_at.to_rfc3339();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO diagnosis_messages (id, session_id, role, content, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        message.id,
+        message.session_id,
+        message.role,
+        message.content,
+        created_at,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let event = AuditEvent::new(
+        AuditEntity::DiagnosisMessage,
+        &message.id,
+        Some(&session.plant_id),
+        AuditAction::Created,
+        message,
+    )?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(())
+}
+
+async fn record(
+    conn: &mut SqliteConnection,
+    session: &DiagnosisSession,
+    action: AuditAction,
+) -> Result<()> {
+    let event = AuditEvent::new(
+        AuditEntity::Diagnosis,
+        &session.id,
+        Some(&session.plant_id),
+        action,
+        session,
+    )?;
+    event_repository::record(conn, &event).await
+}
+
+impl TryFrom<SessionRecord> for DiagnosisSession {
+    type Error = anyhow::Error;
+
+    fn try_from(record: SessionRecord) -> Result<Self> {
+        // Sessions saved before states were recorded only have a status
+        let state_str = record.state.unwrap_or(record.status);
+        let state = DiagnosisState::from_str(&state_str)
+            .ok_or_else(|| anyhow::anyhow!("Invalid diagnosis state"))?;
+
+        Ok(DiagnosisSession {
+            id: record.id,
+            plant_id: record.plant_id,
+            state,
+            diagnosis_context: serde_json::from_str(&record.diagnosis_context)
+                .context("diagnosis_context is not valid JSON")?,
+            created_at: DateTime::parse_from_rfc3339(&record.created_at)
+                .context("created_at is not a valid date")?
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&record.updated_at)
+                .context("updated_at is not a valid date")?
+                .with_timezone(&Utc),
+        })
+    }
+}
+
+impl TryFrom<MessageRecord> for DiagnosisMessage {
+    type Error = anyhow::Error;
+
+    fn try_from(record: MessageRecord) -> Result<Self> {
+        Ok(DiagnosisMessage {
+            id: record.id,
+            session_id: record.session_id,
+            role: record.role,
+            content: record.content,
+            created_at: DateTime::parse_from_rfc3339(&record.created_at)?.with_timezone(&Utc),
+        })
+    }
+}
+
+impl TryFrom<SummaryRecord> for DiagnosisSummary {
+    type Error = anyhow::Error;
+
+    fn try_from(record: SummaryRecord) -> Result<Self> {
+        if !record.context_valid {
+            anyhow::bail!("diagnosis_context is not valid JSON");
+        }
+        let status = DiagnosisStatus::from_str(&record.status)
+            .ok_or_else(|| anyhow::anyhow!("Invalid diagnosis status"))?;
+
+        Ok(DiagnosisSummary {
+            id: record.id,
+            plant_id: record.plant_id,
+            status,
+            problem: record.problem.unwrap_or_default(),
+            result: record.result.map(|r| serde_json::from_str(&r)).transpose()?,
+            created_at: DateTime::parse_from_rfc3339(&record.created_at)?.with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&record.updated_at)?.with_timezone(&Utc),
+        })
+    }
+}
+
+/// Check every diagnosis session, quarantining the unreadable ones with
+/// their conversations and removing them. Returns how many sessions were
+/// checked.
+pub(crate) async fn repair(conn: &mut SqliteConnection) -> Result<(usize, Vec<QuarantinedRow>)> {
+    let rows = sqlx::query_as!(
+        SessionRecord,
+        r#"
+        SELECT id AS "id!", plant_id, status, state, diagnosis_context, created_at, updated_at
+        FROM diagnosis_sessions
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let checked = rows.len();
+    let mut quarantined = Vec::new();
+    for row in rows {
+        let Err(error) = DiagnosisSession::try_from(row.clone()) else {
+            continue;
+        };
+        let error = format!("{:#}", error);
+
+        let messages = sqlx::query_as!(
+            MessageRecord,
+            r#"
+            SELECT id AS "id!", session_id, role, content, created_at
+            FROM diagnosis_messages
+            WHERE session_id = ?
+            ORDER BY created_at, rowid
+            "#,
+            row.id,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        let data = json!({ "session": row, "messages": messages });
+        repair_repository::quarantine(conn, "diagnosis_sessions", &row.id, &data, &error).await?;
+
+        // Its messages go with it
+        sqlx::query!("DELETE FROM diagnosis_sessions WHERE id = ?", row.id)
+            .execute(&mut *conn)
+            .await?;
+
+        log::info!(
+            target: "plant_care::db",
+            table = "diagnosis_sessions",
+            id = row.id.as_str();
+            "Removed diagnosis session {}: {}",
+            row.id,
+            error
+        );
+        quarantined.push(QuarantinedRow {
+            table: "diagnosis_sessions",
+            row_id: row.id,
+            error,
+            repair: Repair::Removed,
+        });
+    }
+
+    Ok((checked, quarantined))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CareSchedule, DiagnosisState, Plant};
+    use crate::repositories::PlantRepository;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_filtered_history_across_plants() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let diagnoses = DiagnosisRepository::new(db);
+
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        let ivy = Plant::new("u".to_string(), "Ivy".to_string(), CareSchedule::default());
+        let other = Plant::new("v".to_string(), "Cactus".to_string(), CareSchedule::default());
+        for plant in [&fern, &ivy, &other] {
+            plants.create(plant).await.unwrap();
+        }
+
+        let mut rot = DiagnosisSession::new(fern.id.clone(), "Mushy stems".to_string());
+        rot.state = DiagnosisState::Concluded;
+        rot.diagnosis_context["result"] = json!({"finding": "Root Rot from overwatering", "severity": "serious"});
+        let pending = DiagnosisSession::new(ivy.id.clone(), "Yellow leaves".to_string());
+        let mut old = DiagnosisSession::new(ivy.id.clone(), "Brown tips".to_string());
+        old.created_at = Utc::now() - Duration::days(30);
+        for session in [&rot, &pending, &old, &DiagnosisSession::new(other.id.clone(), "Spots".to_string())] {
+            diagnoses.create(session).await.unwrap();
+        }
+
+        let everything = DiagnosisFilter::default();
+        assert_eq!(diagnoses.count_matching("u", None, &everything).await.unwrap(), 3);
+        assert_eq!(diagnoses.count_matching("u", Some(&ivy.id), &everything).await.unwrap(), 2);
+
+        let completed = DiagnosisFilter {
+            status: Some(DiagnosisStatus::Completed),
+            ..Default::default()
+        };
+        let found = diagnoses.query_summaries("u", None, &completed, 0, 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, rot.id);
+
+        let recent = DiagnosisFilter {
+            since: Some(Utc::now() - Duration::days(7)),
+            ..Default::default()
+        };
+        assert_eq!(diagnoses.count_matching("u", None, &recent).await.unwrap(), 2);
+        let page = diagnoses.query_summaries("u", None, &recent, 1, 1).await.unwrap();
+        assert_eq!(page.len(), 1);
+
+        let finding = DiagnosisFilter {
+            finding: Some("root rot".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(diagnoses.count_matching("u", None, &finding).await.unwrap(), 1);
+        assert_eq!(diagnoses.count_matching("u", Some(&ivy.id), &finding).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_recent_usage_of_concluded_sessions() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let diagnoses = DiagnosisRepository::new(db);
+
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&fern).await.unwrap();
+
+        let usage = json!({"requests": 3, "prompt_tokens": 9000, "completion_tokens": 700});
+        let mut measured = DiagnosisSession::new(fern.id.clone(), "Drooping".to_string());
+        measured.state = DiagnosisState::Concluded;
+        measured.diagnosis_context["usage"] = usage.clone();
+        let mut unmeasured = DiagnosisSession::new(fern.id.clone(), "Spots".to_string());
+        unmeasured.state = DiagnosisState::Concluded;
+        let mut pending = DiagnosisSession::new(fern.id.clone(), "Curling".to_string());
+        pending.diagnosis_context["usage"] = usage;
+        for session in [&measured, &unmeasured, &pending] {
+            diagnoses.create(session).await.unwrap();
+        }
+
+        let recent = diagnoses.get_recent_usage("u", 10).await.unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].prompt_tokens, 9000);
+        assert!(diagnoses.get_recent_usage("v", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_only_touched_sessions() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let diagnoses = DiagnosisRepository::new(db);
+
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&fern).await.unwrap();
+
+        // Waiting for another user, e.g. on a server sharing the database
+        let elsewhere = DiagnosisSession::new(fern.id.clone(), "Spots".to_string());
+        diagnoses.create(&elsewhere).await.unwrap();
+
+        track_sessions();
+        let mine = DiagnosisSession::new(fern.id.clone(), "Drooping".to_string());
+        diagnoses.create(&mine).await.unwrap();
+
+        assert_eq!(diagnoses.cancel_pending_touched("interrupted").await.unwrap(), 1);
+        let mine = diagnoses.get_by_id(&mine.id).await.unwrap().unwrap();
+        assert_eq!(mine.status(), DiagnosisStatus::Cancelled);
+        assert_eq!(mine.diagnosis_context["cancel_reason"], "interrupted");
+        let elsewhere = diagnoses.get_by_id(&elsewhere.id).await.unwrap().unwrap();
+        assert_eq!(elsewhere.status(), DiagnosisStatus::PendingUserInput);
+    }
+}
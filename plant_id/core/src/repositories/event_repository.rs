@@ -0,0 +1,81 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, Sqlite, SqliteConnection};
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent};
+
+/// Columns selected for every event query
+pub(crate) const EVENT_COLUMNS: &str =
+    "id, entity_type, entity_id, plant_id, action, payload, created_at";
+
+/// Read access to the audit log. Events are written by the other
+/// repositories with `record`, in the same transaction as the change.
+#[derive(Clone)]
+pub struct EventRepository {
+    db: Database,
+}
+
+impl EventRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Every event about a plant and its records, oldest first
+    pub async fn get_by_plant_id(&self, plant_id: &str) -> Result<Vec<AuditEvent>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM events
+            WHERE plant_id = ?
+            ORDER BY created_at, rowid
+            "#,
+            EVENT_COLUMNS
+        ))
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(event_from_row).collect()
+    }
+}
+
+/// Append an event to the audit log
+pub(crate) async fn record(conn: &mut SqliteConnection, event: &AuditEvent) -> Result<()> {
+    sqlx::query::<Sqlite>(
+        r#"
+        INSERT INTO events (id, entity_type, entity_id, plant_id, action, payload, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&event.id)
+    .bind(event.entity.as_str())
+    .bind(&event.entity_id)
+    .bind(&event.plant_id)
+    .bind(event.action.as_str())
+    .bind(serde_json::to_string(&event.payload)?)
+    .bind(event.created_at.to_rfc3339())
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub(crate) fn event_from_row(row: &SqliteRow) -> Result<AuditEvent> {
+    let entity_type: String = row.get("entity_type");
+    let action: String = row.get("action");
+    let created_at: String = row.get("created_at");
+
+    Ok(AuditEvent {
+        id: row.get("id"),
+        entity: AuditEntity::from_str(&entity_type)
+            .ok_or_else(|| anyhow::anyhow!("Unknown audit entity: {}", entity_type))?,
+        entity_id: row.get("entity_id"),
+        plant_id: row.get("plant_id"),
+        action: AuditAction::from_str(&action)
+            .ok_or_else(|| anyhow::anyhow!("Unknown audit action: {}", action))?,
+        payload: serde_json::from_str(row.get("payload"))?,
+        created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+    })
+}
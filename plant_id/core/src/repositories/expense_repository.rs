@@ -0,0 +1,110 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, Expense, ExpenseKind};
+use crate::repositories::event_repository;
+
+const EXPENSE_COLUMNS: &str = "e.id, e.plant_id, e.kind, e.amount_cents, e.description, e.spent_at";
+
+#[derive(Clone)]
+pub struct ExpenseRepository {
+    db: Database,
+}
+
+impl ExpenseRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, expense: &Expense) -> Result<Expense> {
+        let mut tx = self.db.pool().begin().await?;
+        insert(&mut tx, expense).await?;
+        tx.commit().await?;
+
+        Ok(expense.clone())
+    }
+
+    /// Expenses of a plant, oldest first
+    pub async fn get_all_by_plant_id(&self, plant_id: &str) -> Result<Vec<Expense>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM expenses e
+            WHERE e.plant_id = ?
+            ORDER BY e.spent_at
+            "#,
+            EXPENSE_COLUMNS
+        ))
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(expense_from_row).collect()
+    }
+
+    /// Expenses of all of a user's plants, archived ones included, oldest first
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<Expense>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM expenses e
+            JOIN plants p ON p.id = e.plant_id
+            WHERE p.user_id = ?
+            ORDER BY e.spent_at
+            "#,
+            EXPENSE_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(expense_from_row).collect()
+    }
+}
+
+/// Insert an expense on `conn`, with its audit event
+pub(crate) async fn insert(conn: &mut SqliteConnection, expense: &Expense) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO expenses (id, plant_id, kind, amount_cents, description, spent_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&expense.id)
+    .bind(&expense.plant_id)
+    .bind(expense.kind.as_str())
+    .bind(expense.amount_cents)
+    .bind(&expense.description)
+    .bind(expense.spent_at.to_rfc3339())
+    .execute(&mut *conn)
+    .await?;
+
+    let event = AuditEvent::new(
+        AuditEntity::Expense,
+        &expense.id,
+        Some(&expense.plant_id),
+        AuditAction::Created,
+        expense,
+    )?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(())
+}
+
+fn expense_from_row(row: &SqliteRow) -> Result<Expense> {
+    let kind: String = row.get("kind");
+    let spent_at: String = row.get("spent_at");
+
+    Ok(Expense {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        kind: ExpenseKind::from_str(&kind)
+            .ok_or_else(|| anyhow::anyhow!("Unknown expense kind: {}", kind))?,
+        amount_cents: row.get("amount_cents"),
+        description: row.get("description"),
+        spent_at: DateTime::parse_from_rfc3339(&spent_at)?.with_timezone(&Utc),
+    })
+}
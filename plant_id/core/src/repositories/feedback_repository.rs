@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, Feedback, FeedbackKind};
+use crate::repositories::event_repository;
+
+#[derive(Clone)]
+pub struct FeedbackRepository {
+    db: Database,
+}
+
+impl FeedbackRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, feedback: &Feedback) -> Result<Feedback> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO feedback (id, kind, subject, model, rating, comment, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&feedback.id)
+        .bind(feedback.kind.as_str())
+        .bind(&feedback.subject)
+        .bind(&feedback.model)
+        .bind(feedback.rating)
+        .bind(&feedback.comment)
+        .bind(feedback.created_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        let event = AuditEvent::new(
+            AuditEntity::Feedback,
+            &feedback.id,
+            None,
+            AuditAction::Created,
+            feedback,
+        )?;
+        event_repository::record(&mut tx, &event).await?;
+        tx.commit().await?;
+
+        Ok(feedback.clone())
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<Feedback>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, kind, subject, model, rating, comment, created_at
+            FROM feedback
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut feedback = Vec::new();
+        for row in rows {
+            let kind: String = row.get("kind");
+            let created_at: String = row.get("created_at");
+
+            feedback.push(Feedback {
+                id: row.get("id"),
+                kind: FeedbackKind::from_str(&kind)
+                    .context(format!("Unknown feedback kind '{}'", kind))?,
+                subject: row.get("subject"),
+                model: row.get("model"),
+                rating: row.get("rating"),
+                comment: row.get("comment"),
+                created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+            });
+        }
+
+        Ok(feedback)
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, IdentificationDisagreement};
+use crate::repositories::event_repository;
+
+/// Columns selected for every disagreement query
+const DISAGREEMENT_COLUMNS: &str =
+    "d.id, d.plant_id, d.candidates, d.chosen, d.resolution, d.corrected_to, d.created_at, d.updated_at";
+
+#[derive(Clone)]
+pub struct IdentificationRepository {
+    db: Database,
+}
+
+impl IdentificationRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Disagreements over a user's plants, most recent first
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<IdentificationDisagreement>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM identification_disagreements d
+            JOIN plants p ON p.id = d.plant_id
+            WHERE p.user_id = ?
+            ORDER BY d.created_at DESC
+            "#,
+            DISAGREEMENT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(disagreement_from_row).collect()
+    }
+
+    /// The latest disagreement over a plant's photo, if the identifiers
+    /// disagreed when it was added
+    pub async fn get_by_plant_id(&self, plant_id: &str) -> Result<Option<IdentificationDisagreement>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM identification_disagreements d
+            WHERE d.plant_id = ?
+            ORDER BY d.created_at DESC
+            LIMIT 1
+            "#,
+            DISAGREEMENT_COLUMNS
+        ))
+        .bind(plant_id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.as_ref().map(disagreement_from_row).transpose()
+    }
+
+    /// Record a disagreement, or save its correction
+    pub async fn save(&self, disagreement: &IdentificationDisagreement) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let existed = sqlx::query("SELECT 1 FROM identification_disagreements WHERE id = ?")
+            .bind(&disagreement.id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+
+        upsert(&mut tx, disagreement).await?;
+
+        let action = if existed {
+            AuditAction::Updated
+        } else {
+            AuditAction::Created
+        };
+        let event = AuditEvent::new(
+            AuditEntity::IdentificationDisagreement,
+            &disagreement.id,
+            Some(&disagreement.plant_id),
+            action,
+            disagreement,
+        )?;
+        event_repository::record(&mut tx, &event).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Insert a disagreement, or update the correction of a known one
+pub(crate) async fn upsert(conn: &mut SqliteConnection, disagreement: &IdentificationDisagreement) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO identification_disagreements
+            (id, plant_id, candidates, chosen, resolution, corrected_to, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET
+            corrected_to = excluded.corrected_to, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&disagreement.id)
+    .bind(&disagreement.plant_id)
+    .bind(serde_json::to_string(&disagreement.candidates)?)
+    .bind(&disagreement.chosen)
+    .bind(serde_json::to_string(&disagreement.resolution)?)
+    .bind(&disagreement.corrected_to)
+    .bind(disagreement.created_at.to_rfc3339())
+    .bind(disagreement.updated_at.to_rfc3339())
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+fn disagreement_from_row(row: &SqliteRow) -> Result<IdentificationDisagreement> {
+    let candidates: String = row.get("candidates");
+    let resolution: String = row.get("resolution");
+    let created_at: String = row.get("created_at");
+    let updated_at: String = row.get("updated_at");
+
+    Ok(IdentificationDisagreement {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        candidates: serde_json::from_str(&candidates).context("candidates are not species candidates")?,
+        chosen: row.get("chosen"),
+        resolution: serde_json::from_str(&resolution).context("resolution is not a resolution")?,
+        corrected_to: row.get("corrected_to"),
+        created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CareSchedule, Plant, SpeciesCandidate, SpeciesResolution};
+    use crate::repositories::PlantRepository;
+    use crate::services::plant_service;
+
+    #[tokio::test]
+    async fn test_disagreement_is_recorded_then_corrected() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let identifications = IdentificationRepository::new(db);
+
+        let mut plant = Plant::new("u".to_string(), "Swiss cheese plant".to_string(), CareSchedule::default());
+        plant.scientific_name = Some("Monstera deliciosa".to_string());
+        plants.create(&plant).await.unwrap();
+        assert!(plant_service::correct_species(&plants, &identifications, &mut plant, "Monstera adansonii")
+            .await
+            .is_err());
+
+        let candidate = |source: &str, name: &str, common: &str| SpeciesCandidate {
+            source: source.to_string(),
+            scientific_name: name.to_string(),
+            common_names: vec![common.to_string()],
+            confidence: Some(0.6),
+        };
+        let disagreement = IdentificationDisagreement::new(
+            plant.id.clone(),
+            vec![
+                candidate("Plant.id", "Monstera deliciosa", "Swiss cheese plant"),
+                candidate("offline model", "Monstera adansonii", "Adanson's monstera"),
+            ],
+            "Monstera deliciosa".to_string(),
+            SpeciesResolution::Default,
+        );
+        identifications.save(&disagreement).await.unwrap();
+
+        let corrected = plant_service::correct_species(&plants, &identifications, &mut plant, "monstera adansonii")
+            .await
+            .unwrap();
+        assert_eq!(corrected.species(), "Monstera adansonii");
+        assert_eq!(plant.name, "Adanson's monstera");
+
+        let saved = plants.get_by_id(&plant.id, "u").await.unwrap().unwrap();
+        assert_eq!(saved.scientific_name.as_deref(), Some("Monstera adansonii"));
+        let listed = identifications.get_all_by_user("u").await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].corrected_to.as_deref(), Some("Monstera adansonii"));
+        assert_eq!(listed[0].resolution, SpeciesResolution::Default);
+        assert!(identifications.get_all_by_user("someone else").await.unwrap().is_empty());
+    }
+}
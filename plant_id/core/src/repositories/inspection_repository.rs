@@ -0,0 +1,130 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, PreventiveInspection};
+use crate::repositories::event_repository;
+
+/// Columns selected for every inspection query
+const INSPECTION_COLUMNS: &str = "i.id, i.plant_id, i.reason, i.source_diagnosis_id, i.due_on, i.created_at, i.done_at";
+
+#[derive(Clone)]
+pub struct InspectionRepository {
+    db: Database,
+}
+
+impl InspectionRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// The plant's preventive inspections not done yet, soonest first
+    pub async fn get_open_by_plant_id(&self, plant_id: &str) -> Result<Vec<PreventiveInspection>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM preventive_inspections i
+            WHERE i.plant_id = ? AND i.done_at IS NULL
+            ORDER BY i.due_on, i.created_at
+            "#,
+            INSPECTION_COLUMNS
+        ))
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(inspection_from_row).collect()
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<PreventiveInspection>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM preventive_inspections i WHERE i.id = ?",
+            INSPECTION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.as_ref().map(inspection_from_row).transpose()
+    }
+
+    /// Schedule an inspection, or save that it was done
+    pub async fn save(&self, inspection: &PreventiveInspection) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+        save(&mut tx, inspection).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Save an inspection on `conn`, with its audit event
+pub(crate) async fn save(conn: &mut SqliteConnection, inspection: &PreventiveInspection) -> Result<()> {
+    let existed = sqlx::query("SELECT 1 FROM preventive_inspections WHERE id = ?")
+        .bind(&inspection.id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .is_some();
+
+    upsert(&mut *conn, inspection).await?;
+
+    let action = if existed {
+        AuditAction::Updated
+    } else {
+        AuditAction::Created
+    };
+    let event = AuditEvent::new(
+        AuditEntity::PreventiveInspection,
+        &inspection.id,
+        Some(&inspection.plant_id),
+        action,
+        inspection,
+    )?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(())
+}
+
+/// Insert an inspection, or update when a known one was done
+pub(crate) async fn upsert(conn: &mut SqliteConnection, inspection: &PreventiveInspection) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO preventive_inspections
+            (id, plant_id, reason, source_diagnosis_id, due_on, created_at, done_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET
+            done_at = excluded.done_at
+        "#,
+    )
+    .bind(&inspection.id)
+    .bind(&inspection.plant_id)
+    .bind(&inspection.reason)
+    .bind(&inspection.source_diagnosis_id)
+    .bind(inspection.due_on.to_string())
+    .bind(inspection.created_at.to_rfc3339())
+    .bind(inspection.done_at.map(|at| at.to_rfc3339()))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+fn inspection_from_row(row: &SqliteRow) -> Result<PreventiveInspection> {
+    let due_on: String = row.get("due_on");
+    let created_at: String = row.get("created_at");
+    let done_at: Option<String> = row.get("done_at");
+
+    Ok(PreventiveInspection {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        reason: row.get("reason"),
+        source_diagnosis_id: row.get("source_diagnosis_id"),
+        due_on: NaiveDate::parse_from_str(&due_on, "%Y-%m-%d")?,
+        created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+        done_at: done_at
+            .map(|at| DateTime::parse_from_rfc3339(&at).map(|at| at.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{Job, JobKind, JobStatus};
+
+/// Columns selected for every job query
+const JOB_COLUMNS: &str =
+    "id, user_id, plant_id, kind, payload, status, attempts, last_error, run_at, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct JobRepository {
+    db: Database,
+}
+
+impl JobRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Queue a job, unless the same work (kind, user, plant and payload) is
+    /// already pending. Returns the pending job either way.
+    pub async fn enqueue(&self, job: &Job) -> Result<Job> {
+        let payload = job.payload.as_ref().map(serde_json::to_string).transpose()?;
+        let pending = sqlx::query(&format!(
+            "SELECT {} FROM jobs
+             WHERE kind = ? AND status = ? AND user_id IS ? AND plant_id IS ? AND payload IS ?",
+            JOB_COLUMNS
+        ))
+        .bind(job.kind.as_str())
+        .bind(JobStatus::Pending.as_str())
+        .bind(&job.user_id)
+        .bind(&job.plant_id)
+        .bind(&payload)
+        .fetch_optional(self.db.pool())
+        .await?;
+        if let Some(row) = pending {
+            return job_from_row(&row);
+        }
+
+        self.save(job).await?;
+        Ok(job.clone())
+    }
+
+    pub async fn save(&self, job: &Job) -> Result<()> {
+        let payload = job.payload.as_ref().map(serde_json::to_string).transpose()?;
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, user_id, plant_id, kind, payload, status, attempts, last_error, run_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                status = excluded.status, attempts = excluded.attempts, last_error = excluded.last_error,
+                run_at = excluded.run_at, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&job.id)
+        .bind(&job.user_id)
+        .bind(&job.plant_id)
+        .bind(job.kind.as_str())
+        .bind(payload)
+        .bind(job.status.as_str())
+        .bind(job.attempts)
+        .bind(&job.last_error)
+        .bind(job.run_at.to_rfc3339())
+        .bind(job.created_at.to_rfc3339())
+        .bind(job.updated_at.to_rfc3339())
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Pending jobs due by `now`, longest due first
+    pub async fn get_due(&self, now: DateTime<Utc>) -> Result<Vec<Job>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM jobs WHERE status = ? AND run_at <= ? ORDER BY run_at, created_at",
+            JOB_COLUMNS
+        ))
+        .bind(JobStatus::Pending.as_str())
+        .bind(now.to_rfc3339())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(job_from_row).collect()
+    }
+
+    /// A user's jobs and those of the whole device, newest first
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<Job>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM jobs WHERE user_id = ? OR user_id IS NULL ORDER BY created_at DESC",
+            JOB_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(job_from_row).collect()
+    }
+}
+
+fn job_from_row(row: &SqliteRow) -> Result<Job> {
+    let kind: String = row.get("kind");
+    let payload: Option<String> = row.get("payload");
+    let status: String = row.get("status");
+    let run_at: String = row.get("run_at");
+    let created_at: String = row.get("created_at");
+    let updated_at: String = row.get("updated_at");
+
+    Ok(Job {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        plant_id: row.get("plant_id"),
+        kind: JobKind::from_str(&kind).with_context(|| format!("Unknown job kind: {}", kind))?,
+        payload: payload.map(|p| serde_json::from_str(&p)).transpose()?,
+        status: JobStatus::from_str(&status).with_context(|| format!("Unknown job status: {}", status))?,
+        attempts: row.get("attempts"),
+        last_error: row.get("last_error"),
+        run_at: DateTime::parse_from_rfc3339(&run_at)?.with_timezone(&Utc),
+        created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+    })
+}
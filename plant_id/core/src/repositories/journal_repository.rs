@@ -0,0 +1,84 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteConnection;
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, JournalEntry};
+use crate::repositories::event_repository;
+
+#[derive(Clone)]
+pub struct JournalRepository {
+    db: Database,
+}
+
+impl JournalRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, entry: &JournalEntry) -> Result<JournalEntry> {
+        let mut tx = self.db.pool().begin().await?;
+        insert(&mut tx, entry).await?;
+        tx.commit().await?;
+
+        Ok(entry.clone())
+    }
+
+    pub async fn get_all_by_plant_id(&self, plant_id: &str) -> Result<Vec<JournalEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, plant_id, note, is_problem, created_at
+            FROM journal_entries
+            WHERE plant_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let created_at: String = row.get("created_at");
+
+            entries.push(JournalEntry {
+                id: row.get("id"),
+                plant_id: row.get("plant_id"),
+                note: row.get("note"),
+                is_problem: row.get("is_problem"),
+                created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Insert a journal entry on `conn`, with its audit event
+pub(crate) async fn insert(conn: &mut SqliteConnection, entry: &JournalEntry) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO journal_entries (id, plant_id, note, is_problem, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&entry.id)
+    .bind(&entry.plant_id)
+    .bind(&entry.note)
+    .bind(entry.is_problem)
+    .bind(entry.created_at.to_rfc3339())
+    .execute(&mut *conn)
+    .await?;
+
+    let event = AuditEvent::new(
+        AuditEntity::Journal,
+        &entry.id,
+        Some(&entry.plant_id),
+        AuditAction::Created,
+        entry,
+    )?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+/*!
+ * REPOSITORIES MODULE
+ *
+ * Data access layer that handles persistence operations.
+ * These are secondary adapters in hexagonal architecture,
+ * abstracting database operations from the business logic.
+ */
+
+// Declare repository modules
+pub mod attribute_repository;
+pub mod care_override_repository;
+pub mod care_task_repository;
+pub mod chat_repository;
+pub mod diagnosis_repository;
+pub mod event_repository;
+pub mod expense_repository;
+pub mod feedback_repository;
+pub mod identification_repository;
+pub mod inspection_repository;
+pub mod job_repository;
+pub mod journal_repository;
+pub mod moisture_repository;
+pub mod outcome_repository;
+pub mod plant_cache;
+pub mod plant_repository;
+pub mod quarantine_repository;
+pub mod repair_repository;
+pub mod room_repository;
+pub mod schedule_version_repository;
+pub mod shopping_repository;
+pub mod stats_repository;
+pub mod sync_repository;
+pub mod template_repository;
+pub mod user_repository;
+pub mod wishlist_repository;
+
+// Re-export repository structs for easier access
+pub use attribute_repository::AttributeRepository;
+pub use care_override_repository::CareOverrideRepository;
+pub use care_task_repository::CareTaskRepository;
+pub use chat_repository::ChatRepository;
+pub use diagnosis_repository::DiagnosisRepository;
+pub use event_repository::EventRepository;
+pub use expense_repository::ExpenseRepository;
+pub use feedback_repository::FeedbackRepository;
+pub use identification_repository::IdentificationRepository;
+pub use inspection_repository::InspectionRepository;
+pub use job_repository::JobRepository;
+pub use journal_repository::JournalRepository;
+pub use moisture_repository::MoistureRepository;
+pub use outcome_repository::OutcomeRepository;
+pub use plant_cache::PlantCache;
+pub use plant_repository::PlantRepository;
+pub use quarantine_repository::QuarantineRepository;
+pub use repair_repository::RepairRepository;
+pub use room_repository::RoomRepository;
+pub use schedule_version_repository::ScheduleVersionRepository;
+pub use shopping_repository::ShoppingRepository;
+pub use stats_repository::StatsRepository;
+pub use sync_repository::SyncRepository;
+pub use template_repository::TemplateRepository;
+pub use user_repository::UserRepository;
+pub use wishlist_repository::WishlistRepository;
+
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteConnection;
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, MoistureReading};
+use crate::repositories::event_repository;
+
+#[derive(Clone)]
+pub struct MoistureRepository {
+    db: Database,
+}
+
+impl MoistureRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, reading: &MoistureReading) -> Result<MoistureReading> {
+        let mut tx = self.db.pool().begin().await?;
+        insert(&mut tx, reading).await?;
+        tx.commit().await?;
+
+        Ok(reading.clone())
+    }
+
+    /// Readings of a plant, oldest first
+    pub async fn get_all_by_plant_id(&self, plant_id: &str) -> Result<Vec<MoistureReading>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, plant_id, value, recorded_at
+            FROM moisture_readings
+            WHERE plant_id = ?
+            ORDER BY recorded_at
+            "#,
+        )
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut readings = Vec::new();
+        for row in rows {
+            let recorded_at: String = row.get("recorded_at");
+
+            readings.push(MoistureReading {
+                id: row.get("id"),
+                plant_id: row.get("plant_id"),
+                value: row.get("value"),
+                recorded_at: DateTime::parse_from_rfc3339(&recorded_at)?.with_timezone(&Utc),
+            });
+        }
+
+        Ok(readings)
+    }
+}
+
+/// Insert a reading on `conn`, with its audit event
+pub(crate) async fn insert(conn: &mut SqliteConnection, reading: &MoistureReading) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO moisture_readings (id, plant_id, value, recorded_at)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(&reading.id)
+    .bind(&reading.plant_id)
+    .bind(reading.value)
+    .bind(reading.recorded_at.to_rfc3339())
+    .execute(&mut *conn)
+    .await?;
+
+    let event = AuditEvent::new(
+        AuditEntity::MoistureReading,
+        &reading.id,
+        Some(&reading.plant_id),
+        AuditAction::Created,
+        reading,
+    )?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(())
+}
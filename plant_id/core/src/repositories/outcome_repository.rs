@@ -0,0 +1,107 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, TreatmentOutcome};
+use crate::repositories::event_repository;
+
+#[derive(Clone)]
+pub struct OutcomeRepository {
+    db: Database,
+}
+
+impl OutcomeRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, outcome: &TreatmentOutcome) -> Result<TreatmentOutcome> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO treatment_outcomes (id, plant_id, species, diagnosis_id, finding, recommendation, worked, note, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&outcome.id)
+        .bind(&outcome.plant_id)
+        .bind(&outcome.species)
+        .bind(&outcome.diagnosis_id)
+        .bind(&outcome.finding)
+        .bind(&outcome.recommendation)
+        .bind(outcome.worked)
+        .bind(&outcome.note)
+        .bind(outcome.created_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        let event = AuditEvent::new(
+            AuditEntity::TreatmentOutcome,
+            &outcome.id,
+            Some(&outcome.plant_id),
+            AuditAction::Created,
+            outcome,
+        )?;
+        event_repository::record(&mut tx, &event).await?;
+        tx.commit().await?;
+
+        Ok(outcome.clone())
+    }
+
+    /// Outcomes recorded for one plant, newest first
+    pub async fn get_by_plant_id(&self, plant_id: &str) -> Result<Vec<TreatmentOutcome>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, plant_id, species, diagnosis_id, finding, recommendation, worked, note, created_at
+            FROM treatment_outcomes
+            WHERE plant_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(outcome_from_row).collect()
+    }
+
+    /// Outcomes recorded for a species by any of the user's plants
+    pub async fn get_by_species(&self, species: &str, user_id: &str) -> Result<Vec<TreatmentOutcome>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT o.id, o.plant_id, o.species, o.diagnosis_id, o.finding, o.recommendation,
+                   o.worked, o.note, o.created_at
+            FROM treatment_outcomes o
+            JOIN plants p ON p.id = o.plant_id
+            WHERE o.species = ? AND p.user_id = ?
+            ORDER BY o.created_at DESC
+            "#,
+        )
+        .bind(species)
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(outcome_from_row).collect()
+    }
+}
+
+fn outcome_from_row(row: &SqliteRow) -> Result<TreatmentOutcome> {
+    let created_at: String = row.get("created_at");
+
+    Ok(TreatmentOutcome {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        species: row.get("species"),
+        diagnosis_id: row.get("diagnosis_id"),
+        finding: row.get("finding"),
+        recommendation: row.get("recommendation"),
+        worked: row.get("worked"),
+        note: row.get("note"),
+        created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+    })
+}
+
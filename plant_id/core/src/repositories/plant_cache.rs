@@ -0,0 +1,133 @@
+/*!
+ * PLANT CACHE
+ *
+ * Keeps recently read plants in memory, so the long-running front-ends
+ * (HTTP and gRPC servers, chat bots) stop going to the database for the
+ * same plants on every request. `PlantRepository` reads through it when
+ * the database has one, and every write to the plants table clears what
+ * it touched. Entries also expire after PLANT_CACHE_TTL_SECS, as another
+ * process (a CLI command, a sync) may change the database behind it.
+ */
+
+use moka::future::Cache;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::domain::Plant;
+
+/// How long a plant is kept when PLANT_CACHE_TTL_SECS is not set
+const DEFAULT_TTL_SECS: u64 = 30;
+
+/// Most plants kept at once; collections count as one per plant
+const MAX_PLANTS: u64 = 10_000;
+
+#[derive(Clone)]
+pub struct PlantCache {
+    /// Plants by ID
+    plants: Cache<String, Plant>,
+    /// Each user's collection (plants not archived), as `get_all_by_user`
+    /// returns it
+    collections: Cache<String, Arc<Vec<Plant>>>,
+}
+
+impl PlantCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            plants: Cache::builder().max_capacity(MAX_PLANTS).time_to_live(ttl).build(),
+            collections: Cache::builder()
+                .max_capacity(MAX_PLANTS)
+                .weigher(|_, plants: &Arc<Vec<Plant>>| plants.len().try_into().unwrap_or(u32::MAX).max(1))
+                .time_to_live(ttl)
+                .build(),
+        }
+    }
+
+    /// A cache expiring entries after PLANT_CACHE_TTL_SECS, or none if that
+    /// is 0
+    pub fn from_env() -> Option<Self> {
+        let ttl = std::env::var("PLANT_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(DEFAULT_TTL_SECS);
+        (ttl > 0).then(|| Self::new(Duration::from_secs(ttl)))
+    }
+
+    pub(crate) async fn plant(&self, id: &str, user_id: &str) -> Option<Plant> {
+        self.plants.get(id).await.filter(|plant| plant.user_id == user_id)
+    }
+
+    pub(crate) async fn collection(&self, user_id: &str) -> Option<Arc<Vec<Plant>>> {
+        self.collections.get(user_id).await
+    }
+
+    pub(crate) async fn insert_plant(&self, plant: &Plant) {
+        self.plants.insert(plant.id.clone(), plant.clone()).await;
+    }
+
+    /// Keep a user's collection, and each of its plants by ID
+    pub(crate) async fn insert_collection(&self, user_id: &str, plants: &[Plant]) {
+        for plant in plants {
+            self.insert_plant(plant).await;
+        }
+        self.collections.insert(user_id.to_string(), Arc::new(plants.to_vec())).await;
+    }
+
+    /// Forget a plant that was written, and the collection it is in
+    pub(crate) async fn invalidate(&self, plant: &Plant) {
+        self.plants.invalidate(&plant.id).await;
+        self.collections.invalidate(&plant.user_id).await;
+    }
+
+    /// Forget everything, after writes that may touch any plant
+    pub(crate) fn invalidate_all(&self) {
+        self.plants.invalidate_all();
+        self.collections.invalidate_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use crate::domain::CareSchedule;
+    use crate::repositories::PlantRepository;
+
+    #[tokio::test]
+    async fn test_reads_are_cached_until_a_write() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let db = db.with_plant_cache(PlantCache::new(Duration::from_secs(60)));
+        let plants = PlantRepository::new(db.clone());
+
+        let mut fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&fern).await.unwrap();
+        assert_eq!(plants.get_all_by_user("u").await.unwrap().len(), 1);
+        assert!(plants.get_by_id(&fern.id, "u").await.unwrap().is_some());
+
+        // Changed behind the repository's back: the cached plant is served
+        sqlx::query("UPDATE plants SET name = 'Renamed' WHERE id = ?")
+            .bind(&fern.id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let cached = PlantRepository::new(db.clone()).get_by_id(&fern.id, "u").await.unwrap().unwrap();
+        assert_eq!(cached.name, "Fern");
+        assert!(plants.get_by_id(&fern.id, "someone-else").await.unwrap().is_none());
+
+        // Writing through any repository clears it
+        fern.nickname = Some("Fernando".to_string());
+        plants.update(&fern).await.unwrap();
+        assert_eq!(
+            plants.get_by_id(&fern.id, "u").await.unwrap().unwrap().nickname.as_deref(),
+            Some("Fernando")
+        );
+
+        let pothos = Plant::new("u".to_string(), "Pothos".to_string(), CareSchedule::default());
+        plants.create(&pothos).await.unwrap();
+        assert_eq!(plants.get_all_by_user("u").await.unwrap().len(), 2);
+
+        plants.delete(&pothos.id, "u").await.unwrap();
+        assert!(plants.get_by_id(&pothos.id, "u").await.unwrap().is_none());
+        assert_eq!(plants.get_all_by_user("u").await.unwrap().len(), 1);
+    }
+}
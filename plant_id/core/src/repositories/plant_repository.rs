@@ -0,0 +1,805 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::sqlite::SqliteConnection;
+use std::collections::HashSet;
+use std::time::Instant;
+
+use crate::adapters::metrics_adapter;
+use crate::config::Database;
+use crate::domain::{
+    ArchiveReason, AuditAction, AuditEntity, AuditEvent, CareDifficulty, CareOverrides, CareSchedule, CareScheduleVersion,
+    CareTask, CareTaskKind, NameMatch, Plant, PlantArchive, RequestError, ScheduleSource,
+};
+use crate::dto::PlantSnapshotDto;
+use crate::repositories::{
+    attribute_repository, care_override_repository, care_task_repository, chat_repository, diagnosis_repository,
+    event_repository, expense_repository, inspection_repository, journal_repository, moisture_repository,
+    quarantine_repository, schedule_version_repository,
+};
+use crate::repositories::repair_repository::{self, QuarantinedRow, Repair};
+
+/// A row of the plants table, as the queries below select it. The queries
+/// are checked against the schema at build time, from the query data in
+/// `.sqlx` when there is no `DATABASE_URL`.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+struct PlantRecord {
+    id: String,
+    user_id: String,
+    name: String,
+    nickname: Option<String>,
+    scientific_name: Option<String>,
+    common_names: String,
+    care_schedule: String,
+    image_url: Option<String>,
+    image_hash: Option<String>,
+    created_at: String,
+    updated_at: String,
+    archived_at: Option<String>,
+    archive_reason: Option<String>,
+    room: Option<String>,
+    tags: String,
+    difficulty: Option<String>,
+    family: Option<String>,
+    acquired_at: Option<String>,
+    acquired_from: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct PlantRepository {
+    db: Database,
+}
+
+impl PlantRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, plant: &Plant) -> Result<Plant> {
+        self.create_with_schedule_source(plant, ScheduleSource::Manual).await
+    }
+
+    /// Save a new plant, recording its care schedule as version 1 from `source`
+    pub async fn create_with_schedule_source(&self, plant: &Plant, source: ScheduleSource) -> Result<Plant> {
+        let started = Instant::now();
+        let mut tx = self.db.pool().begin().await?;
+        insert(&mut tx, plant, source).await?;
+        tx.commit().await?;
+        metrics_adapter::record_db_query("plants.create", started);
+        if let Some(cache) = self.db.plant_cache() {
+            cache.invalidate(plant).await;
+        }
+
+        Ok(plant.clone())
+    }
+
+    pub async fn get_by_id(&self, id: &str, user_id: &str) -> Result<Option<Plant>> {
+        let cache = self.db.plant_cache();
+        if let Some(cache) = cache {
+            if let Some(plant) = cache.plant(id, user_id).await {
+                record_cache(true);
+                return Ok(Some(plant));
+            }
+        }
+
+        let started = Instant::now();
+        let row = sqlx::query_as!(
+            PlantRecord,
+            r#"
+            SELECT id AS "id!", user_id, name, nickname, scientific_name, common_names, care_schedule, image_url, image_hash, created_at, updated_at,
+                archived_at, archive_reason, room, tags, difficulty, family, acquired_at, acquired_from
+            FROM plants
+            WHERE id = ? AND user_id = ?
+            "#,
+            id,
+            user_id,
+        )
+        .fetch_optional(self.db.pool())
+        .await?;
+        metrics_adapter::record_db_query("plants.get_by_id", started);
+
+        let plant = row
+            .map(Plant::try_from)
+            .transpose()
+            .with_context(|| format!("Plant {} is corrupt; run `plant-care db repair`", id))?;
+        if let (Some(cache), Some(plant)) = (cache, &plant) {
+            record_cache(false);
+            cache.insert_plant(plant).await;
+        }
+
+        Ok(plant)
+    }
+
+    /// The plant in a user's collection that was added from the photo
+    /// with SHA-256 `image_hash`
+    pub async fn get_by_image_hash(&self, image_hash: &str, user_id: &str) -> Result<Option<Plant>> {
+        let row = sqlx::query_as!(
+            PlantRecord,
+            r#"
+            SELECT id AS "id!", user_id, name, nickname, scientific_name, common_names, care_schedule, image_url, image_hash, created_at, updated_at,
+                archived_at, archive_reason, room, tags, difficulty, family, acquired_at, acquired_from
+            FROM plants
+            WHERE image_hash = ? AND user_id = ? AND archived_at IS NULL
+            ORDER BY created_at
+            LIMIT 1
+            "#,
+            image_hash,
+            user_id,
+        )
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(Plant::try_from).transpose()
+    }
+
+    /// Whether any plant, archived or not, still shows the image at `url`
+    pub async fn image_in_use(&self, url: &str) -> Result<bool> {
+        let count = sqlx::query_scalar!("SELECT COUNT(*) FROM plants WHERE image_url = ?", url)
+            .fetch_one(self.db.pool())
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    /// Plants currently in the collection (not archived)
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<Plant>> {
+        let cache = self.db.plant_cache();
+        if let Some(cache) = cache {
+            if let Some(plants) = cache.collection(user_id).await {
+                record_cache(true);
+                return Ok(plants.to_vec());
+            }
+        }
+
+        let started = Instant::now();
+        let rows = sqlx::query_as!(
+            PlantRecord,
+            r#"
+            SELECT id AS "id!", user_id, name, nickname, scientific_name, common_names, care_schedule, image_url, image_hash, created_at, updated_at,
+                archived_at, archive_reason, room, tags, difficulty, family, acquired_at, acquired_from
+            FROM plants
+            WHERE user_id = ? AND archived_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+            user_id,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        metrics_adapter::record_db_query("plants.get_all_by_user", started);
+
+        let plants = repair_repository::skip_corrupt("plants", rows, |row| row.id.clone());
+        if let Some(cache) = cache {
+            record_cache(false);
+            cache.insert_collection(user_id, &plants).await;
+        }
+
+        Ok(plants)
+    }
+
+    /// Find a plant by ID, or by a name when that is unambiguous. A nickname
+    /// wins over a plant's own name, which wins over a scientific name, which
+    /// wins over a common name, so "Monstera deliciosa" finds the plant named
+    /// that way even when another one was identified as the species.
+    pub async fn find(&self, identifier: &str, user_id: &str) -> Result<Plant> {
+        if let Some(plant) = self.get_by_id(identifier, user_id).await? {
+            return Ok(plant);
+        }
+
+        let plants = self.get_all_by_user(user_id).await?;
+        let matches: Vec<(NameMatch, Plant)> = plants
+            .into_iter()
+            .filter_map(|p| p.name_match(identifier).map(|m| (m, p)))
+            .collect();
+        let best = matches.iter().map(|(m, _)| *m).min().ok_or(RequestError::NotFound("Plant"))?;
+        let mut matches: Vec<Plant> = matches
+            .into_iter()
+            .filter(|(m, _)| *m == best)
+            .map(|(_, p)| p)
+            .collect();
+
+        if matches.len() > 1 {
+            let ids: Vec<&str> = matches.iter().map(|p| p.id.as_str()).collect();
+            return Err(RequestError::AmbiguousName {
+                name: identifier.to_string(),
+                ids: ids.join(", "),
+            }
+            .into());
+        }
+
+        Ok(matches.remove(0))
+    }
+
+    /// Plants that have left the collection, most recently archived first
+    pub async fn get_archived_by_user(&self, user_id: &str) -> Result<Vec<Plant>> {
+        let rows = sqlx::query_as!(
+            PlantRecord,
+            r#"
+            SELECT id AS "id!", user_id, name, nickname, scientific_name, common_names, care_schedule, image_url, image_hash, created_at, updated_at,
+                archived_at, archive_reason, room, tags, difficulty, family, acquired_at, acquired_from
+            FROM plants
+            WHERE user_id = ? AND archived_at IS NOT NULL
+            ORDER BY archived_at DESC
+            "#,
+            user_id,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(repair_repository::skip_corrupt("plants", rows, |row| row.id.clone()))
+    }
+
+    pub async fn delete(&self, id: &str, user_id: &str) -> Result<()> {
+        let started = Instant::now();
+        let mut tx = self.db.pool().begin().await?;
+
+        // Snapshot the plant for the audit log before it is gone
+        let row = sqlx::query_as!(
+            PlantRecord,
+            r#"
+            SELECT id AS "id!", user_id, name, nickname, scientific_name, common_names, care_schedule, image_url, image_hash, created_at, updated_at,
+                archived_at, archive_reason, room, tags, difficulty, family, acquired_at, acquired_from
+            FROM plants
+            WHERE id = ? AND user_id = ?
+            "#,
+            id,
+            user_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(row) = row else {
+            return Ok(());
+        };
+        let plant = Plant::try_from(row)?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM plants
+            WHERE id = ? AND user_id = ?
+            "#,
+            id,
+            user_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record(&mut tx, &plant, AuditAction::Deleted).await?;
+        tx.commit().await?;
+        metrics_adapter::record_db_query("plants.delete", started);
+        if let Some(cache) = self.db.plant_cache() {
+            cache.invalidate(&plant).await;
+        }
+
+        Ok(())
+    }
+
+    /// Save a plant. A changed care schedule counts as edited by hand.
+    pub async fn update(&self, plant: &Plant) -> Result<()> {
+        self.save(std::slice::from_ref(plant), &ScheduleSource::Manual, false).await?;
+        Ok(())
+    }
+
+    /// Save a plant whose care schedule was just set from `source`. A new
+    /// version is recorded even when the schedule did not change, as it was
+    /// still renewed.
+    pub async fn update_schedule(&self, plant: &Plant, source: ScheduleSource) -> Result<CareScheduleVersion> {
+        let mut versions = self.save(std::slice::from_ref(plant), &source, true).await?;
+        versions.pop().context("No care schedule version was recorded")
+    }
+
+    /// Save several plants in one transaction: either all are updated or
+    /// none. Each changed care schedule is recorded as a new version from
+    /// `schedule_source`.
+    pub async fn update_many(&self, plants: &[Plant], schedule_source: &ScheduleSource) -> Result<Vec<CareScheduleVersion>> {
+        self.save(plants, schedule_source, false).await
+    }
+
+    /// Hand a plant over to the user now in `plant.user_id`, in one
+    /// transaction. Its reminders follow it, as they belong to the plant,
+    /// and its queued jobs are moved along. With `history_copy`, an archived
+    /// copy left with the previous owner, its diagnosis sessions stay with
+    /// that copy. Returns the number of sessions that moved to the copy.
+    pub async fn transfer(&self, plant: &Plant, previous_owner: &str, history_copy: Option<&Plant>) -> Result<u64> {
+        let started = Instant::now();
+        let mut tx = self.db.pool().begin().await?;
+
+        let updated_at = plant.updated_at.to_rfc3339();
+        let moved = sqlx::query!(
+            "UPDATE plants SET user_id = ?, updated_at = ? WHERE id = ? AND user_id = ?",
+            plant.user_id,
+            updated_at,
+            plant.id,
+            previous_owner,
+        )
+        .execute(&mut *tx)
+        .await?;
+        if moved.rows_affected() == 0 {
+            return Err(RequestError::NotFound("Plant").into());
+        }
+        record(&mut tx, plant, AuditAction::Updated).await?;
+
+        sqlx::query!("UPDATE jobs SET user_id = ? WHERE plant_id = ?", plant.user_id, plant.id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut detached = 0;
+        if let Some(copy) = history_copy {
+            insert(&mut tx, copy, ScheduleSource::Manual).await?;
+            detached = sqlx::query!("UPDATE diagnosis_sessions SET plant_id = ? WHERE plant_id = ?", copy.id, plant.id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        tx.commit().await?;
+        metrics_adapter::record_db_query("plants.transfer", started);
+        // Both owners' collections changed
+        if let Some(cache) = self.db.plant_cache() {
+            cache.invalidate_all();
+        }
+
+        Ok(detached)
+    }
+
+    /// Put a plant back the way it was in `snapshot`, all or nothing: the
+    /// plant, its attributes, care overrides and reminders as they were, and the history
+    /// records gone since. History added since is kept. Returns whether the
+    /// plant had been deleted and was created again, and the number of
+    /// history records put back.
+    pub async fn restore_snapshot(&self, snapshot: &PlantSnapshotDto) -> Result<(bool, usize)> {
+        let started = Instant::now();
+        let plant = &snapshot.plant;
+        let mut tx = self.db.pool().begin().await?;
+
+        let exists = sqlx::query_scalar!("SELECT id FROM plants WHERE id = ? AND user_id = ?", plant.id, plant.user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+        if exists {
+            update(&mut tx, std::slice::from_ref(plant), &ScheduleSource::Imported, false).await?;
+        } else {
+            insert(&mut tx, plant, ScheduleSource::Imported).await?;
+        }
+        let care = snapshot
+            .care_overrides
+            .clone()
+            .unwrap_or_else(|| CareOverrides::new(&plant.id, plant.care_schedule.clone()));
+        care_override_repository::save(&mut tx, &care).await?;
+
+        // Attributes and care tasks are state: set since the snapshot means changed
+        let keys = sqlx::query_scalar!(r#"SELECT key AS "key!" FROM plant_attributes WHERE plant_id = ?"#, plant.id)
+            .fetch_all(&mut *tx)
+            .await?;
+        for key in keys.iter().filter(|key| !snapshot.attributes.iter().any(|a| a.key == **key)) {
+            attribute_repository::delete(&mut tx, &plant.id, key).await?;
+        }
+        for attribute in &snapshot.attributes {
+            attribute_repository::save(&mut tx, attribute).await?;
+        }
+        let tasks = sqlx::query!(r#"SELECT id AS "id!", kind AS "kind!" FROM care_tasks WHERE plant_id = ?"#, plant.id)
+            .fetch_all(&mut *tx)
+            .await?;
+        for task in tasks.into_iter().filter(|task| !snapshot.care_tasks.iter().any(|t| t.id == task.id)) {
+            let kind = CareTaskKind::from_str(&task.kind)
+                .ok_or_else(|| anyhow::anyhow!("Unknown care task kind: {}", task.kind))?;
+            let task = CareTask {
+                id: task.id,
+                plant_id: plant.id.clone(),
+                kind,
+                last_done_at: None,
+            };
+            care_task_repository::save(&mut tx, &task).await?;
+        }
+        for task in &snapshot.care_tasks {
+            care_task_repository::save(&mut tx, task).await?;
+        }
+        if let Some(quarantine) = &snapshot.quarantine {
+            quarantine_repository::save(&mut tx, quarantine).await?;
+        }
+        for inspection in &snapshot.inspections {
+            inspection_repository::save(&mut tx, inspection).await?;
+        }
+
+        let mut restored = 0;
+        let expenses: HashSet<String> =
+            sqlx::query_scalar!(r#"SELECT id AS "id!" FROM expenses WHERE plant_id = ?"#, plant.id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .collect();
+        for expense in snapshot.expenses.iter().filter(|e| !expenses.contains(&e.id)) {
+            expense_repository::insert(&mut tx, expense).await?;
+            restored += 1;
+        }
+        let diagnoses: HashSet<String> =
+            sqlx::query_scalar!(r#"SELECT id AS "id!" FROM diagnosis_sessions WHERE plant_id = ?"#, plant.id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .collect();
+        for session in snapshot.diagnoses.iter().filter(|s| !diagnoses.contains(&s.id)) {
+            diagnosis_repository::insert(&mut tx, session).await?;
+            for message in snapshot.diagnosis_messages.iter().filter(|m| m.session_id == session.id) {
+                diagnosis_repository::insert_message(&mut tx, session, message).await?;
+            }
+            restored += 1;
+        }
+        let journal: HashSet<String> =
+            sqlx::query_scalar!(r#"SELECT id AS "id!" FROM journal_entries WHERE plant_id = ?"#, plant.id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .collect();
+        for entry in snapshot.journal.iter().filter(|e| !journal.contains(&e.id)) {
+            journal_repository::insert(&mut tx, entry).await?;
+            restored += 1;
+        }
+        let readings: HashSet<String> =
+            sqlx::query_scalar!(r#"SELECT id AS "id!" FROM moisture_readings WHERE plant_id = ?"#, plant.id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .collect();
+        for reading in snapshot.moisture_readings.iter().filter(|r| !readings.contains(&r.id)) {
+            moisture_repository::insert(&mut tx, reading).await?;
+            restored += 1;
+        }
+        let chats: HashSet<String> =
+            sqlx::query_scalar!(r#"SELECT id AS "id!" FROM chat_sessions WHERE plant_id = ?"#, plant.id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .collect();
+        for chat in snapshot.chats.iter().filter(|c| !chats.contains(&c.id)) {
+            chat_repository::insert(&mut tx, chat).await?;
+            restored += 1;
+        }
+
+        tx.commit().await?;
+        metrics_adapter::record_db_query("plants.restore_snapshot", started);
+        if let Some(cache) = self.db.plant_cache() {
+            cache.invalidate(plant).await;
+        }
+
+        Ok((!exists, restored))
+    }
+
+    async fn save(
+        &self,
+        plants: &[Plant],
+        schedule_source: &ScheduleSource,
+        always_version: bool,
+    ) -> Result<Vec<CareScheduleVersion>> {
+        let started = Instant::now();
+        let mut tx = self.db.pool().begin().await?;
+        let versions = update(&mut tx, plants, schedule_source, always_version).await?;
+        tx.commit().await?;
+        metrics_adapter::record_db_query("plants.update", started);
+        if let Some(cache) = self.db.plant_cache() {
+            for plant in plants {
+                cache.invalidate(plant).await;
+            }
+        }
+
+        Ok(versions)
+    }
+}
+
+/// Update plant rows on `conn`, with their audit events and, when the care
+/// schedule changed (or `always_version`), a new schedule version
+async fn update(
+    conn: &mut SqliteConnection,
+    plants: &[Plant],
+    schedule_source: &ScheduleSource,
+    always_version: bool,
+) -> Result<Vec<CareScheduleVersion>> {
+    let mut versions = Vec::new();
+
+    for plant in plants {
+        let stored = sqlx::query_scalar!("SELECT care_schedule FROM plants WHERE id = ?", plant.id)
+            .fetch_optional(&mut *conn)
+            .await?;
+        // An unreadable stored schedule is replaced like any other
+        let previous: Option<CareSchedule> = stored.and_then(|s| serde_json::from_str(&s).ok());
+        let changed = |schedule: &CareSchedule| {
+            previous
+                .as_ref()
+                .is_none_or(|previous| !previous.changes_to(schedule).is_empty())
+        };
+        // The owner's changes are laid over a renewed schedule
+        let mut plant = plant.clone();
+        if changed(&plant.care_schedule) || always_version {
+            plant.care_schedule = care_override_repository::merge(
+                &mut *conn,
+                &plant.id,
+                previous.as_ref(),
+                &plant.care_schedule,
+                schedule_source,
+            )
+            .await?;
+        }
+        let schedule_changed = changed(&plant.care_schedule);
+
+        let care_schedule_json = serde_json::to_string(&plant.care_schedule)?;
+        let common_names = serde_json::to_string(&plant.common_names)?;
+        let updated_at = plant.updated_at.to_rfc3339();
+        let archived_at = plant.archived.as_ref().map(|a| a.archived_at.to_rfc3339());
+        let archive_reason = plant.archived.as_ref().map(|a| a.reason.as_str());
+        let tags = serde_json::to_string(&plant.tags)?;
+        let difficulty = plant.difficulty.as_ref().map(serde_json::to_string).transpose()?;
+        let acquired_at = plant.acquired_at.map(|at| at.to_rfc3339());
+
+        sqlx::query!(
+            r#"
+            UPDATE plants
+            SET name = ?, nickname = ?, scientific_name = ?, common_names = ?, care_schedule = ?, image_url = ?,
+                image_hash = ?, updated_at = ?, archived_at = ?, archive_reason = ?, room = ?, tags = ?, difficulty = ?,
+                family = ?, acquired_at = ?, acquired_from = ?
+            WHERE id = ?
+            "#,
+            plant.name,
+            plant.nickname,
+            plant.scientific_name,
+            common_names,
+            care_schedule_json,
+            plant.image_url,
+            plant.image_hash,
+            updated_at,
+            archived_at,
+            archive_reason,
+            plant.room,
+            tags,
+            difficulty,
+            plant.family,
+            acquired_at,
+            plant.acquired_from,
+            plant.id,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        record(&mut *conn, &plant, AuditAction::Updated).await?;
+        if schedule_changed || always_version {
+            versions.push(
+                schedule_version_repository::record(
+                    &mut *conn,
+                    &plant.id,
+                    previous.as_ref(),
+                    &plant.care_schedule,
+                    schedule_source.clone(),
+                )
+                .await?,
+            );
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Count a plant read answered by the cache (`hit`) or the database
+fn record_cache(hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    metrics::counter!(metrics_adapter::PLANT_CACHE, "result" => result).increment(1);
+}
+
+/// Insert a plant row, with its audit event and first schedule version
+async fn insert(conn: &mut SqliteConnection, plant: &Plant, source: ScheduleSource) -> Result<()> {
+    let care_schedule_json = serde_json::to_string(&plant.care_schedule)?;
+    let common_names = serde_json::to_string(&plant.common_names)?;
+    let created_at = plant.created_at.to_rfc3339();
+    let updated_at = plant.updated_at.to_rfc3339();
+    let archived_at = plant.archived.as_ref().map(|a| a.archived_at.to_rfc3339());
+    let archive_reason = plant.archived.as_ref().map(|a| a.reason.as_str());
+    let tags = serde_json::to_string(&plant.tags)?;
+    let difficulty = plant.difficulty.as_ref().map(serde_json::to_string).transpose()?;
+    let acquired_at = plant.acquired_at.map(|at| at.to_rfc3339());
+
+    sqlx::query!(
+        r#"
+            INSERT INTO plants (id, user_id, name, nickname, scientific_name, common_names, care_schedule, image_url, image_hash, created_at, updated_at, archived_at, archive_reason, room, tags, difficulty, family, acquired_at, acquired_from)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        plant.id,
+        plant.user_id,
+        plant.name,
+        plant.nickname,
+        plant.scientific_name,
+        common_names,
+        care_schedule_json,
+        plant.image_url,
+        plant.image_hash,
+        created_at,
+        updated_at,
+        archived_at,
+        archive_reason,
+        plant.room,
+        tags,
+        difficulty,
+        plant.family,
+        acquired_at,
+        plant.acquired_from,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    record(&mut *conn, plant, AuditAction::Created).await?;
+    schedule_version_repository::record(&mut *conn, &plant.id, None, &plant.care_schedule, source).await?;
+
+    Ok(())
+}
+
+async fn record(conn: &mut SqliteConnection, plant: &Plant, action: AuditAction) -> Result<()> {
+    let event = AuditEvent::new(AuditEntity::Plant, &plant.id, Some(&plant.id), action, plant)?;
+    event_repository::record(conn, &event).await
+}
+
+impl TryFrom<PlantRecord> for Plant {
+    type Error = anyhow::Error;
+
+    fn try_from(record: PlantRecord) -> Result<Self> {
+        let archived = match (record.archived_at, record.archive_reason) {
+            (Some(archived_at), Some(reason)) => Some(PlantArchive {
+                reason: ArchiveReason::from_str(&reason)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown archive reason: {}", reason))?,
+                archived_at: DateTime::parse_from_rfc3339(&archived_at)
+                    .context("archived_at is not a valid date")?
+                    .with_timezone(&Utc),
+            }),
+            _ => None,
+        };
+
+        Ok(Plant {
+            id: record.id,
+            user_id: record.user_id,
+            name: record.name,
+            nickname: record.nickname,
+            scientific_name: record.scientific_name,
+            common_names: serde_json::from_str(&record.common_names)
+                .context("common_names is not a list of names")?,
+            care_schedule: serde_json::from_str(&record.care_schedule)
+                .context("care_schedule is not a valid care schedule")?,
+            image_url: record.image_url,
+            image_hash: record.image_hash,
+            created_at: DateTime::parse_from_rfc3339(&record.created_at)
+                .context("created_at is not a valid date")?
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&record.updated_at)
+                .context("updated_at is not a valid date")?
+                .with_timezone(&Utc),
+            archived,
+            room: record.room,
+            tags: serde_json::from_str(&record.tags).context("tags is not a list of tags")?,
+            difficulty: record
+                .difficulty
+                .map(|d| serde_json::from_str(&d))
+                .transpose()
+                .context("difficulty is not a valid rating")?,
+            family: record.family,
+            acquired_at: record
+                .acquired_at
+                .map(|at| DateTime::parse_from_rfc3339(&at).map(|at| at.with_timezone(&Utc)))
+                .transpose()
+                .context("acquired_at is not a valid date")?,
+            acquired_from: record.acquired_from,
+        })
+    }
+}
+
+impl PlantRecord {
+    /// Reset the fields that cannot be read to defaults, returning their
+    /// names. Unreadable archive details bring the plant back into the
+    /// collection.
+    fn reset_unreadable(&mut self) -> Result<Vec<&'static str>> {
+        let mut reset = Vec::new();
+        let now = Utc::now().to_rfc3339();
+
+        if serde_json::from_str::<CareSchedule>(&self.care_schedule).is_err() {
+            self.care_schedule = serde_json::to_string(&CareSchedule::default())?;
+            reset.push("care_schedule");
+        }
+        if DateTime::parse_from_rfc3339(&self.created_at).is_err() {
+            self.created_at = now.clone();
+            reset.push("created_at");
+        }
+        if DateTime::parse_from_rfc3339(&self.updated_at).is_err() {
+            self.updated_at = now;
+            reset.push("updated_at");
+        }
+        if self
+            .acquired_at
+            .as_ref()
+            .is_some_and(|at| DateTime::parse_from_rfc3339(at).is_err())
+        {
+            self.acquired_at = None;
+            reset.push("acquired_at");
+        }
+        if let (Some(archived_at), Some(reason)) = (&self.archived_at, &self.archive_reason) {
+            if DateTime::parse_from_rfc3339(archived_at).is_err() || ArchiveReason::from_str(reason).is_none() {
+                self.archived_at = None;
+                self.archive_reason = None;
+                reset.push("archived");
+            }
+        }
+        if serde_json::from_str::<Vec<String>>(&self.common_names).is_err() {
+            self.common_names = "[]".to_string();
+            reset.push("common_names");
+        }
+        if serde_json::from_str::<Vec<String>>(&self.tags).is_err() {
+            self.tags = "[]".to_string();
+            reset.push("tags");
+        }
+        if let Some(difficulty) = &self.difficulty {
+            if serde_json::from_str::<CareDifficulty>(difficulty).is_err() {
+                self.difficulty = None;
+                reset.push("difficulty");
+            }
+        }
+
+        Ok(reset)
+    }
+}
+
+/// Check every plant, quarantining the unreadable ones and resetting their
+/// bad fields so they load again. Returns how many plants were checked.
+/// Plants are repaired in place rather than removed, since removing one
+/// would also remove its diagnoses, tasks and journal.
+pub(crate) async fn repair(conn: &mut SqliteConnection) -> Result<(usize, Vec<QuarantinedRow>)> {
+    let rows = sqlx::query_as!(
+        PlantRecord,
+        r#"
+        SELECT id AS "id!", user_id, name, nickname, scientific_name, common_names, care_schedule, image_url, image_hash, created_at, updated_at,
+            archived_at, archive_reason, room, tags, difficulty, family, acquired_at, acquired_from
+        FROM plants
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let checked = rows.len();
+    let mut quarantined = Vec::new();
+    for mut row in rows {
+        let Err(error) = Plant::try_from(row.clone()) else {
+            continue;
+        };
+        let error = format!("{:#}", error);
+        repair_repository::quarantine(conn, "plants", &row.id, &row, &error).await?;
+
+        let reset = row.reset_unreadable()?;
+        sqlx::query!(
+            r#"
+            UPDATE plants
+            SET care_schedule = ?, common_names = ?, created_at = ?, updated_at = ?, archived_at = ?,
+                archive_reason = ?, tags = ?, difficulty = ?, acquired_at = ?
+            WHERE id = ?
+            "#,
+            row.care_schedule,
+            row.common_names,
+            row.created_at,
+            row.updated_at,
+            row.archived_at,
+            row.archive_reason,
+            row.tags,
+            row.difficulty,
+            row.acquired_at,
+            row.id,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        log::info!(
+            target: "plant_care::db",
+            table = "plants",
+            id = row.id.as_str();
+            "Repaired plant {}: {}",
+            row.id,
+            error
+        );
+        quarantined.push(QuarantinedRow {
+            table: "plants",
+            row_id: row.id,
+            error,
+            repair: Repair::Reset(reset),
+        });
+    }
+
+    Ok((checked, quarantined))
+}
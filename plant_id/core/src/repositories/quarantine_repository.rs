@@ -0,0 +1,186 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, Quarantine};
+use crate::repositories::event_repository;
+
+/// Columns selected for every quarantine query
+const QUARANTINE_COLUMNS: &str =
+    "q.id, q.plant_id, q.reason, q.started_at, q.last_inspected_at, q.released_at, q.release_diagnosis_id";
+
+#[derive(Clone)]
+pub struct QuarantineRepository {
+    db: Database,
+}
+
+impl QuarantineRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Quarantines of a user's plants that have not been released, oldest first
+    pub async fn get_active_by_user(&self, user_id: &str) -> Result<Vec<Quarantine>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM plant_quarantines q
+            JOIN plants p ON p.id = q.plant_id
+            WHERE p.user_id = ? AND q.released_at IS NULL
+            ORDER BY q.started_at
+            "#,
+            QUARANTINE_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(quarantine_from_row).collect()
+    }
+
+    /// The plant's quarantine, if it is quarantined
+    pub async fn get_active_by_plant_id(&self, plant_id: &str) -> Result<Option<Quarantine>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM plant_quarantines q
+            WHERE q.plant_id = ? AND q.released_at IS NULL
+            "#,
+            QUARANTINE_COLUMNS
+        ))
+        .bind(plant_id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.as_ref().map(quarantine_from_row).transpose()
+    }
+
+    /// Start a quarantine, or save an inspection or release
+    pub async fn save(&self, quarantine: &Quarantine) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+        save(&mut tx, quarantine).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Save a quarantine on `conn`, with its audit event
+pub(crate) async fn save(conn: &mut SqliteConnection, quarantine: &Quarantine) -> Result<()> {
+    let existed = sqlx::query("SELECT 1 FROM plant_quarantines WHERE id = ?")
+        .bind(&quarantine.id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .is_some();
+
+    upsert(&mut *conn, quarantine).await?;
+
+    let action = if existed {
+        AuditAction::Updated
+    } else {
+        AuditAction::Created
+    };
+    let event = AuditEvent::new(
+        AuditEntity::Quarantine,
+        &quarantine.id,
+        Some(&quarantine.plant_id),
+        action,
+        quarantine,
+    )?;
+    event_repository::record(&mut *conn, &event).await?;
+
+    Ok(())
+}
+
+/// Insert a quarantine, or update the inspection and release of a known one
+pub(crate) async fn upsert(conn: &mut SqliteConnection, quarantine: &Quarantine) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO plant_quarantines
+            (id, plant_id, reason, started_at, last_inspected_at, released_at, release_diagnosis_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET
+            last_inspected_at = excluded.last_inspected_at,
+            released_at = excluded.released_at,
+            release_diagnosis_id = excluded.release_diagnosis_id
+        "#,
+    )
+    .bind(&quarantine.id)
+    .bind(&quarantine.plant_id)
+    .bind(&quarantine.reason)
+    .bind(quarantine.started_at.to_rfc3339())
+    .bind(quarantine.last_inspected_at.map(|at| at.to_rfc3339()))
+    .bind(quarantine.released_at.map(|at| at.to_rfc3339()))
+    .bind(&quarantine.release_diagnosis_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+fn quarantine_from_row(row: &SqliteRow) -> Result<Quarantine> {
+    let parse = |column: &str| -> Result<Option<DateTime<Utc>>> {
+        let value: Option<String> = row.get(column);
+        Ok(match value {
+            Some(value) => Some(DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc)),
+            None => None,
+        })
+    };
+    let started_at: String = row.get("started_at");
+
+    Ok(Quarantine {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        reason: row.get("reason"),
+        started_at: DateTime::parse_from_rfc3339(&started_at)?.with_timezone(&Utc),
+        last_inspected_at: parse("last_inspected_at")?,
+        released_at: parse("released_at")?,
+        release_diagnosis_id: row.get("release_diagnosis_id"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CareSchedule, DiagnosisSession, DiagnosisState, Plant};
+    use crate::repositories::{DiagnosisRepository, JournalRepository, PlantRepository};
+    use crate::services::QuarantineService;
+
+    #[tokio::test]
+    async fn test_quarantine_until_clean_diagnosis() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let diagnoses = DiagnosisRepository::new(db.clone());
+        let quarantines = QuarantineRepository::new(db.clone());
+        let service = QuarantineService::new(
+            plants.clone(),
+            diagnoses.clone(),
+            JournalRepository::new(db),
+            quarantines.clone(),
+        );
+
+        let plant = Plant::new("u".to_string(), "Calathea".to_string(), CareSchedule::default());
+        plants.create(&plant).await.unwrap();
+        service.quarantine(&plant.id, "u", Some("spider mites".to_string())).await.unwrap();
+        assert!(service.quarantine(&plant.id, "u", None).await.is_err());
+        assert_eq!(service.get_active("u").await.unwrap().len(), 1);
+        assert!(service.get_active("someone else").await.unwrap().is_empty());
+
+        // Not diagnosed since
+        assert!(service.release(&plant.id, "u").await.is_err());
+
+        let mut diagnosis = DiagnosisSession::new(plant.id.clone(), "Follow-up".to_string());
+        diagnosis.state = DiagnosisState::Concluded;
+        diagnosis.diagnosis_context["result"] =
+            serde_json::json!({"finding": "No pests left", "severity": "info"});
+        diagnoses.create(&diagnosis).await.unwrap();
+
+        let released = service.release(&plant.id, "u").await.unwrap();
+        assert_eq!(released.release_diagnosis_id.as_deref(), Some(diagnosis.id.as_str()));
+        assert!(quarantines.get_active_by_plant_id(&plant.id).await.unwrap().is_none());
+        assert!(service.release(&plant.id, "u").await.is_err());
+    }
+}
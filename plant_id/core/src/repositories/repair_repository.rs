@@ -0,0 +1,174 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::sqlite::SqliteConnection;
+use uuid::Uuid;
+
+use crate::config::Database;
+use crate::repositories::{diagnosis_repository, plant_repository};
+
+/// What `db repair` did with an unreadable row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Repair {
+    /// The named fields were reset to defaults
+    Reset(Vec<&'static str>),
+    /// The row was removed
+    Removed,
+}
+
+/// An unreadable row, copied to the quarantined_rows table as it was
+#[derive(Debug, Clone, Serialize)]
+pub struct QuarantinedRow {
+    pub table: &'static str,
+    pub row_id: String,
+    /// Why the row could not be read
+    pub error: String,
+    pub repair: Repair,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepairReport {
+    pub plants_checked: usize,
+    pub sessions_checked: usize,
+    pub quarantined: Vec<QuarantinedRow>,
+}
+
+#[derive(Clone)]
+pub struct RepairRepository {
+    db: Database,
+}
+
+impl RepairRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Re-validate every plant and diagnosis session, quarantining the
+    /// rows that cannot be read. Repairs are local fixes and are not
+    /// recorded in the audit log, so they never sync to other devices.
+    pub async fn repair(&self) -> Result<RepairReport> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let (plants_checked, mut quarantined) = plant_repository::repair(&mut tx).await?;
+        let (sessions_checked, sessions) = diagnosis_repository::repair(&mut tx).await?;
+        quarantined.extend(sessions);
+
+        tx.commit().await?;
+
+        Ok(RepairReport {
+            plants_checked,
+            sessions_checked,
+            quarantined,
+        })
+    }
+}
+
+/// Keep a copy of an unreadable row before it is reset or removed
+pub(crate) async fn quarantine(
+    conn: &mut SqliteConnection,
+    table: &str,
+    row_id: &str,
+    data: &impl Serialize,
+    error: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO quarantined_rows (id, table_name, row_id, data, error, quarantined_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(table)
+    .bind(row_id)
+    .bind(serde_json::to_string(data)?)
+    .bind(error)
+    .bind(Utc::now().to_rfc3339())
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Read the rows of a listing, leaving out the ones that cannot be read
+/// instead of failing the whole listing. Each is logged with its ID.
+pub(crate) fn skip_corrupt<R, T>(table: &'static str, rows: Vec<R>, id: impl Fn(&R) -> String) -> Vec<T>
+where
+    T: TryFrom<R, Error = anyhow::Error>,
+{
+    rows.into_iter()
+        .filter_map(|row| {
+            let row_id = id(&row);
+            T::try_from(row)
+                .map_err(|e| {
+                    log::warn!(
+                        target: "plant_care::db",
+                        table,
+                        id = row_id.as_str();
+                        "Skipping corrupt row {} of {}: {:#}; run `plant-care db repair`",
+                        row_id,
+                        table,
+                        e
+                    );
+                })
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CareSchedule, DiagnosisSession, Plant};
+    use crate::repositories::{DiagnosisRepository, PlantRepository};
+
+    #[tokio::test]
+    async fn test_corrupt_rows_are_skipped_then_repaired() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let diagnoses = DiagnosisRepository::new(db.clone());
+
+        let good = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        let bad = Plant::new("u".to_string(), "Pothos".to_string(), CareSchedule::default());
+        plants.create(&good).await.unwrap();
+        plants.create(&bad).await.unwrap();
+        let session = DiagnosisSession::new(bad.id.clone(), "yellow leaves".to_string());
+        diagnoses.create(&session).await.unwrap();
+
+        sqlx::query("UPDATE plants SET care_schedule = '{\"water\":' WHERE id = ?")
+            .bind(&bad.id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        sqlx::query("UPDATE diagnosis_sessions SET diagnosis_context = 'not json' WHERE id = ?")
+            .bind(&session.id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        let listed = plants.get_all_by_user("u").await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, good.id);
+        assert!(diagnoses.get_summaries_by_plant_id(&bad.id, 0, 10).await.unwrap().is_empty());
+        assert!(plants.get_by_id(&bad.id, "u").await.is_err());
+
+        let report = RepairRepository::new(db.clone()).repair().await.unwrap();
+        assert_eq!((report.plants_checked, report.sessions_checked), (2, 1));
+        assert_eq!(report.quarantined.len(), 2);
+        assert_eq!(report.quarantined[0].row_id, bad.id);
+        assert_eq!(report.quarantined[0].repair, Repair::Reset(vec!["care_schedule"]));
+        assert_eq!(report.quarantined[1].repair, Repair::Removed);
+
+        assert_eq!(plants.get_all_by_user("u").await.unwrap().len(), 2);
+        assert!(diagnoses.get_by_id(&session.id).await.unwrap().is_none());
+        let kept: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM quarantined_rows")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(kept, 2);
+
+        let again = RepairRepository::new(db).repair().await.unwrap();
+        assert!(again.quarantined.is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::room::room_id;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, Environment, Plant, Room, RoomProfile, WindowOrientation};
+use crate::repositories::event_repository;
+
+/// Columns selected for every room query
+const ROOM_COLUMNS: &str = "id, user_id, name, window, sun_hours, temperature, humidity, drafty, heated, updated_at";
+
+#[derive(Clone)]
+pub struct RoomRepository {
+    db: Database,
+}
+
+impl RoomRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// A user's rooms, by name
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<Room>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM rooms
+            WHERE user_id = ?
+            ORDER BY name COLLATE NOCASE
+            "#,
+            ROOM_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(room_from_row).collect()
+    }
+
+    /// A user's room by name (case-insensitive)
+    pub async fn get_by_name(&self, name: &str, user_id: &str) -> Result<Option<Room>> {
+        let row = sqlx::query(&format!("SELECT {} FROM rooms WHERE id = ?", ROOM_COLUMNS))
+            .bind(room_id(user_id, name))
+            .fetch_optional(self.db.pool())
+            .await?;
+
+        row.as_ref().map(room_from_row).transpose()
+    }
+
+    /// The profile of a plant's room, for the AI; None when the plant has
+    /// no room
+    pub async fn profile_for(&self, plant: &Plant) -> Result<Option<RoomProfile>> {
+        let Some(name) = plant.room.as_deref() else {
+            return Ok(None);
+        };
+        let room = self.get_by_name(name, &plant.user_id).await?;
+        Ok(Some(RoomProfile::of(name, room.as_ref())))
+    }
+
+    /// Insert or update a room
+    pub async fn save(&self, room: &Room) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let existed = sqlx::query("SELECT 1 FROM rooms WHERE id = ?")
+            .bind(&room.id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+
+        sqlx::query(
+            r#"
+            INSERT INTO rooms (id, user_id, name, window, sun_hours, temperature, humidity, drafty, heated, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name, window = excluded.window,
+                sun_hours = excluded.sun_hours, temperature = excluded.temperature,
+                humidity = excluded.humidity, drafty = excluded.drafty,
+                heated = excluded.heated, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&room.id)
+        .bind(&room.user_id)
+        .bind(&room.name)
+        .bind(room.window.map(|w| w.as_str()))
+        .bind(room.sun_hours)
+        .bind(room.environment.temperature)
+        .bind(room.environment.humidity)
+        .bind(room.environment.drafty)
+        .bind(room.environment.heated)
+        .bind(room.updated_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        let action = if existed {
+            AuditAction::Updated
+        } else {
+            AuditAction::Created
+        };
+        record(&mut tx, room, action).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Forget a room's light. Returns false if the user has no room by that
+    /// name; plants in it keep their room.
+    pub async fn delete(&self, name: &str, user_id: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let row = sqlx::query(&format!("SELECT {} FROM rooms WHERE id = ?", ROOM_COLUMNS))
+            .bind(room_id(user_id, name))
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(row) = row else {
+            return Ok(false);
+        };
+        let room = room_from_row(&row)?;
+
+        sqlx::query("DELETE FROM rooms WHERE id = ?")
+            .bind(&room.id)
+            .execute(&mut *tx)
+            .await?;
+
+        record(&mut tx, &room, AuditAction::Deleted).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+}
+
+async fn record(conn: &mut SqliteConnection, room: &Room, action: AuditAction) -> Result<()> {
+    let event = AuditEvent::new(AuditEntity::Room, &room.id, None, action, room)?;
+    event_repository::record(conn, &event).await
+}
+
+fn room_from_row(row: &SqliteRow) -> Result<Room> {
+    let window: Option<String> = row.get("window");
+    let updated_at: String = row.get("updated_at");
+
+    Ok(Room {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        window: window
+            .map(|w| {
+                WindowOrientation::from_str(&w)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown window orientation: {}", w))
+            })
+            .transpose()?,
+        sun_hours: row.get("sun_hours"),
+        environment: Environment {
+            temperature: row.get("temperature"),
+            humidity: row.get("humidity"),
+            drafty: row.get("drafty"),
+            heated: row.get("heated"),
+        },
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+    })
+}
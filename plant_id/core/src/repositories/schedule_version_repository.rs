@@ -0,0 +1,181 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, CareSchedule, CareScheduleVersion, ScheduleSource};
+use crate::repositories::event_repository;
+
+const VERSION_COLUMNS: &str = "id, plant_id, version, schedule, changes, source, created_at";
+
+/// Reads the versions of plants' care schedules. They are written by
+/// `PlantRepository` whenever it saves a changed schedule.
+#[derive(Clone)]
+pub struct ScheduleVersionRepository {
+    db: Database,
+}
+
+impl ScheduleVersionRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Versions of a plant's schedule, newest first
+    pub async fn get_all_by_plant_id(&self, plant_id: &str) -> Result<Vec<CareScheduleVersion>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM care_schedule_versions WHERE plant_id = ? ORDER BY version DESC, created_at DESC",
+            VERSION_COLUMNS
+        ))
+        .bind(plant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(version_from_row).collect()
+    }
+
+    pub async fn get_latest_by_plant_id(&self, plant_id: &str) -> Result<Option<CareScheduleVersion>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM care_schedule_versions WHERE plant_id = ? ORDER BY version DESC, created_at DESC LIMIT 1",
+            VERSION_COLUMNS
+        ))
+        .bind(plant_id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.as_ref().map(version_from_row).transpose()
+    }
+
+    pub async fn get_by_version(&self, plant_id: &str, version: u32) -> Result<Option<CareScheduleVersion>> {
+        // Two devices can number a version the same before they sync; the
+        // later one wins
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM care_schedule_versions WHERE plant_id = ? AND version = ? ORDER BY created_at DESC LIMIT 1",
+            VERSION_COLUMNS
+        ))
+        .bind(plant_id)
+        .bind(version)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.as_ref().map(version_from_row).transpose()
+    }
+}
+
+/// Save a new version of a plant's schedule, numbered after its latest one
+pub(crate) async fn record(
+    conn: &mut SqliteConnection,
+    plant_id: &str,
+    previous: Option<&CareSchedule>,
+    schedule: &CareSchedule,
+    source: ScheduleSource,
+) -> Result<CareScheduleVersion> {
+    let latest: Option<u32> = sqlx::query_scalar("SELECT MAX(version) FROM care_schedule_versions WHERE plant_id = ?")
+        .bind(plant_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    let version = CareScheduleVersion::new(
+        plant_id.to_string(),
+        latest.unwrap_or(0) + 1,
+        previous,
+        schedule.clone(),
+        source,
+    );
+
+    insert(&mut *conn, &version).await?;
+    let event = AuditEvent::new(
+        AuditEntity::ScheduleVersion,
+        &version.id,
+        Some(plant_id),
+        AuditAction::Created,
+        &version,
+    )?;
+    event_repository::record(conn, &event).await?;
+
+    Ok(version)
+}
+
+/// Insert a version as it is, keeping one that was already saved
+pub(crate) async fn insert(conn: &mut SqliteConnection, version: &CareScheduleVersion) -> Result<()> {
+    sqlx::query(&format!(
+        "INSERT OR IGNORE INTO care_schedule_versions ({}) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        VERSION_COLUMNS
+    ))
+    .bind(&version.id)
+    .bind(&version.plant_id)
+    .bind(version.version)
+    .bind(serde_json::to_string(&version.schedule)?)
+    .bind(serde_json::to_string(&version.changes)?)
+    .bind(serde_json::to_string(&version.source)?)
+    .bind(version.created_at.to_rfc3339())
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+fn version_from_row(row: &SqliteRow) -> Result<CareScheduleVersion> {
+    let schedule: String = row.get("schedule");
+    let changes: String = row.get("changes");
+    let source: String = row.get("source");
+    let created_at: String = row.get("created_at");
+
+    Ok(CareScheduleVersion {
+        id: row.get("id"),
+        plant_id: row.get("plant_id"),
+        version: row.get("version"),
+        schedule: serde_json::from_str(&schedule).context("schedule is not a care schedule")?,
+        changes: serde_json::from_str(&changes)?,
+        source: serde_json::from_str(&source).context("source is not a schedule source")?,
+        created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CareSchedule, Plant, ScheduleSource};
+    use crate::repositories::PlantRepository;
+    use crate::services::care_schedule_service;
+
+    #[tokio::test]
+    async fn test_versions_and_rollback() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let versions = ScheduleVersionRepository::new(db);
+
+        let mut plant = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&plant).await.unwrap();
+
+        // Saving the plant without touching the schedule adds no version
+        plants.update(&plant).await.unwrap();
+        plant.care_schedule.water = "Keep the soil moist".to_string();
+        let source = ScheduleSource::Ai {
+            model: "some/model".to_string(),
+        };
+        let second = plants.update_schedule(&plant, source.clone()).await.unwrap();
+        assert_eq!(second.version, 2);
+        assert_eq!(second.changes.len(), 1);
+        assert_eq!(second.changes[0].field, "Water");
+
+        care_schedule_service::rollback_schedule(&plants, &versions, &mut plant, 1).await.unwrap();
+        assert_eq!(plant.care_schedule.water, CareSchedule::default().water);
+        assert!(care_schedule_service::rollback_schedule(&plants, &versions, &mut plant, 9).await.is_err());
+
+        let all = versions.get_all_by_plant_id(&plant.id).await.unwrap();
+        let numbered: Vec<(u32, ScheduleSource)> = all.iter().map(|v| (v.version, v.source.clone())).collect();
+        assert_eq!(
+            numbered,
+            vec![
+                (3, ScheduleSource::Rollback { version: 1 }),
+                (2, source),
+                (1, ScheduleSource::Manual),
+            ]
+        );
+        let latest = versions.get_latest_by_plant_id(&plant.id).await.unwrap().unwrap();
+        assert_eq!(latest.version, 3);
+        let saved = plants.get_by_id(&plant.id, "u").await.unwrap().unwrap();
+        assert_eq!(saved.care_schedule.water, CareSchedule::default().water);
+    }
+}
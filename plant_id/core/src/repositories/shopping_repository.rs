@@ -0,0 +1,120 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, ShoppingEntry};
+use crate::repositories::event_repository;
+
+/// Columns selected for every shopping entry query
+const ENTRY_COLUMNS: &str = "id, user_id, name, removed, updated_at";
+
+#[derive(Clone)]
+pub struct ShoppingRepository {
+    db: Database,
+}
+
+impl ShoppingRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, entry: &ShoppingEntry) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO shopping_entries (id, user_id, name, removed, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&entry.id)
+        .bind(&entry.user_id)
+        .bind(&entry.name)
+        .bind(entry.removed)
+        .bind(entry.updated_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        record(&mut tx, entry, AuditAction::Created).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// A user's entry for an item, ignoring case
+    pub async fn get_by_name(&self, name: &str, user_id: &str) -> Result<Option<ShoppingEntry>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM shopping_entries
+            WHERE user_id = ? AND name = ? COLLATE NOCASE
+            "#,
+            ENTRY_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(|row| entry_from_row(&row)).transpose()
+    }
+
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<ShoppingEntry>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM shopping_entries
+            WHERE user_id = ?
+            ORDER BY name COLLATE NOCASE
+            "#,
+            ENTRY_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(entry_from_row).collect()
+    }
+
+    pub async fn update(&self, entry: &ShoppingEntry) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE shopping_entries
+            SET name = ?, removed = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&entry.name)
+        .bind(entry.removed)
+        .bind(entry.updated_at.to_rfc3339())
+        .bind(&entry.id)
+        .execute(&mut *tx)
+        .await?;
+
+        record(&mut tx, entry, AuditAction::Updated).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+async fn record(conn: &mut SqliteConnection, entry: &ShoppingEntry, action: AuditAction) -> Result<()> {
+    let event = AuditEvent::new(AuditEntity::ShoppingEntry, &entry.id, None, action, entry)?;
+    event_repository::record(conn, &event).await
+}
+
+fn entry_from_row(row: &SqliteRow) -> Result<ShoppingEntry> {
+    let updated_at: String = row.get("updated_at");
+
+    Ok(ShoppingEntry {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        removed: row.get("removed"),
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+    })
+}
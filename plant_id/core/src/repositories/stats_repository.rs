@@ -0,0 +1,235 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::Row;
+
+use crate::config::Database;
+
+/// The queries that grow with the collection, with the SQL they run, for
+/// checking which indexes they use
+const HOT_QUERIES: &[(&str, &str)] = &[
+    (
+        "plants.get_all_by_user",
+        "SELECT id FROM plants WHERE user_id = ? AND archived_at IS NULL ORDER BY created_at DESC",
+    ),
+    (
+        "diagnoses.get_summaries_by_plant_id",
+        "SELECT id FROM diagnosis_sessions WHERE plant_id = ? ORDER BY created_at DESC LIMIT ? OFFSET ?",
+    ),
+    (
+        "diagnoses.get_messages",
+        "SELECT id FROM diagnosis_messages WHERE session_id = ? ORDER BY created_at, rowid",
+    ),
+    (
+        "chats.get_all_by_plant_id",
+        "SELECT id FROM chat_sessions WHERE plant_id = ? ORDER BY created_at DESC",
+    ),
+    (
+        "care_tasks.get_all_by_plant_id",
+        "SELECT id FROM care_tasks WHERE plant_id = ?",
+    ),
+    (
+        "journal.get_all_by_plant_id",
+        "SELECT id FROM journal_entries WHERE plant_id = ?",
+    ),
+    (
+        "moisture.get_all_by_plant_id",
+        "SELECT id FROM moisture_readings WHERE plant_id = ? ORDER BY recorded_at",
+    ),
+    (
+        "expenses.get_all_by_plant_id",
+        "SELECT id FROM expenses WHERE plant_id = ? ORDER BY spent_at",
+    ),
+    (
+        "care_schedule_versions.get_all_by_plant_id",
+        "SELECT id FROM care_schedule_versions WHERE plant_id = ? ORDER BY version DESC",
+    ),
+    (
+        "identification_disagreements.get_by_plant_id",
+        "SELECT id FROM identification_disagreements WHERE plant_id = ? ORDER BY created_at DESC LIMIT 1",
+    ),
+    (
+        "plant_quarantines.get_active_by_plant_id",
+        "SELECT id FROM plant_quarantines WHERE plant_id = ? AND released_at IS NULL",
+    ),
+    (
+        "events.get_by_plant_id",
+        "SELECT id FROM events WHERE plant_id = ? ORDER BY created_at, rowid",
+    ),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseStats {
+    /// Size of the database file, in bytes
+    pub file_bytes: i64,
+    /// Tables, largest first
+    pub tables: Vec<TableStats>,
+    pub queries: Vec<QueryPlan>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableStats {
+    pub name: String,
+    pub rows: i64,
+    /// Space taken by the table's own pages, in bytes
+    pub bytes: i64,
+    pub indexes: Vec<IndexStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStats {
+    pub name: String,
+    pub bytes: i64,
+    /// Hot queries whose plan uses the index
+    pub used_by: Vec<String>,
+}
+
+/// How SQLite runs one of the hot queries
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryPlan {
+    pub query: String,
+    /// Index the query searches, None when it scans the whole table
+    pub index: Option<String>,
+    pub detail: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct StatsRepository {
+    db: Database,
+}
+
+impl StatsRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn stats(&self) -> Result<DatabaseStats> {
+        let queries = self.query_plans().await?;
+
+        let page_count: i64 = sqlx::query_scalar("PRAGMA page_count").fetch_one(self.db.pool()).await?;
+        let page_size: i64 = sqlx::query_scalar("PRAGMA page_size").fetch_one(self.db.pool()).await?;
+
+        let objects = sqlx::query(
+            r#"
+            SELECT m.type, m.name, m.tbl_name, COALESCE(SUM(s.pgsize), 0) AS bytes
+            FROM sqlite_master m
+            LEFT JOIN dbstat s ON s.name = m.name
+            WHERE m.type IN ('table', 'index') AND m.name NOT LIKE 'sqlite_%'
+            GROUP BY m.name
+            ORDER BY m.tbl_name, m.type DESC, m.name
+            "#,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut tables: Vec<TableStats> = Vec::new();
+        for object in &objects {
+            let kind: String = object.get("type");
+            let name: String = object.get("name");
+            let bytes: i64 = object.get("bytes");
+
+            if kind == "table" {
+                // Table names come from the schema, not user input
+                let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM \"{}\"", name))
+                    .fetch_one(self.db.pool())
+                    .await?;
+                tables.push(TableStats {
+                    name,
+                    rows,
+                    bytes,
+                    indexes: Vec::new(),
+                });
+            } else {
+                let table: String = object.get("tbl_name");
+                let used_by = queries
+                    .iter()
+                    .filter(|q| q.index.as_deref() == Some(name.as_str()))
+                    .map(|q| q.query.clone())
+                    .collect();
+                if let Some(owner) = tables.iter_mut().find(|t| t.name == table) {
+                    owner.indexes.push(IndexStats { name, bytes, used_by });
+                }
+            }
+        }
+        tables.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(DatabaseStats {
+            file_bytes: page_count * page_size,
+            tables,
+            queries,
+        })
+    }
+
+    /// The statements creating the tables and indexes, in a stable order
+    pub async fn schema(&self) -> Result<Vec<String>> {
+        let statements = sqlx::query_scalar(
+            r#"
+            SELECT sql FROM sqlite_master
+            WHERE type IN ('table', 'index') AND sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+            ORDER BY tbl_name, type DESC, name
+            "#,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(statements)
+    }
+
+    /// The plans SQLite picks for the hot queries
+    async fn query_plans(&self) -> Result<Vec<QueryPlan>> {
+        let mut plans = Vec::new();
+        for (query, sql) in HOT_QUERIES {
+            let rows = sqlx::query(&format!("EXPLAIN QUERY PLAN {}", sql))
+                .fetch_all(self.db.pool())
+                .await?;
+            let detail: Vec<String> = rows.iter().map(|row| row.get("detail")).collect();
+
+            plans.push(QueryPlan {
+                query: query.to_string(),
+                index: detail.iter().find_map(|d| index_in_plan(d)).map(str::to_string),
+                detail,
+            });
+        }
+        Ok(plans)
+    }
+}
+
+/// The index a query plan step searches, e.g. `idx_plants_user_id` in
+/// "SEARCH plants USING INDEX idx_plants_user_id (user_id=?)"
+fn index_in_plan(detail: &str) -> Option<&str> {
+    let (_, rest) = detail
+        .split_once("USING COVERING INDEX ")
+        .or_else(|| detail.split_once("USING INDEX "))?;
+    rest.split_whitespace().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_in_plan() {
+        assert_eq!(
+            index_in_plan("SEARCH plants USING INDEX idx_plants_user_id (user_id=?)"),
+            Some("idx_plants_user_id")
+        );
+        assert_eq!(
+            index_in_plan("SEARCH events USING COVERING INDEX idx_events_plant_id (plant_id=?)"),
+            Some("idx_events_plant_id")
+        );
+        assert_eq!(index_in_plan("SCAN care_tasks"), None);
+        assert_eq!(index_in_plan("USE TEMP B-TREE FOR ORDER BY"), None);
+    }
+
+    #[tokio::test]
+    async fn test_stats_of_migrated_database() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+
+        let stats = StatsRepository::new(db).stats().await.unwrap();
+        let plants = stats.tables.iter().find(|t| t.name == "plants").unwrap();
+        assert_eq!(plants.rows, 0);
+        let index = plants.indexes.iter().find(|i| i.name == "idx_plants_user_id").unwrap();
+        assert_eq!(index.used_by, vec!["plants.get_all_by_user".to_string()]);
+        assert!(stats.queries.iter().all(|q| q.index.is_some()), "{:?}", stats.queries);
+    }
+}
@@ -0,0 +1,591 @@
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::SqliteConnection;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::config::Database;
+use crate::domain::{
+    AuditAction, AuditEntity, AuditEvent, CareOverrides, CareScheduleVersion, CareTask, CareTemplate, ChatSession, DiagnosisMessage, DiagnosisSession, Expense, Feedback, IdentificationDisagreement,
+    JournalEntry, MoistureReading, Plant, PlantAttribute, PreventiveInspection, Quarantine, Room, ShoppingEntry, TreatmentOutcome, WishlistItem,
+};
+use crate::repositories::event_repository::{self, event_from_row, EVENT_COLUMNS};
+use crate::repositories::{
+    care_override_repository, identification_repository, inspection_repository, quarantine_repository,
+    schedule_version_repository,
+};
+
+/// Sync bookkeeping: which local events still have to be pushed, and which
+/// batches from other devices have been applied
+#[derive(Clone)]
+pub struct SyncRepository {
+    db: Database,
+}
+
+impl SyncRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// ID this device writes its batches under, created on first use
+    pub async fn device_id(&self) -> Result<String> {
+        let existing: Option<String> =
+            sqlx::query_scalar("SELECT value FROM sync_state WHERE key = 'device_id'")
+                .fetch_optional(self.db.pool())
+                .await?;
+        if let Some(device_id) = existing {
+            return Ok(device_id);
+        }
+
+        let device_id = Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO sync_state (key, value) VALUES ('device_id', ?)")
+            .bind(&device_id)
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(device_id)
+    }
+
+    /// Names of the batches already pulled from other devices
+    pub async fn applied_batches(&self) -> Result<HashSet<String>> {
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM sync_batches")
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(names.into_iter().collect())
+    }
+
+    /// IDs of every record that appears in the audit log
+    pub async fn recorded_entity_ids(&self) -> Result<HashSet<String>> {
+        let ids: Vec<String> = sqlx::query_scalar("SELECT DISTINCT entity_id FROM events")
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// IDs of the records of one kind that appear in the audit log, for
+    /// records keyed by another record's ID
+    pub async fn recorded_ids_of(&self, entity: AuditEntity) -> Result<HashSet<String>> {
+        let ids: Vec<String> = sqlx::query_scalar("SELECT DISTINCT entity_id FROM events WHERE entity_type = ?")
+            .bind(entity.as_str())
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Add events to the audit log for records it does not cover yet
+    pub async fn record_baseline(&self, events: &[AuditEvent]) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+        for event in events {
+            event_repository::record(&mut tx, event).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Events made on this device that have not been pushed, oldest first
+    pub async fn unsynced_events(&self) -> Result<Vec<AuditEvent>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM events
+            WHERE synced = 0
+            ORDER BY created_at, rowid
+            "#,
+            EVENT_COLUMNS
+        ))
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(event_from_row).collect()
+    }
+
+    /// Mark events as pushed, or as superseded by another device's changes
+    pub async fn mark_synced(&self, event_ids: &[String]) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+        for id in event_ids {
+            sqlx::query("UPDATE events SET synced = 1 WHERE id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Apply events pulled from other devices, in order, and remember the
+    /// batches they came from. Returns how many events changed a record.
+    pub async fn apply(&self, batches: &[String], events: &[AuditEvent]) -> Result<usize> {
+        let mut tx = self.db.pool().begin().await?;
+        let mut applied = 0;
+
+        for event in events {
+            if apply_event(&mut tx, event).await? {
+                applied += 1;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO events (id, entity_type, entity_id, plant_id, action, payload, created_at, synced)
+                VALUES (?, ?, ?, ?, ?, ?, ?, 1)
+                "#,
+            )
+            .bind(&event.id)
+            .bind(event.entity.as_str())
+            .bind(&event.entity_id)
+            .bind(&event.plant_id)
+            .bind(event.action.as_str())
+            .bind(serde_json::to_string(&event.payload)?)
+            .bind(event.created_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for name in batches {
+            sqlx::query("INSERT OR IGNORE INTO sync_batches (name, applied_at) VALUES (?, ?)")
+                .bind(name)
+                .bind(Utc::now().to_rfc3339())
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        // Any plant may have changed, and deleted ones are known by ID only
+        if let Some(cache) = self.db.plant_cache() {
+            cache.invalidate_all();
+        }
+
+        Ok(applied)
+    }
+}
+
+/// Bring a record in line with an event's snapshot. Records of plants that
+/// no longer exist here are skipped.
+async fn apply_event(conn: &mut SqliteConnection, event: &AuditEvent) -> Result<bool> {
+    let payload = event.payload.clone();
+
+    match (event.entity, event.action) {
+        (AuditEntity::Plant, AuditAction::Deleted) => {
+            delete(conn, "plants", &event.entity_id).await?;
+        }
+        (AuditEntity::Diagnosis, AuditAction::Deleted) => {
+            delete(conn, "diagnosis_sessions", &event.entity_id).await?;
+        }
+        (AuditEntity::CareTemplate, AuditAction::Deleted) => {
+            delete(conn, "templates", &event.entity_id).await?;
+        }
+        (AuditEntity::PlantAttribute, AuditAction::Deleted) => {
+            delete(conn, "plant_attributes", &event.entity_id).await?;
+        }
+        (AuditEntity::Room, AuditAction::Deleted) => {
+            delete(conn, "rooms", &event.entity_id).await?;
+        }
+        (AuditEntity::WishlistItem, AuditAction::Deleted) => {
+            delete(conn, "wishlist_items", &event.entity_id).await?;
+        }
+        (AuditEntity::CareOverrides, AuditAction::Deleted) => {
+            care_override_repository::delete(conn, &event.entity_id).await?;
+        }
+        (_, AuditAction::Deleted) => return Ok(false),
+        (AuditEntity::Plant, _) => {
+            let plant: Plant = serde_json::from_value(payload)?;
+            sqlx::query(
+                r#"
+                INSERT INTO plants (id, user_id, name, nickname, scientific_name, common_names, care_schedule, image_url, image_hash, created_at, updated_at, archived_at, archive_reason, room, tags, difficulty, family, acquired_at, acquired_from)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    name = excluded.name, nickname = excluded.nickname, scientific_name = excluded.scientific_name,
+                    common_names = excluded.common_names, care_schedule = excluded.care_schedule,
+                    image_url = excluded.image_url, image_hash = excluded.image_hash,
+                    updated_at = excluded.updated_at,
+                    archived_at = excluded.archived_at, archive_reason = excluded.archive_reason,
+                    room = excluded.room, tags = excluded.tags, difficulty = excluded.difficulty,
+                    family = excluded.family, acquired_at = excluded.acquired_at,
+                    acquired_from = excluded.acquired_from
+                "#,
+            )
+            .bind(&plant.id)
+            .bind(&plant.user_id)
+            .bind(&plant.name)
+            .bind(&plant.nickname)
+            .bind(&plant.scientific_name)
+            .bind(serde_json::to_string(&plant.common_names)?)
+            .bind(serde_json::to_string(&plant.care_schedule)?)
+            .bind(&plant.image_url)
+            .bind(&plant.image_hash)
+            .bind(plant.created_at.to_rfc3339())
+            .bind(plant.updated_at.to_rfc3339())
+            .bind(plant.archived.as_ref().map(|a| a.archived_at.to_rfc3339()))
+            .bind(plant.archived.as_ref().map(|a| a.reason.as_str()))
+            .bind(&plant.room)
+            .bind(serde_json::to_string(&plant.tags)?)
+            .bind(plant.difficulty.as_ref().map(serde_json::to_string).transpose()?)
+            .bind(&plant.family)
+            .bind(plant.acquired_at.map(|at| at.to_rfc3339()))
+            .bind(&plant.acquired_from)
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::Diagnosis, _) => {
+            let session: DiagnosisSession = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &session.plant_id).await? {
+                return Ok(false);
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO diagnosis_sessions (id, plant_id, status, state, diagnosis_context, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    status = excluded.status, state = excluded.state,
+                    diagnosis_context = excluded.diagnosis_context, updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&session.id)
+            .bind(&session.plant_id)
+            .bind(session.status().as_str())
+            .bind(session.state.to_string())
+            .bind(serde_json::to_string(&session.diagnosis_context)?)
+            .bind(session.created_at.to_rfc3339())
+            .bind(session.updated_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::DiagnosisMessage, _) => {
+            let message: DiagnosisMessage = serde_json::from_value(payload)?;
+            let session = sqlx::query("SELECT 1 FROM diagnosis_sessions WHERE id = ?")
+                .bind(&message.session_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+            if session.is_none() {
+                return Ok(false);
+            }
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO diagnosis_messages (id, session_id, role, content, created_at)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&message.id)
+            .bind(&message.session_id)
+            .bind(&message.role)
+            .bind(&message.content)
+            .bind(message.created_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::Chat, _) => {
+            let session: ChatSession = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &session.plant_id).await? {
+                return Ok(false);
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO chat_sessions (id, plant_id, messages, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    messages = excluded.messages, updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&session.id)
+            .bind(&session.plant_id)
+            .bind(serde_json::to_string(&session.messages)?)
+            .bind(session.created_at.to_rfc3339())
+            .bind(session.updated_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::Journal, _) => {
+            let entry: JournalEntry = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &entry.plant_id).await? {
+                return Ok(false);
+            }
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO journal_entries (id, plant_id, note, is_problem, created_at)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&entry.id)
+            .bind(&entry.plant_id)
+            .bind(&entry.note)
+            .bind(entry.is_problem)
+            .bind(entry.created_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::MoistureReading, _) => {
+            let reading: MoistureReading = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &reading.plant_id).await? {
+                return Ok(false);
+            }
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO moisture_readings (id, plant_id, value, recorded_at)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(&reading.id)
+            .bind(&reading.plant_id)
+            .bind(reading.value)
+            .bind(reading.recorded_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::ScheduleVersion, _) => {
+            let version: CareScheduleVersion = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &version.plant_id).await? {
+                return Ok(false);
+            }
+            schedule_version_repository::insert(conn, &version).await?;
+        }
+        (AuditEntity::IdentificationDisagreement, _) => {
+            let disagreement: IdentificationDisagreement = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &disagreement.plant_id).await? {
+                return Ok(false);
+            }
+            identification_repository::upsert(conn, &disagreement).await?;
+        }
+        (AuditEntity::Quarantine, _) => {
+            let quarantine: Quarantine = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &quarantine.plant_id).await? {
+                return Ok(false);
+            }
+            quarantine_repository::upsert(conn, &quarantine).await?;
+        }
+        (AuditEntity::PreventiveInspection, _) => {
+            let inspection: PreventiveInspection = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &inspection.plant_id).await? {
+                return Ok(false);
+            }
+            inspection_repository::upsert(conn, &inspection).await?;
+        }
+        (AuditEntity::CareOverrides, _) => {
+            let care: CareOverrides = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &care.plant_id).await? {
+                return Ok(false);
+            }
+            care_override_repository::upsert(conn, &care).await?;
+        }
+        (AuditEntity::Expense, _) => {
+            let expense: Expense = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &expense.plant_id).await? {
+                return Ok(false);
+            }
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO expenses (id, plant_id, kind, amount_cents, description, spent_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&expense.id)
+            .bind(&expense.plant_id)
+            .bind(expense.kind.as_str())
+            .bind(expense.amount_cents)
+            .bind(&expense.description)
+            .bind(expense.spent_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::PlantAttribute, _) => {
+            let attribute: PlantAttribute = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &attribute.plant_id).await? {
+                return Ok(false);
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO plant_attributes (id, plant_id, key, value, updated_at)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&attribute.id)
+            .bind(&attribute.plant_id)
+            .bind(&attribute.key)
+            .bind(&attribute.value)
+            .bind(attribute.updated_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::CareTask, _) => {
+            let task: CareTask = serde_json::from_value(payload)?;
+            if !plant_exists(conn, &task.plant_id).await? {
+                return Ok(false);
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO care_tasks (id, plant_id, kind, last_done_at)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET last_done_at = excluded.last_done_at
+                "#,
+            )
+            .bind(&task.id)
+            .bind(&task.plant_id)
+            .bind(task.kind.as_str())
+            .bind(task.last_done_at.map(|t| t.to_rfc3339()))
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::CareTemplate, _) => {
+            let template: CareTemplate = serde_json::from_value(payload)?;
+            // Names are unique: a template made under the same name on this
+            // device gives way to the synced one
+            sqlx::query("DELETE FROM templates WHERE user_id = ? AND name = ? COLLATE NOCASE AND id != ?")
+                .bind(&template.user_id)
+                .bind(&template.name)
+                .bind(&template.id)
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO templates (id, user_id, name, care_schedule, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    name = excluded.name, care_schedule = excluded.care_schedule,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&template.id)
+            .bind(&template.user_id)
+            .bind(&template.name)
+            .bind(serde_json::to_string(&template.schedule)?)
+            .bind(template.created_at.to_rfc3339())
+            .bind(template.updated_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::Room, _) => {
+            let room: Room = serde_json::from_value(payload)?;
+            sqlx::query(
+                r#"
+                INSERT INTO rooms (id, user_id, name, window, sun_hours, temperature, humidity, drafty, heated, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    name = excluded.name, window = excluded.window,
+                    sun_hours = excluded.sun_hours, temperature = excluded.temperature,
+                    humidity = excluded.humidity, drafty = excluded.drafty,
+                    heated = excluded.heated, updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&room.id)
+            .bind(&room.user_id)
+            .bind(&room.name)
+            .bind(room.window.map(|w| w.as_str()))
+            .bind(room.sun_hours)
+            .bind(room.environment.temperature)
+            .bind(room.environment.humidity)
+            .bind(room.environment.drafty)
+            .bind(room.environment.heated)
+            .bind(room.updated_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::WishlistItem, _) => {
+            let item: WishlistItem = serde_json::from_value(payload)?;
+            sqlx::query(
+                r#"
+                INSERT INTO wishlist_items (id, user_id, species, common_name, note, added_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    species = excluded.species, common_name = excluded.common_name,
+                    note = excluded.note
+                "#,
+            )
+            .bind(&item.id)
+            .bind(&item.user_id)
+            .bind(&item.species)
+            .bind(&item.common_name)
+            .bind(&item.note)
+            .bind(item.added_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::ShoppingEntry, _) => {
+            let entry: ShoppingEntry = serde_json::from_value(payload)?;
+            // Names are unique, as for templates
+            sqlx::query("DELETE FROM shopping_entries WHERE user_id = ? AND name = ? COLLATE NOCASE AND id != ?")
+                .bind(&entry.user_id)
+                .bind(&entry.name)
+                .bind(&entry.id)
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO shopping_entries (id, user_id, name, removed, updated_at)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    name = excluded.name, removed = excluded.removed,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&entry.id)
+            .bind(&entry.user_id)
+            .bind(&entry.name)
+            .bind(entry.removed)
+            .bind(entry.updated_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::TreatmentOutcome, _) => {
+            let outcome: TreatmentOutcome = serde_json::from_value(payload)?;
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO treatment_outcomes (id, plant_id, species, diagnosis_id, finding, recommendation, worked, note, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&outcome.id)
+            .bind(&outcome.plant_id)
+            .bind(&outcome.species)
+            .bind(&outcome.diagnosis_id)
+            .bind(&outcome.finding)
+            .bind(&outcome.recommendation)
+            .bind(outcome.worked)
+            .bind(&outcome.note)
+            .bind(outcome.created_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+        (AuditEntity::Feedback, _) => {
+            let feedback: Feedback = serde_json::from_value(payload)?;
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO feedback (id, kind, subject, model, rating, comment, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&feedback.id)
+            .bind(feedback.kind.as_str())
+            .bind(&feedback.subject)
+            .bind(&feedback.model)
+            .bind(feedback.rating)
+            .bind(&feedback.comment)
+            .bind(feedback.created_at.to_rfc3339())
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(true)
+}
+
+async fn plant_exists(conn: &mut SqliteConnection, plant_id: &str) -> Result<bool> {
+    let row = sqlx::query("SELECT 1 FROM plants WHERE id = ?")
+        .bind(plant_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    Ok(row.is_some())
+}
+
+async fn delete(conn: &mut SqliteConnection, table: &str, id: &str) -> Result<()> {
+    sqlx::query(&format!("DELETE FROM {} WHERE id = ?", table))
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
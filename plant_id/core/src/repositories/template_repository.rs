@@ -0,0 +1,139 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, CareSchedule, CareTemplate};
+use crate::repositories::event_repository;
+
+/// Columns selected for every template query
+const TEMPLATE_COLUMNS: &str = "id, user_id, name, care_schedule, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct TemplateRepository {
+    db: Database,
+}
+
+impl TemplateRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, template: &CareTemplate) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO templates (id, user_id, name, care_schedule, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&template.id)
+        .bind(&template.user_id)
+        .bind(&template.name)
+        .bind(serde_json::to_string(&template.schedule)?)
+        .bind(template.created_at.to_rfc3339())
+        .bind(template.updated_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        record(&mut tx, template, AuditAction::Created).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// A user's template by name, ignoring case
+    pub async fn get_by_name(&self, name: &str, user_id: &str) -> Result<Option<CareTemplate>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM templates
+            WHERE user_id = ? AND name = ? COLLATE NOCASE
+            "#,
+            TEMPLATE_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(|row| template_from_row(&row)).transpose()
+    }
+
+    /// A user's templates, alphabetically
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<CareTemplate>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM templates
+            WHERE user_id = ?
+            ORDER BY name COLLATE NOCASE
+            "#,
+            TEMPLATE_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(template_from_row).collect()
+    }
+
+    pub async fn update(&self, template: &CareTemplate) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE templates
+            SET name = ?, care_schedule = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&template.name)
+        .bind(serde_json::to_string(&template.schedule)?)
+        .bind(template.updated_at.to_rfc3339())
+        .bind(&template.id)
+        .execute(&mut *tx)
+        .await?;
+
+        record(&mut tx, template, AuditAction::Updated).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, template: &CareTemplate) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query("DELETE FROM templates WHERE id = ?")
+            .bind(&template.id)
+            .execute(&mut *tx)
+            .await?;
+
+        record(&mut tx, template, AuditAction::Deleted).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+async fn record(conn: &mut SqliteConnection, template: &CareTemplate, action: AuditAction) -> Result<()> {
+    let event = AuditEvent::new(AuditEntity::CareTemplate, &template.id, None, action, template)?;
+    event_repository::record(conn, &event).await
+}
+
+fn template_from_row(row: &SqliteRow) -> Result<CareTemplate> {
+    let schedule: CareSchedule = serde_json::from_str(row.get("care_schedule"))?;
+    let created_at: String = row.get("created_at");
+    let updated_at: String = row.get("updated_at");
+
+    Ok(CareTemplate {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        schedule,
+        created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+    })
+}
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::{User, UserRole};
+
+const USER_COLUMNS: &str = "id, role, pin_hash, api_key_hash, updated_at";
+
+/// Roles are local to this device, so changes are neither audited nor
+/// synced
+#[derive(Clone)]
+pub struct UserRepository {
+    db: Database,
+}
+
+impl UserRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<User>> {
+        let row = sqlx::query(&format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.db.pool())
+            .await?;
+
+        row.as_ref().map(user_from_row).transpose()
+    }
+
+    /// The user an API key belongs to, by the key's hash
+    pub async fn get_by_api_key_hash(&self, api_key_hash: &str) -> Result<Option<User>> {
+        let row = sqlx::query(&format!("SELECT {} FROM users WHERE api_key_hash = ?", USER_COLUMNS))
+            .bind(api_key_hash)
+            .fetch_optional(self.db.pool())
+            .await?;
+
+        row.as_ref().map(user_from_row).transpose()
+    }
+
+    /// Users with an API key, by ID
+    pub async fn get_all_with_api_key(&self) -> Result<Vec<User>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM users WHERE api_key_hash IS NOT NULL ORDER BY id",
+            USER_COLUMNS
+        ))
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(user_from_row).collect()
+    }
+
+    /// Role of a user; users never recorded are owners
+    pub async fn role(&self, id: &str) -> Result<UserRole> {
+        Ok(self
+            .get_by_id(id)
+            .await?
+            .map(|user| user.role)
+            .unwrap_or(UserRole::Owner))
+    }
+
+    /// Insert or update a user
+    pub async fn save(&self, user: &User) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, role, pin_hash, api_key_hash, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                role = excluded.role, pin_hash = excluded.pin_hash,
+                api_key_hash = excluded.api_key_hash, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&user.id)
+        .bind(user.role.as_str())
+        .bind(&user.pin_hash)
+        .bind(&user.api_key_hash)
+        .bind(user.updated_at.to_rfc3339())
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn user_from_row(row: &SqliteRow) -> Result<User> {
+    let role: String = row.get("role");
+    let updated_at: String = row.get("updated_at");
+
+    Ok(User {
+        id: row.get("id"),
+        role: UserRole::from_str(&role).ok_or_else(|| anyhow::anyhow!("Unknown user role: {}", role))?,
+        pin_hash: row.get("pin_hash"),
+        api_key_hash: row.get("api_key_hash"),
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+    })
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnection, SqliteRow};
+use sqlx::Row;
+
+use crate::config::Database;
+use crate::domain::wishlist::wishlist_item_id;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, WishlistItem};
+use crate::repositories::event_repository;
+
+/// Columns selected for every wishlist query
+const ITEM_COLUMNS: &str = "id, user_id, species, common_name, note, added_at";
+
+#[derive(Clone)]
+pub struct WishlistRepository {
+    db: Database,
+}
+
+impl WishlistRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// A user's wishlist, most recently added first
+    pub async fn get_all_by_user(&self, user_id: &str) -> Result<Vec<WishlistItem>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM wishlist_items
+            WHERE user_id = ?
+            ORDER BY added_at DESC
+            "#,
+            ITEM_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(item_from_row).collect()
+    }
+
+    /// Put a species on the wishlist, replacing its note if it was already
+    /// there
+    pub async fn save(&self, item: &WishlistItem) -> Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let existed = sqlx::query("SELECT 1 FROM wishlist_items WHERE id = ?")
+            .bind(&item.id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+
+        sqlx::query(
+            r#"
+            INSERT INTO wishlist_items (id, user_id, species, common_name, note, added_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                species = excluded.species, common_name = excluded.common_name,
+                note = excluded.note
+            "#,
+        )
+        .bind(&item.id)
+        .bind(&item.user_id)
+        .bind(&item.species)
+        .bind(&item.common_name)
+        .bind(&item.note)
+        .bind(item.added_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        let action = if existed {
+            AuditAction::Updated
+        } else {
+            AuditAction::Created
+        };
+        record(&mut tx, item, action).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Take a species off the wishlist. Returns false if it was not on it.
+    pub async fn delete(&self, species: &str, user_id: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let row = sqlx::query(&format!("SELECT {} FROM wishlist_items WHERE id = ?", ITEM_COLUMNS))
+            .bind(wishlist_item_id(user_id, species))
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(row) = row else {
+            return Ok(false);
+        };
+        let item = item_from_row(&row)?;
+
+        sqlx::query("DELETE FROM wishlist_items WHERE id = ?")
+            .bind(&item.id)
+            .execute(&mut *tx)
+            .await?;
+
+        record(&mut tx, &item, AuditAction::Deleted).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+}
+
+async fn record(conn: &mut SqliteConnection, item: &WishlistItem, action: AuditAction) -> Result<()> {
+    let event = AuditEvent::new(AuditEntity::WishlistItem, &item.id, None, action, item)?;
+    event_repository::record(conn, &event).await
+}
+
+fn item_from_row(row: &SqliteRow) -> Result<WishlistItem> {
+    let added_at: String = row.get("added_at");
+
+    Ok(WishlistItem {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        species: row.get("species"),
+        common_name: row.get("common_name"),
+        note: row.get("note"),
+        added_at: DateTime::parse_from_rfc3339(&added_at)?.with_timezone(&Utc),
+    })
+}
@@ -0,0 +1,248 @@
+/*!
+ * ANONYMIZER
+ *
+ * Strips what could identify the owner from an export, so a collection
+ * can be shared publicly or attached to a bug report:
+ *
+ * - GPS coordinates, with the rest of the photos' metadata (EXIF)
+ * - user identifiers, replaced by ANONYMOUS_USER
+ * - file paths of photos, in plants and diagnoses
+ * - journal entries mentioning personal details: email addresses, phone
+ *   numbers, coordinates, and whatever else the given patterns match
+ * - the same details in other free text (diagnosis problems and messages,
+ *   chats, attribute values, expense descriptions), replaced by REDACTED,
+ *   and nicknames or sources of plants that mention them
+ *
+ * Anonymized archives can still be restored with `import`.
+ */
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use regex::Regex;
+use std::io::Cursor;
+
+use crate::dto::{CollectionArchiveDto, PlantExportRow};
+
+/// User of the plants in an anonymized archive
+pub const ANONYMOUS_USER: &str = "anonymous";
+
+/// What personal details in free text are replaced by
+pub const REDACTED: &str = "[removed]";
+
+/// Personal details looked for in free text, besides those given
+const DEFAULT_PATTERNS: &[&str] = &[
+    // Email addresses
+    r"[\w.+-]+@[\w-]+\.[\w.-]+",
+    // Phone numbers, international ("+31 6 1234 5678"), national
+    // ("020 123 4567") or American ("(555) 123-4567"), but not dates
+    r"(?:\+|\b0)\d[\d ()-]{6,}\d|\(\d{3}\) ?\d{3}-\d{4}",
+    // Coordinates, such as "52.3702, 4.8952"
+    r"-?\d{1,2}\.\d{3,},\s*-?\d{1,3}\.\d{3,}",
+];
+
+/// What was removed from an archive
+#[derive(Debug, Default, PartialEq)]
+pub struct AnonymizeReport {
+    pub photos_cleaned: usize,
+    /// Photos that could not be decoded, so were left out
+    pub photos_dropped: usize,
+    pub journal_removed: usize,
+    /// Other texts personal details were removed from
+    pub texts_redacted: usize,
+}
+
+pub struct Anonymizer {
+    patterns: Vec<Regex>,
+}
+
+impl Anonymizer {
+    /// An anonymizer looking for the default patterns and `patterns`
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = DEFAULT_PATTERNS
+            .iter()
+            .copied()
+            .chain(patterns.iter().map(String::as_str))
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid PII pattern '{}'", p)))
+            .collect::<Result<_>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Whether a text mentions personal details
+    pub fn is_personal(&self, text: &str) -> bool {
+        self.patterns.iter().any(|p| p.is_match(text))
+    }
+
+    /// Replace the personal details in a text by REDACTED, returning
+    /// whether there were any
+    fn redact(&self, text: &mut String) -> bool {
+        if !self.is_personal(text) {
+            return false;
+        }
+        for pattern in &self.patterns {
+            *text = pattern.replace_all(text, REDACTED).into_owned();
+        }
+        true
+    }
+
+    pub fn archive(&self, archive: &mut CollectionArchiveDto) -> AnonymizeReport {
+        let mut report = AnonymizeReport::default();
+
+        for plant in &mut archive.plants {
+            plant.user_id = ANONYMOUS_USER.to_string();
+            plant.image_url = None;
+            for text in [&mut plant.nickname, &mut plant.acquired_from] {
+                if text.as_deref().is_some_and(|t| self.is_personal(t)) {
+                    *text = None;
+                    report.texts_redacted += 1;
+                }
+            }
+        }
+        for session in &mut archive.diagnoses {
+            if let Some(photos) = session.diagnosis_context["photos"].as_array_mut() {
+                for photo in photos.iter_mut().filter_map(|p| p.as_object_mut()) {
+                    photo.remove("url");
+                }
+            }
+            if let Some(problem) = session.diagnosis_context["initial_prompt"].as_str() {
+                let mut problem = problem.to_string();
+                if self.redact(&mut problem) {
+                    session.diagnosis_context["initial_prompt"] = problem.into();
+                    report.texts_redacted += 1;
+                }
+            }
+        }
+
+        let texts = archive
+            .diagnosis_messages
+            .iter_mut()
+            .map(|m| &mut m.content)
+            .chain(archive.chats.iter_mut().flat_map(|c| c.messages.iter_mut().map(|m| &mut m.content)))
+            .chain(archive.attributes.iter_mut().map(|a| &mut a.value))
+            .chain(archive.expenses.iter_mut().map(|e| &mut e.description));
+        for text in texts {
+            if self.redact(text) {
+                report.texts_redacted += 1;
+            }
+        }
+
+        let journal = archive.journal.len();
+        archive.journal.retain(|entry| !self.is_personal(&entry.note));
+        report.journal_removed = journal - archive.journal.len();
+
+        archive.images.retain(|plant_id, encoded| match strip_metadata(encoded) {
+            Ok(cleaned) => {
+                *encoded = cleaned;
+                report.photos_cleaned += 1;
+                true
+            }
+            Err(e) => {
+                log::warn!("Leaving out the photo of plant {}: {:#}", plant_id, e);
+                report.photos_dropped += 1;
+                false
+            }
+        });
+
+        report
+    }
+
+    pub fn plant_rows(&self, rows: &mut [PlantExportRow]) {
+        for row in rows {
+            row.image_url = None;
+        }
+    }
+}
+
+/// Re-encode a base64 encoded photo in its own format, which leaves its
+/// metadata behind
+fn strip_metadata(encoded: &str) -> Result<String> {
+    let bytes = BASE64.decode(encoded).context("Invalid base64")?;
+    let format = image::guess_format(&bytes).context("Unknown image format")?;
+    let decoded = image::load_from_memory_with_format(&bytes, format)?;
+
+    let mut cleaned = Vec::new();
+    decoded.write_to(&mut Cursor::new(&mut cleaned), format)?;
+    Ok(BASE64.encode(cleaned))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        CareSchedule, ChatSession, DiagnosisMessage, DiagnosisSession, JournalEntry, Plant, PlantAttribute,
+    };
+    use chrono::Utc;
+    use image::{ImageFormat, RgbImage};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_archive_is_anonymized() {
+        let mut plant = Plant::new("alice@example.com".to_string(), "Fern".to_string(), CareSchedule::default());
+        plant.image_url = Some("/home/alice/.local/share/plant-care/images/ab12.png".to_string());
+        plant.set_nickname(Some("Carol's fern"));
+        plant.acquired_from = Some("Garden centre".to_string());
+        let mut session = DiagnosisSession::new(plant.id.clone(), "Yellow leaves, mail me at alice@example.com".to_string());
+        session.diagnosis_context["photos"] = serde_json::json!([{ "part": "roots", "url": "/home/alice/roots.jpg" }]);
+        let answer = DiagnosisMessage::new(session.id.clone(), "user", "It stands at 52.3702, 4.8952".to_string());
+        let mut chat = ChatSession::new(plant.id.clone());
+        chat.push_message("user", "Call me on +31 6 1234 5678 when it flowers".to_string());
+        chat.push_message("assistant", "I cannot call you".to_string());
+        let source = PlantAttribute::new(&plant.id, "source shop", "Carol's plants").unwrap();
+
+        let mut png = Vec::new();
+        RgbImage::new(2, 2).write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+        let mut archive = CollectionArchiveDto {
+            format_version: 2,
+            exported_at: Utc::now(),
+            plants: vec![plant.clone()],
+            attributes: vec![source],
+            expenses: Vec::new(),
+            diagnoses: vec![session],
+            diagnosis_messages: vec![answer],
+            journal: vec![
+                JournalEntry::new(plant.id.clone(), "Repotted".to_string(), false),
+                JournalEntry::new(plant.id.clone(), "Cutting from Bob, call +31 6 1234 5678".to_string(), false),
+                JournalEntry::new(plant.id.clone(), "Gave one to Carol Smith".to_string(), false),
+            ],
+            moisture_readings: Vec::new(),
+            chats: vec![chat],
+            care_overrides: Vec::new(),
+            images: HashMap::from([
+                (plant.id.clone(), BASE64.encode(&png)),
+                ("other".to_string(), BASE64.encode(b"not an image")),
+            ]),
+        };
+
+        let anonymizer = Anonymizer::new(&[r"(?i)\bcarol\b".to_string()]).unwrap();
+        let report = anonymizer.archive(&mut archive);
+
+        assert_eq!(
+            report,
+            AnonymizeReport {
+                photos_cleaned: 1,
+                photos_dropped: 1,
+                journal_removed: 2,
+                texts_redacted: 5,
+            }
+        );
+        assert_eq!(archive.plants[0].user_id, ANONYMOUS_USER);
+        assert_eq!(archive.plants[0].image_url, None);
+        assert_eq!(archive.plants[0].nickname, None);
+        assert_eq!(archive.plants[0].acquired_from.as_deref(), Some("Garden centre"));
+        assert_eq!(
+            archive.diagnoses[0].diagnosis_context["initial_prompt"],
+            "Yellow leaves, mail me at [removed]"
+        );
+        assert_eq!(archive.diagnosis_messages[0].content, "It stands at [removed]");
+        assert_eq!(archive.chats[0].messages[0].content, "Call me on [removed] when it flowers");
+        assert_eq!(archive.chats[0].messages[1].content, "I cannot call you");
+        assert_eq!(archive.attributes[0].value, "[removed]'s plants");
+        assert_eq!(archive.diagnoses[0].diagnosis_context["photos"], serde_json::json!([{ "part": "roots" }]));
+        assert_eq!(archive.journal.len(), 1);
+        assert!(archive.images.contains_key(&plant.id));
+
+        assert!(anonymizer.is_personal("GPS 52.3702, 4.8952"));
+        assert!(anonymizer.is_personal("ask (555) 123-4567"));
+        assert!(!anonymizer.is_personal("Watered 250 ml on 2024-05-01"));
+        assert!(Anonymizer::new(&["(".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+/*!
+ * API KEY SERVICE
+ *
+ * Issues the API keys that authenticate requests to the HTTP server and
+ * resolves them to the user ID every repository call is scoped to. Each
+ * user has at most one key; only its hash is stored, so a lost key is
+ * replaced rather than shown again.
+ */
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::crypto;
+use crate::domain::User;
+use crate::repositories::UserRepository;
+
+pub struct ApiKeyService {
+    user_repo: UserRepository,
+}
+
+impl ApiKeyService {
+    pub fn new(user_repo: UserRepository) -> Self {
+        Self { user_repo }
+    }
+
+    /// Create an API key for `user_id`, replacing any previous one. The
+    /// key is only returned here.
+    pub async fn issue(&self, user_id: &str) -> Result<String> {
+        if user_id.trim().is_empty() {
+            anyhow::bail!("User ID is empty");
+        }
+
+        let mut user = self
+            .user_repo
+            .get_by_id(user_id)
+            .await?
+            .unwrap_or_else(|| User::owner(user_id.to_string()));
+
+        let key = crypto::generate_api_key();
+        user.api_key_hash = Some(crypto::hash_api_key(&key));
+        user.updated_at = Utc::now();
+        self.user_repo.save(&user).await?;
+
+        Ok(key)
+    }
+
+    pub async fn revoke(&self, user_id: &str) -> Result<()> {
+        let mut user = match self.user_repo.get_by_id(user_id).await? {
+            Some(user) if user.api_key_hash.is_some() => user,
+            _ => anyhow::bail!("User '{}' has no API key", user_id),
+        };
+
+        user.api_key_hash = None;
+        user.updated_at = Utc::now();
+        self.user_repo.save(&user).await
+    }
+
+    /// Users that can authenticate with an API key
+    pub async fn users(&self) -> Result<Vec<User>> {
+        self.user_repo.get_all_with_api_key().await
+    }
+
+    /// ID of the user `key` belongs to, if it is a current key
+    pub async fn authenticate(&self, key: &str) -> Result<Option<String>> {
+        let user = self
+            .user_repo
+            .get_by_api_key_hash(&crypto::hash_api_key(key))
+            .await?;
+
+        Ok(user.map(|user| user.id))
+    }
+}
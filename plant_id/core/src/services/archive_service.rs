@@ -0,0 +1,277 @@
+/*!
+ * ARCHIVE SERVICE
+ *
+ * Retires plants from the collection (died, gifted or sold) while keeping
+ * their records, hands them over to another user of the household, or
+ * deletes them outright, and summarizes the "graveyard" so patterns in
+ * plant losses become visible.
+ */
+
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::domain::{ArchiveReason, DiagnosisState, Permission, Plant, PlantArchive, RequestError};
+use crate::repositories::{DiagnosisRepository, PlantRepository, UserRepository};
+
+/// An archived plant with what is known about its end
+#[derive(Debug, Clone)]
+pub struct GraveyardEntry {
+    pub plant: Plant,
+    pub lifespan_days: i64,
+    /// Finding of the last concluded diagnosis, if any
+    pub final_finding: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct GraveyardReport {
+    pub entries: Vec<GraveyardEntry>,
+    /// Number of archived plants per reason
+    pub reason_counts: Vec<(ArchiveReason, usize)>,
+    /// Average lifespan of plants that died
+    pub average_lifespan_days: Option<f64>,
+    /// Final diagnoses of plants that died, most common first
+    pub causes_of_death: Vec<(String, usize)>,
+}
+
+/// A plant handed over to another user
+#[derive(Debug, Clone)]
+pub struct PlantTransfer {
+    /// The plant, as its new owner has it
+    pub plant: Plant,
+    /// Archived copy left with the previous owner, holding the diagnosis
+    /// history, when it was detached
+    pub history_copy: Option<Plant>,
+    /// Diagnosis sessions that stayed with the previous owner
+    pub detached_sessions: u64,
+}
+
+pub struct ArchiveService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    user_repo: UserRepository,
+}
+
+impl ArchiveService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        user_repo: UserRepository,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            user_repo,
+        }
+    }
+
+    pub async fn archive_plant(
+        &self,
+        plant_id: &str,
+        user_id: &str,
+        reason: ArchiveReason,
+    ) -> Result<Plant> {
+        self.user_repo.role(user_id).await?.require(Permission::RemovePlants)?;
+
+        let mut plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        if plant.archived.is_some() {
+            anyhow::bail!("{} is already archived", plant.display_name());
+        }
+
+        let now = Utc::now();
+        plant.archived = Some(PlantArchive {
+            reason,
+            archived_at: now,
+        });
+        plant.updated_at = now;
+        self.plant_repo.update(&plant).await?;
+
+        Ok(plant)
+    }
+
+    /// Delete a plant and all its records. Returns the deleted plant so
+    /// the caller can remove its stored photo.
+    pub async fn delete_plant(&self, plant_id: &str, user_id: &str) -> Result<Plant> {
+        self.user_repo.role(user_id).await?.require(Permission::RemovePlants)?;
+
+        let plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+        self.plant_repo.delete(plant_id, user_id).await?;
+
+        Ok(plant)
+    }
+
+    /// Give a plant to another user, e.g. when it changes hands in the
+    /// household. Its reminders and diagnosis history go with it, unless
+    /// `detach_history`: then the history stays with the previous owner, on
+    /// a copy of the plant archived as gifted.
+    pub async fn transfer_plant(
+        &self,
+        plant_id: &str,
+        user_id: &str,
+        to_user: &str,
+        detach_history: bool,
+    ) -> Result<PlantTransfer> {
+        self.user_repo.role(user_id).await?.require(Permission::RemovePlants)?;
+
+        let to_user = to_user.trim();
+        if to_user.is_empty() {
+            anyhow::bail!("Name the user to give the plant to");
+        }
+        if to_user == user_id {
+            anyhow::bail!("The plant is already yours");
+        }
+        // Users are known by a role or API key, or by the plants they have
+        let known = self.user_repo.get_by_id(to_user).await?.is_some()
+            || !self.plant_repo.get_all_by_user(to_user).await?.is_empty()
+            || !self.plant_repo.get_archived_by_user(to_user).await?.is_empty();
+        if !known {
+            anyhow::bail!(
+                "Unknown user '{}'. Create them with 'plant-care api-key create {}' first.",
+                to_user,
+                to_user
+            );
+        }
+
+        let mut plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+        if plant.archived.is_some() {
+            anyhow::bail!("{} is archived; only plants in the collection can be given away", plant.display_name());
+        }
+
+        let now = Utc::now();
+        let history_copy = detach_history.then(|| {
+            let mut copy = plant.clone();
+            copy.id = Uuid::new_v4().to_string();
+            // Photos of the plant are the new owner's to match against
+            copy.image_hash = None;
+            copy.updated_at = now;
+            copy.archived = Some(PlantArchive {
+                reason: ArchiveReason::Gifted,
+                archived_at: now,
+            });
+            copy
+        });
+
+        plant.user_id = to_user.to_string();
+        plant.updated_at = now;
+        let detached_sessions = self.plant_repo.transfer(&plant, user_id, history_copy.as_ref()).await?;
+
+        Ok(PlantTransfer {
+            plant,
+            history_copy,
+            detached_sessions,
+        })
+    }
+
+    pub async fn graveyard(&self, user_id: &str) -> Result<GraveyardReport> {
+        let plants = self.plant_repo.get_archived_by_user(user_id).await?;
+        let mut report = GraveyardReport::default();
+        let mut reason_counts: HashMap<ArchiveReason, usize> = HashMap::new();
+        let mut causes: HashMap<String, usize> = HashMap::new();
+        let mut died_lifespans = Vec::new();
+
+        for plant in plants {
+            let Some(archive) = plant.archived.clone() else {
+                continue;
+            };
+
+            // Sessions are newest first, so this is the final conclusion
+            let final_finding = self
+                .diagnosis_repo
+                .get_all_by_plant_id(&plant.id, user_id)
+                .await?
+                .into_iter()
+                .find(|s| s.state == DiagnosisState::Concluded)
+                .and_then(|s| s.diagnosis_context["result"]["finding"].as_str().map(str::to_string));
+
+            let lifespan_days = plant.lifespan_days();
+            *reason_counts.entry(archive.reason).or_default() += 1;
+
+            if archive.reason == ArchiveReason::Died {
+                died_lifespans.push(lifespan_days);
+                let cause = final_finding.clone().unwrap_or_else(|| "Unknown".to_string());
+                *causes.entry(cause).or_default() += 1;
+            }
+
+            report.entries.push(GraveyardEntry {
+                plant,
+                lifespan_days,
+                final_finding,
+            });
+        }
+
+        if !died_lifespans.is_empty() {
+            report.average_lifespan_days =
+                Some(died_lifespans.iter().sum::<i64>() as f64 / died_lifespans.len() as f64);
+        }
+
+        report.reason_counts = reason_counts.into_iter().collect();
+        report.reason_counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.as_str().cmp(b.0.as_str())));
+
+        report.causes_of_death = causes.into_iter().collect();
+        report.causes_of_death.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use crate::domain::{CareSchedule, DiagnosisSession, User};
+
+    #[tokio::test]
+    async fn test_transfer_plant() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let diagnoses = DiagnosisRepository::new(db.clone());
+        let users = UserRepository::new(db);
+        let service = ArchiveService::new(plants.clone(), diagnoses.clone(), users.clone());
+
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        let ivy = Plant::new("u".to_string(), "Ivy".to_string(), CareSchedule::default());
+        for plant in [&fern, &ivy] {
+            plants.create(plant).await.unwrap();
+            diagnoses
+                .create(&DiagnosisSession::new(plant.id.clone(), "Brown tips".to_string()))
+                .await
+                .unwrap();
+        }
+
+        let error = service.transfer_plant(&fern.id, "u", "v", false).await.unwrap_err();
+        assert!(error.to_string().starts_with("Unknown user 'v'"));
+        users.save(&User::owner("v".to_string())).await.unwrap();
+
+        // The history goes along by default
+        let transfer = service.transfer_plant(&fern.id, "u", "v", false).await.unwrap();
+        assert!(transfer.history_copy.is_none());
+        assert!(plants.get_by_id(&fern.id, "u").await.unwrap().is_none());
+        assert_eq!(plants.get_by_id(&fern.id, "v").await.unwrap().unwrap().user_id, "v");
+        assert_eq!(diagnoses.get_all_by_plant_id(&fern.id, "v").await.unwrap().len(), 1);
+
+        // or stays behind on an archived copy
+        let transfer = service.transfer_plant(&ivy.id, "u", "v", true).await.unwrap();
+        let copy = transfer.history_copy.unwrap();
+        assert_eq!(transfer.detached_sessions, 1);
+        assert!(diagnoses.get_all_by_plant_id(&ivy.id, "v").await.unwrap().is_empty());
+        assert_eq!(diagnoses.get_all_by_plant_id(&copy.id, "u").await.unwrap().len(), 1);
+        let archived = plants.get_archived_by_user("u").await.unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].archived.as_ref().unwrap().reason, ArchiveReason::Gifted);
+    }
+}
@@ -0,0 +1,371 @@
+/*!
+ * ASK SERVICE
+ *
+ * Answers questions about the owner's own collection in plain language
+ * ("when did I last water the fiddle leaf fig?"). The AI is given tools over
+ * the local records - the plants, their journals, care reminders and past
+ * diagnoses - and calls them to look up what it needs before answering.
+ */
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use crate::adapters::ai_adapter::{ToolCall, ToolConversation, ToolDefinition, ToolTurn};
+use crate::adapters::AiAdapter;
+use crate::config;
+use crate::domain::reminder;
+use crate::domain::{DiagnosisFilter, Permission, Plant};
+use crate::repositories::{
+    CareTaskRepository, DiagnosisRepository, JournalRepository, PlantRepository, UserRepository,
+};
+
+/// Turns in which the AI may call tools before it has to answer
+const MAX_TOOL_ROUNDS: usize = 6;
+
+/// Journal entries returned by one lookup
+const MAX_JOURNAL_ENTRIES: usize = 20;
+
+/// Diagnoses returned by one lookup
+const MAX_DIAGNOSES: usize = 10;
+
+/// The answer to a question, with the lookups it is based on
+#[derive(Debug, Clone)]
+pub struct AskAnswer {
+    pub answer: String,
+    /// The tools the AI called, in order, e.g. `read_journal(Fiddle leaf fig)`
+    pub consulted: Vec<String>,
+}
+
+pub struct AskService {
+    tools: CollectionTools,
+    user_repo: UserRepository,
+    ai_adapter: AiAdapter,
+}
+
+impl AskService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        journal_repo: JournalRepository,
+        care_task_repo: CareTaskRepository,
+        diagnosis_repo: DiagnosisRepository,
+        user_repo: UserRepository,
+        ai_adapter: AiAdapter,
+    ) -> Self {
+        Self {
+            tools: CollectionTools {
+                plant_repo,
+                journal_repo,
+                care_task_repo,
+                diagnosis_repo,
+            },
+            user_repo,
+            ai_adapter,
+        }
+    }
+
+    /// Answer a question about the collection, letting the AI look up the
+    /// records it needs. A lookup that fails is reported back to the AI
+    /// rather than ending the conversation.
+    pub async fn ask(&self, question: &str, user_id: &str) -> Result<AskAnswer> {
+        self.user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+
+        let system_prompt = format!(
+            "You are a helpful assistant answering questions about the owner's own houseplant collection.\n\
+             Today is {}. Use the tools to look up the owner's records before answering; never guess \
+             dates or events that are not in them, and say so when the records do not answer the question.\n\
+             Plants can be named by their ID, name, nickname or species. Answer briefly in plain text \
+             (no JSON, no markdown tables).",
+            config::local_zone().today()
+        );
+        let definitions = definitions();
+        let mut conversation = ToolConversation::new(&system_prompt, question);
+        let mut consulted = Vec::new();
+
+        for _ in 0..MAX_TOOL_ROUNDS {
+            match self.ai_adapter.complete_with_tools(&mut conversation, &definitions).await? {
+                ToolTurn::Answer(answer) => return Ok(AskAnswer { answer, consulted }),
+                ToolTurn::Calls(calls) => {
+                    for call in calls {
+                        consulted.push(describe(&call));
+                        let result = match self.tools.run(&call, user_id).await {
+                            Ok(result) => result,
+                            Err(e) => json!({ "error": format!("{:#}", e) }),
+                        };
+                        conversation.push_result(&call, &result);
+                    }
+                }
+            }
+        }
+
+        anyhow::bail!(
+            "The AI did not settle on an answer after {} lookups; try a more specific question",
+            consulted.len()
+        )
+    }
+}
+
+/// The tools the AI may call
+fn definitions() -> Vec<ToolDefinition> {
+    let plant = json!({
+        "type": "string",
+        "description": "Plant ID, name, nickname or species"
+    });
+
+    vec![
+        ToolDefinition {
+            name: "list_plants",
+            description: "List the plants in the collection with their IDs, names, species and rooms",
+            parameters: json!({"type": "object", "properties": {}}),
+        },
+        ToolDefinition {
+            name: "read_journal",
+            description: "Read the journal of a plant, newest entries first: notes the owner wrote \
+                          about it, including problems they noticed",
+            parameters: json!({
+                "type": "object",
+                "properties": { "plant": plant },
+                "required": ["plant"]
+            }),
+        },
+        ToolDefinition {
+            name: "read_reminders",
+            description: "Read the watering and fertilizing reminders: how often each task recurs, \
+                          when it was last done and when it is next due. Covers every plant unless \
+                          one is given.",
+            parameters: json!({
+                "type": "object",
+                "properties": { "plant": plant }
+            }),
+        },
+        ToolDefinition {
+            name: "read_diagnoses",
+            description: "Read past diagnosis sessions, newest first, with the problem described and \
+                          the finding. Covers every plant unless one is given; `finding` keeps only \
+                          sessions whose finding contains the text.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "plant": plant,
+                    "finding": { "type": "string", "description": "Text the finding must contain" }
+                }
+            }),
+        },
+    ]
+}
+
+/// A call as shown to the owner, e.g. `read_journal(Fiddle leaf fig)`
+fn describe(call: &ToolCall) -> String {
+    let arguments: Vec<&str> = call
+        .arguments
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(_, value)| value.as_str())
+        .collect();
+    format!("{}({})", call.name, arguments.join(", "))
+}
+
+/// Read-only lookups over the local records, answering the AI's tool calls
+struct CollectionTools {
+    plant_repo: PlantRepository,
+    journal_repo: JournalRepository,
+    care_task_repo: CareTaskRepository,
+    diagnosis_repo: DiagnosisRepository,
+}
+
+impl CollectionTools {
+    async fn run(&self, call: &ToolCall, user_id: &str) -> Result<Value> {
+        let plant = match call.arguments["plant"].as_str().filter(|p| !p.trim().is_empty()) {
+            Some(identifier) => Some(self.plant_repo.find(identifier.trim(), user_id).await?),
+            None => None,
+        };
+
+        match call.name.as_str() {
+            "list_plants" => self.list_plants(user_id).await,
+            "read_journal" => {
+                let plant = plant.context("Name the plant whose journal to read")?;
+                self.read_journal(&plant).await
+            }
+            "read_reminders" => self.read_reminders(plant, user_id).await,
+            "read_diagnoses" => {
+                let finding = call.arguments["finding"].as_str().map(str::to_string);
+                self.read_diagnoses(plant, finding, user_id).await
+            }
+            other => anyhow::bail!("Unknown tool '{}'", other),
+        }
+    }
+
+    async fn list_plants(&self, user_id: &str) -> Result<Value> {
+        let zone = config::local_zone();
+        let plants: Vec<Value> = self
+            .plant_repo
+            .get_all_by_user(user_id)
+            .await?
+            .iter()
+            .map(|plant| {
+                json!({
+                    "id": plant.id,
+                    "name": plant.name,
+                    "nickname": plant.nickname,
+                    "scientific_name": plant.scientific_name,
+                    "common_names": plant.common_names,
+                    "room": plant.room,
+                    "added": zone.date(plant.created_at)
+                })
+            })
+            .collect();
+
+        Ok(json!({ "plants": plants }))
+    }
+
+    async fn read_journal(&self, plant: &Plant) -> Result<Value> {
+        let zone = config::local_zone();
+        let entries: Vec<Value> = self
+            .journal_repo
+            .get_all_by_plant_id(&plant.id)
+            .await?
+            .iter()
+            .take(MAX_JOURNAL_ENTRIES)
+            .map(|entry| {
+                json!({
+                    "date": zone.date(entry.created_at),
+                    "note": entry.note,
+                    "problem": entry.is_problem
+                })
+            })
+            .collect();
+
+        Ok(json!({ "plant": plant.display_name(), "entries": entries }))
+    }
+
+    async fn read_reminders(&self, plant: Option<Plant>, user_id: &str) -> Result<Value> {
+        let plants = match plant {
+            Some(plant) => vec![plant],
+            None => self.plant_repo.get_all_by_user(user_id).await?,
+        };
+        let zone = config::local_zone();
+        let today = zone.today();
+
+        let mut reminders = Vec::new();
+        for plant in &plants {
+            let done = self.care_task_repo.get_all_by_plant_id(&plant.id).await?;
+            for task in reminder::care_tasks(&plant.id, &done) {
+                let due = task.due_date(&plant.care_schedule, today, zone);
+                reminders.push(json!({
+                    "plant": plant.display_name(),
+                    "task": task.kind.as_str().to_lowercase(),
+                    "every_days": task.interval_days(&plant.care_schedule),
+                    "last_done": task.last_done_at.map(|at| zone.date(at)),
+                    "next_due": due,
+                    "overdue_days": (today - due).num_days().max(0)
+                }));
+            }
+        }
+
+        Ok(json!({ "today": today, "reminders": reminders }))
+    }
+
+    async fn read_diagnoses(&self, plant: Option<Plant>, finding: Option<String>, user_id: &str) -> Result<Value> {
+        let filter = DiagnosisFilter {
+            finding: finding.filter(|f| !f.trim().is_empty()),
+            ..Default::default()
+        };
+        let plant_id = plant.as_ref().map(|p| p.id.as_str());
+        let summaries = self
+            .diagnosis_repo
+            .query_summaries(user_id, plant_id, &filter, 0, MAX_DIAGNOSES)
+            .await?;
+
+        let names: Vec<Plant> = match &plant {
+            Some(plant) => vec![plant.clone()],
+            None => self.plant_repo.get_all_by_user(user_id).await?,
+        };
+        let zone = config::local_zone();
+        let sessions: Vec<Value> = summaries
+            .iter()
+            .map(|summary| {
+                json!({
+                    "plant": names
+                        .iter()
+                        .find(|p| p.id == summary.plant_id)
+                        .map(|p| p.display_name()),
+                    "date": zone.date(summary.created_at),
+                    "problem": summary.problem,
+                    "status": summary.status.as_str(),
+                    "finding": summary.result.as_ref().map(|r| r["finding"].clone()),
+                    "recommendation": summary.result.as_ref().map(|r| r["recommendation"].clone())
+                })
+            })
+            .collect();
+
+        Ok(json!({ "sessions": sessions }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::config::Database;
+    use crate::domain::{CareSchedule, CareTask, CareTaskKind, JournalEntry};
+
+    fn call(name: &str, arguments: Value) -> ToolCall {
+        ToolCall {
+            id: "call_1".to_string(),
+            name: name.to_string(),
+            arguments,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tools_read_the_collection() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let journal = JournalRepository::new(db.clone());
+        let care_tasks = CareTaskRepository::new(db.clone());
+        let tools = CollectionTools {
+            plant_repo: plants.clone(),
+            journal_repo: journal.clone(),
+            care_task_repo: care_tasks.clone(),
+            diagnosis_repo: DiagnosisRepository::new(db),
+        };
+
+        let fig = Plant::new("u".to_string(), "Fiddle leaf fig".to_string(), CareSchedule::default());
+        plants.create(&fig).await.unwrap();
+        journal
+            .create(&JournalEntry::new(fig.id.clone(), "Moved to the window".to_string(), false))
+            .await
+            .unwrap();
+        let mut watered = CareTask::new(&fig.id, CareTaskKind::Water);
+        watered.last_done_at = Some(Utc::now());
+        care_tasks.save(&watered).await.unwrap();
+
+        let listed = tools.run(&call("list_plants", json!({})), "u").await.unwrap();
+        assert_eq!(listed["plants"][0]["name"], "Fiddle leaf fig");
+        assert!(tools.run(&call("list_plants", json!({})), "v").await.unwrap()["plants"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+
+        let entries = tools
+            .run(&call("read_journal", json!({"plant": "fiddle leaf fig"})), "u")
+            .await
+            .unwrap();
+        assert_eq!(entries["entries"][0]["note"], "Moved to the window");
+        assert!(tools.run(&call("read_journal", json!({})), "u").await.is_err());
+        assert!(tools.run(&call("read_journal", json!({"plant": "Cactus"})), "u").await.is_err());
+
+        let reminders = tools.run(&call("read_reminders", Value::Null), "u").await.unwrap();
+        let today = config::local_zone().today();
+        assert_eq!(reminders["reminders"][0]["task"], "water");
+        assert_eq!(reminders["reminders"][0]["last_done"], json!(today));
+        assert!(reminders["reminders"][1]["last_done"].is_null());
+
+        assert!(tools.run(&call("water_plants", json!({})), "u").await.is_err());
+        assert_eq!(
+            describe(&call("read_journal", json!({"plant": "Fiddle leaf fig"}))),
+            "read_journal(Fiddle leaf fig)"
+        );
+    }
+}
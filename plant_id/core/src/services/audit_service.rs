@@ -0,0 +1,120 @@
+/*!
+ * AUDIT SERVICE
+ *
+ * Reads a plant's audit log back as a timeline: every change to the plant
+ * and its records, with what changed compared to the previous snapshot.
+ * Deleted plants can still be audited by ID.
+ */
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::domain::audit_event::describe_changes;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, RequestError};
+use crate::repositories::{EventRepository, PlantRepository};
+
+/// An event with the fields it changed
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub event: AuditEvent,
+    /// One line per changed field, empty unless the event is an update
+    pub changes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlantAudit {
+    pub plant_id: String,
+    pub plant_name: String,
+    /// Whether the plant itself has been deleted
+    pub deleted: bool,
+    pub entries: Vec<AuditEntry>,
+}
+
+pub struct AuditService {
+    plant_repo: PlantRepository,
+    event_repo: EventRepository,
+}
+
+impl AuditService {
+    pub fn new(plant_repo: PlantRepository, event_repo: EventRepository) -> Self {
+        Self {
+            plant_repo,
+            event_repo,
+        }
+    }
+
+    /// Timeline of a plant, found by ID or name (by ID only once deleted)
+    pub async fn plant_history(&self, identifier: &str, user_id: &str) -> Result<PlantAudit> {
+        if let Ok(plant) = self.plant_repo.find(identifier, user_id).await {
+            let events = self.event_repo.get_by_plant_id(&plant.id).await?;
+            return Ok(PlantAudit {
+                plant_name: plant.display_name().to_string(),
+                plant_id: plant.id,
+                deleted: false,
+                entries: audit_entries(events),
+            });
+        }
+
+        // The plant may have been deleted: its snapshots tell who owned it
+        let events = self.event_repo.get_by_plant_id(identifier).await?;
+        let snapshot = events
+            .iter()
+            .rev()
+            .find(|e| e.entity == AuditEntity::Plant && e.payload["user_id"] == user_id)
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        Ok(PlantAudit {
+            plant_id: identifier.to_string(),
+            plant_name: snapshot.payload["name"].as_str().unwrap_or(identifier).to_string(),
+            deleted: events
+                .iter()
+                .any(|e| e.entity == AuditEntity::Plant && e.action == AuditAction::Deleted),
+            entries: audit_entries(events),
+        })
+    }
+}
+
+/// Pair each update with the fields it changed since the previous snapshot
+/// of the same record
+pub fn audit_entries(events: Vec<AuditEvent>) -> Vec<AuditEntry> {
+    let mut last_snapshot: HashMap<String, serde_json::Value> = HashMap::new();
+
+    events
+        .into_iter()
+        .map(|event| {
+            let changes = match (event.action, last_snapshot.get(&event.entity_id)) {
+                (AuditAction::Updated, Some(previous)) => describe_changes(previous, &event.payload),
+                _ => Vec::new(),
+            };
+            last_snapshot.insert(event.entity_id.clone(), event.payload.clone());
+            AuditEntry { event, changes }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(entity_id: &str, action: AuditAction, payload: serde_json::Value) -> AuditEvent {
+        AuditEvent::new(AuditEntity::Plant, entity_id, Some("p1"), action, &payload).unwrap()
+    }
+
+    #[test]
+    fn test_audit_entries_diff_against_same_record() {
+        let entries = audit_entries(vec![
+            event("p1", AuditAction::Created, json!({"name": "Monstera"})),
+            event("d1", AuditAction::Created, json!({"status": "PENDING_USER_INPUT"})),
+            event("p1", AuditAction::Updated, json!({"name": "Monty"})),
+            event("d1", AuditAction::Updated, json!({"status": "COMPLETED"})),
+        ]);
+
+        assert!(entries[0].changes.is_empty());
+        assert_eq!(entries[2].changes, vec![r#"name: "Monstera" → "Monty""#]);
+        assert_eq!(
+            entries[3].changes,
+            vec![r#"status: "PENDING_USER_INPUT" → "COMPLETED""#]
+        );
+    }
+}
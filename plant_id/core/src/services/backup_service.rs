@@ -0,0 +1,502 @@
+/*!
+ * BACKUP SERVICE
+ *
+ * Creates full archives of a user's collection (plants, their attributes and
+ * expenses, diagnoses, journal, moisture readings, chats, care overrides and photos) and restores them into the local
+ * database.
+ *
+ * A snapshot is the same for a single plant, plus its care tasks,
+ * quarantine and inspections, taken before a risky edit. Restoring one puts
+ * the plant, its attributes, care overrides and reminders back the way they were and
+ * re-adds history records that are gone, all in one transaction; history
+ * added since is kept.
+ */
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::adapters::{storage_adapter, StoragePort};
+use crate::domain::{RequestError, ScheduleSource};
+use crate::dto::{CollectionArchiveDto, PlantSnapshotDto};
+use crate::repositories::{
+    AttributeRepository, CareOverrideRepository, CareTaskRepository, ChatRepository, DiagnosisRepository, ExpenseRepository,
+    InspectionRepository, JournalRepository, MoistureRepository, PlantRepository, QuarantineRepository,
+};
+
+/// Current archive format version
+const ARCHIVE_FORMAT_VERSION: u32 = 2;
+
+/// Current plant snapshot format version
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Outcome of restoring an archive
+#[derive(Debug, Default)]
+pub struct RestoreSummary {
+    pub plants_restored: usize,
+    /// Plants already present in the database, left untouched
+    pub plants_skipped: usize,
+}
+
+/// Outcome of restoring a plant snapshot
+#[derive(Debug, Default)]
+pub struct SnapshotRestoreSummary {
+    /// The plant had been deleted and was created again
+    pub recreated: bool,
+    /// Journal entries, readings, expenses, diagnoses and chats put back
+    pub records_restored: usize,
+}
+
+pub struct BackupService {
+    plant_repo: PlantRepository,
+    attribute_repo: AttributeRepository,
+    expense_repo: ExpenseRepository,
+    diagnosis_repo: DiagnosisRepository,
+    journal_repo: JournalRepository,
+    moisture_repo: MoistureRepository,
+    chat_repo: ChatRepository,
+    care_task_repo: CareTaskRepository,
+    quarantine_repo: QuarantineRepository,
+    inspection_repo: InspectionRepository,
+    care_override_repo: CareOverrideRepository,
+    storage_adapter: Arc<dyn StoragePort>,
+}
+
+impl BackupService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        plant_repo: PlantRepository,
+        attribute_repo: AttributeRepository,
+        expense_repo: ExpenseRepository,
+        diagnosis_repo: DiagnosisRepository,
+        journal_repo: JournalRepository,
+        moisture_repo: MoistureRepository,
+        chat_repo: ChatRepository,
+        care_task_repo: CareTaskRepository,
+        quarantine_repo: QuarantineRepository,
+        inspection_repo: InspectionRepository,
+        care_override_repo: CareOverrideRepository,
+        storage_adapter: Arc<dyn StoragePort>,
+    ) -> Self {
+        Self {
+            plant_repo,
+            attribute_repo,
+            expense_repo,
+            diagnosis_repo,
+            journal_repo,
+            moisture_repo,
+            chat_repo,
+            care_task_repo,
+            quarantine_repo,
+            inspection_repo,
+            care_override_repo,
+            storage_adapter,
+        }
+    }
+
+    pub async fn create_archive(&self, user_id: &str) -> Result<CollectionArchiveDto> {
+        let mut plants = self.plant_repo.get_all_by_user(user_id).await?;
+        plants.extend(self.plant_repo.get_archived_by_user(user_id).await?);
+
+        let mut archive = CollectionArchiveDto {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            plants: Vec::new(),
+            attributes: Vec::new(),
+            expenses: Vec::new(),
+            diagnoses: Vec::new(),
+            diagnosis_messages: Vec::new(),
+            journal: Vec::new(),
+            moisture_readings: Vec::new(),
+            chats: Vec::new(),
+            care_overrides: Vec::new(),
+            images: HashMap::new(),
+        };
+
+        for plant in plants {
+            archive
+                .attributes
+                .extend(self.attribute_repo.get_all_by_plant_id(&plant.id).await?);
+            archive
+                .expenses
+                .extend(self.expense_repo.get_all_by_plant_id(&plant.id).await?);
+            for session in self
+                .diagnosis_repo
+                .get_all_by_plant_id(&plant.id, user_id)
+                .await?
+            {
+                archive
+                    .diagnosis_messages
+                    .extend(self.diagnosis_repo.get_messages(&session.id, 0, None).await?);
+                archive.diagnoses.push(session);
+            }
+            archive
+                .journal
+                .extend(self.journal_repo.get_all_by_plant_id(&plant.id).await?);
+            archive
+                .moisture_readings
+                .extend(self.moisture_repo.get_all_by_plant_id(&plant.id).await?);
+            archive
+                .chats
+                .extend(self.chat_repo.get_all_by_plant_id(&plant.id).await?);
+            archive
+                .care_overrides
+                .extend(self.care_override_repo.get_by_plant_id(&plant.id).await?);
+
+            if let Some(url) = &plant.image_url {
+                match self.storage_adapter.read_image(url).await {
+                    Ok(bytes) => {
+                        archive.images.insert(plant.id.clone(), BASE64.encode(bytes));
+                    }
+                    Err(e) => log::warn!("Could not archive image for plant {}: {:#}", plant.id, e),
+                }
+            }
+
+            archive.plants.push(plant);
+        }
+
+        Ok(archive)
+    }
+
+    /// Restore an archive for `user_id`. Plants that already exist are
+    /// skipped along with their history, so restoring twice is harmless.
+    pub async fn restore_archive(
+        &self,
+        archive: CollectionArchiveDto,
+        user_id: &str,
+    ) -> Result<RestoreSummary> {
+        if archive.format_version > ARCHIVE_FORMAT_VERSION {
+            anyhow::bail!(
+                "Archive format version {} is newer than supported version {}",
+                archive.format_version,
+                ARCHIVE_FORMAT_VERSION
+            );
+        }
+
+        let mut summary = RestoreSummary::default();
+        let mut restored_ids = Vec::new();
+
+        for mut plant in archive.plants {
+            if self.plant_repo.get_by_id(&plant.id, user_id).await?.is_some() {
+                summary.plants_skipped += 1;
+                continue;
+            }
+
+            plant.user_id = user_id.to_string();
+            plant.image_url = None;
+            plant.image_hash = None;
+            if let Some(encoded) = archive.images.get(&plant.id) {
+                let image_data = BASE64
+                    .decode(encoded)
+                    .context("Failed to decode archived image")?;
+                let (url, hash) =
+                    storage_adapter::store_by_hash(self.storage_adapter.as_ref(), &image_data).await?;
+                plant.image_url = Some(url);
+                plant.image_hash = Some(hash);
+            }
+
+            self.plant_repo
+                .create_with_schedule_source(&plant, ScheduleSource::Imported)
+                .await?;
+            restored_ids.push(plant.id);
+            summary.plants_restored += 1;
+        }
+
+        for attribute in archive.attributes.iter().filter(|a| restored_ids.contains(&a.plant_id)) {
+            self.attribute_repo.save(attribute).await?;
+        }
+        for expense in archive.expenses.iter().filter(|e| restored_ids.contains(&e.plant_id)) {
+            self.expense_repo.create(expense).await?;
+        }
+        for mut session in archive
+            .diagnoses
+            .into_iter()
+            .filter(|s| restored_ids.contains(&s.plant_id))
+        {
+            let legacy_messages = session.take_legacy_history();
+            self.diagnosis_repo.create(&session).await?;
+
+            let messages = archive
+                .diagnosis_messages
+                .iter()
+                .filter(|m| m.session_id == session.id)
+                .chain(&legacy_messages);
+            for message in messages {
+                self.diagnosis_repo.append_message(&session, message).await?;
+            }
+        }
+        for entry in archive.journal.iter().filter(|e| restored_ids.contains(&e.plant_id)) {
+            self.journal_repo.create(entry).await?;
+        }
+        for reading in archive
+            .moisture_readings
+            .iter()
+            .filter(|r| restored_ids.contains(&r.plant_id))
+        {
+            self.moisture_repo.create(reading).await?;
+        }
+        for chat in archive.chats.iter().filter(|c| restored_ids.contains(&c.plant_id)) {
+            self.chat_repo.create(chat).await?;
+        }
+        for care in archive.care_overrides.iter().filter(|c| restored_ids.contains(&c.plant_id)) {
+            self.care_override_repo.save(care).await?;
+        }
+
+        Ok(summary)
+    }
+
+    /// Everything recorded about one plant, as it is now
+    pub async fn create_snapshot(&self, plant_id: &str, user_id: &str) -> Result<PlantSnapshotDto> {
+        let plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        let image = match &plant.image_url {
+            Some(url) => match self.storage_adapter.read_image(url).await {
+                Ok(bytes) => Some(BASE64.encode(bytes)),
+                Err(e) => {
+                    log::warn!("Could not snapshot image for plant {}: {:#}", plant.id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let diagnoses = self.diagnosis_repo.get_all_by_plant_id(&plant.id, user_id).await?;
+        let mut diagnosis_messages = Vec::new();
+        for session in &diagnoses {
+            diagnosis_messages.extend(self.diagnosis_repo.get_messages(&session.id, 0, None).await?);
+        }
+
+        Ok(PlantSnapshotDto {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            taken_at: Utc::now(),
+            image,
+            attributes: self.attribute_repo.get_all_by_plant_id(&plant.id).await?,
+            care_tasks: self.care_task_repo.get_all_by_plant_id(&plant.id).await?,
+            quarantine: self.quarantine_repo.get_active_by_plant_id(&plant.id).await?,
+            inspections: self.inspection_repo.get_open_by_plant_id(&plant.id).await?,
+            expenses: self.expense_repo.get_all_by_plant_id(&plant.id).await?,
+            diagnoses,
+            diagnosis_messages,
+            journal: self.journal_repo.get_all_by_plant_id(&plant.id).await?,
+            moisture_readings: self.moisture_repo.get_all_by_plant_id(&plant.id).await?,
+            chats: self.chat_repo.get_all_by_plant_id(&plant.id).await?,
+            care_overrides: self.care_override_repo.get_by_plant_id(&plant.id).await?,
+            plant,
+        })
+    }
+
+    /// Put a plant back the way it was in `snapshot`, creating it again if
+    /// it was deleted. History records added since are kept.
+    pub async fn restore_snapshot(&self, snapshot: PlantSnapshotDto, user_id: &str) -> Result<SnapshotRestoreSummary> {
+        if snapshot.format_version > SNAPSHOT_FORMAT_VERSION {
+            anyhow::bail!(
+                "Snapshot format version {} is newer than supported version {}",
+                snapshot.format_version,
+                SNAPSHOT_FORMAT_VERSION
+            );
+        }
+
+        check_snapshot_records(&snapshot)?;
+
+        let mut snapshot = snapshot;
+        let plant = &mut snapshot.plant;
+        plant.user_id = user_id.to_string();
+        plant.image_url = None;
+        plant.image_hash = None;
+        if let Some(encoded) = &snapshot.image {
+            let image_data = BASE64.decode(encoded).context("Failed to decode snapshot image")?;
+            let (url, hash) = storage_adapter::store_by_hash(self.storage_adapter.as_ref(), &image_data).await?;
+            plant.image_url = Some(url);
+            plant.image_hash = Some(hash);
+        }
+
+        // One transaction, so a failure leaves the plant as it was
+        let (recreated, records_restored) = self.plant_repo.restore_snapshot(&snapshot).await?;
+
+        Ok(SnapshotRestoreSummary {
+            recreated,
+            records_restored,
+        })
+    }
+}
+
+/// Make sure every record in a snapshot belongs to its plant, so restoring
+/// one can't write into another plant's history
+fn check_snapshot_records(snapshot: &PlantSnapshotDto) -> Result<()> {
+    let plant_id = snapshot.plant.id.as_str();
+    let plant_ids = snapshot
+        .attributes
+        .iter()
+        .map(|a| ("attribute", a.plant_id.as_str()))
+        .chain(snapshot.care_tasks.iter().map(|t| ("care task", t.plant_id.as_str())))
+        .chain(snapshot.quarantine.iter().map(|q| ("quarantine", q.plant_id.as_str())))
+        .chain(snapshot.inspections.iter().map(|i| ("inspection", i.plant_id.as_str())))
+        .chain(snapshot.expenses.iter().map(|e| ("expense", e.plant_id.as_str())))
+        .chain(snapshot.diagnoses.iter().map(|d| ("diagnosis", d.plant_id.as_str())))
+        .chain(snapshot.journal.iter().map(|e| ("journal entry", e.plant_id.as_str())))
+        .chain(snapshot.moisture_readings.iter().map(|r| ("moisture reading", r.plant_id.as_str())))
+        .chain(snapshot.chats.iter().map(|c| ("chat", c.plant_id.as_str())))
+        .chain(snapshot.care_overrides.iter().map(|c| ("care overrides", c.plant_id.as_str())));
+    for (kind, id) in plant_ids {
+        if id != plant_id {
+            anyhow::bail!("Snapshot has a {} of another plant ({})", kind, id);
+        }
+    }
+
+    let session_ids: HashSet<&str> = snapshot.diagnoses.iter().map(|d| d.id.as_str()).collect();
+    if let Some(message) = snapshot
+        .diagnosis_messages
+        .iter()
+        .find(|m| !session_ids.contains(m.session_id.as_str()))
+    {
+        anyhow::bail!(
+            "Snapshot has a diagnosis message of a session it doesn't include ({})",
+            message.session_id
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::storage_adapter::LocalStorageAdapter;
+    use crate::config::Database;
+    use crate::domain::enums::CareTaskKind;
+    use crate::domain::{CareSchedule, CareTask, DiagnosisMessage, JournalEntry, Plant, PlantAttribute};
+
+    #[tokio::test]
+    async fn test_snapshot_puts_a_plant_back() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let attributes = AttributeRepository::new(db.clone());
+        let tasks = CareTaskRepository::new(db.clone());
+        let journal = JournalRepository::new(db.clone());
+        let service = BackupService::new(
+            plants.clone(),
+            attributes.clone(),
+            ExpenseRepository::new(db.clone()),
+            DiagnosisRepository::new(db.clone()),
+            journal.clone(),
+            MoistureRepository::new(db.clone()),
+            ChatRepository::new(db.clone()),
+            tasks.clone(),
+            QuarantineRepository::new(db.clone()),
+            InspectionRepository::new(db.clone()),
+            CareOverrideRepository::new(db.clone()),
+            Arc::new(LocalStorageAdapter::new()),
+        );
+
+        let mut plant = Plant::new("u".to_string(), "Monstera".to_string(), CareSchedule::default());
+        plants.create(&plant).await.unwrap();
+        plant.care_schedule.water = "Every 4 days".to_string();
+        plants.update(&plant).await.unwrap();
+        attributes.save(&PlantAttribute::new(&plant.id, "pot", "terracotta").unwrap()).await.unwrap();
+        let before = JournalEntry::new(plant.id.clone(), "Repotted".to_string(), false);
+        journal.create(&before).await.unwrap();
+        let snapshot = service.create_snapshot(&plant.id, "u").await.unwrap();
+        assert_eq!(snapshot.attributes.len(), 1);
+        assert_eq!(snapshot.journal.len(), 1);
+        assert_eq!(snapshot.care_overrides.as_ref().unwrap().overrides.water.as_deref(), Some("Every 4 days"));
+
+        // A risky edit, plus a note worth keeping
+        plant.name = "Renamed".to_string();
+        plant.care_schedule.water = "Every 7 days".to_string();
+        plants.update(&plant).await.unwrap();
+        attributes.save(&PlantAttribute::new(&plant.id, "soil", "peat").unwrap()).await.unwrap();
+        let mut watered = CareTask::new(&plant.id, CareTaskKind::Water);
+        watered.last_done_at = Some(Utc::now());
+        tasks.save(&watered).await.unwrap();
+        journal
+            .create(&JournalEntry::new(plant.id.clone(), "New leaf".to_string(), false))
+            .await
+            .unwrap();
+
+        let summary = service.restore_snapshot(snapshot.clone(), "u").await.unwrap();
+        assert!(!summary.recreated);
+        assert_eq!(summary.records_restored, 0);
+        assert_eq!(plants.get_by_id(&plant.id, "u").await.unwrap().unwrap().name, "Monstera");
+        let keys: Vec<String> = attributes
+            .get_all_by_plant_id(&plant.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| a.key)
+            .collect();
+        assert_eq!(keys, vec!["pot"]);
+        let care = service.care_override_repo.get_by_plant_id(&plant.id).await.unwrap().unwrap();
+        assert_eq!(care.overrides.water.as_deref(), Some("Every 4 days"));
+        assert!(tasks.get_all_by_plant_id(&plant.id).await.unwrap()[0].last_done_at.is_none());
+        assert_eq!(journal.get_all_by_plant_id(&plant.id).await.unwrap().len(), 2);
+
+        // A restore that fails halfway changes nothing
+        plant.name = "Renamed again".to_string();
+        plants.update(&plant).await.unwrap();
+        let other = Plant::new("u".to_string(), "Ivy".to_string(), CareSchedule::default());
+        plants.create(&other).await.unwrap();
+        let taken = JournalEntry::new(other.id.clone(), "Repotted".to_string(), false);
+        journal.create(&taken).await.unwrap();
+        let mut conflicting = snapshot.clone();
+        conflicting.attributes.clear();
+        conflicting.journal.push(JournalEntry { plant_id: plant.id.clone(), ..taken });
+        assert!(service.restore_snapshot(conflicting, "u").await.is_err());
+        assert_eq!(plants.get_by_id(&plant.id, "u").await.unwrap().unwrap().name, "Renamed again");
+        assert_eq!(attributes.get_all_by_plant_id(&plant.id).await.unwrap().len(), 1);
+
+        // A deleted plant comes back with its history
+        plants.delete(&plant.id, "u").await.unwrap();
+        let summary = service.restore_snapshot(snapshot, "u").await.unwrap();
+        assert!(summary.recreated);
+        assert_eq!(summary.records_restored, 1);
+        assert_eq!(journal.get_all_by_plant_id(&plant.id).await.unwrap()[0].id, before.id);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_with_another_plants_records_is_refused() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let attributes = AttributeRepository::new(db.clone());
+        let service = BackupService::new(
+            plants.clone(),
+            attributes.clone(),
+            ExpenseRepository::new(db.clone()),
+            DiagnosisRepository::new(db.clone()),
+            JournalRepository::new(db.clone()),
+            MoistureRepository::new(db.clone()),
+            ChatRepository::new(db.clone()),
+            CareTaskRepository::new(db.clone()),
+            QuarantineRepository::new(db.clone()),
+            InspectionRepository::new(db.clone()),
+            CareOverrideRepository::new(db.clone()),
+            Arc::new(LocalStorageAdapter::new()),
+        );
+
+        let plant = Plant::new("u".to_string(), "Monstera".to_string(), CareSchedule::default());
+        plants.create(&plant).await.unwrap();
+        let other = Plant::new("u".to_string(), "Ivy".to_string(), CareSchedule::default());
+        plants.create(&other).await.unwrap();
+        let snapshot = service.create_snapshot(&plant.id, "u").await.unwrap();
+
+        let mut foreign = snapshot.clone();
+        foreign.attributes.push(PlantAttribute::new(&other.id, "pot", "plastic").unwrap());
+        let err = service.restore_snapshot(foreign, "u").await.unwrap_err();
+        assert!(err.to_string().contains("another plant"));
+        assert!(attributes.get_all_by_plant_id(&other.id).await.unwrap().is_empty());
+
+        let mut orphaned = snapshot;
+        orphaned
+            .diagnosis_messages
+            .push(DiagnosisMessage::new("unknown".to_string(), "user", "Yellow leaves".to_string()));
+        let err = service.restore_snapshot(orphaned, "u").await.unwrap_err();
+        assert!(err.to_string().contains("session it doesn't include"));
+    }
+}
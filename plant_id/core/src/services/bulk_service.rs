@@ -0,0 +1,197 @@
+/*!
+ * BULK SERVICE
+ *
+ * Applies the same changes (tags, room moves, a schedule template,
+ * archiving) to every plant matching a filter. Changes are previewed
+ * first and saved together in one transaction. Archiving needs the
+ * permission to remove plants, which a plant sitter does not have.
+ */
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+use crate::domain::{ArchiveReason, Permission, Plant, PlantArchive, PlantFilter, ScheduleSource};
+use crate::repositories::{PlantRepository, TemplateRepository, UserRepository};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkAction {
+    AddTag(String),
+    RemoveTag(String),
+    /// Move to a room, or out of any room
+    MoveToRoom(Option<String>),
+    /// Replace the care schedule with a template's, by template name
+    ApplyTemplate(String),
+    /// Take the plants out of the collection, keeping their records
+    Archive(ArchiveReason),
+}
+
+/// A matching plant as it will be saved, with what changes
+#[derive(Debug, Clone)]
+pub struct BulkChange {
+    pub plant: Plant,
+    pub changes: Vec<String>,
+    /// Template the care schedule was replaced with, if any
+    pub template: Option<String>,
+}
+
+pub struct BulkService {
+    plant_repo: PlantRepository,
+    template_repo: TemplateRepository,
+    user_repo: UserRepository,
+}
+
+impl BulkService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        template_repo: TemplateRepository,
+        user_repo: UserRepository,
+    ) -> Self {
+        Self {
+            plant_repo,
+            template_repo,
+            user_repo,
+        }
+    }
+
+    /// Compute the changes for every plant in the collection matching the
+    /// filter, without saving anything. Plants the actions would leave
+    /// unchanged are left out.
+    pub async fn preview(
+        &self,
+        filter: &PlantFilter,
+        actions: &[BulkAction],
+        user_id: &str,
+    ) -> Result<Vec<BulkChange>> {
+        if actions.is_empty() {
+            anyhow::bail!("No changes given");
+        }
+        if actions.iter().any(|a| matches!(a, BulkAction::Archive(_))) {
+            self.user_repo.role(user_id).await?.require(Permission::RemovePlants)?;
+        }
+
+        let mut schedule = None;
+        for action in actions {
+            if let BulkAction::ApplyTemplate(name) = action {
+                let template = self
+                    .template_repo
+                    .get_by_name(name, user_id)
+                    .await?
+                    .with_context(|| format!("Template '{}' not found", name))?;
+                schedule = Some((template.name, template.schedule));
+            }
+        }
+
+        let now = Utc::now();
+        let mut preview = Vec::new();
+        for mut plant in self.plant_repo.get_all_by_user(user_id).await? {
+            if !filter.matches(&plant) {
+                continue;
+            }
+
+            let mut changes = Vec::new();
+            let mut template = None;
+            for action in actions {
+                match action {
+                    BulkAction::AddTag(tag) => {
+                        if plant.add_tag(tag) {
+                            changes.push(format!("+tag {}", tag.trim().to_lowercase()));
+                        }
+                    }
+                    BulkAction::RemoveTag(tag) => {
+                        if plant.remove_tag(tag) {
+                            changes.push(format!("-tag {}", tag.trim().to_lowercase()));
+                        }
+                    }
+                    BulkAction::MoveToRoom(room) => {
+                        if plant.room != *room {
+                            changes.push(match room {
+                                Some(room) => format!("move to {}", room),
+                                None => "remove from room".to_string(),
+                            });
+                            plant.room = room.clone();
+                        }
+                    }
+                    BulkAction::ApplyTemplate(_) => {
+                        if let Some((name, schedule)) = &schedule {
+                            plant.care_schedule = schedule.clone();
+                            changes.push(format!("care schedule from template {}", name));
+                            template = Some(name.clone());
+                        }
+                    }
+                    BulkAction::Archive(reason) => {
+                        plant.archived = Some(PlantArchive {
+                            reason: *reason,
+                            archived_at: now,
+                        });
+                        changes.push(format!("archive ({})", reason.as_str().to_lowercase()));
+                    }
+                }
+            }
+
+            if !changes.is_empty() {
+                plant.updated_at = now;
+                preview.push(BulkChange {
+                    plant,
+                    changes,
+                    template,
+                });
+            }
+        }
+
+        Ok(preview)
+    }
+
+    /// Save previewed changes, all or none
+    pub async fn apply(&self, changes: &[BulkChange], user_id: &str) -> Result<usize> {
+        // Only archiving takes plants out of the collection
+        if changes.iter().any(|c| c.plant.archived.is_some()) {
+            self.user_repo.role(user_id).await?.require(Permission::RemovePlants)?;
+        }
+
+        let plants: Vec<Plant> = changes.iter().map(|c| c.plant.clone()).collect();
+        // Every change of a preview applies the same template, if any
+        let source = match changes.iter().find_map(|c| c.template.clone()) {
+            Some(name) => ScheduleSource::Template { name },
+            None => ScheduleSource::Manual,
+        };
+        self.plant_repo.update_many(&plants, &source).await?;
+
+        Ok(plants.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use crate::domain::{CareSchedule, User, UserRole};
+
+    #[tokio::test]
+    async fn test_sitter_cannot_bulk_archive() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let users = UserRepository::new(db.clone());
+        let service = BulkService::new(plants.clone(), TemplateRepository::new(db), users.clone());
+
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&fern).await.unwrap();
+        let archive = [BulkAction::Archive(ArchiveReason::Died)];
+        let everything = PlantFilter::default();
+        let changes = service.preview(&everything, &archive, "u").await.unwrap();
+
+        let mut sitter = User::owner("u".to_string());
+        sitter.role = UserRole::Sitter;
+        users.save(&sitter).await.unwrap();
+
+        let error = service.preview(&everything, &archive, "u").await.unwrap_err();
+        assert!(error.to_string().contains("cannot remove plants"));
+        assert!(service.apply(&changes, "u").await.is_err());
+        assert_eq!(plants.get_all_by_user("u").await.unwrap().len(), 1);
+
+        // Other changes are still allowed
+        let tag = [BulkAction::AddTag("shelf".to_string())];
+        let changes = service.preview(&everything, &tag, "u").await.unwrap();
+        assert_eq!(service.apply(&changes, "u").await.unwrap(), 1);
+    }
+}
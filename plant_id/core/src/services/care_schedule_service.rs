@@ -0,0 +1,363 @@
+/*!
+ * CARE SCHEDULE SERVICE
+ *
+ * Generates care schedules with the AI and sanity-checks them before they
+ * are persisted. Schedules that fail validation are regenerated a limited
+ * number of times. Manual edits are checked the same way.
+ *
+ * When a species database is configured, the fields it covers replace the
+ * AI's answers; the AI still fills in the rest.
+ *
+ * Every saved schedule becomes a new version of the plant's care schedule,
+ * recording where it came from. The latest version tells which schedules
+ * have gone stale, and earlier ones can be rolled back to. Regenerating
+ * calls the AI for each plant, so a plant sitter cannot do it.
+ */
+
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::adapters::{AiAdapter, ProgressReporter, SpeciesDataPort};
+use crate::domain::validation::{validate_care_schedule, validate_care_schedule_update};
+use crate::domain::{
+    CareSchedule, CareScheduleUpdate, CareScheduleVersion, Growth, MergedField, Permission, Plant, RoomProfile,
+    ScheduleSource, SpeciesProfile, Staleness,
+};
+use crate::repositories::{
+    CareOverrideRepository, PlantRepository, RoomRepository, ScheduleVersionRepository, UserRepository,
+};
+
+/// How many times a schedule is generated before giving up
+const MAX_GENERATION_ATTEMPTS: usize = 3;
+
+/// A validated care schedule together with any warnings raised
+#[derive(Debug, Clone)]
+pub struct GeneratedCareSchedule {
+    pub schedule: CareSchedule,
+    pub warnings: Vec<String>,
+    /// Species data the schedule is based on, when a database knew the plant
+    pub reference: Option<SpeciesProfile>,
+}
+
+/// Outcome of regenerating schedules for several plants
+#[derive(Debug, Default)]
+pub struct RegenerationReport {
+    /// Updated plants with the version recorded for their new schedule and
+    /// any warnings raised for it
+    pub updated: Vec<(Plant, CareScheduleVersion, Vec<String>)>,
+    /// Plants whose schedule could not be regenerated, with the reason
+    pub failed: Vec<(Plant, String)>,
+}
+
+#[derive(Clone)]
+pub struct CareScheduleService {
+    ai_adapter: AiAdapter,
+    species_data: Option<Arc<dyn SpeciesDataPort>>,
+}
+
+impl CareScheduleService {
+    pub fn new(ai_adapter: AiAdapter, species_data: Option<Arc<dyn SpeciesDataPort>>) -> Self {
+        Self {
+            ai_adapter,
+            species_data,
+        }
+    }
+
+    /// Look a plant up in the configured species database. Failures are
+    /// logged, as the AI can still generate the whole schedule.
+    pub async fn lookup_species(&self, plant_name: &str) -> Option<SpeciesProfile> {
+        let species_data = self.species_data.as_ref()?;
+        match species_data.lookup(plant_name).await {
+            Ok(profile) => profile,
+            Err(e) => {
+                log::warn!("{} lookup failed for {}: {:#}", species_data.name(), plant_name, e);
+                None
+            }
+        }
+    }
+
+    /// Generate a care schedule, regenerating any that fail validation. The
+    /// specimen's `growth`, when known, lets the AI suit it to its maturity,
+    /// and its `room` to where it lives.
+    pub async fn generate(
+        &self,
+        plant_name: &str,
+        growth: Option<&Growth>,
+        room: Option<&RoomProfile>,
+    ) -> Result<GeneratedCareSchedule> {
+        let reference = self.lookup_species(plant_name).await;
+        let mut last_errors = Vec::new();
+
+        for attempt in 1..=MAX_GENERATION_ATTEMPTS {
+            let generated = self.ai_adapter.generate_care_schedule(plant_name, growth, room).await?;
+            let (schedule, reference) = match &reference {
+                Some(profile) => apply_reference(generated, profile),
+                None => (generated, None),
+            };
+            let report = validate_care_schedule(&schedule);
+
+            if report.is_valid() {
+                let mut warnings = report.warnings;
+                if attempt > 1 {
+                    warnings.insert(
+                        0,
+                        format!(
+                            "Schedule was regenerated {} time(s) after failing validation ({})",
+                            attempt - 1,
+                            last_errors.join("; ")
+                        ),
+                    );
+                }
+
+                return Ok(GeneratedCareSchedule {
+                    schedule,
+                    warnings,
+                    reference,
+                });
+            }
+
+            log::warn!(
+                "Rejected care schedule for {} (attempt {}): {}",
+                plant_name,
+                attempt,
+                report.errors.join("; ")
+            );
+            last_errors = report.errors;
+        }
+
+        anyhow::bail!(
+            "AI returned an invalid care schedule {} times: {}",
+            MAX_GENERATION_ATTEMPTS,
+            last_errors.join("; ")
+        )
+    }
+
+    /// Generate schedules for several plants, given by name, growth and
+    /// room, concurrently, with at most `max_parallel` AI calls in flight. Results
+    /// are in input order; each finished plant is reported to `progress`.
+    async fn generate_many(
+        &self,
+        plants: &[(String, Option<Growth>, Option<RoomProfile>)],
+        max_parallel: usize,
+        progress: &dyn ProgressReporter,
+    ) -> Vec<Result<GeneratedCareSchedule>> {
+        let semaphore = Arc::new(Semaphore::new(max_parallel.max(1)));
+        let mut tasks = JoinSet::new();
+
+        for (index, (name, growth, room)) in plants.iter().enumerate() {
+            let service = self.clone();
+            let semaphore = semaphore.clone();
+            let name = name.clone();
+            let growth = growth.clone();
+            let room = room.clone();
+            tasks.spawn(async move {
+                // The semaphore is never closed, so acquiring cannot fail
+                let _permit = semaphore.acquire_owned().await;
+                (index, service.generate(&name, growth.as_ref(), room.as_ref()).await)
+            });
+        }
+
+        let mut results: Vec<Option<Result<GeneratedCareSchedule>>> = plants.iter().map(|_| None).collect();
+        let mut finished = 0;
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((index, result)) => {
+                    finished += 1;
+                    progress.step(&format!(
+                        "Finished {}/{}: {}",
+                        finished,
+                        plants.len(),
+                        plants[index].0
+                    ));
+                    results[index] = Some(result);
+                }
+                Err(e) => log::error!("Care schedule task failed: {}", e),
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| Err(anyhow::anyhow!("Generation task did not complete"))))
+            .collect()
+    }
+
+    /// Plants of a user whose schedule is older than `max_age` or was
+    /// generated by another model than the current one
+    pub async fn find_stale(
+        &self,
+        plant_repo: &PlantRepository,
+        version_repo: &ScheduleVersionRepository,
+        user_id: &str,
+        max_age: Duration,
+    ) -> Result<Vec<(Plant, Staleness)>> {
+        let now = Utc::now();
+        let mut stale = Vec::new();
+
+        for plant in plant_repo.get_all_by_user(user_id).await? {
+            let latest = version_repo.get_latest_by_plant_id(&plant.id).await?;
+            if let Some(staleness) = Staleness::of(&plant, latest.as_ref(), max_age, self.ai_adapter.model(), now) {
+                stale.push((plant, staleness));
+            }
+        }
+
+        Ok(stale)
+    }
+
+    /// Regenerate and save the schedules of the given plants, recording
+    /// each new schedule as a version generated by the current model. Each
+    /// is written for the room the plant is in. Fails before any AI call
+    /// unless the owners of the plants may start AI sessions.
+    pub async fn regenerate(
+        &self,
+        plant_repo: &PlantRepository,
+        room_repo: &RoomRepository,
+        user_repo: &UserRepository,
+        plants: Vec<Plant>,
+        max_parallel: usize,
+        progress: &dyn ProgressReporter,
+    ) -> Result<RegenerationReport> {
+        let users: BTreeSet<&str> = plants.iter().map(|p| p.user_id.as_str()).collect();
+        for user_id in users {
+            user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+        }
+
+        let now = Utc::now();
+        let mut requests = Vec::new();
+        for plant in &plants {
+            let room = room_repo.profile_for(plant).await?;
+            requests.push((plant.species().to_string(), Some(Growth::of(plant, now)), room));
+        }
+        let results = self.generate_many(&requests, max_parallel, progress).await;
+        progress.step("Saving care schedules...");
+        let mut report = RegenerationReport::default();
+
+        for (mut plant, result) in plants.into_iter().zip(results) {
+            match result {
+                Ok(generated) => {
+                    plant.care_schedule = generated.schedule;
+                    plant.updated_at = Utc::now();
+                    let source = ScheduleSource::Ai {
+                        model: self.ai_adapter.model().to_string(),
+                    };
+                    let version = plant_repo.update_schedule(&plant, source).await?;
+                    // The owner's overrides stay over the new schedule
+                    plant.care_schedule = version.schedule.clone();
+                    report.updated.push((plant, version, generated.warnings));
+                }
+                Err(e) => report.failed.push((plant, format!("{:#}", e))),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Apply manual changes to a plant's care schedule and save it, returning
+/// any warnings raised for the new schedule
+pub async fn edit_plant_schedule(
+    plant_repo: &PlantRepository,
+    plant: &mut Plant,
+    update: &CareScheduleUpdate,
+) -> Result<Vec<String>> {
+    let (schedule, warnings) = validate_care_schedule_update(&plant.care_schedule, update)?;
+
+    plant.care_schedule = schedule;
+    plant.updated_at = Utc::now();
+    plant_repo.update(plant).await?;
+
+    Ok(warnings)
+}
+
+/// Drop the owner's changes to a plant's care schedule, going back to its
+/// species baseline. Returns the fields that were changed.
+pub async fn reset_overrides(
+    plant_repo: &PlantRepository,
+    override_repo: &CareOverrideRepository,
+    plant: &mut Plant,
+) -> Result<Vec<MergedField>> {
+    let care = override_repo.get(plant).await?;
+    let reset: Vec<MergedField> = care.fields().into_iter().filter(|f| f.baseline.is_some()).collect();
+    if reset.is_empty() {
+        return Ok(reset);
+    }
+
+    // An edit back to the baseline leaves nothing to override
+    plant.care_schedule = care.baseline;
+    plant.updated_at = Utc::now();
+    plant_repo.update(plant).await?;
+
+    Ok(reset)
+}
+
+/// Restore the care schedule of an earlier version, saved as a new version
+pub async fn rollback_schedule(
+    plant_repo: &PlantRepository,
+    version_repo: &ScheduleVersionRepository,
+    plant: &mut Plant,
+    version: u32,
+) -> Result<CareScheduleVersion> {
+    let earlier = version_repo
+        .get_by_version(&plant.id, version)
+        .await?
+        .with_context(|| format!("{} has no care schedule version {}", plant.display_name(), version))?;
+
+    plant.care_schedule = earlier.schedule;
+    plant.updated_at = Utc::now();
+    plant_repo.update_schedule(plant, ScheduleSource::Rollback { version }).await
+}
+
+/// Replace the AI's answers with the species database's where it has them.
+/// The reference is returned only when it was applied.
+fn apply_reference(
+    schedule: CareSchedule,
+    profile: &SpeciesProfile,
+) -> (CareSchedule, Option<SpeciesProfile>) {
+    if profile.care_fields().is_empty() {
+        return (schedule, None);
+    }
+
+    match validate_care_schedule_update(&schedule, &profile.care_update()) {
+        Ok((based, _)) => (based, Some(profile.clone())),
+        Err(e) => {
+            log::warn!("Ignoring {} data for {}: {:#}", profile.source, profile.scientific_name, e);
+            (schedule, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SilentProgress;
+    use crate::config::Database;
+    use crate::domain::{User, UserRole};
+
+    #[tokio::test]
+    async fn test_sitter_cannot_regenerate() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        // Only needed to build the adapter; the AI is never called
+        std::env::set_var("OPENROUTER_API_KEY", "test-key");
+        let plants = PlantRepository::new(db.clone());
+        let users = UserRepository::new(db.clone());
+        let service = CareScheduleService::new(AiAdapter::new().unwrap(), None);
+
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&fern).await.unwrap();
+        let mut sitter = User::owner("u".to_string());
+        sitter.role = UserRole::Sitter;
+        users.save(&sitter).await.unwrap();
+
+        let error = service
+            .regenerate(&plants, &RoomRepository::new(db), &users, vec![fern.clone()], 1, &SilentProgress)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("cannot start AI sessions"));
+        let saved = plants.get_by_id(&fern.id, "u").await.unwrap().unwrap();
+        assert_eq!(saved.updated_at, fern.updated_at);
+    }
+}
@@ -0,0 +1,191 @@
+//! CHAT SERVICE
+//!
+//! Open-ended conversation about a plant. Each chat is grounded in the
+//! plant's vitals, custom attributes, journal, and past diagnoses, but -
+//! unlike the diagnostic kernel - the AI simply replies in prose rather than
+//! following the structured action protocol.
+
+use anyhow::Result;
+
+use crate::adapters::AiAdapter;
+use crate::config;
+use crate::domain::{ChatSession, DiagnosisState, Permission, Plant, RequestError};
+use crate::repositories::{
+    AttributeRepository, ChatRepository, DiagnosisRepository, JournalRepository, PlantRepository,
+    UserRepository,
+};
+
+/// Maximum number of journal entries included in the chat context
+const MAX_JOURNAL_ENTRIES: usize = 10;
+
+/// Maximum number of past diagnoses included in the chat context
+const MAX_DIAGNOSES: usize = 5;
+
+pub struct ChatService {
+    plant_repo: PlantRepository,
+    attribute_repo: AttributeRepository,
+    diagnosis_repo: DiagnosisRepository,
+    journal_repo: JournalRepository,
+    chat_repo: ChatRepository,
+    user_repo: UserRepository,
+    ai_adapter: AiAdapter,
+}
+
+impl ChatService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        attribute_repo: AttributeRepository,
+        diagnosis_repo: DiagnosisRepository,
+        journal_repo: JournalRepository,
+        chat_repo: ChatRepository,
+        user_repo: UserRepository,
+        ai_adapter: AiAdapter,
+    ) -> Self {
+        Self {
+            plant_repo,
+            attribute_repo,
+            diagnosis_repo,
+            journal_repo,
+            chat_repo,
+            user_repo,
+            ai_adapter,
+        }
+    }
+
+    /// Start a new chat session for a plant
+    pub async fn start_chat(&self, plant_id: &str, user_id: &str) -> Result<ChatSession> {
+        self.user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+
+        // Verify plant exists and belongs to user
+        let _ = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        let session = ChatSession::new(plant_id.to_string());
+        self.chat_repo.create(&session).await
+    }
+
+    /// Resume the most recent chat session for a plant, if any
+    pub async fn resume_chat(&self, plant_id: &str, user_id: &str) -> Result<Option<ChatSession>> {
+        let _ = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        let sessions = self.chat_repo.get_all_by_plant_id(plant_id).await?;
+        Ok(sessions.into_iter().next())
+    }
+
+    /// Send a user message and return the AI reply
+    pub async fn send_message(
+        &self,
+        session: &mut ChatSession,
+        message: String,
+        user_id: &str,
+    ) -> Result<String> {
+        self.user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+
+        let plant = self
+            .plant_repo
+            .get_by_id(&session.plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        let system_prompt = self.build_system_prompt(&plant).await?;
+
+        session.push_message("user", message);
+
+        let reply = self
+            .ai_adapter
+            .get_chat_completion(&system_prompt, &session.messages)
+            .await?;
+
+        session.push_message("assistant", reply.clone());
+        self.chat_repo.update(session).await?;
+
+        Ok(reply)
+    }
+
+    /// Build the grounding prompt from the plant's records
+    async fn build_system_prompt(&self, plant: &Plant) -> Result<String> {
+        let mut prompt = String::from(
+            "You are a friendly, knowledgeable botanist chatting with a plant owner about one of their plants.\n\
+             Answer conversationally in plain text (no JSON). Base your answers on the plant records below \
+             and say so when the records do not cover a question.\n\n",
+        );
+
+        prompt.push_str(&format!("## Plant\nName: {}\n", plant.name));
+        if let Some(nickname) = &plant.nickname {
+            prompt.push_str(&format!("The owner calls it: {}\n", nickname));
+        }
+        prompt.push_str(&format!(
+            "Care schedule:\n- Light: {}\n- Water: {}\n- Humidity: {}\n- Temperature: {}\n",
+            plant.care_schedule.light,
+            plant.care_schedule.water,
+            plant.care_schedule.humidity,
+            plant.care_schedule.temperature
+        ));
+        if !plant.care_schedule.care_instructions.is_empty() {
+            prompt.push_str(&format!("- Notes: {}\n", plant.care_schedule.care_instructions));
+        }
+
+        let attributes = self.attribute_repo.get_all_by_plant_id(&plant.id).await?;
+        if !attributes.is_empty() {
+            prompt.push_str("Recorded by the owner:\n");
+            for attribute in &attributes {
+                prompt.push_str(&format!("- {}: {}\n", attribute.key, attribute.value));
+            }
+        }
+
+        let journal = self.journal_repo.get_all_by_plant_id(&plant.id).await?;
+        if !journal.is_empty() {
+            prompt.push_str("\n## Journal (most recent first)\n");
+            for entry in journal.iter().take(MAX_JOURNAL_ENTRIES) {
+                prompt.push_str(&format!(
+                    "- {}: {}\n",
+                    config::local_zone().date(entry.created_at),
+                    entry.note
+                ));
+            }
+        }
+
+        let diagnoses = self
+            .diagnosis_repo
+            .get_all_by_plant_id(&plant.id, &plant.user_id)
+            .await?;
+        if !diagnoses.is_empty() {
+            prompt.push_str("\n## Past diagnoses (most recent first)\n");
+            for session in diagnoses.iter().take(MAX_DIAGNOSES) {
+                let problem = session.diagnosis_context["initial_prompt"]
+                    .as_str()
+                    .unwrap_or("unknown problem");
+                let date = config::local_zone().date(session.created_at);
+
+                match (session.state, session.diagnosis_context.get("result")) {
+                    (DiagnosisState::Concluded, Some(result)) => {
+                        prompt.push_str(&format!(
+                            "- {}: \"{}\" -> finding: {}; recommendation: {}\n",
+                            date,
+                            problem,
+                            result["finding"].as_str().unwrap_or("N/A"),
+                            result["recommendation"].as_str().unwrap_or("N/A")
+                        ));
+                    }
+                    _ => {
+                        prompt.push_str(&format!(
+                            "- {}: \"{}\" ({})\n",
+                            date,
+                            problem,
+                            session.status().as_str()
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(prompt)
+    }
+}
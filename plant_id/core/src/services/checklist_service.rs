@@ -0,0 +1,401 @@
+/*!
+ * CHECKLIST SERVICE
+ *
+ * Builds the day's care checklist across the collection: due waterings and
+ * fertilizing, treatments recommended by recent diagnoses, follow-ups on
+ * treatments that should have shown results by now, and inspections of
+ * quarantined plants and of relatives of plants found with a pest or
+ * disease. Completing an item updates the schedule it came from.
+ */
+
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use serde_json::json;
+
+use crate::config;
+use crate::domain::reminder;
+use crate::domain::{
+    CareTask, CareTaskKind, DiagnosisSession, DiagnosisState, LocalZone, NotificationEvent, Plant,
+    PreventiveInspection, Quarantine, RequestError,
+};
+use crate::repositories::{
+    CareTaskRepository, DiagnosisRepository, InspectionRepository, OutcomeRepository, PlantRepository,
+    QuarantineRepository,
+};
+use crate::services::triage_service::{FOLLOW_UP_AFTER_DAYS, FOLLOW_UP_WINDOW_DAYS};
+use crate::services::EventBus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecklistKind {
+    Water,
+    Fertilize,
+    /// Apply the recommendation of a concluded diagnosis
+    Treatment,
+    /// Report whether a treatment worked
+    FollowUp,
+    /// Look a quarantined plant, or a relative of a diagnosed one, over for
+    /// pests
+    Inspect,
+}
+
+impl ChecklistKind {
+    /// Prefix of item IDs of this kind
+    fn prefix(&self) -> &'static str {
+        match self {
+            Self::Water => "water",
+            Self::Fertilize => "feed",
+            Self::Treatment => "treat",
+            Self::FollowUp => "checkin",
+            Self::Inspect => "inspect",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChecklistItem {
+    /// Short, stable ID used to mark the item done (e.g. `water-1a2b3c4d`)
+    pub id: String,
+    pub kind: ChecklistKind,
+    pub plant_id: String,
+    pub plant_name: String,
+    pub description: String,
+    /// Days past due, 0 when due today
+    pub overdue_days: i64,
+    /// Care task or diagnosis the item comes from
+    pub source_id: String,
+}
+
+impl ChecklistItem {
+    fn new(
+        kind: ChecklistKind,
+        plant: &Plant,
+        source_id: &str,
+        description: String,
+        overdue_days: i64,
+    ) -> Self {
+        Self {
+            id: format!("{}-{}", kind.prefix(), &source_id[..source_id.len().min(8)]),
+            kind,
+            plant_id: plant.id.clone(),
+            plant_name: plant.display_name().to_string(),
+            description,
+            overdue_days,
+            source_id: source_id.to_string(),
+        }
+    }
+}
+
+pub struct ChecklistService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    outcome_repo: OutcomeRepository,
+    care_task_repo: CareTaskRepository,
+    quarantine_repo: QuarantineRepository,
+    inspection_repo: InspectionRepository,
+    event_bus: EventBus,
+}
+
+impl ChecklistService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        outcome_repo: OutcomeRepository,
+        care_task_repo: CareTaskRepository,
+        quarantine_repo: QuarantineRepository,
+        inspection_repo: InspectionRepository,
+        event_bus: EventBus,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            outcome_repo,
+            care_task_repo,
+            quarantine_repo,
+            inspection_repo,
+            event_bus,
+        }
+    }
+
+    /// Everything due on `today` or earlier, most overdue first. Days are
+    /// those of the owner's time zone.
+    pub async fn checklist(&self, user_id: &str, today: NaiveDate) -> Result<Vec<ChecklistItem>> {
+        let zone = config::local_zone();
+        let mut items = Vec::new();
+
+        for plant in self.plant_repo.get_all_by_user(user_id).await? {
+            let done = self.care_task_repo.get_all_by_plant_id(&plant.id).await?;
+            for task in reminder::care_tasks(&plant.id, &done) {
+                if let Some(item) = care_task_item(&plant, &task, today, zone) {
+                    items.push(item);
+                }
+            }
+
+            if let Some(quarantine) = self.quarantine_repo.get_active_by_plant_id(&plant.id).await? {
+                items.extend(inspection_item(&plant, &quarantine, today, zone));
+            }
+            for inspection in self.inspection_repo.get_open_by_plant_id(&plant.id).await? {
+                items.extend(preventive_inspection_item(&plant, &inspection, today));
+            }
+
+            let sessions = self
+                .diagnosis_repo
+                .get_all_by_plant_id(&plant.id, user_id)
+                .await?;
+            // Sessions are newest first, so this is the latest conclusion,
+            // the one `check_in` records outcomes for
+            if let Some(session) = sessions.iter().find(|s| s.state == DiagnosisState::Concluded) {
+                let outcomes = self.outcome_repo.get_by_plant_id(&plant.id).await?;
+                if !outcomes.iter().any(|o| o.diagnosis_id == session.id) {
+                    items.extend(diagnosis_item(&plant, session, today, zone));
+                }
+            }
+        }
+
+        items.sort_by(|a, b| {
+            b.overdue_days
+                .cmp(&a.overdue_days)
+                .then_with(|| a.plant_name.cmp(&b.plant_name))
+        });
+        Ok(items)
+    }
+
+    /// Find an item of the checklist by its ID, or an unambiguous prefix of it
+    pub async fn find(&self, item_id: &str, user_id: &str, today: NaiveDate) -> Result<ChecklistItem> {
+        let items = self.checklist(user_id, today).await?;
+        let mut matches = items.into_iter().filter(|item| item.id.starts_with(item_id));
+
+        let item = matches
+            .next()
+            .with_context(|| format!("No task '{}' on today's checklist", item_id))?;
+        if matches.next().is_some() {
+            anyhow::bail!("'{}' matches several tasks; use the full task ID", item_id);
+        }
+
+        Ok(item)
+    }
+
+    /// Mark an item done, rescheduling the care task or treatment it came from.
+    /// Follow-ups are completed by recording the treatment outcome instead.
+    pub async fn complete(&self, item: &ChecklistItem, user_id: &str) -> Result<()> {
+        match item.kind {
+            ChecklistKind::Water | ChecklistKind::Fertilize => {
+                let kind = if item.kind == ChecklistKind::Water {
+                    CareTaskKind::Water
+                } else {
+                    CareTaskKind::Fertilize
+                };
+                let mut task = CareTask::new(&item.plant_id, kind);
+                task.last_done_at = Some(Utc::now());
+                self.care_task_repo.save(&task).await
+            }
+            ChecklistKind::Treatment => {
+                let mut session = self
+                    .diagnosis_repo
+                    .get_by_id(&item.source_id)
+                    .await?
+                    .ok_or(RequestError::NotFound("Diagnosis session"))?;
+                self.plant_repo
+                    .get_by_id(&session.plant_id, user_id)
+                    .await?
+                    .ok_or(RequestError::Unauthorized("diagnosis"))?;
+
+                session.diagnosis_context["treatment_applied_at"] = json!(Utc::now().to_rfc3339());
+                session.updated_at = Utc::now();
+                self.diagnosis_repo.update(&session).await
+            }
+            ChecklistKind::FollowUp => {
+                anyhow::bail!("Follow-ups are completed by checking in on the treatment outcome")
+            }
+            ChecklistKind::Inspect => {
+                if let Some(mut inspection) = self.inspection_repo.get_by_id(&item.source_id).await? {
+                    inspection.done_at = Some(Utc::now());
+                    return self.inspection_repo.save(&inspection).await;
+                }
+
+                let mut quarantine = self
+                    .quarantine_repo
+                    .get_active_by_plant_id(&item.plant_id)
+                    .await?
+                    .context("The plant is no longer quarantined")?;
+                quarantine.last_inspected_at = Some(Utc::now());
+                self.quarantine_repo.save(&quarantine).await
+            }
+        }
+    }
+
+    /// Send a watering reminder to the webhooks for every overdue plant
+    pub async fn notify_overdue(&self, items: &[ChecklistItem]) {
+        for item in items {
+            if item.kind == ChecklistKind::Water && item.overdue_days > 0 {
+                self.event_bus
+                    .publish(NotificationEvent::WateringOverdue {
+                        plant_id: item.plant_id.clone(),
+                        plant_name: item.plant_name.clone(),
+                        days_overdue: item.overdue_days,
+                    })
+                    .await;
+            }
+        }
+    }
+}
+
+/// Checklist item for a care task due on `today` or earlier
+fn care_task_item(plant: &Plant, task: &CareTask, today: NaiveDate, zone: &LocalZone) -> Option<ChecklistItem> {
+    let due = task.due_date(&plant.care_schedule, today, zone);
+    if due > today {
+        return None;
+    }
+
+    let (kind, description) = match task.kind {
+        CareTaskKind::Water => (
+            ChecklistKind::Water,
+            format!("Water (every {} days)", task.interval_days(&plant.care_schedule)),
+        ),
+        CareTaskKind::Fertilize => (
+            ChecklistKind::Fertilize,
+            format!("Fertilize (every {} days)", task.interval_days(&plant.care_schedule)),
+        ),
+    };
+    Some(ChecklistItem::new(kind, plant, &task.id, description, (today - due).num_days()))
+}
+
+/// Inspection of a quarantined plant, when one is due on `today` or earlier
+fn inspection_item(
+    plant: &Plant,
+    quarantine: &Quarantine,
+    today: NaiveDate,
+    zone: &LocalZone,
+) -> Option<ChecklistItem> {
+    let due = quarantine.next_inspection(zone);
+    if due > today {
+        return None;
+    }
+
+    let description = match &quarantine.reason {
+        Some(reason) => format!("Inspect for {} (quarantined)", reason),
+        None => "Inspect for pests (quarantined)".to_string(),
+    };
+    Some(ChecklistItem::new(
+        ChecklistKind::Inspect,
+        plant,
+        &quarantine.id,
+        description,
+        (today - due).num_days(),
+    ))
+}
+
+/// Preventive inspection of a plant, when it is due on `today` or earlier
+fn preventive_inspection_item(
+    plant: &Plant,
+    inspection: &PreventiveInspection,
+    today: NaiveDate,
+) -> Option<ChecklistItem> {
+    if !inspection.is_open() || inspection.due_on > today {
+        return None;
+    }
+
+    Some(ChecklistItem::new(
+        ChecklistKind::Inspect,
+        plant,
+        &inspection.id,
+        format!("Inspect for {}", inspection.reason),
+        (today - inspection.due_on).num_days(),
+    ))
+}
+
+/// Treatment or follow-up item for a concluded diagnosis without an outcome
+fn diagnosis_item(
+    plant: &Plant,
+    session: &DiagnosisSession,
+    today: NaiveDate,
+    zone: &LocalZone,
+) -> Option<ChecklistItem> {
+    if session.state != DiagnosisState::Concluded {
+        return None;
+    }
+
+    let result = &session.diagnosis_context["result"];
+    let finding = result["finding"].as_str().unwrap_or("unknown finding");
+    let concluded = zone.date(session.updated_at);
+    let age = (today - concluded).num_days();
+    let follow_up_due = concluded + Duration::days(FOLLOW_UP_AFTER_DAYS);
+
+    if age >= FOLLOW_UP_AFTER_DAYS {
+        if age > FOLLOW_UP_WINDOW_DAYS {
+            return None;
+        }
+        return Some(ChecklistItem::new(
+            ChecklistKind::FollowUp,
+            plant,
+            &session.id,
+            format!("Check whether the treatment for '{}' worked", finding),
+            (today - follow_up_due).num_days(),
+        ));
+    }
+
+    let applied = session.diagnosis_context.get("treatment_applied_at").is_some();
+    let recommendation = result["recommendation"].as_str()?;
+    (!applied).then(|| {
+        ChecklistItem::new(
+            ChecklistKind::Treatment,
+            plant,
+            &session.id,
+            format!("Treat '{}': {}", finding, recommendation),
+            age,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+
+    fn concluded(days_ago: i64, today: NaiveDate) -> DiagnosisSession {
+        let mut session = DiagnosisSession::new("p1".to_string(), "Yellow leaves".to_string());
+        session.state = DiagnosisState::Concluded;
+        session.diagnosis_context["result"] =
+            json!({"finding": "Overwatering", "recommendation": "Let the soil dry out"});
+        session.updated_at = (today - Duration::days(days_ago)).and_hms_opt(12, 0, 0).unwrap().and_utc();
+        session
+    }
+
+    #[test]
+    fn test_recent_diagnosis_becomes_treatment_then_follow_up() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let plant = Plant::new("u".to_string(), "Monstera".to_string(), CareSchedule::default());
+        let utc = LocalZone::utc();
+
+        let item = diagnosis_item(&plant, &concluded(2, today), today, &utc).unwrap();
+        assert_eq!(item.kind, ChecklistKind::Treatment);
+        assert!(item.id.starts_with("treat-"));
+
+        let mut applied = concluded(2, today);
+        applied.diagnosis_context["treatment_applied_at"] = json!("2024-05-19T10:00:00Z");
+        assert!(diagnosis_item(&plant, &applied, today, &utc).is_none());
+
+        let item = diagnosis_item(&plant, &concluded(9, today), today, &utc).unwrap();
+        assert_eq!(item.kind, ChecklistKind::FollowUp);
+        assert_eq!(item.overdue_days, 2);
+
+        assert!(diagnosis_item(&plant, &concluded(90, today), today, &utc).is_none());
+    }
+
+    #[test]
+    fn test_care_task_item_only_when_due() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let plant = Plant::new("u".to_string(), "Monstera".to_string(), CareSchedule::default());
+        let utc = LocalZone::utc();
+        let mut task = CareTask::new(&plant.id, CareTaskKind::Water);
+
+        let item = care_task_item(&plant, &task, today, &utc).unwrap();
+        assert_eq!(item.overdue_days, 0);
+        assert_eq!(item.description, "Water (every 7 days)");
+
+        task.last_done_at = Some((today - Duration::days(3)).and_hms_opt(8, 0, 0).unwrap().and_utc());
+        assert!(care_task_item(&plant, &task, today, &utc).is_none());
+
+        task.last_done_at = Some((today - Duration::days(10)).and_hms_opt(8, 0, 0).unwrap().and_utc());
+        assert_eq!(care_task_item(&plant, &task, today, &utc).unwrap().overdue_days, 3);
+    }
+}
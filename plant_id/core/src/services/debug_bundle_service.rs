@@ -0,0 +1,286 @@
+/*!
+ * DEBUG BUNDLE SERVICE
+ *
+ * Gathers what a bug report needs into one zip file: the version and
+ * platform, the configuration with its secrets redacted, the recent log
+ * lines, the AI traces of the failing diagnosis session and the database
+ * schema. Nothing else of the collection goes in, but the traces hold the
+ * session's conversation, so the bundle should be looked over before it
+ * is shared.
+ */
+
+use anyhow::Result;
+use chrono::Utc;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Cursor, ErrorKind, Write};
+use std::path::PathBuf;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::adapters::ai_adapter;
+use crate::domain::{DiagnosisFilter, DiagnosisStatus, RequestError};
+use crate::repositories::{DiagnosisRepository, PlantRepository, StatsRepository};
+
+/// Log lines kept, the most recent ones
+pub const LOG_LINES: usize = 500;
+
+/// Cancelled sessions looked through for the latest that failed
+const FAILED_SESSIONS_SEARCHED: usize = 20;
+
+/// Settings reported in the bundle, when set
+const CONFIG_VARS: &[&str] = &[
+    "AI_MAX_TOKENS", "AI_MODEL", "AI_MODEL_PRICE", "AI_RETRIES", "AI_SEED", "AI_STYLE", "AI_TEMPERATURE", "AI_TOP_P",
+    "ALL_PROXY", "AWS_ACCESS_KEY_ID", "AWS_SECRET_ACCESS_KEY", "CA_BUNDLE", "DATABASE_PATH",
+    "DIAGNOSIS_CONFIRM_COST", "DISCORD_BOT_TOKEN", "DISCORD_GUILD_ID", "GRPC_TOKEN", "HTTPS_PROXY",
+    "HTTP_PROXY", "LOG_FILE", "LOG_FORMAT", "MAX_IMAGE_MB", "METRICS_FILE", "NOTIFY_STREAKS",
+    "NOTIFY_WEBHOOKS", "OFFLINE_MODEL_COMMAND", "OPENROUTER_API_KEY", "OPENROUTER_BASE_URL",
+    "PERENUAL_API_KEY", "PLANT_CACHE_TTL_SECS", "PLANT_ID_API_KEY", "PLANT_ID_BASE_URL",
+    "REQUEST_TIMEOUT_SECS", "RUST_LOG", "S3_BUCKET", "S3_ENDPOINT", "S3_REGION", "SEARCH_BACKEND",
+    "SECOND_IDENTIFIER", "SESSIONS_DIR", "SLOW_QUERY_MS", "SPECIES_DATA_SOURCE", "STORAGE_BACKEND",
+    "STORAGE_DIR", "SYNC_REMOTE", "TELEGRAM_ALLOWED_CHATS", "TELEGRAM_BOT_TOKEN", "THEME",
+    "THUMBNAIL_DIR", "TIMEZONE", "TRACES_DIR", "TREFLE_API_TOKEN",
+];
+
+/// Parts of a setting's name telling that its value is a secret
+const SECRET_NAMES: &[&str] = &["KEY", "TOKEN", "SECRET", "PASSWORD", "PASSPHRASE"];
+
+/// Where a bundle's parts come from, besides the database
+#[derive(Debug, Clone, Default)]
+pub struct BundleSources {
+    /// Version of the program making the bundle
+    pub version: String,
+    /// The session whose traces go in; by default the latest that failed
+    pub session_id: Option<String>,
+    /// Log files, oldest first
+    pub log_files: Vec<PathBuf>,
+}
+
+/// The files of a debug bundle, by name
+#[derive(Debug)]
+pub struct DebugBundle {
+    pub files: Vec<(String, Vec<u8>)>,
+    /// Session the traces are of, if any
+    pub session_id: Option<String>,
+    /// Fingerprint of the database schema
+    pub schema_version: String,
+}
+
+impl DebugBundle {
+    /// The bundle as a zip file
+    pub fn to_zip(&self) -> Result<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in &self.files {
+            zip.start_file(name.as_str(), SimpleFileOptions::default())?;
+            zip.write_all(contents)?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+}
+
+pub struct DebugBundleService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    stats_repo: StatsRepository,
+}
+
+impl DebugBundleService {
+    pub fn new(plant_repo: PlantRepository, diagnosis_repo: DiagnosisRepository, stats_repo: StatsRepository) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            stats_repo,
+        }
+    }
+
+    pub async fn collect(&self, user_id: &str, sources: &BundleSources) -> Result<DebugBundle> {
+        let session_id = match &sources.session_id {
+            Some(id) => {
+                let session = self
+                    .diagnosis_repo
+                    .get_by_id(id)
+                    .await?
+                    .ok_or(RequestError::NotFound("Diagnosis session"))?;
+                self.plant_repo
+                    .get_by_id(&session.plant_id, user_id)
+                    .await?
+                    .ok_or(RequestError::Unauthorized("diagnosis"))?;
+                Some(session.id)
+            }
+            None => self.latest_failed_session(user_id).await?,
+        };
+
+        let schema = self.stats_repo.schema().await?.join(";\n\n") + ";\n";
+        let schema_version = format!("{:x}", Sha256::digest(&schema))[..12].to_string();
+
+        let info = json!({
+            "version": sources.version,
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "created_at": Utc::now(),
+            "schema_version": schema_version,
+            "session_id": session_id,
+        });
+        let mut files = vec![
+            ("info.json".to_string(), serde_json::to_vec_pretty(&info)?),
+            ("config.txt".to_string(), config_report().into_bytes()),
+            ("logs.jsonl".to_string(), recent_lines(&sources.log_files, LOG_LINES).into_bytes()),
+            ("schema.sql".to_string(), schema.into_bytes()),
+        ];
+        if let Some(id) = &session_id {
+            let trace = ai_adapter::trace_path(id);
+            // Sessions run before traces were kept have none
+            match fs::read(&trace) {
+                Ok(contents) => files.push((format!("traces/{}.jsonl", id), contents)),
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => log::warn!("Could not read the AI trace {}: {}", trace.display(), e),
+            }
+        }
+
+        Ok(DebugBundle {
+            files,
+            session_id,
+            schema_version,
+        })
+    }
+
+    /// The user's latest session cancelled because the command running it
+    /// failed
+    async fn latest_failed_session(&self, user_id: &str) -> Result<Option<String>> {
+        let filter = DiagnosisFilter {
+            status: Some(DiagnosisStatus::Cancelled),
+            ..Default::default()
+        };
+        let cancelled = self
+            .diagnosis_repo
+            .query_summaries(user_id, None, &filter, 0, FAILED_SESSIONS_SEARCHED)
+            .await?;
+        for summary in cancelled {
+            let Some(session) = self.diagnosis_repo.get_by_id(&summary.id).await? else {
+                continue;
+            };
+            if session.diagnosis_context["cancel_reason"] == "failed" {
+                return Ok(Some(session.id));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The settings that are set, one `NAME=value` per line, redacted
+fn config_report() -> String {
+    CONFIG_VARS
+        .iter()
+        .filter_map(|name| {
+            let value = std::env::var(name).ok()?;
+            Some(format!("{}={}\n", name, redact(name, &value)))
+        })
+        .collect()
+}
+
+/// A setting's value, without the secrets it holds: secrets are replaced
+/// entirely, and URLs lose their credentials and query, webhook URLs their
+/// path as well
+pub fn redact(name: &str, value: &str) -> String {
+    if SECRET_NAMES.iter().any(|secret| name.contains(secret)) {
+        return "<redacted>".to_string();
+    }
+    if name == "NOTIFY_WEBHOOKS" {
+        return value
+            .split(',')
+            .map(|webhook| match webhook.split_once(':') {
+                Some((backend, url)) if url.contains("://") => format!("{}:{}", backend, redact_url(url, true)),
+                _ => redact_url(webhook, true),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+    }
+    redact_url(value, false)
+}
+
+/// A URL without credentials and query, and without its path when
+/// `whole_path`; other values are left alone
+fn redact_url(value: &str, whole_path: bool) -> String {
+    let Some((scheme, rest)) = value.split_once("://") else {
+        return value.to_string();
+    };
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(end);
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let path = tail.split(['?', '#']).next().unwrap_or_default();
+
+    match (whole_path, path.is_empty()) {
+        (true, false) => format!("{}://{}/<redacted>", scheme, host),
+        _ => format!("{}://{}{}", scheme, host, path),
+    }
+}
+
+/// The last `count` lines of the files, read in order; missing files are
+/// skipped
+fn recent_lines(files: &[PathBuf], count: usize) -> String {
+    let contents: Vec<String> = files.iter().filter_map(|file| fs::read_to_string(file).ok()).collect();
+    let lines: Vec<&str> = contents.iter().flat_map(|c| c.lines()).collect();
+    let mut recent = lines[lines.len().saturating_sub(count)..].join("\n");
+    if !recent.is_empty() {
+        recent.push('\n');
+    }
+    recent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use zip::ZipArchive;
+
+    #[tokio::test]
+    async fn test_bundle_without_failed_sessions() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let service = DebugBundleService::new(
+            PlantRepository::new(db.clone()),
+            DiagnosisRepository::new(db.clone()),
+            StatsRepository::new(db),
+        );
+
+        let bundle = service
+            .collect(
+                "u",
+                &BundleSources {
+                    version: "1.2.3".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(bundle.session_id, None);
+        assert_eq!(bundle.schema_version.len(), 12);
+
+        let mut zip = ZipArchive::new(Cursor::new(bundle.to_zip().unwrap())).unwrap();
+        let names: Vec<&str> = zip.file_names().collect();
+        assert_eq!(names.len(), 4);
+        assert!(names.contains(&"schema.sql"));
+        let info: serde_json::Value = serde_json::from_reader(zip.by_name("info.json").unwrap()).unwrap();
+        assert_eq!(info["version"], "1.2.3");
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        assert_eq!(redact("OPENROUTER_API_KEY", "sk-or-123"), "<redacted>");
+        assert_eq!(redact("TELEGRAM_BOT_TOKEN", "123:abc"), "<redacted>");
+        assert_eq!(
+            redact("OPENROUTER_BASE_URL", "https://user:pw@gateway.local:4000/v1?key=abc"),
+            "https://gateway.local:4000/v1"
+        );
+        assert_eq!(
+            redact(
+                "NOTIFY_WEBHOOKS",
+                "slack:https://hooks.slack.com/services/T0/B0/xyz,ntfy:https://ntfy.sh/my-plants"
+            ),
+            "slack:https://hooks.slack.com/<redacted>,ntfy:https://ntfy.sh/<redacted>"
+        );
+        assert_eq!(redact("SYNC_REMOTE", "dir:/mnt/nas/plant-sync"), "dir:/mnt/nas/plant-sync");
+        assert_eq!(redact("AI_MODEL", "openai/gpt-4o"), "openai/gpt-4o");
+    }
+}
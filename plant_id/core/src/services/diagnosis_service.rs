@@ -0,0 +1,982 @@
+//! DIAGNOSIS SERVICE
+//!
+//! DIAGNOSTIC KERNEL - Core business logic for AI-driven plant diagnosis.
+//!
+//! This uses a sandbox executor to safely process AI-generated responses.
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use std::sync::Arc;
+
+use crate::adapters::ai_adapter::{self, ImageAttachment};
+use crate::adapters::payload::PayloadError;
+use crate::adapters::{
+    metrics_adapter, payload, AiAdapter, SandboxExecutor, ActionEffect, HealthAssessmentPort, SearchPort, StoragePort,
+};
+use crate::domain::ai_usage;
+use crate::domain::context_budget::{self, CONTEXT_TOKEN_THRESHOLD};
+use crate::domain::encyclopedia;
+use crate::domain::language;
+use crate::domain::moisture_reading;
+use crate::domain::treatment_outcome;
+use crate::domain::validation::validate_care_schedule;
+use crate::domain::{
+    ContainmentPlan, DiagnosisBatch, DiagnosisMessage, DiagnosisSession, ModelPrice, NotificationEvent,
+    Permission, Plant, RequestError, ScheduleSource, Severity, TokenUsage,
+};
+use crate::dto::{
+    CareUpdateSuggestionDto, DiagnosisAskResponse, DiagnosisConcludeResponse,
+    DiagnosisPhotoRequestResponse,
+    DiagnosisResponseDto, DiagnosisStartDto, DiagnosisUpdateDto,
+};
+use crate::repositories::{
+    AttributeRepository, DiagnosisRepository, MoistureRepository, OutcomeRepository,
+    PlantRepository, RoomRepository, UserRepository,
+};
+use crate::services::EventBus;
+
+pub struct DiagnosisService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    outcome_repo: OutcomeRepository,
+    moisture_repo: MoistureRepository,
+    attribute_repo: AttributeRepository,
+    room_repo: RoomRepository,
+    user_repo: UserRepository,
+    ai_adapter: AiAdapter,
+    storage_adapter: Arc<dyn StoragePort>,
+    search_port: Arc<dyn SearchPort>,
+    event_bus: EventBus,
+    sandbox_executor: SandboxExecutor,
+    /// Assesses the photo a diagnosis starts with, if configured
+    health_assessor: Option<Arc<dyn HealthAssessmentPort>>,
+}
+
+/// Only the most recent photos are sent with each diagnosis cycle
+const MAX_PHOTOS_PER_CYCLE: usize = 3;
+
+/// Limit on SEARCH actions per session, so the AI cannot loop on lookups
+const MAX_SEARCHES_PER_SESSION: usize = 3;
+
+/// Search snippets are truncated to keep the context small
+const MAX_SNIPPET_CHARS: usize = 500;
+
+/// How a request for a photo is recorded in the conversation
+const PHOTO_REQUEST_PREFIX: &str = "Please attach a photo of the ";
+
+/// Concluded sessions the expected usage of a diagnosis is averaged from
+const USAGE_HISTORY: usize = 20;
+
+/// Expected cost in USD above which starting a diagnosis is confirmed,
+/// unless `DIAGNOSIS_CONFIRM_COST` sets another
+const DEFAULT_CONFIRM_COST: f64 = 0.10;
+
+/// What a diagnosis cost, or is expected to
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosisCost {
+    pub model: String,
+    pub usage: TokenUsage,
+    /// In USD; unknown when the model has no listed price and
+    /// `AI_MODEL_PRICE` is not set
+    pub usd: Option<f64>,
+}
+
+/// Expected cost of diagnoses about to start
+#[derive(Debug, Clone, Serialize)]
+pub struct CostEstimate {
+    pub expected: DiagnosisCost,
+    /// Earlier sessions the usage is averaged from; none when a typical
+    /// diagnosis is assumed
+    pub sessions: usize,
+    /// Cost in USD above which starting should be confirmed
+    pub threshold: f64,
+}
+
+impl CostEstimate {
+    pub fn needs_confirmation(&self) -> bool {
+        self.expected.usd.is_some_and(|usd| usd > self.threshold)
+    }
+}
+
+impl DiagnosisService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        outcome_repo: OutcomeRepository,
+        moisture_repo: MoistureRepository,
+        attribute_repo: AttributeRepository,
+        room_repo: RoomRepository,
+        user_repo: UserRepository,
+        ai_adapter: AiAdapter,
+        storage_adapter: Arc<dyn StoragePort>,
+        search_port: Arc<dyn SearchPort>,
+        event_bus: EventBus,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            outcome_repo,
+            moisture_repo,
+            attribute_repo,
+            room_repo,
+            user_repo,
+            ai_adapter,
+            storage_adapter,
+            search_port,
+            event_bus,
+            sandbox_executor: SandboxExecutor::new(),
+            health_assessor: None,
+        }
+    }
+
+    /// Have the photo a diagnosis starts with assessed first, seeding the
+    /// session with the diseases found in it; without an assessor the photo
+    /// is only kept
+    pub fn with_health_assessment(mut self, assessor: Option<Arc<dyn HealthAssessmentPort>>) -> Self {
+        self.health_assessor = assessor;
+        self
+    }
+
+    pub async fn start_diagnosis(
+        &self,
+        plant_id: &str,
+        dto: DiagnosisStartDto,
+        user_id: String,
+    ) -> Result<DiagnosisResponseDto> {
+        self.start(plant_id, dto, user_id, None).await
+    }
+
+    /// Start the session of the next plant in a batch, with what the
+    /// sessions of the other plants have found so far
+    pub async fn start_linked_diagnosis(
+        &self,
+        batch: &mut DiagnosisBatch,
+        plant_id: &str,
+        user_id: String,
+    ) -> Result<DiagnosisResponseDto> {
+        batch.plant(plant_id).context("Plant is not part of this batch")?;
+        self.refresh_batch(batch).await?;
+
+        let dto = DiagnosisStartDto {
+            prompt: batch.problem.clone(),
+            photo: None,
+        };
+        let response = self
+            .start(plant_id, dto, user_id, Some(batch.shared_context(plant_id)))
+            .await?;
+        batch.attach(plant_id, response.diagnosis_id().to_string());
+
+        Ok(response)
+    }
+
+    /// Expected cost of `diagnoses` sessions, from the average usage of the
+    /// user's recent concluded sessions and the model's price
+    pub async fn estimate_cost(&self, user_id: &str, diagnoses: usize) -> Result<CostEstimate> {
+        let history = self.diagnosis_repo.get_recent_usage(user_id, USAGE_HISTORY).await?;
+        let average = TokenUsage::average(&history).unwrap_or(ai_usage::TYPICAL_DIAGNOSIS);
+
+        let mut usage = TokenUsage::default();
+        for _ in 0..diagnoses {
+            usage.add(&average);
+        }
+
+        Ok(CostEstimate {
+            expected: self.cost_of(usage)?,
+            sessions: history.len(),
+            threshold: confirm_cost()?,
+        })
+    }
+
+    /// What a session has cost so far, if its usage was recorded
+    pub async fn session_cost(&self, diagnosis_id: &str, user_id: &str) -> Result<Option<DiagnosisCost>> {
+        let session = self.get_diagnosis(diagnosis_id, user_id).await?;
+        TokenUsage::of_session(&session.diagnosis_context)
+            .map(|usage| self.cost_of(usage))
+            .transpose()
+    }
+
+    fn cost_of(&self, usage: TokenUsage) -> Result<DiagnosisCost> {
+        let price = match std::env::var("AI_MODEL_PRICE") {
+            Ok(price) if !price.trim().is_empty() => {
+                Some(ModelPrice::parse(&price).context("Invalid AI_MODEL_PRICE")?)
+            }
+            _ => ModelPrice::of(self.ai_adapter.model()),
+        };
+
+        Ok(DiagnosisCost {
+            model: self.ai_adapter.model().to_string(),
+            usage,
+            usd: price.map(|p| p.cost(&usage)),
+        })
+    }
+
+    /// Combine the findings of a batch into a containment plan, saved with
+    /// every linked session. Plants whose session did not conclude are
+    /// included as undiagnosed.
+    pub async fn conclude_batch(&self, batch: &mut DiagnosisBatch, user_id: &str) -> Result<ContainmentPlan> {
+        self.refresh_batch(batch).await?;
+        if batch.diagnosed().next().is_none() {
+            anyhow::bail!("No plant in the batch has been diagnosed yet");
+        }
+
+        let plan = self.ai_adapter.plan_containment(batch).await?;
+        batch.containment = Some(plan.clone());
+
+        for diagnosis_id in batch.plants.iter().filter_map(|p| p.diagnosis_id.as_deref()) {
+            let mut session = self.get_diagnosis(diagnosis_id, user_id).await?;
+            session.diagnosis_context["batch"] = serde_json::to_value(&*batch)?;
+            session.updated_at = Utc::now();
+            self.diagnosis_repo.update(&session).await?;
+        }
+
+        Ok(plan)
+    }
+
+    /// Note the conclusions of the batch's sessions that have concluded
+    async fn refresh_batch(&self, batch: &mut DiagnosisBatch) -> Result<()> {
+        let linked: Vec<(String, String)> = batch
+            .plants
+            .iter()
+            .filter_map(|p| Some((p.plant_id.clone(), p.diagnosis_id.clone()?)))
+            .collect();
+
+        for (plant_id, diagnosis_id) in linked {
+            let Some(session) = self.diagnosis_repo.get_by_id(&diagnosis_id).await? else {
+                continue;
+            };
+            let result = &session.diagnosis_context["result"];
+            if let Some(finding) = result["finding"].as_str() {
+                let severity = result["severity"].as_str().and_then(Severity::from_str);
+                batch.record_finding(&plant_id, finding.to_string(), severity);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn start(
+        &self,
+        plant_id: &str,
+        dto: DiagnosisStartDto,
+        user_id: String,
+        batch: Option<JsonValue>,
+    ) -> Result<DiagnosisResponseDto> {
+        self.user_repo.role(&user_id).await?.require(Permission::StartAiSessions)?;
+
+        // Verify plant exists and belongs to user
+        let plant = self
+            .plant_repo
+            .get_by_id(plant_id, &user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        // Outcomes of earlier treatments on the same species
+        let outcomes = self
+            .outcome_repo
+            .get_by_species(&treatment_outcome::species_key(plant.species()), &user_id)
+            .await?;
+        let species_experience = treatment_outcome::summarize_outcomes(&outcomes);
+
+        // Store the photo of the problem and have it assessed
+        let photo = match &dto.photo {
+            Some(encoded) => Some(self.store_photo(encoded).await?),
+            None => None,
+        };
+        let health_assessment = match (&photo, &self.health_assessor) {
+            (Some((image_data, _)), Some(assessor)) => match assessor.assess_health(image_data).await {
+                Ok(assessment) => Some(assessment),
+                Err(e) => {
+                    log::warn!("Health assessment failed, diagnosing without it: {:#}", e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        // Create new diagnosis session
+        let mut session = DiagnosisSession::new(plant_id.to_string(), dto.prompt.clone());
+
+        // Add plant vitals to context
+        let vitals = self.plant_vitals(&plant).await?;
+        if let Some(context) = session.diagnosis_context.as_object_mut() {
+            context.insert("plant_vitals".to_string(), vitals);
+            if !species_experience.is_empty() {
+                context.insert("species_experience".to_string(), json!(species_experience));
+            }
+            if let Some(batch) = batch {
+                context.insert("batch".to_string(), batch);
+            }
+            if let Some(language) = language::detect(&dto.prompt) {
+                context.insert("language".to_string(), json!(language));
+            }
+            if let Some((_, url)) = photo {
+                context.insert(
+                    "photos".to_string(),
+                    json!([{ "part": "plant", "url": url, "attached_at": Utc::now().to_rfc3339() }]),
+                );
+            }
+            if let Some(assessment) = health_assessment {
+                context.insert("health_assessment".to_string(), serde_json::to_value(assessment)?);
+            }
+        }
+        session.gather_vitals()?;
+
+        // Save session, with the problem description as its first turn
+        session = self.diagnosis_repo.create(&session).await?;
+        self.append_message(&session, "user", dto.prompt).await?;
+
+        // Run diagnosis cycle
+        self.run_diagnosis_cycle(session, user_id).await
+    }
+
+    pub async fn update_diagnosis(
+        &self,
+        diagnosis_id: &str,
+        dto: DiagnosisUpdateDto,
+        user_id: String,
+    ) -> Result<DiagnosisResponseDto> {
+        self.user_repo.role(&user_id).await?.require(Permission::StartAiSessions)?;
+
+        // Get existing session
+        let mut session = self
+            .diagnosis_repo
+            .get_by_id(diagnosis_id)
+            .await?
+            .ok_or(RequestError::NotFound("Diagnosis session"))?;
+
+        // Verify user owns the plant
+        let _ = self
+            .plant_repo
+            .get_by_id(&session.plant_id, &user_id)
+            .await?
+            .ok_or(RequestError::Unauthorized("diagnosis"))?;
+
+        session.expect_answer()?;
+
+        // Store an attached photo and note which request it answers
+        let photo_url = match &dto.photo {
+            Some(encoded) => Some(self.store_photo(encoded).await?.1),
+            None => None,
+        };
+
+        // Note the attached photo in the context, and the language when the
+        // problem description was too short to tell
+        if let Some(context) = session.diagnosis_context.as_object_mut() {
+            if !context.contains_key("language") {
+                if let Some(language) = language::detect(&dto.message) {
+                    context.insert("language".to_string(), json!(language));
+                }
+            }
+
+            let requested_part = context
+                .remove("pending_photo_request")
+                .and_then(|r| r["part"].as_str().map(str::to_string));
+
+            if let Some(url) = photo_url {
+                let photos = context.entry("photos").or_insert_with(|| json!([]));
+                if let Some(photos) = photos.as_array_mut() {
+                    photos.push(json!({
+                        "part": requested_part.unwrap_or_else(|| "plant".to_string()),
+                        "url": url,
+                        "attached_at": Utc::now().to_rfc3339()
+                    }));
+                }
+            }
+        }
+
+        // Append user message to the conversation
+        self.append_message(&session, "user", dto.message).await?;
+
+        // Run diagnosis cycle
+        self.run_diagnosis_cycle(session, user_id).await
+    }
+
+    /// Fork a session at turn `at` (numbered from 1), one of the owner's
+    /// answers, into a new session with the turns before it. Returns the
+    /// question the new session waits on; the original session is kept.
+    pub async fn branch_diagnosis(
+        &self,
+        diagnosis_id: &str,
+        at: usize,
+        user_id: String,
+    ) -> Result<DiagnosisResponseDto> {
+        self.user_repo.role(&user_id).await?.require(Permission::StartAiSessions)?;
+
+        // Verifies the user owns the plant
+        let session = self.get_diagnosis(diagnosis_id, &user_id).await?;
+        let turns = self.diagnosis_repo.get_messages(&session.id, 0, None).await?;
+        let (branch, copied) = session.branch(&turns, at)?;
+
+        let mut branch = self.diagnosis_repo.create(&branch).await?;
+        for turn in &copied {
+            self.diagnosis_repo.append_message(&branch, turn).await?;
+        }
+
+        let question = copied.last().map(|t| t.content.clone()).unwrap_or_default();
+        if let Some((part, reason)) = parse_photo_request(&question) {
+            branch.diagnosis_context["pending_photo_request"] = json!({ "part": part });
+            branch.updated_at = Utc::now();
+            self.diagnosis_repo.update(&branch).await?;
+            return Ok(DiagnosisResponseDto::RequestPhoto(DiagnosisPhotoRequestResponse {
+                diagnosis_id: branch.id,
+                part,
+                reason,
+            }));
+        }
+
+        Ok(DiagnosisResponseDto::Ask(DiagnosisAskResponse {
+            diagnosis_id: branch.id,
+            question,
+        }))
+    }
+
+    /// Apply the care schedule changes proposed by a concluded diagnosis
+    pub async fn apply_care_update(&self, diagnosis_id: &str, user_id: &str) -> Result<Plant> {
+        let mut session = self
+            .diagnosis_repo
+            .get_by_id(diagnosis_id)
+            .await?
+            .ok_or(RequestError::NotFound("Diagnosis session"))?;
+
+        // Verify user owns the plant
+        let mut plant = self
+            .plant_repo
+            .get_by_id(&session.plant_id, user_id)
+            .await?
+            .ok_or(RequestError::Unauthorized("diagnosis"))?;
+
+        let suggestion = proposed_care_update(&session.diagnosis_context)
+            .context("This diagnosis has no pending care schedule update")?;
+
+        let schedule = suggestion.changes.apply_to(&plant.care_schedule);
+        let report = validate_care_schedule(&schedule);
+        if !report.is_valid() {
+            anyhow::bail!("Suggested care schedule is invalid: {}", report.errors.join("; "));
+        }
+
+        plant.care_schedule = schedule;
+        plant.updated_at = Utc::now();
+        self.plant_repo.update_schedule(&plant, ScheduleSource::Diagnosis).await?;
+
+        session.diagnosis_context["care_update"]["status"] = json!("applied");
+        session.updated_at = Utc::now();
+        self.diagnosis_repo.update(&session).await?;
+
+        Ok(plant)
+    }
+
+    pub async fn get_diagnosis(
+        &self,
+        diagnosis_id: &str,
+        user_id: &str,
+    ) -> Result<DiagnosisSession> {
+        let session = self
+            .diagnosis_repo
+            .get_by_id(diagnosis_id)
+            .await?
+            .ok_or(RequestError::NotFound("Diagnosis session"))?;
+
+        // Verify user owns the plant
+        let _ = self
+            .plant_repo
+            .get_by_id(&session.plant_id, user_id)
+            .await?
+            .ok_or(RequestError::Unauthorized("diagnosis"))?;
+
+        Ok(session)
+    }
+
+    pub async fn delete_diagnosis(&self, diagnosis_id: &str, user_id: &str) -> Result<()> {
+        let session = self
+            .diagnosis_repo
+            .get_by_id(diagnosis_id)
+            .await?
+            .ok_or(RequestError::NotFound("Diagnosis session"))?;
+
+        // Verify user owns the plant
+        let _ = self
+            .plant_repo
+            .get_by_id(&session.plant_id, user_id)
+            .await?
+            .ok_or(RequestError::Unauthorized("diagnosis"))?;
+
+        for url in photo_urls(&session.diagnosis_context) {
+            if let Err(e) = self.storage_adapter.delete_image(url).await {
+                log::warn!("Could not delete diagnosis photo {}: {:#}", url, e);
+            }
+        }
+
+        self.diagnosis_repo.delete(diagnosis_id).await
+    }
+
+    pub async fn get_all_by_plant_id(
+        &self,
+        plant_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<DiagnosisSession>> {
+        // Verify user owns the plant
+        let _ = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        self.diagnosis_repo
+            .get_all_by_plant_id(plant_id, user_id)
+            .await
+    }
+
+    /// A page of a diagnosis conversation, oldest turn first, with the
+    /// total number of turns
+    pub async fn get_conversation(
+        &self,
+        diagnosis_id: &str,
+        user_id: &str,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<(Vec<DiagnosisMessage>, usize)> {
+        // Verifies the user owns the plant
+        let session = self.get_diagnosis(diagnosis_id, user_id).await?;
+
+        let messages = self
+            .diagnosis_repo
+            .get_messages(&session.id, offset, limit)
+            .await?;
+        let total = self.diagnosis_repo.count_messages(&session.id).await?;
+
+        Ok((messages, total))
+    }
+
+    async fn append_message(
+        &self,
+        session: &DiagnosisSession,
+        role: &str,
+        content: String,
+    ) -> Result<()> {
+        let message = DiagnosisMessage::new(session.id.clone(), role, content);
+        self.diagnosis_repo.append_message(session, &message).await
+    }
+
+    /// What the diagnostic kernel knows about the plant up front, with the
+    /// room it lives in, the owner's custom attributes and the soil moisture
+    /// trend when enough readings were logged
+    async fn plant_vitals(&self, plant: &Plant) -> Result<JsonValue> {
+        let mut vitals = json!({
+            "name": plant.name,
+            "care_schedule": plant.care_schedule
+        });
+
+        if let Some(room) = self.room_repo.profile_for(plant).await? {
+            vitals["room"] = serde_json::to_value(room)?;
+        }
+
+        let attributes = self.attribute_repo.get_all_by_plant_id(&plant.id).await?;
+        if !attributes.is_empty() {
+            let attributes: serde_json::Map<String, JsonValue> = attributes
+                .into_iter()
+                .map(|a| (a.key, JsonValue::String(a.value)))
+                .collect();
+            vitals["attributes"] = JsonValue::Object(attributes);
+        }
+
+        let readings = self.moisture_repo.get_all_by_plant_id(&plant.id).await?;
+        if let Some(trend) = moisture_reading::analyze(&readings, &plant.care_schedule, Utc::now()) {
+            vitals["moisture_trend"] = json!({
+                "summary": trend.summary(),
+                "warning": trend.warning(),
+                "details": trend
+            });
+        }
+
+        Ok(vitals)
+    }
+
+    async fn run_diagnosis_cycle(
+        &self,
+        session: DiagnosisSession,
+        user_id: String,
+    ) -> Result<DiagnosisResponseDto> {
+        let result = self.diagnosis_cycle(session, user_id).await;
+        let kind = match &result {
+            Ok(DiagnosisResponseDto::Ask(_)) => "ask",
+            Ok(DiagnosisResponseDto::RequestPhoto(_)) => "request_photo",
+            Ok(DiagnosisResponseDto::Conclude(_)) => "conclude",
+            Err(_) => "error",
+        };
+        metrics::counter!(metrics_adapter::DIAGNOSIS_CYCLES, "result" => kind).increment(1);
+
+        result
+    }
+
+    async fn diagnosis_cycle(
+        &self,
+        mut session: DiagnosisSession,
+        _user_id: String,
+    ) -> Result<DiagnosisResponseDto> {
+        // Only the turns not yet folded into the summary are sent
+        let mut history: Vec<JsonValue> = self
+            .diagnosis_repo
+            .get_messages(
+                &session.id,
+                context_budget::summarized_turns(&session.diagnosis_context),
+                None,
+            )
+            .await?
+            .iter()
+            .map(DiagnosisMessage::to_turn)
+            .collect();
+        // The tokens of this cycle's requests are added to the session's usage
+        let ai = self.ai_adapter.metered().traced(ai_adapter::trace_path(&session.id));
+        self.compact_context(&ai, &mut session, &mut history).await;
+
+        // Generate AI response for the current diagnosis context
+        // The diagnostic prompt is already built into generate_diagnosis_response()
+        let photos = self.load_recent_photos(&session.diagnosis_context).await;
+        let ai_response = ai
+            .generate_diagnosis_response(
+                &context_budget::with_history(&session.diagnosis_context, &history),
+                &photos,
+            )
+            .await?;
+        add_usage(&mut session.diagnosis_context, &ai.usage());
+
+        // Use sandbox executor to parse and validate the AI response
+        let execution_result = self
+            .sandbox_executor
+            .execute_code(&ai_response, &session.diagnosis_context)
+            .await?;
+
+        // Execute the action
+        let effect = self
+            .sandbox_executor
+            .execute_action(&execution_result, &mut session.diagnosis_context)?;
+
+        match effect {
+            ActionEffect::Continue => {
+                // LOG_STATE was executed, continue with another cycle
+                session.updated_at = Utc::now();
+                self.diagnosis_repo.update(&session).await?;
+
+                // Recursively run another cycle
+                Box::pin(self.run_diagnosis_cycle(session, _user_id)).await
+            }
+            ActionEffect::FetchPlantVitals => {
+                // Should not happen since we populate vitals at start
+                // But if it does, fetch and continue
+                let plant = self
+                    .plant_repo
+                    .get_by_id(&session.plant_id, &_user_id)
+                    .await?
+                    .ok_or(RequestError::NotFound("Plant"))?;
+
+                let vitals = self.plant_vitals(&plant).await?;
+                if let Some(context) = session.diagnosis_context.as_object_mut() {
+                    context.insert("plant_vitals".to_string(), vitals);
+                }
+
+                session.updated_at = Utc::now();
+                self.diagnosis_repo.update(&session).await?;
+
+                // Run another cycle with vitals now available
+                Box::pin(self.run_diagnosis_cycle(session, _user_id)).await
+            }
+            ActionEffect::AskUser(question) => {
+                // Add AI question to the conversation
+                self.append_message(&session, "assistant", question.clone())
+                    .await?;
+
+                session.ask()?;
+                session.updated_at = Utc::now();
+                self.diagnosis_repo.update(&session).await?;
+
+                Ok(DiagnosisResponseDto::Ask(DiagnosisAskResponse {
+                    diagnosis_id: session.id,
+                    question,
+                }))
+            }
+            ActionEffect::RequestPhoto { part, reason } => {
+                // Record the request so the attached photo can be labelled
+                let message = photo_request_message(&part, reason.as_deref());
+                self.append_message(&session, "assistant", message).await?;
+
+                if let Some(context) = session.diagnosis_context.as_object_mut() {
+                    context.insert(
+                        "pending_photo_request".to_string(),
+                        json!({ "part": part.clone() }),
+                    );
+                }
+
+                session.ask()?;
+                session.updated_at = Utc::now();
+                self.diagnosis_repo.update(&session).await?;
+
+                Ok(DiagnosisResponseDto::RequestPhoto(DiagnosisPhotoRequestResponse {
+                    diagnosis_id: session.id,
+                    part,
+                    reason,
+                }))
+            }
+            ActionEffect::SuggestCareUpdate { changes, reason } => {
+                // Keep the proposal for the conclusion; the user confirms it later
+                if let Some(context) = session.diagnosis_context.as_object_mut() {
+                    context.insert(
+                        "care_update".to_string(),
+                        json!({
+                            "changes": changes,
+                            "reason": reason,
+                            "status": "proposed"
+                        }),
+                    );
+                }
+
+                session.updated_at = Utc::now();
+                self.diagnosis_repo.update(&session).await?;
+
+                Box::pin(self.run_diagnosis_cycle(session, _user_id)).await
+            }
+            ActionEffect::Search(query) => {
+                let entry = self.run_search(&session.diagnosis_context, &query).await;
+
+                if let Some(context) = session.diagnosis_context.as_object_mut() {
+                    let results = context.entry("search_results").or_insert_with(|| json!([]));
+                    if let Some(results) = results.as_array_mut() {
+                        results.push(entry);
+                    }
+                }
+
+                session.updated_at = Utc::now();
+                self.diagnosis_repo.update(&session).await?;
+
+                Box::pin(self.run_diagnosis_cycle(session, _user_id)).await
+            }
+            ActionEffect::Conclude {
+                finding,
+                recommendation,
+                severity,
+                confidence,
+                alternatives,
+            } => {
+                // Link the finding to matching encyclopedia entries
+                let encyclopedia_entries: Vec<String> = encyclopedia::find_mentions(&finding)
+                    .into_iter()
+                    .map(|e| e.name.clone())
+                    .collect();
+
+                // Save result to context
+                if let Some(context) = session.diagnosis_context.as_object_mut() {
+                    context.insert(
+                        "result".to_string(),
+                        json!({
+                            "finding": finding.clone(),
+                            "recommendation": recommendation.clone(),
+                            "encyclopedia_entries": encyclopedia_entries.clone(),
+                            "severity": severity,
+                            "confidence": confidence,
+                            "alternatives": alternatives.clone()
+                        }),
+                    );
+                }
+
+                session.conclude()?;
+                session.updated_at = Utc::now();
+                self.diagnosis_repo.update(&session).await?;
+
+                let care_update = proposed_care_update(&session.diagnosis_context);
+
+                self.event_bus
+                    .publish(NotificationEvent::DiagnosisConcluded {
+                        plant_id: session.plant_id.clone(),
+                        plant_name: session.diagnosis_context["plant_vitals"]["name"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        diagnosis_id: session.id.clone(),
+                        finding: finding.clone(),
+                        recommendation: recommendation.clone(),
+                        severity,
+                    })
+                    .await;
+
+                Ok(DiagnosisResponseDto::Conclude(Box::new(DiagnosisConcludeResponse {
+                    diagnosis_id: session.id,
+                    finding,
+                    recommendation,
+                    encyclopedia_entries,
+                    care_update,
+                    severity,
+                    confidence,
+                    alternatives,
+                })))
+            }
+        }
+    }
+
+    /// Summarize older turns once the context outgrows its token budget,
+    /// dropping them from `history`. Failures are logged and the full
+    /// context is kept.
+    async fn compact_context(&self, ai: &AiAdapter, session: &mut DiagnosisSession, history: &mut Vec<JsonValue>) {
+        let Some(input) = context_budget::compaction_input(
+            &session.diagnosis_context,
+            history,
+            CONTEXT_TOKEN_THRESHOLD,
+        ) else {
+            return;
+        };
+
+        match ai
+            .summarize_diagnosis_context(
+                input.previous_summary.as_deref(),
+                &input.old_turns,
+                &input.state,
+            )
+            .await
+        {
+            Ok(summary) => {
+                log::debug!(
+                    "Summarized {} turns of diagnosis {}",
+                    input.old_turns.len(),
+                    session.id
+                );
+                context_budget::apply_summary(
+                    &mut session.diagnosis_context,
+                    input.old_turns.len(),
+                    summary,
+                );
+                history.drain(..input.old_turns.len());
+            }
+            Err(e) => log::warn!("Could not summarize diagnosis context: {:#}", e),
+        }
+    }
+
+    /// Run a search requested by the AI, returning the context entry to record.
+    /// Failures are recorded too, so the AI knows not to retry.
+    async fn run_search(&self, context: &serde_json::Value, query: &str) -> serde_json::Value {
+        let previous = context["search_results"].as_array().map_or(0, Vec::len);
+        if previous >= MAX_SEARCHES_PER_SESSION {
+            return json!({
+                "query": query,
+                "error": "Search limit reached for this session; continue without searching"
+            });
+        }
+
+        match self.search_port.search(query).await {
+            Ok(results) => json!({
+                "query": query,
+                "results": results
+                    .into_iter()
+                    .map(|r| json!({
+                        "title": r.title,
+                        "snippet": r.snippet.chars().take(MAX_SNIPPET_CHARS).collect::<String>(),
+                        "url": r.url
+                    }))
+                    .collect::<Vec<_>>()
+            }),
+            Err(e) => {
+                log::warn!("Diagnosis search for '{}' failed: {:#}", query, e);
+                json!({ "query": query, "error": "Search failed" })
+            }
+        }
+    }
+
+    /// Decode and store a base64 encoded diagnosis photo, returning it with
+    /// the URL it was stored at
+    async fn store_photo(&self, encoded: &str) -> Result<(Vec<u8>, String)> {
+        let image_data = BASE64.decode(encoded).map_err(|_| PayloadError::InvalidEncoding {
+            name: "The photo".to_string(),
+        })?;
+        let extension = payload::check_image("The photo", &image_data)?
+            .extensions_str()
+            .first()
+            .copied()
+            .unwrap_or("jpg");
+        let filename = format!("diagnosis-{}.{}", uuid::Uuid::new_v4(), extension);
+        let url = self.storage_adapter.upload_image(&image_data, &filename).await?;
+        Ok((image_data, url))
+    }
+
+    /// Read the most recently attached photos so the AI can see them.
+    /// Photos that can no longer be read are skipped.
+    async fn load_recent_photos(&self, context: &serde_json::Value) -> Vec<ImageAttachment> {
+        let Some(photos) = context["photos"].as_array() else {
+            return Vec::new();
+        };
+
+        let mut attachments = Vec::new();
+        for photo in photos.iter().rev().take(MAX_PHOTOS_PER_CYCLE).rev() {
+            let Some(url) = photo["url"].as_str() else {
+                continue;
+            };
+            match self.storage_adapter.read_image(url).await {
+                Ok(data) => attachments.push(ImageAttachment {
+                    description: photo["part"].as_str().unwrap_or("plant").to_string(),
+                    data,
+                }),
+                Err(e) => log::warn!("Could not read diagnosis photo {}: {:#}", url, e),
+            }
+        }
+
+        attachments
+    }
+}
+
+/// Care schedule changes proposed during a diagnosis and not yet applied
+/// The turn recording the AI's request for a photo
+fn photo_request_message(part: &str, reason: Option<&str>) -> String {
+    match reason {
+        Some(reason) => format!("{}{}. {}", PHOTO_REQUEST_PREFIX, part, reason),
+        None => format!("{}{}.", PHOTO_REQUEST_PREFIX, part),
+    }
+}
+
+/// The part and reason of a turn recording a request for a photo
+fn parse_photo_request(message: &str) -> Option<(String, Option<String>)> {
+    let rest = message.strip_prefix(PHOTO_REQUEST_PREFIX)?;
+    let (part, reason) = rest.split_once('.')?;
+    let reason = Some(reason.trim()).filter(|r| !r.is_empty()).map(str::to_string);
+    Some((part.to_string(), reason))
+}
+
+/// Cost in USD above which starting a diagnosis is confirmed
+fn confirm_cost() -> Result<f64> {
+    match std::env::var("DIAGNOSIS_CONFIRM_COST") {
+        Ok(cost) if !cost.trim().is_empty() => cost
+            .trim()
+            .trim_start_matches('$')
+            .parse::<f64>()
+            .ok()
+            .filter(|c| c.is_finite() && *c >= 0.0)
+            .with_context(|| format!("Invalid DIAGNOSIS_CONFIRM_COST '{}': expected an amount in USD, like 0.10", cost)),
+        _ => Ok(DEFAULT_CONFIRM_COST),
+    }
+}
+
+/// Add the tokens of a cycle to the usage recorded in the context
+fn add_usage(context: &mut JsonValue, cycle: &TokenUsage) {
+    let mut usage = TokenUsage::of_session(context).unwrap_or_default();
+    usage.add(cycle);
+    context["usage"] = json!(usage);
+}
+
+fn proposed_care_update(context: &serde_json::Value) -> Option<CareUpdateSuggestionDto> {
+    let care_update = &context["care_update"];
+    if care_update["status"] != "proposed" {
+        return None;
+    }
+
+    Some(CareUpdateSuggestionDto {
+        changes: serde_json::from_value(care_update["changes"].clone()).ok()?,
+        reason: care_update["reason"].as_str().map(str::to_string),
+    })
+}
+
+/// URLs of photos attached to a diagnosis
+fn photo_urls(context: &serde_json::Value) -> Vec<&str> {
+    context["photos"]
+        .as_array()
+        .map(|photos| photos.iter().filter_map(|p| p["url"].as_str()).collect())
+        .unwrap_or_default()
+}
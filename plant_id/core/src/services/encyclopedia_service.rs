@@ -0,0 +1,76 @@
+/*!
+ * ENCYCLOPEDIA SERVICE
+ *
+ * Looks up pests and diseases in the bundled encyclopedia, falling back to
+ * the AI for anything not covered, and works out which plants in the
+ * collection are susceptible.
+ */
+
+use anyhow::{Context, Result};
+
+use crate::adapters::AiAdapter;
+use crate::domain::encyclopedia::{self, EncyclopediaEntry, EntryKind};
+use crate::domain::Plant;
+use crate::repositories::PlantRepository;
+
+/// Where an encyclopedia entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntrySource {
+    Bundled,
+    Ai,
+}
+
+#[derive(Debug, Clone)]
+pub struct LookupResult {
+    pub entry: EncyclopediaEntry,
+    pub source: EntrySource,
+    pub susceptible_plants: Vec<Plant>,
+}
+
+pub struct EncyclopediaService {
+    plant_repo: PlantRepository,
+    /// Only needed for entries missing from the bundled encyclopedia
+    ai_adapter: Option<AiAdapter>,
+}
+
+impl EncyclopediaService {
+    pub fn new(plant_repo: PlantRepository, ai_adapter: Option<AiAdapter>) -> Self {
+        Self {
+            plant_repo,
+            ai_adapter,
+        }
+    }
+
+    pub async fn lookup(&self, kind: EntryKind, name: &str, user_id: &str) -> Result<LookupResult> {
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+
+        let (entry, source) = match encyclopedia::lookup(kind, name) {
+            Some(entry) => (entry.clone(), EntrySource::Bundled),
+            None => {
+                let ai_adapter = self.ai_adapter.as_ref().with_context(|| {
+                    format!(
+                        "'{}' is not in the bundled encyclopedia and no AI is configured (run `plant-care init`)",
+                        name
+                    )
+                })?;
+                let plant_names: Vec<String> = plants.iter().map(|p| p.name.clone()).collect();
+                let mut entry = ai_adapter
+                    .describe_encyclopedia_entry(kind, name, &plant_names)
+                    .await?;
+                entry.susceptible = entry.susceptible.iter().map(|s| s.to_lowercase()).collect();
+                (entry, EntrySource::Ai)
+            }
+        };
+
+        let susceptible_plants = plants
+            .into_iter()
+            .filter(|p| entry.is_susceptible(p))
+            .collect();
+
+        Ok(LookupResult {
+            entry,
+            source,
+            susceptible_plants,
+        })
+    }
+}
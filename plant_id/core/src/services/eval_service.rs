@@ -0,0 +1,298 @@
+/*!
+ * EVAL SERVICE
+ *
+ * Runs canned diagnosis scenarios against one or more AI models and scores
+ * the conclusions against the expected findings, so models can be compared
+ * before switching `AI_MODEL`.
+ *
+ * Each model runs against a private in-memory database; the user's
+ * collection is never touched.
+ */
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::adapters::{AiAdapter, SearchPort, StoragePort};
+use crate::config::Database;
+use crate::domain::{CareSchedule, Plant};
+use crate::dto::{
+    AlternativeDiagnosisDto, DiagnosisConcludeResponse, DiagnosisResponseDto, DiagnosisStartDto,
+    DiagnosisUpdateDto,
+};
+use crate::repositories::{
+    AttributeRepository, DiagnosisRepository, MoistureRepository, OutcomeRepository, PlantRepository, RoomRepository,
+    UserRepository,
+};
+use crate::services::{DiagnosisService, EventBus};
+
+/// Upper bound on answers per scenario, in case a model never concludes
+const MAX_TURNS: usize = 10;
+
+/// Reply used once a scenario's scripted answers run out
+const FALLBACK_ANSWER: &str = "I'm not sure.";
+
+/// Score for an expected finding that only appears among the alternatives
+const ALTERNATIVE_SCORE: f64 = 0.5;
+
+const EVAL_USER_ID: &str = "eval";
+
+/// A canned diagnosis scenario
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvalCase {
+    pub name: String,
+    /// Plant species the scenario is about
+    pub plant: String,
+    /// Problem description that starts the diagnosis
+    pub problem: String,
+    /// Answers given, in order, to the AI's questions
+    #[serde(default)]
+    pub answers: Vec<String>,
+    /// Acceptable findings; a conclusion matches if it contains any of them
+    pub expected: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EvalSuite {
+    cases: Vec<EvalCase>,
+}
+
+/// Parse a YAML file with a top-level `cases` list
+pub fn parse_cases(yaml: &str) -> Result<Vec<EvalCase>> {
+    let suite: EvalSuite = serde_yaml::from_str(yaml).context("Invalid eval cases file")?;
+
+    if suite.cases.is_empty() {
+        anyhow::bail!("Eval cases file contains no cases");
+    }
+    if let Some(case) = suite.cases.iter().find(|c| c.expected.is_empty()) {
+        anyhow::bail!("Eval case '{}' has no expected findings", case.name);
+    }
+
+    Ok(suite.cases)
+}
+
+/// Score a conclusion: 1 if the finding matches, `ALTERNATIVE_SCORE` if only
+/// an alternative does, 0 otherwise
+pub fn score_conclusion(
+    finding: &str,
+    alternatives: &[AlternativeDiagnosisDto],
+    expected: &[String],
+) -> f64 {
+    let matches = |text: &str| {
+        let text = text.to_lowercase();
+        expected.iter().any(|e| text.contains(&e.to_lowercase()))
+    };
+
+    if matches(finding) {
+        1.0
+    } else if alternatives.iter().any(|a| matches(&a.finding)) {
+        ALTERNATIVE_SCORE
+    } else {
+        0.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub case_name: String,
+    pub finding: Option<String>,
+    pub score: f64,
+    /// Number of scripted answers given before the conclusion
+    pub turns: usize,
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelReport {
+    pub model: String,
+    pub results: Vec<CaseResult>,
+}
+
+impl ModelReport {
+    /// Cases whose finding matched exactly
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|r| r.score >= 1.0).count()
+    }
+
+    pub fn errors(&self) -> usize {
+        self.results.iter().filter(|r| r.error.is_some()).count()
+    }
+
+    pub fn mean_score(&self) -> f64 {
+        mean(self.results.iter().map(|r| r.score))
+    }
+
+    pub fn mean_turns(&self) -> f64 {
+        mean(self.results.iter().map(|r| r.turns as f64))
+    }
+
+    pub fn mean_seconds(&self) -> f64 {
+        mean(self.results.iter().map(|r| r.elapsed.as_secs_f64()))
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+pub struct EvalService {
+    storage_adapter: Arc<dyn StoragePort>,
+    search_port: Arc<dyn SearchPort>,
+}
+
+impl EvalService {
+    pub fn new(storage_adapter: Arc<dyn StoragePort>, search_port: Arc<dyn SearchPort>) -> Self {
+        Self {
+            storage_adapter,
+            search_port,
+        }
+    }
+
+    /// Run every case against one model. Failing cases are recorded with
+    /// their error instead of aborting the run.
+    pub async fn run_model(&self, model: &str, cases: &[EvalCase]) -> Result<ModelReport> {
+        let db = Database::in_memory().await?;
+        db.migrate().await?;
+
+        let plant_repo = PlantRepository::new(db.clone());
+        let diagnosis_service = DiagnosisService::new(
+            plant_repo.clone(),
+            DiagnosisRepository::new(db.clone()),
+            OutcomeRepository::new(db.clone()),
+            MoistureRepository::new(db.clone()),
+            AttributeRepository::new(db.clone()),
+            RoomRepository::new(db.clone()),
+            UserRepository::new(db),
+            AiAdapter::with_model(model.to_string())?,
+            self.storage_adapter.clone(),
+            self.search_port.clone(),
+            // Eval runs are not real diagnoses, so nothing is announced
+            EventBus::default(),
+        );
+
+        let mut results = Vec::new();
+        for case in cases {
+            let started = Instant::now();
+            let plant = Plant::new(
+                EVAL_USER_ID.to_string(),
+                case.plant.clone(),
+                CareSchedule::default(),
+            );
+            let plant = plant_repo.create(&plant).await?;
+
+            let result = match run_case(&diagnosis_service, &plant.id, case).await {
+                Ok((conclusion, turns)) => CaseResult {
+                    case_name: case.name.clone(),
+                    score: score_conclusion(
+                        &conclusion.finding,
+                        &conclusion.alternatives,
+                        &case.expected,
+                    ),
+                    finding: Some(conclusion.finding),
+                    turns,
+                    elapsed: started.elapsed(),
+                    error: None,
+                },
+                Err(e) => CaseResult {
+                    case_name: case.name.clone(),
+                    finding: None,
+                    score: 0.0,
+                    turns: 0,
+                    elapsed: started.elapsed(),
+                    error: Some(format!("{:#}", e)),
+                },
+            };
+            results.push(result);
+        }
+
+        Ok(ModelReport {
+            model: model.to_string(),
+            results,
+        })
+    }
+}
+
+/// Drive one diagnosis to its conclusion with the scripted answers
+async fn run_case(
+    diagnosis_service: &DiagnosisService,
+    plant_id: &str,
+    case: &EvalCase,
+) -> Result<(DiagnosisConcludeResponse, usize)> {
+    let dto = DiagnosisStartDto {
+        prompt: case.problem.clone(),
+        photo: None,
+    };
+    let mut response = diagnosis_service
+        .start_diagnosis(plant_id, dto, EVAL_USER_ID.to_string())
+        .await?;
+    let mut answers = case.answers.iter();
+    let mut turns = 0;
+
+    loop {
+        let (diagnosis_id, message) = match response {
+            DiagnosisResponseDto::Conclude(conclusion) => return Ok((*conclusion, turns)),
+            DiagnosisResponseDto::Ask(ask) => (
+                ask.diagnosis_id,
+                answers.next().map_or(FALLBACK_ANSWER, String::as_str).to_string(),
+            ),
+            DiagnosisResponseDto::RequestPhoto(request) => (
+                request.diagnosis_id,
+                format!("I can't take a photo of the {} right now.", request.part),
+            ),
+        };
+
+        turns += 1;
+        if turns > MAX_TURNS {
+            anyhow::bail!("No conclusion after {} answers", MAX_TURNS);
+        }
+
+        response = diagnosis_service
+            .update_diagnosis(
+                &diagnosis_id,
+                DiagnosisUpdateDto { message, photo: None },
+                EVAL_USER_ID.to_string(),
+            )
+            .await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cases() {
+        let yaml = r#"
+cases:
+  - name: soggy monstera
+    plant: Monstera deliciosa
+    problem: Yellowing lower leaves
+    answers: ["Every two days", "The soil smells sour"]
+    expected: [overwatering, root rot]
+"#;
+        let cases = parse_cases(yaml).unwrap();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].answers.len(), 2);
+        assert!(parse_cases("cases: []").is_err());
+    }
+
+    #[test]
+    fn test_score_conclusion() {
+        let expected = vec!["root rot".to_string()];
+        let alternatives = vec![AlternativeDiagnosisDto {
+            finding: "Root rot".to_string(),
+            probability: 0.2,
+        }];
+
+        assert_eq!(score_conclusion("Early root rot", &[], &expected), 1.0);
+        assert_eq!(score_conclusion("Overwatering", &alternatives, &expected), ALTERNATIVE_SCORE);
+        assert_eq!(score_conclusion("Sun scorch", &[], &expected), 0.0);
+    }
+}
@@ -0,0 +1,172 @@
+/*!
+ * EVENT BUS
+ *
+ * Fans notification events out to every subscribed `NotificationPort`.
+ * Delivery is best effort: a failing webhook is logged and never fails
+ * the operation that raised the event. With a job queue, the event is
+ * queued for that webhook and redelivered by the job worker.
+ */
+
+use std::sync::Arc;
+
+use crate::adapters::NotificationPort;
+use crate::domain::NotificationEvent;
+#[cfg(feature = "native")]
+use crate::repositories::JobRepository;
+
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Vec<Arc<dyn NotificationPort>>,
+    /// Where failed deliveries are queued
+    #[cfg(feature = "native")]
+    queue: Option<JobRepository>,
+}
+
+impl EventBus {
+    pub fn new(subscribers: Vec<Arc<dyn NotificationPort>>) -> Self {
+        Self {
+            subscribers,
+            #[cfg(feature = "native")]
+            queue: None,
+        }
+    }
+
+    /// Number of subscribed webhooks
+    pub fn len(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// Deliver an event to every subscriber routed to take it, returning
+    /// how many succeeded
+    pub async fn publish(&self, event: NotificationEvent) -> usize {
+        self.deliver(event, true).await
+    }
+
+    /// Deliver an event to every subscriber whatever their routes, as
+    /// `notify test` does to try them all
+    pub async fn publish_to_all(&self, event: NotificationEvent) -> usize {
+        self.deliver(event, false).await
+    }
+
+    async fn deliver(&self, event: NotificationEvent, routed: bool) -> usize {
+        let mut delivered = 0;
+
+        for subscriber in &self.subscribers {
+            if routed && !subscriber.accepts(&event) {
+                continue;
+            }
+            match subscriber.notify(&event).await {
+                Ok(()) => delivered += 1,
+                Err(e) => {
+                    log::warn!(
+                        "Failed to deliver {} to {} webhook: {:#}",
+                        event.name(),
+                        subscriber.name(),
+                        e
+                    );
+                    #[cfg(feature = "native")]
+                    self.queue_delivery(subscriber, &event, format!("{:#}", e)).await;
+                }
+            }
+        }
+
+        delivered
+    }
+}
+
+#[cfg(feature = "native")]
+mod queue {
+    use anyhow::{Context, Result};
+    use async_trait::async_trait;
+    use serde::{Deserialize, Serialize};
+    use std::sync::Arc;
+
+    use super::EventBus;
+    use crate::adapters::NotificationPort;
+    use crate::domain::{Job, JobKind, NotificationEvent};
+    use crate::repositories::JobRepository;
+    use crate::services::{JobHandler, JobOutcome};
+
+    /// Payload of a webhook delivery job
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Delivery {
+        /// Position of the webhook in NOTIFY_WEBHOOKS
+        webhook: usize,
+        /// Its backend, to tell whether the configuration changed since
+        backend: String,
+        event: NotificationEvent,
+    }
+
+    impl EventBus {
+        /// Queue failed deliveries, to be retried by the job worker
+        pub fn with_queue(mut self, queue: JobRepository) -> Self {
+            self.queue = Some(queue);
+            self
+        }
+
+        pub(super) async fn queue_delivery(
+            &self,
+            subscriber: &Arc<dyn NotificationPort>,
+            event: &NotificationEvent,
+            error: String,
+        ) {
+            let (Some(queue), Some(webhook)) = (
+                &self.queue,
+                self.subscribers.iter().position(|s| Arc::ptr_eq(s, subscriber)),
+            ) else {
+                return;
+            };
+            let delivery = Delivery {
+                webhook,
+                backend: subscriber.name().to_string(),
+                event: event.clone(),
+            };
+            let queued = match serde_json::to_value(delivery) {
+                Ok(payload) => {
+                    let job = Job {
+                        last_error: Some(error),
+                        ..Job::new(None, JobKind::DeliverWebhook).with_payload(payload)
+                    };
+                    queue.enqueue(&job).await.map(|_| ())
+                }
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = queued {
+                log::warn!("Could not queue {} for redelivery: {:#}", event.name(), e);
+            }
+        }
+    }
+
+    #[async_trait]
+    impl JobHandler for EventBus {
+        fn kind(&self) -> JobKind {
+            JobKind::DeliverWebhook
+        }
+
+        async fn run(&self, job: &Job) -> Result<JobOutcome> {
+            let delivery: Delivery = serde_json::from_value(job.payload.clone().unwrap_or_default())
+                .context("Malformed webhook delivery")?;
+            let Some(subscriber) = self
+                .subscribers
+                .get(delivery.webhook)
+                .filter(|s| s.name() == delivery.backend)
+            else {
+                return Ok(JobOutcome::Skipped(format!(
+                    "The {} webhook is no longer configured",
+                    delivery.backend
+                )));
+            };
+
+            subscriber.notify(&delivery.event).await?;
+            Ok(JobOutcome::Done(format!(
+                "{} delivered to {}",
+                delivery.event.name(),
+                subscriber.name()
+            )))
+        }
+    }
+}
@@ -0,0 +1,327 @@
+/*!
+ * EXPLAIN SERVICE
+ *
+ * Explains why a plant's stored care guidance makes sense: the AI is given
+ * the guidance, what is known about where the plant lives (its room, its
+ * attributes, soil moisture readings) and numbered reference sources - the
+ * species database entry and encyclopedia articles about the species - and
+ * cites the sources its explanation relies on. A plant sitter cannot ask
+ * for an explanation, as it calls the AI.
+ */
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::adapters::{AiAdapter, SearchPort, SpeciesDataPort};
+use crate::domain::{moisture_reading, CareSchedule, Permission, Plant, RequestError};
+use crate::repositories::{
+    AttributeRepository, MoistureRepository, PlantRepository, RoomRepository, ScheduleVersionRepository,
+    UserRepository,
+};
+
+/// Encyclopedia articles given to the AI as sources
+const MAX_ARTICLES: usize = 2;
+
+/// The part of a care schedule to explain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CareTopic {
+    Water,
+    Light,
+    Humidity,
+    Temperature,
+}
+
+impl CareTopic {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Water => "water",
+            Self::Light => "light",
+            Self::Humidity => "humidity",
+            Self::Temperature => "temperature",
+        }
+    }
+
+    /// The schedule's guidance on the topic
+    pub fn guidance<'a>(&self, schedule: &'a CareSchedule) -> &'a str {
+        match self {
+            Self::Water => &schedule.water,
+            Self::Light => &schedule.light,
+            Self::Humidity => &schedule.humidity,
+            Self::Temperature => &schedule.temperature,
+        }
+    }
+}
+
+/// A reference the explanation cites
+#[derive(Debug, Clone, Serialize)]
+pub struct Citation {
+    /// Number the explanation cites it by, e.g. 1 for "[1]"
+    pub number: usize,
+    pub title: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CareExplanation {
+    pub topic: CareTopic,
+    pub guidance: String,
+    /// Where the current schedule came from, e.g. "generated by gpt-4o"
+    pub guidance_source: Option<String>,
+    pub explanation: String,
+    /// Only the sources the explanation cites
+    pub citations: Vec<Citation>,
+}
+
+/// A reference given to the AI
+struct Source {
+    citation: Citation,
+    excerpt: Value,
+}
+
+pub struct ExplainService {
+    plant_repo: PlantRepository,
+    attribute_repo: AttributeRepository,
+    moisture_repo: MoistureRepository,
+    room_repo: RoomRepository,
+    version_repo: ScheduleVersionRepository,
+    user_repo: UserRepository,
+    ai_adapter: AiAdapter,
+    search: Arc<dyn SearchPort>,
+    species_data: Option<Arc<dyn SpeciesDataPort>>,
+}
+
+impl ExplainService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        plant_repo: PlantRepository,
+        attribute_repo: AttributeRepository,
+        moisture_repo: MoistureRepository,
+        room_repo: RoomRepository,
+        version_repo: ScheduleVersionRepository,
+        user_repo: UserRepository,
+        ai_adapter: AiAdapter,
+        search: Arc<dyn SearchPort>,
+        species_data: Option<Arc<dyn SpeciesDataPort>>,
+    ) -> Self {
+        Self {
+            plant_repo,
+            attribute_repo,
+            moisture_repo,
+            room_repo,
+            version_repo,
+            user_repo,
+            ai_adapter,
+            search,
+            species_data,
+        }
+    }
+
+    /// Explain the plant's guidance on `topic`
+    pub async fn explain(&self, plant_id: &str, user_id: &str, topic: CareTopic) -> Result<CareExplanation> {
+        self.user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+
+        let plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+        let guidance = topic.guidance(&plant.care_schedule).to_string();
+        let guidance_source = self
+            .version_repo
+            .get_latest_by_plant_id(&plant.id)
+            .await?
+            .map(|version| version.source.describe());
+
+        let sources = self.sources(&plant).await;
+        let request = json!({
+            "plant": plant.name,
+            "species": plant.scientific_name,
+            "family": plant.family,
+            "topic": topic.as_str(),
+            "guidance": guidance,
+            "surroundings": self.surroundings(&plant).await?,
+            "sources": sources
+                .iter()
+                .map(|s| json!({"number": s.citation.number, "title": s.citation.title, "excerpt": s.excerpt}))
+                .collect::<Vec<_>>(),
+        });
+
+        let answer = self.ai_adapter.explain_care(&request).await?;
+        let (explanation, cited) = check_citations(&answer.explanation, &answer.cited, sources.len());
+        let citations = sources
+            .into_iter()
+            .map(|s| s.citation)
+            .filter(|c| cited.contains(&c.number))
+            .collect();
+
+        Ok(CareExplanation {
+            topic,
+            guidance,
+            guidance_source,
+            explanation,
+            citations,
+        })
+    }
+
+    /// The room, attributes and soil moisture readings of the plant
+    async fn surroundings(&self, plant: &Plant) -> Result<Value> {
+        let mut surroundings = json!({});
+
+        if let Some(room) = self.room_repo.profile_for(plant).await? {
+            surroundings["room"] = serde_json::to_value(room)?;
+        }
+
+        let attributes = self.attribute_repo.get_all_by_plant_id(&plant.id).await?;
+        if !attributes.is_empty() {
+            surroundings["attributes"] = attributes
+                .into_iter()
+                .map(|a| (a.key, Value::String(a.value)))
+                .collect::<serde_json::Map<_, _>>()
+                .into();
+        }
+
+        let readings = self.moisture_repo.get_all_by_plant_id(&plant.id).await?;
+        if let Some(trend) = moisture_reading::analyze(&readings, &plant.care_schedule, Utc::now()) {
+            surroundings["soil_moisture"] = json!({
+                "summary": trend.summary(),
+                "warning": trend.warning(),
+            });
+        }
+
+        Ok(surroundings)
+    }
+
+    /// The species database entry and encyclopedia articles about the
+    /// species, numbered from 1. Lookups that fail are left out.
+    async fn sources(&self, plant: &Plant) -> Vec<Source> {
+        let species = plant.scientific_name.as_deref().unwrap_or(&plant.name);
+        let mut sources = Vec::new();
+
+        if let Some(species_data) = &self.species_data {
+            match species_data.lookup(species).await {
+                Ok(Some(profile)) => sources.push(Source {
+                    citation: Citation {
+                        number: sources.len() + 1,
+                        title: format!("{}: {}", profile.source, profile.scientific_name),
+                        url: None,
+                    },
+                    excerpt: json!(profile),
+                }),
+                Ok(None) => {}
+                Err(e) => log::warn!("{} lookup failed for {}: {:#}", species_data.name(), species, e),
+            }
+        }
+
+        match self.search.search(species).await {
+            Ok(results) => {
+                for result in results.into_iter().take(MAX_ARTICLES) {
+                    sources.push(Source {
+                        citation: Citation {
+                            number: sources.len() + 1,
+                            title: result.title,
+                            url: result.url,
+                        },
+                        excerpt: json!(result.snippet),
+                    });
+                }
+            }
+            Err(e) => log::warn!("Search for {} failed: {:#}", species, e),
+        }
+
+        sources
+    }
+}
+
+/// The explanation without citations of sources it was not given, and the
+/// numbers of those it cites, in the text as "[n]" or in the AI's list
+fn check_citations(explanation: &str, listed: &[usize], sources: usize) -> (String, Vec<usize>) {
+    let known = |number: usize| (1..=sources).contains(&number);
+    let mut cited: Vec<usize> = listed.iter().copied().filter(|n| known(*n)).collect();
+    let mut checked = String::with_capacity(explanation.len());
+
+    let mut rest = explanation;
+    while let Some(start) = rest.find('[') {
+        let marker = rest[start + 1..]
+            .find(']')
+            .and_then(|end| Some((end, rest[start + 1..start + 1 + end].trim().parse::<usize>().ok()?)));
+        match marker {
+            Some((end, number)) => {
+                if known(number) {
+                    cited.push(number);
+                    checked.push_str(&rest[..start + end + 2]);
+                } else {
+                    checked.push_str(rest[..start].trim_end());
+                }
+                rest = &rest[start + end + 2..];
+            }
+            None => {
+                checked.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    checked.push_str(rest);
+
+    cited.sort_unstable();
+    cited.dedup();
+    (checked, cited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::search_adapter::EncyclopediaSearchAdapter;
+    use crate::config::Database;
+    use crate::domain::{User, UserRole};
+
+    #[test]
+    fn test_check_citations() {
+        let (text, cited) = check_citations("Native to rainforests [2], so it likes moist soil [1][2].", &[], 2);
+        assert_eq!(text, "Native to rainforests [2], so it likes moist soil [1][2].");
+        assert_eq!(cited, vec![1, 2]);
+
+        // Sources it was not given are dropped
+        let (text, cited) = check_citations("It climbs trees [1], in loose soil [7].", &[1, 7], 1);
+        assert_eq!(text, "It climbs trees [1], in loose soil.");
+        assert_eq!(cited, vec![1]);
+        let (text, cited) = check_citations("Water [sparingly] in winter [1].", &[], 0);
+        assert_eq!(text, "Water [sparingly] in winter.");
+        assert!(cited.is_empty());
+
+        assert_eq!(CareTopic::Water.guidance(&CareSchedule::default()), "Water when top inch of soil is dry");
+    }
+
+    #[tokio::test]
+    async fn test_sitter_cannot_ask_for_an_explanation() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        // Only needed to build the adapter; the AI is never called
+        std::env::set_var("OPENROUTER_API_KEY", "test-key");
+        let plants = PlantRepository::new(db.clone());
+        let users = UserRepository::new(db.clone());
+        let service = ExplainService::new(
+            plants.clone(),
+            AttributeRepository::new(db.clone()),
+            MoistureRepository::new(db.clone()),
+            RoomRepository::new(db.clone()),
+            ScheduleVersionRepository::new(db),
+            users.clone(),
+            AiAdapter::new().unwrap(),
+            Arc::new(EncyclopediaSearchAdapter),
+            None,
+        );
+
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&fern).await.unwrap();
+        let mut sitter = User::owner("u".to_string());
+        sitter.role = UserRole::Sitter;
+        users.save(&sitter).await.unwrap();
+
+        let error = service.explain(&fern.id, "u", CareTopic::Water).await.unwrap_err();
+        assert!(error.to_string().contains("cannot start AI sessions"));
+    }
+}
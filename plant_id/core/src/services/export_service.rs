@@ -0,0 +1,137 @@
+/*!
+ * EXPORT SERVICE
+ *
+ * Flattens the collection, diagnosis history and upcoming care into row
+ * records and renders them in spreadsheet-friendly formats.
+ */
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::config;
+use crate::domain::reminder;
+use crate::dto::{DiagnosisExportRow, PlantExportRow, ReminderExportRow};
+use crate::repositories::{
+    CareTaskRepository, DiagnosisRepository, InspectionRepository, PlantRepository, QuarantineRepository,
+};
+
+pub struct ExportService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    care_task_repo: CareTaskRepository,
+    quarantine_repo: QuarantineRepository,
+    inspection_repo: InspectionRepository,
+}
+
+impl ExportService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        care_task_repo: CareTaskRepository,
+        quarantine_repo: QuarantineRepository,
+        inspection_repo: InspectionRepository,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            care_task_repo,
+            quarantine_repo,
+            inspection_repo,
+        }
+    }
+
+    pub async fn plant_rows(&self, user_id: &str) -> Result<Vec<PlantExportRow>> {
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+
+        Ok(plants
+            .into_iter()
+            .map(|p| PlantExportRow {
+                id: p.id,
+                name: p.name,
+                nickname: p.nickname,
+                room: p.room,
+                tags: p.tags.join("; "),
+                difficulty: p.difficulty.map(|d| d.rating),
+                light: p.care_schedule.light,
+                water: p.care_schedule.water,
+                humidity: p.care_schedule.humidity,
+                temperature: p.care_schedule.temperature,
+                care_instructions: p.care_schedule.care_instructions,
+                image_url: p.image_url,
+                created_at: p.created_at,
+                updated_at: p.updated_at,
+            })
+            .collect())
+    }
+
+    pub async fn diagnosis_rows(&self, user_id: &str) -> Result<Vec<DiagnosisExportRow>> {
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        let mut rows = Vec::new();
+
+        for plant in plants {
+            let sessions = self
+                .diagnosis_repo
+                .get_all_by_plant_id(&plant.id, user_id)
+                .await?;
+
+            for session in sessions {
+                let context = &session.diagnosis_context;
+                let status = session.status();
+                rows.push(DiagnosisExportRow {
+                    id: session.id,
+                    plant_id: plant.id.clone(),
+                    plant_name: plant.display_name().to_string(),
+                    status: status.as_str().to_string(),
+                    problem: context["initial_prompt"].as_str().unwrap_or_default().to_string(),
+                    finding: context["result"]["finding"].as_str().map(String::from),
+                    recommendation: context["result"]["recommendation"]
+                        .as_str()
+                        .map(String::from),
+                    created_at: session.created_at,
+                    updated_at: session.updated_at,
+                });
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Everything coming up for each plant, soonest first within a plant.
+    /// Days are those of the owner's time zone.
+    pub async fn reminder_rows(&self, user_id: &str) -> Result<Vec<ReminderExportRow>> {
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        let zone = config::local_zone();
+        let today = zone.date(Utc::now());
+        let mut rows = Vec::new();
+
+        for plant in plants {
+            let tasks = self.care_task_repo.get_all_by_plant_id(&plant.id).await?;
+            let quarantine = self.quarantine_repo.get_active_by_plant_id(&plant.id).await?;
+            let inspections = self.inspection_repo.get_open_by_plant_id(&plant.id).await?;
+
+            let reminders =
+                reminder::reminders(&plant, &tasks, quarantine.as_ref(), &inspections, today, zone);
+            rows.extend(reminders.into_iter().map(|r| ReminderExportRow {
+                plant_id: plant.id.clone(),
+                plant_name: plant.display_name().to_string(),
+                kind: r.kind,
+                due: r.due,
+                overdue_days: r.overdue_days(today),
+                source_id: r.source_id,
+            }));
+        }
+
+        Ok(rows)
+    }
+}
+
+/// Render rows as CSV with a header line
+pub fn to_csv<T: Serialize>(rows: &[T]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row)?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
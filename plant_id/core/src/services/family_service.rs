@@ -0,0 +1,206 @@
+/*!
+ * FAMILY SERVICE
+ *
+ * Groups the collection by botanical family or genus, and follows a pest or
+ * disease diagnosis of one plant to its relatives: they are pointed out,
+ * those the encyclopedia lists as susceptible first, and can be given a
+ * preventive inspection on the checklist.
+ */
+
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::domain::encyclopedia::{self, EncyclopediaEntry};
+use crate::domain::{plant_family, DiagnosisState, Plant, PlantGroup, PreventiveInspection, RequestError};
+use crate::repositories::{DiagnosisRepository, InspectionRepository, PlantRepository};
+
+/// A relative of a diagnosed plant
+#[derive(Debug, Clone)]
+pub struct RelativeAtRisk {
+    pub plant: Plant,
+    /// Whether the encyclopedia lists the plant as susceptible to what was found
+    pub susceptible: bool,
+}
+
+/// A pest or disease found on a plant that has relatives in the collection
+#[derive(Debug, Clone)]
+pub struct FamilyWarning {
+    pub diagnosis_id: String,
+    pub plant: Plant,
+    /// Names of the pests and diseases found
+    pub threats: Vec<String>,
+    /// Susceptible relatives first
+    pub relatives: Vec<RelativeAtRisk>,
+}
+
+pub struct FamilyService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    inspection_repo: InspectionRepository,
+}
+
+impl FamilyService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        inspection_repo: InspectionRepository,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            inspection_repo,
+        }
+    }
+
+    /// A user's plants by family, or genus when the family is unknown
+    pub async fn groups(&self, user_id: &str) -> Result<Vec<PlantGroup>> {
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        Ok(plant_family::group(&plants))
+    }
+
+    /// The relatives to warn about after a diagnosis, when it concluded with
+    /// a pest or disease of the encyclopedia and the plant has any
+    pub async fn warning(&self, diagnosis_id: &str, user_id: &str) -> Result<Option<FamilyWarning>> {
+        let session = self
+            .diagnosis_repo
+            .get_by_id(diagnosis_id)
+            .await?
+            .ok_or(RequestError::NotFound("Diagnosis session"))?;
+        let plant = self
+            .plant_repo
+            .get_by_id(&session.plant_id, user_id)
+            .await?
+            .ok_or(RequestError::Unauthorized("diagnosis"))?;
+        if session.state != DiagnosisState::Concluded {
+            return Ok(None);
+        }
+
+        let threats = threats(&session.diagnosis_context["result"]);
+        if threats.is_empty() {
+            return Ok(None);
+        }
+
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        let mut relatives: Vec<RelativeAtRisk> = plant_family::relatives(&plant, &plants)
+            .into_iter()
+            .map(|relative| RelativeAtRisk {
+                susceptible: threats.iter().any(|t| t.is_susceptible(relative)),
+                plant: relative.clone(),
+            })
+            .collect();
+        if relatives.is_empty() {
+            return Ok(None);
+        }
+        relatives.sort_by_key(|r| !r.susceptible);
+
+        Ok(Some(FamilyWarning {
+            diagnosis_id: session.id,
+            plant,
+            threats: threats.iter().map(|t| t.name.clone()).collect(),
+            relatives,
+        }))
+    }
+
+    /// Put a preventive inspection of each relative on the checklist for
+    /// `due_on`, skipping those already scheduled for the same diagnosis
+    pub async fn schedule_inspections(
+        &self,
+        warning: &FamilyWarning,
+        due_on: NaiveDate,
+    ) -> Result<Vec<PreventiveInspection>> {
+        let reason = format!("{} (found on {})", warning.threats.join(", "), warning.plant.display_name());
+
+        let mut scheduled = Vec::new();
+        for relative in &warning.relatives {
+            let open = self.inspection_repo.get_open_by_plant_id(&relative.plant.id).await?;
+            if open
+                .iter()
+                .any(|i| i.source_diagnosis_id.as_deref() == Some(warning.diagnosis_id.as_str()))
+            {
+                continue;
+            }
+
+            let inspection = PreventiveInspection::new(
+                relative.plant.id.clone(),
+                reason.clone(),
+                Some(warning.diagnosis_id.clone()),
+                due_on,
+            );
+            self.inspection_repo.save(&inspection).await?;
+            scheduled.push(inspection);
+        }
+
+        Ok(scheduled)
+    }
+}
+
+/// The pests and diseases of a diagnosis result: the encyclopedia entries it
+/// was linked to, or those its finding mentions
+fn threats(result: &serde_json::Value) -> Vec<&'static EncyclopediaEntry> {
+    let linked: Vec<&'static EncyclopediaEntry> = result["encyclopedia_entries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|name| encyclopedia::entries().iter().find(|e| name.as_str() == Some(e.name.as_str())))
+        .collect();
+    if !linked.is_empty() {
+        return linked;
+    }
+    encyclopedia::find_mentions(result["finding"].as_str().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use crate::domain::{CareSchedule, DiagnosisSession};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_pest_on_one_plant_warns_its_relatives() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let diagnoses = DiagnosisRepository::new(db.clone());
+        let inspections = InspectionRepository::new(db.clone());
+        let service = FamilyService::new(plants.clone(), diagnoses.clone(), inspections.clone());
+
+        let mut sick = Plant::new("u".to_string(), "Calathea".to_string(), CareSchedule::default());
+        sick.scientific_name = Some("Calathea orbifolia".to_string());
+        sick.family = Some("Marantaceae".to_string());
+        let mut relative = Plant::new("u".to_string(), "Prayer plant".to_string(), CareSchedule::default());
+        relative.scientific_name = Some("Maranta leuconeura".to_string());
+        relative.family = Some("Marantaceae".to_string());
+        let mut stranger = Plant::new("u".to_string(), "Cactus".to_string(), CareSchedule::default());
+        stranger.family = Some("Cactaceae".to_string());
+        for plant in [&sick, &relative, &stranger] {
+            plants.create(plant).await.unwrap();
+        }
+        assert_eq!(service.groups("u").await.unwrap()[0].plants.len(), 2);
+
+        let mut diagnosis = DiagnosisSession::new(sick.id.clone(), "Webbing".to_string());
+        diagnosis.state = DiagnosisState::Concluded;
+        diagnosis.diagnosis_context["result"] = json!({"finding": "Overwatering", "severity": "minor"});
+        diagnoses.create(&diagnosis).await.unwrap();
+        assert!(service.warning(&diagnosis.id, "u").await.unwrap().is_none());
+
+        diagnosis.diagnosis_context["result"] = json!({"finding": "Spider mites on the undersides", "severity": "serious"});
+        diagnoses.update(&diagnosis).await.unwrap();
+        let warning = service.warning(&diagnosis.id, "u").await.unwrap().unwrap();
+        assert_eq!(warning.threats, vec!["Spider mites"]);
+        assert_eq!(warning.relatives.len(), 1);
+        assert_eq!(warning.relatives[0].plant.id, relative.id);
+
+        let today = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let scheduled = service.schedule_inspections(&warning, today).await.unwrap();
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled[0].reason, "Spider mites (found on Calathea)");
+        assert!(service.schedule_inspections(&warning, today).await.unwrap().is_empty());
+
+        let mut open = inspections.get_open_by_plant_id(&relative.id).await.unwrap();
+        assert_eq!(open.len(), 1);
+        open[0].done_at = Some(chrono::Utc::now());
+        inspections.save(&open[0]).await.unwrap();
+        assert!(inspections.get_open_by_plant_id(&relative.id).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+/*!
+ * GALLERY SERVICE
+ *
+ * Renders a read-only static HTML gallery of the collection (photos, names
+ * and care cards) that can be published to any static host such as
+ * GitHub Pages. No IDs, file paths or diagnosis details are included.
+ */
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::adapters::StoragePort;
+use crate::config;
+use crate::repositories::PlantRepository;
+use crate::templates::{self, TemplateValue};
+
+pub struct GalleryService {
+    plant_repo: PlantRepository,
+    storage_adapter: Arc<dyn StoragePort>,
+}
+
+impl GalleryService {
+    pub fn new(plant_repo: PlantRepository, storage_adapter: Arc<dyn StoragePort>) -> Self {
+        Self {
+            plant_repo,
+            storage_adapter,
+        }
+    }
+
+    /// Render the gallery into `out_dir`, returning the number of plants published
+    pub async fn publish(&self, user_id: &str, out_dir: &Path, title: &str) -> Result<usize> {
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+
+        let images_dir = out_dir.join("images");
+        fs::create_dir_all(&images_dir).context("Failed to create output directory")?;
+
+        let mut cards = String::new();
+        for (index, plant) in plants.iter().enumerate() {
+            let image_html = match &plant.image_url {
+                Some(url) => match self.storage_adapter.read_image(url).await {
+                    Ok(bytes) => {
+                        let extension = Path::new(url)
+                            .extension()
+                            .and_then(|e| e.to_str())
+                            .unwrap_or("jpg");
+                        let filename = format!("plant-{}.{}", index + 1, extension);
+                        fs::write(images_dir.join(&filename), bytes)?;
+
+                        format!(
+                            r#"<img src="images/{}" alt="{}">"#,
+                            filename,
+                            templates::escape_html(plant.display_name())
+                        )
+                    }
+                    Err(e) => {
+                        log::warn!("Skipping image for plant {}: {:#}", plant.id, e);
+                        r#"<div class="placeholder">🌿</div>"#.to_string()
+                    }
+                },
+                None => r#"<div class="placeholder">🌿</div>"#.to_string(),
+            };
+
+            let schedule = &plant.care_schedule;
+            cards.push_str(&templates::render(
+                templates::PLANT_CARD,
+                &[
+                    ("image", TemplateValue::Html(&image_html)),
+                    ("name", TemplateValue::Text(plant.display_name())),
+                    ("light", TemplateValue::Text(&schedule.light)),
+                    ("water", TemplateValue::Text(&schedule.water)),
+                    ("humidity", TemplateValue::Text(&schedule.humidity)),
+                    ("temperature", TemplateValue::Text(&schedule.temperature)),
+                    ("care_instructions", TemplateValue::Text(&schedule.care_instructions)),
+                ],
+            ));
+        }
+
+        let subtitle = format!(
+            "{} plants · updated {}",
+            plants.len(),
+            config::local_zone().today()
+        );
+        let page = templates::render(
+            templates::GALLERY_PAGE,
+            &[
+                ("title", TemplateValue::Text(title)),
+                ("subtitle", TemplateValue::Text(&subtitle)),
+                ("cards", TemplateValue::Html(&cards)),
+            ],
+        );
+
+        fs::write(out_dir.join("index.html"), page).context("Failed to write index.html")?;
+
+        Ok(plants.len())
+    }
+}
@@ -0,0 +1,79 @@
+/*!
+ * HEALTH SERVICE
+ *
+ * Gathers what the health index is drawn from (care tasks, diagnoses,
+ * treatment outcomes and journal notes) and scores each plant, so the
+ * collection can be sorted by which plants are doing worst.
+ */
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::config;
+use crate::domain::{health_index, HealthIndex, Plant};
+use crate::repositories::{CareTaskRepository, DiagnosisRepository, JournalRepository, OutcomeRepository};
+
+pub struct HealthService {
+    care_task_repo: CareTaskRepository,
+    diagnosis_repo: DiagnosisRepository,
+    outcome_repo: OutcomeRepository,
+    journal_repo: JournalRepository,
+}
+
+impl HealthService {
+    pub fn new(
+        care_task_repo: CareTaskRepository,
+        diagnosis_repo: DiagnosisRepository,
+        outcome_repo: OutcomeRepository,
+        journal_repo: JournalRepository,
+    ) -> Self {
+        Self {
+            care_task_repo,
+            diagnosis_repo,
+            outcome_repo,
+            journal_repo,
+        }
+    }
+
+    pub async fn health_index(&self, plant: &Plant) -> Result<HealthIndex> {
+        let tasks = self.care_task_repo.get_all_by_plant_id(&plant.id).await?;
+        let sessions = self
+            .diagnosis_repo
+            .get_all_by_plant_id(&plant.id, &plant.user_id)
+            .await?;
+        let outcomes = self.outcome_repo.get_by_plant_id(&plant.id).await?;
+        let journal = self.journal_repo.get_all_by_plant_id(&plant.id).await?;
+
+        Ok(health_index::compute(
+            &plant.care_schedule,
+            &tasks,
+            &sessions,
+            &outcomes,
+            &journal,
+            Utc::now(),
+            config::local_zone(),
+        ))
+    }
+
+    /// Score every plant, keeping their order
+    pub async fn score_all(&self, plants: Vec<Plant>) -> Result<Vec<(Plant, HealthIndex)>> {
+        let mut scored = Vec::with_capacity(plants.len());
+        for plant in plants {
+            let index = self.health_index(&plant).await?;
+            scored.push((plant, index));
+        }
+        Ok(scored)
+    }
+
+    /// Plants that need attention, least healthy first
+    pub async fn needs_attention(&self, plants: Vec<Plant>) -> Result<Vec<(Plant, HealthIndex)>> {
+        let mut scored: Vec<_> = self
+            .score_all(plants)
+            .await?
+            .into_iter()
+            .filter(|(_, index)| index.needs_attention())
+            .collect();
+        scored.sort_by_key(|(_, index)| index.score);
+        Ok(scored)
+    }
+}
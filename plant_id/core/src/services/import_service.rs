@@ -0,0 +1,180 @@
+/*!
+ * IMPORT SERVICE
+ *
+ * Brings a collection over from another plant app's CSV export: its
+ * plants, their photos and their watering and fertilizing logs. Plants
+ * already in the collection are skipped. Imported plants get a care
+ * schedule the same way as when the AI is down: the default one, replaced
+ * by a queued job.
+ */
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::adapters::{http_client, payload, storage_adapter, StoragePort};
+use crate::config;
+use crate::domain::enums::{CareTaskKind, JobKind};
+use crate::domain::{CareTask, ImportedPlant, Job, JournalEntry, LocalZone, Plant, ScheduleSource, SourceApp};
+use crate::repositories::{CareTaskRepository, JobRepository, JournalRepository, PlantRepository};
+
+/// Outcome of importing an export
+#[derive(Debug, Default)]
+pub struct AppImportSummary {
+    pub imported: Vec<Plant>,
+    /// Plants of the export already in the collection, as (their name in
+    /// the export, the plant they matched)
+    pub skipped: Vec<(String, Plant)>,
+    /// Watering and fertilizing events recorded
+    pub care_events: usize,
+    /// Photos that could not be read; their plants were imported without
+    pub photo_errors: Vec<String>,
+}
+
+pub struct ImportService {
+    plant_repo: PlantRepository,
+    care_task_repo: CareTaskRepository,
+    journal_repo: JournalRepository,
+    job_repo: JobRepository,
+    storage_adapter: Arc<dyn StoragePort>,
+}
+
+impl ImportService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        care_task_repo: CareTaskRepository,
+        journal_repo: JournalRepository,
+        job_repo: JobRepository,
+        storage_adapter: Arc<dyn StoragePort>,
+    ) -> Self {
+        Self {
+            plant_repo,
+            care_task_repo,
+            journal_repo,
+            job_repo,
+            storage_adapter,
+        }
+    }
+
+    /// Import the CSV export of `app`. Photo paths are relative to
+    /// `base_dir`, the folder of the export.
+    pub async fn import_csv(
+        &self,
+        app: SourceApp,
+        csv_text: &str,
+        base_dir: &Path,
+        user_id: &str,
+    ) -> Result<AppImportSummary> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(csv_text.as_bytes());
+        let headers: Vec<String> = reader
+            .headers()
+            .with_context(|| format!("File is not a {} CSV export", app.as_str()))?
+            .iter()
+            .map(str::to_string)
+            .collect();
+        let rows = reader
+            .records()
+            .map(|record| record.map(|r| r.iter().map(str::to_string).collect::<Vec<_>>()))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("File is not a {} CSV export", app.as_str()))?;
+        let imported = app.read(&headers, &rows)?;
+
+        let mut collection = self.plant_repo.get_all_by_user(user_id).await?;
+        let mut summary = AppImportSummary::default();
+        for entry in imported {
+            if let Some(existing) = entry.duplicate_of(&collection) {
+                summary.skipped.push((entry.display_name().to_string(), existing.clone()));
+                continue;
+            }
+
+            let mut plant = entry.to_plant(user_id);
+            if let Some(photo) = &entry.photo {
+                match self.fetch_photo(photo, base_dir).await {
+                    Ok(image_data) => {
+                        let (url, hash) =
+                            storage_adapter::store_by_hash(self.storage_adapter.as_ref(), &image_data).await?;
+                        // The same photo means the same plant, whatever its name
+                        if let Some(existing) = self.plant_repo.get_by_image_hash(&hash, user_id).await? {
+                            summary.skipped.push((entry.display_name().to_string(), existing));
+                            continue;
+                        }
+                        plant.image_url = Some(url);
+                        plant.image_hash = Some(hash);
+                    }
+                    Err(e) => summary
+                        .photo_errors
+                        .push(format!("{}: {:#}", entry.display_name(), e)),
+                }
+            }
+
+            let plant = self
+                .plant_repo
+                .create_with_schedule_source(&plant, ScheduleSource::Placeholder)
+                .await?;
+            self.job_repo
+                .enqueue(&Job::new(Some(user_id), JobKind::GenerateSchedule).for_plant(&plant.id))
+                .await?;
+            summary.care_events += self.record_care(&plant, &entry, app).await?;
+            collection.push(plant.clone());
+            summary.imported.push(plant);
+        }
+
+        Ok(summary)
+    }
+
+    /// Log the plant's watering and fertilizing in its journal, and count
+    /// its care tasks as last done when the app last saw them done
+    async fn record_care(&self, plant: &Plant, entry: &ImportedPlant, app: SourceApp) -> Result<usize> {
+        let zone = config::local_zone();
+        let mut events = 0;
+        for (kind, days, action) in [
+            (CareTaskKind::Water, &entry.watered, "Watered"),
+            (CareTaskKind::Fertilize, &entry.fertilized, "Fertilized"),
+        ] {
+            for day in days {
+                let mut journal_entry =
+                    JournalEntry::new(plant.id.clone(), format!("{} (imported from {})", action, app.as_str()), false);
+                journal_entry.created_at = midday(zone, *day);
+                self.journal_repo.create(&journal_entry).await?;
+                events += 1;
+            }
+
+            if let Some(last) = days.last() {
+                let mut task = CareTask::new(&plant.id, kind);
+                task.last_done_at = Some(midday(zone, *last));
+                self.care_task_repo.save(&task).await?;
+            }
+        }
+        if let Some(notes) = &entry.notes {
+            let note = format!("Notes from {}: {}", app.as_str(), notes);
+            self.journal_repo.create(&JournalEntry::new(plant.id.clone(), note, false)).await?;
+        }
+
+        Ok(events)
+    }
+
+    /// A photo of the export: a URL, or a path relative to its folder
+    async fn fetch_photo(&self, photo: &str, base_dir: &Path) -> Result<Vec<u8>> {
+        if !(photo.starts_with("http://") || photo.starts_with("https://")) {
+            return payload::read_image(&base_dir.join(photo));
+        }
+
+        let response = http_client::build_client()?
+            .get(photo)
+            .send()
+            .await
+            .map_err(|e| http_client::request_error("Photo download", e))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Downloading {} failed with status {}", photo, response.status());
+        }
+        let image_data = response.bytes().await?.to_vec();
+        payload::check_image(photo, &image_data)?;
+        Ok(image_data)
+    }
+}
+
+/// Noon of `day` in the local time zone, as the app only gave the day
+fn midday(zone: &LocalZone, day: NaiveDate) -> DateTime<Utc> {
+    zone.start_of_day(day) + Duration::hours(12)
+}
@@ -0,0 +1,367 @@
+/*!
+ * JOB SERVICE
+ *
+ * The job queue's worker. Each kind of job is run by a `JobHandler`
+ * registered with the service: the event bus redelivers webhooks, the
+ * sync service syncs, and the schedule handlers below write care
+ * schedules. `run_due` runs the jobs that are due once; `work` keeps
+ * doing so, as `plant-care jobs run` does. A job whose handler fails is
+ * retried with exponential backoff until it has failed MAX_JOB_ATTEMPTS
+ * times. Jobs of a kind no handler was registered for stay pending.
+ */
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::adapters::SilentProgress;
+use crate::domain::{Job, JobKind, ScheduleSource};
+use crate::repositories::{JobRepository, PlantRepository, RoomRepository, ScheduleVersionRepository, UserRepository};
+use crate::services::CareScheduleService;
+
+/// Jobs run at once
+const MAX_PARALLEL: usize = 4;
+
+/// What a job did
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobOutcome {
+    Done(String),
+    /// Nothing was left to do, e.g. the owner set a schedule by hand
+    Skipped(String),
+}
+
+/// Runs the jobs of one kind
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    fn kind(&self) -> JobKind;
+
+    /// Whether the job must run alone, as it writes in a transaction that
+    /// other jobs' writes would break
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    /// Do the job's work; an error has it retried later
+    async fn run(&self, job: &Job) -> Result<JobOutcome>;
+}
+
+/// Outcome of a run of the due jobs, each job with what it did or why it
+/// failed
+#[derive(Debug, Default)]
+pub struct JobReport {
+    pub done: Vec<(Job, String)>,
+    pub skipped: Vec<(Job, String)>,
+    /// Jobs that failed this time; they are given up once their status is
+    /// `Failed`
+    pub failed: Vec<(Job, String)>,
+    /// Due jobs of a kind no handler was registered for
+    pub waiting: Vec<Job>,
+}
+
+impl JobReport {
+    pub fn is_empty(&self) -> bool {
+        self.done.is_empty() && self.skipped.is_empty() && self.failed.is_empty() && self.waiting.is_empty()
+    }
+}
+
+pub struct JobService {
+    job_repo: JobRepository,
+    handlers: Vec<Arc<dyn JobHandler>>,
+}
+
+impl JobService {
+    pub fn new(job_repo: JobRepository) -> Self {
+        Self {
+            job_repo,
+            handlers: Vec::new(),
+        }
+    }
+
+    pub fn with_handler(mut self, handler: Arc<dyn JobHandler>) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    pub async fn enqueue(&self, job: &Job) -> Result<Job> {
+        self.job_repo.enqueue(job).await
+    }
+
+    /// A user's jobs and those of the whole device, newest first
+    pub async fn jobs(&self, user_id: &str) -> Result<Vec<Job>> {
+        self.job_repo.get_all_by_user(user_id).await
+    }
+
+    /// Run every job that is due: those that must run alone one after the
+    /// other, then the rest MAX_PARALLEL at a time
+    pub async fn run_due(&self) -> Result<JobReport> {
+        let mut report = JobReport::default();
+        let semaphore = Arc::new(Semaphore::new(MAX_PARALLEL));
+        let mut shared = Vec::new();
+
+        for job in self.job_repo.get_due(Utc::now()).await? {
+            match self.handlers.iter().find(|h| h.kind() == job.kind).cloned() {
+                Some(handler) if handler.exclusive() => {
+                    let result = handler.run(&job).await;
+                    self.record(job, result, &mut report).await?;
+                }
+                Some(handler) => shared.push((job, handler)),
+                None => report.waiting.push(job),
+            }
+        }
+
+        let mut tasks = JoinSet::new();
+        for (job, handler) in shared {
+            let semaphore = semaphore.clone();
+            tasks.spawn(async move {
+                // The semaphore is never closed, so acquiring cannot fail
+                let _permit = semaphore.acquire_owned().await;
+                let result = handler.run(&job).await;
+                (job, result)
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((job, result)) => self.record(job, result, &mut report).await?,
+                Err(e) => log::error!("Job task failed: {}", e),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Save how a job's run went, retrying it later if it failed
+    async fn record(&self, mut job: Job, result: Result<JobOutcome>, report: &mut JobReport) -> Result<()> {
+        match result {
+            Ok(outcome) => {
+                job.succeeded();
+                self.job_repo.save(&job).await?;
+                match outcome {
+                    JobOutcome::Done(summary) => report.done.push((job, summary)),
+                    JobOutcome::Skipped(reason) => report.skipped.push((job, reason)),
+                }
+            }
+            Err(e) => {
+                let error = format!("{:#}", e);
+                job.failed(error.clone());
+                self.job_repo.save(&job).await?;
+                report.failed.push((job, error));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the due jobs every `poll` for as long as the process runs,
+    /// handing each non-empty report to `on_report`
+    pub async fn work(&self, poll: std::time::Duration, mut on_report: impl FnMut(&JobReport)) -> Result<()> {
+        loop {
+            let report = self.run_due().await?;
+            if !report.is_empty() {
+                on_report(&report);
+            }
+            tokio::time::sleep(poll).await;
+        }
+    }
+}
+
+/// Writes the care schedule of a plant added while the AI could not be
+/// reached, unless the owner has set one in the meantime
+pub struct GenerateScheduleHandler {
+    plant_repo: PlantRepository,
+    version_repo: ScheduleVersionRepository,
+    room_repo: RoomRepository,
+    user_repo: UserRepository,
+    care_schedule_service: CareScheduleService,
+}
+
+impl GenerateScheduleHandler {
+    pub fn new(
+        plant_repo: PlantRepository,
+        version_repo: ScheduleVersionRepository,
+        room_repo: RoomRepository,
+        user_repo: UserRepository,
+        care_schedule_service: CareScheduleService,
+    ) -> Self {
+        Self {
+            plant_repo,
+            version_repo,
+            room_repo,
+            user_repo,
+            care_schedule_service,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for GenerateScheduleHandler {
+    fn kind(&self) -> JobKind {
+        JobKind::GenerateSchedule
+    }
+
+    async fn run(&self, job: &Job) -> Result<JobOutcome> {
+        let (Some(plant_id), Some(user_id)) = (&job.plant_id, &job.user_id) else {
+            anyhow::bail!("The job names no plant");
+        };
+        let Some(plant) = self.plant_repo.get_by_id(plant_id, user_id).await? else {
+            return Ok(JobOutcome::Skipped("The plant is gone".to_string()));
+        };
+        let latest = self.version_repo.get_latest_by_plant_id(&plant.id).await?;
+        if let Some(source) = latest.map(|v| v.source).filter(|s| *s != ScheduleSource::Placeholder) {
+            return Ok(JobOutcome::Skipped(format!(
+                "The care schedule of {} was already replaced ({})",
+                plant.display_name(),
+                source.describe()
+            )));
+        }
+
+        let name = plant.display_name().to_string();
+        let report = self
+            .care_schedule_service
+            .regenerate(&self.plant_repo, &self.room_repo, &self.user_repo, vec![plant], 1, &SilentProgress)
+            .await?;
+        if let Some((_, error)) = report.failed.first() {
+            anyhow::bail!("{}: {}", name, error);
+        }
+        Ok(JobOutcome::Done(format!("for {}", name)))
+    }
+}
+
+/// Regenerates a user's care schedules older than the `stale_days` of the
+/// job's payload, or written by another model
+pub struct RefreshSchedulesHandler {
+    plant_repo: PlantRepository,
+    version_repo: ScheduleVersionRepository,
+    room_repo: RoomRepository,
+    user_repo: UserRepository,
+    care_schedule_service: CareScheduleService,
+}
+
+impl RefreshSchedulesHandler {
+    pub fn new(
+        plant_repo: PlantRepository,
+        version_repo: ScheduleVersionRepository,
+        room_repo: RoomRepository,
+        user_repo: UserRepository,
+        care_schedule_service: CareScheduleService,
+    ) -> Self {
+        Self {
+            plant_repo,
+            version_repo,
+            room_repo,
+            user_repo,
+            care_schedule_service,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for RefreshSchedulesHandler {
+    fn kind(&self) -> JobKind {
+        JobKind::RefreshSchedules
+    }
+
+    async fn run(&self, job: &Job) -> Result<JobOutcome> {
+        let Some(user_id) = &job.user_id else {
+            anyhow::bail!("The job names no user");
+        };
+        let stale_days = job
+            .payload
+            .as_ref()
+            .and_then(|p| p["stale_days"].as_i64())
+            .unwrap_or(180);
+
+        let stale = self
+            .care_schedule_service
+            .find_stale(&self.plant_repo, &self.version_repo, user_id, Duration::days(stale_days))
+            .await?;
+        if stale.is_empty() {
+            return Ok(JobOutcome::Skipped("All care schedules are up to date".to_string()));
+        }
+
+        let plants = stale.into_iter().map(|(plant, _)| plant).collect();
+        let report = self
+            .care_schedule_service
+            .regenerate(&self.plant_repo, &self.room_repo, &self.user_repo, plants, MAX_PARALLEL, &SilentProgress)
+            .await?;
+        // Those regenerated are no longer stale, so a retry does the rest
+        if !report.failed.is_empty() {
+            let failed: Vec<String> = report
+                .failed
+                .iter()
+                .map(|(plant, e)| format!("{}: {}", plant.display_name(), e))
+                .collect();
+            anyhow::bail!(
+                "{} regenerated, {} failed ({})",
+                report.updated.len(),
+                failed.len(),
+                failed.join("; ")
+            );
+        }
+        Ok(JobOutcome::Done(format!("{} schedule(s) regenerated", report.updated.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use crate::domain::JobStatus;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fails its first `failures` runs
+    struct Flaky {
+        failures: u32,
+        runs: AtomicU32,
+    }
+
+    #[async_trait]
+    impl JobHandler for Flaky {
+        fn kind(&self) -> JobKind {
+            JobKind::Sync
+        }
+
+        async fn run(&self, _job: &Job) -> Result<JobOutcome> {
+            if self.runs.fetch_add(1, Ordering::SeqCst) < self.failures {
+                anyhow::bail!("remote unreachable");
+            }
+            Ok(JobOutcome::Done("synced".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_jobs_are_retried_once_due() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let jobs = JobRepository::new(db);
+        let service = JobService::new(jobs.clone()).with_handler(Arc::new(Flaky {
+            failures: 1,
+            runs: AtomicU32::new(0),
+        }));
+
+        let job = service.enqueue(&Job::new(Some("u"), JobKind::Sync)).await.unwrap();
+        // The same work is only queued once
+        assert_eq!(service.enqueue(&Job::new(Some("u"), JobKind::Sync)).await.unwrap().id, job.id);
+        let webhook = service.enqueue(&Job::new(None, JobKind::DeliverWebhook)).await.unwrap();
+
+        let report = service.run_due().await.unwrap();
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.waiting, vec![webhook]);
+
+        // Backing off: not due again yet
+        let report = service.run_due().await.unwrap();
+        assert!(report.failed.is_empty() && report.done.is_empty());
+
+        let due_later = jobs.get_due(Utc::now() + Duration::minutes(1)).await.unwrap();
+        let mut retried = due_later.into_iter().find(|j| j.id == job.id).unwrap();
+        assert_eq!((retried.attempts, retried.last_error.as_deref()), (1, Some("remote unreachable")));
+        retried.run_at = Utc::now();
+        jobs.save(&retried).await.unwrap();
+
+        let report = service.run_due().await.unwrap();
+        assert_eq!(report.done.len(), 1);
+        assert_eq!(report.done[0].0.status, JobStatus::Done);
+        assert_eq!(service.jobs("u").await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,234 @@
+/*!
+ * LABEL SERVICE
+ *
+ * Renders printable plant labels (name, watering frequency and a QR code
+ * of the plant ID) and reads the QR code back from a photo or scan of a
+ * label, so a pot can be matched to its plant.
+ */
+
+use anyhow::{Context, Result};
+use font8x8::{UnicodeFonts, BASIC_FONTS, LATIN_FONTS};
+use image::{GrayImage, ImageFormat, Luma};
+use qrcode::{Color, QrCode};
+use std::io::Cursor;
+
+use crate::domain::Plant;
+
+/// Prefix of the text encoded in a label's QR code, before the plant ID
+const PAYLOAD_PREFIX: &str = "plant-care:plant:";
+
+/// Width of a label, in pixels
+const LABEL_WIDTH: u32 = 720;
+
+/// Space around the QR code and the text, in pixels
+const MARGIN: u32 = 16;
+
+/// Size of one QR module, in pixels
+const MODULE_SIZE: u32 = 6;
+
+/// Blank modules around the QR code that scanners need to find it
+const QUIET_ZONE: u32 = 4;
+
+/// Glyphs are 8x8 pixels, scaled up by these factors
+const NAME_SCALE: u32 = 3;
+const TEXT_SCALE: u32 = 2;
+
+const MAX_NAME_LINES: usize = 2;
+const MAX_TEXT_LINES: usize = 4;
+
+const BLACK: Luma<u8> = Luma([0]);
+const WHITE: Luma<u8> = Luma([255]);
+
+/// Text encoded in the QR code of a plant's label
+pub fn label_payload(plant_id: &str) -> String {
+    format!("{}{}", PAYLOAD_PREFIX, plant_id)
+}
+
+/// Plant ID encoded in a label's QR code, if the text comes from a label
+pub fn parse_label_payload(text: &str) -> Option<&str> {
+    text.trim()
+        .strip_prefix(PAYLOAD_PREFIX)
+        .filter(|id| !id.is_empty())
+}
+
+/// Render a plant's label as a PNG
+pub fn render_label(plant: &Plant) -> Result<Vec<u8>> {
+    let code = QrCode::new(label_payload(&plant.id)).context("Plant ID does not fit in a QR code")?;
+    let modules = code.width() as u32;
+    let qr_size = (modules + 2 * QUIET_ZONE) * MODULE_SIZE;
+
+    let text_x = qr_size + MARGIN;
+    let text_width = LABEL_WIDTH - text_x - MARGIN;
+    let name_lines = wrap(plant.display_name(), chars_per_line(text_width, NAME_SCALE), MAX_NAME_LINES);
+    let water_lines = wrap(
+        &format!("Water: {}", plant.care_schedule.water),
+        chars_per_line(text_width, TEXT_SCALE),
+        MAX_TEXT_LINES,
+    );
+
+    let text_height = name_lines.len() as u32 * line_height(NAME_SCALE)
+        + MARGIN
+        + water_lines.len() as u32 * line_height(TEXT_SCALE);
+    let height = qr_size.max(text_height + 2 * MARGIN);
+
+    let mut label = GrayImage::from_pixel(LABEL_WIDTH, height, WHITE);
+
+    let offset = (height - qr_size) / 2 + QUIET_ZONE * MODULE_SIZE;
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let x = offset + (i as u32 % modules) * MODULE_SIZE;
+            let y = offset + (i as u32 / modules) * MODULE_SIZE;
+            fill(&mut label, x, y, MODULE_SIZE);
+        }
+    }
+
+    let mut y = (height - text_height) / 2;
+    for line in &name_lines {
+        draw_text(&mut label, line, text_x, y, NAME_SCALE);
+        y += line_height(NAME_SCALE);
+    }
+    y += MARGIN;
+    for line in &water_lines {
+        draw_text(&mut label, line, text_x, y, TEXT_SCALE);
+        y += line_height(TEXT_SCALE);
+    }
+
+    let mut png = Vec::new();
+    label.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+
+    Ok(png)
+}
+
+/// Find a label's QR code in an image and return the plant ID it encodes
+pub fn read_label(image_data: &[u8]) -> Result<String> {
+    let image = image::load_from_memory(image_data)
+        .context("Unsupported or corrupt image")?
+        .to_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32)[0],
+    );
+
+    let grids = prepared.detect_grids();
+    if grids.is_empty() {
+        anyhow::bail!("No QR code found in the image");
+    }
+
+    grids
+        .iter()
+        .filter_map(|grid| grid.decode().ok())
+        .find_map(|(_, text)| parse_label_payload(&text).map(str::to_string))
+        .context("The QR code is not a plant label")
+}
+
+fn chars_per_line(width: u32, scale: u32) -> usize {
+    (width / (8 * scale)) as usize
+}
+
+fn line_height(scale: u32) -> u32 {
+    11 * scale
+}
+
+/// Break text into lines of at most `width` characters, splitting words
+/// only when they are longer than a line. Text beyond `max_lines` is cut
+/// off with "...".
+fn wrap(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        loop {
+            let used = current.chars().count();
+            let needed = if used == 0 { word.len() } else { used + 1 + word.len() };
+            if needed <= width {
+                if used > 0 {
+                    current.push(' ');
+                }
+                current.extend(word.iter());
+                break;
+            }
+            if used > 0 {
+                lines.push(std::mem::take(&mut current));
+                continue;
+            }
+            let rest = word.split_off(width);
+            lines.push(word.into_iter().collect());
+            word = rest;
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        let last = &mut lines[max_lines - 1];
+        let kept: String = last.chars().take(width.saturating_sub(3)).collect();
+        *last = format!("{}...", kept.trim_end());
+    }
+
+    lines
+}
+
+fn draw_text(label: &mut GrayImage, text: &str, x: u32, y: u32, scale: u32) {
+    for (i, c) in text.chars().enumerate() {
+        let glyph = BASIC_FONTS
+            .get(c)
+            .or_else(|| LATIN_FONTS.get(c))
+            .or_else(|| BASIC_FONTS.get('?'))
+            .unwrap_or_default();
+        let glyph_x = x + i as u32 * 8 * scale;
+
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..8 {
+                if bits & (1 << col) != 0 {
+                    fill(label, glyph_x + col * scale, y + row as u32 * scale, scale);
+                }
+            }
+        }
+    }
+}
+
+/// Paint a black square, clipped to the label
+fn fill(label: &mut GrayImage, x: u32, y: u32, size: u32) {
+    for py in y..(y + size).min(label.height()) {
+        for px in x..(x + size).min(label.width()) {
+            label.put_pixel(px, py, BLACK);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CareSchedule;
+
+    #[test]
+    fn test_label_round_trip() {
+        let plant = Plant::new(
+            "u".to_string(),
+            "Monstera deliciosa".to_string(),
+            CareSchedule::default(),
+        );
+
+        let png = render_label(&plant).unwrap();
+        assert_eq!(read_label(&png).unwrap(), plant.id);
+    }
+
+    #[test]
+    fn test_parse_label_payload() {
+        assert_eq!(parse_label_payload(&label_payload("abc")), Some("abc"));
+        assert_eq!(parse_label_payload("plant-care:plant:"), None);
+        assert_eq!(parse_label_payload("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("Water every 7 days", 10, 4), vec!["Water", "every 7", "days"]);
+        assert_eq!(wrap("Philodendron", 5, 4), vec!["Philo", "dendr", "on"]);
+        assert_eq!(wrap("one two three four", 3, 2), vec!["one", "..."]);
+    }
+}
@@ -0,0 +1,173 @@
+/*!
+ * SERVICES MODULE
+ *
+ * Application services that orchestrate business logic.
+ * These implement use cases by coordinating between domain models,
+ * repositories, and external adapters.
+ *
+ * Only the event bus builds without the `native` feature; the other
+ * services need the repositories.
+ */
+
+// Declare service modules
+#[cfg(feature = "native")]
+pub mod anonymizer;
+#[cfg(feature = "native")]
+pub mod api_key_service;
+#[cfg(feature = "native")]
+pub mod archive_service;
+#[cfg(feature = "native")]
+pub mod ask_service;
+#[cfg(feature = "native")]
+pub mod audit_service;
+#[cfg(feature = "native")]
+pub mod backup_service;
+#[cfg(feature = "native")]
+pub mod bulk_service;
+#[cfg(feature = "native")]
+pub mod care_schedule_service;
+#[cfg(feature = "native")]
+pub mod chat_service;
+#[cfg(feature = "native")]
+pub mod checklist_service;
+#[cfg(feature = "native")]
+pub mod debug_bundle_service;
+#[cfg(feature = "native")]
+pub mod diagnosis_service;
+#[cfg(feature = "native")]
+pub mod encyclopedia_service;
+#[cfg(feature = "native")]
+pub mod eval_service;
+pub mod event_bus;
+#[cfg(feature = "native")]
+pub mod explain_service;
+#[cfg(feature = "native")]
+pub mod export_service;
+#[cfg(feature = "native")]
+pub mod family_service;
+#[cfg(feature = "native")]
+pub mod gallery_service;
+#[cfg(feature = "native")]
+pub mod health_service;
+#[cfg(feature = "native")]
+pub mod import_service;
+#[cfg(feature = "native")]
+pub mod job_service;
+#[cfg(feature = "native")]
+pub mod label_service;
+#[cfg(feature = "native")]
+pub mod outcome_service;
+#[cfg(feature = "native")]
+pub mod placement_service;
+#[cfg(feature = "native")]
+pub mod plant_service;
+#[cfg(feature = "native")]
+pub mod profile_service;
+#[cfg(feature = "native")]
+pub mod quarantine_service;
+#[cfg(feature = "native")]
+pub mod query_service;
+#[cfg(feature = "native")]
+pub mod recommendation_service;
+#[cfg(feature = "native")]
+pub mod report_service;
+#[cfg(feature = "native")]
+pub mod shopping_service;
+#[cfg(feature = "native")]
+pub mod sitter_service;
+#[cfg(feature = "native")]
+pub mod streak_service;
+#[cfg(feature = "native")]
+pub mod sync_service;
+#[cfg(feature = "native")]
+pub mod template_service;
+#[cfg(feature = "native")]
+pub mod thumbnail_service;
+#[cfg(feature = "native")]
+pub mod transcript_service;
+#[cfg(feature = "native")]
+pub mod triage_service;
+#[cfg(feature = "native")]
+pub mod vacation_service;
+
+// Re-export service structs for easier access
+#[cfg(feature = "native")]
+pub use anonymizer::{AnonymizeReport, Anonymizer};
+#[cfg(feature = "native")]
+pub use api_key_service::ApiKeyService;
+#[cfg(feature = "native")]
+pub use archive_service::{ArchiveService, PlantTransfer};
+#[cfg(feature = "native")]
+pub use ask_service::AskService;
+#[cfg(feature = "native")]
+pub use audit_service::AuditService;
+#[cfg(feature = "native")]
+pub use backup_service::BackupService;
+#[cfg(feature = "native")]
+pub use bulk_service::BulkService;
+#[cfg(feature = "native")]
+pub use care_schedule_service::CareScheduleService;
+#[cfg(feature = "native")]
+pub use chat_service::ChatService;
+#[cfg(feature = "native")]
+pub use checklist_service::ChecklistService;
+#[cfg(feature = "native")]
+pub use debug_bundle_service::{BundleSources, DebugBundle, DebugBundleService};
+#[cfg(feature = "native")]
+pub use diagnosis_service::{CostEstimate, DiagnosisCost, DiagnosisService};
+#[cfg(feature = "native")]
+pub use encyclopedia_service::EncyclopediaService;
+#[cfg(feature = "native")]
+pub use eval_service::EvalService;
+pub use event_bus::EventBus;
+#[cfg(feature = "native")]
+pub use explain_service::ExplainService;
+#[cfg(feature = "native")]
+pub use export_service::ExportService;
+#[cfg(feature = "native")]
+pub use family_service::FamilyService;
+#[cfg(feature = "native")]
+pub use gallery_service::GalleryService;
+#[cfg(feature = "native")]
+pub use health_service::HealthService;
+#[cfg(feature = "native")]
+pub use import_service::{AppImportSummary, ImportService};
+#[cfg(feature = "native")]
+pub use job_service::{
+    GenerateScheduleHandler, JobHandler, JobOutcome, JobReport, JobService, RefreshSchedulesHandler,
+};
+#[cfg(feature = "native")]
+pub use outcome_service::OutcomeService;
+#[cfg(feature = "native")]
+pub use placement_service::PlacementService;
+#[cfg(feature = "native")]
+pub use plant_service::{KeepPlantId, PlantCreation, PlantService, SpeciesChoice, SpeciesChooser};
+#[cfg(feature = "native")]
+pub use profile_service::ProfileService;
+#[cfg(feature = "native")]
+pub use quarantine_service::QuarantineService;
+#[cfg(feature = "native")]
+pub use query_service::QueryService;
+#[cfg(feature = "native")]
+pub use recommendation_service::RecommendationService;
+#[cfg(feature = "native")]
+pub use report_service::ReportService;
+#[cfg(feature = "native")]
+pub use shopping_service::ShoppingService;
+#[cfg(feature = "native")]
+pub use sitter_service::SitterService;
+#[cfg(feature = "native")]
+pub use streak_service::{CareStreaks, StreakService};
+#[cfg(feature = "native")]
+pub use sync_service::SyncService;
+#[cfg(feature = "native")]
+pub use template_service::TemplateService;
+#[cfg(feature = "native")]
+pub use thumbnail_service::ThumbnailService;
+#[cfg(feature = "native")]
+pub use transcript_service::TranscriptService;
+#[cfg(feature = "native")]
+pub use triage_service::TriageService;
+#[cfg(feature = "native")]
+pub use vacation_service::VacationService;
+
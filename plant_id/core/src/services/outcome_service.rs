@@ -0,0 +1,104 @@
+/*!
+ * OUTCOME SERVICE
+ *
+ * Records follow-up check-ins on concluded diagnoses: did the
+ * recommendation work? Outcomes are grouped per species and fed into
+ * future diagnoses of the same species.
+ */
+
+use anyhow::{Context, Result};
+
+use crate::domain::treatment_outcome::{self, SpeciesExperience};
+use crate::domain::{DiagnosisState, JournalEntry, RequestError, TreatmentOutcome};
+use crate::repositories::{DiagnosisRepository, JournalRepository, OutcomeRepository, PlantRepository};
+
+pub struct OutcomeService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    journal_repo: JournalRepository,
+    outcome_repo: OutcomeRepository,
+}
+
+impl OutcomeService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        journal_repo: JournalRepository,
+        outcome_repo: OutcomeRepository,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            journal_repo,
+            outcome_repo,
+        }
+    }
+
+    /// Record whether the latest concluded diagnosis of a plant worked.
+    /// A journal entry is also written, which counts as a follow-up for triage.
+    pub async fn check_in(
+        &self,
+        plant_id: &str,
+        user_id: &str,
+        worked: bool,
+        note: Option<String>,
+    ) -> Result<TreatmentOutcome> {
+        let plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        // Sessions are newest first, so this is the latest conclusion
+        let session = self
+            .diagnosis_repo
+            .get_all_by_plant_id(plant_id, user_id)
+            .await?
+            .into_iter()
+            .find(|s| s.state == DiagnosisState::Concluded)
+            .context("This plant has no concluded diagnosis to check in on")?;
+
+        let result = &session.diagnosis_context["result"];
+        let finding = result["finding"].as_str().unwrap_or("unknown finding").to_string();
+        let recommendation = result["recommendation"].as_str().unwrap_or_default().to_string();
+
+        let outcome = TreatmentOutcome::new(
+            plant.id.clone(),
+            plant.species(),
+            session.id,
+            finding,
+            recommendation,
+            worked,
+            note,
+        );
+        let outcome = self.outcome_repo.create(&outcome).await?;
+
+        let mut journal_note = format!(
+            "Follow-up on '{}': {}",
+            outcome.finding,
+            if worked { "treatment worked" } else { "treatment did not work" }
+        );
+        if let Some(note) = &outcome.note {
+            journal_note.push_str(&format!(" - {}", note));
+        }
+        self.journal_repo
+            .create(&JournalEntry::new(plant.id, journal_note, !worked))
+            .await?;
+
+        Ok(outcome)
+    }
+
+    /// What has worked for this species across the user's plants
+    pub async fn species_experience(
+        &self,
+        plant_name: &str,
+        user_id: &str,
+    ) -> Result<Vec<SpeciesExperience>> {
+        let outcomes = self
+            .outcome_repo
+            .get_by_species(&treatment_outcome::species_key(plant_name), user_id)
+            .await?;
+
+        Ok(treatment_outcome::summarize_outcomes(&outcomes))
+    }
+}
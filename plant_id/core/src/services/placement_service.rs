@@ -0,0 +1,85 @@
+/*!
+ * PLACEMENT SERVICE
+ *
+ * Advises which room each plant should live in: the plants' light needs
+ * are matched against the rooms' recorded light, then the AI explains the
+ * suggested moves. A plant sitter can get the advice but not the
+ * explanations.
+ */
+
+use anyhow::Result;
+use serde_json::json;
+
+use crate::adapters::AiAdapter;
+use crate::domain::placement::{self, PlacementAdvice};
+use crate::domain::Permission;
+use crate::repositories::{PlantRepository, RoomRepository, UserRepository};
+
+pub struct PlacementService {
+    plant_repo: PlantRepository,
+    room_repo: RoomRepository,
+    user_repo: UserRepository,
+    ai_adapter: AiAdapter,
+}
+
+impl PlacementService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        room_repo: RoomRepository,
+        user_repo: UserRepository,
+        ai_adapter: AiAdapter,
+    ) -> Self {
+        Self {
+            plant_repo,
+            room_repo,
+            user_repo,
+            ai_adapter,
+        }
+    }
+
+    /// Advice for every plant. Moves are explained by the AI when `explain`
+    /// is set and it can be reached, which a plant sitter may not ask for.
+    pub async fn advise(&self, user_id: &str, explain: bool) -> Result<Vec<PlacementAdvice>> {
+        if explain {
+            self.user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+        }
+
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        let rooms = self.room_repo.get_all_by_user(user_id).await?;
+        if rooms.is_empty() {
+            anyhow::bail!("No room has its light recorded yet");
+        }
+
+        let mut advice = placement::advise(&plants, &rooms);
+
+        let moves: Vec<_> = advice
+            .iter()
+            .filter(|a| a.is_move())
+            .map(|a| json!({
+                "plant_id": a.plant_id,
+                "name": a.plant_name,
+                "needs": a.requirement.map(|r| r.to_string()),
+                "current_room": a.room,
+                "current_light": a.light.map(|l| l.to_string()),
+                "suggested_room": a.suggested_room,
+                "suggested_light": a.suggested_light.map(|l| l.to_string()),
+            }))
+            .collect();
+        if !explain || moves.is_empty() {
+            return Ok(advice);
+        }
+
+        match self.ai_adapter.explain_placements(&json!(moves)).await {
+            Ok(explanations) => {
+                for explanation in explanations {
+                    if let Some(entry) = advice.iter_mut().find(|a| a.plant_id == explanation.plant_id) {
+                        entry.explanation = Some(explanation.explanation);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Could not explain placement advice: {:#}", e),
+        }
+
+        Ok(advice)
+    }
+}
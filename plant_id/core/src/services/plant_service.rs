@@ -0,0 +1,377 @@
+/*!
+ * PLANT SERVICE
+ *
+ * Business logic for plant management operations.
+ *
+ * A new plant's photo is identified by Plant.id and, when one is
+ * configured, a second identifier. When they name different species, the
+ * front-end's chooser picks one or asks the AI to break the tie, and the
+ * disagreement is recorded so that the species can be corrected later.
+ */
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::sync::Arc;
+
+use crate::adapters::{
+    payload, storage_adapter, AiAdapter, IdentifierPort, PlantIdAdapter, ProgressReporter, SpeciesDataPort, StoragePort,
+};
+use crate::crypto;
+use crate::domain::{
+    CareSchedule, Growth, IdentificationDisagreement, Job, JobKind, NotificationEvent, Plant, SpeciesResolution, ScheduleSource, SpeciesCandidate,
+};
+use crate::dto::PlantCreationDto;
+use crate::repositories::{IdentificationRepository, JobRepository, PlantRepository};
+use crate::services::care_schedule_service::CareScheduleService;
+use crate::services::EventBus;
+
+/// Outcome of adding a plant
+pub enum PlantCreation {
+    /// A new plant, with any care schedule warnings, the disagreement over
+    /// its species if the identifiers had one, and the job queued to
+    /// generate its care schedule if the AI could not
+    Created {
+        plant: Plant,
+        warnings: Vec<String>,
+        disagreement: Option<Box<IdentificationDisagreement>>,
+        queued: Option<Box<Job>>,
+    },
+    /// The photo was added before; nothing was created
+    Existing(Plant),
+}
+
+/// What to do when the identifiers name different species
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeciesChoice {
+    /// The owner picked the candidate at this index
+    Candidate(usize),
+    /// Have the AI look at the photo and pick
+    Tiebreak,
+    /// Keep Plant.id's answer without asking
+    Default,
+}
+
+/// Port through which the front-end settles disagreeing identifications
+pub trait SpeciesChooser: Send + Sync {
+    /// Choose between the candidates, Plant.id's first
+    fn choose(&self, candidates: &[SpeciesCandidate]) -> Result<SpeciesChoice>;
+}
+
+/// Keeps Plant.id's answer, for front-ends that cannot ask the owner. The
+/// disagreement is still recorded.
+pub struct KeepPlantId;
+
+impl SpeciesChooser for KeepPlantId {
+    fn choose(&self, _candidates: &[SpeciesCandidate]) -> Result<SpeciesChoice> {
+        Ok(SpeciesChoice::Default)
+    }
+}
+
+pub struct PlantService {
+    plant_repo: PlantRepository,
+    identification_repo: IdentificationRepository,
+    job_repo: JobRepository,
+    plant_id_adapter: PlantIdAdapter,
+    second_identifier: Option<Arc<dyn IdentifierPort>>,
+    ai_adapter: AiAdapter,
+    care_schedule_service: CareScheduleService,
+    storage_adapter: Arc<dyn StoragePort>,
+    event_bus: EventBus,
+}
+
+impl PlantService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        plant_repo: PlantRepository,
+        identification_repo: IdentificationRepository,
+        job_repo: JobRepository,
+        plant_id_adapter: PlantIdAdapter,
+        second_identifier: Option<Arc<dyn IdentifierPort>>,
+        ai_adapter: AiAdapter,
+        species_data: Option<Arc<dyn SpeciesDataPort>>,
+        storage_adapter: Arc<dyn StoragePort>,
+        event_bus: EventBus,
+    ) -> Self {
+        Self {
+            plant_repo,
+            identification_repo,
+            job_repo,
+            plant_id_adapter,
+            second_identifier,
+            care_schedule_service: CareScheduleService::new(ai_adapter.clone(), species_data),
+            ai_adapter,
+            storage_adapter,
+            event_bus,
+        }
+    }
+
+    /// Create a plant, or return the plant already added from the same
+    /// photo unless `dto.force_new` is set. Each step is reported to
+    /// `progress`; `chooser` settles disagreeing identifications.
+    pub async fn create_plant(
+        &self,
+        dto: PlantCreationDto,
+        user_id: String,
+        progress: &dyn ProgressReporter,
+        chooser: &dyn SpeciesChooser,
+    ) -> Result<PlantCreation> {
+        let image_data = dto
+            .images
+            .first()
+            .map(|encoded| BASE64.decode(encoded).context("Failed to decode base64 image"))
+            .transpose()?;
+        // Refuse a huge or unreadable photo before uploading it anywhere
+        if let Some(image_data) = &image_data {
+            payload::check_image("The photo", image_data)?;
+        }
+
+        // Adding the same photo again is a no-op, before any AI request
+        if let (Some(image_data), false) = (&image_data, dto.force_new) {
+            let hash = crypto::content_hash(image_data);
+            if let Some(plant) = self.plant_repo.get_by_image_hash(&hash, &user_id).await? {
+                return Ok(PlantCreation::Existing(plant));
+            }
+        }
+
+        // Step 1: Identify plant from image
+        progress.step("Identifying plant...");
+        let mut identification = self
+            .plant_id_adapter
+            .identify_plant(&dto)
+            .await
+            .context("Failed to identify plant")?;
+
+        // Step 1b: Get a second opinion, and settle any disagreement
+        let mut disputed = None;
+        if let (Some(identifier), Some(image_data)) = (&self.second_identifier, &image_data) {
+            progress.step(&format!("Checking the species with the {}...", identifier.name()));
+            match identifier.identify(image_data, &identification).await {
+                Ok(Some(second)) if !second.same_species(&identification) => {
+                    let candidates = vec![identification.clone(), second];
+                    let (chosen, resolution) = self
+                        .settle(&candidates, image_data, progress, chooser)
+                        .await?;
+                    identification = candidates[chosen].clone();
+                    disputed = Some((candidates, resolution));
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!(
+                    "The {} could not identify the plant, keeping Plant.id's answer: {:#}",
+                    identifier.name(),
+                    e
+                ),
+            }
+        }
+        let plant_name = identification.display_name().to_string();
+        // The scientific name is the unambiguous one to look the species up by
+        let species = identification.scientific_name.clone();
+
+        // Step 2: Use the given care schedule, or generate and validate one.
+        // When the AI cannot, the plant gets the default schedule and a job
+        // is queued to generate it later.
+        let mut unavailable = None;
+        let (schedule, warnings, source, reference) = match dto.care_schedule.clone() {
+            Some(schedule) => {
+                let source = match dto.template.clone() {
+                    Some(name) => ScheduleSource::Template { name },
+                    None => ScheduleSource::Manual,
+                };
+                // Only for the family, as the schedule is given
+                let reference = self.care_schedule_service.lookup_species(&species).await;
+                (schedule, Vec::new(), source, reference)
+            }
+            None => {
+                progress.step(&format!("Generating care schedule for {}...", plant_name));
+                // Only a plant had for a while or of known origin says
+                // anything about its maturity. A new plant is in no room yet.
+                let growth = (dto.acquired_at.is_some() || dto.acquired_from.is_some())
+                    .then(|| Growth::new(dto.acquired_at.unwrap_or_else(Utc::now), dto.acquired_from.clone(), Utc::now()));
+                match self.care_schedule_service.generate(&species, growth.as_ref(), None).await {
+                    Ok(generated) => {
+                        if let Some(reference) = &generated.reference {
+                            progress.step(&format!(
+                                "Used {} data for {}",
+                                reference.source,
+                                reference.care_fields().join(", ")
+                            ));
+                        }
+                        let source = ScheduleSource::Ai {
+                            model: self.ai_adapter.model().to_string(),
+                        };
+                        (generated.schedule, generated.warnings, source, generated.reference)
+                    }
+                    Err(e) => {
+                        let error = format!("{:#}", e);
+                        log::warn!("Could not generate care schedule of {}, queuing it: {}", plant_name, error);
+                        let warning = format!(
+                            "The care schedule could not be generated ({}); the plant has the default one until the queued job writes it",
+                            error
+                        );
+                        unavailable = Some(error);
+                        let reference = self.care_schedule_service.lookup_species(&species).await;
+                        (CareSchedule::default(), vec![warning], ScheduleSource::Placeholder, reference)
+                    }
+                }
+            }
+        };
+
+        // Step 3: Rate how demanding the plant is; the plant is still added
+        // without a rating if this fails
+        progress.step(&format!("Rating care difficulty of {}...", plant_name));
+        let difficulty = match self.ai_adapter.rate_care_difficulty(&species).await {
+            Ok(difficulty) => Some(difficulty),
+            Err(e) => {
+                log::warn!("Could not rate care difficulty of {}: {:#}", plant_name, e);
+                None
+            }
+        };
+
+        // Step 4: Save image under its content hash
+        let stored_image = match &image_data {
+            Some(image_data) => {
+                progress.step("Storing image...");
+                Some(storage_adapter::store_by_hash(self.storage_adapter.as_ref(), image_data).await?)
+            }
+            None => None,
+        };
+
+        // Step 5: Create and save plant
+        progress.step("Saving plant...");
+        let mut plant = Plant::new(user_id, plant_name, schedule);
+        plant.set_nickname(dto.nickname.as_deref());
+        plant.scientific_name = Some(identification.scientific_name);
+        plant.common_names = identification.common_names;
+        if let Some((url, hash)) = stored_image {
+            plant.image_url = Some(url);
+            plant.image_hash = Some(hash);
+        }
+        plant.difficulty = difficulty;
+        plant.family = reference.and_then(|r| r.family);
+        plant.acquired_at = dto.acquired_at;
+        plant.set_acquired_from(dto.acquired_from.as_deref());
+
+        let plant = self.plant_repo.create_with_schedule_source(&plant, source).await?;
+        let queued = match unavailable {
+            Some(error) => {
+                let job = Job {
+                    last_error: Some(error),
+                    ..Job::new(Some(&plant.user_id), JobKind::GenerateSchedule).for_plant(&plant.id)
+                };
+                Some(Box::new(self.job_repo.enqueue(&job).await?))
+            }
+            None => None,
+        };
+
+        let disagreement = match disputed {
+            Some((candidates, resolution)) => {
+                let disagreement = IdentificationDisagreement::new(
+                    plant.id.clone(),
+                    candidates,
+                    plant.scientific_name.clone().unwrap_or_default(),
+                    resolution,
+                );
+                self.identification_repo.save(&disagreement).await?;
+                Some(Box::new(disagreement))
+            }
+            None => None,
+        };
+
+        self.event_bus
+            .publish(NotificationEvent::PlantAdded {
+                plant_id: plant.id.clone(),
+                plant_name: plant.display_name().to_string(),
+            })
+            .await;
+
+        Ok(PlantCreation::Created {
+            plant,
+            warnings,
+            disagreement,
+            queued,
+        })
+    }
+
+    /// Pick one of the disagreeing candidates, returning its index and how
+    /// it was picked
+    async fn settle(
+        &self,
+        candidates: &[SpeciesCandidate],
+        image_data: &[u8],
+        progress: &dyn ProgressReporter,
+        chooser: &dyn SpeciesChooser,
+    ) -> Result<(usize, SpeciesResolution)> {
+        let mut choice = Ok(SpeciesChoice::Default);
+        progress.suspend(&mut || choice = chooser.choose(candidates));
+
+        match choice? {
+            SpeciesChoice::Candidate(index) if index < candidates.len() => Ok((index, SpeciesResolution::Owner)),
+            SpeciesChoice::Candidate(index) => anyhow::bail!("There is no candidate species {}", index + 1),
+            SpeciesChoice::Default => Ok((0, SpeciesResolution::Default)),
+            SpeciesChoice::Tiebreak => {
+                progress.step("Asking the AI to look at the photo...");
+                let tiebreak = self
+                    .ai_adapter
+                    .break_identification_tie(image_data, candidates)
+                    .await
+                    .context("Failed to break the identification tie")?;
+                let picked = SpeciesCandidate {
+                    source: self.ai_adapter.model().to_string(),
+                    scientific_name: tiebreak.scientific_name.clone(),
+                    common_names: Vec::new(),
+                    confidence: None,
+                };
+                let index = candidates
+                    .iter()
+                    .position(|c| c.same_species(&picked))
+                    .with_context(|| {
+                        format!("The AI picked {}, which neither identifier suggested", tiebreak.scientific_name)
+                    })?;
+                Ok((
+                    index,
+                    SpeciesResolution::Tiebreak {
+                        model: self.ai_adapter.model().to_string(),
+                        reasoning: tiebreak.reasoning,
+                    },
+                ))
+            }
+        }
+    }
+}
+
+/// Correct the species of a plant whose identification was disputed. The
+/// plant is renamed as it would have been added, with the common names of
+/// the matching candidate, if any.
+pub async fn correct_species(
+    plant_repo: &PlantRepository,
+    identification_repo: &IdentificationRepository,
+    plant: &mut Plant,
+    species: &str,
+) -> Result<IdentificationDisagreement> {
+    let species = species.trim();
+    if species.is_empty() {
+        anyhow::bail!("The species cannot be empty");
+    }
+    let mut disagreement = identification_repo
+        .get_by_plant_id(&plant.id)
+        .await?
+        .with_context(|| format!("The identifiers agreed on the species of {}", plant.display_name()))?;
+
+    let candidate = disagreement.candidate(species).cloned().unwrap_or_else(|| SpeciesCandidate {
+        source: "you".to_string(),
+        scientific_name: species.to_string(),
+        common_names: Vec::new(),
+        confidence: None,
+    });
+    plant.name = candidate.display_name().to_string();
+    plant.scientific_name = Some(candidate.scientific_name);
+    plant.common_names = candidate.common_names;
+    plant.updated_at = chrono::Utc::now();
+    plant_repo.update(plant).await?;
+
+    disagreement.corrected_to = plant.scientific_name.clone();
+    disagreement.updated_at = plant.updated_at;
+    identification_repo.save(&disagreement).await?;
+
+    Ok(disagreement)
+}
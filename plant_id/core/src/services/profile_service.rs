@@ -0,0 +1,135 @@
+/*!
+ * PROFILE SERVICE
+ *
+ * Exports a plant as a shareable, self-contained profile and imports
+ * profiles shared by other users into the local collection.
+ */
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::adapters::{storage_adapter, StoragePort};
+use crate::config;
+use crate::domain::{DiagnosisState, JournalEntry, Plant, RequestError, ScheduleSource};
+use crate::dto::{DiagnosisSummaryDto, PlantProfileDto};
+use crate::repositories::{DiagnosisRepository, JournalRepository, PlantRepository};
+use crate::services::ThumbnailService;
+
+/// Current profile format version
+const PROFILE_FORMAT_VERSION: u32 = 1;
+
+pub struct ProfileService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    journal_repo: JournalRepository,
+    storage_adapter: Arc<dyn StoragePort>,
+    thumbnail_service: ThumbnailService,
+}
+
+impl ProfileService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        journal_repo: JournalRepository,
+        storage_adapter: Arc<dyn StoragePort>,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            journal_repo,
+            thumbnail_service: ThumbnailService::new(storage_adapter.clone()),
+            storage_adapter,
+        }
+    }
+
+    /// Build a shareable profile for a plant
+    pub async fn export_profile(&self, plant_id: &str, user_id: &str) -> Result<PlantProfileDto> {
+        let plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+
+        // Only concluded findings are shared; transcripts may contain personal details
+        let diagnoses = self
+            .diagnosis_repo
+            .get_all_by_plant_id(plant_id, user_id)
+            .await?
+            .into_iter()
+            .filter(|s| s.state == DiagnosisState::Concluded)
+            .filter_map(|s| {
+                let result = s.diagnosis_context.get("result")?;
+                Some(DiagnosisSummaryDto {
+                    date: s.updated_at,
+                    finding: result["finding"].as_str()?.to_string(),
+                    recommendation: result["recommendation"].as_str()?.to_string(),
+                })
+            })
+            .collect();
+
+        let thumbnail = match &plant.image_url {
+            Some(url) => match self.thumbnail_service.get_thumbnail(url).await {
+                Ok(png) => Some(BASE64.encode(png)),
+                Err(e) => {
+                    log::warn!("Could not create thumbnail for plant {}: {:#}", plant.id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Ok(PlantProfileDto {
+            format_version: PROFILE_FORMAT_VERSION,
+            name: plant.name,
+            care_schedule: plant.care_schedule,
+            diagnoses,
+            thumbnail,
+            exported_at: Utc::now(),
+        })
+    }
+
+    /// Create a new plant from a shared profile. Shared diagnosis summaries
+    /// are kept as journal entries so they inform future chats.
+    pub async fn import_profile(&self, profile: PlantProfileDto, user_id: String) -> Result<Plant> {
+        if profile.format_version > PROFILE_FORMAT_VERSION {
+            anyhow::bail!(
+                "Profile format version {} is newer than supported version {}",
+                profile.format_version,
+                PROFILE_FORMAT_VERSION
+            );
+        }
+
+        let mut plant = Plant::new(user_id, profile.name, profile.care_schedule);
+
+        if let Some(thumbnail) = &profile.thumbnail {
+            let image_data = BASE64
+                .decode(thumbnail)
+                .context("Failed to decode embedded profile image")?;
+            let (url, hash) =
+                storage_adapter::store_by_hash(self.storage_adapter.as_ref(), &image_data).await?;
+            plant.image_url = Some(url);
+            plant.image_hash = Some(hash);
+        }
+
+        let plant = self
+            .plant_repo
+            .create_with_schedule_source(&plant, ScheduleSource::Imported)
+            .await?;
+
+        for diagnosis in profile.diagnoses {
+            let note = format!(
+                "Shared history ({}): {} - {}",
+                config::local_zone().date(diagnosis.date),
+                diagnosis.finding,
+                diagnosis.recommendation
+            );
+            self.journal_repo
+                .create(&JournalEntry::new(plant.id.clone(), note, false))
+                .await?;
+        }
+
+        Ok(plant)
+    }
+}
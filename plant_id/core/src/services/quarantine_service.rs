@@ -0,0 +1,95 @@
+/*!
+ * QUARANTINE SERVICE
+ *
+ * Keeps infested plants apart from the collection: quarantining flags the
+ * plant and schedules weekly inspections on the checklist, and the plant is
+ * released only once a follow-up diagnosis finds it clean.
+ */
+
+use anyhow::{Context, Result};
+
+use crate::domain::{DiagnosisState, JournalEntry, Quarantine, RequestError};
+use crate::repositories::{DiagnosisRepository, JournalRepository, PlantRepository, QuarantineRepository};
+
+pub struct QuarantineService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    journal_repo: JournalRepository,
+    quarantine_repo: QuarantineRepository,
+}
+
+impl QuarantineService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        journal_repo: JournalRepository,
+        quarantine_repo: QuarantineRepository,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            journal_repo,
+            quarantine_repo,
+        }
+    }
+
+    /// Quarantined plants of a user, quarantined longest first
+    pub async fn get_active(&self, user_id: &str) -> Result<Vec<Quarantine>> {
+        self.quarantine_repo.get_active_by_user(user_id).await
+    }
+
+    /// Flag a plant as quarantined. A journal entry notes when and why.
+    pub async fn quarantine(&self, plant_id: &str, user_id: &str, reason: Option<String>) -> Result<Quarantine> {
+        let plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+        if self.quarantine_repo.get_active_by_plant_id(&plant.id).await?.is_some() {
+            anyhow::bail!("{} is already quarantined", plant.display_name());
+        }
+
+        let quarantine = Quarantine::new(plant.id.clone(), reason);
+        self.quarantine_repo.save(&quarantine).await?;
+
+        let note = match &quarantine.reason {
+            Some(reason) => format!("Quarantined: {}", reason),
+            None => "Quarantined".to_string(),
+        };
+        self.journal_repo.create(&JournalEntry::new(plant.id, note, true)).await?;
+
+        Ok(quarantine)
+    }
+
+    /// Release a plant on its latest diagnosis, which must have concluded
+    /// since the quarantine started and found nothing to treat
+    pub async fn release(&self, plant_id: &str, user_id: &str) -> Result<Quarantine> {
+        let plant = self
+            .plant_repo
+            .get_by_id(plant_id, user_id)
+            .await?
+            .ok_or(RequestError::NotFound("Plant"))?;
+        let mut quarantine = self
+            .quarantine_repo
+            .get_active_by_plant_id(&plant.id)
+            .await?
+            .with_context(|| format!("{} is not quarantined", plant.display_name()))?;
+
+        // Sessions are newest first, so this is the latest conclusion
+        let diagnosis = self
+            .diagnosis_repo
+            .get_all_by_plant_id(&plant.id, user_id)
+            .await?
+            .into_iter()
+            .find(|s| s.state == DiagnosisState::Concluded)
+            .context("The plant has not been diagnosed since it was quarantined")?;
+
+        quarantine.release(&diagnosis)?;
+        self.quarantine_repo.save(&quarantine).await?;
+        self.journal_repo
+            .create(&JournalEntry::new(plant.id, "Released from quarantine".to_string(), false))
+            .await?;
+
+        Ok(quarantine)
+    }
+}
@@ -0,0 +1,209 @@
+/*!
+ * QUERY SERVICE
+ *
+ * The read model behind the collection views. Each plant is joined with
+ * what those views show about it - its health index, latest diagnosis,
+ * upcoming reminders, photo count and quarantine - from a single read of
+ * its records, so the views no longer gather them one repository at a time.
+ */
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::config;
+use crate::domain::{health_index, reminder, DiagnosisStatus, HealthIndex, Plant, Quarantine, Reminder};
+use crate::repositories::{
+    CareTaskRepository, DiagnosisRepository, InspectionRepository, JournalRepository, OutcomeRepository,
+    PlantRepository, QuarantineRepository,
+};
+
+/// A plant's most recent diagnosis session, concluded or not
+#[derive(Debug, Clone)]
+pub struct LatestDiagnosis {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub status: DiagnosisStatus,
+    /// What the session found, once it has concluded
+    pub finding: Option<String>,
+}
+
+/// A plant with the fields computed from its records
+#[derive(Debug, Clone)]
+pub struct PlantOverview {
+    pub plant: Plant,
+    pub health: HealthIndex,
+    pub latest_diagnosis: Option<LatestDiagnosis>,
+    /// Care tasks and inspections coming up, soonest first
+    pub reminders: Vec<Reminder>,
+    /// The photo the plant was added from and those attached to its diagnoses
+    pub photo_count: usize,
+    pub quarantine: Option<Quarantine>,
+}
+
+impl PlantOverview {
+    pub fn next_reminder(&self) -> Option<&Reminder> {
+        self.reminders.first()
+    }
+}
+
+pub struct QueryService {
+    plant_repo: PlantRepository,
+    care_task_repo: CareTaskRepository,
+    diagnosis_repo: DiagnosisRepository,
+    outcome_repo: OutcomeRepository,
+    journal_repo: JournalRepository,
+    quarantine_repo: QuarantineRepository,
+    inspection_repo: InspectionRepository,
+}
+
+impl QueryService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        care_task_repo: CareTaskRepository,
+        diagnosis_repo: DiagnosisRepository,
+        outcome_repo: OutcomeRepository,
+        journal_repo: JournalRepository,
+        quarantine_repo: QuarantineRepository,
+        inspection_repo: InspectionRepository,
+    ) -> Self {
+        Self {
+            plant_repo,
+            care_task_repo,
+            diagnosis_repo,
+            outcome_repo,
+            journal_repo,
+            quarantine_repo,
+            inspection_repo,
+        }
+    }
+
+    /// Overviews of every plant in a user's collection
+    pub async fn collection(&self, user_id: &str) -> Result<Vec<PlantOverview>> {
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        self.overviews(plants).await
+    }
+
+    /// Overviews of the given plants, keeping their order, e.g. after they
+    /// were filtered
+    pub async fn overviews(&self, plants: Vec<Plant>) -> Result<Vec<PlantOverview>> {
+        let mut overviews = Vec::with_capacity(plants.len());
+        for plant in plants {
+            overviews.push(self.overview(plant).await?);
+        }
+        Ok(overviews)
+    }
+
+    pub async fn overview(&self, plant: Plant) -> Result<PlantOverview> {
+        let tasks = self.care_task_repo.get_all_by_plant_id(&plant.id).await?;
+        // Newest first
+        let sessions = self
+            .diagnosis_repo
+            .get_all_by_plant_id(&plant.id, &plant.user_id)
+            .await?;
+        let outcomes = self.outcome_repo.get_by_plant_id(&plant.id).await?;
+        let journal = self.journal_repo.get_all_by_plant_id(&plant.id).await?;
+        let quarantine = self.quarantine_repo.get_active_by_plant_id(&plant.id).await?;
+        let inspections = self.inspection_repo.get_open_by_plant_id(&plant.id).await?;
+
+        let zone = config::local_zone();
+        let now = Utc::now();
+        let health = health_index::compute(
+            &plant.care_schedule,
+            &tasks,
+            &sessions,
+            &outcomes,
+            &journal,
+            now,
+            zone,
+        );
+        let reminders =
+            reminder::reminders(&plant, &tasks, quarantine.as_ref(), &inspections, zone.date(now), zone);
+        let latest_diagnosis = sessions.first().map(|session| LatestDiagnosis {
+            id: session.id.clone(),
+            started_at: session.created_at,
+            status: session.status(),
+            finding: session.diagnosis_context["result"]["finding"]
+                .as_str()
+                .map(str::to_string),
+        });
+        let photo_count = usize::from(plant.image_url.is_some())
+            + sessions
+                .iter()
+                .filter_map(|s| s.diagnosis_context["photos"].as_array())
+                .map(Vec::len)
+                .sum::<usize>();
+
+        Ok(PlantOverview {
+            plant,
+            health,
+            latest_diagnosis,
+            reminders,
+            photo_count,
+            quarantine,
+        })
+    }
+}
+
+/// The overviews of plants needing attention, least healthy first
+pub fn needing_attention(overviews: Vec<PlantOverview>) -> Vec<PlantOverview> {
+    let mut found: Vec<PlantOverview> = overviews
+        .into_iter()
+        .filter(|o| o.health.needs_attention())
+        .collect();
+    found.sort_by_key(|o| o.health.score);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use crate::domain::{CareSchedule, DiagnosisSession, DiagnosisState, ReminderKind};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_overview_joins_the_plant_records() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let plants = PlantRepository::new(db.clone());
+        let diagnoses = DiagnosisRepository::new(db.clone());
+        let quarantines = QuarantineRepository::new(db.clone());
+        let service = QueryService::new(
+            plants.clone(),
+            CareTaskRepository::new(db.clone()),
+            diagnoses.clone(),
+            OutcomeRepository::new(db.clone()),
+            JournalRepository::new(db.clone()),
+            quarantines.clone(),
+            InspectionRepository::new(db),
+        );
+
+        let mut plant = Plant::new("u".to_string(), "Calathea".to_string(), CareSchedule::default());
+        plant.image_url = Some("file:///calathea.jpg".to_string());
+        plants.create(&plant).await.unwrap();
+
+        let overview = service.overview(plant.clone()).await.unwrap();
+        assert!(overview.latest_diagnosis.is_none());
+        assert_eq!(overview.photo_count, 1);
+        assert_eq!(overview.next_reminder().unwrap().kind, ReminderKind::Water);
+        assert!(overview.quarantine.is_none());
+
+        let mut diagnosis = DiagnosisSession::new(plant.id.clone(), "Webbing".to_string());
+        diagnosis.state = DiagnosisState::Concluded;
+        diagnosis.diagnosis_context["result"] = json!({"finding": "Spider mites", "severity": "serious"});
+        diagnosis.diagnosis_context["photos"] = json!([{"url": "a.jpg"}, {"url": "b.jpg"}]);
+        diagnoses.create(&diagnosis).await.unwrap();
+        quarantines.save(&Quarantine::new(plant.id.clone(), None)).await.unwrap();
+
+        let overviews = service.collection("u").await.unwrap();
+        assert_eq!(overviews.len(), 1);
+        let overview = &overviews[0];
+        let latest = overview.latest_diagnosis.as_ref().unwrap();
+        assert_eq!(latest.status, DiagnosisStatus::Completed);
+        assert_eq!(latest.finding.as_deref(), Some("Spider mites"));
+        assert_eq!(overview.photo_count, 3);
+        assert!(overview.quarantine.is_some());
+        assert!(overview.reminders.iter().any(|r| r.kind == ReminderKind::Inspect));
+        assert!(overview.health.score < 100);
+    }
+}
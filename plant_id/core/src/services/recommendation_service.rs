@@ -0,0 +1,110 @@
+/*!
+ * RECOMMENDATION SERVICE
+ *
+ * Suggests new plants: the AI proposes species for the owner's light, pets
+ * and effort level, knowing what they already own, and the suggestions are
+ * checked against the bundled toxicity list before they are shown. Picks
+ * go on the wishlist. Only the owner can ask for suggestions, as a plant
+ * sitter cannot start AI sessions.
+ */
+
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use crate::adapters::AiAdapter;
+use crate::domain::recommendation::{self, Conditions};
+use crate::domain::toxicity::Pet;
+use crate::domain::{Permission, Recommendation, WishlistItem};
+use crate::repositories::{PlantRepository, UserRepository, WishlistRepository};
+
+/// Suggestions asked for on top of the requested number, as some are
+/// dropped when checked
+const EXTRA_SUGGESTIONS: usize = 3;
+
+pub struct RecommendationService {
+    plant_repo: PlantRepository,
+    wishlist_repo: WishlistRepository,
+    user_repo: UserRepository,
+    ai_adapter: AiAdapter,
+}
+
+impl RecommendationService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        wishlist_repo: WishlistRepository,
+        user_repo: UserRepository,
+        ai_adapter: AiAdapter,
+    ) -> Self {
+        Self {
+            plant_repo,
+            wishlist_repo,
+            user_repo,
+            ai_adapter,
+        }
+    }
+
+    /// Up to `count` plants that suit the conditions and are not in the
+    /// collection or on the wishlist yet
+    pub async fn recommend(
+        &self,
+        user_id: &str,
+        conditions: &Conditions,
+        count: usize,
+    ) -> Result<Vec<Recommendation>> {
+        self.user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        let wishlist = self.wishlist_repo.get_all_by_user(user_id).await?;
+
+        let request = json!({
+            "count": count + EXTRA_SUGGESTIONS,
+            "light": conditions.light.to_string(),
+            "pets": conditions.pets.iter().map(Pet::as_str).collect::<Vec<_>>(),
+            "effort": conditions.effort,
+            "owned": plants.iter().map(|p| &p.name).collect::<Vec<_>>(),
+            "wishlist": wishlist.iter().map(|w| &w.species).collect::<Vec<_>>(),
+        });
+
+        let suggestions = self
+            .ai_adapter
+            .recommend_plants(&request)
+            .await
+            .context("Could not get plant suggestions")?
+            .into_iter()
+            .map(|s| {
+                let mut toxic_to = Vec::new();
+                if s.toxic_to_cats {
+                    toxic_to.push(Pet::Cat);
+                }
+                if s.toxic_to_dogs {
+                    toxic_to.push(Pet::Dog);
+                }
+                Recommendation {
+                    species: s.species,
+                    common_name: s.common_name,
+                    reason: s.reason,
+                    light: s.light,
+                    difficulty: s.difficulty,
+                    toxic_to,
+                    toxicity_verified: false,
+                }
+            })
+            .collect();
+
+        let mut recommendations = recommendation::screen(suggestions, conditions, &plants, &wishlist);
+        recommendations.truncate(count);
+        Ok(recommendations)
+    }
+
+    /// Put a recommended plant on the wishlist, with its reason as the note
+    pub async fn add_to_wishlist(&self, user_id: &str, recommendation: &Recommendation) -> Result<WishlistItem> {
+        let item = WishlistItem::new(
+            user_id,
+            &recommendation.species,
+            recommendation.common_name.clone(),
+            recommendation.reason.clone(),
+        )?;
+        self.wishlist_repo.save(&item).await?;
+        Ok(item)
+    }
+}
@@ -0,0 +1,94 @@
+/*!
+ * REPORT SERVICE
+ *
+ * Reports across the whole collection's history. The problem report
+ * gathers the findings of completed diagnoses, archived plants included,
+ * and shows which problems keep coming back on a plant, species or room.
+ */
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::domain::encyclopedia;
+use crate::domain::enums::{DiagnosisStatus, Severity};
+use crate::domain::{DiagnosisFilter, Incident, Plant, ProblemReport};
+use crate::repositories::{DiagnosisRepository, PlantRepository};
+
+/// Sessions read from the repository at a time
+const PAGE_SIZE: usize = 500;
+
+pub struct ReportService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+}
+
+impl ReportService {
+    pub fn new(plant_repo: PlantRepository, diagnosis_repo: DiagnosisRepository) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+        }
+    }
+
+    /// Recurring problems, from diagnoses started since `since`. Findings
+    /// with nothing to treat are left out.
+    pub async fn problems(&self, user_id: &str, since: DateTime<Utc>) -> Result<ProblemReport> {
+        let mut plants: HashMap<String, Plant> = HashMap::new();
+        for plant in self.plant_repo.get_all_by_user(user_id).await? {
+            plants.insert(plant.id.clone(), plant);
+        }
+        for plant in self.plant_repo.get_archived_by_user(user_id).await? {
+            plants.insert(plant.id.clone(), plant);
+        }
+
+        let filter = DiagnosisFilter {
+            status: Some(DiagnosisStatus::Completed),
+            since: Some(since),
+            ..Default::default()
+        };
+        let mut incidents = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = self
+                .diagnosis_repo
+                .query_summaries(user_id, None, &filter, offset, PAGE_SIZE)
+                .await?;
+            offset += page.len();
+            let last_page = page.len() < PAGE_SIZE;
+
+            for summary in page {
+                let Some(result) = &summary.result else { continue };
+                let Some(finding) = result["finding"].as_str().map(str::trim).filter(|f| !f.is_empty()) else {
+                    continue;
+                };
+                if result["severity"].as_str().and_then(Severity::from_str) == Some(Severity::Info) {
+                    continue;
+                }
+                let Some(plant) = plants.get(&summary.plant_id) else { continue };
+
+                // The entry linked at conclusion, or else one the finding names
+                let entry = result["encyclopedia_entries"][0]
+                    .as_str()
+                    .map(str::to_string)
+                    .or_else(|| encyclopedia::find_mentions(finding).first().map(|e| e.name.clone()));
+                incidents.push(Incident {
+                    session_id: summary.id.clone(),
+                    plant_id: plant.id.clone(),
+                    plant_name: plant.display_name().to_string(),
+                    species: plant.species().to_string(),
+                    room: plant.room.clone(),
+                    finding: finding.to_string(),
+                    entry,
+                    concluded_at: summary.updated_at,
+                });
+            }
+
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(ProblemReport::new(incidents, since))
+    }
+}
@@ -0,0 +1,162 @@
+/*!
+ * SHOPPING SERVICE
+ *
+ * Builds the shopping list from the supplies recent diagnoses call for
+ * (their recommendations and the treatment steps of the pests and
+ * diseases they found), plus what the owner added or removed by hand.
+ */
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+
+use crate::domain::encyclopedia;
+use crate::domain::shopping_list::{self, ShoppingSuggestion};
+use crate::domain::{ShoppingEntry, ShoppingItem};
+use crate::repositories::{DiagnosisRepository, PlantRepository, ShoppingRepository};
+
+pub struct ShoppingService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    shopping_repo: ShoppingRepository,
+}
+
+impl ShoppingService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        shopping_repo: ShoppingRepository,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            shopping_repo,
+        }
+    }
+
+    /// The shopping list, from diagnoses concluded since `since`
+    pub async fn list(&self, user_id: &str, since: DateTime<Utc>) -> Result<Vec<ShoppingItem>> {
+        let plant_names: HashMap<String, String> = self
+            .plant_repo
+            .get_all_by_user(user_id)
+            .await?
+            .into_iter()
+            .map(|p| (p.id.clone(), p.display_name().to_string()))
+            .collect();
+
+        let mut suggestions = Vec::new();
+        for summary in self
+            .diagnosis_repo
+            .get_concluded_summaries_since(user_id, since)
+            .await?
+        {
+            let Some(result) = &summary.result else {
+                continue;
+            };
+
+            // The recommendation, then the treatment of each linked entry
+            let mut texts: Vec<&str> = result["recommendation"].as_str().into_iter().collect();
+            for name in result["encyclopedia_entries"].as_array().into_iter().flatten() {
+                let entry = encyclopedia::entries()
+                    .iter()
+                    .find(|e| name.as_str() == Some(e.name.as_str()));
+                texts.extend(entry.into_iter().flat_map(|e| e.treatment.iter().map(String::as_str)));
+            }
+
+            let reason = format!(
+                "{}: {}",
+                plant_names.get(&summary.plant_id).map_or("Unknown plant", String::as_str),
+                result["finding"].as_str().unwrap_or("diagnosis")
+            );
+            for supply in texts.iter().flat_map(|t| shopping_list::find_supplies(t)) {
+                suggestions.push(ShoppingSuggestion {
+                    supply: supply.to_string(),
+                    reason: reason.clone(),
+                    recommended_at: summary.updated_at,
+                });
+            }
+        }
+
+        let entries = self.shopping_repo.get_all_by_user(user_id).await?;
+        Ok(shopping_list::build_list(&suggestions, &entries))
+    }
+
+    /// Put an item on the list by hand
+    pub async fn add(&self, name: &str, user_id: &str) -> Result<ShoppingEntry> {
+        self.save(name, false, user_id).await
+    }
+
+    /// Take an item off the list, until a newer diagnosis recommends it
+    pub async fn remove(&self, name: &str, user_id: &str) -> Result<ShoppingEntry> {
+        self.save(name, true, user_id).await
+    }
+
+    async fn save(&self, name: &str, removed: bool, user_id: &str) -> Result<ShoppingEntry> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Item name cannot be empty");
+        }
+
+        match self.shopping_repo.get_by_name(name, user_id).await? {
+            Some(mut entry) => {
+                entry.name = name.to_string();
+                entry.removed = removed;
+                entry.updated_at = Utc::now();
+                self.shopping_repo.update(&entry).await?;
+                Ok(entry)
+            }
+            None => {
+                let entry = ShoppingEntry::new(user_id.to_string(), name.to_string(), removed);
+                self.shopping_repo.create(&entry).await?;
+                Ok(entry)
+            }
+        }
+    }
+}
+
+/// Render the list as a Markdown checklist
+pub fn to_markdown(items: &[ShoppingItem], date: NaiveDate) -> String {
+    let mut markdown = format!("# Plant Care Shopping List ({})\n\n", date);
+    if items.is_empty() {
+        markdown.push_str("Nothing to buy.\n");
+    }
+
+    for item in items {
+        markdown.push_str(&format!("- [ ] {}", item.name));
+        if !item.reasons.is_empty() {
+            markdown.push_str(&format!(" (for {})", item.reasons.join("; ")));
+        }
+        markdown.push('\n');
+    }
+
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_checklist() {
+        let items = [
+            ShoppingItem {
+                name: "Gloves".to_string(),
+                reasons: Vec::new(),
+                added_manually: true,
+            },
+            ShoppingItem {
+                name: "Neem oil".to_string(),
+                reasons: vec!["Fern: Spider mites".to_string(), "Ivy: Aphids".to_string()],
+                added_manually: false,
+            },
+        ];
+        let date = NaiveDate::from_ymd_opt(2025, 10, 25).unwrap();
+
+        assert_eq!(
+            to_markdown(&items, date),
+            "# Plant Care Shopping List (2025-10-25)\n\n\
+             - [ ] Gloves\n\
+             - [ ] Neem oil (for Fern: Spider mites; Ivy: Aphids)\n"
+        );
+    }
+}
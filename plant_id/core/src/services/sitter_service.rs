@@ -0,0 +1,73 @@
+/*!
+ * SITTER SERVICE
+ *
+ * Hands the device over to a plant sitter and back. While sitter mode is
+ * on, services refuse the actions the sitter role does not allow; only the
+ * PIN chosen at handover switches back to the owner.
+ */
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::crypto;
+use crate::domain::{User, UserRole};
+use crate::repositories::UserRepository;
+
+/// Shortest PIN accepted for ending sitter mode
+pub const MIN_PIN_LEN: usize = 4;
+
+pub struct SitterService {
+    user_repo: UserRepository,
+}
+
+impl SitterService {
+    pub fn new(user_repo: UserRepository) -> Self {
+        Self { user_repo }
+    }
+
+    pub async fn role(&self, user_id: &str) -> Result<UserRole> {
+        self.user_repo.role(user_id).await
+    }
+
+    /// Restrict `user_id` to the sitter role until `end` is called with `pin`
+    pub async fn start(&self, user_id: &str, pin: &str) -> Result<()> {
+        if pin.chars().count() < MIN_PIN_LEN {
+            anyhow::bail!("The PIN must have at least {} characters", MIN_PIN_LEN);
+        }
+
+        let mut user = self
+            .user_repo
+            .get_by_id(user_id)
+            .await?
+            .unwrap_or_else(|| User::owner(user_id.to_string()));
+        if user.role == UserRole::Sitter {
+            anyhow::bail!("Sitter mode is already on");
+        }
+
+        user.role = UserRole::Sitter;
+        user.pin_hash = Some(crypto::hash_secret(pin)?);
+        user.updated_at = Utc::now();
+        self.user_repo.save(&user).await
+    }
+
+    /// Give `user_id` the owner role back
+    pub async fn end(&self, user_id: &str, pin: &str) -> Result<()> {
+        let mut user = match self.user_repo.get_by_id(user_id).await? {
+            Some(user) if user.role == UserRole::Sitter => user,
+            _ => anyhow::bail!("Sitter mode is not on"),
+        };
+
+        let pin_matches = user
+            .pin_hash
+            .as_deref()
+            .is_some_and(|hash| crypto::verify_secret(pin, hash));
+        if !pin_matches {
+            anyhow::bail!("Wrong PIN");
+        }
+
+        user.role = UserRole::Owner;
+        user.pin_hash = None;
+        user.updated_at = Utc::now();
+        self.user_repo.save(&user).await
+    }
+}
@@ -0,0 +1,137 @@
+/*!
+ * STREAK SERVICE
+ *
+ * Works out runs of on-time waterings for each plant and across the
+ * collection from the audit log, and tells the webhooks when a run reaches
+ * a badge (only with NOTIFY_STREAKS set, as not everyone wants them).
+ */
+
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::config;
+use crate::domain::care_streak::{self, Watering};
+use crate::domain::{Badge, CareTask, CareTaskKind, NotificationEvent, Plant, Streak};
+use crate::repositories::{CareTaskRepository, EventRepository, PlantRepository};
+use crate::services::EventBus;
+
+#[derive(Debug, Clone)]
+pub struct PlantStreak {
+    pub plant: Plant,
+    pub streak: Streak,
+    /// Waterings on record
+    pub waterings: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CareStreaks {
+    /// Across the collection: a late watering of any plant ends it
+    pub collection: Streak,
+    /// Longest current streak first
+    pub plants: Vec<PlantStreak>,
+}
+
+pub struct StreakService {
+    plant_repo: PlantRepository,
+    care_task_repo: CareTaskRepository,
+    event_repo: EventRepository,
+    event_bus: EventBus,
+}
+
+impl StreakService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        care_task_repo: CareTaskRepository,
+        event_repo: EventRepository,
+        event_bus: EventBus,
+    ) -> Self {
+        Self {
+            plant_repo,
+            care_task_repo,
+            event_repo,
+            event_bus,
+        }
+    }
+
+    /// Streaks of the collection as of `today` in the owner's time zone
+    pub async fn streaks(&self, user_id: &str, today: NaiveDate) -> Result<CareStreaks> {
+        let zone = config::local_zone();
+        let mut all: Vec<Watering> = Vec::new();
+        let mut any_overdue = false;
+        let mut plants = Vec::new();
+
+        for plant in self.plant_repo.get_all_by_user(user_id).await? {
+            let events = self.event_repo.get_by_plant_id(&plant.id).await?;
+            let task = self
+                .care_task_repo
+                .get_all_by_plant_id(&plant.id)
+                .await?
+                .into_iter()
+                .find(|t| t.kind == CareTaskKind::Water)
+                .unwrap_or_else(|| CareTask::new(&plant.id, CareTaskKind::Water));
+            let overdue = task.last_done_at.is_some() && task.due_date(&plant.care_schedule, today, zone) < today;
+
+            let times = care_streak::watered_at(&events);
+            let waterings = care_streak::mark_waterings(&times, task.interval_days(&plant.care_schedule), zone);
+            any_overdue |= overdue;
+            plants.push(PlantStreak {
+                streak: Streak::of(&waterings, overdue),
+                waterings: waterings.len(),
+                plant,
+            });
+            all.extend(waterings);
+        }
+
+        all.sort_by_key(|w| w.at);
+        plants.sort_by(|a, b| {
+            b.streak
+                .current
+                .cmp(&a.streak.current)
+                .then_with(|| a.plant.display_name().cmp(b.plant.display_name()))
+        });
+        Ok(CareStreaks {
+            collection: Streak::of(&all, any_overdue),
+            plants,
+        })
+    }
+
+    /// Badges just earned by watering `plant_id`: the streaks were counted
+    /// after the watering, so one reaching a milestone exactly got there now
+    pub fn milestones(streaks: &CareStreaks, plant_id: &str) -> Vec<NotificationEvent> {
+        let mut events = Vec::new();
+
+        if let Some(watered) = streaks.plants.iter().find(|p| p.plant.id == plant_id) {
+            if let Some(badge) = Badge::milestone(watered.streak.current) {
+                events.push(NotificationEvent::StreakMilestone {
+                    plant_id: Some(watered.plant.id.clone()),
+                    plant_name: Some(watered.plant.display_name().to_string()),
+                    streak: badge.streak,
+                    badge: badge.name.to_string(),
+                });
+            }
+        }
+        if let Some(badge) = Badge::milestone(streaks.collection.current) {
+            events.push(NotificationEvent::StreakMilestone {
+                plant_id: None,
+                plant_name: None,
+                streak: badge.streak,
+                badge: badge.name.to_string(),
+            });
+        }
+
+        events
+    }
+
+    /// Send milestones to the webhooks, if NOTIFY_STREAKS is set
+    pub async fn notify(&self, milestones: &[NotificationEvent]) {
+        let enabled = std::env::var("NOTIFY_STREAKS")
+            .is_ok_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"));
+        if !enabled {
+            return;
+        }
+
+        for event in milestones {
+            self.event_bus.publish(event.clone()).await;
+        }
+    }
+}
@@ -0,0 +1,355 @@
+/*!
+ * SYNC SERVICE
+ *
+ * Shares one collection between devices through their audit logs. Each
+ * sync pulls the event batches other devices pushed to the remote,
+ * applies their snapshots (last write wins per record), then pushes this
+ * device's new events as a batch of its own.
+ *
+ * A plant's care overrides are logged under the plant's ID, so they travel
+ * with its merged schedule and a conflict over one is a conflict over both.
+ *
+ * A record changed on both sides since the last sync is a conflict; the
+ * caller decides which version to keep. Queued syncs, run by the job
+ * worker, keep the latest change.
+ */
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::adapters::SyncRemote;
+use crate::domain::{AuditAction, AuditEntity, AuditEvent, Job, JobKind};
+use crate::repositories::{
+    CareOverrideRepository, ChatRepository, DiagnosisRepository, JournalRepository, MoistureRepository,
+    PlantRepository, SyncRepository,
+};
+use crate::services::{JobHandler, JobOutcome};
+
+/// A record changed both on this device and on another one
+#[derive(Debug, Clone)]
+pub struct SyncConflict {
+    pub entity: AuditEntity,
+    pub entity_id: String,
+    /// Latest unpushed change made on this device
+    pub local: AuditEvent,
+    /// Latest change pulled from the remote
+    pub remote: AuditEvent,
+}
+
+impl SyncConflict {
+    /// Human-readable name of the conflicting record
+    pub fn label(&self) -> String {
+        let name = self.local.payload["name"].as_str();
+        match (self.entity, name) {
+            (AuditEntity::Plant, Some(name)) => format!("plant {}", name),
+            (AuditEntity::CareTemplate, Some(name)) => format!("template {}", name),
+            (AuditEntity::ShoppingEntry, Some(name)) => format!("shopping list item {}", name),
+            _ => format!(
+                "{} {}",
+                self.entity.as_str().to_lowercase().replace('_', " "),
+                &self.entity_id[..self.entity_id.len().min(8)]
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    KeepLocal,
+    KeepRemote,
+}
+
+/// Keep whichever version was written last
+pub fn last_write_wins(conflict: &SyncConflict) -> Resolution {
+    if conflict.local.created_at > conflict.remote.created_at {
+        Resolution::KeepLocal
+    } else {
+        Resolution::KeepRemote
+    }
+}
+
+/// Events one device pushed in one sync
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncBatch {
+    device_id: String,
+    events: Vec<AuditEvent>,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// Where the batches are stored
+    pub location: String,
+    /// Records that had never been synced and were added to the log
+    pub baseline: usize,
+    /// Changes pulled from other devices and applied here
+    pub pulled: usize,
+    /// Changes pushed from this device
+    pub pushed: usize,
+    pub conflicts: usize,
+}
+
+pub struct SyncService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    chat_repo: ChatRepository,
+    journal_repo: JournalRepository,
+    moisture_repo: MoistureRepository,
+    care_override_repo: CareOverrideRepository,
+    sync_repo: SyncRepository,
+    remote: Arc<dyn SyncRemote>,
+}
+
+impl SyncService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        chat_repo: ChatRepository,
+        journal_repo: JournalRepository,
+        moisture_repo: MoistureRepository,
+        care_override_repo: CareOverrideRepository,
+        sync_repo: SyncRepository,
+        remote: Arc<dyn SyncRemote>,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            chat_repo,
+            journal_repo,
+            moisture_repo,
+            care_override_repo,
+            sync_repo,
+            remote,
+        }
+    }
+
+    /// Pull and apply other devices' changes, then push this device's.
+    /// `resolve` is asked which version to keep for every conflict.
+    pub async fn sync(
+        &self,
+        user_id: &str,
+        mut resolve: impl FnMut(&SyncConflict) -> Result<Resolution>,
+    ) -> Result<SyncReport> {
+        let device_id = self.sync_repo.device_id().await?;
+        let mut report = SyncReport {
+            location: self.remote.location(),
+            baseline: self.record_baseline(user_id).await?,
+            ..Default::default()
+        };
+
+        // Pull
+        let applied = self.sync_repo.applied_batches().await?;
+        let own_prefix = format!("{}/", device_id);
+        let mut batch_names: Vec<String> = self
+            .remote
+            .list_batches()
+            .await
+            .context("Failed to list the sync remote")?
+            .into_iter()
+            .filter(|name| !name.starts_with(&own_prefix) && !applied.contains(name))
+            .collect();
+        batch_names.sort();
+
+        let mut remote_events = Vec::new();
+        for name in &batch_names {
+            let data = self.remote.read_batch(name).await?;
+            let batch: SyncBatch = serde_json::from_slice(&data)
+                .with_context(|| format!("Malformed sync batch {}", name))?;
+            remote_events.extend(batch.events);
+        }
+        remote_events.sort_by_key(|e| e.created_at);
+
+        // Resolve records changed on both sides
+        let local_events = self.sync_repo.unsynced_events().await?;
+        let conflicts = find_conflicts(&local_events, &remote_events);
+        report.conflicts = conflicts.len();
+
+        let mut keep_local = HashSet::new();
+        let mut superseded = Vec::new();
+        for conflict in &conflicts {
+            match resolve(conflict)? {
+                Resolution::KeepLocal => {
+                    keep_local.insert(conflict.entity_id.clone());
+                }
+                Resolution::KeepRemote => superseded.extend(
+                    local_events
+                        .iter()
+                        .filter(|e| e.entity_id == conflict.entity_id)
+                        .map(|e| e.id.clone()),
+                ),
+            }
+        }
+        remote_events.retain(|e| !keep_local.contains(&e.entity_id));
+
+        report.pulled = self.sync_repo.apply(&batch_names, &remote_events).await?;
+        self.sync_repo.mark_synced(&superseded).await?;
+
+        // Push
+        let outgoing: Vec<AuditEvent> = local_events
+            .into_iter()
+            .filter(|e| !superseded.contains(&e.id))
+            .collect();
+        if !outgoing.is_empty() {
+            let name = format!("{}/{:020}.json", device_id, Utc::now().timestamp_millis());
+            let ids: Vec<String> = outgoing.iter().map(|e| e.id.clone()).collect();
+            let batch = SyncBatch {
+                device_id,
+                events: outgoing,
+            };
+            self.remote
+                .write_batch(&name, &serde_json::to_vec(&batch)?)
+                .await
+                .context("Failed to push changes to the sync remote")?;
+            self.sync_repo.mark_synced(&ids).await?;
+            report.pushed = ids.len();
+        }
+
+        Ok(report)
+    }
+
+    /// Snapshot records created before the audit log existed, so the first
+    /// sync shares the whole collection. Returns how many were added.
+    async fn record_baseline(&self, user_id: &str) -> Result<usize> {
+        let recorded = self.sync_repo.recorded_entity_ids().await?;
+        // Keyed by plant ID, so the plant's own events do not cover them
+        let recorded_overrides = self.sync_repo.recorded_ids_of(AuditEntity::CareOverrides).await?;
+        let mut plants = self.plant_repo.get_all_by_user(user_id).await?;
+        plants.extend(self.plant_repo.get_archived_by_user(user_id).await?);
+
+        let created = AuditAction::Created;
+        let mut events = Vec::new();
+        let mut overrides = Vec::new();
+        for plant in &plants {
+            let plant_id = Some(plant.id.as_str());
+            events.push(AuditEvent::new(AuditEntity::Plant, &plant.id, plant_id, created, plant)?);
+            for session in self.diagnosis_repo.get_all_by_plant_id(&plant.id, user_id).await? {
+                events.push(AuditEvent::new(AuditEntity::Diagnosis, &session.id, plant_id, created, &session)?);
+                for message in self.diagnosis_repo.get_messages(&session.id, 0, None).await? {
+                    events.push(AuditEvent::new(AuditEntity::DiagnosisMessage, &message.id, plant_id, created, &message)?);
+                }
+            }
+            for session in self.chat_repo.get_all_by_plant_id(&plant.id).await? {
+                events.push(AuditEvent::new(AuditEntity::Chat, &session.id, plant_id, created, &session)?);
+            }
+            for entry in self.journal_repo.get_all_by_plant_id(&plant.id).await? {
+                events.push(AuditEvent::new(AuditEntity::Journal, &entry.id, plant_id, created, &entry)?);
+            }
+            for reading in self.moisture_repo.get_all_by_plant_id(&plant.id).await? {
+                events.push(AuditEvent::new(AuditEntity::MoistureReading, &reading.id, plant_id, created, &reading)?);
+            }
+            if !recorded_overrides.contains(&plant.id) {
+                if let Some(care) = self.care_override_repo.get_by_plant_id(&plant.id).await? {
+                    overrides.push(AuditEvent::new(AuditEntity::CareOverrides, &plant.id, plant_id, created, &care)?);
+                }
+            }
+        }
+        events.retain(|e| !recorded.contains(&e.entity_id));
+        events.extend(overrides);
+
+        self.sync_repo.record_baseline(&events).await?;
+        Ok(events.len())
+    }
+}
+
+#[async_trait]
+impl JobHandler for SyncService {
+    fn kind(&self) -> JobKind {
+        JobKind::Sync
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    async fn run(&self, job: &Job) -> Result<JobOutcome> {
+        let Some(user_id) = &job.user_id else {
+            anyhow::bail!("The job names no user");
+        };
+        let report = self.sync(user_id, |conflict| Ok(last_write_wins(conflict))).await?;
+        Ok(JobOutcome::Done(format!(
+            "with {}: {} pulled, {} pushed",
+            report.location, report.pulled, report.pushed
+        )))
+    }
+}
+
+/// Records with unpushed local changes that other devices also changed,
+/// in the order the remote changes were made
+pub fn find_conflicts(local: &[AuditEvent], remote: &[AuditEvent]) -> Vec<SyncConflict> {
+    let mut latest_local: HashMap<&str, &AuditEvent> = HashMap::new();
+    for event in local {
+        latest_local.insert(&event.entity_id, event);
+    }
+
+    let mut latest_remote: Vec<&AuditEvent> = Vec::new();
+    for event in remote {
+        latest_remote.retain(|e| e.entity_id != event.entity_id);
+        latest_remote.push(event);
+    }
+
+    latest_remote
+        .into_iter()
+        .filter_map(|remote| {
+            let local = latest_local.get(remote.entity_id.as_str())?;
+            // Both sides reached the same state: nothing to choose
+            if local.action == remote.action && local.payload == remote.payload {
+                return None;
+            }
+            Some(SyncConflict {
+                entity: remote.entity,
+                entity_id: remote.entity_id.clone(),
+                local: (*local).clone(),
+                remote: remote.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    fn event(entity_id: &str, name: &str, minutes_ago: i64) -> AuditEvent {
+        let mut event = AuditEvent::new(
+            AuditEntity::Plant,
+            entity_id,
+            Some(entity_id),
+            AuditAction::Updated,
+            &json!({ "name": name }),
+        )
+        .unwrap();
+        event.created_at = Utc::now() - Duration::minutes(minutes_ago);
+        event
+    }
+
+    #[test]
+    fn test_find_conflicts_only_for_records_changed_on_both_sides() {
+        let local = vec![event("p1", "Monty", 5), event("p2", "Fern", 5)];
+        let remote = vec![
+            event("p1", "Monstera", 10),
+            event("p1", "Big Monstera", 1),
+            event("p3", "Cactus", 1),
+        ];
+
+        let conflicts = find_conflicts(&local, &remote);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].entity_id, "p1");
+        assert_eq!(conflicts[0].remote.payload["name"], "Big Monstera");
+        assert_eq!(conflicts[0].label(), "plant Monty");
+        assert_eq!(last_write_wins(&conflicts[0]), Resolution::KeepRemote);
+    }
+
+    #[test]
+    fn test_same_change_on_both_sides_is_no_conflict() {
+        let local = vec![event("p1", "Monty", 5)];
+        let remote = vec![event("p1", "Monty", 1)];
+
+        assert!(find_conflicts(&local, &remote).is_empty());
+    }
+}
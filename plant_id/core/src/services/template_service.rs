@@ -0,0 +1,138 @@
+/*!
+ * TEMPLATE SERVICE
+ *
+ * Manages care schedule templates: named schedules for groups of plants,
+ * taken from a plant whose care works well and applied to new plants
+ * instead of asking the AI again.
+ */
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+use crate::domain::validation::validate_care_schedule;
+use crate::domain::{CareScheduleUpdate, CareTemplate, Plant, RequestError, ScheduleSource};
+use crate::repositories::{PlantRepository, TemplateRepository};
+
+pub struct TemplateService {
+    template_repo: TemplateRepository,
+    plant_repo: PlantRepository,
+}
+
+impl TemplateService {
+    pub fn new(template_repo: TemplateRepository, plant_repo: PlantRepository) -> Self {
+        Self {
+            template_repo,
+            plant_repo,
+        }
+    }
+
+    /// Save a plant's current care schedule as a new template
+    pub async fn create_from_plant(
+        &self,
+        name: &str,
+        plant_identifier: &str,
+        user_id: &str,
+    ) -> Result<CareTemplate> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Template name cannot be empty");
+        }
+        if self.template_repo.get_by_name(name, user_id).await?.is_some() {
+            anyhow::bail!("A template named '{}' already exists", name);
+        }
+
+        let plant = self.plant_repo.find(plant_identifier, user_id).await?;
+        let template = CareTemplate::new(user_id.to_string(), name.to_string(), plant.care_schedule);
+        self.template_repo.create(&template).await?;
+
+        Ok(template)
+    }
+
+    pub async fn list(&self, user_id: &str) -> Result<Vec<CareTemplate>> {
+        self.template_repo.get_all_by_user(user_id).await
+    }
+
+    /// A template by name, ignoring case
+    pub async fn get(&self, name: &str, user_id: &str) -> Result<CareTemplate> {
+        self.template_repo
+            .get_by_name(name, user_id)
+            .await?
+            .with_context(|| format!("Template '{}' not found", name))
+    }
+
+    /// Change a template's schedule and/or name, returning it with any
+    /// validation warnings for the new schedule
+    pub async fn edit(
+        &self,
+        name: &str,
+        update: &CareScheduleUpdate,
+        new_name: Option<&str>,
+        user_id: &str,
+    ) -> Result<(CareTemplate, Vec<String>)> {
+        let mut template = self.get(name, user_id).await?;
+        if update.is_empty() && new_name.is_none() {
+            anyhow::bail!("Nothing to change");
+        }
+
+        if let Some(new_name) = new_name.map(str::trim) {
+            if new_name.is_empty() {
+                anyhow::bail!("Template name cannot be empty");
+            }
+            let taken = self.template_repo.get_by_name(new_name, user_id).await?;
+            if taken.is_some_and(|t| t.id != template.id) {
+                anyhow::bail!("A template named '{}' already exists", new_name);
+            }
+            template.name = new_name.to_string();
+        }
+
+        let schedule = update.apply_to(&template.schedule);
+        let report = validate_care_schedule(&schedule);
+        if !report.is_valid() {
+            return Err(RequestError::Invalid {
+                what: "care schedule",
+                reason: report.errors.join("; "),
+            }
+            .into());
+        }
+
+        template.schedule = schedule;
+        template.updated_at = Utc::now();
+        self.template_repo.update(&template).await?;
+
+        Ok((template, report.warnings))
+    }
+
+    pub async fn delete(&self, name: &str, user_id: &str) -> Result<CareTemplate> {
+        let template = self.get(name, user_id).await?;
+        self.template_repo.delete(&template).await?;
+
+        Ok(template)
+    }
+
+    /// Replace a plant's care schedule with a template's, returning both
+    pub async fn apply(
+        &self,
+        name: &str,
+        plant_identifier: &str,
+        user_id: &str,
+    ) -> Result<(CareTemplate, Plant)> {
+        let template = self.get(name, user_id).await?;
+        let mut plant = self.plant_repo.find(plant_identifier, user_id).await?;
+
+        plant.care_schedule = template.schedule.clone();
+        plant.updated_at = Utc::now();
+        // The owner's overrides stay over the template's schedule
+        plant.care_schedule = self
+            .plant_repo
+            .update_schedule(
+                &plant,
+                ScheduleSource::Template {
+                    name: template.name.clone(),
+                },
+            )
+            .await?
+            .schedule;
+
+        Ok((template, plant))
+    }
+}
@@ -0,0 +1,81 @@
+/*!
+ * THUMBNAIL SERVICE
+ *
+ * Generates small PNG thumbnails of stored plant images and caches them
+ * on the local disk, so previews don't re-download or re-decode the
+ * full-size photo every time.
+ */
+
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use image::ImageFormat;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::adapters::StoragePort;
+
+/// Longest edge of a generated thumbnail, in pixels
+const THUMBNAIL_SIZE: u32 = 256;
+
+pub struct ThumbnailService {
+    storage_adapter: Arc<dyn StoragePort>,
+    cache_dir: PathBuf,
+}
+
+impl ThumbnailService {
+    pub fn new(storage_adapter: Arc<dyn StoragePort>) -> Self {
+        let cache_dir = std::env::var("THUMBNAIL_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                let mut dir = dirs::cache_dir().unwrap_or_else(|| PathBuf::from("."));
+                dir.push("plant-care");
+                dir.push("thumbnails");
+                dir
+            });
+
+        // Create directory if it doesn't exist
+        fs::create_dir_all(&cache_dir).ok();
+
+        Self {
+            storage_adapter,
+            cache_dir,
+        }
+    }
+
+    /// Get the PNG thumbnail for a stored image, generating it on first use
+    pub async fn get_thumbnail(&self, image_url: &str) -> Result<Vec<u8>> {
+        let cache_path = self.cache_path(image_url);
+        if let Ok(bytes) = fs::read(&cache_path) {
+            return Ok(bytes);
+        }
+
+        let original = self.storage_adapter.read_image(image_url).await?;
+        let thumbnail = make_thumbnail(&original)?;
+
+        if let Err(e) = fs::write(&cache_path, &thumbnail) {
+            log::warn!("Could not cache thumbnail {}: {}", cache_path.display(), e);
+        }
+
+        Ok(thumbnail)
+    }
+
+    fn cache_path(&self, image_url: &str) -> PathBuf {
+        // The image URL is unique per stored image, so a stable hash of it
+        // makes a good cache key
+        let key = uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_URL, image_url.as_bytes());
+        self.cache_dir.join(format!("{}.png", key))
+    }
+}
+
+/// Downscale an encoded image to a PNG thumbnail
+pub fn make_thumbnail(image_data: &[u8]) -> Result<Vec<u8>> {
+    let image = image::load_from_memory(image_data).context("Unsupported or corrupt image")?;
+    let thumbnail = image.resize(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Triangle);
+
+    let mut png = Vec::new();
+    thumbnail.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+
+    Ok(png)
+}
@@ -0,0 +1,75 @@
+/*!
+ * TRANSCRIPT SERVICE
+ *
+ * Keeps a Markdown transcript of each diagnosis session in the data
+ * directory (`sessions/<id>.md`), rewritten after every turn while the
+ * session runs so it can be followed live and reviewed or shared later
+ * without exporting anything.
+ */
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config;
+use crate::domain::diagnosis_transcript;
+use crate::domain::RequestError;
+use crate::repositories::{DiagnosisRepository, PlantRepository};
+
+pub struct TranscriptService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    sessions_dir: PathBuf,
+}
+
+impl TranscriptService {
+    pub fn new(plant_repo: PlantRepository, diagnosis_repo: DiagnosisRepository) -> Self {
+        let sessions_dir = std::env::var("SESSIONS_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                let mut dir = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+                dir.push("plant-care");
+                dir.push("sessions");
+                dir
+            });
+
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            sessions_dir,
+        }
+    }
+
+    /// Where the transcript of a session is kept
+    pub fn path(&self, diagnosis_id: &str) -> PathBuf {
+        self.sessions_dir.join(format!("{}.md", diagnosis_id))
+    }
+
+    /// Write the transcript of the session as it stands, replacing the
+    /// previous one in a single step so a viewer never sees half of it
+    pub async fn write(&self, diagnosis_id: &str, user_id: &str) -> Result<PathBuf> {
+        let session = self
+            .diagnosis_repo
+            .get_by_id(diagnosis_id)
+            .await?
+            .ok_or(RequestError::NotFound("Diagnosis session"))?;
+        let plant = self
+            .plant_repo
+            .get_by_id(&session.plant_id, user_id)
+            .await?
+            .ok_or(RequestError::Unauthorized("diagnosis"))?;
+        let messages = self.diagnosis_repo.get_messages(&session.id, 0, None).await?;
+
+        let markdown =
+            diagnosis_transcript::to_markdown(&session, plant.display_name(), &messages, config::local_zone());
+
+        fs::create_dir_all(&self.sessions_dir)
+            .with_context(|| format!("Failed to create {}", self.sessions_dir.display()))?;
+        let path = self.path(&session.id);
+        let partial = path.with_extension("md.partial");
+        fs::write(&partial, markdown).with_context(|| format!("Failed to write {}", partial.display()))?;
+        fs::rename(&partial, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+
+        Ok(path)
+    }
+}
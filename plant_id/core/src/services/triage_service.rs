@@ -0,0 +1,254 @@
+/*!
+ * TRIAGE SERVICE
+ *
+ * Finds every plant in the collection with open symptoms and asks the AI to
+ * rank them by urgency, so the sickest plant gets looked at first. The
+ * severity of a plant's latest diagnosis comes before the AI's urgency, so
+ * a critical finding always tops the list. A plant sitter cannot triage,
+ * as ranking calls the AI.
+ */
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+use crate::adapters::AiAdapter;
+use crate::config;
+use crate::domain::{DiagnosisState, Permission, Plant, Severity};
+use crate::repositories::{DiagnosisRepository, JournalRepository, PlantRepository, UserRepository};
+
+/// Journal problems older than this are no longer considered open
+const PROBLEM_WINDOW_DAYS: i64 = 30;
+
+/// A concluded diagnosis should be followed up after this many days
+pub(crate) const FOLLOW_UP_AFTER_DAYS: i64 = 7;
+
+/// Diagnoses older than this no longer need a follow-up
+pub(crate) const FOLLOW_UP_WINDOW_DAYS: i64 = 60;
+
+/// A plant with open symptoms, optionally ranked by the AI
+#[derive(Debug, Clone)]
+pub struct TriageResult {
+    pub plant: Plant,
+    pub symptoms: Vec<String>,
+    /// Severity of the latest diagnosis, if it is recent enough to follow up
+    pub severity: Option<Severity>,
+    pub urgency: Option<u8>,
+    pub reason: Option<String>,
+}
+
+pub struct TriageService {
+    plant_repo: PlantRepository,
+    diagnosis_repo: DiagnosisRepository,
+    journal_repo: JournalRepository,
+    user_repo: UserRepository,
+    ai_adapter: AiAdapter,
+}
+
+impl TriageService {
+    pub fn new(
+        plant_repo: PlantRepository,
+        diagnosis_repo: DiagnosisRepository,
+        journal_repo: JournalRepository,
+        user_repo: UserRepository,
+        ai_adapter: AiAdapter,
+    ) -> Self {
+        Self {
+            plant_repo,
+            diagnosis_repo,
+            journal_repo,
+            user_repo,
+            ai_adapter,
+        }
+    }
+
+    /// Collect plants with open symptoms and rank them, most severe then most
+    /// urgent first. If the AI ranking fails, plants of the same severity are
+    /// ordered by number of symptoms.
+    pub async fn triage(&self, user_id: &str) -> Result<Vec<TriageResult>> {
+        self.user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+
+        let mut results = self.collect_candidates(user_id).await?;
+        if results.is_empty() {
+            return Ok(results);
+        }
+
+        let candidates = json!(results
+            .iter()
+            .map(|r| json!({
+                "plant_id": r.plant.id,
+                "name": r.plant.name,
+                "symptoms": r.symptoms,
+                "severity": r.severity,
+            }))
+            .collect::<Vec<_>>());
+
+        match self.ai_adapter.rank_triage_candidates(&candidates).await {
+            Ok(rankings) => {
+                for ranking in rankings {
+                    if let Some(result) = results.iter_mut().find(|r| r.plant.id == ranking.plant_id) {
+                        result.urgency = Some(ranking.urgency.min(10));
+                        result.reason = Some(ranking.reason);
+                    }
+                }
+            }
+            Err(e) => log::warn!("AI triage ranking failed, falling back to symptom count: {:#}", e),
+        }
+
+        sort_by_priority(&mut results);
+
+        Ok(results)
+    }
+
+    /// Find plants with recent journal problems, pending diagnoses,
+    /// critical findings, or concluded diagnoses that are overdue for a
+    /// follow-up
+    async fn collect_candidates(&self, user_id: &str) -> Result<Vec<TriageResult>> {
+        let now = Utc::now();
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        let mut results = Vec::new();
+
+        for plant in plants {
+            let mut symptoms = Vec::new();
+
+            let journal = self.journal_repo.get_all_by_plant_id(&plant.id).await?;
+            for entry in &journal {
+                if entry.is_problem && now - entry.created_at <= Duration::days(PROBLEM_WINDOW_DAYS) {
+                    symptoms.push(format!(
+                        "Journal ({}): {}",
+                        config::local_zone().date(entry.created_at),
+                        entry.note
+                    ));
+                }
+            }
+
+            let sessions = self
+                .diagnosis_repo
+                .get_all_by_plant_id(&plant.id, user_id)
+                .await?;
+            for session in sessions.iter().filter(|s| !s.state.is_final()) {
+                symptoms.push(format!(
+                    "Unfinished diagnosis ({}): {}",
+                    config::local_zone().date(session.created_at),
+                    session.diagnosis_context["initial_prompt"]
+                        .as_str()
+                        .unwrap_or("unknown problem")
+                ));
+            }
+
+            // Sessions are newest first, so this is the latest conclusion
+            let mut severity = None;
+            if let Some(concluded) = sessions.iter().find(|s| s.state == DiagnosisState::Concluded) {
+                let result = &concluded.diagnosis_context["result"];
+                let finding = result["finding"].as_str().unwrap_or("unknown finding");
+                let age = now - concluded.updated_at;
+                let checked_in = journal.iter().any(|e| e.created_at > concluded.updated_at)
+                    || sessions.iter().any(|s| s.created_at > concluded.updated_at);
+
+                if age <= Duration::days(FOLLOW_UP_WINDOW_DAYS) {
+                    severity = result["severity"].as_str().and_then(Severity::from_str);
+                }
+
+                if age >= Duration::days(FOLLOW_UP_AFTER_DAYS)
+                    && age <= Duration::days(FOLLOW_UP_WINDOW_DAYS)
+                    && !checked_in
+                {
+                    symptoms.push(format!(
+                        "Follow-up overdue on '{}' (concluded {})",
+                        finding,
+                        config::local_zone().date(concluded.updated_at)
+                    ));
+                } else if age < Duration::days(FOLLOW_UP_AFTER_DAYS)
+                    && severity == Some(Severity::Critical)
+                    && !checked_in
+                {
+                    symptoms.push(format!(
+                        "Critical finding '{}' (concluded {})",
+                        finding,
+                        config::local_zone().date(concluded.updated_at)
+                    ));
+                }
+            }
+
+            if !symptoms.is_empty() {
+                results.push(TriageResult {
+                    plant,
+                    symptoms,
+                    severity,
+                    urgency: None,
+                    reason: None,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// Order triage results most severe first, then by the AI's urgency, then
+/// by number of symptoms. A plant without a diagnosed severity ranks with
+/// minor findings, so an unexplained symptom is not pushed below a finding
+/// that needs no treatment.
+fn sort_by_priority(results: &mut [TriageResult]) {
+    let severity = |r: &TriageResult| r.severity.unwrap_or(Severity::Minor);
+    results.sort_by(|a, b| {
+        severity(b)
+            .cmp(&severity(a))
+            .then_with(|| b.urgency.cmp(&a.urgency))
+            .then_with(|| b.symptoms.len().cmp(&a.symptoms.len()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use crate::domain::{CareSchedule, User, UserRole};
+
+    fn result(name: &str, severity: Option<Severity>, urgency: Option<u8>) -> TriageResult {
+        TriageResult {
+            plant: Plant::new("u".to_string(), name.to_string(), CareSchedule::default()),
+            symptoms: vec!["Yellow leaves".to_string()],
+            severity,
+            urgency,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_severity_comes_before_urgency() {
+        let mut results = vec![
+            result("Fern", Some(Severity::Info), Some(9)),
+            result("Pothos", None, Some(8)),
+            result("Monstera", Some(Severity::Critical), Some(2)),
+            result("Cactus", Some(Severity::Serious), None),
+        ];
+        sort_by_priority(&mut results);
+
+        let names: Vec<&str> = results.iter().map(|r| r.plant.name.as_str()).collect();
+        assert_eq!(names, vec!["Monstera", "Cactus", "Pothos", "Fern"]);
+    }
+
+    #[tokio::test]
+    async fn test_sitter_cannot_triage() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        // Only needed to build the adapter; the AI is never called
+        std::env::set_var("OPENROUTER_API_KEY", "test-key");
+        let users = UserRepository::new(db.clone());
+        let service = TriageService::new(
+            PlantRepository::new(db.clone()),
+            DiagnosisRepository::new(db.clone()),
+            JournalRepository::new(db),
+            users.clone(),
+            AiAdapter::new().unwrap(),
+        );
+
+        let mut sitter = User::owner("u".to_string());
+        sitter.role = UserRole::Sitter;
+        users.save(&sitter).await.unwrap();
+
+        let error = service.triage("u").await.unwrap_err();
+        assert!(error.to_string().contains("cannot start AI sessions"));
+    }
+}
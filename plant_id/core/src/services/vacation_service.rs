@@ -0,0 +1,99 @@
+/*!
+ * VACATION SERVICE
+ *
+ * Plans care of the whole collection while the owner is away: watering
+ * visits worked out from each care schedule, then a pre-departure
+ * checklist and sitter notes completed by the AI. Only the owner can
+ * plan, as the plan calls the AI.
+ */
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::json;
+
+use crate::adapters::AiAdapter;
+use crate::domain::{Permission, VacationPlan};
+use crate::repositories::{PlantRepository, UserRepository};
+
+pub struct VacationService {
+    plant_repo: PlantRepository,
+    user_repo: UserRepository,
+    ai_adapter: AiAdapter,
+}
+
+impl VacationService {
+    pub fn new(plant_repo: PlantRepository, user_repo: UserRepository, ai_adapter: AiAdapter) -> Self {
+        Self {
+            plant_repo,
+            user_repo,
+            ai_adapter,
+        }
+    }
+
+    /// Plan for a trip from `from` to `to`, both included. When the AI
+    /// cannot be reached the plan only has the schedule-based steps.
+    pub async fn plan(&self, user_id: &str, from: NaiveDate, to: NaiveDate) -> Result<VacationPlan> {
+        self.user_repo.role(user_id).await?.require(Permission::StartAiSessions)?;
+
+        let plants = self.plant_repo.get_all_by_user(user_id).await?;
+        if plants.is_empty() {
+            anyhow::bail!("No plants in your collection");
+        }
+
+        let mut plan = VacationPlan::new(from, to, &plants)?;
+
+        let trip = json!({
+            "trip_days": plan.trip_days(),
+            "plants": plan.plants.iter().zip(&plants).map(|(entry, plant)| json!({
+                "plant_id": entry.plant_id,
+                "name": entry.plant_name,
+                "room": entry.room,
+                "care_schedule": plant.care_schedule,
+                "sitter_watering_days": entry.watering_dates,
+            })).collect::<Vec<_>>()
+        });
+
+        match self.ai_adapter.advise_vacation(&trip).await {
+            Ok(advice) => {
+                plan.prep.extend(advice.prep_tips.into_iter().filter(|t| !t.trim().is_empty()));
+                for note in advice.plants {
+                    if let Some(entry) = plan.plants.iter_mut().find(|e| e.plant_id == note.plant_id) {
+                        entry.note = Some(note.note);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Could not get vacation advice: {:#}", e),
+        }
+
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Database;
+    use crate::domain::{CareSchedule, Plant, User, UserRole};
+
+    #[tokio::test]
+    async fn test_sitter_cannot_plan_a_vacation() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        // Only needed to build the adapter; the AI is never called
+        std::env::set_var("OPENROUTER_API_KEY", "test-key");
+        let plants = PlantRepository::new(db.clone());
+        let users = UserRepository::new(db);
+        let service = VacationService::new(plants.clone(), users.clone(), AiAdapter::new().unwrap());
+
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        plants.create(&fern).await.unwrap();
+        let mut sitter = User::owner("u".to_string());
+        sitter.role = UserRole::Sitter;
+        users.save(&sitter).await.unwrap();
+
+        let from = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 7, 14).unwrap();
+        let error = service.plan("u", from, to).await.unwrap_err();
+        assert!(error.to_string().contains("cannot start AI sessions"));
+    }
+}
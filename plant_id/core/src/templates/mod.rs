@@ -0,0 +1,49 @@
+/*!
+ * TEMPLATES
+ *
+ * Minimal HTML templating used by the static gallery generator.
+ * Templates are bundled at compile time and use `{{key}}` placeholders.
+ */
+
+/// Page layout for the published gallery
+pub const GALLERY_PAGE: &str = include_str!("gallery.html");
+
+/// A single plant card within the gallery
+pub const PLANT_CARD: &str = include_str!("plant_card.html");
+
+/// Value substituted into a template placeholder
+pub enum TemplateValue<'a> {
+    /// Plain text, HTML-escaped on insertion
+    Text(&'a str),
+    /// Pre-rendered HTML, inserted as-is
+    Html(&'a str),
+}
+
+/// Replace `{{key}}` placeholders in a template
+pub fn render(template: &str, values: &[(&str, TemplateValue)]) -> String {
+    let mut output = template.to_string();
+    for (key, value) in values {
+        let rendered = match value {
+            TemplateValue::Text(text) => escape_html(text),
+            TemplateValue::Html(html) => html.to_string(),
+        };
+        output = output.replace(&format!("{{{{{}}}}}", key), &rendered);
+    }
+    output
+}
+
+/// Escape text for safe inclusion in HTML content and attributes
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,343 @@
+/*!
+ * DISCORD BOT
+ *
+ * Serves a plant community server through slash commands. The database is
+ * shared, but every Discord user has their own collection: plants and
+ * diagnoses are stored under the user ID `discord:<discord user id>`.
+ *
+ * Slash commands are registered globally, or for one server when
+ * `DISCORD_GUILD_ID` is set (global registration can take a while to show up).
+ */
+
+use anyhow::{Context as _, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serenity::all::{
+    Attachment, Client, Command, CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, EditInteractionResponse, EventHandler, GatewayIntents, GuildId,
+    Interaction, Ready, ResolvedValue, UserId,
+};
+use serenity::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use plant_care_core::config::get_secret;
+use plant_care_core::{
+    Database, DiagnosisResponseDto, DiagnosisStartDto, DiagnosisUpdateDto, PlantRepository,
+};
+
+use super::{add_plant, diagnosis_service, render_conclusion};
+
+/// Discord rejects messages longer than this
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// A Discord user's open conversation with the bot
+#[derive(Debug, Clone)]
+enum UserState {
+    Diagnosing { plant_id: String, diagnosis_id: String },
+    /// A conclusion proposed care schedule changes that can be applied
+    CareUpdateProposed { diagnosis_id: String },
+}
+
+struct Handler {
+    db: Database,
+    guild_id: Option<GuildId>,
+    users: Mutex<HashMap<UserId, UserState>>,
+}
+
+/// A configured bot, ready to connect
+pub struct DiscordBot {
+    token: String,
+    handler: Handler,
+}
+
+impl DiscordBot {
+    /// Configure the bot from `DISCORD_BOT_TOKEN` and optionally `DISCORD_GUILD_ID`
+    pub fn from_env(db: Database) -> Result<Self> {
+        let token = get_secret("DISCORD_BOT_TOKEN")?;
+        let guild_id = match std::env::var("DISCORD_GUILD_ID") {
+            Ok(id) => Some(GuildId::new(
+                id.trim().parse().context("DISCORD_GUILD_ID must be a number")?,
+            )),
+            Err(_) => None,
+        };
+
+        Ok(Self {
+            token,
+            handler: Handler {
+                db,
+                guild_id,
+                users: Mutex::new(HashMap::new()),
+            },
+        })
+    }
+
+    /// Connect to Discord and serve commands until interrupted
+    pub async fn run(self) -> Result<()> {
+        // Slash commands need no privileged intents
+        let mut client = Client::builder(&self.token, GatewayIntents::empty())
+            .event_handler(self.handler)
+            .await
+            .context("Failed to create Discord client")?;
+
+        client.start().await.context("Discord connection failed")
+    }
+}
+
+/// User ID a Discord user's plants are stored under
+fn user_id(user: UserId) -> String {
+    format!("discord:{}", user)
+}
+
+fn commands() -> Vec<CreateCommand> {
+    vec![
+        CreateCommand::new("identify")
+            .description("Identify a plant from a photo and add it to your collection")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Attachment, "photo", "Photo of the plant")
+                    .required(true),
+            ),
+        CreateCommand::new("diagnose")
+            .description("Start diagnosing a problem with one of your plants")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "plant", "Plant name or ID")
+                    .required(true),
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "problem", "What's wrong")
+                    .required(true),
+            )
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::Attachment,
+                "photo",
+                "Photo of the problem",
+            )),
+        CreateCommand::new("answer")
+            .description("Answer the current diagnosis question")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "text", "Your answer")
+                    .required(true),
+            )
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::Attachment,
+                "photo",
+                "Photo, if one was requested",
+            )),
+        CreateCommand::new("apply")
+            .description("Apply the care schedule changes from your last diagnosis"),
+        CreateCommand::new("plants").description("List your plants"),
+    ]
+}
+
+/// Cut a reply down to Discord's message limit
+fn truncate(reply: String) -> String {
+    if reply.chars().count() <= MAX_MESSAGE_CHARS {
+        return reply;
+    }
+    let mut truncated: String = reply.chars().take(MAX_MESSAGE_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        let registered = match self.guild_id {
+            Some(guild_id) => guild_id.set_commands(&ctx.http, commands()).await,
+            None => Command::set_global_commands(&ctx.http, commands()).await,
+        };
+
+        match registered {
+            Ok(_) => log::info!("Discord bot connected as {}", ready.user.name),
+            Err(e) => log::error!("Failed to register Discord slash commands: {}", e),
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Command(command) = interaction else {
+            return;
+        };
+
+        // AI calls take longer than the 3 seconds Discord waits for a reply
+        if let Err(e) = command.defer(&ctx.http).await {
+            log::warn!("Failed to acknowledge Discord command: {}", e);
+            return;
+        }
+
+        let reply = match self.dispatch(&command).await {
+            Ok(reply) => reply,
+            Err(e) => {
+                log::warn!("Discord command /{} failed: {:#}", command.data.name, e);
+                format!("⚠️ {:#}", e)
+            }
+        };
+
+        let response = EditInteractionResponse::new().content(truncate(reply));
+        if let Err(e) = command.edit_response(&ctx.http, response).await {
+            log::warn!("Failed to send Discord reply: {}", e);
+        }
+    }
+}
+
+impl Handler {
+    fn get(&self, user: UserId) -> Option<UserState> {
+        self.users.lock().unwrap().get(&user).cloned()
+    }
+
+    fn set(&self, user: UserId, state: Option<UserState>) {
+        let mut users = self.users.lock().unwrap();
+        match state {
+            Some(state) => users.insert(user, state),
+            None => users.remove(&user),
+        };
+    }
+
+    async fn dispatch(&self, command: &CommandInteraction) -> Result<String> {
+        let user_id = user_id(command.user.id);
+        let mut text = None;
+        let mut photo: Option<&Attachment> = None;
+        let mut plant = None;
+        for option in command.data.options() {
+            match (option.name, option.value) {
+                ("photo", ResolvedValue::Attachment(attachment)) => photo = Some(attachment),
+                ("plant", ResolvedValue::String(value)) => plant = Some(value),
+                ("problem" | "text", ResolvedValue::String(value)) => text = Some(value),
+                _ => {}
+            }
+        }
+
+        match command.data.name.as_str() {
+            "identify" => {
+                let photo = photo.context("Attach a photo of the plant")?;
+                add_plant(&self.db, &user_id, photo.download().await?).await
+            }
+            "diagnose" => {
+                let plant = PlantRepository::new(self.db.clone())
+                    .find(plant.context("Missing plant")?, &user_id)
+                    .await?;
+                let dto = DiagnosisStartDto {
+                    prompt: text.context("Missing problem")?.to_string(),
+                    photo: match photo {
+                        Some(attachment) => Some(BASE64.encode(attachment.download().await?)),
+                        None => None,
+                    },
+                };
+                let response = diagnosis_service(&self.db)?
+                    .start_diagnosis(&plant.id, dto, user_id)
+                    .await?;
+                Ok(self.handle_response(command.user.id, plant.id, response).await)
+            }
+            "answer" => {
+                let Some(UserState::Diagnosing {
+                    plant_id,
+                    diagnosis_id,
+                }) = self.get(command.user.id)
+                else {
+                    return Ok("You have no diagnosis in progress. Start one with /diagnose.".to_string());
+                };
+
+                let photo = match photo {
+                    Some(attachment) => Some(BASE64.encode(attachment.download().await?)),
+                    None => None,
+                };
+                let dto = DiagnosisUpdateDto {
+                    message: text.context("Missing answer")?.to_string(),
+                    photo,
+                };
+                let response = diagnosis_service(&self.db)?
+                    .update_diagnosis(&diagnosis_id, dto, user_id)
+                    .await?;
+                Ok(self.handle_response(command.user.id, plant_id, response).await)
+            }
+            "apply" => match self.get(command.user.id) {
+                Some(UserState::CareUpdateProposed { diagnosis_id }) => {
+                    let plant = diagnosis_service(&self.db)?
+                        .apply_care_update(&diagnosis_id, &user_id)
+                        .await?;
+                    self.set(command.user.id, None);
+                    Ok(format!("✅ Care schedule for {} updated", plant.display_name()))
+                }
+                _ => Ok("There are no care schedule changes to apply.".to_string()),
+            },
+            "plants" => {
+                let plants = PlantRepository::new(self.db.clone())
+                    .get_all_by_user(&user_id)
+                    .await?;
+                if plants.is_empty() {
+                    return Ok("No plants yet. Add one with /identify!".to_string());
+                }
+                Ok(plants
+                    .iter()
+                    .map(|p| format!("🌿 {} (`{}`)", p.display_name(), p.id))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+            other => anyhow::bail!("Unknown command /{}", other),
+        }
+    }
+
+    /// Update the user's state from a diagnosis response and render the reply
+    async fn handle_response(
+        &self,
+        user: UserId,
+        plant_id: String,
+        response: DiagnosisResponseDto,
+    ) -> String {
+        match response {
+            DiagnosisResponseDto::Ask(ask) => {
+                self.set(
+                    user,
+                    Some(UserState::Diagnosing {
+                        plant_id,
+                        diagnosis_id: ask.diagnosis_id,
+                    }),
+                );
+                format!("{}\n\nReply with /answer.", ask.question)
+            }
+            DiagnosisResponseDto::RequestPhoto(request) => {
+                let mut reply = format!("📷 Please send a photo of the {}.", request.part);
+                if let Some(reason) = &request.reason {
+                    reply.push_str(&format!("\n{}", reason));
+                }
+                reply.push_str("\nReply with /answer and attach the photo.");
+                self.set(
+                    user,
+                    Some(UserState::Diagnosing {
+                        plant_id,
+                        diagnosis_id: request.diagnosis_id,
+                    }),
+                );
+                reply
+            }
+            DiagnosisResponseDto::Conclude(conclusion) => {
+                let plant = PlantRepository::new(self.db.clone())
+                    .get_by_id(&plant_id, &user_id(user))
+                    .await
+                    .ok()
+                    .flatten();
+                let reply = render_conclusion(&conclusion, plant.as_ref().map(|p| &p.care_schedule));
+
+                let next = conclusion
+                    .care_update
+                    .as_ref()
+                    .map(|_| UserState::CareUpdateProposed {
+                        diagnosis_id: conclusion.diagnosis_id.clone(),
+                    });
+                self.set(user, next);
+                reply
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_long_reply() {
+        let reply = truncate("x".repeat(MAX_MESSAGE_CHARS + 10));
+        assert_eq!(reply.chars().count(), MAX_MESSAGE_CHARS);
+        assert!(reply.ends_with('…'));
+        assert_eq!(truncate("short".to_string()), "short");
+    }
+}
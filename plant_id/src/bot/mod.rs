@@ -0,0 +1,136 @@
+/*!
+ * BOT FRONT-ENDS
+ *
+ * Chat bots are primary adapters like the CLI: they translate chat
+ * messages into calls to the plant_care_core services. Replies are plain
+ * text shared by every platform.
+ */
+
+pub mod discord;
+pub mod telegram;
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+use plant_care_core::adapters::{
+    identifier_adapter, notification_adapter, plant_id_adapter, search_adapter, species_data_adapter, storage_adapter, SilentProgress,
+};
+use plant_care_core::{
+    AiAdapter, AttributeRepository, CareSchedule, Database, DiagnosisConcludeResponse, DiagnosisRepository,
+    DiagnosisService, EventBus, MoistureRepository, OutcomeRepository, PlantCreationDto,
+    PlantIdAdapter, PlantRepository, PlantService, RoomRepository, UserRepository,
+};
+use plant_care_core::repositories::{IdentificationRepository, JobRepository};
+use plant_care_core::services::{KeepPlantId, PlantCreation};
+
+fn diagnosis_service(db: &Database) -> Result<DiagnosisService> {
+    Ok(DiagnosisService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        OutcomeRepository::new(db.clone()),
+        MoistureRepository::new(db.clone()),
+        AttributeRepository::new(db.clone()),
+        RoomRepository::new(db.clone()),
+        UserRepository::new(db.clone()),
+        AiAdapter::new()?,
+        storage_adapter::from_env()?,
+        search_adapter::from_env()?,
+        EventBus::new(notification_adapter::from_env()?).with_queue(JobRepository::new(db.clone())),
+    )
+    .with_health_assessment(plant_id_adapter::health_assessor()))
+}
+
+/// Render a conclusion, with the proposed care schedule changes when the
+/// current schedule is known
+fn render_conclusion(conclusion: &DiagnosisConcludeResponse, schedule: Option<&CareSchedule>) -> String {
+    let mut reply = format!("🎯 {}\n\n{}", conclusion.finding, conclusion.recommendation);
+
+    if let Some(severity) = conclusion.severity {
+        reply.push_str(&format!("\n\nSeverity: {}", severity.as_str()));
+    }
+
+    if let Some(confidence) = conclusion.confidence {
+        reply.push_str(&format!("\n\nConfidence: {:.0}%", confidence * 100.0));
+    }
+    for alternative in &conclusion.alternatives {
+        reply.push_str(&format!(
+            "\n  {:.0}% {}",
+            alternative.probability * 100.0,
+            alternative.finding
+        ));
+    }
+
+    if let (Some(care_update), Some(schedule)) = (&conclusion.care_update, schedule) {
+        reply.push_str("\n\n📋 Suggested care schedule changes:");
+        for (label, current, proposed) in care_update.changes.changes(schedule) {
+            reply.push_str(&format!("\n{}: {} → {}", label, current, proposed));
+        }
+        reply.push_str("\nSend /apply to update the schedule.");
+    }
+
+    reply
+}
+
+/// Identify the plant in a photo and add it to the user's collection
+async fn add_plant(db: &Database, user_id: &str, image: Vec<u8>) -> Result<String> {
+    let plant_service = PlantService::new(
+        PlantRepository::new(db.clone()),
+        IdentificationRepository::new(db.clone()),
+        JobRepository::new(db.clone()),
+        PlantIdAdapter::new()?,
+        identifier_adapter::from_env()?,
+        AiAdapter::new()?,
+        species_data_adapter::from_env()?,
+        storage_adapter::from_env()?,
+        EventBus::new(notification_adapter::from_env()?).with_queue(JobRepository::new(db.clone())),
+    );
+
+    let dto = PlantCreationDto {
+        images: vec![BASE64.encode(image)],
+        latitude: None,
+        longitude: None,
+        care_schedule: None,
+        template: None,
+        force_new: false,
+        nickname: None,
+        acquired_at: None,
+        acquired_from: None,
+    };
+    // There is no way to ask in a chat message; Plant.id's answer is kept
+    let (plant, warnings, disagreement) = match plant_service
+        .create_plant(dto, user_id.to_string(), &SilentProgress, &KeepPlantId)
+        .await?
+    {
+        // A queued schedule job is mentioned among the warnings
+        PlantCreation::Created {
+            plant,
+            warnings,
+            disagreement,
+            queued: _,
+        } => (plant, warnings, disagreement),
+        PlantCreation::Existing(plant) => {
+            return Ok(format!("🌱 {} is already in your collection", plant.display_name()))
+        }
+    };
+
+    let mut reply = format!(
+        "🌱 Added {}\n\n☀️ {}\n💧 {}\n💨 {}\n🌡 {}",
+        plant.name,
+        plant.care_schedule.light,
+        plant.care_schedule.water,
+        plant.care_schedule.humidity,
+        plant.care_schedule.temperature
+    );
+    for warning in warnings {
+        reply.push_str(&format!("\n⚠️ {}", warning));
+    }
+    if let Some(disagreement) = disagreement {
+        let candidates: Vec<String> = disagreement.candidates.iter().map(|c| c.describe()).collect();
+        reply.push_str(&format!(
+            "\n🔍 The identifiers disagreed: {}. Correct it with `plant-care disagreements correct` if needed.",
+            candidates.join(" vs ")
+        ));
+    }
+
+    Ok(reply)
+}
@@ -0,0 +1,355 @@
+/*!
+ * TELEGRAM BOT
+ *
+ * Runs the plant care assistant as a Telegram bot. Sending a photo adds
+ * the plant to the collection; `/diagnose <plant>` starts a diagnosis
+ * conversation that continues over chat, including photo requests.
+ *
+ * The bot acts on the local collection, so only chats listed in
+ * `TELEGRAM_ALLOWED_CHATS` are served.
+ */
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use teloxide::net::Download;
+use teloxide::prelude::*;
+use teloxide::types::ChatAction;
+
+use plant_care_core::config::{self, get_secret};
+use plant_care_core::{
+    Database, DiagnosisResponseDto, DiagnosisStartDto, DiagnosisUpdateDto, PlantRepository,
+};
+
+use super::{add_plant, diagnosis_service, render_conclusion};
+
+const USER_ID: &str = "local-user";
+
+const HELP: &str = "🌿 Plant Care Bot
+
+📷 Send a photo of a plant to identify it and add it to your collection.
+
+/list - show your plants
+/diagnose <plant> - start a diagnosis (plant name or ID)
+/apply - apply the care schedule changes from the last diagnosis
+/cancel - stop the current conversation";
+
+/// Where a chat is in its conversation with the bot
+#[derive(Debug, Clone)]
+enum ChatState {
+    AwaitingProblem { plant_id: String },
+    Diagnosing { plant_id: String, diagnosis_id: String },
+    AwaitingPhoto { plant_id: String, diagnosis_id: String, part: String },
+    /// A conclusion proposed care schedule changes that can be applied
+    CareUpdateProposed { diagnosis_id: String },
+}
+
+struct BotState {
+    db: Database,
+    allowed_chats: Vec<ChatId>,
+    chats: Mutex<HashMap<ChatId, ChatState>>,
+}
+
+impl BotState {
+    fn get(&self, chat: ChatId) -> Option<ChatState> {
+        self.chats.lock().unwrap().get(&chat).cloned()
+    }
+
+    fn set(&self, chat: ChatId, state: Option<ChatState>) {
+        let mut chats = self.chats.lock().unwrap();
+        match state {
+            Some(state) => chats.insert(chat, state),
+            None => chats.remove(&chat),
+        };
+    }
+}
+
+/// A configured bot, ready to serve chats
+pub struct TelegramBot {
+    bot: Bot,
+    state: Arc<BotState>,
+}
+
+impl TelegramBot {
+    /// Configure the bot from `TELEGRAM_BOT_TOKEN` and `TELEGRAM_ALLOWED_CHATS`
+    pub fn from_env(db: Database) -> Result<Self> {
+        let token = get_secret("TELEGRAM_BOT_TOKEN")?;
+        let allowed_chats =
+            parse_allowed_chats(&std::env::var("TELEGRAM_ALLOWED_CHATS").unwrap_or_default())?;
+        if allowed_chats.is_empty() {
+            log::warn!("TELEGRAM_ALLOWED_CHATS is empty; every chat will be refused");
+        }
+
+        Ok(Self {
+            bot: Bot::new(token),
+            state: Arc::new(BotState {
+                db,
+                allowed_chats,
+                chats: Mutex::new(HashMap::new()),
+            }),
+        })
+    }
+
+    /// Serve chats until interrupted
+    pub async fn run(self) {
+        let state = self.state;
+        teloxide::repl(self.bot, move |bot: Bot, msg: Message| {
+            let state = state.clone();
+            async move { handle_message(bot, msg, state).await }
+        })
+        .await;
+    }
+}
+
+/// Parse a comma-separated list of chat IDs
+fn parse_allowed_chats(value: &str) -> Result<Vec<ChatId>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse()
+                .map(ChatId)
+                .with_context(|| format!("Invalid chat ID '{}' in TELEGRAM_ALLOWED_CHATS", id))
+        })
+        .collect()
+}
+
+/// Split "/command@botname args" into the command and its arguments
+fn parse_command(text: &str) -> Option<(&str, &str)> {
+    let text = text.strip_prefix('/')?;
+    let (command, args) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let command = command.split('@').next().unwrap_or(command);
+    Some((command, args.trim()))
+}
+
+async fn handle_message(bot: Bot, msg: Message, state: Arc<BotState>) -> ResponseResult<()> {
+    let chat = msg.chat.id;
+
+    if !state.allowed_chats.contains(&chat) {
+        bot.send_message(
+            chat,
+            format!(
+                "This bot is private. To allow this chat, add {} to TELEGRAM_ALLOWED_CHATS.",
+                chat
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_chat_action(chat, ChatAction::Typing).await?;
+
+    let reply = match dispatch(&bot, &msg, &state).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            log::warn!("Telegram message from chat {} failed: {:#}", chat, e);
+            format!("⚠️ {:#}", e)
+        }
+    };
+
+    bot.send_message(chat, reply).await?;
+    Ok(())
+}
+
+async fn dispatch(bot: &Bot, msg: &Message, state: &BotState) -> Result<String> {
+    let chat = msg.chat.id;
+
+    if let Some(photos) = msg.photo() {
+        // Telegram sends several sizes; the last one is the largest
+        let photo = photos.last().context("Message has no photo")?;
+        let file = bot.get_file(photo.file.id.clone()).await?;
+        let mut data = Vec::new();
+        bot.download_file(&file.path, &mut data).await?;
+
+        return match state.get(chat) {
+            Some(ChatState::AwaitingPhoto {
+                plant_id,
+                diagnosis_id,
+                part,
+            }) => {
+                let dto = DiagnosisUpdateDto {
+                    message: format!("Here is a photo of the {}.", part),
+                    photo: Some(BASE64.encode(&data)),
+                };
+                continue_diagnosis(state, chat, plant_id, &diagnosis_id, dto).await
+            }
+            _ => add_plant(&state.db, USER_ID, data).await,
+        };
+    }
+
+    let text = msg.text().unwrap_or_default().trim();
+
+    if let Some((command, args)) = parse_command(text) {
+        return match command {
+            "list" => list_plants(state).await,
+            "diagnose" => {
+                if args.is_empty() {
+                    return Ok("Usage: /diagnose <plant name or ID>".to_string());
+                }
+                let plant = PlantRepository::new(state.db.clone())
+                    .find(args, USER_ID)
+                    .await?;
+                state.set(chat, Some(ChatState::AwaitingProblem { plant_id: plant.id.clone() }));
+                Ok(format!("What's wrong with your {}?", plant.display_name()))
+            }
+            "apply" => match state.get(chat) {
+                Some(ChatState::CareUpdateProposed { diagnosis_id }) => {
+                    let plant = diagnosis_service(&state.db)?
+                        .apply_care_update(&diagnosis_id, USER_ID)
+                        .await?;
+                    state.set(chat, None);
+                    Ok(format!("✅ Care schedule for {} updated", plant.display_name()))
+                }
+                _ => Ok("There are no care schedule changes to apply.".to_string()),
+            },
+            "cancel" => {
+                state.set(chat, None);
+                Ok("Cancelled.".to_string())
+            }
+            _ => Ok(HELP.to_string()),
+        };
+    }
+
+    match state.get(chat) {
+        Some(ChatState::AwaitingProblem { plant_id }) => {
+            let dto = DiagnosisStartDto {
+                prompt: text.to_string(),
+                photo: None,
+            };
+            let response = diagnosis_service(&state.db)?
+                .start_diagnosis(&plant_id, dto, USER_ID.to_string())
+                .await?;
+            Ok(handle_response(state, chat, plant_id, response).await)
+        }
+        Some(ChatState::Diagnosing {
+            plant_id,
+            diagnosis_id,
+        }) => {
+            let dto = DiagnosisUpdateDto {
+                message: text.to_string(),
+                photo: None,
+            };
+            continue_diagnosis(state, chat, plant_id, &diagnosis_id, dto).await
+        }
+        // A text reply to a photo request means the photo is skipped
+        Some(ChatState::AwaitingPhoto {
+            plant_id,
+            diagnosis_id,
+            part,
+        }) => {
+            let dto = DiagnosisUpdateDto {
+                message: format!("I can't take a photo of the {} right now. {}", part, text),
+                photo: None,
+            };
+            continue_diagnosis(state, chat, plant_id, &diagnosis_id, dto).await
+        }
+        Some(ChatState::CareUpdateProposed { .. }) | None => Ok(HELP.to_string()),
+    }
+}
+
+async fn continue_diagnosis(
+    state: &BotState,
+    chat: ChatId,
+    plant_id: String,
+    diagnosis_id: &str,
+    dto: DiagnosisUpdateDto,
+) -> Result<String> {
+    let response = diagnosis_service(&state.db)?
+        .update_diagnosis(diagnosis_id, dto, USER_ID.to_string())
+        .await?;
+    Ok(handle_response(state, chat, plant_id, response).await)
+}
+
+/// Update the chat state from a diagnosis response and render the reply
+async fn handle_response(
+    state: &BotState,
+    chat: ChatId,
+    plant_id: String,
+    response: DiagnosisResponseDto,
+) -> String {
+    match response {
+        DiagnosisResponseDto::Ask(ask) => {
+            state.set(
+                chat,
+                Some(ChatState::Diagnosing {
+                    plant_id,
+                    diagnosis_id: ask.diagnosis_id,
+                }),
+            );
+            ask.question
+        }
+        DiagnosisResponseDto::RequestPhoto(request) => {
+            let mut reply = format!("📷 Please send a photo of the {}.", request.part);
+            if let Some(reason) = &request.reason {
+                reply.push_str(&format!("\n{}", reason));
+            }
+            reply.push_str("\n(Reply with text to skip.)");
+            state.set(
+                chat,
+                Some(ChatState::AwaitingPhoto {
+                    plant_id,
+                    diagnosis_id: request.diagnosis_id,
+                    part: request.part,
+                }),
+            );
+            reply
+        }
+        DiagnosisResponseDto::Conclude(conclusion) => {
+            let plant = PlantRepository::new(state.db.clone())
+                .get_by_id(&plant_id, USER_ID)
+                .await
+                .ok()
+                .flatten();
+            let reply = render_conclusion(&conclusion, plant.as_ref().map(|p| &p.care_schedule));
+
+            let next = conclusion
+                .care_update
+                .as_ref()
+                .map(|_| ChatState::CareUpdateProposed {
+                    diagnosis_id: conclusion.diagnosis_id.clone(),
+                });
+            state.set(chat, next);
+            reply
+        }
+    }
+}
+
+async fn list_plants(state: &BotState) -> Result<String> {
+    let plants = PlantRepository::new(state.db.clone())
+        .get_all_by_user(USER_ID)
+        .await?;
+
+    if plants.is_empty() {
+        return Ok("No plants yet. Send a photo to add one!".to_string());
+    }
+
+    let lines: Vec<String> = plants
+        .iter()
+        .map(|p| format!("🌿 {} ({})", p.display_name(), config::local_zone().date(p.created_at)))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("/diagnose Monstera"), Some(("diagnose", "Monstera")));
+        assert_eq!(parse_command("/list@PlantCareBot"), Some(("list", "")));
+        assert_eq!(parse_command("yellow leaves"), None);
+    }
+
+    #[test]
+    fn test_parse_allowed_chats() {
+        assert_eq!(
+            parse_allowed_chats("123, -456").unwrap(),
+            vec![ChatId(123), ChatId(-456)]
+        );
+        assert!(parse_allowed_chats("").unwrap().is_empty());
+        assert!(parse_allowed_chats("abc").is_err());
+    }
+}
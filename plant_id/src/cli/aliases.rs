@@ -0,0 +1,570 @@
+/*
+ * COMMAND ALIASES
+ *
+ * Shortcuts of the owner's own, defined in the `[aliases]` table of the
+ * configuration file (see `config::config_file`):
+ *
+ *   [aliases]
+ *   w = "today --done"
+ *   fern = "show \"Boston fern\""
+ *
+ * The same table may be written inline (`aliases = { w = "today --done" }`)
+ * or with dotted keys (`aliases.w = "today --done"`), and an expansion may
+ * be a multi-line string (three double or single quotes).
+ *
+ * An alias is expanded in place of the command word before clap parses the
+ * arguments, so `plant-care w 2` runs `plant-care today --done 2`. It may
+ * use another alias, but never shadows a built-in command or its short
+ * aliases (`ls`, `rm`, `dx`). Only the aliases are read; other tables are
+ * skipped over and left for other settings. A config file that cannot be
+ * read is reported and ignored, so it never stops a command.
+ *
+ * The file is read by the small TOML reader below rather than the `toml`
+ * crate, which the build does not depend on.
+ */
+
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::Path;
+
+use plant_care_core::config;
+
+use super::Cli;
+
+/// Most aliases expanded in one command line, to stop loops
+const MAX_EXPANSIONS: usize = 10;
+
+/// The command line with any alias in command position expanded
+pub fn expand_aliases(args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let path = config::config_file();
+    let parsed = match std::fs::read_to_string(&path) {
+        Ok(contents) => parse_aliases(&contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(args),
+        Err(e) => Err(e.into()),
+    };
+    let aliases = match parsed {
+        Ok(aliases) => aliases,
+        Err(e) => {
+            log::warn!("Aliases in config file {} are ignored: {:#}", path.display(), e);
+            return Ok(args);
+        }
+    };
+
+    expand(args, &aliases, &path)
+}
+
+fn expand(mut args: Vec<OsString>, aliases: &HashMap<String, String>, path: &Path) -> Result<Vec<OsString>> {
+    if aliases.is_empty() {
+        return Ok(args);
+    }
+    let cli = Cli::command();
+
+    let mut expanded = Vec::new();
+    while let Some(index) = command_position(&cli, &args) {
+        let Some(word) = args[index].to_str().map(str::to_string) else {
+            break;
+        };
+        if word == "help" || cli.find_subcommand(&word).is_some() {
+            if aliases.contains_key(&word) {
+                log::warn!("The alias '{}' in {} is ignored: it names a command", word, path.display());
+            }
+            break;
+        }
+        let Some(expansion) = aliases.get(&word) else {
+            break;
+        };
+        if expanded.contains(&word) || expanded.len() == MAX_EXPANSIONS {
+            anyhow::bail!("The alias '{}' in {} expands to itself", word, path.display());
+        }
+
+        let words = split_words(expansion).with_context(|| format!("Invalid alias '{}' in {}", word, path.display()))?;
+        args.splice(index..=index, words.into_iter().map(OsString::from));
+        expanded.push(word);
+    }
+
+    Ok(args)
+}
+
+/// Index of the command word: the first argument that is neither an option
+/// nor an option's value
+fn command_position(cli: &clap::Command, args: &[OsString]) -> Option<usize> {
+    let mut index = 1;
+    while index < args.len() {
+        let arg = args[index].to_str()?;
+        if arg == "--" {
+            return None;
+        }
+
+        let takes_value = if let Some(long) = arg.strip_prefix("--") {
+            !long.contains('=')
+                && cli
+                    .get_arguments()
+                    .find(|a| a.get_long() == Some(long))
+                    .is_some_and(|a| a.get_action().takes_values())
+        } else if let Some(shorts) = arg.strip_prefix('-').filter(|s| !s.is_empty()) {
+            // Only the last of bundled flags (-qv) can take the next argument
+            shorts.chars().count() == 1
+                && cli
+                    .get_arguments()
+                    .find(|a| a.get_short() == shorts.chars().next())
+                    .is_some_and(|a| a.get_action().takes_values())
+        } else {
+            return Some(index);
+        };
+
+        index += if takes_value { 2 } else { 1 };
+    }
+    None
+}
+
+/// Which table the entries being read belong to
+#[derive(Clone, Copy, PartialEq)]
+enum Table {
+    /// Before any table header
+    Root,
+    Aliases,
+    Other,
+}
+
+/// The aliases of a config file: the `name = "expansion"` entries of the
+/// `[aliases]` table, of an inline `aliases = { ... }` table, or under
+/// dotted `aliases.name` keys
+fn parse_aliases(contents: &str) -> Result<HashMap<String, String>> {
+    let mut aliases = HashMap::new();
+    let mut table = Table::Root;
+    let mut reader = Reader::new(contents);
+
+    loop {
+        reader.skip_blank();
+        if reader.at_end() {
+            break;
+        }
+        let number = reader.line_number();
+        let line = reader.line();
+        if line.starts_with('[') {
+            table = match table_name(line) {
+                Some("aliases") => Table::Aliases,
+                _ => Table::Other,
+            };
+            reader.skip_line();
+            continue;
+        }
+
+        let invalid = || format!("line {}: expected name = \"command\" in [aliases], got '{}'", number, line.trim());
+        let key = reader.key().filter(|_| reader.eat('='));
+        let is_alias = match (table, &key) {
+            (Table::Aliases, _) => true,
+            (Table::Root, Some(key)) => key[0] == "aliases",
+            _ => false,
+        };
+        if !is_alias {
+            // A setting of no concern here
+            if key.is_none() || reader.skip_value().is_none() {
+                reader.skip_line();
+            }
+            continue;
+        }
+
+        let entries = match (table, key.as_deref()) {
+            (Table::Aliases, Some([name])) | (Table::Root, Some([_, name])) => vec![(name.clone(), reader.string())],
+            (Table::Root, Some([_])) => reader.inline_table().with_context(invalid)?,
+            _ => anyhow::bail!(invalid()),
+        };
+        for (name, value) in entries {
+            let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+            let value = value.filter(|_| valid).with_context(invalid)?;
+            aliases.insert(name, value);
+        }
+        if !reader.end_of_line() {
+            anyhow::bail!(invalid());
+        }
+    }
+
+    Ok(aliases)
+}
+
+/// Name of the table a `[name]` header starts, allowing spaces inside the
+/// brackets, a quoted name and a trailing comment. Arrays of tables
+/// (`[[name]]`) have none.
+fn table_name(line: &str) -> Option<&str> {
+    let header = line.split('#').next()?.trim();
+    let name = header.strip_prefix('[')?.strip_suffix(']')?.trim();
+    if name.starts_with('[') {
+        return None;
+    }
+    Some(name.trim_matches('"'))
+}
+
+/// Just enough of a TOML reader for the aliases: keys, strings and inline
+/// tables, and skipping over any other value
+struct Reader<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn at_end(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    fn line_number(&self) -> usize {
+        self.text[..self.position].matches('\n').count() + 1
+    }
+
+    /// The rest of the current line
+    fn line(&self) -> &'a str {
+        self.rest().lines().next().unwrap_or_default()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Skip spaces, then `c` if it is next
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_spaces();
+        if self.peek() == Some(c) {
+            self.position += c.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.position += s.len();
+            return true;
+        }
+        false
+    }
+
+    /// Skip spaces and tabs
+    fn skip_spaces(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    }
+
+    /// Skip whitespace, line breaks and comments
+    fn skip_blank(&mut self) {
+        loop {
+            let rest = self.rest();
+            self.position += rest.len() - rest.trim_start().len();
+            if self.peek() != Some('#') {
+                return;
+            }
+            self.skip_line();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        let rest = self.rest();
+        self.position += rest.find('\n').map_or(rest.len(), |i| i + 1);
+    }
+
+    /// Whether nothing but a comment is left on the line
+    fn end_of_line(&mut self) -> bool {
+        self.skip_spaces();
+        let rest = self.rest();
+        rest.is_empty() || rest.starts_with(['\n', '#']) || rest.starts_with("\r\n")
+    }
+
+    /// A key, split at its dots (`aliases.w`, `"aliases"."w"`)
+    fn key(&mut self) -> Option<Vec<String>> {
+        let mut parts = Vec::new();
+        loop {
+            self.skip_spaces();
+            let part = match self.peek()? {
+                '"' | '\'' => self.string()?,
+                _ => {
+                    let rest = self.rest();
+                    let bare = rest.trim_start_matches(|c: char| c.is_alphanumeric() || c == '-' || c == '_');
+                    let part = &rest[..rest.len() - bare.len()];
+                    if part.is_empty() {
+                        return None;
+                    }
+                    self.position += part.len();
+                    part.to_string()
+                }
+            };
+            parts.push(part);
+            if !self.eat('.') {
+                return Some(parts);
+            }
+        }
+    }
+
+    /// A basic (`"`, with escapes) or literal (`'`) string, either of them
+    /// multi-line when it starts with three quotes
+    fn string(&mut self) -> Option<String> {
+        self.skip_spaces();
+        let quote = self.peek().filter(|q| *q == '"' || *q == '\'')?;
+        let triple = if quote == '"' { "\"\"\"" } else { "'''" };
+        let multi_line = self.eat_str(triple);
+        if multi_line {
+            // A line break right after the opening quotes is not part of it
+            if !self.eat_str("\n") {
+                self.eat_str("\r\n");
+            }
+        } else {
+            self.position += 1;
+        }
+
+        let mut value = String::new();
+        loop {
+            if multi_line && self.eat_str(triple) {
+                // Up to two quotes may end the text right before the closing ones
+                while self.peek() == Some(quote) {
+                    value.push(quote);
+                    self.position += 1;
+                }
+                return Some(value);
+            }
+            let c = self.peek()?;
+            self.position += c.len_utf8();
+            match c {
+                c if c == quote && !multi_line => return Some(value),
+                '\n' if !multi_line => return None,
+                '\\' if quote == '"' => {
+                    let escaped = self.peek()?;
+                    if multi_line && escaped.is_whitespace() {
+                        // A backslash at the end of a line joins it to the next
+                        let rest = self.rest();
+                        self.position += rest.len() - rest.trim_start().len();
+                        continue;
+                    }
+                    self.position += escaped.len_utf8();
+                    value.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        c => c,
+                    });
+                }
+                c => value.push(c),
+            }
+        }
+    }
+
+    /// The entries of an inline table, each with its value when that is a
+    /// string
+    fn inline_table(&mut self) -> Option<Vec<(String, Option<String>)>> {
+        if !self.eat('{') {
+            return None;
+        }
+        let mut entries = Vec::new();
+        loop {
+            self.skip_blank();
+            if self.eat('}') {
+                return Some(entries);
+            }
+            let [name] = <[String; 1]>::try_from(self.key()?).ok()?;
+            if !self.eat('=') {
+                return None;
+            }
+            let value = match self.string() {
+                Some(value) => Some(value),
+                None => {
+                    self.skip_value()?;
+                    None
+                }
+            };
+            entries.push((name, value));
+            self.skip_blank();
+            if !self.eat(',') && self.peek() != Some('}') {
+                return None;
+            }
+        }
+    }
+
+    /// Skip a value of any type: a string, an array, an inline table, or a
+    /// number, boolean or date
+    fn skip_value(&mut self) -> Option<()> {
+        self.skip_spaces();
+        match self.peek()? {
+            '"' | '\'' => {
+                self.string()?;
+            }
+            '[' => {
+                self.position += 1;
+                loop {
+                    self.skip_blank();
+                    if self.eat(']') {
+                        break;
+                    }
+                    self.skip_value()?;
+                    self.skip_blank();
+                    if !self.eat(',') && self.peek() != Some(']') {
+                        return None;
+                    }
+                }
+            }
+            '{' => {
+                self.position += 1;
+                loop {
+                    self.skip_blank();
+                    if self.eat('}') {
+                        break;
+                    }
+                    self.key()?;
+                    if !self.eat('=') {
+                        return None;
+                    }
+                    self.skip_value()?;
+                    self.skip_blank();
+                    if !self.eat(',') && self.peek() != Some('}') {
+                        return None;
+                    }
+                }
+            }
+            _ => {
+                let rest = self.rest();
+                let end = rest.find([',', ']', '}', '#', '\n']).unwrap_or(rest.len());
+                if rest[..end].trim().is_empty() {
+                    return None;
+                }
+                self.position += end;
+            }
+        }
+        Some(())
+    }
+}
+
+/// Split an alias into words like a shell would: on whitespace, keeping
+/// quoted text together
+fn split_words(text: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        anyhow::bail!("unclosed quote");
+    }
+    if in_word {
+        words.push(word);
+    }
+    if words.is_empty() {
+        anyhow::bail!("it is empty");
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<OsString> {
+        line.split(' ').map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_expand() {
+        let aliases = parse_aliases(
+            r#"
+            # Shortcuts
+            [aliases]
+            w = "today --done"
+            fern = 'show "Boston fern"'
+            f = "fern" # another alias
+            list = "list --tag x"
+
+            [other]
+            ignored = 1
+
+            [ aliases ]  # more shortcuts
+            d = "today --done"
+            "#,
+        )
+        .unwrap();
+        let path = Path::new("config.toml");
+
+        assert_eq!(
+            expand(args("plant-care --output json w 2"), &aliases, path).unwrap(),
+            args("plant-care --output json today --done 2")
+        );
+        assert_eq!(
+            expand(args("plant-care -q f"), &aliases, path).unwrap(),
+            ["plant-care", "-q", "show", "Boston fern"].map(OsString::from)
+        );
+        // Built-in commands win, and aliases' arguments are left alone
+        assert_eq!(expand(args("plant-care list"), &aliases, path).unwrap(), args("plant-care list"));
+        assert_eq!(expand(args("plant-care show w"), &aliases, path).unwrap(), args("plant-care show w"));
+
+        let looping = HashMap::from([("a".to_string(), "b".to_string()), ("b".to_string(), "a".to_string())]);
+        assert!(expand(args("plant-care a"), &looping, path).is_err());
+        assert_eq!(aliases["d"], "today --done");
+        assert!(parse_aliases("[aliases]\nw = today").is_err());
+    }
+
+    #[test]
+    fn test_inline_dotted_and_multi_line_aliases() {
+        let aliases = parse_aliases(
+            r#"
+            theme = "dark"
+            aliases = { w = "today --done", f = 'show "Boston fern"' }
+            aliases.d = "diagnose"
+            "aliases"."m" = """\
+            moisture \
+              log"""
+
+            [notifications]
+            quiet = [
+                "22:00",
+                "07:00", # overnight
+            ]
+            note = """
+            [aliases]
+            x = "not an alias"
+            """
+            limits = { max = 3, at = 1979-05-27 07:32:00Z }
+
+            [aliases]
+            l = '''list --tag "x"'''
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(aliases["w"], "today --done");
+        assert_eq!(aliases["f"], "show \"Boston fern\"");
+        assert_eq!(aliases["d"], "diagnose");
+        assert_eq!(aliases["m"], "moisture log");
+        assert_eq!(aliases["l"], "list --tag \"x\"");
+        assert!(!aliases.contains_key("x"));
+        assert_eq!(aliases.len(), 5);
+
+        assert!(parse_aliases("aliases = { w = 3 }").is_err());
+        assert!(parse_aliases("aliases = { w = \"today\"").is_err());
+        assert!(parse_aliases("[aliases]\nw = \"\"\"today").is_err());
+    }
+}
@@ -7,35 +7,322 @@
  */
 
 use anyhow::{Context, Result};
-use console::style;
-use dialoguer::{theme::ColorfulTheme, Input};
-use indicatif::{ProgressBar, ProgressStyle};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use console::Term;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use dialoguer::{Confirm, Input, MultiSelect, Password, Select};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::adapters::{AiAdapter, PlantIdAdapter, StorageAdapter};
-use crate::config::Database;
-use crate::domain::enums::DiagnosisStatus;
-use crate::dto::{DiagnosisStartDto, DiagnosisUpdateDto, PlantCreationDto};
-use crate::repositories::{DiagnosisRepository, PlantRepository};
-use crate::services::{DiagnosisService, PlantService};
+use super::output::{self, note, out};
+use super::preview;
+use super::theme;
+use crate::bot::discord::DiscordBot;
+use crate::bot::telegram::TelegramBot;
+use crate::grpc::GrpcServer;
+use crate::http::HttpServer;
+use crate::logging;
+use super::{ExportFormat, ExportTarget};
+use plant_care_core::adapters::{
+    identifier_adapter, notification_adapter, payload, plant_id_adapter, search_adapter, species_data_adapter, storage_adapter, sync_adapter,
+    AiAdapter, PlantIdAdapter,
+};
+use plant_care_core::config::secrets::{self, KeyringSecretStore, SecretStore};
+use plant_care_core::config::{self, Database};
+use plant_care_core::crypto;
+use plant_care_core::pdf;
+use plant_care_core::domain::ai_usage;
+use plant_care_core::domain::encyclopedia::{self, EntryKind};
+use plant_care_core::domain::care_difficulty::MAX_DIFFICULTY;
+use plant_care_core::domain::diagnosis_session::LOW_CONFIDENCE_THRESHOLD;
+use plant_care_core::domain::enums::DiagnosisStatus;
+use plant_care_core::domain::audit_event::describe_changes;
+use plant_care_core::domain::feedback::{self, MAX_RATING, MIN_RATING};
+use plant_care_core::domain::job::MAX_JOB_ATTEMPTS;
+use plant_care_core::domain::expense;
+use plant_care_core::domain::moisture_reading::{self, MAX_MOISTURE};
+use plant_care_core::domain::placement::{Fit, PlacementAdvice};
+use plant_care_core::domain::plant_age;
+use plant_care_core::domain::plant_attribute;
+use plant_care_core::domain::quarantine;
+use plant_care_core::domain::recommendation::Conditions;
+use plant_care_core::domain::species_catalog::{self, SpeciesMatch};
+use plant_care_core::domain::{
+    ArchiveReason, AttributeFilter, AuditEntity, Badge, CareOverrides, CareSchedule, CareScheduleUpdate, CareSheet, ContainmentPlan, DiagnosisBatch, DiagnosisFilter, DiagnosisMessage, DifficultyFilter, Environment, Expense, ExpenseKind, Feedback, FeedbackKind, GroupRank, Growth, HealthIndex,
+    IdentificationDisagreement, Job, JobKind, JobStatus, JournalEntry, MoistureReading, MoistureTrend, NotificationEvent, Plant, PlantAttribute, PlantFilter, Quarantine, Reminder, ReminderKind, Room, RoomProfile, Severity, SourceApp, SpeciesCandidate,
+    SpeciesResolution, Streak, UserRole, WindowOrientation, WishlistItem,
+};
+use plant_care_core::dto::{
+    AlternativeDiagnosisDto, CollectionArchiveDto, DiagnosisConcludeResponse, DiagnosisResponseDto,
+    DiagnosisStartDto, DiagnosisUpdateDto, PlantCreationDto, PlantProfileDto, PlantSnapshotDto,
+};
+use plant_care_core::repositories::{
+    AttributeRepository, CareOverrideRepository, CareTaskRepository, ChatRepository, DiagnosisRepository, EventRepository, ExpenseRepository, FeedbackRepository, IdentificationRepository, InspectionRepository, JobRepository, JournalRepository,
+    MoistureRepository, OutcomeRepository, PlantCache, PlantRepository, QuarantineRepository, RepairRepository, RoomRepository, ScheduleVersionRepository, ShoppingRepository, StatsRepository, SyncRepository, TemplateRepository,
+    UserRepository, WishlistRepository,
+};
+use plant_care_core::repositories::repair_repository::Repair;
+use plant_care_core::services::bulk_service::BulkAction;
+use plant_care_core::services::care_schedule_service::{self, RegenerationReport};
+use plant_care_core::services::checklist_service::ChecklistKind;
+use plant_care_core::services::encyclopedia_service::EntrySource;
+use plant_care_core::services::eval_service::{self, ModelReport};
+use plant_care_core::services::explain_service::CareTopic;
+use plant_care_core::services::plant_service;
+use plant_care_core::services::query_service::{self, PlantOverview};
+use plant_care_core::services::sitter_service::MIN_PIN_LEN;
+use plant_care_core::services::sync_service::{self, Resolution, SyncConflict};
+use plant_care_core::services::{
+    export_service, label_service, shopping_service, Anonymizer, ApiKeyService, ArchiveService, AskService, AuditService, BackupService, BulkService, CareScheduleService, ChatService,
+    BundleSources, ChecklistService, DebugBundleService, DiagnosisService, EncyclopediaService, EvalService,
+    EventBus, ExplainService, ExportService, FamilyService, GalleryService, GenerateScheduleHandler, JobReport, JobService, OutcomeService, PlacementService, PlantCreation, PlantService, ProfileService, QuarantineService, QueryService, RecommendationService, RefreshSchedulesHandler, ImportService, ReportService, ShoppingService, SitterService, SpeciesChoice, StreakService, SpeciesChooser, SyncService, TemplateService, ThumbnailService,
+    TranscriptService, TriageService, VacationService,
+};
 
+/// How long image links printed by `show` stay valid
+const IMAGE_LINK_EXPIRY_SECS: u32 = 3600;
+
+/// Width of inline image previews, in terminal cells
+const SHOW_PREVIEW_WIDTH: u32 = 40;
+const LIST_PREVIEW_WIDTH: u32 = 16;
+
+/// Print an inline thumbnail of a stored image; failures are non-fatal
+async fn print_image_preview(thumbnails: &ThumbnailService, image_url: &str, width: u32) {
+    if output::is_quiet() || output::is_accessible() {
+        return;
+    }
+
+    let result = match thumbnails.get_thumbnail(image_url).await {
+        Ok(png) => preview::print_preview(&png, width),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        log::debug!("Skipping preview for {}: {:#}", image_url, e);
+    }
+}
+
+/// Resolve a plant by ID, falling back to a case-insensitive name match
+async fn find_plant(plant_repo: &PlantRepository, identifier: &str) -> Result<Plant> {
+    plant_repo.find(identifier, "local-user").await
+}
+
+/// Print a care schedule, with its instructions when there are any
+fn print_care_schedule(schedule: &CareSchedule) {
+    out!("\n{}", theme::heading("Care Schedule:"));
+    out!("  {} {}", theme::muted("Light:"), schedule.light);
+    out!("  {} {}", theme::muted("Water:"), schedule.water);
+    out!("  {} {}", theme::muted("Humidity:"), schedule.humidity);
+    out!("  {} {}", theme::muted("Temperature:"), schedule.temperature);
+
+    if !schedule.care_instructions.is_empty() {
+        out!("\n{}", theme::heading("Care Instructions:"));
+        out!("  {}", schedule.care_instructions);
+    }
+}
+
+/// Print a plant's care schedule, marking the fields the owner changed
+/// from the species baseline
+fn print_merged_schedule(care: &CareOverrides) {
+    if care.is_empty() {
+        return print_care_schedule(&care.merged());
+    }
+
+    let fields = care.fields();
+    let (instructions, fields) = fields.split_last().expect("a schedule has fields");
+    let marker = |baseline: &str| theme::muted(format!("✎ (species: {})", baseline));
+    out!("\n{}", theme::heading("Care Schedule:"));
+    for field in fields {
+        match &field.baseline {
+            Some(baseline) => out!("  {} {} {}", theme::muted(format!("{}:", field.label)), field.value, marker(baseline)),
+            None => out!("  {} {}", theme::muted(format!("{}:", field.label)), field.value),
+        }
+    }
+
+    if !instructions.value.is_empty() || instructions.baseline.is_some() {
+        out!("\n{}", theme::heading("Care Instructions:"));
+        out!("  {}", instructions.value);
+        if let Some(baseline) = &instructions.baseline {
+            out!("  {}", marker(baseline));
+        }
+    }
+    note!(
+        "\n{}",
+        theme::muted("✎ Your changes are kept when the schedule is regenerated; `care reset` drops them.")
+    );
+}
+
+/// The name a plant is shown under, with the species after it when that
+/// differs: the nickname before the species' name, or before the scientific
+/// name with `scientific`
+fn plant_title(plant: &Plant, scientific: bool) -> (&str, Option<&str>) {
+    let species = if scientific { plant.species() } else { plant.name.as_str() };
+    let (title, subtitle) = match plant.nickname.as_deref() {
+        Some(nickname) => (nickname, species),
+        None => (species, plant.name.as_str()),
+    };
+    (title, Some(subtitle).filter(|s| *s != title))
+}
+
+/// Print where a plant lives and its tags, when set
+fn print_room_and_tags(plant: &Plant) {
+    if let Some(room) = &plant.room {
+        out!("  {} {}", theme::muted("Room:"), room);
+    }
+    if !plant.tags.is_empty() {
+        out!("  {} {}", theme::muted("Tags:"), plant.tags.join(", "));
+    }
+}
+
+/// Print a plant's care difficulty as stars, with its common failure modes
+/// unless `brief`
+fn print_difficulty(plant: &Plant, brief: bool) {
+    let Some(difficulty) = &plant.difficulty else {
+        return;
+    };
+
+    let rating = difficulty.rating.min(MAX_DIFFICULTY) as usize;
+    let stars = format!("{}{}", "★".repeat(rating), "☆".repeat(MAX_DIFFICULTY as usize - rating));
+    let mut line = format!("{} {}/{}", theme::warning(stars), rating, MAX_DIFFICULTY);
+    if difficulty.beginner_friendly {
+        line.push_str(&format!(" {}", theme::good("(beginner friendly)")));
+    }
+    out!("  {} {}", theme::muted("Difficulty:"), line);
+
+    if !brief && !difficulty.failure_modes.is_empty() {
+        out!("  {} {}", theme::muted("Common failures:"), difficulty.failure_modes.join(", "));
+    }
+}
+
+/// Health index as a coloured badge, e.g. "♥ 86"
+fn health_badge(health: &HealthIndex) -> String {
+    let badge = format!("♥ {}", health.score);
+    if health.score >= 80 {
+        theme::good(badge).to_string()
+    } else if health.score >= 50 {
+        theme::warning(badge).to_string()
+    } else {
+        theme::danger(badge).to_string()
+    }
+}
+
+/// Surface care schedule validation warnings to the user
+fn print_schedule_warnings(warnings: &[String]) {
+    for warning in warnings {
+        out!("{} {}", theme::alert("⚠"), theme::warning(warning));
+    }
+}
+
+/// Warn about the characters of a PDF's text its fonts can't show
+fn print_unsupported_pdf_chars(text: &str) {
+    let unsupported = pdf::unsupported_chars(text);
+    if unsupported.is_empty() {
+        return;
+    }
+    let chars: Vec<String> = unsupported.iter().map(char::to_string).collect();
+    out!(
+        "{} {}",
+        theme::alert("⚠"),
+        theme::warning(format!(
+            "The PDF shows \"?\" for {} (save as .md to keep them)",
+            chars.join(" ")
+        ))
+    );
+}
+
+/// An instant in the owner's time zone, for display
+fn local(at: DateTime<Utc>) -> DateTime<FixedOffset> {
+    config::local_zone().local(at)
+}
+
+/// A plant as `list --output json` prints it
+#[derive(serde::Serialize)]
+struct PlantListing<'a> {
+    #[serde(flatten)]
+    plant: &'a Plant,
+    health: &'a HealthIndex,
+    last_diagnosed_at: Option<DateTime<Utc>>,
+    quarantine: Option<&'a Quarantine>,
+    next_reminder: Option<&'a Reminder>,
+    photo_count: usize,
+}
+
+impl<'a> From<&'a PlantOverview> for PlantListing<'a> {
+    fn from(overview: &'a PlantOverview) -> Self {
+        Self {
+            plant: &overview.plant,
+            health: &overview.health,
+            last_diagnosed_at: overview.latest_diagnosis.as_ref().map(|d| d.started_at),
+            quarantine: overview.quarantine.as_ref(),
+            next_reminder: overview.next_reminder(),
+            photo_count: overview.photo_count,
+        }
+    }
+}
+
+fn query_service(db: &Database) -> QueryService {
+    QueryService::new(
+        PlantRepository::new(db.clone()),
+        CareTaskRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        OutcomeRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        QuarantineRepository::new(db.clone()),
+        InspectionRepository::new(db.clone()),
+    )
+}
+
+/// "💧 Water in 3 days (Thu 2024-05-23)", "🔍 Inspect today"
+fn reminder_label(reminder: &Reminder, today: NaiveDate) -> String {
+    let (icon, task) = match reminder.kind {
+        ReminderKind::Water => ("💧", "Water"),
+        ReminderKind::Fertilize => ("🌱", "Fertilize"),
+        ReminderKind::Inspect => ("🔍", "Inspect"),
+    };
+    let when = match reminder.overdue_days(today) {
+        0 => "today".to_string(),
+        -1 => "tomorrow".to_string(),
+        days if days < 0 => format!("in {} days ({})", -days, reminder.due.format("%a %Y-%m-%d")),
+        1 => "1 day overdue".to_string(),
+        days => format!("{} days overdue", days),
+    };
+    format!("{} {} {}", icon, task, when)
+}
+
+/// Report a listing with nothing to show: an empty array in JSON mode
+fn nothing_to_list(message: &str) -> Result<()> {
+    if output::is_json() {
+        return output::json(&[(); 0]);
+    }
+    out!("{}", theme::warning(message));
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn add_plant(
     db: Database,
     image_path: String,
     _name: Option<String>,
     latitude: Option<f64>,
     longitude: Option<f64>,
+    template: Option<String>,
+    force_new: bool,
+    nickname: Option<String>,
+    acquired: Option<NaiveDate>,
+    acquired_from: Option<String>,
 ) -> Result<()> {
-    println!("{}", style("🌱 Adding new plant...").green().bold());
+    note!("{}", theme::title("🌱 Adding new plant..."));
 
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message("Reading image file...");
+    let acquired_at = acquired.map(acquired_at).transpose()?;
+
+    let template = match template {
+        Some(name) => Some(template_service(&db).get(&name, "local-user").await?),
+        None => None,
+    };
+
+    let progress = output::progress("Reading image file...");
 
     // Read and encode image
     let image_path = Path::new(&image_path);
@@ -43,111 +330,405 @@ pub async fn add_plant(
         anyhow::bail!("Image file not found: {}", image_path.display());
     }
 
-    let image_bytes = fs::read(image_path)
-        .context("Failed to read image file")?;
-    let base64_image = base64::encode(&image_bytes);
+    let image_bytes = payload::read_image(image_path)?;
+    let base64_image = BASE64.encode(&image_bytes);
 
     // Initialize services
     let plant_id_adapter = PlantIdAdapter::new()?;
     let ai_adapter = AiAdapter::new()?;
-    let storage_adapter = StorageAdapter::new();
+    let model = ai_adapter.model().to_string();
+    let storage_adapter = storage_adapter::from_env()?;
     let plant_repo = PlantRepository::new(db.clone());
     let plant_service = PlantService::new(
         plant_repo,
+        IdentificationRepository::new(db.clone()),
+        JobRepository::new(db.clone()),
         plant_id_adapter,
+        identifier_adapter::from_env()?,
         ai_adapter,
+        species_data_adapter::from_env()?,
         storage_adapter,
+        EventBus::new(notification_adapter::from_env()?).with_queue(JobRepository::new(db.clone())),
     );
 
-    spinner.set_message("Identifying plant...");
-
     let dto = PlantCreationDto {
         images: vec![base64_image],
         latitude,
         longitude,
+        care_schedule: template.as_ref().map(|t| t.schedule.clone()),
+        template: template.as_ref().map(|t| t.name.clone()),
+        force_new,
+        nickname,
+        acquired_at,
+        acquired_from,
     };
 
-    let plant = plant_service.create_plant(dto, "local-user".to_string()).await?;
+    let creation = plant_service
+        .create_plant(dto, "local-user".to_string(), progress.as_ref(), &PromptSpeciesChooser)
+        .await?;
 
-    spinner.finish_and_clear();
+    progress.finish();
+
+    let (plant, warnings, disagreement, queued) = match creation {
+        PlantCreation::Created {
+            plant,
+            warnings,
+            disagreement,
+            queued,
+        } => (plant, warnings, disagreement, queued),
+        PlantCreation::Existing(plant) => {
+            out!("{}", theme::success("✓ This photo was already added"));
+            out!("  {} {}", theme::muted("ID:"), plant.id);
+            out!("  {} {}", theme::muted("Name:"), plant.display_name());
+            note!(
+                "\n{}",
+                theme::muted("Use --force-new to add it as another plant.")
+            );
+            return Ok(());
+        }
+    };
+
+    out!("{}", theme::success("✓ Plant added successfully!"));
+    if let Some(template) = &template {
+        out!("  {} {}", theme::muted("Care schedule from template:"), template.name);
+    }
+    if let Some(disagreement) = &disagreement {
+        print_disagreement(disagreement);
+        note!(
+            "  {}",
+            theme::muted("Wrong species? Fix it with plant-care disagreements correct <PLANT> <SPECIES>")
+        );
+    }
+    print_schedule_warnings(&warnings);
+    if queued.is_some() {
+        note!(
+            "  {}",
+            theme::muted("The job worker (plant-care jobs run) writes the schedule once the AI is back")
+        );
+    }
+    out!("\n{}", theme::heading("Plant Details:"));
+    out!("  {} {}", theme::muted("ID:"), plant.id);
+    out!("  {} {}", theme::muted("Name:"), plant.name);
+    if let Some(nickname) = &plant.nickname {
+        out!("  {} {}", theme::muted("Nickname:"), nickname);
+    }
+    print_difficulty(&plant, false);
+    out!("\n{}", theme::heading("Care Schedule:"));
+    out!("  {} {}", theme::muted("Light:"), plant.care_schedule.light);
+    out!("  {} {}", theme::muted("Water:"), plant.care_schedule.water);
+    out!("  {} {}", theme::muted("Humidity:"), plant.care_schedule.humidity);
+    out!("  {} {}", theme::muted("Temperature:"), plant.care_schedule.temperature);
 
-    println!("{}", style("✓ Plant added successfully!").green().bold());
-    println!("\n{}", style("Plant Details:").cyan().bold());
-    println!("  {} {}", style("ID:").dim(), plant.id);
-    println!("  {} {}", style("Name:").dim(), plant.name);
-    println!("\n{}", style("Care Schedule:").cyan().bold());
-    println!("  {} {}", style("Light:").dim(), plant.care_schedule.light);
-    println!("  {} {}", style("Water:").dim(), plant.care_schedule.water);
-    println!("  {} {}", style("Humidity:").dim(), plant.care_schedule.humidity);
-    println!("  {} {}", style("Temperature:").dim(), plant.care_schedule.temperature);
+    out!();
+    prompt_for_feedback(&db, FeedbackKind::CareSchedule, plant.name.clone(), model).await?;
 
     Ok(())
 }
 
-pub async fn list_plants(db: Database) -> Result<()> {
-    let plant_repo = PlantRepository::new(db);
-    let plants = plant_repo.get_all_by_user("local-user").await?;
+pub async fn list_plants(
+    db: Database,
+    difficulty: Option<String>,
+    conditions: Vec<String>,
+    needs_attention: bool,
+    scientific: bool,
+) -> Result<()> {
+    let difficulty = difficulty.as_deref().map(DifficultyFilter::parse).transpose()?;
+    let attribute_filter = AttributeFilter::parse(&conditions)?;
+    let mut overviews = query_service(&db).collection("local-user").await?;
 
-    if plants.is_empty() {
-        println!("{}", style("No plants in your collection yet.").yellow());
-        println!("Use {} to add your first plant!", style("plant-care add --image <path>").green());
+    if overviews.is_empty() {
+        nothing_to_list("No plants in your collection yet.")?;
+        note!("Use {} to add your first plant!", theme::good("plant-care add --image <path>"));
         return Ok(());
     }
 
-    println!("{}", style(format!("🌿 Your Plant Collection ({} plants)", plants.len())).green().bold());
-    println!();
+    // Neighbours are found across the whole collection, before filtering
+    let plants: Vec<Plant> = overviews.iter().map(|o| o.plant.clone()).collect();
+    let quarantines: Vec<Quarantine> = overviews.iter().filter_map(|o| o.quarantine.clone()).collect();
+    let neighbours: Vec<(String, Vec<String>)> = quarantine::neighbours(&plants, &quarantines)
+        .into_iter()
+        .map(|(plant, sick)| (plant.id.clone(), sick.into_iter().map(str::to_string).collect()))
+        .collect();
+
+    if let Some(filter) = &difficulty {
+        overviews.retain(|o| filter.matches(o.plant.difficulty.as_ref()));
+        if overviews.is_empty() {
+            nothing_to_list("No plants with that care difficulty.")?;
+            note!("Plants added before difficulty ratings were introduced have no rating.");
+            return Ok(());
+        }
+    }
+
+    if !attribute_filter.is_empty() {
+        let attributes = AttributeRepository::new(db.clone()).get_all_by_user("local-user").await?;
+        overviews.retain(|o| {
+            let own: Vec<PlantAttribute> =
+                attributes.iter().filter(|a| a.plant_id == o.plant.id).cloned().collect();
+            attribute_filter.matches(&own)
+        });
+        if overviews.is_empty() {
+            return nothing_to_list("No plants with those attributes.");
+        }
+    }
+
+    if needs_attention {
+        overviews = query_service::needing_attention(overviews);
+        if overviews.is_empty() {
+            if output::is_json() {
+                return output::json(&[(); 0]);
+            }
+            out!("{}", theme::good("✓ No plant needs attention right now."));
+            return Ok(());
+        }
+    }
+
+    if output::is_json() {
+        let listings: Vec<PlantListing> = overviews.iter().map(PlantListing::from).collect();
+        return output::json(&listings);
+    }
+
+    let title = if needs_attention { "🩺 Plants Needing Attention" } else { "🌿 Your Plant Collection" };
+    out!("{}", theme::title(format!("{} ({} plants)", title, overviews.len())));
+    out!();
 
-    for plant in plants {
-        println!("{}", style(&plant.name).cyan().bold());
-        println!("  {} {}", style("ID:").dim(), plant.id);
-        println!("  {} {}", style("Added:").dim(), plant.created_at.format("%Y-%m-%d"));
-        println!();
+    let thumbnails = ThumbnailService::new(storage_adapter::from_env()?);
+    let today = config::local_zone().today();
+
+    for overview in &overviews {
+        let plant = &overview.plant;
+        if let Some(url) = &plant.image_url {
+            print_image_preview(&thumbnails, url, LIST_PREVIEW_WIDTH).await;
+        }
+        let (title, subtitle) = plant_title(plant, scientific);
+        let mut heading = theme::heading(title).to_string();
+        if let Some(subtitle) = subtitle {
+            heading.push_str(&format!(" {}", theme::muted(format!("({})", subtitle))));
+        }
+        out!("{} {}", heading, health_badge(&overview.health));
+        out!("  {} {}", theme::muted("ID:"), plant.id);
+        out!("  {} {}", theme::muted("Added:"), output::date(plant.created_at, "%Y-%m-%d"));
+        out!("  {} {}", theme::muted("Age:"), age_label(plant));
+        if let Some(latest) = &overview.latest_diagnosis {
+            out!("  {} {}", theme::muted("Last diagnosed:"), output::date(latest.started_at, "%Y-%m-%d"));
+        }
+        if let Some(reminder) = overview.next_reminder() {
+            out!("  {} {}", theme::muted("Next:"), reminder_label(reminder, today));
+        }
+        if overview.photo_count > 0 {
+            out!("  {} {}", theme::muted("Photos:"), overview.photo_count);
+        }
+        print_room_and_tags(plant);
+        print_difficulty(plant, true);
+        if let Some(quarantine) = &overview.quarantine {
+            out!("  {}", theme::danger(quarantine_label(quarantine)));
+        }
+        if let Some((_, sick)) = neighbours.iter().find(|(id, _)| *id == plant.id) {
+            out!(
+                "  {}",
+                theme::warning(format!("⚠ Shares a room with quarantined {}; check it for pests", sick.join(", ")))
+            );
+        }
+        out!();
     }
 
     Ok(())
 }
 
+/// Noon of `day` in the local time zone, as the moment a plant was acquired
+fn acquired_at(day: NaiveDate) -> Result<DateTime<Utc>> {
+    let zone = config::local_zone();
+    if day > zone.today() {
+        anyhow::bail!("The date {} is in the future", day);
+    }
+    // Noon, so the day stays the same whatever the time zone
+    Ok(zone.start_of_day(day) + chrono::Duration::hours(12))
+}
+
+/// "2024-03-01 (7 months ago), garden center"
+fn acquired_label(plant: &Plant) -> String {
+    let mut label = output::date(plant.acquired(), "%Y-%m-%d");
+    if let Some(from) = &plant.acquired_from {
+        label.push_str(&format!(", {}", from));
+    }
+    label
+}
+
+/// "3 months (likely a seedling)"
+fn age_label(plant: &Plant) -> String {
+    let growth = Growth::of(plant, Utc::now());
+    let age = plant_age::describe_age(growth.days_owned);
+    match growth.maturity() {
+        Some(maturity) => format!("{} {}", age, theme::muted(format!("(likely a {})", maturity.as_str()))),
+        None => age,
+    }
+}
+
+/// "🚫 Quarantined since 2024-05-01 (2 weeks ago): spider mites"
+fn quarantine_label(quarantine: &Quarantine) -> String {
+    let since = format!("🚫 Quarantined since {}", output::date(quarantine.started_at, "%Y-%m-%d"));
+    match &quarantine.reason {
+        Some(reason) => format!("{}: {}", since, reason),
+        None => since,
+    }
+}
+
 pub async fn show_plant(db: Database, plant_identifier: String) -> Result<()> {
-    let plant_repo = PlantRepository::new(db);
+    let plant_repo = PlantRepository::new(db.clone());
 
     // Try to find plant by ID or name
-    let plant = plant_repo
-        .get_by_id(&plant_identifier, "local-user")
-        .await?
-        .or_else(|| {
-            // TODO: Search by name
-            None
-        })
-        .context("Plant not found")?;
+    let overview = query_service(&db).overview(find_plant(&plant_repo, &plant_identifier).await?).await?;
+    let plant = &overview.plant;
+    let attributes = AttributeRepository::new(db.clone()).get_all_by_plant_id(&plant.id).await?;
+    let care = CareOverrideRepository::new(db.clone()).get(plant).await?;
+
+    if output::is_json() {
+        return output::json(&serde_json::json!({
+            "plant": plant,
+            "care_baseline": care.baseline,
+            "care_overrides": care.overrides,
+            "attributes": attributes,
+            "health": overview.health,
+            "last_diagnosed_at": overview.latest_diagnosis.as_ref().map(|d| d.started_at),
+            "next_reminder": overview.next_reminder(),
+            "photo_count": overview.photo_count,
+        }));
+    }
 
-    println!("{}", style(&plant.name).green().bold());
-    println!("\n{}", style("Details:").cyan().bold());
-    println!("  {} {}", style("ID:").dim(), plant.id);
-    println!("  {} {}", style("Added:").dim(), plant.created_at.format("%Y-%m-%d %H:%M"));
+    match plant_title(plant, false) {
+        (title, Some(species)) => out!("{} {}", theme::title(title), theme::muted(format!("({})", species))),
+        (title, None) => out!("{}", theme::title(title)),
+    }
+    out!("\n{}", theme::heading("Details:"));
+    out!("  {} {}", theme::muted("ID:"), plant.id);
+    if let Some(scientific_name) = &plant.scientific_name {
+        out!("  {} {}", theme::muted("Scientific name:"), theme::scientific(scientific_name));
+    }
+    if let Some(family) = &plant.family {
+        out!("  {} {}", theme::muted("Family:"), family);
+    }
+    let other_names: Vec<&str> = plant
+        .common_names
+        .iter()
+        .map(String::as_str)
+        .filter(|n| !n.eq_ignore_ascii_case(&plant.name))
+        .collect();
+    if !other_names.is_empty() {
+        out!("  {} {}", theme::muted("Also known as:"), other_names.join(", "));
+    }
+    out!("  {} {}", theme::muted("Added:"), output::date(plant.created_at, "%Y-%m-%d %H:%M"));
+    if plant.acquired_at.is_some() || plant.acquired_from.is_some() {
+        out!("  {} {}", theme::muted("Acquired:"), acquired_label(plant));
+    }
+    out!("  {} {}", theme::muted("Age:"), age_label(plant));
+    out!("  {} {}", theme::muted("Health:"), health_badge(&overview.health));
+    if let Some(latest) = &overview.latest_diagnosis {
+        out!("  {} {}", theme::muted("Last diagnosed:"), output::date(latest.started_at, "%Y-%m-%d %H:%M"));
+    }
+    if let Some(reminder) = overview.next_reminder() {
+        out!("  {} {}", theme::muted("Next:"), reminder_label(reminder, config::local_zone().today()));
+    }
+    if overview.photo_count > 0 {
+        out!("  {} {}", theme::muted("Photos:"), overview.photo_count);
+    }
+    print_room_and_tags(plant);
+    print_difficulty(plant, false);
 
     if let Some(url) = &plant.image_url {
-        println!("  {} {}", style("Image:").dim(), url);
+        let storage = storage_adapter::from_env()?;
+        let link = storage
+            .presigned_url(url, IMAGE_LINK_EXPIRY_SECS)
+            .await
+            .unwrap_or_else(|_| url.clone());
+        out!("  {} {}", theme::muted("Image:"), link);
+
+        out!();
+        print_image_preview(&ThumbnailService::new(storage), url, SHOW_PREVIEW_WIDTH).await;
+    }
+
+    if !attributes.is_empty() {
+        out!("\n{}", theme::heading("Attributes:"));
+        for attribute in &attributes {
+            out!("  {} {}", theme::muted(format!("{}:", attribute.key)), attribute.value);
+        }
     }
 
-    println!("\n{}", style("Care Schedule:").cyan().bold());
-    println!("  {} {}", style("Light:").dim(), plant.care_schedule.light);
-    println!("  {} {}", style("Water:").dim(), plant.care_schedule.water);
-    println!("  {} {}", style("Humidity:").dim(), plant.care_schedule.humidity);
-    println!("  {} {}", style("Temperature:").dim(), plant.care_schedule.temperature);
+    print_merged_schedule(&care);
 
-    if !plant.care_schedule.care_instructions.is_empty() {
-        println!("\n{}", style("Care Instructions:").cyan().bold());
-        println!("  {}", plant.care_schedule.care_instructions);
+    let readings = MoistureRepository::new(db).get_all_by_plant_id(&plant.id).await?;
+    if let Some(trend) = moisture_reading::analyze(&readings, &plant.care_schedule, chrono::Utc::now()) {
+        out!("\n{}", theme::heading("Soil Moisture:"));
+        print_moisture_trend(&trend);
     }
 
     Ok(())
 }
 
-pub async fn delete_plant(db: Database, plant_identifier: String) -> Result<()> {
+pub async fn set_acquired(
+    db: Database,
+    plant_identifier: String,
+    on: Option<NaiveDate>,
+    from: Option<String>,
+) -> Result<()> {
     let plant_repo = PlantRepository::new(db);
-    plant_repo.delete(&plant_identifier, "local-user").await?;
+    let mut plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    if on.is_none() && from.is_none() {
+        if output::is_json() {
+            let growth = Growth::of(&plant, Utc::now());
+            return output::json(&serde_json::json!({
+                "plant_id": plant.id,
+                "acquired_at": plant.acquired(),
+                "acquired_from": plant.acquired_from,
+                "days_owned": growth.days_owned,
+                "maturity": growth.maturity(),
+            }));
+        }
+        out!("{}", theme::title(plant.display_name()));
+        out!("  {} {}", theme::muted("Acquired:"), acquired_label(&plant));
+        out!("  {} {}", theme::muted("Age:"), age_label(&plant));
+        if plant.acquired_at.is_none() {
+            note!("Use {} to record when you got it.", theme::good("--on YYYY-MM-DD"));
+        }
+        return Ok(());
+    }
+
+    if let Some(day) = on {
+        plant.acquired_at = Some(acquired_at(day)?);
+    }
+    if let Some(from) = &from {
+        plant.set_acquired_from(Some(from));
+    }
+    plant.updated_at = Utc::now();
+    plant_repo.update(&plant).await?;
+
+    out!("{}", theme::success(format!("✓ {} acquired {}", plant.display_name(), acquired_label(&plant))));
+    out!("  {} {}", theme::muted("Age:"), age_label(&plant));
+    note!(
+        "Run {} to suit its care schedule to its age.",
+        theme::good(format!("plant-care care regenerate {}", plant.id))
+    );
+
+    Ok(())
+}
+
+pub async fn delete_plant(db: Database, plant_identifier: String) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+    let archive_service = ArchiveService::new(
+        plant_repo.clone(),
+        DiagnosisRepository::new(db.clone()),
+        UserRepository::new(db),
+    );
+    let plant = archive_service.delete_plant(&plant.id, "local-user").await?;
+
+    // Plants added from the same photo share its stored image
+    if let Some(url) = &plant.image_url {
+        if !plant_repo.image_in_use(url).await? {
+            storage_adapter::from_env()?.delete_image(url).await?;
+        }
+    }
 
-    println!("{}", style("✓ Plant deleted successfully").green().bold());
+    out!("{}", theme::success("✓ Plant deleted successfully"));
 
     Ok(())
 }
@@ -156,176 +737,4313 @@ pub async fn diagnose_plant(
     db: Database,
     plant_identifier: String,
     problem: String,
+    image: Option<String>,
+    yes: bool,
 ) -> Result<()> {
-    println!("{}", style("🔍 Starting diagnostic session...").green().bold());
-    println!();
+    note!("{}", theme::title("🔍 Starting diagnostic session..."));
+    out!();
 
     // Initialize services
     let plant_repo = PlantRepository::new(db.clone());
-    let diagnosis_repo = DiagnosisRepository::new(db.clone());
     let ai_adapter = AiAdapter::new()?;
-
-    let diagnosis_service = DiagnosisService::new(
-        plant_repo.clone(),
-        diagnosis_repo.clone(),
-        ai_adapter,
-    );
+    let model = ai_adapter.model().to_string();
+    let diagnosis_service = diagnosis_service(&db, ai_adapter)?;
 
     // Find plant
-    let plant = plant_repo
-        .get_by_id(&plant_identifier, "local-user")
-        .await?
-        .context("Plant not found")?;
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+    let photo = match &image {
+        Some(path) => Some(BASE64.encode(payload::read_image(Path::new(path))?)),
+        None => None,
+    };
 
-    println!("Diagnosing: {}", style(&plant.name).cyan().bold());
-    println!("Problem: {}", style(&problem).yellow());
-    println!();
+    out!("Diagnosing: {}", theme::heading(plant.display_name()));
+    out!("Problem: {}", theme::warning(&problem));
+    if let Some(path) = &image {
+        out!("Photo: {}", path);
+    }
+    if !confirm_diagnosis_cost(&diagnosis_service, 1, yes).await? {
+        return Ok(());
+    }
+    out!();
 
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message("AI is analyzing...");
+    let progress = output::progress("AI is analyzing...");
 
     // Start diagnosis
-    let dto = DiagnosisStartDto { prompt: problem };
+    let dto = DiagnosisStartDto {
+        prompt: problem,
+        photo,
+    };
     let response = diagnosis_service
         .start_diagnosis(&plant.id, dto, "local-user".to_string())
         .await?;
 
-    spinner.finish_and_clear();
+    progress.finish();
 
-    // Interactive loop
-    match response {
-        crate::dto::DiagnosisResponseDto::Ask(ask_response) => {
-            let mut diagnosis_id = ask_response.diagnosis_id;
-            let mut question = ask_response.question;
+    let transcripts = transcript_service(&db);
+    let conclusion = run_diagnosis(&diagnosis_service, &transcripts, response).await?;
 
-            loop {
-                println!("{} {}", style("AI:").cyan().bold(), question);
+    out!();
+    print_conclusion(&conclusion);
+    print_session_cost(&diagnosis_service, &conclusion.diagnosis_id).await?;
 
-                let answer: String = Input::with_theme(&ColorfulTheme::default())
-                    .with_prompt("You")
-                    .interact_text()?;
+    out!();
+    prompt_for_feedback(&db, FeedbackKind::Diagnosis, conclusion.diagnosis_id.clone(), model).await?;
 
-                let spinner = ProgressBar::new_spinner();
-                spinner.set_style(
-                    ProgressStyle::default_spinner()
-                        .template("{spinner:.green} {msg}")
-                        .unwrap(),
-                );
-                spinner.set_message("AI is thinking...");
+    offer_care_update(&diagnosis_service, &plant, &conclusion).await?;
+    offer_family_inspections(&db, &conclusion).await?;
 
-                let update_dto = DiagnosisUpdateDto { message: answer };
-                let response = diagnosis_service
-                    .update_diagnosis(&diagnosis_id, update_dto, "local-user".to_string())
-                    .await?;
+    out!();
+    let keep_chatting = Confirm::with_theme(&*theme::prompts())
+        .with_prompt("Keep chatting about this plant?")
+        .default(false)
+        .interact()?;
 
-                spinner.finish_and_clear();
+    if keep_chatting {
+        run_chat(db, &plant, false).await?;
+    }
 
-                match response {
-                    crate::dto::DiagnosisResponseDto::Ask(ask_response) => {
-                        diagnosis_id = ask_response.diagnosis_id;
-                        question = ask_response.question;
-                    }
-                    crate::dto::DiagnosisResponseDto::Conclude(conclude_response) => {
-                        println!();
-                        println!("{}", style("🎯 Diagnosis Complete!").green().bold());
-                        println!();
-                        println!("{}", style("Finding:").cyan().bold());
-                        println!("  {}", conclude_response.finding);
-                        println!();
-                        println!("{}", style("Recommendation:").cyan().bold());
-                        println!("  {}", conclude_response.recommendation);
-                        break;
-                    }
-                }
-            }
-        }
-        crate::dto::DiagnosisResponseDto::Conclude(conclude_response) => {
-            println!("{}", style("🎯 Diagnosis Complete!").green().bold());
-            println!();
-            println!("{}", style("Finding:").cyan().bold());
-            println!("  {}", conclude_response.finding);
-            println!();
-            println!("{}", style("Recommendation:").cyan().bold());
-            println!("  {}", conclude_response.recommendation);
+    Ok(())
+}
+
+/// Diagnose several plants showing the same problem in linked sessions,
+/// then combine the findings into one containment plan
+pub async fn diagnose_plants(db: Database, plant_identifiers: Vec<String>, problem: String, yes: bool) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let diagnosis_service = diagnosis_service(&db, AiAdapter::new()?)?;
+
+    let mut plants: Vec<Plant> = Vec::new();
+    for identifier in &plant_identifiers {
+        let plant = find_plant(&plant_repo, identifier.trim()).await?;
+        if !plants.iter().any(|p| p.id == plant.id) {
+            plants.push(plant);
         }
     }
+    if plants.len() < 2 {
+        anyhow::bail!("A batch diagnosis needs at least two different plants");
+    }
+
+    note!(
+        "{}",
+        theme::title(format!("🔍 Starting linked diagnosis of {} plants...", plants.len()))
+    );
+    out!("Problem: {}", theme::warning(&problem));
+    if !confirm_diagnosis_cost(&diagnosis_service, plants.len(), yes).await? {
+        return Ok(());
+    }
+
+    let transcripts = transcript_service(&db);
+    let mut batch = DiagnosisBatch::new(problem, &plants);
+    for (index, plant) in plants.iter().enumerate() {
+        out!();
+        out!(
+            "{} {}",
+            theme::muted(format!("[{}/{}]", index + 1, plants.len())),
+            theme::heading(format!("Diagnosing: {}", plant.display_name()))
+        );
+
+        let progress = output::progress("AI is analyzing...");
+        let response = diagnosis_service
+            .start_linked_diagnosis(&mut batch, &plant.id, "local-user".to_string())
+            .await?;
+        progress.finish();
+
+        let conclusion = run_diagnosis(&diagnosis_service, &transcripts, response).await?;
+        out!();
+        print_conclusion(&conclusion);
+        print_session_cost(&diagnosis_service, &conclusion.diagnosis_id).await?;
+        offer_care_update(&diagnosis_service, plant, &conclusion).await?;
+        offer_family_inspections(&db, &conclusion).await?;
+    }
+
+    out!();
+    let progress = output::progress("Working out how to contain it...");
+    let plan = diagnosis_service.conclude_batch(&mut batch, "local-user").await?;
+    progress.finish();
+
+    out!();
+    print_containment(&batch, &plan);
 
     Ok(())
 }
 
-pub async fn show_history(db: Database, plant_identifier: String) -> Result<()> {
+/// Fork a diagnosis session at one of the owner's answers and carry on from
+/// the AI's question before it with a different answer
+pub async fn branch_diagnosis(db: Database, session_id: String, at: usize, yes: bool) -> Result<()> {
     let plant_repo = PlantRepository::new(db.clone());
-    let diagnosis_repo = DiagnosisRepository::new(db);
+    let diagnosis_repo = DiagnosisRepository::new(db.clone());
+    let ai_adapter = AiAdapter::new()?;
+    let model = ai_adapter.model().to_string();
+    let diagnosis_service = diagnosis_service(&db, ai_adapter)?;
 
+    let original = diagnosis_service.get_diagnosis(&session_id, "local-user").await?;
     let plant = plant_repo
-        .get_by_id(&plant_identifier, "local-user")
+        .get_by_id(&original.plant_id, "local-user")
         .await?
         .context("Plant not found")?;
 
-    let sessions = diagnosis_repo
-        .get_all_by_plant_id(&plant.id, "local-user")
+    note!("{}", theme::title(format!("🔀 Branching the diagnosis of {} at turn {}...", plant.display_name(), at)));
+    if !confirm_diagnosis_cost(&diagnosis_service, 1, yes).await? {
+        return Ok(());
+    }
+    let response = diagnosis_service
+        .branch_diagnosis(&original.id, at, "local-user".to_string())
         .await?;
 
-    if sessions.is_empty() {
-        println!("{}", style("No diagnosis history for this plant.").yellow());
-        return Ok(());
+    out!();
+    for (number, turn) in diagnosis_repo
+        .get_messages(response.diagnosis_id(), 0, None)
+        .await?
+        .iter()
+        .enumerate()
+        .take(at.saturating_sub(2))
+    {
+        print_turn(number + 1, turn);
     }
 
-    println!(
-        "{}",
-        style(format!("📋 Diagnosis History for {} ({} sessions)", plant.name, sessions.len()))
-            .green()
-            .bold()
+    let transcripts = transcript_service(&db);
+    let conclusion = run_diagnosis(&diagnosis_service, &transcripts, response).await?;
+
+    out!();
+    print_conclusion(&conclusion);
+    print_session_cost(&diagnosis_service, &conclusion.diagnosis_id).await?;
+    out!(
+        "{} {}",
+        theme::muted("The original session is kept:"),
+        theme::accent(&original.id)
     );
-    println!();
 
-    for session in sessions {
-        println!("{}", style(&session.id).cyan());
-        println!("  {} {:?}", style("Status:").dim(), session.status);
-        println!("  {} {}", style("Created:").dim(), session.created_at.format("%Y-%m-%d %H:%M"));
+    out!();
+    prompt_for_feedback(&db, FeedbackKind::Diagnosis, conclusion.diagnosis_id.clone(), model).await?;
 
-        if session.status == DiagnosisStatus::Completed {
-            if let Some(result) = session.diagnosis_context.get("result") {
-                println!("  {} {}", style("Finding:").dim(), result.get("finding").and_then(|v| v.as_str()).unwrap_or("N/A"));
-            }
-        }
-        println!();
-    }
+    offer_care_update(&diagnosis_service, &plant, &conclusion).await?;
+    offer_family_inspections(&db, &conclusion).await?;
 
     Ok(())
 }
 
-pub async fn generate_care(plant_name: String) -> Result<()> {
-    println!("{}", style(format!("🌿 Generating care schedule for {}...", plant_name)).green().bold());
+fn diagnosis_service(db: &Database, ai_adapter: AiAdapter) -> Result<DiagnosisService> {
+    Ok(DiagnosisService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        OutcomeRepository::new(db.clone()),
+        MoistureRepository::new(db.clone()),
+        AttributeRepository::new(db.clone()),
+        RoomRepository::new(db.clone()),
+        UserRepository::new(db.clone()),
+        ai_adapter,
+        storage_adapter::from_env()?,
+        search_adapter::from_env()?,
+        EventBus::new(notification_adapter::from_env()?).with_queue(JobRepository::new(db.clone())),
+    )
+    .with_health_assessment(plant_id_adapter::health_assessor()))
+}
+
+/// Show what the diagnoses are expected to cost and, above the
+/// `DIAGNOSIS_CONFIRM_COST` threshold, ask before starting them. False when
+/// the owner declines.
+async fn confirm_diagnosis_cost(diagnosis_service: &DiagnosisService, diagnoses: usize, yes: bool) -> Result<bool> {
+    let estimate = diagnosis_service.estimate_cost("local-user", diagnoses).await?;
+    let basis = match estimate.sessions {
+        0 => "a typical diagnosis".to_string(),
+        1 => "your last diagnosis".to_string(),
+        n => format!("your last {} diagnoses", n),
+    };
+    let tokens = estimate.expected.usage.total_tokens();
+    match estimate.expected.usd {
+        Some(usd) => out!(
+            "{} ~{} {}",
+            theme::muted("Estimated cost:"),
+            theme::accent(ai_usage::format_usd(usd)),
+            theme::muted(format!("({} tokens of {}, based on {})", tokens, estimate.expected.model, basis))
+        ),
+        None => out!(
+            "{} ~{} tokens {}",
+            theme::muted("Estimated usage:"),
+            theme::accent(tokens),
+            theme::muted(format!("(no known price for {}; set AI_MODEL_PRICE)", estimate.expected.model))
+        ),
+    }
+
+    if yes || !estimate.needs_confirmation() {
+        return Ok(true);
+    }
+    if !Term::stdout().is_term() {
+        anyhow::bail!("The estimated cost is above DIAGNOSIS_CONFIRM_COST; pass --yes to start without a terminal");
+    }
+    let start = Confirm::with_theme(&*theme::prompts())
+        .with_prompt(format!(
+            "That is above your {} limit. Start anyway?",
+            ai_usage::format_usd(estimate.threshold)
+        ))
+        .default(false)
+        .interact()?;
+    if !start {
+        out!("{}", theme::warning("Cancelled"));
+    }
+
+    Ok(start)
+}
 
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
+/// Show what a concluded session actually cost, from its recorded usage
+async fn print_session_cost(diagnosis_service: &DiagnosisService, diagnosis_id: &str) -> Result<()> {
+    let Some(cost) = diagnosis_service.session_cost(diagnosis_id, "local-user").await? else {
+        return Ok(());
+    };
+
+    let usage = format!(
+        "{} request{}, {} prompt + {} completion tokens",
+        cost.usage.requests,
+        if cost.usage.requests == 1 { "" } else { "s" },
+        cost.usage.prompt_tokens,
+        cost.usage.completion_tokens
     );
-    spinner.set_message("Consulting AI...");
+    match cost.usd {
+        Some(usd) => out!(
+            "{} {} {}",
+            theme::muted("Cost:"),
+            theme::accent(ai_usage::format_usd(usd)),
+            theme::muted(format!("({})", usage))
+        ),
+        None => out!("{} {}", theme::muted("Usage:"), theme::muted(usage)),
+    }
 
-    let ai_adapter = AiAdapter::new()?;
-    let care_schedule = ai_adapter.generate_care_schedule(&plant_name).await?;
+    Ok(())
+}
 
-    spinner.finish_and_clear();
+fn transcript_service(db: &Database) -> TranscriptService {
+    TranscriptService::new(PlantRepository::new(db.clone()), DiagnosisRepository::new(db.clone()))
+}
+
+/// Answer the AI's questions and photo requests until it concludes,
+/// keeping the session's transcript up to date after every turn
+async fn run_diagnosis(
+    diagnosis_service: &DiagnosisService,
+    transcripts: &TranscriptService,
+    mut response: DiagnosisResponseDto,
+) -> Result<Box<DiagnosisConcludeResponse>> {
+    if let Some(path) = write_transcript(transcripts, response.diagnosis_id()).await {
+        note!("{} {}", theme::muted("📝 Live transcript:"), theme::muted(path.display()));
+        out!();
+    }
+
+    loop {
+        let (diagnosis_id, update_dto) = match response {
+            DiagnosisResponseDto::Ask(ask_response) => {
+                out!("{} {}", theme::heading("AI:"), ask_response.question);
+
+                let answer: String = Input::with_theme(&*theme::prompts())
+                    .with_prompt("You")
+                    .interact_text()?;
+
+                (ask_response.diagnosis_id, DiagnosisUpdateDto { message: answer, photo: None })
+            }
+            DiagnosisResponseDto::RequestPhoto(photo_request) => {
+                out!(
+                    "{} 📷 Please attach a photo of the {}.",
+                    theme::heading("AI:"),
+                    theme::warning(&photo_request.part)
+                );
+                if let Some(reason) = &photo_request.reason {
+                    out!("    {}", theme::muted(reason));
+                }
+
+                (photo_request.diagnosis_id, prompt_for_photo(&photo_request.part)?)
+            }
+            DiagnosisResponseDto::Conclude(conclude_response) => {
+                return Ok(conclude_response);
+            }
+        };
 
-    println!();
-    println!("{}", style("Care Schedule:").cyan().bold());
-    println!("  {} {}", style("Light:").dim(), care_schedule.light);
-    println!("  {} {}", style("Water:").dim(), care_schedule.water);
-    println!("  {} {}", style("Humidity:").dim(), care_schedule.humidity);
-    println!("  {} {}", style("Temperature:").dim(), care_schedule.temperature);
+        let progress = output::progress("AI is thinking...");
 
-    if !care_schedule.care_instructions.is_empty() {
-        println!("\n{}", style("Care Instructions:").cyan().bold());
-        println!("  {}", care_schedule.care_instructions);
+        response = diagnosis_service
+            .update_diagnosis(&diagnosis_id, update_dto, "local-user".to_string())
+            .await?;
+
+        progress.finish();
+        write_transcript(transcripts, &diagnosis_id).await;
+    }
+}
+
+/// Rewrite a session's transcript; a failure is only logged, as the
+/// session itself is saved either way
+async fn write_transcript(transcripts: &TranscriptService, diagnosis_id: &str) -> Option<PathBuf> {
+    match transcripts.write(diagnosis_id, "local-user").await {
+        Ok(path) => Some(path),
+        Err(e) => {
+            log::warn!("Could not write the transcript of {}: {:#}", diagnosis_id, e);
+            None
+        }
+    }
+}
+
+/// Show the care schedule changes a diagnosis proposed and apply them if
+/// the owner agrees
+async fn offer_care_update(
+    diagnosis_service: &DiagnosisService,
+    plant: &Plant,
+    conclusion: &DiagnosisConcludeResponse,
+) -> Result<()> {
+    let Some(care_update) = &conclusion.care_update else {
+        return Ok(());
+    };
+    let changes = care_update.changes.changes(&plant.care_schedule);
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    out!();
+    out!("{}", theme::heading("📋 Suggested care schedule changes:"));
+    if let Some(reason) = &care_update.reason {
+        out!("  {}", theme::muted(reason));
+    }
+    for (label, current, proposed) in &changes {
+        out!("  {}", theme::strong(format!("{}:", label)));
+        out!("    {}", theme::bad(format!("- {}", current)));
+        out!("    {}", theme::good(format!("+ {}", proposed)));
+    }
+
+    let apply = Confirm::with_theme(&*theme::prompts())
+        .with_prompt("Update the care schedule?")
+        .default(true)
+        .interact()?;
+
+    if apply {
+        diagnosis_service
+            .apply_care_update(&conclusion.diagnosis_id, "local-user")
+            .await?;
+        out!("{}", theme::success("✓ Care schedule updated"));
+    }
+
+    Ok(())
+}
+
+/// Point out the relatives of a plant found with a pest or disease, and
+/// offer to put a preventive inspection of each on the checklist
+async fn offer_family_inspections(db: &Database, conclusion: &DiagnosisConcludeResponse) -> Result<()> {
+    let family_service = family_service(db);
+    let Some(warning) = family_service.warning(&conclusion.diagnosis_id, "local-user").await? else {
+        return Ok(());
+    };
+
+    out!();
+    out!(
+        "{}",
+        theme::alert(format!(
+            "⚠ {} can spread to relatives of {}:",
+            warning.threats.join(", "),
+            warning.plant.display_name()
+        ))
+    );
+    for relative in &warning.relatives {
+        let note = if relative.susceptible { " (known to be susceptible)" } else { "" };
+        out!("  • {}{}", theme::accent(relative.plant.display_name()), theme::muted(note));
+    }
+
+    let schedule = Confirm::with_theme(&*theme::prompts())
+        .with_prompt("Add a preventive inspection of each to today's checklist?")
+        .default(true)
+        .interact()?;
+
+    if schedule {
+        let scheduled = family_service
+            .schedule_inspections(&warning, config::local_zone().today())
+            .await?;
+        out!(
+            "{}",
+            theme::success(format!("✓ {} inspection(s) added to the checklist", scheduled.len()))
+        );
+    }
+
+    Ok(())
+}
+
+fn print_containment(batch: &DiagnosisBatch, plan: &ContainmentPlan) {
+    out!("{}", theme::title("🛡  Containment Plan"));
+    out!();
+    out!("{} {}", theme::heading("Suspected cause:"), plan.suspected_cause);
+    for plant in &batch.plants {
+        let finding = match (&plant.finding, plant.severity) {
+            (Some(finding), Some(severity)) => format!("{} ({})", finding, styled_severity(severity)),
+            (Some(finding), None) => finding.clone(),
+            (None, _) => theme::muted("not diagnosed").to_string(),
+        };
+        out!("  • {}: {}", theme::strong(&plant.name), finding);
+    }
+
+    if !plan.isolate.is_empty() {
+        out!();
+        out!("{} {}", theme::danger("Isolate:"), plan.isolate.join(", "));
+    }
+
+    if !plan.steps.is_empty() {
+        out!();
+        out!("{}", theme::heading("Steps:"));
+        for (index, step) in plan.steps.iter().enumerate() {
+            out!("  {}. {}", index + 1, step);
+        }
+    }
+
+    if let Some(monitor) = &plan.monitor {
+        out!();
+        out!("{} {}", theme::alert("Keep an eye on:"), monitor);
+    }
+}
+
+/// Ask for the path of a requested photo; an empty path skips the request
+fn prompt_for_photo(part: &str) -> Result<DiagnosisUpdateDto> {
+    loop {
+        let path: String = Input::with_theme(&*theme::prompts())
+            .with_prompt("Photo path (leave empty to skip)")
+            .allow_empty(true)
+            .interact_text()?;
+
+        if path.trim().is_empty() {
+            return Ok(DiagnosisUpdateDto {
+                message: format!("I can't take a photo of the {} right now.", part),
+                photo: None,
+            });
+        }
+
+        match payload::read_image(Path::new(path.trim())) {
+            Ok(image_data) => {
+                return Ok(DiagnosisUpdateDto {
+                    message: format!("Here is a photo of the {}.", part),
+                    photo: Some(BASE64.encode(image_data)),
+                });
+            }
+            Err(e) => out!("{} {}", theme::bad("Could not read photo:"), e),
+        }
+    }
+}
+
+/// Asks which species to keep when the identifiers disagree. Plant.id's
+/// answer is kept when not running in a terminal.
+struct PromptSpeciesChooser;
+
+impl SpeciesChooser for PromptSpeciesChooser {
+    fn choose(&self, candidates: &[SpeciesCandidate]) -> Result<SpeciesChoice> {
+        if !Term::stdout().is_term() || output::is_json() {
+            return Ok(SpeciesChoice::Default);
+        }
+
+        out!("{}", theme::alert("🔍 The identifiers disagree on the species:"));
+        let mut options: Vec<String> = candidates
+            .iter()
+            .map(|c| match c.common_names.first() {
+                Some(common) => format!("{}, {}", c.describe(), common),
+                None => c.describe(),
+            })
+            .collect();
+        options.push("Let the AI look at the photo and decide".to_string());
+
+        let choice = Select::with_theme(&*theme::prompts())
+            .with_prompt("Which is it?")
+            .items(&options)
+            .default(0)
+            .interact_opt()?;
+
+        Ok(match choice {
+            Some(index) if index < candidates.len() => SpeciesChoice::Candidate(index),
+            Some(_) => SpeciesChoice::Tiebreak,
+            None => SpeciesChoice::Default,
+        })
+    }
+}
+
+/// The candidates of a disputed identification and how the species was chosen
+fn print_disagreement(disagreement: &IdentificationDisagreement) {
+    out!("  {} {}", theme::muted("Identifiers disagreed:"), {
+        let candidates: Vec<String> = disagreement.candidates.iter().map(|c| c.describe()).collect();
+        candidates.join(" vs ")
+    });
+    out!(
+        "  {} {} {}",
+        theme::muted("Species:"),
+        disagreement.species(),
+        theme::muted(format!("({})", match &disagreement.corrected_to {
+            Some(_) => format!("corrected by you, was {}", disagreement.chosen),
+            None => disagreement.resolution.describe(),
+        }))
+    );
+    if let SpeciesResolution::Tiebreak { reasoning, .. } = &disagreement.resolution {
+        out!("  {} {}", theme::muted("Why:"), reasoning);
+    }
+}
+
+/// Ask the user to rate an AI answer. Skipped when not running in a terminal.
+async fn prompt_for_feedback(
+    db: &Database,
+    kind: FeedbackKind,
+    subject: String,
+    model: String,
+) -> Result<()> {
+    if !Term::stdout().is_term() {
+        return Ok(());
+    }
+
+    let rating: String = Input::with_theme(&*theme::prompts())
+        .with_prompt(format!(
+            "Rate this answer {}-{} (leave empty to skip)",
+            MIN_RATING, MAX_RATING
+        ))
+        .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), String> {
+            match input.trim() {
+                "" => Ok(()),
+                value => match value.parse::<u8>() {
+                    Ok(rating) if (MIN_RATING..=MAX_RATING).contains(&rating) => Ok(()),
+                    _ => Err(format!("Enter a number from {} to {}", MIN_RATING, MAX_RATING)),
+                },
+            }
+        })
+        .interact_text()?;
+
+    let Ok(rating) = rating.trim().parse::<u8>() else {
+        return Ok(());
+    };
+
+    let comment: String = Input::with_theme(&*theme::prompts())
+        .with_prompt("Comment (optional)")
+        .allow_empty(true)
+        .interact_text()?;
+    let comment = Some(comment.trim().to_string()).filter(|c| !c.is_empty());
+
+    FeedbackRepository::new(db.clone())
+        .create(&Feedback::new(kind, subject, model, rating, comment)?)
+        .await?;
+    out!("{}", theme::good("✓ Thanks for the feedback"));
+
+    Ok(())
+}
+
+/// A severity in its triage color: red for critical down to dim for info
+fn styled_severity(severity: Severity) -> console::StyledObject<&'static str> {
+    let label = severity.as_str();
+    match severity {
+        Severity::Critical => theme::danger(label),
+        Severity::Serious => theme::bad(label),
+        Severity::Minor => theme::warning(label),
+        Severity::Info => theme::muted(label),
+    }
+}
+
+/// Show the confidence of a finding and its differential diagnoses,
+/// warning when the AI is unsure
+fn print_confidence(confidence: Option<f64>, alternatives: &[AlternativeDiagnosisDto]) {
+    if let Some(confidence) = confidence {
+        out!("  {} {:.0}%", theme::muted("Confidence:"), confidence * 100.0);
+        if confidence < LOW_CONFIDENCE_THRESHOLD {
+            out!(
+                "  {} {}",
+                theme::alert("⚠"),
+                theme::warning("Low confidence: watch the plant closely and consider a second opinion")
+            );
+        }
+    }
+
+    if !alternatives.is_empty() {
+        out!("  {}", theme::muted("Alternatives:"));
+        for alternative in alternatives {
+            out!("    {:>3.0}% {}", alternative.probability * 100.0, alternative.finding);
+        }
+    }
+}
+
+fn print_conclusion(conclusion: &DiagnosisConcludeResponse) {
+    out!("{}", theme::title("🎯 Diagnosis Complete!"));
+    out!();
+    out!("{}", theme::heading("Finding:"));
+    out!("  {}", conclusion.finding);
+    if let Some(severity) = conclusion.severity {
+        out!("  {} {}", theme::muted("Severity:"), styled_severity(severity));
+    }
+    out!();
+    out!("{}", theme::heading("Recommendation:"));
+    out!("  {}", conclusion.recommendation);
+
+    if conclusion.confidence.is_some() || !conclusion.alternatives.is_empty() {
+        out!();
+        print_confidence(conclusion.confidence, &conclusion.alternatives);
+    }
+
+    for name in &conclusion.encyclopedia_entries {
+        if let Some(entry) = encyclopedia::entries().iter().find(|e| &e.name == name) {
+            out!();
+            out!(
+                "{} {}",
+                theme::muted("📖 See also:"),
+                theme::good(format!("plant-care lookup {} \"{}\"", entry.kind.as_str(), entry.name))
+            );
+        }
+    }
+}
+
+pub async fn chat_with_plant(db: Database, plant_identifier: String, resume: bool) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    run_chat(db, &plant, resume).await
+}
+
+/// Interactive chat loop shared by `chat` and the post-diagnosis follow-up
+async fn run_chat(db: Database, plant: &Plant, resume: bool) -> Result<()> {
+    let chat_service = ChatService::new(
+        PlantRepository::new(db.clone()),
+        AttributeRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        ChatRepository::new(db.clone()),
+        UserRepository::new(db),
+        AiAdapter::new()?,
+    );
+
+    let existing = if resume {
+        chat_service.resume_chat(&plant.id, "local-user").await?
+    } else {
+        None
+    };
+
+    let mut session = match existing {
+        Some(session) => {
+            out!(
+                "{}",
+                theme::title(format!("💬 Resuming chat about {}", plant.display_name()))
+            );
+            for message in &session.messages {
+                let speaker = if message.role == "assistant" { "AI:" } else { "You:" };
+                out!("{} {}", theme::heading(speaker), message.content);
+            }
+            session
+        }
+        None => {
+            let session = chat_service.start_chat(&plant.id, "local-user").await?;
+            out!(
+                "{}",
+                theme::title(format!("💬 Chatting about {}", plant.display_name()))
+            );
+            session
+        }
+    };
+    out!("{}", theme::muted("Type 'exit' or leave empty to end the chat."));
+    out!();
+
+    loop {
+        let message: String = Input::with_theme(&*theme::prompts())
+            .with_prompt("You")
+            .allow_empty(true)
+            .interact_text()?;
+
+        let message = message.trim();
+        if message.is_empty() || message.eq_ignore_ascii_case("exit") || message.eq_ignore_ascii_case("quit") {
+            break;
+        }
+
+        let progress = output::progress("AI is thinking...");
+
+        let reply = chat_service
+            .send_message(&mut session, message.to_string(), "local-user")
+            .await?;
+
+        progress.finish();
+
+        out!("{} {}", theme::heading("AI:"), reply);
+        out!();
+    }
+
+    Ok(())
+}
+
+pub async fn ask(db: Database, question: String) -> Result<()> {
+    let question = question.trim();
+    if question.is_empty() {
+        anyhow::bail!("Ask a question about your plants, e.g. \"when did I last water the fern?\"");
+    }
+
+    let ask_service = AskService::new(
+        PlantRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        CareTaskRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        UserRepository::new(db),
+        AiAdapter::new()?,
+    );
+
+    let progress = output::progress("Looking through your records...");
+    let answer = ask_service.ask(question, "local-user").await?;
+    progress.finish();
+
+    if output::is_json() {
+        return output::json(&serde_json::json!({
+            "question": question,
+            "answer": answer.answer,
+            "consulted": answer.consulted,
+        }));
+    }
+
+    out!("{} {}", theme::heading("AI:"), answer.answer);
+    if !answer.consulted.is_empty() {
+        note!("{}", theme::muted(format!("Looked at: {}", answer.consulted.join(", "))));
+    }
+
+    Ok(())
+}
+
+pub async fn journal(
+    db: Database,
+    plant_identifier: String,
+    note: Option<String>,
+    problem: bool,
+) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let journal_repo = JournalRepository::new(db);
+
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    if let Some(note) = note {
+        journal_repo
+            .create(&JournalEntry::new(plant.id.clone(), note, problem))
+            .await?;
+        out!("{}", theme::success("✓ Journal entry added"));
+        return Ok(());
+    }
+
+    let entries = journal_repo.get_all_by_plant_id(&plant.id).await?;
+    if entries.is_empty() {
+        out!("{}", theme::warning("No journal entries for this plant."));
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("📓 Journal for {} ({} entries)", plant.display_name(), entries.len()))
+    );
+    out!();
+
+    for entry in entries {
+        let marker = if entry.is_problem {
+            theme::warning("⚠ ").to_string()
+        } else {
+            String::new()
+        };
+        out!(
+            "{} {}{}",
+            theme::muted(local(entry.created_at).format("%Y-%m-%d %H:%M")),
+            marker,
+            entry.note
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn set_attribute(
+    db: Database,
+    plant_identifier: String,
+    key: String,
+    value: Option<String>,
+    remove: bool,
+) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let attribute_repo = AttributeRepository::new(db);
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    if remove {
+        let key = plant_attribute::normalize_key(&key)?;
+        if attribute_repo.delete(&plant.id, &key).await? {
+            out!("{}", theme::success(format!("✓ Removed {} from {}", key, plant.display_name())));
+        } else {
+            out!("{}", theme::warning(format!("{} has no {}.", plant.display_name(), key)));
+        }
+        return Ok(());
+    }
+
+    let value = value.context("Give a value, or --remove to clear the attribute")?;
+    let attribute = PlantAttribute::new(&plant.id, &key, &value)?;
+    attribute_repo.save(&attribute).await?;
+    out!(
+        "{}",
+        theme::success(format!("✓ {} of {} set to {}", attribute.key, plant.display_name(), attribute.value))
+    );
+
+    Ok(())
+}
+
+pub async fn add_expense(
+    db: Database,
+    plant_identifier: String,
+    amount: String,
+    description: Option<String>,
+    purchase: bool,
+    on: Option<chrono::NaiveDate>,
+) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    let kind = if purchase { ExpenseKind::Purchase } else { ExpenseKind::Supply };
+    let mut expense = Expense::new(
+        plant.id.clone(),
+        kind,
+        expense::parse_amount(&amount)?,
+        description.unwrap_or_default(),
+    )?;
+    if let Some(day) = on {
+        let zone = config::local_zone();
+        if day > zone.today() {
+            anyhow::bail!("The date {} is in the future", day);
+        }
+        // Noon, so the expense stays on that day whatever the time zone
+        expense.spent_at = zone.start_of_day(day) + chrono::Duration::hours(12);
+    }
+
+    ExpenseRepository::new(db).create(&expense).await?;
+    out!(
+        "{}",
+        theme::success(format!(
+            "✓ {} ({}) logged for {}",
+            expense.description,
+            expense::format_amount(expense.amount_cents),
+            plant.display_name()
+        ))
+    );
+
+    Ok(())
+}
+
+pub async fn expenses(db: Database, plant_identifier: Option<String>) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let expense_repo = ExpenseRepository::new(db);
+
+    if let Some(identifier) = plant_identifier {
+        let plant = find_plant(&plant_repo, &identifier).await?;
+        let expenses = expense_repo.get_all_by_plant_id(&plant.id).await?;
+        if expenses.is_empty() {
+            out!("{}", theme::warning(format!("No expenses logged for {}.", plant.display_name())));
+            note!("Log one with {}", theme::good("plant-care expense <PLANT> 12.99 \"new pot\""));
+            return Ok(());
+        }
+
+        let total: i64 = expenses.iter().map(|e| e.amount_cents).sum();
+        out!(
+            "{}",
+            theme::title(format!("💰 Expenses for {} (total {})", plant.display_name(), expense::format_amount(total)))
+        );
+        out!();
+        for entry in &expenses {
+            out!(
+                "{}  {:>9}  {}",
+                theme::muted(local(entry.spent_at).format("%Y-%m-%d")),
+                expense::format_amount(entry.amount_cents),
+                entry.description
+            );
+        }
+        return Ok(());
+    }
+
+    let expenses = expense_repo.get_all_by_user("local-user").await?;
+    if expenses.is_empty() {
+        out!("{}", theme::warning("No expenses logged yet."));
+        note!("Log one with {}", theme::good("plant-care expense <PLANT> 12.99 \"new pot\""));
+        return Ok(());
+    }
+
+    let mut plants = plant_repo.get_all_by_user("local-user").await?;
+    plants.extend(plant_repo.get_archived_by_user("local-user").await?);
+    let names: std::collections::HashMap<&str, &str> = plants.iter().map(|p| (p.id.as_str(), p.display_name())).collect();
+
+    let total: i64 = expenses.iter().map(|e| e.amount_cents).sum();
+    out!("{}", theme::title(format!("💰 Plant Expenses (total {})", expense::format_amount(total))));
+
+    let mut by_plant: Vec<_> = expense::totals_by_plant(&expenses).into_iter().collect();
+    by_plant.sort_by_key(|(_, costs)| std::cmp::Reverse(costs.total()));
+    out!("\n{}", theme::heading("By plant:"));
+    for (plant_id, costs) in &by_plant {
+        out!(
+            "  {:<28} {:>9}  {}",
+            names.get(plant_id.as_str()).copied().unwrap_or(plant_id.as_str()),
+            expense::format_amount(costs.total()),
+            theme::muted(format!(
+                "purchase {}, supplies {}",
+                expense::format_amount(costs.purchase),
+                expense::format_amount(costs.supplies)
+            ))
+        );
+    }
+
+    out!("\n{}", theme::heading("By month:"));
+    for (month, cents) in expense::totals_by_month(&expenses, config::local_zone()) {
+        out!("  {:<28} {:>9}", month, expense::format_amount(cents));
+    }
+
+    Ok(())
+}
+
+pub async fn moisture(db: Database, plant_identifier: String, value: Option<u8>) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let moisture_repo = MoistureRepository::new(db);
+
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    if let Some(value) = value {
+        moisture_repo
+            .create(&MoistureReading::new(plant.id.clone(), value)?)
+            .await?;
+        out!("{}", theme::success(format!("✓ Moisture reading logged ({}%)", value)));
+    }
+
+    let readings = moisture_repo.get_all_by_plant_id(&plant.id).await?;
+    let now = chrono::Utc::now();
+
+    if value.is_none() {
+        let since = now - chrono::Duration::days(moisture_reading::TREND_WINDOW_DAYS);
+        let recent: Vec<&MoistureReading> = readings.iter().filter(|r| r.recorded_at >= since).collect();
+        if recent.is_empty() {
+            out!(
+                "{}",
+                theme::warning(format!(
+                    "No moisture readings in the last {} days.",
+                    moisture_reading::TREND_WINDOW_DAYS
+                ))
+            );
+            note!("Log one with {}", theme::good(format!("plant-care moisture <PLANT> <0-{}>", MAX_MOISTURE)));
+            return Ok(());
+        }
+
+        out!(
+            "{}",
+            theme::title(format!("💧 Soil moisture of {} ({} readings)", plant.display_name(), recent.len()))
+        );
+        out!();
+        for reading in recent {
+            out!(
+                "{} {:>3}% {}",
+                theme::muted(local(reading.recorded_at).format("%Y-%m-%d %H:%M")),
+                reading.value,
+                theme::water("█".repeat(reading.value.div_ceil(10) as usize))
+            );
+        }
+    }
+
+    out!();
+    match moisture_reading::analyze(&readings, &plant.care_schedule, now) {
+        Some(trend) => print_moisture_trend(&trend),
+        None => note!(
+            "{}",
+            theme::muted(format!(
+                "Log at least {} readings within {} days to see a trend.",
+                moisture_reading::MIN_TREND_READINGS,
+                moisture_reading::TREND_WINDOW_DAYS
+            ))
+        ),
+    }
+
+    Ok(())
+}
+
+/// Print a moisture trend, with its watering warning when there is one
+fn print_moisture_trend(trend: &MoistureTrend) {
+    out!("  {} {}", theme::muted("Trend:"), trend.summary());
+    out!("  {} every {} days", theme::muted("Watering:"), trend.watering_interval_days);
+    if let Some(warning) = trend.warning() {
+        out!("  {} {}", theme::warning("⚠"), theme::warning(warning));
+    }
+}
+
+pub async fn show_history(
+    db: Database,
+    plant_identifier: Option<String>,
+    filter: DiagnosisFilter,
+    limit: u32,
+    page: u32,
+    session: Option<String>,
+) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let diagnosis_repo = DiagnosisRepository::new(db);
+
+    // Without a plant, the history covers the whole collection
+    let plant = match &plant_identifier {
+        Some(identifier) => Some(find_plant(&plant_repo, identifier).await?),
+        None => None,
+    };
+    let plant_id = plant.as_ref().map(|p| p.id.as_str());
+
+    if let (Some(plant), Some(prefix)) = (&plant, session) {
+        return show_transcript(&diagnosis_repo, plant, &prefix).await;
+    }
+
+    let total = diagnosis_repo.count_matching("local-user", plant_id, &filter).await?;
+    if total == 0 {
+        return nothing_to_list(match (&plant, filter.is_empty()) {
+            (Some(_), true) => "No diagnosis history for this plant.",
+            (None, true) => "No diagnosis history yet.",
+            (_, false) => "No diagnosis sessions match those filters.",
+        });
+    }
+
+    let subject = match &plant {
+        Some(plant) => plant.display_name().to_string(),
+        None => "your collection".to_string(),
+    };
+    let limit = limit as usize;
+    let pages = total.div_ceil(limit);
+    if page as usize > pages {
+        anyhow::bail!(
+            "Page {} does not exist: {} has {} page{} of diagnosis history",
+            page,
+            subject,
+            pages,
+            if pages == 1 { "" } else { "s" }
+        );
+    }
+
+    let summaries = diagnosis_repo
+        .query_summaries("local-user", plant_id, &filter, (page as usize - 1) * limit, limit)
+        .await?;
+
+    // Plant names, when sessions of several plants are listed
+    let mut names: HashMap<String, Plant> = HashMap::new();
+    if plant.is_none() {
+        let mut plants = plant_repo.get_all_by_user("local-user").await?;
+        plants.extend(plant_repo.get_archived_by_user("local-user").await?);
+        names.extend(plants.into_iter().map(|p| (p.id.clone(), p)));
+    }
+
+    if output::is_json() {
+        return output::json(&serde_json::json!({
+            "plant_id": plant_id,
+            "total": total,
+            "page": page,
+            "pages": pages,
+            "sessions": summaries,
+        }));
+    }
+
+    out!(
+        "{}",
+        theme::title(format!(
+            "📋 Diagnosis History for {} ({} session{}{})",
+            subject,
+            total,
+            if total == 1 { "" } else { "s" },
+            if filter.is_empty() { "" } else { " matching" }
+        ))
+    );
+    out!();
+
+    for summary in &summaries {
+        out!("{}", theme::accent(&summary.id));
+        if let Some(other) = names.get(&summary.plant_id) {
+            out!("  {} {}", theme::muted("Plant:"), other.display_name());
+        }
+        if !summary.problem.is_empty() {
+            out!("  {} {}", theme::muted("Problem:"), summary.problem);
+        }
+        out!("  {} {:?}", theme::muted("Status:"), summary.status);
+        out!("  {} {}", theme::muted("Created:"), output::date(summary.created_at, "%Y-%m-%d %H:%M"));
+
+        if summary.status == DiagnosisStatus::Completed {
+            if let Some(result) = &summary.result {
+                print_diagnosis_result(result, false);
+            }
+        }
+        out!();
+    }
+
+    if pages > 1 {
+        out!("Page {} of {}", page, pages);
+        if (page as usize) < pages {
+            note!("{}", theme::muted(format!("Use --page {} to see older sessions", page + 1)));
+        }
+    }
+
+    // Offer to open a transcript when someone is at the terminal
+    if !Term::stdout().is_term() || output::is_quiet() {
+        note!(
+            "{}",
+            theme::muted(format!(
+                "Use 'history {} --session <ID>' to read a conversation",
+                plant_identifier.as_deref().unwrap_or("<PLANT>")
+            ))
+        );
+        return Ok(());
+    }
+
+    let mut options: Vec<String> = summaries
+        .iter()
+        .map(|s| format!("{}  {}", &s.id[..s.id.len().min(8)], s.problem))
+        .collect();
+    options.push("Done".to_string());
+    let choice = Select::with_theme(&*theme::prompts())
+        .with_prompt("Read a conversation?")
+        .items(&options)
+        .default(options.len() - 1)
+        .interact()?;
+
+    if let Some(summary) = summaries.get(choice) {
+        let owner = match &plant {
+            Some(plant) => plant,
+            None => names.get(&summary.plant_id).context("Plant not found")?,
+        };
+        out!();
+        show_transcript(&diagnosis_repo, owner, &summary.id).await?;
+    }
+
+    Ok(())
+}
+
+/// Print the full conversation of one of a plant's diagnosis sessions
+async fn show_transcript(diagnosis_repo: &DiagnosisRepository, plant: &Plant, prefix: &str) -> Result<()> {
+    let matches = diagnosis_repo.find_summaries_by_prefix(&plant.id, prefix).await?;
+    let summary = match matches.as_slice() {
+        [summary] => summary,
+        [] => anyhow::bail!("No diagnosis session of {} starts with '{}'", plant.display_name(), prefix),
+        _ => anyhow::bail!(
+            "'{}' matches {} sessions of {}; use more of the ID",
+            prefix,
+            matches.len(),
+            plant.display_name()
+        ),
+    };
+    let messages = diagnosis_repo.get_messages(&summary.id, 0, None).await?;
+
+    if output::is_json() {
+        return output::json(&serde_json::json!({ "session": summary, "messages": messages }));
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("🩺 Diagnosis of {} on {}", plant.display_name(), output::date(summary.created_at, "%Y-%m-%d %H:%M")))
+    );
+    out!("  {} {}", theme::muted("ID:"), summary.id);
+    out!("  {} {:?}", theme::muted("Status:"), summary.status);
+    out!();
+
+    if messages.is_empty() {
+        out!("{}", theme::warning("No conversation recorded for this session."));
+    }
+    for (number, message) in messages.iter().enumerate() {
+        print_turn(number + 1, message);
+    }
+
+    if let Some(result) = &summary.result {
+        out!();
+        out!("{}", theme::heading("Conclusion:"));
+        print_diagnosis_result(result, true);
+    }
+
+    Ok(())
+}
+
+/// Print a turn of a diagnosis conversation with its number, which
+/// `diagnose branch --at` refers to
+fn print_turn(number: usize, message: &DiagnosisMessage) {
+    let speaker = if message.role == "assistant" {
+        theme::heading("AI:")
+    } else {
+        theme::alert("You:")
+    };
+    out!("{} {} {}", theme::muted(format!("{:>2}.", number)), speaker, message.content);
+}
+
+/// Print the stored result of a concluded diagnosis
+fn print_diagnosis_result(result: &serde_json::Value, with_recommendation: bool) {
+    out!("  {} {}", theme::muted("Finding:"), result.get("finding").and_then(|v| v.as_str()).unwrap_or("N/A"));
+    if let Some(severity) = result["severity"].as_str().and_then(Severity::from_str) {
+        out!("  {} {}", theme::muted("Severity:"), styled_severity(severity));
+    }
+    if let Some(recommendation) = result["recommendation"].as_str().filter(|_| with_recommendation) {
+        out!("  {} {}", theme::muted("Recommendation:"), recommendation);
+    }
+    let alternatives: Vec<AlternativeDiagnosisDto> =
+        serde_json::from_value(result["alternatives"].clone()).unwrap_or_default();
+    print_confidence(result["confidence"].as_f64(), &alternatives);
+    if let Some(entries) = result.get("encyclopedia_entries").and_then(|v| v.as_array()) {
+        let names: Vec<&str> = entries.iter().filter_map(|e| e.as_str()).collect();
+        if !names.is_empty() {
+            out!("  {} {}", theme::muted("See also:"), names.join(", "));
+        }
+    }
+}
+
+pub async fn audit(db: Database, plant_identifier: String) -> Result<()> {
+    let audit_service = AuditService::new(PlantRepository::new(db.clone()), EventRepository::new(db));
+    let audit = audit_service.plant_history(&plant_identifier, "local-user").await?;
+
+    let count = audit.entries.len();
+    let mut title = format!(
+        "📜 Audit Log for {} ({} change{})",
+        audit.plant_name,
+        count,
+        if count == 1 { "" } else { "s" }
+    );
+    if audit.deleted {
+        title.push_str(" - deleted");
+    }
+    out!("{}", theme::title(title));
+    out!();
+
+    if audit.entries.is_empty() {
+        out!("{}", theme::warning("No changes recorded yet."));
+        return Ok(());
+    }
+
+    for entry in &audit.entries {
+        let event = &entry.event;
+        let mut subject = event.entity.as_str().to_lowercase().replace('_', " ");
+        if event.entity != AuditEntity::Plant {
+            subject.push_str(&format!(" {}", &event.entity_id[..event.entity_id.len().min(8)]));
+        }
+        out!(
+            "{} {} {}",
+            theme::muted(local(event.created_at).format("%Y-%m-%d %H:%M:%S")),
+            theme::accent(subject),
+            event.action.as_str().to_lowercase()
+        );
+        for change in &entry.changes {
+            out!("    {}", change);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn generate_care(db: Database, plant_name: String, room: Option<String>) -> Result<()> {
+    let room = match room {
+        Some(name) => {
+            let recorded = RoomRepository::new(db.clone()).get_by_name(&name, "local-user").await?;
+            if recorded.is_none() {
+                note!(
+                    "{}",
+                    theme::warning(format!("No room named '{}' is recorded; add it with `plant-care room set`", name))
+                );
+            }
+            Some(RoomProfile::of(&name, recorded.as_ref()))
+        }
+        None => None,
+    };
+    let ai_adapter = AiAdapter::new()?;
+    let Some(plant_name) = confirm_species_name(&ai_adapter, plant_name).await? else {
+        out!("{}", theme::warning("Cancelled"));
+        return Ok(());
+    };
+
+    note!("{}", theme::title(format!("🌿 Generating care schedule for {}...", plant_name)));
+
+    let progress = output::progress("Consulting AI...");
+
+    let model = ai_adapter.model().to_string();
+    let care_schedule_service = CareScheduleService::new(ai_adapter, species_data_adapter::from_env()?);
+    let generated = care_schedule_service.generate(&plant_name, None, room.as_ref()).await?;
+
+    progress.finish();
+    print_schedule_warnings(&generated.warnings);
+    if let Some(reference) = &generated.reference {
+        note!(
+            "{}",
+            theme::muted(format!(
+                "Based on {} data for {} ({})",
+                reference.source,
+                reference.scientific_name,
+                reference.care_fields().join(", ")
+            ))
+        );
+    }
+
+    print_care_schedule(&generated.schedule);
+
+    out!();
+    prompt_for_feedback(&db, FeedbackKind::CareSchedule, plant_name, model).await?;
+
+    Ok(())
+}
+
+/// The name to write a care schedule for: the one typed, when it is a known
+/// species or the owner keeps it, or the species they meant instead. Names
+/// the bundled catalog does not know are checked with the AI, and the owner
+/// asked, before a schedule is paid for. None when they cancelled.
+async fn confirm_species_name(ai_adapter: &AiAdapter, plant_name: String) -> Result<Option<String>> {
+    let interactive = Term::stdout().is_term() && !output::is_json();
+    // (name to use, how to offer it)
+    let suggestions: Vec<(String, String)> = match species_catalog::resolve(&plant_name) {
+        SpeciesMatch::Known(_) => return Ok(Some(plant_name)),
+        SpeciesMatch::Close(close) => close.iter().map(|s| (s.scientific_name.clone(), s.describe())).collect(),
+        SpeciesMatch::Unknown if !interactive => Vec::new(),
+        SpeciesMatch::Unknown => {
+            let spinner = output::spinner("Checking the name...");
+            let check = ai_adapter.check_species_name(&plant_name).await;
+            spinner.finish_and_clear();
+            match check {
+                Ok(check) => match check.suggestion.filter(|s| !s.trim().eq_ignore_ascii_case(plant_name.trim())) {
+                    Some(suggestion) => vec![(suggestion.clone(), suggestion)],
+                    None if check.is_plant => return Ok(Some(plant_name)),
+                    None => Vec::new(),
+                },
+                Err(e) => {
+                    note!("{}", theme::muted(format!("Could not check the name: {:#}", e)));
+                    Vec::new()
+                }
+            }
+        }
+    };
+
+    if !interactive {
+        let hint = match suggestions.first() {
+            Some((_, label)) => format!(" Did you mean {}?", label),
+            None => String::new(),
+        };
+        note!("{}", theme::warning(format!("'{}' is not a species we know.{}", plant_name, hint)));
+        return Ok(Some(plant_name));
+    }
+
+    if suggestions.is_empty() {
+        let generate = Confirm::with_theme(&*theme::prompts())
+            .with_prompt(format!("'{}' does not look like a plant. Write a care schedule for it anyway?", plant_name))
+            .default(false)
+            .interact()?;
+        return Ok(generate.then_some(plant_name));
+    }
+
+    let mut options: Vec<String> = suggestions.iter().map(|(_, label)| label.clone()).collect();
+    options.push(format!("No, keep '{}'", plant_name));
+    let choice = Select::with_theme(&*theme::prompts())
+        .with_prompt(format!("'{}' is not a species we know. Did you mean", plant_name))
+        .items(&options)
+        .default(0)
+        .interact_opt()?;
+
+    Ok(match choice {
+        Some(index) if index < suggestions.len() => Some(suggestions[index].0.clone()),
+        Some(_) => Some(plant_name),
+        None => None,
+    })
+}
+
+pub async fn species_lookup(name: String) -> Result<()> {
+    let Some(species_data) = species_data_adapter::from_env()? else {
+        out!("{}", theme::warning("No species database is configured."));
+        note!(
+            "Set {} or {} (with PERENUAL_API_KEY or TREFLE_API_TOKEN) to look plants up.",
+            theme::good("SPECIES_DATA_SOURCE=perenual"),
+            theme::good("SPECIES_DATA_SOURCE=trefle")
+        );
+        return Ok(());
+    };
+
+    let spinner = output::spinner(format!("Searching {}...", species_data.name()));
+    let profile = species_data.lookup(&name).await;
+    spinner.finish_and_clear();
+
+    let Some(profile) = profile? else {
+        out!("{}", theme::warning(format!("{} does not know '{}'.", species_data.name(), name)));
+        return Ok(());
+    };
+
+    out!("{}", theme::title(format!("📖 {}", profile.scientific_name)));
+    if let Some(common_name) = &profile.common_name {
+        out!("  {} {}", theme::muted("Common name:"), common_name);
+    }
+    if let Some(family) = &profile.family {
+        out!("  {} {}", theme::muted("Family:"), family);
+    }
+
+    out!("\n{}", theme::heading("Care Data:"));
+    let fields = [
+        ("Light:", &profile.light),
+        ("Water:", &profile.water),
+        ("Humidity:", &profile.humidity),
+        ("Temperature:", &profile.temperature),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            out!("  {} {}", theme::muted(label), value);
+        }
+    }
+    if profile.care_fields().is_empty() {
+        out!("  {}", theme::muted("None recorded for this species"));
+    }
+
+    out!();
+    note!("{}", theme::muted(format!("Source: {}", profile.source)));
+
+    Ok(())
+}
+
+pub async fn regenerate_care(db: Database, plant_identifier: Option<String>, jobs: usize) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plants = match plant_identifier {
+        Some(identifier) => vec![find_plant(&plant_repo, &identifier).await?],
+        None => plant_repo.get_all_by_user("local-user").await?,
+    };
+
+    if plants.is_empty() {
+        out!("{}", theme::warning("No plants in your collection yet."));
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("🌿 Regenerating care schedules for {} plant(s)...", plants.len()))
+    );
+
+    let progress = output::progress(&format!("Consulting AI ({} at a time)...", jobs.max(1)));
+
+    let care_schedule_service = CareScheduleService::new(AiAdapter::new()?, species_data_adapter::from_env()?);
+    let report = care_schedule_service
+        .regenerate(
+            &plant_repo,
+            &RoomRepository::new(db.clone()),
+            &UserRepository::new(db),
+            plants,
+            jobs,
+            progress.as_ref(),
+        )
+        .await?;
+
+    progress.finish();
+
+    print_regeneration_report(&report)
+}
+
+/// List the plants a regeneration updated and failed on, failing when any did
+fn print_regeneration_report(report: &RegenerationReport) -> Result<()> {
+    out!();
+    for (plant, version, warnings) in &report.updated {
+        let changed = match version.changes.len() {
+            0 => "unchanged".to_string(),
+            1 => "1 field changed".to_string(),
+            n => format!("{} fields changed", n),
+        };
+        out!("{} {} {}", theme::good("✓"), plant.display_name(), theme::muted(format!("({})", changed)));
+        print_schedule_warnings(warnings);
+    }
+    for (plant, error) in &report.failed {
+        out!("{} {} {}", theme::bad("✗"), plant.display_name(), theme::muted(format!("({})", error)));
+    }
+
+    out!();
+    out!(
+        "{} updated, {} failed",
+        theme::success(report.updated.len()),
+        theme::danger(report.failed.len())
+    );
+
+    if !report.failed.is_empty() {
+        anyhow::bail!("{} care schedule(s) could not be regenerated", report.failed.len());
+    }
+
+    Ok(())
+}
+
+pub async fn refresh_care(db: Database, stale_days: u32, dry_run: bool, jobs: usize, queue: bool) -> Result<()> {
+    if queue {
+        let job = Job::new(Some("local-user"), JobKind::RefreshSchedules)
+            .with_payload(serde_json::json!({ "stale_days": stale_days }));
+        let job = JobRepository::new(db).enqueue(&job).await?;
+        out!("{}", theme::success(format!("✓ Queued the refresh of schedules older than {} days", stale_days)));
+        note!("  {}", theme::muted(format!("Job {}; the job worker (plant-care jobs run) runs it", job.id)));
+        return Ok(());
+    }
+
+    let plant_repo = PlantRepository::new(db.clone());
+    let version_repo = ScheduleVersionRepository::new(db.clone());
+    let care_schedule_service = CareScheduleService::new(AiAdapter::new()?, species_data_adapter::from_env()?);
+
+    let stale = care_schedule_service
+        .find_stale(&plant_repo, &version_repo, "local-user", chrono::Duration::days(stale_days.into()))
+        .await?;
+
+    if stale.is_empty() {
+        out!(
+            "{}",
+            theme::good(format!("All care schedules are up to date (newer than {} days, same model).", stale_days))
+        );
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("🔄 {} stale care schedule(s):", stale.len()))
+    );
+    for (plant, staleness) in &stale {
+        out!("  {} {}", plant.display_name(), theme::muted(format!("({})", staleness.describe())));
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let progress = output::progress(&format!("Consulting AI ({} at a time)...", jobs.max(1)));
+    let plants = stale.into_iter().map(|(plant, _)| plant).collect();
+    let report = care_schedule_service
+        .regenerate(
+            &plant_repo,
+            &RoomRepository::new(db.clone()),
+            &UserRepository::new(db),
+            plants,
+            jobs,
+            progress.as_ref(),
+        )
+        .await?;
+    progress.finish();
+
+    print_regeneration_report(&report)
+}
+
+pub async fn schedule_history(db: Database, plant_identifier: String) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+    let versions = ScheduleVersionRepository::new(db).get_all_by_plant_id(&plant.id).await?;
+
+    out!(
+        "{}",
+        theme::title(format!("📅 Care Schedule History for {}", plant.display_name()))
+    );
+    out!();
+
+    if versions.is_empty() {
+        out!("{}", theme::warning("No versions of the care schedule were recorded yet."));
+        return Ok(());
+    }
+
+    for version in &versions {
+        out!(
+            "{} {} {}",
+            theme::strong(format!("Version {}", version.version)),
+            theme::accent(output::date(version.created_at, "%Y-%m-%d %H:%M")),
+            theme::muted(format!("({})", version.source.describe()))
+        );
+        if version.changes.is_empty() {
+            out!("  {}", theme::muted("No changes"));
+        }
+        for change in &version.changes {
+            out!("  {} {} {} {}", theme::muted(format!("{}:", change.field)), change.old, theme::muted("→"), change.new);
+        }
+        out!();
+    }
+
+    Ok(())
+}
+
+pub async fn rollback_care(db: Database, plant_identifier: String, version: u32) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let mut plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    let restored = care_schedule_service::rollback_schedule(
+        &plant_repo,
+        &ScheduleVersionRepository::new(db),
+        &mut plant,
+        version,
+    )
+    .await?;
+
+    out!(
+        "{}",
+        theme::success(format!(
+            "✓ Restored version {} of the care schedule for {} as version {}",
+            version,
+            plant.display_name(),
+            restored.version
+        ))
+    );
+    out!();
+    print_care_schedule(&plant.care_schedule);
+
+    Ok(())
+}
+
+pub async fn edit_care(db: Database, plant_identifier: String, update: CareScheduleUpdate) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let mut plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    let warnings = care_schedule_service::edit_plant_schedule(&plant_repo, &mut plant, &update).await?;
+    let care = CareOverrideRepository::new(db).get(&plant).await?;
+
+    if output::is_json() {
+        return output::json(&serde_json::json!({
+            "plant_id": plant.id,
+            "care_schedule": plant.care_schedule,
+            "care_baseline": care.baseline,
+            "care_overrides": care.overrides,
+            "warnings": warnings,
+        }));
+    }
+
+    out!(
+        "{}",
+        theme::success(format!("✓ Care schedule of {} updated", plant.display_name()))
+    );
+    print_schedule_warnings(&warnings);
+    print_merged_schedule(&care);
+
+    Ok(())
+}
+
+pub async fn reset_care(db: Database, plant_identifier: String) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let mut plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    let reset =
+        care_schedule_service::reset_overrides(&plant_repo, &CareOverrideRepository::new(db), &mut plant).await?;
+
+    if output::is_json() {
+        return output::json(&serde_json::json!({
+            "plant_id": plant.id,
+            "reset": reset,
+            "care_schedule": plant.care_schedule,
+        }));
+    }
+
+    if reset.is_empty() {
+        out!("{} has no changes of its own to reset.", plant.display_name());
+        return Ok(());
+    }
+    out!(
+        "{}",
+        theme::success(format!(
+            "✓ Reset the care schedule of {} to its species baseline",
+            plant.display_name()
+        ))
+    );
+    for field in &reset {
+        out!(
+            "  {} {} → {}",
+            theme::muted(format!("{}:", field.label)),
+            field.value,
+            field.baseline.as_deref().unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn triage(db: Database) -> Result<()> {
+    note!("{}", theme::title("🚑 Triaging your collection..."));
+
+    let triage_service = TriageService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        UserRepository::new(db.clone()),
+        AiAdapter::new()?,
+    );
+
+    let progress = output::progress("Ranking plants by urgency...");
+
+    let results = triage_service.triage("local-user").await?;
+
+    progress.finish();
+
+    if results.is_empty() {
+        out!("{}", theme::good("No plants with open symptoms. 🎉"));
+        return Ok(());
+    }
+
+    out!();
+    for (i, result) in results.iter().enumerate() {
+        out!("{} {}", theme::muted(format!("{}.", i + 1)), theme::heading(result.plant.display_name()));
+        if let Some(severity) = result.severity {
+            out!("  {} {}", theme::muted("Severity:"), styled_severity(severity));
+        }
+        if let Some(urgency) = result.urgency {
+            out!("  {} {}/10", theme::muted("Urgency:"), urgency);
+        }
+        if let Some(reason) = &result.reason {
+            out!("  {} {}", theme::muted("Why:"), reason);
+        }
+        for symptom in &result.symptoms {
+            out!("  {} {}", theme::warning("•"), symptom);
+        }
+        out!();
+    }
+
+    let most_urgent = &results[0];
+    let start = Confirm::with_theme(&*theme::prompts())
+        .with_prompt(format!("Start a diagnosis for {}?", most_urgent.plant.display_name()))
+        .default(true)
+        .interact()?;
+
+    if start {
+        let problem: String = Input::with_theme(&*theme::prompts())
+            .with_prompt("Describe the problem")
+            .with_initial_text(most_urgent.symptoms.join("; "))
+            .interact_text()?;
+
+        diagnose_plant(db, most_urgent.plant.id.clone(), problem, None, false).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn share_plant(db: Database, plant_identifier: String, out: String) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    let profile_service = ProfileService::new(
+        plant_repo,
+        DiagnosisRepository::new(db.clone()),
+        JournalRepository::new(db),
+        storage_adapter::from_env()?,
+    );
+
+    let profile = profile_service.export_profile(&plant.id, "local-user").await?;
+    fs::write(&out, serde_json::to_string_pretty(&profile)?)
+        .context("Failed to write profile file")?;
+
+    out!("{}", theme::success("✓ Plant profile exported"));
+    out!("  {} {}", theme::muted("File:"), out);
+    out!("  {} {}", theme::muted("Diagnoses:"), profile.diagnoses.len());
+    if profile.thumbnail.is_none() {
+        out!("  {}", theme::muted("No image embedded"));
+    }
+
+    Ok(())
+}
+
+pub async fn print_label(db: Database, plant_identifier: String, out: String) -> Result<()> {
+    let plant_repo = PlantRepository::new(db);
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    let png = label_service::render_label(&plant)?;
+    fs::write(&out, png).context("Failed to write label file")?;
+
+    out!("{}", theme::success("✓ Label created"));
+    out!("  {} {}", theme::muted("File:"), out);
+    out!("  {} {}", theme::muted("Plant:"), plant.display_name());
+    note!("Scan it later with {}", theme::good("plant-care scan <photo>"));
+
+    Ok(())
+}
+
+pub async fn print_care_sheet(db: Database, room: Option<String>, out: String) -> Result<()> {
+    let mut plants = PlantRepository::new(db).get_all_by_user("local-user").await?;
+    if let Some(room) = &room {
+        plants.retain(|plant| plant.room.as_deref().is_some_and(|r| r.eq_ignore_ascii_case(room)));
+    }
+    if plants.is_empty() {
+        match &room {
+            Some(room) => anyhow::bail!("No plants in room '{}'", room),
+            None => anyhow::bail!("No plants in your collection yet"),
+        }
+    }
+
+    let sheet = CareSheet::new(room.as_deref(), &plants, config::local_zone().today());
+    let contents = if out.to_lowercase().ends_with(".pdf") {
+        let cards: Vec<pdf::Card> = sheet
+            .plants
+            .iter()
+            .map(|entry| pdf::Card {
+                title: entry.name.clone(),
+                subtitle: sheet.plant_subtitle(entry),
+                fields: entry.fields().into_iter().map(|(label, text)| (label.to_string(), text)).collect(),
+            })
+            .collect();
+        let mut text = vec![sheet.title(), sheet.subtitle()];
+        for card in &cards {
+            text.push(card.title.clone());
+            text.extend(card.subtitle.clone());
+            text.extend(card.fields.iter().map(|(label, field)| format!("{} {}", label, field)));
+        }
+        print_unsupported_pdf_chars(&text.join("\n"));
+        pdf::from_cards(&sheet.title(), &sheet.subtitle(), &cards)
+    } else {
+        sheet.to_markdown().into_bytes()
+    };
+    fs::write(&out, contents).context("Failed to write care sheet")?;
+
+    out!("{}", theme::success("✓ Care sheet created"));
+    out!("  {} {}", theme::muted("File:"), out);
+    out!("  {} {}", theme::muted("Plants:"), sheet.plants.len());
+
+    Ok(())
+}
+
+pub async fn report_problems(db: Database, since: Option<NaiveDate>) -> Result<()> {
+    let zone = config::local_zone();
+    let since = match since {
+        Some(day) => zone.start_of_day(day),
+        None => Utc::now() - chrono::Duration::days(365),
+    };
+    let report = ReportService::new(PlantRepository::new(db.clone()), DiagnosisRepository::new(db))
+        .problems("local-user", since)
+        .await?;
+
+    if output::is_json() {
+        return output::json(&report);
+    }
+    if report.problems.is_empty() {
+        out!("{}", theme::good("✓ No problems found by diagnoses in this period."));
+        return Ok(());
+    }
+
+    out!("{}", theme::title("📋 Problem Report"));
+    out!(
+        "{}",
+        theme::muted(format!(
+            "{} diagnos{} since {}",
+            report.diagnoses,
+            if report.diagnoses == 1 { "is" } else { "es" },
+            zone.date(report.since).format("%b %-d, %Y")
+        ))
+    );
+    out!();
+
+    if report.recurring.is_empty() {
+        out!("{}", theme::good("✓ No problem has come back on a plant, species or room."));
+    } else {
+        out!("{}", theme::heading("Recurring"));
+        for recurring in &report.recurring {
+            out!(
+                "  ⚠ {} {}",
+                theme::warning(recurring.describe()),
+                theme::muted(format!("(last {})", zone.date(recurring.last_seen).format("%b %-d")))
+            );
+        }
+    }
+
+    out!();
+    out!("{}", theme::heading("All problems"));
+    let width = report.problems.iter().map(|p| p.problem.chars().count()).max().unwrap_or(0);
+    for problem in &report.problems {
+        out!(
+            "  {:<width$} {:>3}×  {}",
+            problem.problem,
+            problem.incidents.len(),
+            theme::muted(problem.plant_names().join(", ")),
+            width = width
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn scan_label(db: Database, image: String) -> Result<()> {
+    let image_data = fs::read(&image).with_context(|| format!("Failed to read image {}", image))?;
+    let plant_id = label_service::read_label(&image_data)?;
+
+    show_plant(db, plant_id).await
+}
+
+pub async fn import_profile(db: Database, file: String) -> Result<()> {
+    let contents = fs::read_to_string(&file).context("Failed to read profile file")?;
+    let profile: PlantProfileDto =
+        serde_json::from_str(&contents).context("File is not a valid plant profile")?;
+
+    let profile_service = ProfileService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        JournalRepository::new(db),
+        storage_adapter::from_env()?,
+    );
+
+    let plant = profile_service.import_profile(profile, "local-user".to_string()).await?;
+
+    out!("{}", theme::success("✓ Plant profile imported"));
+    out!("  {} {}", theme::muted("ID:"), plant.id);
+    out!("  {} {}", theme::muted("Name:"), plant.display_name());
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn export(
+    db: Database,
+    format: ExportFormat,
+    what: Option<ExportTarget>,
+    out: Option<String>,
+    encrypt: bool,
+    passphrase_file: Option<String>,
+    anonymize: bool,
+    pii_patterns: Vec<String>,
+) -> Result<()> {
+    if encrypt && !matches!(format, ExportFormat::Archive) {
+        anyhow::bail!("--encrypt is only supported with --format archive");
+    }
+    let anonymizer = anonymize.then(|| Anonymizer::new(&pii_patterns)).transpose()?;
+
+    let (contents, summary) = match format {
+        ExportFormat::Archive => {
+            let mut archive = backup_service(&db)?.create_archive("local-user").await?;
+            let mut summary = format!("{} plants", archive.plants.len());
+            if let Some(anonymizer) = &anonymizer {
+                let report = anonymizer.archive(&mut archive);
+                summary = format!(
+                    "{}, anonymized ({} photo(s) cleaned, {} left out; {} journal entries left out; \
+                     personal details removed from {} other text(s))",
+                    summary, report.photos_cleaned, report.photos_dropped, report.journal_removed, report.texts_redacted
+                );
+            }
+            let mut contents = serde_json::to_vec_pretty(&archive)?;
+            if encrypt {
+                let passphrase = read_passphrase(passphrase_file.as_deref(), true)?;
+                contents = crypto::encrypt(&contents, &passphrase)?;
+            }
+            (contents, summary)
+        }
+        ExportFormat::Csv | ExportFormat::Json => {
+            let what = what.context("--what is required for csv and json exports")?;
+            let export_service = ExportService::new(
+                PlantRepository::new(db.clone()),
+                DiagnosisRepository::new(db.clone()),
+                CareTaskRepository::new(db.clone()),
+                QuarantineRepository::new(db.clone()),
+                InspectionRepository::new(db),
+            );
+
+            let (contents, count) = match what {
+                ExportTarget::Plants => {
+                    let mut rows = export_service.plant_rows("local-user").await?;
+                    if let Some(anonymizer) = &anonymizer {
+                        anonymizer.plant_rows(&mut rows);
+                    }
+                    (render_export(&rows, format)?, rows.len())
+                }
+                ExportTarget::Diagnoses => {
+                    let rows = export_service.diagnosis_rows("local-user").await?;
+                    (render_export(&rows, format)?, rows.len())
+                }
+                ExportTarget::Reminders => {
+                    let rows = export_service.reminder_rows("local-user").await?;
+                    (render_export(&rows, format)?, rows.len())
+                }
+            };
+            (contents.into_bytes(), format!("{} rows", count))
+        }
+    };
+
+    match out {
+        Some(path) => {
+            fs::write(&path, contents).context("Failed to write export file")?;
+            eprintln!(
+                "{}",
+                theme::success(format!("✓ Exported {} to {}", summary, path))
+            );
+        }
+        None => std::io::Write::write_all(&mut std::io::stdout(), &contents)?,
+    }
+
+    Ok(())
+}
+
+pub async fn import_archive(
+    db: Database,
+    file: String,
+    passphrase_file: Option<String>,
+) -> Result<()> {
+    let mut contents = fs::read(&file).context("Failed to read archive file")?;
+    if crypto::is_encrypted(&contents) {
+        let passphrase = read_passphrase(passphrase_file.as_deref(), false)?;
+        contents = crypto::decrypt(&contents, &passphrase)?;
+    }
+
+    let archive: CollectionArchiveDto =
+        serde_json::from_slice(&contents).context("File is not a valid collection archive")?;
+
+    let summary = backup_service(&db)?
+        .restore_archive(archive, "local-user")
+        .await?;
+
+    out!(
+        "{}",
+        theme::success(format!("✓ Restored {} plants", summary.plants_restored))
+    );
+    if summary.plants_skipped > 0 {
+        out!(
+            "  {} {} plants already in your collection were skipped",
+            theme::muted("Note:"),
+            summary.plants_skipped
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn import_from_app(db: Database, file: String, app: SourceApp) -> Result<()> {
+    let contents = fs::read_to_string(&file).context("Failed to read export file")?;
+    let base_dir = Path::new(&file).parent().unwrap_or(Path::new("."));
+    let import_service = ImportService::new(
+        PlantRepository::new(db.clone()),
+        CareTaskRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        JobRepository::new(db),
+        storage_adapter::from_env()?,
+    );
+    let summary = import_service.import_csv(app, &contents, base_dir, "local-user").await?;
+
+    let count = summary.imported.len();
+    out!(
+        "{}",
+        theme::success(format!(
+            "✓ Imported {} plant{} from {}",
+            count,
+            if count == 1 { "" } else { "s" },
+            app.as_str()
+        ))
+    );
+    for plant in &summary.imported {
+        match &plant.scientific_name {
+            Some(species) if species != plant.display_name() => {
+                out!("  {} {}", plant.display_name(), theme::muted(format!("({})", species)))
+            }
+            _ => out!("  {}", plant.display_name()),
+        }
+    }
+    if summary.care_events > 0 {
+        out!(
+            "  {} {} watering and fertilizing events",
+            theme::muted("History:"),
+            summary.care_events
+        );
+    }
+    for (name, existing) in &summary.skipped {
+        out!(
+            "  {} {} is already in your collection as {}",
+            theme::muted("Skipped:"),
+            name,
+            existing.display_name()
+        );
+    }
+    for error in &summary.photo_errors {
+        out!("  {} {}", theme::warning("Photo left out:"), error);
+    }
+    if count > 0 {
+        note!(
+            "The plants have the default care schedule until queued jobs write theirs; run {}",
+            theme::good("plant-care jobs run --once")
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn snapshot_plant(db: Database, plant_identifier: String, out: Option<String>) -> Result<()> {
+    let plant = find_plant(&PlantRepository::new(db.clone()), &plant_identifier).await?;
+    let snapshot = backup_service(&db)?.create_snapshot(&plant.id, "local-user").await?;
+
+    let out = out.unwrap_or_else(|| {
+        format!(
+            "{}-{}.snapshot.json",
+            plant.id,
+            snapshot.taken_at.format("%Y%m%d-%H%M%S")
+        )
+    });
+    fs::write(&out, serde_json::to_string_pretty(&snapshot)?).context("Failed to write snapshot file")?;
+
+    out!("{}", theme::success(format!("✓ Snapshot of {} saved", plant.display_name())));
+    out!("  {} {}", theme::muted("File:"), out);
+    out!(
+        "  {} {} journal entries, {} diagnoses, {} readings, {} expenses, {} chats",
+        theme::muted("History:"),
+        snapshot.journal.len(),
+        snapshot.diagnoses.len(),
+        snapshot.moisture_readings.len(),
+        snapshot.expenses.len(),
+        snapshot.chats.len()
+    );
+    if snapshot.plant.image_url.is_some() && snapshot.image.is_none() {
+        out!("  {}", theme::warning("The photo could not be read and was left out"));
+    }
+    note!("Undo later changes with {}", theme::good(format!("plant-care restore-snapshot {}", out)));
+
+    Ok(())
+}
+
+pub async fn restore_snapshot(db: Database, file: String, yes: bool) -> Result<()> {
+    let contents = fs::read(&file).context("Failed to read snapshot file")?;
+    let snapshot: PlantSnapshotDto =
+        serde_json::from_slice(&contents).context("File is not a valid plant snapshot")?;
+    let name = snapshot.plant.display_name().to_string();
+
+    if !yes {
+        if !Term::stdout().is_term() {
+            anyhow::bail!("Pass --yes to restore a snapshot without a terminal");
+        }
+        out!(
+            "{} {} will be put back the way it was on {}. Its details, attributes and reminders are replaced; \
+             history added since is kept.",
+            theme::warning("⚠"),
+            theme::heading(&name),
+            local(snapshot.taken_at).format("%Y-%m-%d %H:%M")
+        );
+        let confirmed = Confirm::with_theme(&*theme::prompts())
+            .with_prompt("Restore the snapshot?")
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            out!("{}", theme::warning("Cancelled"));
+            return Ok(());
+        }
+    }
+
+    let summary = backup_service(&db)?.restore_snapshot(snapshot, "local-user").await?;
+
+    if summary.recreated {
+        out!("{}", theme::success(format!("✓ {} was created again from the snapshot", name)));
+    } else {
+        out!("{}", theme::success(format!("✓ {} restored", name)));
+    }
+    if summary.records_restored > 0 {
+        out!(
+            "  {} {} history record{} put back",
+            theme::muted("History:"),
+            summary.records_restored,
+            if summary.records_restored == 1 { "" } else { "s" }
+        );
+    }
+
+    Ok(())
+}
+
+fn backup_service(db: &Database) -> Result<BackupService> {
+    Ok(BackupService::new(
+        PlantRepository::new(db.clone()),
+        AttributeRepository::new(db.clone()),
+        ExpenseRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        MoistureRepository::new(db.clone()),
+        ChatRepository::new(db.clone()),
+        CareTaskRepository::new(db.clone()),
+        QuarantineRepository::new(db.clone()),
+        InspectionRepository::new(db.clone()),
+        CareOverrideRepository::new(db.clone()),
+        storage_adapter::from_env()?,
+    ))
+}
+
+/// Read an archive passphrase from a file, or prompt for it interactively
+fn read_passphrase(passphrase_file: Option<&str>, confirm: bool) -> Result<String> {
+    if let Some(path) = passphrase_file {
+        let passphrase = fs::read_to_string(path).context("Failed to read passphrase file")?;
+        return Ok(passphrase.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    let theme = theme::prompts();
+    let prompt = Password::with_theme(&*theme).with_prompt("Archive passphrase");
+    let passphrase = if confirm {
+        prompt
+            .with_confirmation("Confirm passphrase", "Passphrases don't match")
+            .interact()?
+    } else {
+        prompt.interact()?
+    };
+
+    Ok(passphrase)
+}
+
+fn render_export<T: serde::Serialize>(rows: &[T], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Csv => export_service::to_csv(rows),
+        ExportFormat::Json | ExportFormat::Archive => {
+            Ok(serde_json::to_string_pretty(rows)? + "\n")
+        }
+    }
+}
+
+pub async fn publish(db: Database, out: String, title: String) -> Result<()> {
+    let gallery_service = GalleryService::new(PlantRepository::new(db), storage_adapter::from_env()?);
+
+    let count = gallery_service
+        .publish("local-user", Path::new(&out), &title)
+        .await?;
+
+    out!(
+        "{}",
+        theme::success(format!("✓ Published {} plants to {}", count, out))
+    );
+    out!(
+        "  {} {}",
+        theme::muted("Open:"),
+        Path::new(&out).join("index.html").display()
+    );
+
+    Ok(())
+}
+
+pub async fn explain(db: Database, plant_identifier: String, topic: CareTopic) -> Result<()> {
+    let plant = find_plant(&PlantRepository::new(db.clone()), &plant_identifier).await?;
+    let explain_service = ExplainService::new(
+        PlantRepository::new(db.clone()),
+        AttributeRepository::new(db.clone()),
+        MoistureRepository::new(db.clone()),
+        RoomRepository::new(db.clone()),
+        ScheduleVersionRepository::new(db.clone()),
+        UserRepository::new(db.clone()),
+        AiAdapter::new()?,
+        search_adapter::from_env()?,
+        species_data_adapter::from_env()?,
+    );
+
+    let progress = output::progress("Looking up sources and asking the AI...");
+    let explanation = explain_service.explain(&plant.id, "local-user", topic).await;
+    progress.finish();
+    let explanation = explanation?;
+
+    if output::is_json() {
+        return output::json(&explanation);
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("💡 Why {} is cared for this way: {}", plant.display_name(), topic.as_str()))
+    );
+    out!();
+    out!("{} {}", theme::muted("Guidance:"), explanation.guidance);
+    if let Some(source) = &explanation.guidance_source {
+        out!("{} {}", theme::muted("Schedule:"), source);
+    }
+    out!();
+    out!("{}", explanation.explanation);
+
+    out!();
+    if explanation.citations.is_empty() {
+        out!("{}", theme::muted("No sources cited; this explanation is the AI's own."));
+    } else {
+        out!("{}", theme::heading("Sources:"));
+        for citation in &explanation.citations {
+            match &citation.url {
+                Some(url) => out!("  [{}] {} {}", citation.number, citation.title, theme::muted(url)),
+                None => out!("  [{}] {}", citation.number, citation.title),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn lookup(db: Database, kind: EntryKind, name: String) -> Result<()> {
+    let encyclopedia_service = EncyclopediaService::new(PlantRepository::new(db), AiAdapter::new().ok());
+
+    let progress = output::progress("Looking up encyclopedia...");
+
+    let result = encyclopedia_service.lookup(kind, &name, "local-user").await;
+
+    progress.finish();
+    let result = result?;
+    let entry = &result.entry;
+
+    out!("{}", theme::title(format!("📖 {}", entry.name)));
+    if !entry.aliases.is_empty() {
+        out!("  {} {}", theme::muted("Also known as:"), entry.aliases.join(", "));
+    }
+    if result.source == EntrySource::Ai {
+        out!("  {}", theme::warning("Not in the bundled encyclopedia; AI-generated entry"));
+    }
+
+    out!("\n{}", theme::heading("Symptoms:"));
+    for symptom in &entry.symptoms {
+        out!("  • {}", symptom);
+    }
+
+    out!("\n{}", theme::heading("Treatment:"));
+    for (i, step) in entry.treatment.iter().enumerate() {
+        out!("  {}. {}", i + 1, step);
+    }
+
+    out!("\n{}", theme::heading("Susceptible plants in your collection:"));
+    if result.susceptible_plants.is_empty() {
+        out!("  {}", theme::muted("None of your plants are known to be susceptible"));
+    } else {
+        for plant in &result.susceptible_plants {
+            out!("  {} {} {}", theme::warning("⚠"), plant.display_name(), theme::muted(&plant.id));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn init() -> Result<()> {
+    out!("{}", theme::title("🔑 Plant Care Setup"));
+    out!(
+        "{}",
+        theme::muted("API keys are stored in your OS keychain. Leave a key empty to keep the current value.")
+    );
+    out!();
+
+    let store = KeyringSecretStore;
+    let theme = theme::prompts();
+
+    for key in secrets::MANAGED_SECRETS {
+        let status = match store.get(key) {
+            Ok(Some(_)) => theme::good("stored"),
+            Ok(None) => theme::warning("not set"),
+            Err(_) => theme::bad("keychain unavailable"),
+        };
+        out!("  {} {}", theme::muted(format!("{}:", key)), status);
+
+        let value = Password::with_theme(&*theme)
+            .with_prompt(*key)
+            .allow_empty_password(true)
+            .interact()?;
+
+        if !value.is_empty() {
+            store.set(key, value.trim())?;
+            out!("  {}", theme::good("✓ Saved"));
+        }
+    }
+
+    note!();
+    note!(
+        "{}",
+        theme::muted("Environment variables with the same names still take precedence.")
+    );
+
+    Ok(())
+}
+
+pub async fn archive_plant(db: Database, plant_identifier: String, reason: ArchiveReason) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    let confirmed = Confirm::with_theme(&*theme::prompts())
+        .with_prompt(format!(
+            "Archive {} as {}?",
+            plant.display_name(),
+            reason.as_str().to_lowercase()
+        ))
+        .default(true)
+        .interact()?;
+
+    if !confirmed {
+        out!("{}", theme::warning("Cancelled"));
+        return Ok(());
+    }
+
+    let archive_service = ArchiveService::new(
+        plant_repo,
+        DiagnosisRepository::new(db.clone()),
+        UserRepository::new(db),
+    );
+    let plant = archive_service
+        .archive_plant(&plant.id, "local-user", reason)
+        .await?;
+
+    out!("{}", theme::success("✓ Plant archived"));
+    out!(
+        "  {} {} days in your collection",
+        theme::muted(format!("{}:", plant.display_name())),
+        plant.lifespan_days()
+    );
+
+    Ok(())
+}
+
+pub async fn transfer_plant(
+    db: Database,
+    plant_identifier: String,
+    to: String,
+    detach_history: bool,
+    yes: bool,
+) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    if !yes {
+        let confirmed = Confirm::with_theme(&*theme::prompts())
+            .with_prompt(format!(
+                "Give {} to {}{}?",
+                plant.display_name(),
+                to.trim(),
+                if detach_history { ", keeping its diagnosis history" } else { "" }
+            ))
+            .default(true)
+            .interact()?;
+        if !confirmed {
+            out!("{}", theme::warning("Cancelled"));
+            return Ok(());
+        }
+    }
+
+    let archive_service = ArchiveService::new(
+        plant_repo,
+        DiagnosisRepository::new(db.clone()),
+        UserRepository::new(db),
+    );
+    let transfer = archive_service
+        .transfer_plant(&plant.id, "local-user", &to, detach_history)
+        .await?;
+
+    out!(
+        "{}",
+        theme::success(format!("✓ {} now belongs to {}", transfer.plant.display_name(), transfer.plant.user_id))
+    );
+    match &transfer.history_copy {
+        Some(copy) => {
+            out!(
+                "  {} {} session{} kept on an archived copy ({})",
+                theme::muted("Diagnosis history:"),
+                transfer.detached_sessions,
+                if transfer.detached_sessions == 1 { "" } else { "s" },
+                copy.id
+            );
+            note!("See it with {}", theme::good(format!("plant-care history {}", copy.id)));
+        }
+        None => out!("  {}", theme::muted("Its reminders and diagnosis history went with it")),
+    }
+
+    Ok(())
+}
+
+pub async fn graveyard(db: Database) -> Result<()> {
+    let archive_service = ArchiveService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        UserRepository::new(db),
+    );
+    let report = archive_service.graveyard("local-user").await?;
+
+    if report.entries.is_empty() {
+        out!("{}", theme::good("No archived plants. Keep it up! 🌱"));
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("🪦 Plant Graveyard ({} plants)", report.entries.len()))
+    );
+    out!();
+
+    for entry in &report.entries {
+        let Some(archive) = &entry.plant.archived else {
+            continue;
+        };
+        out!(
+            "{} {}",
+            theme::heading(entry.plant.display_name()),
+            theme::muted(format!("({})", archive.reason.as_str().to_lowercase()))
+        );
+        out!(
+            "  {} {} → {} ({} days)",
+            theme::muted("Kept:"),
+            local(entry.plant.created_at).format("%Y-%m-%d"),
+            local(archive.archived_at).format("%Y-%m-%d"),
+            entry.lifespan_days
+        );
+        if let Some(finding) = &entry.final_finding {
+            out!("  {} {}", theme::muted("Last diagnosis:"), finding);
+        }
+    }
+
+    out!();
+    out!("{}", theme::heading("Stats:"));
+    for (reason, count) in &report.reason_counts {
+        out!("  {} {}", theme::muted(format!("{}:", reason.as_str().to_lowercase())), count);
+    }
+    if let Some(average) = report.average_lifespan_days {
+        out!("  {} {:.0} days", theme::muted("Average lifespan (died):"), average);
+    }
+
+    if !report.causes_of_death.is_empty() {
+        out!();
+        out!("{}", theme::heading("Most common causes of death:"));
+        for (cause, count) in &report.causes_of_death {
+            out!("  {} {}", theme::bad(format!("{}×", count)), cause);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn check_in(
+    db: Database,
+    plant_identifier: String,
+    worked: Option<bool>,
+    note: Option<String>,
+) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+
+    let worked = match worked {
+        Some(worked) => worked,
+        None => Confirm::with_theme(&*theme::prompts())
+            .with_prompt(format!("Did the last treatment help {}?", plant.display_name()))
+            .interact()?,
+    };
+
+    let outcome_service = OutcomeService::new(
+        plant_repo,
+        DiagnosisRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        OutcomeRepository::new(db),
+    );
+    let outcome = outcome_service
+        .check_in(&plant.id, "local-user", worked, note)
+        .await?;
+
+    out!("{}", theme::success("✓ Check-in recorded"));
+    out!("  {} {}", theme::muted("Finding:"), outcome.finding);
+    out!("  {} {}", theme::muted("Recommendation:"), outcome.recommendation);
+
+    let experience = outcome_service
+        .species_experience(plant.species(), "local-user")
+        .await?;
+    out!();
+    out!(
+        "{}",
+        theme::heading(format!("Treatment history for {}:", plant.display_name()))
+    );
+    for entry in &experience {
+        out!(
+            "  {} {} → {}",
+            theme::muted(format!("{}✓ {}✗", entry.worked, entry.failed)),
+            entry.finding,
+            entry.recommendation
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn feedback_stats(db: Database) -> Result<()> {
+    let entries = FeedbackRepository::new(db).get_all().await?;
+
+    if entries.is_empty() {
+        out!("{}", theme::warning("No feedback given yet."));
+        note!("You are asked to rate each diagnosis and care schedule.");
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("⭐ Feedback ({} ratings)", entries.len()))
+    );
+    out!();
+
+    for kind in [FeedbackKind::Diagnosis, FeedbackKind::CareSchedule] {
+        let ratings: Vec<u8> = entries
+            .iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| e.rating)
+            .collect();
+        if ratings.is_empty() {
+            continue;
+        }
+        let average = ratings.iter().map(|&r| f64::from(r)).sum::<f64>() / ratings.len() as f64;
+        out!(
+            "  {} {:.1} ({} ratings)",
+            theme::muted(format!("{}:", feedback_kind_label(kind))),
+            average,
+            ratings.len()
+        );
+    }
+
+    out!();
+    out!("{}", theme::heading("By model:"));
+    for rating in feedback::compare_models(&entries) {
+        out!(
+            "  {:.1} {} {}",
+            rating.average,
+            theme::strong(&rating.model),
+            theme::muted(format!(
+                "({}, {} ratings)",
+                feedback_kind_label(rating.kind).to_lowercase(),
+                rating.count
+            ))
+        );
+    }
+
+    let comments: Vec<&Feedback> = entries.iter().filter(|e| e.comment.is_some()).take(5).collect();
+    if !comments.is_empty() {
+        out!();
+        out!("{}", theme::heading("Recent comments:"));
+        for entry in comments {
+            out!(
+                "  {} {} {}",
+                theme::warning(format!("{}★", entry.rating)),
+                entry.comment.as_deref().unwrap_or_default(),
+                theme::muted(format!("({})", entry.model))
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn feedback_kind_label(kind: FeedbackKind) -> &'static str {
+    match kind {
+        FeedbackKind::Diagnosis => "Diagnoses",
+        FeedbackKind::CareSchedule => "Care schedules",
+    }
+}
+
+pub async fn eval(models: Vec<String>, cases_path: String) -> Result<()> {
+    let yaml = fs::read_to_string(&cases_path)
+        .with_context(|| format!("Failed to read {}", cases_path))?;
+    let cases = eval_service::parse_cases(&yaml)?;
+
+    out!(
+        "{}",
+        theme::title(format!(
+            "🧪 Evaluating {} models on {} cases...",
+            models.len(),
+            cases.len()
+        ))
+    );
+
+    let eval_service = EvalService::new(storage_adapter::from_env()?, search_adapter::from_env()?);
+    let mut reports = Vec::new();
+
+    for model in &models {
+        let progress = output::progress(&format!("Running {}...", model));
+
+        let report = eval_service.run_model(model, &cases).await?;
+        progress.finish();
+
+        print_model_report(&report);
+        reports.push(report);
+    }
+
+    out!("{}", theme::heading("Comparison:"));
+    out!(
+        "  {:<40} {:>6} {:>6} {:>6} {:>8} {:>6}",
+        "Model", "Passed", "Score", "Turns", "Time", "Errors"
+    );
+    reports.sort_by(|a, b| b.mean_score().total_cmp(&a.mean_score()));
+    for report in &reports {
+        out!(
+            "  {:<40} {:>6} {:>6.2} {:>6.1} {:>7.1}s {:>6}",
+            report.model,
+            format!("{}/{}", report.passed(), report.results.len()),
+            report.mean_score(),
+            report.mean_turns(),
+            report.mean_seconds(),
+            report.errors()
+        );
+    }
+
+    Ok(())
+}
+
+fn print_model_report(report: &ModelReport) {
+    out!("{}", theme::heading(&report.model));
+    for result in &report.results {
+        let mark = if result.score >= 1.0 {
+            theme::good("✓")
+        } else if result.score > 0.0 {
+            theme::warning("~")
+        } else {
+            theme::bad("✗")
+        };
+        let outcome = match (&result.finding, &result.error) {
+            (Some(finding), _) => finding.clone(),
+            (None, Some(error)) => theme::bad(format!("error: {}", error)).to_string(),
+            (None, None) => String::new(),
+        };
+        out!("  {} {} {}", mark, theme::muted(format!("{}:", result.case_name)), outcome);
+    }
+    out!();
+}
+
+/// `db` with a plant cache, unless PLANT_CACHE_TTL_SECS=0, for the servers
+/// and bots that read the same plants over and over
+fn long_running(db: Database) -> Database {
+    match PlantCache::from_env() {
+        Some(cache) => db.with_plant_cache(cache),
+        None => db,
+    }
+}
+
+pub async fn telegram_bot(db: Database) -> Result<()> {
+    let bot = TelegramBot::from_env(long_running(db))?;
+
+    out!("{}", theme::title("🤖 Telegram bot running (Ctrl-C to stop)"));
+    bot.run().await;
+
+    Ok(())
+}
+
+pub async fn discord_bot(db: Database) -> Result<()> {
+    let bot = DiscordBot::from_env(long_running(db))?;
+
+    out!("{}", theme::title("🤖 Discord bot running (Ctrl-C to stop)"));
+    bot.run().await
+}
+
+pub async fn http_server(db: Database, addr: std::net::SocketAddr) -> Result<()> {
+    let has_keys = !ApiKeyService::new(UserRepository::new(db.clone())).users().await?.is_empty();
+    let server = HttpServer::from_env(long_running(db), addr)?;
+
+    out!(
+        "{}",
+        theme::title(format!("🌐 HTTP API listening on http://{} (Ctrl-C to stop)", addr))
+    );
+    note!("API docs: {}", theme::accent(format!("http://{}/docs", addr)));
+    if !has_keys {
+        note!(
+            "{} Every request needs an API key; create one with {}",
+            theme::warning("⚠"),
+            theme::good("plant-cli api-key create")
+        );
+    }
+    server.run().await
+}
+
+pub async fn api_key_create(db: Database, user: String) -> Result<()> {
+    let key = ApiKeyService::new(UserRepository::new(db)).issue(&user).await?;
+
+    out!("{}", theme::success(format!("✓ API key created for {}", user)));
+    out!("  {}", key);
+    note!("Send it as {}. It is not shown again.", theme::accent("Authorization: Bearer <key>"));
+
+    Ok(())
+}
+
+pub async fn api_key_revoke(db: Database, user: String) -> Result<()> {
+    ApiKeyService::new(UserRepository::new(db)).revoke(&user).await?;
+
+    out!("{}", theme::success(format!("✓ API key of {} revoked", user)));
+
+    Ok(())
+}
+
+pub async fn api_key_list(db: Database) -> Result<()> {
+    let users = ApiKeyService::new(UserRepository::new(db)).users().await?;
+    if users.is_empty() {
+        out!("{}", theme::warning("No API keys."));
+        note!("Create one with {}", theme::good("plant-cli api-key create [USER]"));
+        return Ok(());
+    }
+
+    out!("{}", theme::title(format!("🔑 API Keys ({})", users.len())));
+    for user in &users {
+        out!(
+            "  {} {}",
+            theme::strong(&user.id),
+            theme::muted(format!("(updated {})", local(user.updated_at).format("%Y-%m-%d")))
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn grpc_server(db: Database, addr: std::net::SocketAddr) -> Result<()> {
+    let server = GrpcServer::from_env(long_running(db), addr)?;
+
+    out!(
+        "{}",
+        theme::title(format!("📡 gRPC API listening on {} (Ctrl-C to stop)", addr))
+    );
+    if !server.requires_token() {
+        note!("No GRPC_TOKEN set: calls are not authenticated");
+    }
+    server.run().await
+}
+
+pub async fn notify_test() -> Result<()> {
+    let event_bus = EventBus::new(notification_adapter::from_env()?);
+    if event_bus.is_empty() {
+        out!("{}", theme::warning("No webhooks configured."));
+        note!(
+            "Set {} to a comma-separated list of json:<url>, slack:<url>, ntfy:<url> or email:<address>.",
+            theme::good("NOTIFY_WEBHOOKS")
+        );
+        return Ok(());
+    }
+
+    let event = NotificationEvent::PlantAdded {
+        plant_id: "test".to_string(),
+        plant_name: "Test plant".to_string(),
+    };
+    let webhooks = event_bus.len();
+    let delivered = event_bus.publish_to_all(event).await;
+
+    if delivered == webhooks {
+        out!("{}", theme::success(format!("✓ Delivered to {} webhooks", delivered)));
+    } else {
+        anyhow::bail!(
+            "Delivered to {} of {} webhooks (run with RUST_LOG=warn for details)",
+            delivered,
+            webhooks
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn vacation(
+    db: Database,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+    out: Option<String>,
+) -> Result<()> {
+    let vacation_service = VacationService::new(
+        PlantRepository::new(db.clone()),
+        UserRepository::new(db),
+        AiAdapter::new()?,
+    );
+
+    let progress = output::progress("Planning care while you're away...");
+    let plan = vacation_service.plan("local-user", from, to).await;
+    progress.finish();
+    let plan = plan?;
+
+    if let Some(path) = out {
+        let markdown = plan.to_markdown();
+        let contents = if path.to_lowercase().ends_with(".pdf") {
+            print_unsupported_pdf_chars(&markdown);
+            pdf::from_markdown(&markdown)
+        } else {
+            markdown.into_bytes()
+        };
+        fs::write(&path, contents).context("Failed to write vacation plan")?;
+
+        out!("{}", theme::success("✓ Vacation plan saved"));
+        out!("  {} {}", theme::muted("File:"), path);
+        out!("  {} {} days, {} plants", theme::muted("Trip:"), plan.trip_days(), plan.plants.len());
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!(
+            "🧳 Vacation Care Plan ({} to {}, {} days)",
+            plan.from.format("%b %-d"),
+            plan.to.format("%b %-d"),
+            plan.trip_days()
+        ))
+    );
+
+    out!("\n{}", theme::heading("Before You Leave:"));
+    for item in &plan.prep {
+        out!("  ☐ {}", item);
+    }
+
+    out!("\n{}", theme::heading("Plant Sitter Instructions:"));
+    for entry in &plan.plants {
+        out!();
+        match &entry.room {
+            Some(room) => out!("  {} {}", theme::strong(&entry.plant_name), theme::muted(format!("({})", room))),
+            None => out!("  {}", theme::strong(&entry.plant_name)),
+        }
+        if entry.watering_dates.is_empty() {
+            out!("    {} {}", theme::muted("Water on:"), theme::good("no watering needed"));
+        } else {
+            let dates: Vec<String> = entry
+                .watering_dates
+                .iter()
+                .map(|d| d.format("%a %b %-d").to_string())
+                .collect();
+            out!("    {} {}", theme::muted("Water on:"), theme::water(dates.join(", ")));
+            out!("    {} {}", theme::muted("How:"), entry.watering);
+        }
+        out!("    {} {}", theme::muted("Light:"), entry.light);
+        if let Some(note) = &entry.note {
+            out!("    {} {}", theme::muted("Note:"), note);
+        }
+    }
+
+    out!();
+    note!(
+        "Save it for your sitter with {} or {}",
+        theme::good("--out plan.md"),
+        theme::good("--out plan.pdf")
+    );
+    note!(
+        "Leaving them this device? Restrict it with {}",
+        theme::good("plant-cli sitter start")
+    );
+
+    Ok(())
+}
+
+pub async fn sitter_start(db: Database) -> Result<()> {
+    let sitter_service = SitterService::new(UserRepository::new(db));
+    if sitter_service.role("local-user").await? == UserRole::Sitter {
+        anyhow::bail!("Sitter mode is already on");
+    }
+
+    note!("Choose a PIN of at least {} characters to end sitter mode later.", MIN_PIN_LEN);
+    let pin = Password::with_theme(&*theme::prompts())
+        .with_prompt("Owner PIN")
+        .with_confirmation("Confirm PIN", "PINs don't match")
+        .interact()?;
+    sitter_service.start("local-user", &pin).await?;
+
+    out!("{}", theme::success("✓ Sitter mode on"));
+    out!("  {} view plants and care instructions, mark tasks done, log moisture", theme::muted("Allowed:"));
+    out!("  {} delete or archive plants, diagnose, chat", theme::muted("Blocked:"));
+    note!("Hand back with {}", theme::good("plant-cli sitter stop"));
+
+    Ok(())
+}
+
+pub async fn sitter_stop(db: Database) -> Result<()> {
+    let sitter_service = SitterService::new(UserRepository::new(db));
+    if sitter_service.role("local-user").await? != UserRole::Sitter {
+        anyhow::bail!("Sitter mode is not on");
+    }
+
+    let pin = Password::with_theme(&*theme::prompts())
+        .with_prompt("Owner PIN")
+        .interact()?;
+    sitter_service.end("local-user", &pin).await?;
+
+    out!("{}", theme::success("✓ Sitter mode off, welcome back!"));
+
+    Ok(())
+}
+
+pub async fn sitter_status(db: Database) -> Result<()> {
+    let role = SitterService::new(UserRepository::new(db)).role("local-user").await?;
+    match role {
+        UserRole::Sitter => out!("{}", theme::alert("🔒 Sitter mode is on")),
+        UserRole::Owner => out!("{}", theme::good("Sitter mode is off")),
+    }
+
+    Ok(())
+}
+
+fn sync_service(db: &Database) -> Result<SyncService> {
+    Ok(SyncService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        ChatRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        MoistureRepository::new(db.clone()),
+        CareOverrideRepository::new(db.clone()),
+        SyncRepository::new(db.clone()),
+        sync_adapter::from_env()?,
+    ))
+}
+
+pub async fn sync(db: Database, queue: bool) -> Result<()> {
+    let sync_service = sync_service(&db)?;
+    if queue {
+        let job = JobRepository::new(db).enqueue(&Job::new(Some("local-user"), JobKind::Sync)).await?;
+        out!("{}", theme::success("✓ Queued a sync; changed records keep their latest version"));
+        note!("  {}", theme::muted(format!("Job {}; the job worker (plant-care jobs run) runs it", job.id)));
+        return Ok(());
+    }
+
+    let spinner = output::spinner("Syncing...");
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+
+    let interactive = Term::stdout().is_term();
+    let report = sync_service
+        .sync("local-user", |conflict| {
+            if !interactive {
+                return Ok(sync_service::last_write_wins(conflict));
+            }
+            spinner.suspend(|| resolve_conflict(conflict))
+        })
+        .await;
+    spinner.finish_and_clear();
+    let report = report?;
+
+    out!("{}", theme::success(format!("✓ Synced with {}", report.location)));
+    if report.baseline > 0 {
+        out!("  {} {}", theme::muted("Existing records shared:"), report.baseline);
+    }
+    out!("  {} {}", theme::muted("Changes pulled:"), report.pulled);
+    out!("  {} {}", theme::muted("Changes pushed:"), report.pushed);
+    if report.conflicts > 0 {
+        out!("  {} {}", theme::muted("Conflicts resolved:"), report.conflicts);
+    }
+
+    Ok(())
+}
+
+/// Ask which version of a record changed on both devices to keep
+fn resolve_conflict(conflict: &SyncConflict) -> Result<Resolution> {
+    out!();
+    out!(
+        "{}",
+        theme::alert(format!("⚠️  {} was changed on this device and on another one", conflict.label()))
+    );
+    let changes = describe_changes(&conflict.remote.payload, &conflict.local.payload);
+    if !changes.is_empty() {
+        out!("  {}", theme::muted("Other device → this device:"));
+        for change in changes {
+            out!("    {}", change);
+        }
+    }
+
+    let options = [
+        format!(
+            "Keep this device's version ({})",
+            local(conflict.local.created_at).format("%Y-%m-%d %H:%M")
+        ),
+        format!(
+            "Keep the other device's version ({})",
+            local(conflict.remote.created_at).format("%Y-%m-%d %H:%M")
+        ),
+    ];
+    let default = match sync_service::last_write_wins(conflict) {
+        Resolution::KeepLocal => 0,
+        Resolution::KeepRemote => 1,
+    };
+    let choice = Select::with_theme(&*theme::prompts())
+        .items(&options)
+        .default(default)
+        .interact()?;
+
+    Ok(if choice == 0 {
+        Resolution::KeepLocal
+    } else {
+        Resolution::KeepRemote
+    })
+}
+
+pub async fn today(db: Database, done: Option<String>) -> Result<()> {
+    let checklist_service = ChecklistService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        OutcomeRepository::new(db.clone()),
+        CareTaskRepository::new(db.clone()),
+        QuarantineRepository::new(db.clone()),
+        InspectionRepository::new(db.clone()),
+        EventBus::new(notification_adapter::from_env()?).with_queue(JobRepository::new(db.clone())),
+    );
+    let today = config::local_zone().today();
+
+    if let Some(task_id) = done {
+        let item = checklist_service.find(&task_id, "local-user", today).await?;
+        if item.kind == ChecklistKind::FollowUp {
+            // Follow-ups are answered with a check-in on the treatment
+            return check_in(db, item.plant_id, None, None).await;
+        }
+
+        checklist_service.complete(&item, "local-user").await?;
+        out!(
+            "{}",
+            theme::success(format!("✓ Done: {} - {}", item.plant_name, item.description))
+        );
+
+        if item.kind == ChecklistKind::Water {
+            let streak_service = streak_service(&db)?;
+            let streaks = streak_service.streaks("local-user", today).await?;
+            let milestones = StreakService::milestones(&streaks, &item.plant_id);
+            for milestone in &milestones {
+                out!("{} - {}", theme::title(milestone.title()), milestone.message());
+            }
+            streak_service.notify(&milestones).await;
+        }
+        return Ok(());
+    }
+
+    let items = checklist_service.checklist("local-user", today).await?;
+    checklist_service.notify_overdue(&items).await;
+    let streaks = streak_service(&db)?.streaks("local-user", today).await?;
+
+    if items.is_empty() {
+        out!("{}", theme::good("Nothing to do today. Enjoy your plants! 🌿"));
+        print_streak(&streaks.collection);
+
+        let overviews = query_service(&db).collection("local-user").await?;
+        let next = overviews
+            .iter()
+            .filter_map(|o| o.next_reminder().map(|r| (o, r)))
+            .min_by_key(|(_, r)| r.due);
+        if let Some((overview, reminder)) = next {
+            note!(
+                "{} {} - {}",
+                theme::muted("Next up:"),
+                theme::accent(overview.plant.display_name()),
+                reminder_label(reminder, today)
+            );
+        }
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("📅 Care Checklist for {} ({} tasks)", today.format("%a %Y-%m-%d"), items.len()))
+    );
+    print_streak(&streaks.collection);
+    out!();
+
+    for item in &items {
+        let icon = match item.kind {
+            ChecklistKind::Water => "💧",
+            ChecklistKind::Fertilize => "🌱",
+            ChecklistKind::Treatment => "💊",
+            ChecklistKind::FollowUp => "🔁",
+            ChecklistKind::Inspect => "🔍",
+        };
+        let overdue = if item.overdue_days > 0 {
+            let days = format!(
+                "({} day{} overdue)",
+                item.overdue_days,
+                if item.overdue_days == 1 { "" } else { "s" }
+            );
+            format!(" {}", theme::bad(days))
+        } else {
+            String::new()
+        };
+        out!(
+            "☐ {} {} - {}{}",
+            icon,
+            theme::heading(&item.plant_name),
+            item.description,
+            overdue
+        );
+        out!("  {} {}", theme::muted("ID:"), item.id);
+    }
+
+    note!();
+    note!(
+        "Mark a task done with {}",
+        theme::good("plant-care today --done <ID>")
+    );
+
+    Ok(())
+}
+
+fn quarantine_service(db: &Database) -> QuarantineService {
+    QuarantineService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        JournalRepository::new(db.clone()),
+        QuarantineRepository::new(db.clone()),
+    )
+}
+
+pub async fn quarantine_plant(db: Database, plant_identifier: String, reason: Option<String>) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let plant = find_plant(&plant_repo, &plant_identifier).await?;
+    let quarantine = quarantine_service(&db).quarantine(&plant.id, "local-user", reason).await?;
+
+    out!(
+        "{}",
+        theme::danger(format!("🚫 {} is quarantined", plant.display_name()))
+    );
+    out!(
+        "  {} {}",
+        theme::muted("Next inspection:"),
+        quarantine.next_inspection(config::local_zone()).format("%Y-%m-%d")
+    );
+
+    let plants = plant_repo.get_all_by_user("local-user").await?;
+    let neighbours: Vec<&str> = quarantine::neighbours(&plants, &[quarantine])
+        .into_iter()
+        .map(|(plant, _)| plant.display_name())
+        .collect();
+    if !neighbours.is_empty() {
+        out!();
+        out!(
+            "{} {}",
+            theme::alert("⚠ Check these plants in the same room for pests:"),
+            neighbours.join(", ")
+        );
+    }
+
+    note!();
+    note!(
+        "Inspections show up in {}. Once a follow-up {} finds the plant clean, run {}",
+        theme::good("plant-care today"),
+        theme::good("plant-care diagnose"),
+        theme::good(format!("plant-care quarantine release {}", plant_identifier))
+    );
+
+    Ok(())
+}
+
+pub async fn quarantine_list(db: Database) -> Result<()> {
+    let quarantines = quarantine_service(&db).get_active("local-user").await?;
+    if output::is_json() {
+        return output::json(&quarantines);
+    }
+    if quarantines.is_empty() {
+        out!("{}", theme::good("✓ No plant is quarantined."));
+        return Ok(());
+    }
+
+    let plant_repo = PlantRepository::new(db);
+    let zone = config::local_zone();
+    out!("{}", theme::danger(format!("🚫 Quarantined Plants ({})", quarantines.len())));
+    out!();
+    for quarantine in &quarantines {
+        let name = plant_repo
+            .get_by_id(&quarantine.plant_id, "local-user")
+            .await?
+            .map(|p| p.display_name().to_string())
+            .unwrap_or_else(|| quarantine.plant_id.clone());
+        out!("{}", theme::heading(name));
+        out!("  {}", quarantine_label(quarantine));
+        out!(
+            "  {} {}",
+            theme::muted("Next inspection:"),
+            quarantine.next_inspection(zone).format("%Y-%m-%d")
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn release_quarantine(db: Database, plant_identifier: String) -> Result<()> {
+    let plant = find_plant(&PlantRepository::new(db.clone()), &plant_identifier).await?;
+    quarantine_service(&db).release(&plant.id, "local-user").await?;
+
+    out!(
+        "{}",
+        theme::success(format!("✓ {} is out of quarantine", plant.display_name()))
+    );
+    Ok(())
+}
+
+fn family_service(db: &Database) -> FamilyService {
+    FamilyService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        InspectionRepository::new(db.clone()),
+    )
+}
+
+pub async fn families(db: Database) -> Result<()> {
+    let groups = family_service(&db).groups("local-user").await?;
+    if groups.is_empty() {
+        return nothing_to_list("No plant has a known family or genus yet.");
+    }
+    if output::is_json() {
+        return output::json(&groups);
+    }
+
+    out!("{}", theme::title(format!("🌿 Plant Families ({})", groups.len())));
+    for group in &groups {
+        let rank = match group.rank {
+            GroupRank::Family => "family",
+            GroupRank::Genus => "genus",
+        };
+        out!();
+        out!(
+            "{} {}",
+            theme::heading(&group.name),
+            theme::muted(format!("({}, {} plant(s))", rank, group.plants.len()))
+        );
+        for plant in &group.plants {
+            match &plant.scientific_name {
+                Some(scientific_name) => out!("  • {} {}", plant.display_name(), theme::scientific(scientific_name)),
+                None => out!("  • {}", plant.display_name()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn template_service(db: &Database) -> TemplateService {
+    TemplateService::new(TemplateRepository::new(db.clone()), PlantRepository::new(db.clone()))
+}
+
+pub async fn template_list(db: Database) -> Result<()> {
+    let templates = template_service(&db).list("local-user").await?;
+
+    if templates.is_empty() {
+        out!("{}", theme::warning("No care schedule templates yet."));
+        note!(
+            "Use {} to save a plant's schedule as one.",
+            theme::good("plant-care template create <NAME> --from <PLANT>")
+        );
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("📋 Care Schedule Templates ({})", templates.len()))
+    );
+    out!();
+    for template in &templates {
+        out!("{}", theme::heading(&template.name));
+        out!("  {} {}", theme::muted("Light:"), template.schedule.light);
+        out!("  {} {}", theme::muted("Water:"), template.schedule.water);
+    }
+
+    Ok(())
+}
+
+pub async fn template_create(db: Database, name: String, plant_identifier: String) -> Result<()> {
+    let template = template_service(&db)
+        .create_from_plant(&name, &plant_identifier, "local-user")
+        .await?;
+
+    out!("{}", theme::success(format!("✓ Template '{}' created", template.name)));
+    print_care_schedule(&template.schedule);
+    note!();
+    note!(
+        "Use it with {}",
+        theme::good(format!("plant-care add --image <path> --template \"{}\"", template.name))
+    );
+
+    Ok(())
+}
+
+pub async fn template_show(db: Database, name: String) -> Result<()> {
+    let template = template_service(&db).get(&name, "local-user").await?;
+
+    out!("{}", theme::title(&template.name));
+    out!("  {} {}", theme::muted("Updated:"), local(template.updated_at).format("%Y-%m-%d %H:%M"));
+    print_care_schedule(&template.schedule);
+
+    Ok(())
+}
+
+pub async fn template_edit(
+    db: Database,
+    name: String,
+    update: CareScheduleUpdate,
+    rename: Option<String>,
+) -> Result<()> {
+    let (template, warnings) = template_service(&db)
+        .edit(&name, &update, rename.as_deref(), "local-user")
+        .await?;
+
+    out!("{}", theme::success(format!("✓ Template '{}' updated", template.name)));
+    print_schedule_warnings(&warnings);
+    print_care_schedule(&template.schedule);
+
+    Ok(())
+}
+
+pub async fn template_apply(db: Database, name: String, plant_identifier: String) -> Result<()> {
+    let (template, plant) = template_service(&db)
+        .apply(&name, &plant_identifier, "local-user")
+        .await?;
+
+    out!(
+        "{}",
+        theme::success(format!("✓ Applied template '{}' to {}", template.name, plant.display_name()))
+    );
+
+    Ok(())
+}
+
+pub async fn template_delete(db: Database, name: String) -> Result<()> {
+    let template = template_service(&db).delete(&name, "local-user").await?;
+
+    out!("{}", theme::success(format!("✓ Template '{}' deleted", template.name)));
+
+    Ok(())
+}
+
+pub async fn bulk(
+    db: Database,
+    filter: Vec<String>,
+    actions: Vec<BulkAction>,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let filter = PlantFilter::parse(&filter)?;
+    let bulk_service = BulkService::new(
+        PlantRepository::new(db.clone()),
+        TemplateRepository::new(db.clone()),
+        UserRepository::new(db.clone()),
+    );
+    let changes = bulk_service.preview(&filter, &actions, "local-user").await?;
+
+    if changes.is_empty() {
+        out!("{}", theme::warning("No plants would change."));
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!(
+            "📦 {} plant{} will change:",
+            changes.len(),
+            if changes.len() == 1 { "" } else { "s" }
+        ))
+    );
+    out!();
+    for change in &changes {
+        out!(
+            "  {} {} {}",
+            theme::heading(change.plant.display_name()),
+            theme::muted(&change.plant.id[..change.plant.id.len().min(8)]),
+            change.changes.join(", ")
+        );
+    }
+    out!();
+
+    if dry_run {
+        out!("{}", theme::warning("Dry run: nothing was changed"));
+        return Ok(());
+    }
+
+    if !yes {
+        if !Term::stdout().is_term() {
+            anyhow::bail!("Pass --yes to apply bulk changes without a terminal");
+        }
+        let confirmed = Confirm::with_theme(&*theme::prompts())
+            .with_prompt("Apply these changes?")
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            out!("{}", theme::warning("Cancelled"));
+            return Ok(());
+        }
+    }
+
+    let updated = bulk_service.apply(&changes, "local-user").await?;
+    out!(
+        "{}",
+        theme::success(format!("✓ Updated {} plant{}", updated, if updated == 1 { "" } else { "s" }))
+    );
+
+    Ok(())
+}
+
+pub async fn care_stats(db: Database) -> Result<()> {
+    let today = config::local_zone().today();
+    let streaks = streak_service(&db)?.streaks("local-user", today).await?;
+
+    out!("{}", theme::title("🏅 Care Streaks"));
+    out!();
+    out!(
+        "{} {} on-time watering{} in a row {}",
+        theme::heading("Collection:"),
+        streaks.collection.current,
+        if streaks.collection.current == 1 { "" } else { "s" },
+        theme::muted(format!("(best {})", streaks.collection.best))
+    );
+    if let Some(badge) = streaks.collection.badge() {
+        out!("  {} {} {}", theme::muted("Badge:"), badge.emoji, theme::good(badge.name));
+    }
+    if let Some(next) = Badge::next(streaks.collection.current) {
+        out!(
+            "  {} {} {} at {} in a row",
+            theme::muted("Next:"),
+            next.emoji,
+            next.name,
+            next.streak
+        );
+    }
+
+    let (watered, never): (Vec<_>, Vec<_>) = streaks.plants.iter().partition(|p| p.waterings > 0);
+    if !watered.is_empty() {
+        out!();
+        out!("{}", theme::heading("Plants:"));
+        for entry in watered {
+            let badge = entry
+                .streak
+                .badge()
+                .map(|b| format!(" {} {}", b.emoji, theme::good(b.name)))
+                .unwrap_or_default();
+            out!(
+                "  {} {} in a row {}{}",
+                theme::accent(entry.plant.display_name()),
+                entry.streak.current,
+                theme::muted(format!("(best {})", entry.streak.best)),
+                badge
+            );
+        }
+    }
+    if !never.is_empty() {
+        note!();
+        note!(
+            "{}",
+            theme::muted(format!(
+                "{} plant{} not watered through the checklist yet",
+                never.len(),
+                if never.len() == 1 { "" } else { "s" }
+            ))
+        );
+    }
+    note!("Mark waterings done with {}", theme::good("plant-care today --done <ID>"));
+
+    Ok(())
+}
+
+/// The collection's watering streak, when there is one
+fn print_streak(streak: &Streak) {
+    if streak.current == 0 {
+        return;
+    }
+    let badge = streak
+        .badge()
+        .map(|b| format!(" {} {}", b.emoji, theme::good(b.name)))
+        .unwrap_or_default();
+    out!(
+        "🔥 {} on-time watering{} in a row{}",
+        streak.current,
+        if streak.current == 1 { "" } else { "s" },
+        badge
+    );
+}
+
+fn streak_service(db: &Database) -> Result<StreakService> {
+    Ok(StreakService::new(
+        PlantRepository::new(db.clone()),
+        CareTaskRepository::new(db.clone()),
+        EventRepository::new(db.clone()),
+        EventBus::new(notification_adapter::from_env()?).with_queue(JobRepository::new(db.clone())),
+    ))
+}
+
+fn shopping_service(db: &Database) -> ShoppingService {
+    ShoppingService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        ShoppingRepository::new(db.clone()),
+    )
+}
+
+/// Start of the window of diagnoses the shopping list draws from
+fn shopping_since(days: u32) -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc::now() - chrono::Duration::days(days as i64)
+}
+
+pub async fn shopping_list(db: Database, days: u32) -> Result<()> {
+    let items = shopping_service(&db).list("local-user", shopping_since(days)).await?;
+
+    if items.is_empty() {
+        out!("{}", theme::warning("Nothing on your shopping list."));
+        note!(
+            "Supplies recommended by diagnoses from the last {} days appear here; add your own with {}",
+            days,
+            theme::good("plant-care shopping add <ITEM>")
+        );
+        return Ok(());
+    }
+
+    let count = items.len();
+    out!(
+        "{}",
+        theme::title(format!("🛒 Shopping List ({} item{})", count, if count == 1 { "" } else { "s" }))
+    );
+    out!();
+
+    for item in &items {
+        let mut line = format!("☐ {}", theme::heading(&item.name));
+        if item.added_manually && item.reasons.is_empty() {
+            line.push_str(&format!(" {}", theme::muted("(added by you)")));
+        }
+        out!("{}", line);
+        if !item.reasons.is_empty() {
+            out!("    {} {}", theme::muted("For:"), item.reasons.join("; "));
+        }
+    }
+
+    out!();
+    note!(
+        "{}",
+        theme::muted("Use 'shopping remove <ITEM>' once bought, or 'shopping export' for a Markdown copy")
+    );
+
+    Ok(())
+}
+
+pub async fn shopping_add(db: Database, item: String) -> Result<()> {
+    let entry = shopping_service(&db).add(&item, "local-user").await?;
+    out!("{}", theme::success(format!("✓ Added {} to your shopping list", entry.name)));
+
+    Ok(())
+}
+
+pub async fn shopping_remove(db: Database, item: String) -> Result<()> {
+    let entry = shopping_service(&db).remove(&item, "local-user").await?;
+    out!("{}", theme::success(format!("✓ Removed {} from your shopping list", entry.name)));
+
+    Ok(())
+}
+
+pub async fn shopping_export(db: Database, days: u32, out: Option<String>) -> Result<()> {
+    let items = shopping_service(&db).list("local-user", shopping_since(days)).await?;
+    let markdown = shopping_service::to_markdown(&items, config::local_zone().today());
+
+    match out {
+        Some(path) => {
+            fs::write(&path, markdown).context("Failed to write shopping list")?;
+            eprintln!(
+                "{}",
+                theme::success(format!("✓ Exported {} items to {}", items.len(), path))
+            );
+        }
+        None => print!("{}", markdown),
+    }
+
+    Ok(())
+}
+
+pub async fn room_set(
+    db: Database,
+    name: String,
+    window: Option<Option<WindowOrientation>>,
+    sun_hours: Option<f64>,
+    environment: Environment,
+) -> Result<()> {
+    let room_repo = RoomRepository::new(db);
+    let existing = room_repo.get_by_name(&name, "local-user").await?;
+    // A new window makes the old sun hours meaningless
+    let (window, sun_hours) = match (window, &existing) {
+        (Some(window), _) => (window, sun_hours),
+        (None, Some(existing)) => (existing.window, sun_hours.or(existing.sun_hours)),
+        (None, None) => anyhow::bail!("'{}' is a new room; pass --window to record it", name.trim()),
+    };
+    let environment = existing
+        .as_ref()
+        .map_or(environment, |existing| existing.environment.updated_with(environment));
+    let room = Room::new("local-user", &name, window, sun_hours)?.with_environment(environment)?;
+    room_repo.save(&room).await?;
+
+    out!(
+        "{}",
+        theme::success(format!("✓ {} gets {}", room.name, room.light_level()))
+    );
+    if sun_hours.is_none() && window.is_some() {
+        note!(
+            "Estimated {} hours of direct sun from the window; pass {} if you know better.",
+            room.direct_sun_hours(),
+            theme::good("--sun-hours")
+        );
+    }
+    if room.environment.is_empty() {
+        note!(
+            "Add its {} so care schedules and diagnoses account for them.",
+            theme::good("--temperature, --humidity, --drafty and --heated")
+        );
+    } else {
+        note!("  {}", theme::muted(room.profile().describe()));
+    }
+
+    Ok(())
+}
+
+pub async fn room_list(db: Database) -> Result<()> {
+    let rooms = RoomRepository::new(db).get_all_by_user("local-user").await?;
+    if rooms.is_empty() {
+        out!("{}", theme::warning("No rooms recorded yet."));
+        note!("Record one with {}", theme::good("plant-care room set \"living room\" --window south"));
+        return Ok(());
+    }
+
+    out!("{}", theme::title(format!("🏠 Your Rooms ({})", rooms.len())));
+    out!();
+    for room in &rooms {
+        let window = match room.window {
+            Some(window) => format!("{} window", window.as_str().to_lowercase()),
+            None => "no window".to_string(),
+        };
+        let hours = match room.sun_hours {
+            Some(hours) => format!("{}h of sun", hours),
+            None => format!("~{}h of sun", room.direct_sun_hours()),
+        };
+        out!(
+            "{} {}",
+            theme::heading(&room.name),
+            theme::muted(format!("({}, {}): {}", window, hours, room.light_level()))
+        );
+        let climate = climate(&room.environment);
+        if !climate.is_empty() {
+            out!("  {}", theme::muted(climate.join(", ")));
+        }
+    }
+
+    Ok(())
+}
+
+/// What is recorded of a room's climate, e.g. ["21°C", "45% humidity", "drafty"]
+fn climate(environment: &Environment) -> Vec<String> {
+    let mut climate = Vec::new();
+    if let Some(temperature) = environment.temperature {
+        climate.push(format!("{}°C", temperature));
+    }
+    if let Some(humidity) = environment.humidity {
+        climate.push(format!("{}% humidity", humidity));
+    }
+    match environment.drafty {
+        Some(true) => climate.push("drafty".to_string()),
+        Some(false) => climate.push("no drafts".to_string()),
+        None => {}
+    }
+    match environment.heated {
+        Some(true) => climate.push("heated".to_string()),
+        Some(false) => climate.push("unheated".to_string()),
+        None => {}
+    }
+    climate
+}
+
+pub async fn room_remove(db: Database, name: String) -> Result<()> {
+    if RoomRepository::new(db).delete(&name, "local-user").await? {
+        out!("{}", theme::success(format!("✓ Forgot the light in {}", name.trim())));
+    } else {
+        out!("{}", theme::warning(format!("No room named '{}'.", name.trim())));
+    }
+
+    Ok(())
+}
+
+pub async fn advise_placement(db: Database, explain: bool) -> Result<()> {
+    let room_repo = RoomRepository::new(db.clone());
+    if room_repo.get_all_by_user("local-user").await?.is_empty() {
+        out!("{}", theme::warning("No room has its light recorded yet."));
+        note!("Record one with {}", theme::good("plant-care room set \"living room\" --window south"));
+        return Ok(());
+    }
+
+    let placement_service = PlacementService::new(
+        PlantRepository::new(db.clone()),
+        room_repo,
+        UserRepository::new(db),
+        AiAdapter::new()?,
+    );
+    let advice = placement_service.advise("local-user", explain).await?;
+
+    out!("{}", theme::title("🪴 Placement Advice"));
+    out!();
+
+    let moves: Vec<&PlacementAdvice> = advice.iter().filter(|a| a.is_move()).collect();
+    if moves.is_empty() {
+        out!("{}", theme::good("No moves to suggest."));
+    }
+    for entry in &moves {
+        let from = entry.room.as_deref().unwrap_or("no room");
+        let to = entry.suggested_room.as_deref().unwrap_or_default();
+        out!("{} {} → {}", theme::heading(format!("Move {}:", entry.plant_name)), from, to);
+        if let (Some(requirement), Some(light)) = (entry.requirement, entry.suggested_light) {
+            let current = entry
+                .light
+                .map(|l| format!(", {} has {}", from, l))
+                .unwrap_or_default();
+            out!("  {}", theme::muted(format!("Needs {}{}, {} has {}", requirement, current, to, light)));
+        }
+        if let Some(explanation) = &entry.explanation {
+            out!("  {}", explanation);
+        }
+        out!();
+    }
+
+    let fits = advice.iter().filter(|a| a.fit == Fit::Fits).count();
+    if fits > 0 {
+        out!("{}", theme::good(format!("✓ {} plant(s) already get the light they need.", fits)));
+    }
+    for entry in advice.iter().filter(|a| !a.is_move() && a.fit != Fit::Fits) {
+        let reason = match entry.fit {
+            Fit::TooDark => "too dark where it is, and no recorded room is brighter".to_string(),
+            Fit::TooBright => "too bright where it is, and no recorded room is shadier".to_string(),
+            Fit::UnknownRoom => match &entry.room {
+                Some(room) => format!("the light in {} is not recorded", room),
+                None => "no recorded room suits it".to_string(),
+            },
+            Fit::UnknownNeeds => "its care schedule does not say how much light it needs".to_string(),
+            Fit::Fits => continue,
+        };
+        out!("{} {}: {}", theme::alert("?"), entry.plant_name, theme::muted(reason));
+    }
+
+    Ok(())
+}
+
+pub async fn recommend(db: Database, conditions: Conditions, count: usize) -> Result<()> {
+    let recommendation_service = RecommendationService::new(
+        PlantRepository::new(db.clone()),
+        WishlistRepository::new(db.clone()),
+        UserRepository::new(db),
+        AiAdapter::new()?,
+    );
+
+    let spinner = output::spinner("Finding plants for you...");
+    let recommendations = recommendation_service.recommend("local-user", &conditions, count).await;
+    spinner.finish_and_clear();
+    let recommendations = recommendations?;
+
+    if recommendations.is_empty() {
+        out!("{}", theme::warning("No suggestion fits these conditions."));
+        note!("Try a higher --effort or more light.");
+        return Ok(());
+    }
+
+    out!("{}", theme::title("🌱 Recommended Plants"));
+    out!();
+    for (i, recommendation) in recommendations.iter().enumerate() {
+        let name = match &recommendation.common_name {
+            Some(common) => format!("{} ({})", common, recommendation.species),
+            None => recommendation.species.clone(),
+        };
+        out!("{} {}", theme::muted(format!("{}.", i + 1)), theme::heading(name));
+        out!("   {}", recommendation.reason);
+
+        let mut facts = vec![format!("Difficulty {}/{}", recommendation.difficulty, MAX_DIFFICULTY)];
+        if !recommendation.light.is_empty() {
+            facts.push(recommendation.light.clone());
+        }
+        let pets = if recommendation.toxic_to.is_empty() {
+            "pet-safe".to_string()
+        } else {
+            let pets: Vec<_> = recommendation.toxic_to.iter().map(|p| format!("{}s", p.as_str())).collect();
+            format!("toxic to {}", pets.join(" and "))
+        };
+        if recommendation.toxicity_verified {
+            facts.push(pets);
+        } else {
+            facts.push(format!("{} (unverified)", pets));
+        }
+        out!("   {}", theme::muted(facts.join(" · ")));
+        out!();
+    }
+
+    if !Term::stdout().is_term() || output::is_quiet() {
+        note!(
+            "{}",
+            theme::muted("Add one to your wishlist with 'wishlist add <SPECIES>'")
+        );
+        return Ok(());
+    }
+
+    let labels: Vec<_> = recommendations
+        .iter()
+        .map(|r| r.common_name.clone().unwrap_or_else(|| r.species.clone()))
+        .collect();
+    let picks = MultiSelect::with_theme(&*theme::prompts())
+        .with_prompt("Add to your wishlist (space to select, enter to confirm)")
+        .items(&labels)
+        .interact()?;
+
+    for pick in picks {
+        let item = recommendation_service
+            .add_to_wishlist("local-user", &recommendations[pick])
+            .await?;
+        out!("{}", theme::good(format!("✓ Added {} to your wishlist", item.display_name())));
+    }
+
+    Ok(())
+}
+
+pub async fn disagreements(db: Database) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let disagreements = IdentificationRepository::new(db).get_all_by_user("local-user").await?;
+    if output::is_json() {
+        return output::json(&disagreements);
+    }
+    if disagreements.is_empty() {
+        out!("{}", theme::good("The identifiers have not disagreed on any plant."));
+        note!("Set SECOND_IDENTIFIER to check new plants with a second identifier.");
+        return Ok(());
+    }
+
+    out!(
+        "{}",
+        theme::title(format!("🔍 Disputed identifications ({})", disagreements.len()))
+    );
+    for disagreement in &disagreements {
+        let name = match plant_repo.get_by_id(&disagreement.plant_id, "local-user").await {
+            Ok(Some(plant)) => plant.display_name().to_string(),
+            _ => disagreement.plant_id.clone(),
+        };
+        out!();
+        out!(
+            "{} {}",
+            theme::heading(name),
+            theme::muted(output::date(disagreement.created_at, "%Y-%m-%d"))
+        );
+        print_disagreement(disagreement);
+    }
+    note!(
+        "\n{}",
+        theme::muted("Fix a wrong species with plant-care disagreements correct <PLANT> <SPECIES>")
+    );
+
+    Ok(())
+}
+
+pub async fn correct_species(db: Database, plant_identifier: String, species: String) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let mut plant = find_plant(&plant_repo, &plant_identifier).await?;
+    let previous_name = plant.display_name().to_string();
+
+    let disagreement = plant_service::correct_species(
+        &plant_repo,
+        &IdentificationRepository::new(db),
+        &mut plant,
+        &species,
+    )
+    .await?;
+
+    out!(
+        "{}",
+        theme::success(format!("✓ {} is now recorded as {}", previous_name, disagreement.species()))
+    );
+    note!(
+        "{}",
+        theme::muted(format!(
+            "Its care schedule was made for {}; regenerate it with plant-care care regenerate {}",
+            disagreement.chosen, plant.id
+        ))
+    );
+
+    Ok(())
+}
+
+pub async fn wishlist_list(db: Database) -> Result<()> {
+    let items = WishlistRepository::new(db).get_all_by_user("local-user").await?;
+    if items.is_empty() {
+        out!("{}", theme::warning("Your wishlist is empty."));
+        note!(
+            "Find plants with {} or add one with {}",
+            theme::good("plant-care recommend --light <LEVEL>"),
+            theme::good("plant-care wishlist add <SPECIES>")
+        );
+        return Ok(());
+    }
+
+    out!("{}", theme::title(format!("⭐ Wishlist ({})", items.len())));
+    out!();
+    for item in &items {
+        out!(
+            "• {} {}",
+            theme::heading(item.display_name()),
+            theme::muted(local(item.added_at).format("%Y-%m-%d"))
+        );
+        if !item.note.is_empty() {
+            out!("  {}", theme::muted(&item.note));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn wishlist_add(db: Database, species: String, note: String) -> Result<()> {
+    let item = WishlistItem::new("local-user", &species, None, note)?;
+    WishlistRepository::new(db).save(&item).await?;
+    out!("{}", theme::success(format!("✓ Added {} to your wishlist", item.display_name())));
+
+    Ok(())
+}
+
+pub async fn wishlist_remove(db: Database, species: String) -> Result<()> {
+    if WishlistRepository::new(db).delete(&species, "local-user").await? {
+        out!("{}", theme::success(format!("✓ Took {} off your wishlist", species.trim())));
+    } else {
+        out!("{}", theme::warning(format!("'{}' is not on your wishlist.", species.trim())));
+    }
+
+    Ok(())
+}
+
+/// A size in bytes for display, e.g. "1.5 MB"
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+pub async fn db_stats(db: Database) -> Result<()> {
+    let stats = StatsRepository::new(db).stats().await?;
+
+    out!(
+        "{}",
+        theme::title(format!("🗄️  Database Statistics ({})", format_bytes(stats.file_bytes)))
+    );
+    out!();
+    out!("{}", theme::muted(format!("{:<34} {:>8} {:>10}", "Table", "Rows", "Size")));
+    for table in &stats.tables {
+        out!(
+            "{:<34} {:>8} {:>10}",
+            theme::accent(&table.name),
+            table.rows,
+            format_bytes(table.bytes)
+        );
+        for index in &table.indexes {
+            let usage = if index.used_by.is_empty() {
+                "not used by the common queries".to_string()
+            } else {
+                format!("used by {}", index.used_by.join(", "))
+            };
+            out!(
+                "  {:<41} {:>10}  {}",
+                theme::muted(&index.name),
+                format_bytes(index.bytes),
+                theme::muted(usage)
+            );
+        }
+    }
+
+    out!();
+    out!("{}", theme::heading("Common Queries:"));
+    for plan in &stats.queries {
+        match &plan.index {
+            Some(index) => out!("  {} {} {}", theme::good("✓"), plan.query, theme::muted(format!("({})", index))),
+            None => out!(
+                "  {} {} {}",
+                theme::warning("⚠"),
+                plan.query,
+                theme::warning(format!("scans the whole table: {}", plan.detail.join("; ")))
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn db_repair(db: Database) -> Result<()> {
+    let report = RepairRepository::new(db).repair().await?;
+
+    out!("{}", theme::title("🔧 Database Repair"));
+    out!(
+        "Checked {} plant(s) and {} diagnosis session(s).",
+        report.plants_checked,
+        report.sessions_checked
+    );
+    out!();
+
+    if report.quarantined.is_empty() {
+        out!("{} No corrupt rows found.", theme::good("✓"));
+        return Ok(());
+    }
+
+    for row in &report.quarantined {
+        let repair = match &row.repair {
+            Repair::Reset(fields) => format!("reset {}", fields.join(", ")),
+            Repair::Removed => "removed".to_string(),
+        };
+        out!(
+            "  {} {} {}: {}",
+            theme::warning("⚠"),
+            row.table,
+            theme::accent(&row.row_id),
+            row.error
+        );
+        out!("    {}", theme::muted(repair));
+    }
+    out!();
+    note!(
+        "{}",
+        theme::muted(format!(
+            "{} row(s) quarantined; the originals are kept in the quarantined_rows table.",
+            report.quarantined.len()
+        ))
+    );
+
+    Ok(())
+}
+
+/// The job worker, with a handler for every kind of job that can run
+/// here, and why the others cannot
+fn job_service(db: &Database) -> Result<(JobService, Vec<(JobKind, String)>)> {
+    let mut unavailable = Vec::new();
+    let mut service = JobService::new(JobRepository::new(db.clone()))
+        .with_handler(Arc::new(EventBus::new(notification_adapter::from_env()?)));
+
+    match AiAdapter::new() {
+        Ok(ai_adapter) => {
+            let care_schedule_service = CareScheduleService::new(ai_adapter, species_data_adapter::from_env()?);
+            service = service
+                .with_handler(Arc::new(GenerateScheduleHandler::new(
+                    PlantRepository::new(db.clone()),
+                    ScheduleVersionRepository::new(db.clone()),
+                    RoomRepository::new(db.clone()),
+                    UserRepository::new(db.clone()),
+                    care_schedule_service.clone(),
+                )))
+                .with_handler(Arc::new(RefreshSchedulesHandler::new(
+                    PlantRepository::new(db.clone()),
+                    ScheduleVersionRepository::new(db.clone()),
+                    RoomRepository::new(db.clone()),
+                    UserRepository::new(db.clone()),
+                    care_schedule_service,
+                )));
+        }
+        Err(e) => {
+            unavailable.push((JobKind::GenerateSchedule, format!("{:#}", e)));
+            unavailable.push((JobKind::RefreshSchedules, format!("{:#}", e)));
+        }
+    }
+    match sync_service(db) {
+        Ok(sync_service) => service = service.with_handler(Arc::new(sync_service)),
+        Err(e) => unavailable.push((JobKind::Sync, format!("{:#}", e))),
+    }
+
+    Ok((service, unavailable))
+}
+
+/// What a job is about: "for" its plant, or the event it delivers
+async fn job_subject(plant_repo: &PlantRepository, job: &Job) -> Result<Option<String>> {
+    if let (Some(plant_id), Some(user_id)) = (&job.plant_id, &job.user_id) {
+        let plant = plant_repo.get_by_id(plant_id, user_id).await?;
+        let name = plant.map_or_else(|| plant_id.clone(), |p| p.display_name().to_string());
+        return Ok(Some(format!("for {}", theme::heading(name))));
+    }
+    Ok(job
+        .payload
+        .as_ref()
+        .and_then(|p| p["event"]["event"].as_str())
+        .map(str::to_string))
+}
+
+pub async fn jobs_list(db: Database) -> Result<()> {
+    let plant_repo = PlantRepository::new(db.clone());
+    let jobs = JobRepository::new(db).get_all_by_user("local-user").await?;
+    if output::is_json() {
+        return output::json(&jobs);
+    }
+    if jobs.is_empty() {
+        out!("{}", theme::good("✓ No queued jobs."));
+        return Ok(());
+    }
+
+    let pending = jobs.iter().filter(|j| j.status == JobStatus::Pending).count();
+    out!("{}", theme::title(format!("⏳ Jobs ({} pending)", pending)));
+    out!();
+    for job in &jobs {
+        let subject = match job_subject(&plant_repo, job).await? {
+            Some(subject) => format!(" {}", subject),
+            None => String::new(),
+        };
+        let status = match job.status {
+            JobStatus::Pending => theme::warning("pending"),
+            JobStatus::Done => theme::good("done"),
+            JobStatus::Failed => theme::danger("gave up"),
+        };
+        let next = match (job.status, job.attempts) {
+            (JobStatus::Pending, 1..) => format!(", next try {}", local(job.run_at).format("%H:%M:%S")),
+            _ => String::new(),
+        };
+        out!(
+            "• {}{} {} {}",
+            job.kind.describe(),
+            subject,
+            status,
+            theme::muted(format!(
+                "(queued {}, {} attempt(s){})",
+                output::date(job.created_at, "%Y-%m-%d %H:%M"),
+                job.attempts,
+                next
+            ))
+        );
+        if let (Some(error), false) = (&job.last_error, job.status == JobStatus::Done) {
+            out!("  {}", theme::muted(error));
+        }
+    }
+    if pending > 0 {
+        note!("\nRun them with {}", theme::good("plant-care jobs run"));
+    }
+
+    Ok(())
+}
+
+pub async fn jobs_run(db: Database, once: bool, poll: u64) -> Result<()> {
+    let (job_service, unavailable) = job_service(&db)?;
+
+    if !once {
+        out!(
+            "{}",
+            theme::title(format!("👷 Running queued jobs, checking every {}s (Ctrl-C to stop)", poll.max(1)))
+        );
+        for (kind, reason) in &unavailable {
+            note!("{}", theme::muted(format!("Cannot {} here: {}", kind.describe(), reason)));
+        }
+        return job_service
+            .work(std::time::Duration::from_secs(poll.max(1)), |report| {
+                print_job_report(report, &[]);
+            })
+            .await;
+    }
+
+    let progress = output::progress("Running due jobs...");
+    let report = job_service.run_due().await?;
+    progress.finish();
+
+    if report.is_empty() {
+        out!("{}", theme::good("✓ No jobs due."));
+        return Ok(());
+    }
+    print_job_report(&report, &unavailable);
+
+    out!();
+    out!(
+        "{} done, {} failed",
+        theme::success(report.done.len()),
+        theme::danger(report.failed.len())
+    );
+    if !report.failed.is_empty() {
+        anyhow::bail!("{} job(s) failed; they are retried once due", report.failed.len());
+    }
+
+    Ok(())
+}
+
+/// One line per job that ran, and for those waiting on a handler when
+/// `unavailable` says why
+fn print_job_report(report: &JobReport, unavailable: &[(JobKind, String)]) {
+    for (job, summary) in &report.done {
+        out!("{} {} {}", theme::good("✓"), job.kind.describe(), summary);
+    }
+    for (job, reason) in &report.skipped {
+        out!("{} {} {}", theme::muted("–"), job.kind.describe(), theme::muted(format!("({})", reason)));
+    }
+    for (job, error) in &report.failed {
+        let retry = match job.status {
+            JobStatus::Failed => "gave up".to_string(),
+            _ => format!(
+                "attempt {} of {}, next try {}",
+                job.attempts,
+                MAX_JOB_ATTEMPTS,
+                local(job.run_at).format("%H:%M:%S")
+            ),
+        };
+        out!(
+            "{} {} {}",
+            theme::bad("✗"),
+            job.kind.describe(),
+            theme::muted(format!("({}; {})", error, retry))
+        );
+    }
+    for (kind, reason) in unavailable {
+        let waiting = report.waiting.iter().filter(|job| job.kind == *kind).count();
+        if waiting > 0 {
+            out!(
+                "{} {} {}",
+                theme::warning("…"),
+                kind.describe(),
+                theme::muted(format!("({} waiting: {})", waiting, reason))
+            );
+        }
+    }
+}
+
+pub async fn debug_bundle(db: Database, session: Option<String>, out: Option<String>) -> Result<()> {
+    let service = DebugBundleService::new(
+        PlantRepository::new(db.clone()),
+        DiagnosisRepository::new(db.clone()),
+        StatsRepository::new(db),
+    );
+    let log_files = match logging::log_file() {
+        Some(file) => vec![logging::old_log_file(&file), file],
+        None => Vec::new(),
+    };
+    let sources = BundleSources {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        session_id: session,
+        log_files,
+    };
+    let bundle = service.collect("local-user", &sources).await?;
+
+    let out = out.unwrap_or_else(|| format!("plant-care-debug-{}.zip", Utc::now().format("%Y%m%d-%H%M%S")));
+    fs::write(&out, bundle.to_zip()?).context("Failed to write debug bundle")?;
+
+    if output::is_json() {
+        let files: Vec<&str> = bundle.files.iter().map(|(name, _)| name.as_str()).collect();
+        return output::json(&serde_json::json!({
+            "file": out,
+            "files": files,
+            "session_id": bundle.session_id,
+            "schema_version": bundle.schema_version,
+        }));
+    }
+
+    out!("{}", theme::success(format!("✓ Debug bundle saved to {}", out)));
+    for (name, contents) in &bundle.files {
+        out!("  {} {}", name, theme::muted(format_bytes(contents.len() as i64)));
+    }
+    match &bundle.session_id {
+        Some(id) if bundle.files.iter().any(|(name, _)| name.starts_with("traces/")) => out!(
+            "\n{} The AI traces hold the conversation of session {}; look them over before sharing the bundle.",
+            theme::muted("Note:"),
+            id
+        ),
+        Some(id) => out!("\n{} No AI traces were recorded for session {}.", theme::muted("Note:"), id),
+        None => out!(
+            "\n{} No failed diagnosis session was found; pass --session to include one's AI traces.",
+            theme::muted("Note:")
+        ),
     }
 
     Ok(())
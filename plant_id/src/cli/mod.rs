@@ -6,12 +6,32 @@
  * into service calls (following hexagonal architecture).
  */
 
+mod aliases;
 mod commands;
+mod output;
+mod preview;
+mod theme;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::time::Duration;
 
-use crate::config::Database;
+pub use aliases::expand_aliases;
+
+use plant_care_core::adapters::ai_adapter::{self, GenerationParams};
+use plant_care_core::adapters::http_client;
+use plant_care_core::config::{self, timezone, Database};
+use plant_care_core::domain::encyclopedia::EntryKind;
+use plant_care_core::domain::placement::LightLevel;
+use plant_care_core::domain::recommendation::{Conditions, Effort};
+use plant_care_core::domain::toxicity::Pet;
+use plant_care_core::domain::{
+    AnswerStyle, ArchiveReason, CareScheduleUpdate, DiagnosisFilter, DiagnosisStatus, Environment, LocalZone, SourceApp, WindowOrientation,
+};
+use plant_care_core::repositories::DiagnosisRepository;
+use plant_care_core::services::bulk_service::BulkAction;
+use plant_care_core::services::explain_service::CareTopic;
 
 #[derive(Parser)]
 #[command(
@@ -21,10 +41,635 @@ use crate::config::Database;
     long_about = "Identify plants, generate care schedules, and diagnose plant health issues using AI"
 )]
 pub struct Cli {
+    /// Timeout in seconds for each external API request
+    #[arg(long, global = true)]
+    timeout: Option<u64>,
+
+    /// Only print essential output: no spinners, emoji or hints
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Disable colored output (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Color palette; light-terminal suits light backgrounds [default: THEME, then default]
+    #[arg(long, global = true, value_enum)]
+    theme: Option<Palette>,
+
+    /// Plain output for screen readers: no emoji, color or spinners, and
+    /// progress announced line by line
+    #[arg(long, global = true)]
+    accessible: bool,
+
+    /// How to report long-running steps [default: spinner, lines with --accessible, none with --quiet]
+    #[arg(long, global = true, value_enum)]
+    progress: Option<ProgressFormat>,
+
+    /// Output format of `list`, `show` and `history`; JSON keeps ISO timestamps
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// Time zone for dates and "today", e.g. Europe/Paris [default: TIMEZONE, then the system's]
+    #[arg(long, global = true)]
+    timezone: Option<String>,
+
+    /// Sampling temperature of AI answers, 0-2 [default: AI_TEMPERATURE, then 0.2 for diagnoses and the model's]
+    #[arg(long, global = true)]
+    ai_temperature: Option<f32>,
+
+    /// Only sample from the most likely tokens making up this share, 0-1 [default: AI_TOP_P]
+    #[arg(long, global = true)]
+    top_p: Option<f32>,
+
+    /// Longest AI answer in tokens [default: AI_MAX_TOKENS]
+    #[arg(long, global = true)]
+    max_tokens: Option<u32>,
+
+    /// Seed for repeatable AI answers, on models that support it [default: AI_SEED]
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+
+    /// How AI answers are worded: beginner or horticulturist, concise or verbose,
+    /// e.g. "beginner,concise" [default: AI_STYLE]
+    #[arg(long, global = true)]
+    style: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// File formats supported by `export`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    /// Full backup of the collection, restorable with `import`
+    Archive,
+}
+
+/// Progress reporting styles for `--progress`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    Spinner,
+    /// One JSON object per line on stderr
+    Json,
+    /// One plain line per step on stderr, the default with --accessible
+    Lines,
+    None,
+}
+
+/// Color palettes for `--theme`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Palette {
+    /// Suits dark terminal backgrounds
+    Default,
+    /// Darker shades without dim or yellow text, for light backgrounds
+    LightTerminal,
+    /// Bright, bold colors that are never dimmed
+    HighContrast,
+    /// No color at all
+    None,
+}
+
+/// Output formats for `--output`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    /// One JSON document on stdout, with hints and previews left out
+    Json,
+}
+
+/// Follow-up answer for `checkin`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CheckInResult {
+    Worked,
+    Failed,
+}
+
+/// Encyclopedia sections available to `lookup`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum LookupKind {
+    Pest,
+    Disease,
+}
+
+impl From<LookupKind> for EntryKind {
+    fn from(kind: LookupKind) -> Self {
+        match kind {
+            LookupKind::Pest => EntryKind::Pest,
+            LookupKind::Disease => EntryKind::Disease,
+        }
+    }
+}
+
+/// Parts of a care schedule `explain` can explain
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExplainTopic {
+    Water,
+    Light,
+    Humidity,
+    Temperature,
+}
+
+impl From<ExplainTopic> for CareTopic {
+    fn from(topic: ExplainTopic) -> Self {
+        match topic {
+            ExplainTopic::Water => CareTopic::Water,
+            ExplainTopic::Light => CareTopic::Light,
+            ExplainTopic::Humidity => CareTopic::Humidity,
+            ExplainTopic::Temperature => CareTopic::Temperature,
+        }
+    }
+}
+
+/// Reasons accepted by `archive`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ArchiveReasonArg {
+    Died,
+    Gifted,
+    Sold,
+}
+
+/// Apps whose exports `import --from` reads
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SourceAppArg {
+    /// Planta's CSV export: one row per plant
+    Planta,
+    /// Greg's CSV export: one row per care event
+    Greg,
+}
+
+impl From<SourceAppArg> for SourceApp {
+    fn from(app: SourceAppArg) -> Self {
+        match app {
+            SourceAppArg::Planta => SourceApp::Planta,
+            SourceAppArg::Greg => SourceApp::Greg,
+        }
+    }
+}
+
+/// Session statuses accepted by `history --status`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum StatusArg {
+    Completed,
+    /// Waiting for your answer
+    Pending,
+    Cancelled,
+}
+
+impl From<StatusArg> for DiagnosisStatus {
+    fn from(status: StatusArg) -> Self {
+        match status {
+            StatusArg::Completed => DiagnosisStatus::Completed,
+            StatusArg::Pending => DiagnosisStatus::PendingUserInput,
+            StatusArg::Cancelled => DiagnosisStatus::Cancelled,
+        }
+    }
+}
+
+impl From<ArchiveReasonArg> for ArchiveReason {
+    fn from(reason: ArchiveReasonArg) -> Self {
+        match reason {
+            ArchiveReasonArg::Died => ArchiveReason::Died,
+            ArchiveReasonArg::Gifted => ArchiveReason::Gifted,
+            ArchiveReasonArg::Sold => ArchiveReason::Sold,
+        }
+    }
+}
+
+/// Window directions accepted by `room set`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum WindowArg {
+    North,
+    East,
+    South,
+    West,
+    /// The room has no window
+    None,
+}
+
+impl From<WindowArg> for Option<WindowOrientation> {
+    fn from(window: WindowArg) -> Self {
+        match window {
+            WindowArg::North => Some(WindowOrientation::North),
+            WindowArg::East => Some(WindowOrientation::East),
+            WindowArg::South => Some(WindowOrientation::South),
+            WindowArg::West => Some(WindowOrientation::West),
+            WindowArg::None => None,
+        }
+    }
+}
+
+/// Light levels accepted by `recommend`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum LightArg {
+    Low,
+    Medium,
+    /// Bright, indirect light
+    Bright,
+    /// Several hours of direct sun
+    Direct,
+}
+
+impl From<LightArg> for LightLevel {
+    fn from(light: LightArg) -> Self {
+        match light {
+            LightArg::Low => LightLevel::Low,
+            LightArg::Medium => LightLevel::Medium,
+            LightArg::Bright => LightLevel::Bright,
+            LightArg::Direct => LightLevel::Direct,
+        }
+    }
+}
+
+/// Pets accepted by `recommend`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum PetArg {
+    Cats,
+    Dogs,
+}
+
+impl From<PetArg> for Pet {
+    fn from(pet: PetArg) -> Self {
+        match pet {
+            PetArg::Cats => Pet::Cat,
+            PetArg::Dogs => Pet::Dog,
+        }
+    }
+}
+
+/// Effort levels accepted by `recommend`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum EffortArg {
+    /// Very forgiving plants only (difficulty 1-2)
+    Low,
+    /// Some attention is fine (difficulty up to 3)
+    Medium,
+    /// Demanding plants are welcome
+    High,
+}
+
+impl From<EffortArg> for Effort {
+    fn from(effort: EffortArg) -> Self {
+        match effort {
+            EffortArg::Low => Effort::Low,
+            EffortArg::Medium => Effort::Medium,
+            EffortArg::High => Effort::High,
+        }
+    }
+}
+
+/// Parse an age in days, written as "180d", "26w" or "180"
+fn parse_age(value: &str) -> Result<u32, String> {
+    let value = value.trim();
+    let (number, unit_days) = match value.strip_suffix('w') {
+        Some(weeks) => (weeks, 7),
+        None => (value.strip_suffix('d').unwrap_or(value), 1),
+    };
+    number
+        .parse::<u32>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit_days))
+        .filter(|days| *days > 0)
+        .ok_or_else(|| format!("expected an age such as '180d' or '26w', got '{}'", value))
+}
+
+/// Datasets that can be exported
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportTarget {
+    Plants,
+    Diagnoses,
+    /// Upcoming waterings, fertilizing and inspections
+    Reminders,
+}
+
+#[derive(Subcommand)]
+enum DiagnoseAction {
+    /// Fork a session at one of your answers to try a different one; the
+    /// original session is kept
+    Branch {
+        /// Session ID, as shown by `history`
+        session: String,
+
+        /// Your answer to replace, as numbered by `history --session`
+        #[arg(long)]
+        at: usize,
+
+        /// Continue without confirming an estimated cost above DIAGNOSIS_CONFIRM_COST
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum QuarantineAction {
+    /// End a quarantine once a follow-up diagnosis found the plant clean
+    Release {
+        /// Plant ID or name
+        plant: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CareAction {
+    /// Regenerate and save care schedules for plants in your collection
+    Regenerate {
+        /// Plant ID or name
+        #[arg(required_unless_present = "all")]
+        plant: Option<String>,
+
+        /// Regenerate every plant in the collection
+        #[arg(long, conflicts_with = "plant")]
+        all: bool,
+
+        /// Maximum number of concurrent AI requests
+        #[arg(short, long, default_value_t = 4)]
+        jobs: usize,
+    },
+    /// Show every version of a plant's care schedule and where it came from
+    History {
+        /// Plant ID or name
+        plant: String,
+    },
+    /// Restore an earlier version of a plant's care schedule
+    Rollback {
+        /// Plant ID or name
+        plant: String,
+        /// Version to restore, as numbered by `care history`
+        #[arg(long)]
+        version: u32,
+    },
+    /// Change a plant's care schedule by hand. Your changes are kept over
+    /// the species baseline when the schedule is regenerated.
+    Edit {
+        /// Plant ID or name
+        plant: String,
+
+        #[arg(long)]
+        light: Option<String>,
+
+        #[arg(long)]
+        water: Option<String>,
+
+        #[arg(long)]
+        humidity: Option<String>,
+
+        #[arg(long)]
+        temperature: Option<String>,
+
+        /// Free-text care instructions
+        #[arg(long)]
+        instructions: Option<String>,
+    },
+    /// Drop your changes to a plant's care schedule, going back to the
+    /// species baseline
+    Reset {
+        /// Plant ID or name
+        plant: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ShoppingAction {
+    /// Put an item on the list by hand
+    Add {
+        /// Item name, e.g. "neem oil"
+        item: String,
+    },
+    /// Take an item off the list (it returns if a newer diagnosis recommends it)
+    Remove {
+        /// Item name
+        item: String,
+    },
+    /// Export the list as a Markdown checklist
+    Export {
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RoomAction {
+    /// Record the light and climate in a room; those of a recorded room
+    /// that are not given are kept
+    Set {
+        /// Room name, as used for plants (e.g. "living room")
+        name: String,
+
+        /// Direction the window faces (required for a new room)
+        #[arg(long, value_enum)]
+        window: Option<WindowArg>,
+
+        /// Hours of direct sun on a typical day (estimated from the window if omitted)
+        #[arg(long, value_name = "HOURS")]
+        sun_hours: Option<f64>,
+
+        /// Average temperature in °C
+        #[arg(long, value_name = "CELSIUS", allow_negative_numbers = true)]
+        temperature: Option<f64>,
+
+        /// Average relative humidity in percent
+        #[arg(long, value_name = "PERCENT")]
+        humidity: Option<f64>,
+
+        /// Whether cold drafts reach the plants (yes/no)
+        #[arg(long, value_name = "YES|NO", value_parser = clap::builder::BoolishValueParser::new())]
+        drafty: Option<bool>,
+
+        /// Whether the room is heated in winter (yes/no)
+        #[arg(long, value_name = "YES|NO", value_parser = clap::builder::BoolishValueParser::new())]
+        heated: Option<bool>,
+    },
+    /// List rooms and their light and climate
+    List,
+    /// Forget a room's light (plants stay in it)
+    Remove {
+        /// Room name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportAction {
+    /// Problems found by completed diagnoses, and those that keep coming
+    /// back on a plant, species or room
+    Problems {
+        /// Only diagnoses started on or after this day (YYYY-MM-DD)
+        /// [default: a year ago]
+        #[arg(long, value_name = "DATE")]
+        since: Option<NaiveDate>,
+    },
+}
+
+#[derive(Subcommand)]
+enum JobsAction {
+    /// Work through the queued jobs as they fall due, until stopped
+    Run {
+        /// Run the jobs due now, then exit
+        #[arg(long)]
+        once: bool,
+
+        /// Seconds between checks for due jobs
+        #[arg(long, default_value_t = 30)]
+        poll: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbAction {
+    /// Table sizes and which indexes the common queries use
+    Stats,
+    /// Re-validate plants and diagnoses, quarantining rows that cannot be read
+    Repair,
+}
+
+#[derive(Subcommand)]
+enum DisagreementAction {
+    /// Record the species a plant really is
+    Correct {
+        /// Plant ID or name
+        plant: String,
+
+        /// Scientific name of the species
+        species: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum WishlistAction {
+    /// Put a plant on the wishlist by hand
+    Add {
+        /// Species or plant name
+        species: String,
+
+        /// Why you want it
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Take a plant off the wishlist
+    Remove {
+        /// Species, as shown on the wishlist
+        species: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum AdviseAction {
+    /// Match each plant's light needs against your rooms and suggest moves
+    Placement {
+        /// Skip the AI explanation of the moves
+        #[arg(long)]
+        no_ai: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplateAction {
+    /// List your care schedule templates
+    List,
+    /// Save a plant's care schedule as a template
+    Create {
+        /// Template name, e.g. "tropical aroid"
+        name: String,
+
+        /// Plant ID or name to take the schedule from
+        #[arg(long)]
+        from: String,
+    },
+    /// Show a template's care schedule
+    Show {
+        /// Template name
+        name: String,
+    },
+    /// Change a template's care schedule or name
+    Edit {
+        /// Template name
+        name: String,
+
+        #[arg(long)]
+        light: Option<String>,
+
+        #[arg(long)]
+        water: Option<String>,
+
+        #[arg(long)]
+        humidity: Option<String>,
+
+        #[arg(long)]
+        temperature: Option<String>,
+
+        /// Free-text care instructions
+        #[arg(long)]
+        instructions: Option<String>,
+
+        /// New name for the template
+        #[arg(long)]
+        rename: Option<String>,
+    },
+    /// Replace a plant's care schedule with a template's
+    Apply {
+        /// Template name
+        name: String,
+
+        /// Plant ID or name
+        plant: String,
+    },
+    /// Delete a template (plants keep their schedules)
+    Delete {
+        /// Template name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum BotPlatform {
+    /// Serve the bot over Telegram (needs TELEGRAM_BOT_TOKEN and TELEGRAM_ALLOWED_CHATS)
+    Telegram,
+    /// Serve slash commands on Discord (needs DISCORD_BOT_TOKEN)
+    Discord,
+}
+
+#[derive(Subcommand)]
+enum NotifyAction {
+    /// Send a test event to every webhook in NOTIFY_WEBHOOKS
+    Test,
+}
+
+#[derive(Subcommand)]
+enum ApiKeyAction {
+    /// Create an API key for a user, replacing their previous key
+    Create {
+        /// User whose collection the key gives access to
+        #[arg(default_value = "local-user")]
+        user: String,
+    },
+    /// Revoke a user's API key
+    Revoke {
+        user: String,
+    },
+    /// List users with an API key
+    List,
+}
+
+#[derive(Subcommand)]
+enum SitterAction {
+    /// Hand over to a plant sitter, who can follow care instructions and
+    /// tick off tasks but not remove plants or use the AI
+    Start,
+    /// End sitter mode (asks for the PIN chosen at handover)
+    Stop,
+    /// Show whether sitter mode is on
+    Status,
+}
+
+#[derive(Subcommand)]
+enum FeedbackAction {
+    /// Show average ratings, comparing the AI models that were used
+    Stats,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Add a new plant to your collection
@@ -44,66 +689,1101 @@ enum Commands {
         /// Longitude for location-based identification
         #[arg(long)]
         longitude: Option<f64>,
+
+        /// Use a saved care schedule template instead of generating a schedule
+        #[arg(short, long)]
+        template: Option<String>,
+
+        /// Add a new plant even if this photo was added before
+        #[arg(long)]
+        force_new: bool,
+
+        /// What you call the plant ("Bob"); its species stays its name
+        #[arg(long)]
+        nickname: Option<String>,
+
+        /// Day you got the plant, if before today (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        acquired: Option<NaiveDate>,
+
+        /// Where the plant came from, e.g. "garden center" or "grown from seed"
+        #[arg(long, value_name = "WHERE")]
+        acquired_from: Option<String>,
     },
 
     /// List all plants in your collection
-    List,
+    #[command(visible_alias = "ls")]
+    List {
+        /// Only plants with a care difficulty rating like 2, "<=2" or ">3" (1 to 5)
+        #[arg(long)]
+        difficulty: Option<String>,
+
+        /// Only plants with an attribute value, e.g. pot_size=14cm; all must match
+        #[arg(long = "where", value_name = "KEY=VALUE")]
+        conditions: Vec<String>,
+
+        /// Only plants whose health index is low, least healthy first
+        #[arg(long)]
+        needs_attention: bool,
+
+        /// Show plants by their scientific names
+        #[arg(long)]
+        scientific: bool,
+    },
 
     /// Show details for a specific plant
     Show {
+        /// Plant ID, name, scientific name or common name
+        plant: String,
+    },
+
+    /// Record when and where you got a plant, or show it
+    Acquired {
         /// Plant ID or name
         plant: String,
+
+        /// Day you got the plant (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        on: Option<NaiveDate>,
+
+        /// Where it came from, e.g. "cutting from a friend" ("" to clear)
+        #[arg(long, value_name = "WHERE")]
+        from: Option<String>,
     },
 
     /// Delete a plant from your collection
+    #[command(visible_alias = "rm")]
     Delete {
         /// Plant ID or name
         plant: String,
     },
 
-    /// Start an interactive diagnosis session for a plant
+    /// Start an interactive diagnosis session for a plant, or linked
+    /// sessions for several plants showing the same problem
+    #[command(visible_alias = "dx", args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Diagnose {
         /// Plant ID or name
-        plant: String,
+        #[arg(required_unless_present = "plants")]
+        plant: Option<String>,
+
+        /// Comma-separated plants with the same problem, e.g. a pest spreading
+        #[arg(long, value_delimiter = ',', conflicts_with = "plant")]
+        plants: Vec<String>,
 
         /// Initial problem description
+        #[arg(short, long, required = true)]
+        problem: Option<String>,
+
+        /// Photo of the problem, assessed by Plant.id before the diagnosis starts
+        #[arg(short, long, conflicts_with = "plants")]
+        image: Option<String>,
+
+        /// Start without confirming an estimated cost above DIAGNOSIS_CONFIRM_COST
         #[arg(short, long)]
-        problem: String,
+        yes: bool,
+
+        #[command(subcommand)]
+        action: Option<DiagnoseAction>,
+    },
+
+    /// Quarantine an infested plant, or list quarantined plants
+    #[command(args_conflicts_with_subcommands = true)]
+    Quarantine {
+        /// Plant ID or name
+        plant: Option<String>,
+
+        /// What the plant is infested with, e.g. "spider mites"
+        #[arg(short, long, requires = "plant")]
+        reason: Option<String>,
+
+        #[command(subcommand)]
+        action: Option<QuarantineAction>,
+    },
+
+    /// List plants grouped by botanical family, or genus when the family is unknown
+    Families,
+
+    /// Chat freely about a plant, grounded in its records
+    Chat {
+        /// Plant ID or name
+        plant: String,
+
+        /// Continue the most recent chat instead of starting a new one
+        #[arg(long)]
+        resume: bool,
+    },
+
+    /// Ask a question about your collection in plain language, e.g. "when did I last water the fern?"
+    Ask {
+        /// The question
+        question: String,
+    },
+
+    /// Add a journal note for a plant, or list its journal
+    Journal {
+        /// Plant ID or name
+        plant: String,
+
+        /// Note to add (omit to list existing entries)
+        note: Option<String>,
+
+        /// Flag the note as describing a problem (used by `triage`)
+        #[arg(long, requires = "note")]
+        problem: bool,
+    },
+
+    /// Set a custom attribute of a plant, such as its pot size or soil mix
+    Set {
+        /// Plant ID or name
+        plant: String,
+
+        /// Attribute name (e.g. pot_size)
+        key: String,
+
+        /// New value
+        #[arg(required_unless_present = "remove")]
+        value: Option<String>,
+
+        /// Remove the attribute instead
+        #[arg(long, conflicts_with = "value")]
+        remove: bool,
+    },
+
+    /// Log money spent on a plant: its purchase price or supplies for it
+    Expense {
+        /// Plant ID or name
+        plant: String,
+
+        /// Amount spent (e.g. 12.99)
+        amount: String,
+
+        /// What it was for (e.g. "new pot")
+        description: Option<String>,
+
+        /// The amount is what the plant itself cost
+        #[arg(long)]
+        purchase: bool,
+
+        /// Day of the expense, if not today (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        on: Option<NaiveDate>,
+    },
+
+    /// Report what your plants have cost, per plant and per month
+    Expenses {
+        /// Only list the expenses of this plant (ID or name)
+        #[arg(long)]
+        plant: Option<String>,
+    },
+
+    /// Log a soil moisture reading for a plant, or show its moisture trend
+    Moisture {
+        /// Plant ID or name
+        plant: String,
+
+        /// Moisture meter reading in percent, from 0 (dry) to 100 (omit to
+        /// show recent readings)
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=100))]
+        value: Option<u8>,
     },
 
-    /// View diagnosis history for a plant
+    /// Look up a pest or disease in the encyclopedia
+    Lookup {
+        /// Encyclopedia section
+        #[arg(value_enum)]
+        kind: LookupKind,
+
+        /// Pest or disease name (e.g. "spider mites")
+        name: String,
+    },
+
+    /// Explain why a plant's care guidance suits its species and surroundings, citing sources
+    Explain {
+        /// Plant ID or name
+        plant: String,
+
+        /// Part of the care schedule to explain
+        #[arg(value_enum)]
+        topic: ExplainTopic,
+    },
+
+    /// Rank plants with open symptoms and diagnose the most urgent one
+    Triage,
+
+    /// View diagnosis history for a plant, or for every plant
     History {
+        /// Plant ID or name
+        #[arg(required_unless_present = "all_plants")]
+        plant: Option<String>,
+
+        /// Show the sessions of every plant in your collection
+        #[arg(long, conflicts_with = "plant")]
+        all_plants: bool,
+
+        /// Only sessions with this status
+        #[arg(long, value_enum)]
+        status: Option<StatusArg>,
+
+        /// Only sessions started on or after this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<NaiveDate>,
+
+        /// Only sessions whose finding contains this text, e.g. "root rot"
+        #[arg(long)]
+        finding: Option<String>,
+
+        /// Sessions shown per page
+        #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        limit: u32,
+
+        /// Page of sessions to show, starting at 1
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        page: u32,
+
+        /// Show the full transcript of one session (ID or the start of it)
+        #[arg(short, long, conflicts_with = "all_plants")]
+        session: Option<String>,
+    },
+
+    /// Review every recorded change to a plant and its records
+    Audit {
+        /// Plant ID or name (ID for deleted plants)
+        plant: String,
+    },
+
+    /// Export a plant as a shareable profile file
+    Share {
         /// Plant ID or name
         plant: String,
+
+        /// Output file (e.g. monstera.plant.json)
+        #[arg(short, long)]
+        out: String,
+    },
+
+    /// Print-ready label with the plant's name, watering and a QR code
+    Label {
+        /// Plant ID or name
+        plant: String,
+
+        /// Output PNG file
+        #[arg(short, long, default_value = "label.png")]
+        out: String,
+    },
+
+    /// One-page care sheet of a room's plants, to stick on the fridge or
+    /// give a sitter
+    Print {
+        /// Only the plants in this room [default: the whole collection]
+        #[arg(short, long)]
+        room: Option<String>,
+
+        /// Output file: PDF when the name ends in .pdf, markdown otherwise
+        #[arg(short, long, default_value = "care-sheet.pdf")]
+        out: String,
+    },
+
+    /// Reports across your diagnosis history
+    Report {
+        #[command(subcommand)]
+        action: ReportAction,
+    },
+
+    /// Show the plant whose label appears in a photo or scan
+    Scan {
+        /// Image containing a label's QR code
+        image: String,
+    },
+
+    /// Import a plant from a shared profile file
+    ImportProfile {
+        /// Path to a profile created with `share`
+        file: String,
+    },
+
+    /// Export your collection or diagnosis history
+    Export {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+
+        /// What to export (required for csv and json)
+        #[arg(short, long, value_enum)]
+        what: Option<ExportTarget>,
+
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        out: Option<String>,
+
+        /// Encrypt the archive with a passphrase
+        #[arg(long)]
+        encrypt: bool,
+
+        /// Read the passphrase from a file instead of prompting
+        #[arg(long)]
+        passphrase_file: Option<String>,
+
+        /// Leave out what could identify you, e.g. to share the export or
+        /// attach it to a bug report: photo locations and paths, user IDs,
+        /// journal entries with personal details, and those details in
+        /// other notes, messages and chats
+        #[arg(long)]
+        anonymize: bool,
+
+        /// Also treat text matching this regular expression as personal
+        /// (repeatable), e.g. "(?i)\bcarol\b"
+        #[arg(long = "pii-pattern", value_name = "REGEX", requires = "anonymize")]
+        pii_patterns: Vec<String>,
+    },
+
+    /// Restore a collection archive created with `export --format archive`,
+    /// or bring plants over from another app's CSV export
+    Import {
+        /// Path to the archive or export file
+        file: String,
+
+        /// The file is a CSV export of this app
+        #[arg(long, value_enum, value_name = "APP")]
+        from: Option<SourceAppArg>,
+
+        /// Read the passphrase from a file instead of prompting
+        #[arg(long, conflicts_with = "from")]
+        passphrase_file: Option<String>,
+    },
+
+    /// Save one plant's full record to a file, e.g. before a bulk edit
+    Snapshot {
+        /// Plant ID or name
+        plant: String,
+
+        /// Output file (defaults to <plant>-<date>.snapshot.json)
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+
+    /// Put a plant back the way it was in a snapshot
+    RestoreSnapshot {
+        /// Path to a snapshot created with `snapshot`
+        file: String,
+
+        /// Restore without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Render a static HTML gallery of your collection
+    Publish {
+        /// Output directory
+        #[arg(short, long)]
+        out: String,
+
+        /// Gallery title
+        #[arg(long, default_value = "My Plant Collection")]
+        title: String,
     },
 
     /// Generate care schedule for a plant (without adding to collection)
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Care {
         /// Plant name
+        #[arg(required = true)]
+        name: Option<String>,
+
+        /// Write the schedule for one of your rooms (see `room set`)
+        #[arg(long)]
+        room: Option<String>,
+
+        #[command(subcommand)]
+        action: Option<CareAction>,
+    },
+
+    /// Regenerate care schedules that are old or were written by another AI model
+    Refresh {
+        /// Regenerate schedules older than this, e.g. "180d" or "26w"
+        #[arg(long, default_value = "180d", value_parser = parse_age)]
+        stale: u32,
+
+        /// List the stale schedules without regenerating them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Maximum number of concurrent AI requests
+        #[arg(short, long, default_value_t = 4)]
+        jobs: usize,
+
+        /// Queue the refresh for the job worker instead, retried until the
+        /// AI answers
+        #[arg(long, conflicts_with = "dry_run")]
+        queue: bool,
+    },
+
+    /// Look a plant up in the species database set by SPECIES_DATA_SOURCE
+    Species {
+        /// Plant name, common or botanical
         name: String,
     },
+
+    /// Store API keys in the OS keychain
+    Init,
+
+    /// Retire a plant from your collection, keeping its records
+    Archive {
+        /// Plant ID or name
+        plant: String,
+
+        /// Why the plant left the collection
+        #[arg(short, long, value_enum)]
+        reason: ArchiveReasonArg,
+    },
+
+    /// Give a plant to another user of the household, with its reminders
+    /// and diagnosis history
+    Transfer {
+        /// Plant ID or name
+        plant: String,
+
+        /// User to give the plant to
+        #[arg(long)]
+        to: String,
+
+        /// Keep the diagnosis history yourself, on an archived copy of the
+        /// plant, so the new owner starts afresh
+        #[arg(long)]
+        detach_history: bool,
+
+        /// Transfer without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Show archived plants and what can be learned from them
+    Graveyard,
+
+    /// Run canned diagnosis scenarios against AI models and compare the results
+    Eval {
+        /// Comma-separated OpenRouter model names
+        #[arg(long, value_delimiter = ',', required = true)]
+        models: Vec<String>,
+
+        /// YAML file with the scenarios
+        #[arg(long)]
+        cases: String,
+    },
+
+    /// Run a chat bot front-end
+    Bot {
+        #[command(subcommand)]
+        platform: BotPlatform,
+    },
+
+    /// Serve the HTTP API, with its OpenAPI document and Swagger UI at /docs
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = crate::http::DEFAULT_ADDR)]
+        addr: std::net::SocketAddr,
+    },
+
+    /// API keys for the HTTP API
+    ApiKey {
+        #[command(subcommand)]
+        action: ApiKeyAction,
+    },
+
+    /// Serve the gRPC API for mobile clients (see proto/plant_care.proto)
+    Grpc {
+        /// Address to listen on; other than loopback needs GRPC_TOKEN
+        #[arg(long, default_value = crate::grpc::DEFAULT_ADDR)]
+        addr: std::net::SocketAddr,
+    },
+
+    /// Webhook notifications
+    Notify {
+        #[command(subcommand)]
+        action: NotifyAction,
+    },
+
+    /// Show today's care checklist
+    Today {
+        /// Mark a task done (ID from the checklist)
+        #[arg(long, value_name = "TASK_ID")]
+        done: Option<String>,
+    },
+
+    /// Streaks of on-time waterings and the badges they earned
+    Stats,
+
+    /// Pre-departure checklist and plant sitter instructions for a trip
+    Vacation {
+        /// First day away (YYYY-MM-DD)
+        #[arg(long)]
+        from: NaiveDate,
+
+        /// Last day away (YYYY-MM-DD)
+        #[arg(long)]
+        to: NaiveDate,
+
+        /// Save the plan to a file instead of printing it: PDF when the
+        /// name ends in .pdf, markdown otherwise
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+
+    /// Restricted sitter mode for while you're away
+    Sitter {
+        #[command(subcommand)]
+        action: SitterAction,
+    },
+
+    /// Share your collection with your other devices through SYNC_REMOTE
+    Sync {
+        /// Queue the sync for the job worker instead, retried until the
+        /// remote is reachable
+        #[arg(long)]
+        queue: bool,
+    },
+
+    /// Ratings you gave to AI answers
+    Feedback {
+        #[command(subcommand)]
+        action: FeedbackAction,
+    },
+
+    /// Change many plants at once (tags, room, template, archiving), previewing them first
+    Bulk {
+        /// Plants to change: room=<room>, tag=<tag>, name=<text> or id=<id>; all must match
+        #[arg(short, long, required = true)]
+        filter: Vec<String>,
+
+        /// Add a tag
+        #[arg(long, value_name = "TAG")]
+        set_tag: Vec<String>,
+
+        /// Remove a tag
+        #[arg(long, value_name = "TAG")]
+        remove_tag: Vec<String>,
+
+        /// Move to a room ("none" to clear the room)
+        #[arg(long, value_name = "ROOM")]
+        set_room: Option<String>,
+
+        /// Apply a care schedule template
+        #[arg(long, value_name = "NAME")]
+        template: Option<String>,
+
+        /// Archive the plants, keeping their records
+        #[arg(long, value_enum, value_name = "REASON")]
+        archive: Option<ArchiveReasonArg>,
+
+        /// Only list the plants that would change
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Apply without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Manage care schedule templates for groups of plants
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+
+    /// Report whether the last diagnosis' recommendation worked
+    Checkin {
+        /// Plant ID or name
+        plant: String,
+
+        /// Outcome of the treatment (prompts if omitted)
+        #[arg(short, long, value_enum)]
+        result: Option<CheckInResult>,
+
+        /// What you observed
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+
+    /// Shopping list of supplies recommended by recent diagnoses
+    Shopping {
+        /// Include diagnoses concluded in the last N days
+        #[arg(long, global = true, default_value_t = 30)]
+        days: u32,
+
+        #[command(subcommand)]
+        action: Option<ShoppingAction>,
+    },
+
+    /// Record the light in your rooms (window direction, hours of sun)
+    Room {
+        #[command(subcommand)]
+        action: RoomAction,
+    },
+
+    /// Advice about your collection
+    Advise {
+        #[command(subcommand)]
+        action: AdviseAction,
+    },
+
+    /// Suggest new plants for your light, pets and care effort
+    Recommend {
+        /// Light the new plants will get
+        #[arg(long, value_enum)]
+        light: LightArg,
+
+        /// Pets the plants must be safe for (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        pets: Vec<PetArg>,
+
+        /// How much care you want to give
+        #[arg(long, value_enum, default_value = "medium")]
+        effort: EffortArg,
+
+        /// Number of suggestions
+        #[arg(long, default_value_t = 5)]
+        count: usize,
+    },
+
+    /// Plants you would like to get
+    Wishlist {
+        #[command(subcommand)]
+        action: Option<WishlistAction>,
+    },
+
+    /// Plants the identifiers named different species for, and how each was settled
+    Disagreements {
+        #[command(subcommand)]
+        action: Option<DisagreementAction>,
+    },
+
+    /// Inspect the database
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+
+    /// List queued work, such as schedules the AI could not write yet, or
+    /// run it
+    Jobs {
+        #[command(subcommand)]
+        action: Option<JobsAction>,
+    },
+
+    /// Collect what a bug report needs into a zip file: version, settings
+    /// with secrets redacted, recent logs, the failing diagnosis session's
+    /// AI traces and the database schema
+    DebugBundle {
+        /// Diagnosis session whose AI traces to include [default: the latest that failed]
+        #[arg(long, value_name = "ID")]
+        session: Option<String>,
+
+        /// Output file (defaults to plant-care-debug-<date>.zip)
+        #[arg(short, long)]
+        out: Option<String>,
+    },
 }
 
 impl Cli {
+    /// Whether the command serves users until it is stopped (the servers and
+    /// bots), rather than running once for the owner
+    pub fn is_long_running(&self) -> bool {
+        matches!(
+            self.command,
+            Commands::Serve { .. } | Commands::Grpc { .. } | Commands::Bot { .. }
+        )
+    }
+
     pub async fn execute(self, db: Database) -> Result<()> {
+        if let Some(secs) = self.timeout {
+            http_client::set_request_timeout(Duration::from_secs(secs));
+        }
+        output::init(self.quiet, self.no_color, self.accessible, self.progress, self.output);
+        theme::init(self.theme)?;
+        if let Some(name) = &self.timezone {
+            timezone::set_local_zone(LocalZone::named(name)?);
+        }
+        ai_adapter::set_generation_params(GenerationParams {
+            temperature: self.ai_temperature,
+            top_p: self.top_p,
+            max_tokens: self.max_tokens,
+            seed: self.seed,
+        })?;
+        if let Some(style) = &self.style {
+            ai_adapter::set_answer_style(AnswerStyle::parse(style)?);
+        }
+
         match self.command {
             Commands::Add {
                 image,
                 name,
                 latitude,
                 longitude,
+                template,
+                force_new,
+                nickname,
+                acquired,
+                acquired_from,
             } => {
-                commands::add_plant(db, image, name, latitude, longitude).await
+                commands::add_plant(
+                    db,
+                    image,
+                    name,
+                    latitude,
+                    longitude,
+                    template,
+                    force_new,
+                    nickname,
+                    acquired,
+                    acquired_from,
+                )
+                .await
             }
-            Commands::List => commands::list_plants(db).await,
+            Commands::List {
+                difficulty,
+                conditions,
+                needs_attention,
+                scientific,
+            } => commands::list_plants(db, difficulty, conditions, needs_attention, scientific).await,
             Commands::Show { plant } => commands::show_plant(db, plant).await,
+            Commands::Acquired { plant, on, from } => commands::set_acquired(db, plant, on, from).await,
             Commands::Delete { plant } => commands::delete_plant(db, plant).await,
-            Commands::Diagnose { plant, problem } => {
-                commands::diagnose_plant(db, plant, problem).await
+            Commands::Diagnose {
+                plant,
+                plants,
+                problem,
+                image,
+                yes,
+                action,
+            } => match (action, plant) {
+                (Some(DiagnoseAction::Branch { session, at, yes }), _) => {
+                    commands::branch_diagnosis(db, session, at, yes).await
+                }
+                (None, Some(plant)) => {
+                    commands::diagnose_plant(db, plant, problem.unwrap_or_default(), image, yes).await
+                }
+                (None, None) => commands::diagnose_plants(db, plants, problem.unwrap_or_default(), yes).await,
+            },
+            Commands::Quarantine { plant, reason, action } => match (action, plant) {
+                (Some(QuarantineAction::Release { plant }), _) => commands::release_quarantine(db, plant).await,
+                (None, Some(plant)) => commands::quarantine_plant(db, plant, reason).await,
+                (None, None) => commands::quarantine_list(db).await,
+            },
+            Commands::Families => commands::families(db).await,
+            Commands::Chat { plant, resume } => {
+                commands::chat_with_plant(db, plant, resume).await
+            }
+            Commands::Ask { question } => commands::ask(db, question).await,
+            Commands::Journal {
+                plant,
+                note,
+                problem,
+            } => commands::journal(db, plant, note, problem).await,
+            Commands::Set {
+                plant,
+                key,
+                value,
+                remove,
+            } => commands::set_attribute(db, plant, key, value, remove).await,
+            Commands::Expense {
+                plant,
+                amount,
+                description,
+                purchase,
+                on,
+            } => commands::add_expense(db, plant, amount, description, purchase, on).await,
+            Commands::Expenses { plant } => commands::expenses(db, plant).await,
+            Commands::Moisture { plant, value } => commands::moisture(db, plant, value).await,
+            Commands::Lookup { kind, name } => commands::lookup(db, kind.into(), name).await,
+            Commands::Explain { plant, topic } => commands::explain(db, plant, topic.into()).await,
+            Commands::Triage => commands::triage(db).await,
+            Commands::History {
+                plant,
+                all_plants: _,
+                status,
+                since,
+                finding,
+                limit,
+                page,
+                session,
+            } => {
+                let filter = DiagnosisFilter {
+                    status: status.map(DiagnosisStatus::from),
+                    since: since.map(|day| config::local_zone().start_of_day(day)),
+                    finding: finding.map(|f| f.trim().to_string()).filter(|f| !f.is_empty()),
+                };
+                commands::show_history(db, plant, filter, limit, page, session).await
+            }
+            Commands::Audit { plant } => commands::audit(db, plant).await,
+            Commands::Care { name, room, action } => match action {
+                Some(CareAction::Regenerate { plant, all: _, jobs }) => {
+                    commands::regenerate_care(db, plant, jobs).await
+                }
+                Some(CareAction::History { plant }) => commands::schedule_history(db, plant).await,
+                Some(CareAction::Rollback { plant, version }) => commands::rollback_care(db, plant, version).await,
+                Some(CareAction::Edit {
+                    plant,
+                    light,
+                    water,
+                    humidity,
+                    temperature,
+                    instructions,
+                }) => {
+                    let update = CareScheduleUpdate {
+                        light,
+                        water,
+                        humidity,
+                        temperature,
+                        care_instructions: instructions,
+                    };
+                    commands::edit_care(db, plant, update).await
+                }
+                Some(CareAction::Reset { plant }) => commands::reset_care(db, plant).await,
+                None => commands::generate_care(db, name.unwrap_or_default(), room).await,
+            },
+            Commands::Refresh {
+                stale,
+                dry_run,
+                jobs,
+                queue,
+            } => commands::refresh_care(db, stale, dry_run, jobs, queue).await,
+            Commands::Species { name } => commands::species_lookup(name).await,
+            Commands::Export {
+                format,
+                what,
+                out,
+                encrypt,
+                passphrase_file,
+                anonymize,
+                pii_patterns,
+            } => {
+                commands::export(db, format, what, out, encrypt, passphrase_file, anonymize, pii_patterns).await
+            }
+            Commands::Publish { out, title } => commands::publish(db, out, title).await,
+            Commands::Share { plant, out } => commands::share_plant(db, plant, out).await,
+            Commands::Label { plant, out } => commands::print_label(db, plant, out).await,
+            Commands::Print { room, out } => commands::print_care_sheet(db, room, out).await,
+            Commands::Report { action } => match action {
+                ReportAction::Problems { since } => commands::report_problems(db, since).await,
+            },
+            Commands::Scan { image } => commands::scan_label(db, image).await,
+            Commands::ImportProfile { file } => commands::import_profile(db, file).await,
+            Commands::Import {
+                file,
+                from,
+                passphrase_file,
+            } => match from {
+                Some(app) => commands::import_from_app(db, file, app.into()).await,
+                None => commands::import_archive(db, file, passphrase_file).await,
+            },
+            Commands::Snapshot { plant, out } => commands::snapshot_plant(db, plant, out).await,
+            Commands::RestoreSnapshot { file, yes } => commands::restore_snapshot(db, file, yes).await,
+            Commands::Init => commands::init(),
+            Commands::Archive { plant, reason } => {
+                commands::archive_plant(db, plant, reason.into()).await
+            }
+            Commands::Transfer {
+                plant,
+                to,
+                detach_history,
+                yes,
+            } => commands::transfer_plant(db, plant, to, detach_history, yes).await,
+            Commands::Graveyard => commands::graveyard(db).await,
+            Commands::Eval { models, cases } => commands::eval(models, cases).await,
+            Commands::Bot { platform } => match platform {
+                BotPlatform::Telegram => commands::telegram_bot(db).await,
+                BotPlatform::Discord => commands::discord_bot(db).await,
+            },
+            Commands::Serve { addr } => commands::http_server(db, addr).await,
+            Commands::ApiKey { action } => match action {
+                ApiKeyAction::Create { user } => commands::api_key_create(db, user).await,
+                ApiKeyAction::Revoke { user } => commands::api_key_revoke(db, user).await,
+                ApiKeyAction::List => commands::api_key_list(db).await,
+            },
+            Commands::Grpc { addr } => commands::grpc_server(db, addr).await,
+            Commands::Notify { action } => match action {
+                NotifyAction::Test => commands::notify_test().await,
+            },
+            Commands::Today { done } => commands::today(db, done).await,
+            Commands::Stats => commands::care_stats(db).await,
+            Commands::Vacation { from, to, out } => commands::vacation(db, from, to, out).await,
+            Commands::Sitter { action } => match action {
+                SitterAction::Start => commands::sitter_start(db).await,
+                SitterAction::Stop => commands::sitter_stop(db).await,
+                SitterAction::Status => commands::sitter_status(db).await,
+            },
+            Commands::Sync { queue } => commands::sync(db, queue).await,
+            Commands::Feedback { action } => match action {
+                FeedbackAction::Stats => commands::feedback_stats(db).await,
+            },
+            Commands::Bulk {
+                filter,
+                set_tag,
+                remove_tag,
+                set_room,
+                template,
+                archive,
+                dry_run,
+                yes,
+            } => {
+                let mut actions: Vec<BulkAction> = set_tag.into_iter().map(BulkAction::AddTag).collect();
+                actions.extend(remove_tag.into_iter().map(BulkAction::RemoveTag));
+                if let Some(room) = set_room {
+                    let room = room.trim().to_string();
+                    let room = (!room.eq_ignore_ascii_case("none")).then_some(room);
+                    actions.push(BulkAction::MoveToRoom(room));
+                }
+                actions.extend(template.map(BulkAction::ApplyTemplate));
+                actions.extend(archive.map(|reason| BulkAction::Archive(reason.into())));
+
+                commands::bulk(db, filter, actions, dry_run, yes).await
             }
-            Commands::History { plant } => commands::show_history(db, plant).await,
-            Commands::Care { name } => commands::generate_care(name).await,
+            Commands::Template { action } => match action {
+                TemplateAction::List => commands::template_list(db).await,
+                TemplateAction::Create { name, from } => {
+                    commands::template_create(db, name, from).await
+                }
+                TemplateAction::Show { name } => commands::template_show(db, name).await,
+                TemplateAction::Edit {
+                    name,
+                    light,
+                    water,
+                    humidity,
+                    temperature,
+                    instructions,
+                    rename,
+                } => {
+                    let update = CareScheduleUpdate {
+                        light,
+                        water,
+                        humidity,
+                        temperature,
+                        care_instructions: instructions,
+                    };
+                    commands::template_edit(db, name, update, rename).await
+                }
+                TemplateAction::Apply { name, plant } => {
+                    commands::template_apply(db, name, plant).await
+                }
+                TemplateAction::Delete { name } => commands::template_delete(db, name).await,
+            },
+            Commands::Checkin { plant, result, note } => {
+                let worked = result.map(|r| matches!(r, CheckInResult::Worked));
+                commands::check_in(db, plant, worked, note).await
+            }
+            Commands::Shopping { days, action } => match action {
+                None => commands::shopping_list(db, days).await,
+                Some(ShoppingAction::Add { item }) => commands::shopping_add(db, item).await,
+                Some(ShoppingAction::Remove { item }) => commands::shopping_remove(db, item).await,
+                Some(ShoppingAction::Export { out }) => commands::shopping_export(db, days, out).await,
+            },
+            Commands::Room { action } => match action {
+                RoomAction::Set {
+                    name,
+                    window,
+                    sun_hours,
+                    temperature,
+                    humidity,
+                    drafty,
+                    heated,
+                } => {
+                    let environment = Environment {
+                        temperature,
+                        humidity,
+                        drafty,
+                        heated,
+                    };
+                    commands::room_set(db, name, window.map(Into::into), sun_hours, environment).await
+                }
+                RoomAction::List => commands::room_list(db).await,
+                RoomAction::Remove { name } => commands::room_remove(db, name).await,
+            },
+            Commands::Advise { action } => match action {
+                AdviseAction::Placement { no_ai } => commands::advise_placement(db, !no_ai).await,
+            },
+            Commands::Recommend {
+                light,
+                pets,
+                effort,
+                count,
+            } => {
+                let conditions = Conditions {
+                    light: light.into(),
+                    pets: pets.into_iter().map(Pet::from).collect(),
+                    effort: effort.into(),
+                };
+                commands::recommend(db, conditions, count).await
+            }
+            Commands::Wishlist { action } => match action {
+                None => commands::wishlist_list(db).await,
+                Some(WishlistAction::Add { species, note }) => {
+                    commands::wishlist_add(db, species, note.unwrap_or_default()).await
+                }
+                Some(WishlistAction::Remove { species }) => commands::wishlist_remove(db, species).await,
+            },
+            Commands::Disagreements { action } => match action {
+                None => commands::disagreements(db).await,
+                Some(DisagreementAction::Correct { plant, species }) => {
+                    commands::correct_species(db, plant, species).await
+                }
+            },
+            Commands::Db { action } => match action {
+                DbAction::Stats => commands::db_stats(db).await,
+                DbAction::Repair => commands::db_repair(db).await,
+            },
+            Commands::Jobs { action } => match action {
+                None => commands::jobs_list(db).await,
+                Some(JobsAction::Run { once, poll }) => commands::jobs_run(db, once, poll).await,
+            },
+            Commands::DebugBundle { session, out } => commands::debug_bundle(db, session, out).await,
         }
     }
 }
 
+
+/// Wait for Ctrl-C, then cancel diagnosis sessions this run left waiting
+/// and exit. Runs as its own task so it still fires while a prompt or
+/// request is blocking the command.
+pub async fn cancel_on_interrupt(db: Database) {
+    if tokio::signal::ctrl_c().await.is_err() {
+        return;
+    }
+
+    console::Term::stderr().show_cursor().ok();
+    eprintln!();
+    eprintln!("{}", theme::alert("Interrupted"));
+
+    cancel_in_flight(&db, "interrupted").await;
+    std::process::exit(130);
+}
+
+/// Mark diagnosis sessions this run started or advanced and left waiting
+/// for input as cancelled, so an aborted run never leaves a half-finished
+/// session behind
+pub async fn cancel_in_flight(db: &Database, reason: &str) {
+    match DiagnosisRepository::new(db.clone())
+        .cancel_pending_touched(reason)
+        .await
+    {
+        Ok(0) => {}
+        Ok(count) => eprintln!(
+            "  {} {} unfinished diagnosis session(s) marked as cancelled",
+            theme::muted("Note:"),
+            count
+        ),
+        Err(e) => log::warn!("Could not cancel in-flight diagnosis sessions: {:#}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("180d"), Ok(180));
+        assert_eq!(parse_age("26w"), Ok(182));
+        assert_eq!(parse_age("90"), Ok(90));
+        assert!(parse_age("0d").is_err());
+        assert!(parse_age("6 months").is_err());
+    }
+}
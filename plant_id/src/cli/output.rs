@@ -0,0 +1,308 @@
+/*
+ * OUTPUT MODES
+ *
+ * Every command renders through this module so the global output flags
+ * apply everywhere:
+ * - `--quiet` hides spinners, previews, hints and blank spacer lines, and
+ *   strips emoji from what is left, for cron jobs and CI logs
+ * - `--no-color` (or a non-empty NO_COLOR) turns off all styling
+ * - `--progress` picks how long-running steps are reported: a spinner,
+ *   JSON lines on stderr, or nothing (the default in quiet mode)
+ * - `--output json` makes listings print one JSON document instead, with
+ *   ISO timestamps; it implies `--quiet` so nothing else reaches stdout
+ * - `--accessible` renders for screen readers: no color, spinners, image
+ *   previews or emoji, status symbols spelled out ("Warning:", "Done:"),
+ *   plain prompts, and progress announced as one line per step
+ *
+ * `out!` prints essential output; `note!` prints output quiet mode drops.
+ * Colors come from the palette in `theme`.
+ */
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use super::{OutputFormat, ProgressFormat};
+use plant_care_core::adapters::{JsonProgress, LineProgress, ProgressReporter, SilentProgress};
+use plant_care_core::config;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+static ACCESSIBLE: AtomicBool = AtomicBool::new(false);
+
+static PROGRESS: OnceLock<ProgressFormat> = OnceLock::new();
+
+/// Apply the output flags for the rest of the process
+pub fn init(
+    quiet: bool,
+    no_color: bool,
+    accessible: bool,
+    progress: Option<ProgressFormat>,
+    format: OutputFormat,
+) {
+    let json = format == OutputFormat::Json;
+    JSON.store(json, Ordering::Relaxed);
+    let quiet = quiet || json;
+    QUIET.store(quiet, Ordering::Relaxed);
+    ACCESSIBLE.store(accessible, Ordering::Relaxed);
+
+    let default = if quiet {
+        ProgressFormat::None
+    } else if accessible {
+        ProgressFormat::Lines
+    } else {
+        ProgressFormat::Spinner
+    };
+    PROGRESS.set(progress.unwrap_or(default)).ok();
+
+    let no_color_env = std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty());
+    if no_color || no_color_env || accessible {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+pub fn is_accessible() -> bool {
+    ACCESSIBLE.load(Ordering::Relaxed)
+}
+
+/// Print `value` as the JSON document of `--output json`
+pub fn json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// A date in the owner's time zone followed by how long ago it was, e.g.
+/// "2024-05-01 (3 weeks ago)"
+pub fn date(at: DateTime<Utc>, format: &str) -> String {
+    let zone = config::local_zone();
+    format!("{} ({})", zone.local(at).format(format), zone.relative(at, Utc::now()))
+}
+
+fn progress_format() -> ProgressFormat {
+    *PROGRESS.get().unwrap_or(&ProgressFormat::Spinner)
+}
+
+/// Reporter for a long-running operation, starting with `message`. Call
+/// `finish` once the operation is over.
+pub fn progress(message: &str) -> Box<dyn ProgressReporter> {
+    let reporter: Box<dyn ProgressReporter> = match progress_format() {
+        ProgressFormat::Spinner => Box::new(SpinnerProgress(spinner(message.to_string()))),
+        ProgressFormat::Json => Box::new(JsonProgress::new()),
+        ProgressFormat::Lines => Box::new(LineProgress),
+        ProgressFormat::None => Box::new(SilentProgress),
+    };
+    reporter.step(message);
+    reporter
+}
+
+/// Spinner shown while waiting on a slow operation, for callers that need
+/// to pause it; hidden unless progress is shown as a spinner
+pub fn spinner(message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    if progress_format() != ProgressFormat::Spinner {
+        return ProgressBar::hidden();
+    }
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    spinner.set_message(message);
+    spinner
+}
+
+struct SpinnerProgress(ProgressBar);
+
+impl ProgressReporter for SpinnerProgress {
+    fn step(&self, message: &str) {
+        self.0.set_message(message.to_string());
+    }
+
+    fn finish(&self) {
+        self.0.finish_and_clear();
+    }
+
+    fn suspend(&self, f: &mut dyn FnMut()) {
+        self.0.suspend(f)
+    }
+}
+
+/// A line as it should be printed in the current mode
+pub fn render(line: &str) -> Cow<'_, str> {
+    let line: Cow<'_, str> = if is_accessible() {
+        Cow::Owned(spell_out_symbols(line))
+    } else {
+        Cow::Borrowed(line)
+    };
+
+    if is_quiet() {
+        let stripped = strip_emoji(&line);
+        let lines: Vec<&str> = stripped.lines().filter(|l| !l.trim().is_empty()).collect();
+        Cow::Owned(lines.join("\n"))
+    } else {
+        line
+    }
+}
+
+/// Replace status symbols with words and drop decorative ones, for screen
+/// readers: "⚠ Low light" reads "Warning: Low light", "♥ 86" reads
+/// "Health 86", and "☐ 💧 Fern" reads "To do: Fern"
+fn spell_out_symbols(line: &str) -> String {
+    let mut spelled = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        // Symbols leading a line mark its status; elsewhere they annotate a count
+        let leading = spelled.trim().is_empty();
+        let word = match c {
+            '✓' | '✔' if leading => "Done:",
+            '✓' | '✔' => "OK",
+            '✗' | '✘' if leading => "Failed:",
+            '✗' | '✘' => "failed",
+            '⚠' => "Warning:",
+            '☐' => "To do:",
+            '♥' => "Health",
+            // Star ratings, meter bars and emoji are followed by their value or
+            // repeat the text next to them
+            '★' | '☆' | '\u{2580}'..='\u{259F}' => {
+                while chars.peek().is_some_and(|&next| next == ' ') {
+                    chars.next();
+                }
+                continue;
+            }
+            c if is_emoji(c) => {
+                while chars.peek().is_some_and(|&next| is_emoji(next) || next == ' ') {
+                    chars.next();
+                }
+                continue;
+            }
+            c => {
+                spelled.push(c);
+                continue;
+            }
+        };
+
+        // "Health: ♥ 69" already says it
+        let label = word.trim_end_matches(':');
+        if spelled.trim_end().trim_end_matches(':').ends_with(label) {
+            while chars.peek().is_some_and(|&next| next == ' ') {
+                chars.next();
+            }
+            continue;
+        }
+
+        if !spelled.is_empty() && !spelled.ends_with(' ') {
+            spelled.push(' ');
+        }
+        spelled.push_str(word);
+        // The presentation selector of "⚠️" is not read out either
+        while chars.peek().is_some_and(|&next| next == '\u{FE0F}' || next == '\u{200D}') {
+            chars.next();
+        }
+        if chars.peek().is_some_and(|&next| next != ' ') {
+            spelled.push(' ');
+        }
+    }
+
+    spelled.trim_end().to_string()
+}
+
+/// Remove emoji and decorative symbols, with the space that follows them
+fn strip_emoji(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if is_emoji(c) {
+            while chars.peek().is_some_and(|&next| is_emoji(next) || next == ' ') {
+                chars.next();
+            }
+            continue;
+        }
+        stripped.push(c);
+    }
+
+    stripped
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF      // pictographs, emoticons, transport, flags
+            | 0x2600..=0x27BF  // miscellaneous symbols and dingbats (☐ ✓ ⚠)
+            | 0x2B00..=0x2BFF  // stars and arrows used as badges
+            | 0x200D           // zero-width joiner
+            | 0xFE0F           // emoji presentation selector
+    )
+}
+
+/// Print an essential line (`println!` syntax). In quiet mode emoji are
+/// stripped and blank lines are skipped.
+macro_rules! out {
+    () => {
+        if !$crate::cli::output::is_quiet() {
+            println!();
+        }
+    };
+    ($($arg:tt)*) => {
+        println!("{}", $crate::cli::output::render(&format!($($arg)*)))
+    };
+}
+
+/// Print a hint or decoration that quiet mode drops (`println!` syntax)
+macro_rules! note {
+    () => {
+        if !$crate::cli::output::is_quiet() {
+            println!();
+        }
+    };
+    ($($arg:tt)*) => {
+        if !$crate::cli::output::is_quiet() {
+            println!("{}", $crate::cli::output::render(&format!($($arg)*)));
+        }
+    };
+}
+
+pub(crate) use {note, out};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_emoji_keeps_text_and_arrows() {
+        assert_eq!(strip_emoji("🌿 Your Plant Collection"), "Your Plant Collection");
+        assert_eq!(strip_emoji("✓ Done: Fern"), "Done: Fern");
+        assert_eq!(strip_emoji("☐ 💧 Fern - Water"), "Fern - Water");
+        assert_eq!(strip_emoji("⚠️ Low confidence"), "Low confidence");
+        assert_eq!(strip_emoji("status: 1 → 2"), "status: 1 → 2");
+    }
+
+    #[test]
+    fn test_spell_out_symbols() {
+        assert_eq!(spell_out_symbols("⚠️ Low confidence"), "Warning: Low confidence");
+        assert_eq!(spell_out_symbols("✓ Plant added successfully!"), "Done: Plant added successfully!");
+        assert_eq!(spell_out_symbols("  ☐ 💧 Fern - Water"), "  To do: Fern - Water");
+        assert_eq!(spell_out_symbols("  Difficulty: ★★★☆☆ 3/5"), "  Difficulty: 3/5");
+        assert_eq!(spell_out_symbols("Fern ♥ 86"), "Fern Health 86");
+        assert_eq!(spell_out_symbols("  Health: ♥ 86"), "  Health: 86");
+        assert_eq!(spell_out_symbols("  2✓ 1✗ Root rot → Repot"), "  2 OK 1 failed Root rot → Repot");
+        assert_eq!(spell_out_symbols("🌿 Your Plant Collection"), "Your Plant Collection");
+    }
+}
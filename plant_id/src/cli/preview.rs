@@ -0,0 +1,207 @@
+/*
+ * TERMINAL IMAGE PREVIEW
+ *
+ * Renders plant thumbnails inline in the terminal. Terminals that support
+ * a graphics protocol (kitty, iTerm2, sixel) get the real image; others
+ * get a unicode half-block approximation using 24-bit colors.
+ *
+ * Detection can be overridden with PLANT_CARE_GRAPHICS=kitty|iterm2|sixel|blocks|none.
+ */
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use console::Term;
+use image::imageops::FilterType;
+use image::RgbImage;
+use std::io::Write;
+
+/// Approximate width of a terminal cell in pixels, used to size sixel output
+const CELL_WIDTH_PX: u32 = 8;
+
+/// Maximum payload size of a single kitty graphics escape sequence
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+    Blocks,
+    None,
+}
+
+fn detect_protocol() -> GraphicsProtocol {
+    if let Ok(forced) = std::env::var("PLANT_CARE_GRAPHICS") {
+        return match forced.as_str() {
+            "kitty" => GraphicsProtocol::Kitty,
+            "iterm2" => GraphicsProtocol::Iterm2,
+            "sixel" => GraphicsProtocol::Sixel,
+            "blocks" => GraphicsProtocol::Blocks,
+            _ => GraphicsProtocol::None,
+        };
+    }
+
+    if !Term::stdout().is_term() {
+        return GraphicsProtocol::None;
+    }
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+
+    if std::env::var("KITTY_WINDOW_ID").is_ok() || term == "xterm-kitty" {
+        GraphicsProtocol::Kitty
+    } else if term_program == "iTerm.app" || term_program == "WezTerm" {
+        GraphicsProtocol::Iterm2
+    } else if term.contains("sixel") || term == "foot" || term_program == "mlterm" {
+        GraphicsProtocol::Sixel
+    } else if console::colors_enabled() {
+        GraphicsProtocol::Blocks
+    } else {
+        GraphicsProtocol::None
+    }
+}
+
+/// Print a PNG thumbnail inline, roughly `width` terminal cells wide
+pub fn print_preview(png: &[u8], width: u32) -> Result<()> {
+    let output = match detect_protocol() {
+        GraphicsProtocol::Kitty => kitty(png, width),
+        GraphicsProtocol::Iterm2 => iterm2(png, width),
+        GraphicsProtocol::Sixel => sixel(&decode(png, width * CELL_WIDTH_PX)?),
+        GraphicsProtocol::Blocks => blocks(&decode(png, width)?),
+        GraphicsProtocol::None => return Ok(()),
+    };
+
+    let mut stdout = std::io::stdout();
+    stdout.write_all(output.as_bytes())?;
+    stdout.write_all(b"\n")?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Decode and scale an image to the given pixel width, keeping aspect ratio
+fn decode(png: &[u8], width: u32) -> Result<RgbImage> {
+    let image = image::load_from_memory(png)?;
+    let height = (image.height() * width / image.width().max(1)).max(1);
+
+    Ok(image
+        .resize_exact(width, height, FilterType::Triangle)
+        .to_rgb8())
+}
+
+fn kitty(png: &[u8], width: u32) -> String {
+    let encoded = BASE64.encode(png);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut output = String::new();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let payload = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            output.push_str(&format!("\x1b_Gf=100,a=T,c={},m={};{}\x1b\\", width, more, payload));
+        } else {
+            output.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, payload));
+        }
+    }
+
+    output
+}
+
+fn iterm2(png: &[u8], width: u32) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;width={};preserveAspectRatio=1;size={}:{}\x07",
+        width,
+        png.len(),
+        BASE64.encode(png)
+    )
+}
+
+/// Encode an image as sixel graphics using a 6x6x6 color cube palette
+fn sixel(image: &RgbImage) -> String {
+    let (width, height) = image.dimensions();
+    let palette_index = |x: u32, y: u32| -> usize {
+        let [r, g, b] = image.get_pixel(x, y).0;
+        let level = |c: u8| (c as usize * 5 + 127) / 255;
+        level(r) * 36 + level(g) * 6 + level(b)
+    };
+
+    let mut output = format!("\x1bPq\"1;1;{};{}", width, height);
+    for i in 0..216 {
+        let percent = |level: usize| level * 100 / 5;
+        output.push_str(&format!(
+            "#{};2;{};{};{}",
+            i,
+            percent(i / 36),
+            percent(i / 6 % 6),
+            percent(i % 6)
+        ));
+    }
+
+    for band in (0..height).step_by(6) {
+        let band_height = (height - band).min(6);
+
+        // Bitmask of rows in this band that use each color, per column
+        let mut masks = vec![vec![0u8; width as usize]; 216];
+        let mut used = vec![false; 216];
+        for x in 0..width {
+            for row in 0..band_height {
+                let color = palette_index(x, band + row);
+                masks[color][x as usize] |= 1 << row;
+                used[color] = true;
+            }
+        }
+
+        for (color, mask) in masks.iter().enumerate().filter(|(c, _)| used[*c]) {
+            output.push_str(&format!("#{}", color));
+            push_run_length_encoded(&mut output, mask);
+            output.push('$');
+        }
+        output.push('-');
+    }
+
+    output.push_str("\x1b\\");
+    output
+}
+
+/// Append sixel characters for a color's column masks, compressing runs
+fn push_run_length_encoded(output: &mut String, mask: &[u8]) {
+    let mut i = 0;
+    while i < mask.len() {
+        let run = mask[i..].iter().take_while(|m| **m == mask[i]).count();
+        let c = (63 + mask[i]) as char;
+        if run > 3 {
+            output.push_str(&format!("!{}{}", run, c));
+        } else {
+            output.extend(std::iter::repeat_n(c, run));
+        }
+        i += run;
+    }
+}
+
+/// Approximate an image with upper half blocks: each cell shows two pixels
+/// (foreground on top, background below), which keeps pixels roughly square
+fn blocks(image: &RgbImage) -> String {
+    let (width, height) = image.dimensions();
+    let mut output = String::new();
+
+    for y in (0..height).step_by(2) {
+        for x in 0..width {
+            let [r1, g1, b1] = image.get_pixel(x, y).0;
+            let [r2, g2, b2] = if y + 1 < height {
+                image.get_pixel(x, y + 1).0
+            } else {
+                [0, 0, 0]
+            };
+            output.push_str(&format!(
+                "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m▀",
+                r1, g1, b1, r2, g2, b2
+            ));
+        }
+        output.push_str("\x1b[0m");
+        if y + 2 < height {
+            output.push('\n');
+        }
+    }
+
+    output
+}
@@ -0,0 +1,217 @@
+/*
+ * TERMINAL THEMES
+ *
+ * Call sites style text by what it is - a heading, a warning, a muted
+ * label - and the palette picked with `--theme` (or THEME) decides how that
+ * looks, so the output stays readable on any terminal background:
+ * - `default` suits dark terminals: green titles, cyan headings, dim labels
+ * - `light-terminal` avoids yellow and dim text, which wash out on white
+ * - `high-contrast` uses bright, bold colors and never dims
+ * - `none` prints plain text, like `--no-color`
+ *
+ * Prompts follow the palette too, through `prompts()`.
+ */
+
+use anyhow::Result;
+use clap::ValueEnum;
+use console::{Color, Style, StyledObject};
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+use std::sync::OnceLock;
+
+use super::{output, Palette};
+
+/// How each role is styled
+struct Styles {
+    title: Style,
+    success: Style,
+    heading: Style,
+    accent: Style,
+    good: Style,
+    warning: Style,
+    alert: Style,
+    bad: Style,
+    danger: Style,
+    muted: Style,
+    strong: Style,
+    scientific: Style,
+    water: Style,
+}
+
+impl Styles {
+    fn of(palette: Palette) -> Self {
+        let plain = Style::new;
+        match palette {
+            Palette::Default => Self {
+                title: plain().green().bold(),
+                success: plain().green().bold(),
+                heading: plain().cyan().bold(),
+                accent: plain().cyan(),
+                good: plain().green(),
+                warning: plain().yellow(),
+                alert: plain().yellow().bold(),
+                bad: plain().red(),
+                danger: plain().red().bold(),
+                muted: plain().dim(),
+                strong: plain().bold(),
+                scientific: plain().italic(),
+                water: plain().blue(),
+            },
+            // Dark shades, with gray instead of dim and brown instead of yellow
+            Palette::LightTerminal => Self {
+                title: plain().fg(Color::Color256(22)).bold(),
+                success: plain().fg(Color::Color256(22)).bold(),
+                heading: plain().blue().bold(),
+                accent: plain().blue(),
+                good: plain().fg(Color::Color256(28)),
+                warning: plain().fg(Color::Color256(130)),
+                alert: plain().fg(Color::Color256(130)).bold(),
+                bad: plain().fg(Color::Color256(124)),
+                danger: plain().fg(Color::Color256(124)).bold(),
+                muted: plain().fg(Color::Color256(240)),
+                strong: plain().bold(),
+                scientific: plain().italic(),
+                water: plain().fg(Color::Color256(25)),
+            },
+            Palette::HighContrast => Self {
+                title: plain().green().bright().bold(),
+                success: plain().green().bright().bold(),
+                heading: plain().cyan().bright().bold().underlined(),
+                accent: plain().cyan().bright(),
+                good: plain().green().bright(),
+                warning: plain().yellow().bright().bold(),
+                alert: plain().black().on_yellow().bold(),
+                bad: plain().red().bright().bold(),
+                danger: plain().white().on_red().bold(),
+                muted: plain(),
+                strong: plain().bold(),
+                scientific: plain().italic(),
+                water: plain().blue().bright().bold(),
+            },
+            Palette::None => Self {
+                title: plain(),
+                success: plain(),
+                heading: plain(),
+                accent: plain(),
+                good: plain(),
+                warning: plain(),
+                alert: plain(),
+                bad: plain(),
+                danger: plain(),
+                muted: plain(),
+                strong: plain(),
+                scientific: plain(),
+                water: plain(),
+            },
+        }
+    }
+}
+
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+static STYLES: OnceLock<Styles> = OnceLock::new();
+
+/// Pick the palette for the rest of the process: `--theme`, then THEME,
+/// then the default
+pub fn init(palette: Option<Palette>) -> Result<()> {
+    let palette = match (palette, std::env::var("THEME")) {
+        (Some(palette), _) => palette,
+        (None, Ok(name)) if !name.trim().is_empty() => Palette::from_str(name.trim(), true).map_err(|_| {
+            anyhow::anyhow!(
+                "Unknown THEME '{}'; expected default, light-terminal, high-contrast or none",
+                name
+            )
+        })?,
+        (None, _) => Palette::Default,
+    };
+    if palette == Palette::None {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+    PALETTE.set(palette).ok();
+    Ok(())
+}
+
+fn palette() -> Palette {
+    *PALETTE.get().unwrap_or(&Palette::Default)
+}
+
+fn styles() -> &'static Styles {
+    STYLES.get_or_init(|| Styles::of(palette()))
+}
+
+/// Theme for interactive prompts; the plain one has no symbols or color
+pub fn prompts() -> Box<dyn Theme> {
+    if output::is_accessible() {
+        return Box::new(SimpleTheme);
+    }
+
+    let styles = styles();
+    Box::new(ColorfulTheme {
+        values_style: styles.accent.clone(),
+        hint_style: styles.muted.clone(),
+        active_item_style: styles.accent.clone(),
+        success_prefix: styles.good.apply_to("✔".to_string()),
+        error_prefix: styles.bad.apply_to("✘".to_string()),
+        error_style: styles.bad.clone(),
+        prompt_prefix: styles.warning.apply_to("?".to_string()),
+        ..ColorfulTheme::default()
+    })
+}
+
+macro_rules! roles {
+    ($($(#[$doc:meta])* $role:ident),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            pub fn $role<D>(value: D) -> StyledObject<D> {
+                styles().$role.apply_to(value)
+            }
+        )*
+    };
+}
+
+roles! {
+    /// Title of a listing or report
+    title,
+    /// Confirmation that something was done
+    success,
+    /// Section heading, or the name leading an entry
+    heading,
+    /// A value to pick out, such as a name or a command
+    accent,
+    /// A healthy or favourable value
+    good,
+    /// Something to keep an eye on
+    warning,
+    /// A warning that needs acting on
+    alert,
+    /// An unhealthy or unfavourable value
+    bad,
+    /// A failure or critical finding
+    danger,
+    /// Labels, hints and secondary details
+    muted,
+    /// Emphasis without a meaning attached
+    strong,
+    /// Scientific names
+    scientific,
+    /// Watering and soil moisture
+    water,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palettes_style_roles_differently() {
+        let dark = Styles::of(Palette::Default);
+        let light = Styles::of(Palette::LightTerminal);
+        let plain = Styles::of(Palette::None);
+
+        let render = |style: &Style| style.apply_to("x").force_styling(true).to_string();
+        assert_eq!(render(&dark.muted), "\u{1b}[2mx\u{1b}[0m");
+        assert_ne!(render(&light.muted), render(&dark.muted));
+        assert_ne!(render(&light.warning), render(&dark.warning));
+        assert_eq!(render(&plain.danger), "x");
+    }
+}
@@ -0,0 +1,379 @@
+/*!
+ * GRPC FRONT-END
+ *
+ * Serves the plant_care_core services over gRPC (proto/plant_care.proto)
+ * for mobile clients. Diagnosis calls are server-streaming: progress is
+ * sent while the AI works, then its question, photo request or conclusion.
+ *
+ * The server acts on the local collection. When GRPC_TOKEN is set every
+ * call must carry it as a bearer token; without it the server only binds
+ * to loopback addresses. Messages may be as large as a photo of
+ * `MAX_IMAGE_MB`.
+ */
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::service::interceptor::InterceptedService;
+use tonic::{Request, Response, Status};
+
+use plant_care_core::adapters::{notification_adapter, payload, plant_id_adapter, search_adapter, storage_adapter};
+use plant_care_core::config::get_secret;
+use plant_care_core::domain::{CareScheduleUpdate, Plant, RequestError};
+use plant_care_core::repositories::JobRepository;
+use plant_care_core::services::care_schedule_service;
+use plant_care_core::{
+    AiAdapter, AttributeRepository, CareSchedule, Database, DiagnosisRepository, DiagnosisResponseDto,
+    DiagnosisService, DiagnosisStartDto, DiagnosisUpdateDto, EventBus, MoistureRepository,
+    OutcomeRepository, PlantRepository, RoomRepository, UserRepository,
+};
+
+pub mod proto {
+    #![allow(clippy::all)]
+    tonic::include_proto!("plantcare.v1");
+}
+
+use proto::plant_care_server::{PlantCare, PlantCareServer};
+use proto::{diagnosis_event, DiagnosisEvent};
+
+const USER_ID: &str = "local-user";
+
+/// Default address, reachable from this machine only
+pub const DEFAULT_ADDR: &str = "127.0.0.1:50051";
+
+type DiagnosisStream = Pin<Box<dyn Stream<Item = Result<DiagnosisEvent, Status>> + Send>>;
+
+type CycleFuture = Pin<Box<dyn Future<Output = Result<DiagnosisResponseDto>> + Send>>;
+
+pub struct GrpcServer {
+    addr: SocketAddr,
+    token: Option<String>,
+    service: PlantCareService,
+}
+
+impl GrpcServer {
+    pub fn from_env(db: Database, addr: SocketAddr) -> Result<Self> {
+        let token = get_secret("GRPC_TOKEN").ok().filter(|t| !t.is_empty());
+        if token.is_none() && !addr.ip().is_loopback() {
+            anyhow::bail!(
+                "Set GRPC_TOKEN to serve on {}; without it only loopback addresses are allowed",
+                addr
+            );
+        }
+
+        let diagnosis_service = DiagnosisService::new(
+            PlantRepository::new(db.clone()),
+            DiagnosisRepository::new(db.clone()),
+            OutcomeRepository::new(db.clone()),
+            MoistureRepository::new(db.clone()),
+            AttributeRepository::new(db.clone()),
+            RoomRepository::new(db.clone()),
+            UserRepository::new(db.clone()),
+            AiAdapter::new()?,
+            storage_adapter::from_env()?,
+            search_adapter::from_env()?,
+            EventBus::new(notification_adapter::from_env()?).with_queue(JobRepository::new(db.clone())),
+        )
+        .with_health_assessment(plant_id_adapter::health_assessor());
+
+        Ok(Self {
+            addr,
+            token,
+            service: PlantCareService {
+                plant_repo: PlantRepository::new(db),
+                diagnosis_service: Arc::new(diagnosis_service),
+            },
+        })
+    }
+
+    pub fn requires_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    // tonic fixes the interceptor's error type to Status
+    #[allow(clippy::result_large_err)]
+    pub async fn run(self) -> Result<()> {
+        let expected = self.token.map(|token| format!("Bearer {}", token));
+        let service = PlantCareServer::new(self.service).max_decoding_message_size(payload::max_request_bytes());
+        let server = InterceptedService::new(service, move |request: Request<()>| {
+            let Some(expected) = &expected else {
+                return Ok(request);
+            };
+            let given = request.metadata().get("authorization").and_then(|v| v.to_str().ok());
+            if given == Some(expected.as_str()) {
+                Ok(request)
+            } else {
+                Err(Status::unauthenticated("Missing or wrong GRPC_TOKEN"))
+            }
+        });
+
+        tonic::transport::Server::builder()
+            .add_service(server)
+            .serve_with_shutdown(self.addr, async {
+                tokio::signal::ctrl_c().await.ok();
+            })
+            .await
+            .context("gRPC server failed")
+    }
+}
+
+struct PlantCareService {
+    plant_repo: PlantRepository,
+    diagnosis_service: Arc<DiagnosisService>,
+}
+
+impl PlantCareService {
+    async fn find_plant(&self, identifier: &str) -> Result<Plant, Status> {
+        self.plant_repo.find(identifier, USER_ID).await.map_err(to_status)
+    }
+
+    /// Run one diagnosis cycle in the background, streaming its progress
+    /// and outcome
+    fn stream_cycle<F>(&self, cycle: F) -> DiagnosisStream
+    where
+        F: FnOnce(Arc<DiagnosisService>) -> CycleFuture + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(4);
+        let service = self.diagnosis_service.clone();
+
+        tokio::spawn(async move {
+            let progress = DiagnosisEvent {
+                event: Some(diagnosis_event::Event::Progress(proto::Progress {
+                    message: "AI is analyzing...".to_string(),
+                })),
+            };
+            if tx.send(Ok(progress)).await.is_err() {
+                return;
+            }
+
+            let result = cycle(service).await.map(diagnosis_event).map_err(to_status);
+            tx.send(result).await.ok();
+        });
+
+        Box::pin(ReceiverStream::new(rx))
+    }
+}
+
+#[tonic::async_trait]
+impl PlantCare for PlantCareService {
+    async fn list_plants(
+        &self,
+        _request: Request<proto::ListPlantsRequest>,
+    ) -> Result<Response<proto::ListPlantsResponse>, Status> {
+        let plants = self.plant_repo.get_all_by_user(USER_ID).await.map_err(to_status)?;
+
+        Ok(Response::new(proto::ListPlantsResponse {
+            plants: plants.iter().map(plant_message).collect(),
+        }))
+    }
+
+    async fn get_plant(
+        &self,
+        request: Request<proto::GetPlantRequest>,
+    ) -> Result<Response<proto::Plant>, Status> {
+        let plant = self.find_plant(&request.into_inner().plant).await?;
+        Ok(Response::new(plant_message(&plant)))
+    }
+
+    async fn update_care_schedule(
+        &self,
+        request: Request<proto::UpdateCareScheduleRequest>,
+    ) -> Result<Response<proto::UpdateCareScheduleResponse>, Status> {
+        let request = request.into_inner();
+        let update = request.changes.map(care_schedule_update).unwrap_or_default();
+
+        let mut plant = self.find_plant(&request.plant).await?;
+        let warnings = care_schedule_service::edit_plant_schedule(&self.plant_repo, &mut plant, &update)
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(proto::UpdateCareScheduleResponse {
+            care_schedule: Some(care_schedule_message(&plant.care_schedule)),
+            warnings,
+        }))
+    }
+
+    type StartDiagnosisStream = DiagnosisStream;
+
+    async fn start_diagnosis(
+        &self,
+        request: Request<proto::StartDiagnosisRequest>,
+    ) -> Result<Response<Self::StartDiagnosisStream>, Status> {
+        let request = request.into_inner();
+        if request.problem.trim().is_empty() {
+            return Err(Status::invalid_argument("Describe the problem to diagnose"));
+        }
+        let plant = self.find_plant(&request.plant).await?;
+
+        let dto = DiagnosisStartDto {
+            prompt: request.problem,
+            photo: request.photo.map(|photo| BASE64.encode(photo)),
+        };
+        Ok(Response::new(self.stream_cycle(move |service| {
+            Box::pin(async move { service.start_diagnosis(&plant.id, dto, USER_ID.to_string()).await })
+        })))
+    }
+
+    type AnswerDiagnosisStream = DiagnosisStream;
+
+    async fn answer_diagnosis(
+        &self,
+        request: Request<proto::AnswerDiagnosisRequest>,
+    ) -> Result<Response<Self::AnswerDiagnosisStream>, Status> {
+        let request = request.into_inner();
+        let dto = DiagnosisUpdateDto {
+            message: request.message,
+            photo: request.photo.map(|photo| BASE64.encode(photo)),
+        };
+        let diagnosis_id = request.diagnosis_id;
+
+        Ok(Response::new(self.stream_cycle(move |service| {
+            Box::pin(async move {
+                service.update_diagnosis(&diagnosis_id, dto, USER_ID.to_string()).await
+            })
+        })))
+    }
+
+    async fn apply_care_update(
+        &self,
+        request: Request<proto::ApplyCareUpdateRequest>,
+    ) -> Result<Response<proto::Plant>, Status> {
+        let plant = self
+            .diagnosis_service
+            .apply_care_update(&request.into_inner().diagnosis_id, USER_ID)
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(plant_message(&plant)))
+    }
+}
+
+/// Map a service error to the closest gRPC status
+fn to_status(error: anyhow::Error) -> Status {
+    let message = format!("{:#}", error);
+    match error.chain().find_map(|e| e.downcast_ref::<RequestError>()) {
+        Some(RequestError::NotFound(_)) => Status::not_found(message),
+        Some(RequestError::AmbiguousName { .. } | RequestError::Invalid { .. }) => Status::invalid_argument(message),
+        Some(RequestError::NotPermitted(_) | RequestError::Unauthorized(_)) => Status::permission_denied(message),
+        None => Status::internal(message),
+    }
+}
+
+fn diagnosis_event(response: DiagnosisResponseDto) -> DiagnosisEvent {
+    let event = match response {
+        DiagnosisResponseDto::Ask(ask) => diagnosis_event::Event::Question(proto::Question {
+            diagnosis_id: ask.diagnosis_id,
+            question: ask.question,
+        }),
+        DiagnosisResponseDto::RequestPhoto(request) => {
+            diagnosis_event::Event::PhotoRequest(proto::PhotoRequest {
+                diagnosis_id: request.diagnosis_id,
+                part: request.part,
+                reason: request.reason,
+            })
+        }
+        DiagnosisResponseDto::Conclude(conclusion) => {
+            let care_update = conclusion.care_update.clone();
+            diagnosis_event::Event::Conclusion(proto::Conclusion {
+                diagnosis_id: conclusion.diagnosis_id,
+                finding: conclusion.finding,
+                recommendation: conclusion.recommendation,
+                confidence: conclusion.confidence,
+                alternatives: conclusion
+                    .alternatives
+                    .into_iter()
+                    .map(|a| proto::Alternative {
+                        finding: a.finding,
+                        probability: a.probability,
+                    })
+                    .collect(),
+                care_update: care_update.as_ref().map(|u| care_schedule_update_message(&u.changes)),
+                care_update_reason: care_update.and_then(|u| u.reason),
+                severity: conclusion.severity.map(|s| s.as_str().to_string()),
+            })
+        }
+    };
+
+    DiagnosisEvent { event: Some(event) }
+}
+
+fn plant_message(plant: &Plant) -> proto::Plant {
+    proto::Plant {
+        id: plant.id.clone(),
+        name: plant.name.clone(),
+        care_schedule: Some(care_schedule_message(&plant.care_schedule)),
+        room: plant.room.clone(),
+        tags: plant.tags.clone(),
+        difficulty: plant.difficulty.as_ref().map(|d| d.rating.into()),
+        created_at: plant.created_at.to_rfc3339(),
+        updated_at: plant.updated_at.to_rfc3339(),
+        scientific_name: plant.scientific_name.clone(),
+        common_names: plant.common_names.clone(),
+        nickname: plant.nickname.clone(),
+    }
+}
+
+fn care_schedule_message(schedule: &CareSchedule) -> proto::CareSchedule {
+    proto::CareSchedule {
+        light: schedule.light.clone(),
+        water: schedule.water.clone(),
+        humidity: schedule.humidity.clone(),
+        temperature: schedule.temperature.clone(),
+        care_instructions: schedule.care_instructions.clone(),
+    }
+}
+
+fn care_schedule_update_message(update: &CareScheduleUpdate) -> proto::CareScheduleUpdate {
+    proto::CareScheduleUpdate {
+        light: update.light.clone(),
+        water: update.water.clone(),
+        humidity: update.humidity.clone(),
+        temperature: update.temperature.clone(),
+        care_instructions: update.care_instructions.clone(),
+    }
+}
+
+/// Blank fields count as unset, as clients cannot always tell them apart
+fn care_schedule_update(message: proto::CareScheduleUpdate) -> CareScheduleUpdate {
+    let field = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+    CareScheduleUpdate {
+        light: field(message.light),
+        water: field(message.water),
+        humidity: field(message.humidity),
+        temperature: field(message.temperature),
+        care_instructions: field(message.care_instructions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_status() {
+        assert_eq!(to_status(RequestError::NotFound("Plant").into()).code(), tonic::Code::NotFound);
+        assert_eq!(
+            to_status(RequestError::NotPermitted("start AI sessions").into()).code(),
+            tonic::Code::PermissionDenied
+        );
+        assert_eq!(to_status(anyhow::anyhow!("AI request failed")).code(), tonic::Code::Internal);
+    }
+
+    #[test]
+    fn test_blank_update_fields_are_unset() {
+        let update = care_schedule_update(proto::CareScheduleUpdate {
+            water: Some("Every 6 days".to_string()),
+            light: Some("  ".to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(update.water.as_deref(), Some("Every 6 days"));
+        assert_eq!(update.light, None);
+    }
+}
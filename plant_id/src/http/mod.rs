@@ -0,0 +1,609 @@
+/*!
+ * HTTP FRONT-END
+ *
+ * Serves the plant_care_core services as a JSON API for third-party
+ * clients. The OpenAPI 3 document is generated from the handlers and DTOs
+ * below and served at `/openapi.json`, with Swagger UI at `/docs`.
+ *
+ * Every API request needs an API key (`plant-cli api-key create`) as a
+ * bearer token. The key decides whose collection the request acts on, so
+ * several people can share one server without seeing each other's plants.
+ *
+ * Prometheus metrics are served at `/metrics`, which like the
+ * documentation needs no key.
+ *
+ * Request bodies may be as large as a base64 photo of `MAX_IMAGE_MB`, so
+ * a photo that is too large or not an image gets the typed error below.
+ *
+ * Plants are served with a link to their photo that expires, never with
+ * where the photo is stored. A failure the client can't fix is answered
+ * with a generic message and logged here.
+ */
+
+use anyhow::{Context, Result};
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{DefaultBodyLimit, Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, patch, post};
+use axum::{Extension, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+use plant_care_core::adapters::{
+    notification_adapter, payload::{self, PayloadError}, plant_id_adapter, search_adapter, storage_adapter, MetricsExporter,
+};
+use plant_care_core::domain::{CareDifficulty, CareScheduleUpdate, Plant, PlantArchive, RequestError};
+use plant_care_core::repositories::JobRepository;
+use plant_care_core::services::{care_schedule_service, ApiKeyService};
+use plant_care_core::{
+    AiAdapter, AttributeRepository, CareSchedule, Database, DiagnosisRepository, DiagnosisResponseDto,
+    DiagnosisService, DiagnosisStartDto, DiagnosisUpdateDto, EventBus, MoistureRepository,
+    OutcomeRepository, PlantRepository, RoomRepository, StoragePort, UserRepository,
+};
+
+/// Default address, reachable from this machine only
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+/// How long the photo links of served plants stay valid
+const IMAGE_LINK_EXPIRY_SECS: u32 = 3600;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Plant Care API",
+        description = "Plants, care schedules and AI diagnoses of a plant care collection"
+    ),
+    paths(
+        list_plants,
+        get_plant,
+        update_care_schedule,
+        start_diagnosis,
+        answer_diagnosis,
+        apply_care_update
+    ),
+    tags(
+        (name = "plants", description = "The plant collection and care schedules"),
+        (name = "diagnoses", description = "AI diagnosis conversations")
+    ),
+    modifiers(&ApiKeyAuth),
+    security(("api_key" = []))
+)]
+pub struct ApiDoc;
+
+/// Declares the bearer API key every request needs
+struct ApiKeyAuth;
+
+impl Modify for ApiKeyAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("API key from `plant-cli api-key create`"))
+                    .build(),
+            ),
+        );
+    }
+}
+
+pub struct HttpServer {
+    addr: SocketAddr,
+    state: Arc<AppState>,
+}
+
+struct AppState {
+    plant_repo: PlantRepository,
+    storage: Arc<dyn StoragePort>,
+    diagnosis_service: DiagnosisService,
+    api_key_service: ApiKeyService,
+    metrics: MetricsExporter,
+}
+
+/// ID of the user a request's API key belongs to
+#[derive(Clone)]
+struct AuthUser(String);
+
+impl HttpServer {
+    pub fn from_env(db: Database, addr: SocketAddr) -> Result<Self> {
+        let storage = storage_adapter::from_env()?;
+        let diagnosis_service = DiagnosisService::new(
+            PlantRepository::new(db.clone()),
+            DiagnosisRepository::new(db.clone()),
+            OutcomeRepository::new(db.clone()),
+            MoistureRepository::new(db.clone()),
+            AttributeRepository::new(db.clone()),
+            RoomRepository::new(db.clone()),
+            UserRepository::new(db.clone()),
+            AiAdapter::new()?,
+            storage.clone(),
+            search_adapter::from_env()?,
+            EventBus::new(notification_adapter::from_env()?).with_queue(JobRepository::new(db.clone())),
+        )
+        .with_health_assessment(plant_id_adapter::health_assessor());
+
+        Ok(Self {
+            addr,
+            state: Arc::new(AppState {
+                plant_repo: PlantRepository::new(db.clone()),
+                storage,
+                diagnosis_service,
+                api_key_service: ApiKeyService::new(UserRepository::new(db)),
+                metrics: MetricsExporter::install()?,
+            }),
+        })
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(self.addr)
+            .await
+            .context(format!("Failed to listen on {}", self.addr))?;
+
+        axum::serve(listener, router(self.state))
+            .with_graceful_shutdown(async {
+                tokio::signal::ctrl_c().await.ok();
+            })
+            .await
+            .context("HTTP server failed")
+    }
+}
+
+fn router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/plants", get(list_plants))
+        .route("/plants/:plant", get(get_plant))
+        .route("/plants/:plant/care-schedule", patch(update_care_schedule))
+        .route("/plants/:plant/diagnoses", post(start_diagnosis))
+        .route("/diagnoses/:diagnosis_id/answers", post(answer_diagnosis))
+        .route("/diagnoses/:diagnosis_id/care-update", post(apply_care_update))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        // Metrics and the documentation are public
+        .route("/metrics", get(metrics))
+        .layer(DefaultBodyLimit::max(payload::max_request_bytes()))
+        .with_state(state)
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi()))
+}
+
+/// Prometheus metrics of this server
+async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
+/// Resolve the bearer API key to its user, rejecting the request without one
+async fn authenticate(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let key = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let user_id = match key {
+        Some(key) => state.api_key_service.authenticate(key.trim()).await?,
+        None => None,
+    };
+    let Some(user_id) = user_id else {
+        let error = "Missing or unknown API key".to_string();
+        return Ok((StatusCode::UNAUTHORIZED, Json(ErrorResponse { error })).into_response());
+    };
+
+    request.extensions_mut().insert(AuthUser(user_id));
+    Ok(next.run(request).await)
+}
+
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// A plant as served to clients
+#[derive(Serialize, ToSchema)]
+struct PlantView {
+    id: String,
+    name: String,
+    nickname: Option<String>,
+    scientific_name: Option<String>,
+    common_names: Vec<String>,
+    care_schedule: CareSchedule,
+    /// Link to the plant's photo, valid for an hour
+    image_link: Option<String>,
+    room: Option<String>,
+    tags: Vec<String>,
+    difficulty: Option<CareDifficulty>,
+    family: Option<String>,
+    acquired_at: Option<DateTime<Utc>>,
+    acquired_from: Option<String>,
+    archived: Option<PlantArchive>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl PlantView {
+    async fn new(plant: Plant, storage: &dyn StoragePort) -> Result<Self> {
+        let image_link = match &plant.image_url {
+            Some(url) => Some(storage.presigned_url(url, IMAGE_LINK_EXPIRY_SECS).await?),
+            None => None,
+        };
+
+        Ok(Self {
+            id: plant.id,
+            name: plant.name,
+            nickname: plant.nickname,
+            scientific_name: plant.scientific_name,
+            common_names: plant.common_names,
+            care_schedule: plant.care_schedule,
+            image_link,
+            room: plant.room,
+            tags: plant.tags,
+            difficulty: plant.difficulty,
+            family: plant.family,
+            acquired_at: plant.acquired_at,
+            acquired_from: plant.acquired_from,
+            archived: plant.archived,
+            created_at: plant.created_at,
+            updated_at: plant.updated_at,
+        })
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+struct CareScheduleResponse {
+    care_schedule: CareSchedule,
+    /// Doubts about the new schedule that did not block saving
+    warnings: Vec<String>,
+}
+
+/// A service error, answered with the closest HTTP status
+struct ApiError(anyhow::Error);
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self(error)
+    }
+}
+
+/// A body that is not the expected JSON. One over the body limit can
+/// only be a photo over `MAX_IMAGE_MB`.
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        let status = rejection.status();
+        let error = anyhow::Error::from(rejection);
+        if status == StatusCode::PAYLOAD_TOO_LARGE {
+            return Self(error.context(format!(
+                "The request is over the {} MB limit (set MAX_IMAGE_MB to allow larger photos)",
+                payload::max_request_bytes() / (1024 * 1024)
+            )));
+        }
+        Self(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = status_code(&self.0);
+        let error = if status == StatusCode::INTERNAL_SERVER_ERROR {
+            // The cause may name files, hosts or queries; keep it in the log
+            log::error!("Request failed: {:#}", self.0);
+            "Internal server error".to_string()
+        } else {
+            format!("{:#}", self.0)
+        };
+        (status, Json(ErrorResponse { error })).into_response()
+    }
+}
+
+fn status_code(error: &anyhow::Error) -> StatusCode {
+    if let Some(rejection) = error.chain().find_map(|e| e.downcast_ref::<JsonRejection>()) {
+        return rejection.status();
+    }
+    if let Some(payload) = error.chain().find_map(|e| e.downcast_ref::<PayloadError>()) {
+        return match payload {
+            PayloadError::ImageTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            PayloadError::UnsupportedImage { .. } => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            PayloadError::InvalidEncoding { .. } => StatusCode::BAD_REQUEST,
+            // The AI or identification service misbehaved, not the client
+            PayloadError::ResponseTooLarge { .. } | PayloadError::UnexpectedContentType { .. } => {
+                StatusCode::BAD_GATEWAY
+            }
+        };
+    }
+
+    match error.chain().find_map(|e| e.downcast_ref::<RequestError>()) {
+        Some(RequestError::NotFound(_)) => StatusCode::NOT_FOUND,
+        Some(RequestError::AmbiguousName { .. } | RequestError::Invalid { .. }) => StatusCode::UNPROCESSABLE_ENTITY,
+        Some(RequestError::NotPermitted(_) | RequestError::Unauthorized(_)) => StatusCode::FORBIDDEN,
+        None => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Plants in the collection, newest first (archived plants excluded)
+#[utoipa::path(
+    get,
+    path = "/plants",
+    tag = "plants",
+    responses(
+        (status = 200, description = "Plants in the collection", body = [PlantView]),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse)
+    )
+)]
+async fn list_plants(
+    State(state): State<Arc<AppState>>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<Vec<PlantView>>, ApiError> {
+    let mut views = Vec::new();
+    for plant in state.plant_repo.get_all_by_user(&user_id).await? {
+        views.push(PlantView::new(plant, state.storage.as_ref()).await?);
+    }
+    Ok(Json(views))
+}
+
+/// A plant by ID, or by name when that is unambiguous
+#[utoipa::path(
+    get,
+    path = "/plants/{plant}",
+    tag = "plants",
+    params(("plant" = String, Path, description = "Plant ID or name")),
+    responses(
+        (status = 200, description = "The plant", body = PlantView),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 404, description = "No such plant", body = ErrorResponse)
+    )
+)]
+async fn get_plant(
+    State(state): State<Arc<AppState>>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Path(plant): Path<String>,
+) -> Result<Json<PlantView>, ApiError> {
+    let plant = state.plant_repo.find(&plant, &user_id).await?;
+    Ok(Json(PlantView::new(plant, state.storage.as_ref()).await?))
+}
+
+/// Change some fields of a plant's care schedule; omitted fields keep
+/// their value
+#[utoipa::path(
+    patch,
+    path = "/plants/{plant}/care-schedule",
+    tag = "plants",
+    params(("plant" = String, Path, description = "Plant ID or name")),
+    request_body = CareScheduleUpdate,
+    responses(
+        (status = 200, description = "The new care schedule", body = CareScheduleResponse),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 404, description = "No such plant", body = ErrorResponse),
+        (status = 422, description = "The new schedule is invalid", body = ErrorResponse)
+    )
+)]
+async fn update_care_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Path(plant): Path<String>,
+    update: Result<Json<CareScheduleUpdate>, JsonRejection>,
+) -> Result<Json<CareScheduleResponse>, ApiError> {
+    let Json(update) = update?;
+    let mut plant = state.plant_repo.find(&plant, &user_id).await?;
+    let warnings = care_schedule_service::edit_plant_schedule(&state.plant_repo, &mut plant, &update).await?;
+
+    Ok(Json(CareScheduleResponse {
+        care_schedule: plant.care_schedule,
+        warnings,
+    }))
+}
+
+/// Start a diagnosis; the AI answers with a question, a photo request or
+/// its conclusion
+#[utoipa::path(
+    post,
+    path = "/plants/{plant}/diagnoses",
+    tag = "diagnoses",
+    params(("plant" = String, Path, description = "Plant ID or name")),
+    request_body = DiagnosisStartDto,
+    responses(
+        (status = 200, description = "The AI's next step", body = DiagnosisResponseDto),
+        (status = 400, description = "The photo is not valid base64", body = ErrorResponse),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Sitter mode is on", body = ErrorResponse),
+        (status = 404, description = "No such plant", body = ErrorResponse),
+        (status = 413, description = "The photo is over MAX_IMAGE_MB", body = ErrorResponse),
+        (status = 415, description = "The photo is not a JPEG, PNG or WebP image", body = ErrorResponse)
+    )
+)]
+async fn start_diagnosis(
+    State(state): State<Arc<AppState>>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Path(plant): Path<String>,
+    dto: Result<Json<DiagnosisStartDto>, JsonRejection>,
+) -> Result<Json<DiagnosisResponseDto>, ApiError> {
+    let Json(dto) = dto?;
+    let plant = state.plant_repo.find(&plant, &user_id).await?;
+    let response = state
+        .diagnosis_service
+        .start_diagnosis(&plant.id, dto, user_id)
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Answer the last question or photo request of a diagnosis
+#[utoipa::path(
+    post,
+    path = "/diagnoses/{diagnosis_id}/answers",
+    tag = "diagnoses",
+    params(("diagnosis_id" = String, Path, description = "Diagnosis ID")),
+    request_body = DiagnosisUpdateDto,
+    responses(
+        (status = 200, description = "The AI's next step", body = DiagnosisResponseDto),
+        (status = 400, description = "The photo is not valid base64", body = ErrorResponse),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Sitter mode is on", body = ErrorResponse),
+        (status = 404, description = "No such diagnosis", body = ErrorResponse),
+        (status = 413, description = "The photo is over MAX_IMAGE_MB", body = ErrorResponse),
+        (status = 415, description = "The photo is not a JPEG, PNG or WebP image", body = ErrorResponse)
+    )
+)]
+async fn answer_diagnosis(
+    State(state): State<Arc<AppState>>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Path(diagnosis_id): Path<String>,
+    dto: Result<Json<DiagnosisUpdateDto>, JsonRejection>,
+) -> Result<Json<DiagnosisResponseDto>, ApiError> {
+    let Json(dto) = dto?;
+    let response = state
+        .diagnosis_service
+        .update_diagnosis(&diagnosis_id, dto, user_id)
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Apply the care schedule changes proposed by a diagnosis conclusion
+#[utoipa::path(
+    post,
+    path = "/diagnoses/{diagnosis_id}/care-update",
+    tag = "diagnoses",
+    params(("diagnosis_id" = String, Path, description = "Diagnosis ID")),
+    responses(
+        (status = 200, description = "The plant with its new care schedule", body = PlantView),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 404, description = "No such diagnosis", body = ErrorResponse)
+    )
+)]
+async fn apply_care_update(
+    State(state): State<Arc<AppState>>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Path(diagnosis_id): Path<String>,
+) -> Result<Json<PlantView>, ApiError> {
+    let plant = state.diagnosis_service.apply_care_update(&diagnosis_id, &user_id).await?;
+    Ok(Json(PlantView::new(plant, state.storage.as_ref()).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_document() {
+        let document = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        assert!(document["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(document["paths"]["/plants/{plant}/care-schedule"]["patch"].is_object());
+        for schema in ["PlantView", "CareScheduleUpdate", "DiagnosisResponseDto", "ErrorResponse"] {
+            assert!(document["components"]["schemas"][schema].is_object(), "{} missing", schema);
+        }
+        assert_eq!(document["components"]["securitySchemes"]["api_key"]["scheme"], "bearer");
+    }
+
+    #[test]
+    fn test_status_code() {
+        let status = |error: RequestError| status_code(&anyhow::Error::from(error).context("Request failed"));
+        assert_eq!(status(RequestError::NotFound("Plant")), StatusCode::NOT_FOUND);
+        let invalid = RequestError::Invalid {
+            what: "care schedule",
+            reason: "Light requirements are empty".to_string(),
+        };
+        assert_eq!(status(invalid), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(status(RequestError::NotPermitted("start AI sessions")), StatusCode::FORBIDDEN);
+        assert_eq!(status(RequestError::Unauthorized("diagnosis")), StatusCode::FORBIDDEN);
+        // Only the type counts, not what the message happens to say
+        let message = anyhow::anyhow!("Config file not found: /home/me/.plant-care/config.toml");
+        assert_eq!(status_code(&message), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let too_large = anyhow::Error::from(PayloadError::ImageTooLarge {
+            name: "The photo".to_string(),
+            size: 30,
+            limit: 20,
+        });
+        assert_eq!(status_code(&too_large.context("Diagnosis failed")), StatusCode::PAYLOAD_TOO_LARGE);
+        let unsupported = PayloadError::UnsupportedImage { name: "The photo".to_string() };
+        assert_eq!(status_code(&unsupported.into()), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let garbled = PayloadError::InvalidEncoding { name: "The photo".to_string() };
+        assert_eq!(status_code(&garbled.into()), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_photo_over_the_default_body_limit() {
+        use axum::body::Body;
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+        use tower::ServiceExt;
+
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        // Only needed to build the adapter; the AI is never called
+        std::env::set_var("OPENROUTER_API_KEY", "test-key");
+        let server = HttpServer::from_env(db.clone(), DEFAULT_ADDR.parse().unwrap()).unwrap();
+        let key = ApiKeyService::new(UserRepository::new(db.clone())).issue("u").await.unwrap();
+        let fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        PlantRepository::new(db).create(&fern).await.unwrap();
+
+        // Over axum's 2 MB default, under MAX_IMAGE_MB, and not an image
+        let photo = BASE64.encode(vec![0u8; 3 * 1024 * 1024]);
+        let body = serde_json::json!({ "prompt": "Yellow leaves", "photo": photo });
+        let request = Request::post(format!("/plants/{}/diagnoses", fern.id))
+            .header(header::AUTHORIZATION, format!("Bearer {}", key))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = router(server.state).oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"], "The photo is not a JPEG, PNG or WebP image");
+    }
+
+    #[tokio::test]
+    async fn test_plant_is_served_with_a_photo_link() {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        // Only needed to build the adapter; the AI is never called
+        std::env::set_var("OPENROUTER_API_KEY", "test-key");
+        let server = HttpServer::from_env(db.clone(), DEFAULT_ADDR.parse().unwrap()).unwrap();
+        let key = ApiKeyService::new(UserRepository::new(db.clone())).issue("u").await.unwrap();
+        let mut fern = Plant::new("u".to_string(), "Fern".to_string(), CareSchedule::default());
+        fern.image_url = Some("/var/lib/plant-care/images/fern.jpg".to_string());
+        PlantRepository::new(db).create(&fern).await.unwrap();
+
+        let get = |path: String| {
+            Request::get(path)
+                .header(header::AUTHORIZATION, format!("Bearer {}", key))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let app = router(server.state);
+
+        let response = app.clone().oneshot(get(format!("/plants/{}", fern.id))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let plant: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(plant["image_link"], "file:///var/lib/plant-care/images/fern.jpg");
+        assert!(plant.get("image_url").is_none() && plant.get("user_id").is_none());
+
+        let response = app.oneshot(get("/plants/Ivy".to_string())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_not_described() {
+        let response = ApiError(anyhow::anyhow!("Failed to open /home/me/.plant-care/plants.db")).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"], "Internal server error");
+    }
+}
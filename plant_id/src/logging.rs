@@ -0,0 +1,166 @@
+/*!
+ * LOGGING
+ *
+ * Logs go to stderr, filtered by `RUST_LOG` (warnings and errors by
+ * default, such as slow queries and corrupt rows). `LOG_FORMAT=json`
+ * writes one JSON object per line instead of text, with the key-value
+ * fields of a record (such as the `query` and `duration_ms` of database
+ * queries) as fields of the object, for log collectors.
+ *
+ * The same records are appended as JSON lines to a log file, `LOG_FILE` or
+ * `plant-care.log` in the data directory (an empty `LOG_FILE` turns it
+ * off), so `plant-care debug-bundle` can attach the recent ones to a bug
+ * report. Past LOG_FILE_MAX_BYTES, the file gets an `.old` extension and a
+ * new one is started.
+ */
+
+use chrono::Utc;
+use log::kv::{self, VisitSource};
+use serde_json::{Map, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use plant_care_core::config;
+
+/// Size past which the log file is started over
+const LOG_FILE_MAX_BYTES: u64 = 1024 * 1024;
+
+pub fn init() {
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn"));
+    if std::env::var("LOG_FORMAT").is_ok_and(|format| format == "json") {
+        builder.format(|buf, record| writeln!(buf, "{}", json_line(record)));
+    }
+    let stderr = builder.build();
+    let max_level = stderr.filter();
+
+    let logger = Tee {
+        stderr,
+        file: log_file().and_then(|path| open_log_file(&path)).map(Mutex::new),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Where logs are kept, unless turned off
+pub fn log_file() -> Option<PathBuf> {
+    match std::env::var("LOG_FILE") {
+        Ok(path) if path.trim().is_empty() => None,
+        Ok(path) => Some(PathBuf::from(path)),
+        Err(_) => Some(config::data_dir().join("plant-care.log")),
+    }
+}
+
+/// Open the log file for appending, starting it over once it is too large.
+/// Logs still go to stderr when it cannot be opened.
+fn open_log_file(path: &Path) -> Option<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).ok()?;
+    }
+    if fs::metadata(path).is_ok_and(|m| m.len() > LOG_FILE_MAX_BYTES) {
+        fs::rename(path, old_log_file(path)).ok();
+    }
+    OpenOptions::new().create(true).append(true).open(path).ok()
+}
+
+/// Where the log file is moved once it is too large
+pub fn old_log_file(path: &Path) -> PathBuf {
+    let mut old = path.as_os_str().to_owned();
+    old.push(".old");
+    PathBuf::from(old)
+}
+
+/// Logs records to stderr and the log file
+struct Tee {
+    stderr: env_logger::Logger,
+    file: Option<Mutex<File>>,
+}
+
+impl log::Log for Tee {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.stderr.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.stderr.matches(record) {
+            return;
+        }
+        self.stderr.log(record);
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            // Losing a line is better than failing whatever was logging
+            writeln!(file, "{}", json_line(record)).ok();
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+        if let Some(file) = &self.file {
+            file.lock().unwrap_or_else(|e| e.into_inner()).flush().ok();
+        }
+    }
+}
+
+/// A log record as a JSON object
+fn json_line(record: &log::Record) -> Value {
+    let mut fields = Map::new();
+    fields.insert("ts".to_string(), Value::from(Utc::now().to_rfc3339()));
+    fields.insert("level".to_string(), Value::from(record.level().as_str()));
+    fields.insert("target".to_string(), Value::from(record.target()));
+    fields.insert("message".to_string(), Value::from(record.args().to_string()));
+
+    let mut collector = FieldCollector(&mut fields);
+    // Collecting into a map cannot fail
+    record.key_values().visit(&mut collector).ok();
+
+    Value::Object(fields)
+}
+
+struct FieldCollector<'a>(&'a mut Map<String, Value>);
+
+impl<'kvs> VisitSource<'kvs> for FieldCollector<'_> {
+    fn visit_pair(&mut self, key: kv::Key<'kvs>, value: kv::Value<'kvs>) -> Result<(), kv::Error> {
+        let value = if let Some(n) = value.to_u64() {
+            Value::from(n)
+        } else if let Some(n) = value.to_i64() {
+            Value::from(n)
+        } else if let Some(n) = value.to_f64() {
+            Value::from(n)
+        } else if let Some(b) = value.to_bool() {
+            Value::from(b)
+        } else {
+            Value::from(value.to_string())
+        };
+        self.0.insert(key.as_str().to_string(), value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_line_includes_fields() {
+        let pairs: &[(&str, kv::Value)] = &[
+            ("query", kv::Value::from("plants.get_all_by_user")),
+            ("duration_ms", kv::Value::from(312u64)),
+        ];
+        let line = json_line(
+            &log::Record::builder()
+                .level(log::Level::Warn)
+                .target("plant_care::db")
+                .args(format_args!("Slow query"))
+                .key_values(&pairs)
+                .build(),
+        );
+
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["target"], "plant_care::db");
+        assert_eq!(line["message"], "Slow query");
+        assert_eq!(line["query"], "plants.get_all_by_user");
+        assert_eq!(line["duration_ms"], 312);
+    }
+}
@@ -6,31 +6,32 @@
  */
 
 // Module declarations - these tell Rust where to find our code modules
-mod adapters;
+// (domain, services and adapters live in the plant_care_core library)
+mod bot;
 mod cli;
-mod config;
-mod domain;
-mod dto;
-mod repositories;
-mod services;
+mod grpc;
+mod http;
+mod logging;
 
 use anyhow::Result;
 use clap::Parser;
 use dotenv::dotenv;
 
 use cli::Cli;
-use config::Database;
+use plant_care_core::adapters::MetricsExporter;
+use plant_care_core::repositories::diagnosis_repository;
+use plant_care_core::Database;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load environment variables from .env file
     dotenv().ok();
 
-    // Initialize logging
-    env_logger::init();
+    // Initialize logging (text, or JSON lines with LOG_FORMAT=json)
+    logging::init();
 
-    // Parse command-line arguments
-    let cli = Cli::parse();
+    // Parse command-line arguments, with the owner's aliases expanded
+    let cli = Cli::parse_from(cli::expand_aliases(std::env::args_os().collect())?);
 
     // Initialize database connection
     let db = Database::new().await?;
@@ -38,8 +39,37 @@ async fn main() -> Result<()> {
     // Run database migrations to ensure tables exist
     db.migrate().await?;
 
+    // Clean up the diagnosis sessions this run leaves waiting on Ctrl-C or
+    // failure. Servers and bots shut down on their own and keep their
+    // users' sessions.
+    let long_running = cli.is_long_running();
+    if !long_running {
+        diagnosis_repository::track_sessions();
+        tokio::spawn(cli::cancel_on_interrupt(db.clone()));
+    }
+
+    // Opt-in metrics file, written once the command has finished
+    let metrics_file = std::env::var("METRICS_FILE").ok().filter(|path| !path.is_empty());
+    let metrics = match metrics_file {
+        Some(_) => Some(MetricsExporter::install()?),
+        None => None,
+    };
+
     // Execute the CLI command
-    cli.execute(db).await?;
+    let result = cli.execute(db.clone()).await;
+
+    if let (Some(metrics), Some(path)) = (&metrics, &metrics_file) {
+        if let Err(e) = metrics.write_file(path) {
+            log::warn!("Could not write metrics file: {:#}", e);
+        }
+    }
+
+    if let Err(e) = result {
+        if !long_running {
+            cli::cancel_in_flight(&db, "failed").await;
+        }
+        return Err(e);
+    }
 
     Ok(())
 }